@@ -0,0 +1,189 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::activity::{ActivityConfig, ActivityState, ActivityTransition};
+use iis2dlpc_rs::mock::{Access, MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// `sleep_state` in `STATUS`.
+const STATUS_SLEEP_STATE: u8 = 0x20;
+/// `sleep_state_ia` in `WAKE_UP_SRC`.
+const WAKE_UP_SRC_SLEEP_STATE: u8 = 0x10;
+/// `sleep_change_ia` in `ALL_INT_SRC`.
+const ALL_INT_SRC_SLEEP_CHANGE: u8 = 0x20;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let cfg = ActivityConfig {
+        mode: SleepOn::ActInact,
+        wake_threshold: 10,
+        wake_dur: 2,
+        sleep_dur: 5,
+        feed: UsrOffOnWu::UserOffsetFeed,
+        sleep_change_on_int2: true,
+    };
+
+    // One write per register, tap, free-fall and offset settings kept, then the routing
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    let wake_up_ths = WakeUpThs::new().with_single_double_tap(1);
+    let wake_up_dur = WakeUpDur::new().with_ff_dur(1);
+    let ctrl7 = Ctrl7::new().with_usr_off_w(1);
+    let ctrl5 = Ctrl5Int2PadCtrl::new().with_int2_drdy(1);
+    sensor.bus.set_reg(Reg::WakeUpThs, wake_up_ths.into_bits());
+    sensor.bus.set_reg(Reg::WakeUpDur, wake_up_dur.into_bits());
+    sensor.bus.set_reg(Reg::Ctrl7, ctrl7.into_bits());
+    sensor.bus.set_reg(Reg::Ctrl5Int2PadCtrl, ctrl5.into_bits());
+    let ctrl7_set = ctrl7.with_usr_off_on_wu(1);
+    sensor.bus.expect(&[
+        Access::read(Reg::WakeUpThs),
+        Access::read(Reg::WakeUpDur),
+        Access::read(Reg::Ctrl7),
+        Access::write(
+            Reg::WakeUpThs,
+            wake_up_ths.with_wk_ths(10).with_sleep_on(1).into_bits(),
+        ),
+        Access::write(
+            Reg::WakeUpDur,
+            wake_up_dur
+                .with_wake_dur(2)
+                .with_sleep_dur(5)
+                .with_stationary(0)
+                .into_bits(),
+        ),
+        Access::write(Reg::Ctrl7, ctrl7_set.into_bits()),
+        Access::read(Reg::Ctrl4Int1PadCtrl),
+        Access::read(Reg::Ctrl5Int2PadCtrl),
+        Access::read(Reg::Ctrl7),
+        Access::write(Reg::Ctrl7, ctrl7_set.into_bits()),
+        Access::write(
+            Reg::Ctrl5Int2PadCtrl,
+            ctrl5.with_int2_sleep_chg(1).into_bits(),
+        ),
+        Access::write(Reg::Ctrl7, ctrl7_set.with_interrupts_enable(1).into_bits()),
+    ]);
+    check(
+        "activity_config_set: Ok",
+        sensor.activity_config_set(&cfg).is_ok(),
+    );
+    check(
+        "activity_config_set: one write per register, then the INT2 routing",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+    check(
+        "activity_config_get: the configuration set",
+        sensor.activity_config_get() == Ok(cfg),
+    );
+    check(
+        "individual getters: the configuration set",
+        sensor.act_mode_get() == Ok(SleepOn::ActInact)
+            && sensor.wkup_threshold_get() == Ok(10)
+            && sensor.wkup_dur_get() == Ok(2)
+            && sensor.act_sleep_dur_get() == Ok(5)
+            && sensor.wkup_feed_data_get() == Ok(UsrOffOnWu::UserOffsetFeed),
+    );
+
+    // Routing left as it is
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.set_reg(Reg::Ctrl5Int2PadCtrl, ctrl5.into_bits());
+    let cfg = ActivityConfig {
+        mode: SleepOn::StatMotion,
+        sleep_change_on_int2: false,
+        ..cfg
+    };
+    check(
+        "sleep_change_on_int2 false: INT2 routing untouched",
+        sensor.activity_config_set(&cfg).is_ok()
+            && sensor.bus.reg(Reg::Ctrl5Int2PadCtrl) == ctrl5.into_bits()
+            && sensor.activity_config_get() == Ok(cfg),
+    );
+
+    // Out of range fields
+    let out_of_range = [
+        ActivityConfig {
+            wake_threshold: 64,
+            ..cfg
+        },
+        ActivityConfig { wake_dur: 4, ..cfg },
+        ActivityConfig {
+            sleep_dur: 16,
+            ..cfg
+        },
+    ];
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.expect(&[]);
+    check(
+        "threshold or duration too large: invalid argument, nothing accessed",
+        out_of_range
+            .iter()
+            .all(|cfg| sensor.activity_config_set(cfg) == Err(Error::InvalidArgument))
+            && sensor.bus.expectations_check() == Ok(()),
+    );
+
+    // State
+    sensor.bus.set_reg(Reg::Status, STATUS_SLEEP_STATE);
+    let inactive = sensor.activity_state_get();
+    sensor.bus.set_reg(Reg::Status, 0);
+    check(
+        "activity_state_get: from the sleep state of STATUS",
+        inactive == Ok(ActivityState::Inactive)
+            && sensor.activity_state_get() == Ok(ActivityState::Active),
+    );
+
+    // Transitions
+    check(
+        "activity_transition_get: no change",
+        sensor.activity_transition_get() == Ok(None),
+    );
+    sensor.bus.set_reg(Reg::AllIntSrc, ALL_INT_SRC_SLEEP_CHANGE);
+    sensor.bus.set_reg(Reg::WakeUpSrc, WAKE_UP_SRC_SLEEP_STATE);
+    let to_inactive = sensor.activity_transition_get();
+    sensor.bus.set_reg(Reg::WakeUpSrc, 0);
+    let to_active = sensor.activity_transition_get();
+    check(
+        "activity_transition_get: to inactive, then to active",
+        to_inactive == Ok(Some(ActivityTransition::ToInactive))
+            && to_active == Ok(Some(ActivityTransition::ToActive)),
+    );
+    check(
+        "activity_transition_get: changes counted",
+        sensor.event_counters().sleep_change == 2,
+    );
+    check(
+        "ActivityTransition::state",
+        ActivityTransition::ToInactive.state() == ActivityState::Inactive
+            && ActivityTransition::ToActive.state() == ActivityState::Active,
+    );
+
+    // Threshold in mg
+    let cfg = ActivityConfig::default().with_wake_threshold_mg(62.5, Fs::_2g);
+    check(
+        "with_wake_threshold_mg at 2 g: nearest code",
+        cfg.wake_threshold == 2 && cfg.wake_threshold_mg(Fs::_4g) == 125.0,
+    );
+    check(
+        "with_wake_threshold_mg: clamped, not a number gives code 0",
+        ActivityConfig::default()
+            .with_wake_threshold_mg(5000.0, Fs::_2g)
+            .wake_threshold
+            == 63
+            && ActivityConfig::default()
+                .with_wake_threshold_mg(f32::NAN, Fs::_2g)
+                .wake_threshold
+                == 0,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
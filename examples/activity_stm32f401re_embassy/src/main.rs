@@ -11,8 +11,8 @@ use embassy_stm32::{bind_interrupts, peripherals, peripherals::USART2};
 use embassy_time::Delay;
 use embedded_hal::delay::DelayNs;
 use heapless::String;
-use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
-use iis2dlpc_rs::{PROPERTY_ENABLE, prelude::*};
+use iis2dlpc_rs::activity::{ActivityConfig, ActivityTransition};
+use iis2dlpc_rs::{I2CAddress, Iis2dlpc, prelude::*};
 
 use {defmt_rtt as _, panic_probe as _};
 
@@ -84,42 +84,32 @@ async fn main(_spawner: Spawner) {
     sensor
         .power_mode_set(Mode::ContLowPwrLowNoise12bit)
         .unwrap();
-    // Set wake-up duration
-    // Wake up duration event 1Lsb = 1 / ODR
-    sensor.wkup_dur_set(2).unwrap();
-    // Set sleep duration
-    // Duration to go in sleep mode (1 = Lsb 512 / ODR)
-    sensor.act_sleep_dur_set(2).unwrap();
-    // Set Activity wake-up threshold
-    // Threshold for wake-up 1 LSB = FS_XL / 64
-    sensor.wkup_threshold_set(2).unwrap();
-    // Data sent to wake-up interrupt function
-    sensor.wkup_feed_data_set(UsrOffOnWu::HpFeed).unwrap();
-    // Config activity / inactivity of stationary / motion detection
-    sensor.act_mode_set(SleepOn::ActInact).unwrap();
-    // Enable activiy detection interrupt
-    let mut int_route = sensor.pin_int1_route_get().unwrap();
-    int_route.set_int1_wu(PROPERTY_ENABLE);
-    sensor.pin_int1_route_set(&int_route).unwrap();
+    // Configure activity / inactivity detection
+    // Wake-up duration 1 LSB = 1 / ODR, sleep duration 1 LSB = 512 / ODR,
+    // wake-up threshold 1 LSB = FS_XL / 64
+    sensor
+        .activity_config_set(&ActivityConfig {
+            mode: SleepOn::ActInact,
+            wake_threshold: 2,
+            wake_dur: 2,
+            sleep_dur: 2,
+            feed: UsrOffOnWu::HpFeed,
+            sleep_change_on_int2: true,
+        })
+        .unwrap();
     // Set Output Data Rate
     sensor.data_rate_set(Odr::_200hz).unwrap();
 
     // Wait Events
     loop {
-        // Read status register
-        let all_sources = sensor.all_sources_get().unwrap();
-
-        // Check if Activity/Inactivity events
-        if all_sources.wake_up_src.sleep_state_ia() == 1 {
-            msg.clear();
-            writeln!(&mut msg, "Inactivity Detected").unwrap();
-            tx.blocking_write(msg.as_bytes()).unwrap();
-        }
-
-        if all_sources.wake_up_src.wu_ia() == 1 {
-            msg.clear();
-            writeln!(&mut msg, "Activity Detected").unwrap();
-            tx.blocking_write(msg.as_bytes()).unwrap();
-        }
+        // Report the changes between activity and inactivity
+        let event = match sensor.activity_transition_get().unwrap() {
+            Some(ActivityTransition::ToInactive) => "Inactivity Detected",
+            Some(ActivityTransition::ToActive) => "Activity Detected",
+            None => continue,
+        };
+        msg.clear();
+        writeln!(&mut msg, "{event}").unwrap();
+        tx.blocking_write(msg.as_bytes()).unwrap();
     }
 }
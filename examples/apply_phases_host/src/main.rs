@@ -0,0 +1,267 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::blob::{ConfigBlob, BLOB_LEN};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::phase::{is_phase_ordered, Phase, REG_PHASES};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::snapshot::{ConfigSnapshot, CONFIG_REGS};
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc, PROPERTY_ENABLE};
+use st_mems_bus::BusOperation;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // The phase table
+    check(
+        "every configuration register has exactly one phase",
+        REG_PHASES.len() == CONFIG_REGS.len()
+            && CONFIG_REGS.iter().all(|reg| {
+                REG_PHASES.iter().filter(|(r, _)| r == reg).count() == 1
+                    && Phase::of(*reg).is_some()
+            }),
+    );
+    check(
+        "no phase for the other registers",
+        Reg::ALL
+            .iter()
+            .filter(|reg| !CONFIG_REGS.contains(reg))
+            .all(|reg| Phase::of(*reg).is_none()),
+    );
+    check(
+        "table in phase order, phases 1 to 7 all used",
+        is_phase_ordered(REG_PHASES.map(|(reg, _)| reg))
+            && Phase::ALL
+                .iter()
+                .enumerate()
+                .all(|(i, phase)| phase.number() as usize == i + 1 && phase.regs().count() > 0),
+    );
+    check(
+        "going back a phase is detected",
+        !is_phase_ordered([Reg::Ctrl6, Reg::Ctrl2]) && !is_phase_ordered([Reg::Ctrl2, Reg::Status]),
+    );
+
+    let idle = config(Odr::Off, Fs::_2g, |_| {});
+    let active = config(Odr::_100hz, Fs::_4g, |sensor| {
+        sensor.usr_offset_x_set(-5).unwrap();
+        sensor.wkup_threshold_set(8).unwrap();
+        sensor.tap_threshold_z_set(9).unwrap();
+        sensor
+            .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_wu(PROPERTY_ENABLE))
+            .unwrap();
+        sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    });
+    let rescaled = config(Odr::_100hz, Fs::_16g, |sensor| {
+        sensor.wkup_threshold_set(3).unwrap();
+    });
+    let configs = [idle, active, rescaled];
+
+    // Every bulk-apply path, from every configuration to every other
+    let paths: Vec<(&str, Box<dyn Fn(&mut Sensor, &ConfigSnapshot)>)> = vec![
+        (
+            "config_snapshot_apply",
+            Box::new(|sensor, target| sensor.config_snapshot_apply(target).unwrap()),
+        ),
+        (
+            "config_snapshot_apply_checked",
+            Box::new(|sensor, target| sensor.config_snapshot_apply_checked(target).unwrap()),
+        ),
+        (
+            "apply_blob",
+            Box::new(|sensor, target| {
+                let mut blob = [0; BLOB_LEN];
+                ConfigBlob::encode(target, &mut blob).unwrap();
+                sensor.apply_blob(&blob).unwrap();
+            }),
+        ),
+        (
+            "power_cycle_recover",
+            Box::new(|sensor, target| {
+                sensor.power_cycle_recover(target).unwrap();
+            }),
+        ),
+        (
+            "apply_delta",
+            Box::new(|sensor, target| {
+                let source = sensor.config_snapshot_get().unwrap();
+                sensor.apply_delta(&source.diff(target)).unwrap();
+            }),
+        ),
+        (
+            "ordered_apply, writes in reverse order",
+            Box::new(|sensor, target| {
+                let writes: Vec<(Reg, u8)> = REG_PHASES
+                    .iter()
+                    .rev()
+                    .map(|(reg, _)| (*reg, target.get(*reg).unwrap()))
+                    .collect();
+                sensor.ordered_apply(&writes).unwrap();
+            }),
+        ),
+    ];
+    for (name, apply) in paths {
+        let mut ok = true;
+        for source in &configs {
+            for target in &configs {
+                let mut sensor = device(source);
+                apply(&mut sensor, target);
+                let regs = phase_writes(&sensor.bus.writes, source);
+                ok &= is_phase_ordered(regs) && sensor.config_snapshot_get().as_ref() == Ok(target);
+            }
+        }
+        check(&format!("{name}: phase-ordered writes, target reached"), ok);
+    }
+
+    // The data rate stop is the only write before the phases
+    let mut sensor = device(&active);
+    sensor
+        .ordered_apply(&[
+            (Reg::Ctrl1, 0x60),
+            (Reg::Ctrl6, Ctrl6::new().with_fs(3).into_bits()),
+        ])
+        .unwrap();
+    let regs: Vec<u8> = sensor.bus.writes.iter().map(|(reg, _)| *reg).collect();
+    check(
+        &format!("rescaling a running stream: {regs:02x?}"),
+        regs == [Reg::Ctrl1 as u8, Reg::Ctrl6 as u8, Reg::Ctrl1 as u8]
+            && sensor
+                .bus
+                .writes
+                .first()
+                .map(|(_, val)| Ctrl1::from_bits(*val).odr())
+                == Some(0),
+    );
+
+    // Only the registers to change are written, the last value given wins
+    let mut sensor = device(&idle);
+    sensor
+        .ordered_apply(&[
+            (Reg::WakeUpThs, 1),
+            (Reg::Ctrl2, idle.get(Reg::Ctrl2).unwrap()),
+            (Reg::WakeUpThs, 2),
+        ])
+        .unwrap();
+    check(
+        "unchanged register skipped, last value wins",
+        sensor.bus.writes == [(Reg::WakeUpThs as u8, 2)],
+    );
+
+    // A register without phase is refused before any access
+    let mut sensor = device(&idle);
+    let err = sensor
+        .ordered_apply(&[(Reg::Ctrl6, 0x10), (Reg::Status, 0)])
+        .unwrap_err();
+    check(
+        "register without phase: refused, no access",
+        err.error == Error::InvalidConfig(ConfigError::NotConfigRegister { reg: Reg::Status })
+            && sensor.bus.writes.is_empty()
+            && sensor.bus.reads == 0,
+    );
+
+    // A failure at any write rolls the device back
+    let writes: Vec<(Reg, u8)> = REG_PHASES
+        .iter()
+        .map(|(reg, _)| (*reg, active.get(*reg).unwrap()))
+        .collect();
+    let count = idle.diff(&active).writes().len();
+    let rolled_back = (0..count).all(|fail_at| {
+        let mut sensor = device(&idle);
+        sensor.bus.fail_at = Some(fail_at);
+        let err = sensor.ordered_apply(&writes).unwrap_err();
+        err.is_rolled_back() && sensor.config_snapshot_get().as_ref() == Ok(&idle)
+    });
+    check(
+        &format!("ordered_apply, failure at each of the {count} writes: rolled back"),
+        rolled_back,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+type Sensor = Iis2dlpc<LogBus, NoDelay>;
+
+/// Registers written by an apply, without the data rate stop written before the phases.
+fn phase_writes(writes: &[(u8, u8)], source: &ConfigSnapshot) -> Vec<Reg> {
+    let stop = writes.first().is_some_and(|(reg, val)| {
+        *reg == Reg::Ctrl1 as u8
+            && Ctrl1::from_bits(*val).odr() == 0
+            && source.data_rate() != Odr::Off
+            && writes.len() > 1
+    });
+    writes
+        .iter()
+        .skip(usize::from(stop))
+        .map(|(reg, _)| Reg::try_from(*reg).unwrap())
+        .collect()
+}
+
+/// Configuration set up on a mock device, high-performance mode.
+fn config(odr: Odr, fs: Fs, setup: impl FnOnce(&mut Iis2dlpc<MockBus, NoDelay>)) -> ConfigSnapshot {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.full_scale_set(fs).unwrap();
+    sensor.block_data_update_set(true).unwrap();
+    setup(&mut sensor);
+    sensor.data_rate_set(odr).unwrap();
+    sensor.config_snapshot_get().unwrap()
+}
+
+/// A device holding `snapshot`, with the logs cleared.
+fn device(snapshot: &ConfigSnapshot) -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(LogBus::default(), NoDelay);
+    sensor.config_snapshot_apply(snapshot).unwrap();
+    sensor.bus.writes.clear();
+    sensor.bus.reads = 0;
+    sensor
+}
+
+/// Mock bus logging the register writes and counting the reads, failing the write at `fail_at` without taking
+/// effect.
+#[derive(Default)]
+struct LogBus {
+    regs: MockBus,
+    writes: Vec<(u8, u8)>,
+    reads: usize,
+    fail_at: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Nack;
+
+impl BusOperation for LogBus {
+    type Error = Nack;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.reads += 1;
+        self.regs.read_bytes(rbuf).map_err(|_| Nack)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push((wbuf[0], wbuf[1]));
+        if self.fail_at == Some(self.writes.len() - 1) {
+            self.fail_at = None;
+            return Err(Nack);
+        }
+        self.regs.write_bytes(wbuf).map_err(|_| Nack)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.reads += 1;
+        self.regs
+            .write_byte_read_bytes(wbuf, rbuf)
+            .map_err(|_| Nack)
+    }
+}
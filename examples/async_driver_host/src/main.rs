@@ -0,0 +1,186 @@
+use std::future::Future;
+use std::pin::pin;
+use std::process::ExitCode;
+use std::task::{Context, Poll, Waker};
+
+use iis2dlpc_rs::asynch::Iis2dlpcAsync;
+use iis2dlpc_rs::fifo::{DataPath, FIFO_DEPTH};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::settle::SettleHint;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc, ID};
+
+type Sensor = Iis2dlpcAsync<MockBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Start-up sequence
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    check(
+        "device_id_get: the identifier",
+        block_on(sensor.device_id_get()) == Ok(ID),
+    );
+    check("init: Ok", block_on(sensor.init()).is_ok());
+    let ctrl2 = Ctrl2::from_bits(sensor.bus.reg(Reg::Ctrl2));
+    check(
+        "init: block data update and auto-increment set",
+        ctrl2.bdu() == 1 && ctrl2.if_add_inc() == 1,
+    );
+
+    // Data path, against the blocking driver running the same sequence
+    let mut blocking = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    let hints = block_on(async {
+        [
+            sensor.full_scale_set(Fs::_8g).await,
+            sensor.power_mode_set(Mode::HighPerformance).await,
+            sensor.data_rate_set(Odr::_100hz).await,
+            sensor.full_scale_set(Fs::_4g).await,
+        ]
+    });
+    blocking.init().unwrap();
+    let expected = [
+        blocking.full_scale_set(Fs::_8g),
+        blocking.power_mode_set(Mode::HighPerformance),
+        blocking.data_rate_set(Odr::_100hz),
+        blocking.full_scale_set(Fs::_4g),
+    ];
+    check(
+        "setters: same hints as the blocking driver",
+        hints == expected && hints[3] != Ok(SettleHint::None),
+    );
+    check(
+        "setters: same registers as the blocking driver",
+        block_on(sensor.config_snapshot_get()) == blocking.config_snapshot_get(),
+    );
+    check(
+        "getters: the values set",
+        block_on(async {
+            (
+                sensor.full_scale_get().await,
+                sensor.power_mode_get().await,
+                sensor.data_rate_get().await,
+            )
+        }) == (Ok(Fs::_4g), Ok(Mode::HighPerformance), Ok(Odr::_100hz)),
+    );
+    check(
+        "power_mode_set: single conversion mode refused at 100 Hz",
+        block_on(sensor.power_mode_set(Mode::SingleLowPwr12bit)) == Err(Error::InvalidArgument),
+    );
+
+    // Output registers, left-justified
+    let sample = [250, -500, 4096];
+    for (k, val) in sample.iter().enumerate() {
+        let [low, high] = i16::to_le_bytes(val << 2);
+        sensor.bus.set_reg(out_reg(2 * k), low);
+        sensor.bus.set_reg(out_reg(2 * k + 1), high);
+    }
+    let before = sensor.bus.transactions();
+    check(
+        "acceleration_raw_get: the sample, in one transaction",
+        block_on(sensor.acceleration_raw_get()) == Ok(sample)
+            && sensor.bus.transactions() == before + 1,
+    );
+    sensor.bus.set_reg(Reg::Status, 0x01);
+    check(
+        "status_reg_get: data-ready",
+        block_on(sensor.status_reg_get()).map(|status| status.drdy()) == Ok(1),
+    );
+    sensor.bus.set_reg(Reg::AllIntSrc, 0x02);
+    let sources = block_on(sensor.all_sources_get());
+    check(
+        "all_sources_get: wake-up flagged and counted",
+        sources.map(|sources| sources.all_int_src.wu_ia()) == Ok(1)
+            && sensor.event_counters().wake_up == 1,
+    );
+
+    // FIFO
+    check(
+        "fifo_watermark_set: 32 invalid",
+        block_on(sensor.fifo_watermark_set(32)) == Err(Error::InvalidArgument),
+    );
+    let set = block_on(async {
+        sensor.fifo_watermark_set(16).await?;
+        sensor.fifo_mode_set(Fmode::StreamMode).await?;
+        Ok::<_, Error<_>>((
+            sensor.fifo_watermark_get().await?,
+            sensor.fifo_mode_get().await?,
+        ))
+    });
+    check(
+        "FIFO: watermark and mode read back, FIFO path active",
+        set == Ok((16, Fmode::StreamMode)) && sensor.data_path_get() == DataPath::Fifo,
+    );
+    check(
+        "acceleration_raw_get: refused on the FIFO path",
+        block_on(sensor.acceleration_raw_get())
+            == Err(Error::InvalidConfig(ConfigError::FifoPathActive)),
+    );
+    // 3 samples stored, over the watermark, with an overrun
+    sensor.bus.set_reg(Reg::FifoSamples, 0xC3);
+    check(
+        "FIFO flags: level, overrun and threshold",
+        block_on(async {
+            (
+                sensor.fifo_data_level_get().await,
+                sensor.fifo_ovr_flag_get().await,
+                sensor.fifo_wtm_flag_get().await,
+            )
+        }) == (Ok(3), Ok(1), Ok(1)),
+    );
+    let mut out = [[0; 3]; FIFO_DEPTH];
+    let before = sensor.bus.transactions();
+    let n = block_on(sensor.fifo_data_get(&mut out));
+    check(
+        "fifo_data_get: 3 samples in one burst read",
+        n == Ok(3) && out[..3] == [sample; 3] && sensor.bus.transactions() == before + 2,
+    );
+    check(
+        "fifo_data_get: 2-sample buffer",
+        block_on(sensor.fifo_data_get(&mut out[..2])) == Ok(2),
+    );
+
+    // Back to the blocking driver
+    let mut sensor = sensor.into_blocking(|bus| bus);
+    check(
+        "into_blocking: FIFO path and configuration kept",
+        sensor.data_path_get() == DataPath::Fifo && sensor.data_rate_get() == Ok(Odr::_100hz),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Output register `k`, from `OUT_X_L` (0) to `OUT_Z_H` (5).
+fn out_reg(k: usize) -> Reg {
+    [
+        Reg::OutXL,
+        Reg::OutXH,
+        Reg::OutYL,
+        Reg::OutYH,
+        Reg::OutZL,
+        Reg::OutZH,
+    ][k]
+}
+
+/// Run a future to completion on the current thread.
+///
+/// The mock bus and the delay never wait, so the future completes without being woken.
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
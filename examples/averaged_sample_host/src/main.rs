@@ -0,0 +1,294 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorType, InputPin};
+use iis2dlpc_rs::fifo::DataPath;
+use iis2dlpc_rs::mock::MockBus;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, SimDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Full sequence from a stream configuration, with stale samples in the FIFO
+    let (mut sensor, sim) = new_sensor();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.fifo_watermark_set(10).unwrap();
+    let saved = sim.borrow().regs.reg(Reg::FifoCtrl);
+    sim.borrow_mut().fifo.extend([sample_bytes([1000; 3]); 3]);
+    sim.borrow_mut().fifo_ctrl_writes.clear();
+    let sums = sensor.averaged_sample_get(8);
+    let sim_ref = sim.borrow();
+    check("sums of the 8 samples collected", sums == Ok(ramp_sums(8)));
+    check(
+        "FIFO_CTRL writes: flush, FIFO mode at 8, flush, previous content",
+        sim_ref.fifo_ctrl_writes
+            == [
+                (Fmode::BypassMode as u8, 0),
+                (Fmode::FifoMode as u8, 8),
+                (Fmode::BypassMode as u8, 0),
+                (Fmode::StreamMode as u8, 10),
+            ],
+    );
+    check(
+        "FIFO_CTRL restored, data path back to the FIFO",
+        sim_ref.regs.reg(Reg::FifoCtrl) == saved && sensor.data_path_get() == DataPath::Fifo,
+    );
+    check("one data period waited per sample", sim_ref.waits == 8);
+    drop(sim_ref);
+
+    // Invalid sample counts
+    for n in [0, 32] {
+        let (mut sensor, sim) = new_sensor();
+        let before = sim.borrow().regs.transactions();
+        let sums = sensor.averaged_sample_get(n);
+        check(
+            &format!("n = {n}: out of range, no bus access"),
+            sums == Err(Error::OutOfRange {
+                requested: n as u32,
+                max: 31,
+            }) && sim.borrow().regs.transactions() == before,
+        );
+    }
+
+    // No data rate
+    let (mut sensor, sim) = new_sensor();
+    sensor.data_rate_set(Odr::Off).unwrap();
+    let sums = sensor.averaged_sample_get(4);
+    check(
+        "accelerometer off: rejected, FIFO_CTRL untouched",
+        sums == Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+            && sim.borrow().fifo_ctrl_writes.is_empty(),
+    );
+
+    // Bounded wait on a stalled sensor, restoration on the error
+    let (mut sensor, sim) = new_sensor();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    let saved = sim.borrow().regs.reg(Reg::FifoCtrl);
+    sim.borrow_mut().stalled = true;
+    let sums = sensor.averaged_sample_get(5);
+    check(
+        "stalled sensor: underrun after 2 * n + 2 periods",
+        sums == Err(Error::FifoUnderrun {
+            available: 0,
+            requested: 5,
+        }) && sim.borrow().waits == 12,
+    );
+    check(
+        "stalled sensor: FIFO_CTRL restored",
+        sim.borrow().regs.reg(Reg::FifoCtrl) == saved && sensor.data_path_get() == DataPath::Fifo,
+    );
+
+    // Extreme samples: 31 × 14-bit full scale fits the sums
+    for val in [-8192, 8191] {
+        let (mut sensor, sim) = new_sensor();
+        sim.borrow_mut().constant = Some([val; 3]);
+        let sums = sensor.averaged_sample_get(31);
+        check(
+            &format!("31 samples at {val}: exact sums"),
+            sums == Ok([31 * val; 3]),
+        );
+    }
+
+    // Means in mg at ±4 g
+    let (mut sensor, sim) = new_sensor();
+    sensor.full_scale_set(Fs::_4g).unwrap();
+    sim.borrow_mut().constant = Some([2048, -1024, 4096]);
+    let mg = sensor.averaged_mg_get(4).unwrap();
+    let expected = [2048.0 * 0.488, -1024.0 * 0.488, 4096.0 * 0.488];
+    check(
+        "means in mg at ±4 g",
+        mg.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-3),
+    );
+
+    // Wait on the INT1 pin, for both polarities
+    for polarity in [HLactive::ActiveHigh, HLactive::ActiveLow] {
+        let (mut sensor, sim) = new_sensor();
+        sensor.pin_polarity_set(polarity).unwrap();
+        let wake_up = Ctrl4Int1PadCtrl::new().with_int1_wu(1);
+        sensor.pin_int1_route_set(&wake_up).unwrap();
+        sim.borrow_mut().fifo_samples_reads = 0;
+        let mut int1 = SimPin(sim.clone());
+        let sums = sensor.averaged_sample_pin_get(6, &mut int1);
+        let sim_ref = sim.borrow();
+        check(
+            &format!("INT1 {polarity:?}: sums of the 6 samples collected"),
+            sums == Ok(ramp_sums(6)) && sim_ref.waits == 6,
+        );
+        check(
+            &format!("INT1 {polarity:?}: FIFO level read only by the drain"),
+            sim_ref.fifo_samples_reads == 2,
+        );
+        check(
+            &format!("INT1 {polarity:?}: FTH routed alone, then routing restored"),
+            sim_ref.fth_routed_alone
+                && sim_ref.regs.reg(Reg::Ctrl4Int1PadCtrl) == wake_up.into_bits(),
+        );
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A sensor at 100 Hz and ±2 g, the FIFO in bypass mode, and the simulation it runs on.
+fn new_sensor() -> (Sensor, Rc<RefCell<Sim>>) {
+    let sim = Rc::new(RefCell::new(Sim::default()));
+    let mut sensor = Iis2dlpc::from_bus(SimBus(sim.clone()), SimDelay(sim.clone()));
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sim.borrow_mut().fifo_ctrl_writes.clear();
+    (sensor, sim)
+}
+
+/// Sample `k` of the ramp collected by the simulated sensor.
+fn ramp(k: i32) -> [i32; 3] {
+    [100 + 3 * k, -2000 + 50 * k, 4096 - 7 * k]
+}
+
+/// Sums of the first `n` samples of the ramp.
+fn ramp_sums(n: i32) -> [i32; 3] {
+    (0..n)
+        .map(ramp)
+        .fold([0; 3], |[x, y, z], [a, b, c]| [x + a, y + b, z + c])
+}
+
+/// FIFO content of a 14-bit sample.
+fn sample_bytes(sample: [i32; 3]) -> [u8; 6] {
+    let mut bytes = [0; 6];
+    for (dst, val) in bytes.chunks_exact_mut(2).zip(sample) {
+        dst.copy_from_slice(&((val as i16) << 2).to_le_bytes());
+    }
+    bytes
+}
+
+/// Simulated sensor: the registers, and a FIFO collecting one sample per data period in FIFO
+/// mode, the period being a wait of the driver.
+#[derive(Default)]
+struct Sim {
+    regs: MockBus,
+    fifo: VecDeque<[u8; 6]>,
+    /// Number of samples collected since the start.
+    collected: i32,
+    /// Sample collected instead of the ramp.
+    constant: Option<[i32; 3]>,
+    /// No sample is collected.
+    stalled: bool,
+    /// Waits of the driver.
+    waits: u32,
+    /// `fmode` and `fth` of each `FIFO_CTRL` write.
+    fifo_ctrl_writes: Vec<(u8, u8)>,
+    /// Number of reads of `FIFO_SAMPLES`.
+    fifo_samples_reads: u32,
+    /// The FIFO threshold was routed alone on INT1 during a wait.
+    fth_routed_alone: bool,
+}
+
+impl Sim {
+    fn threshold_reached(&self) -> bool {
+        self.fifo.len() >= FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fth() as usize
+    }
+}
+
+struct SimBus(Rc<RefCell<Sim>>);
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.borrow_mut().regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        sim.regs.write_bytes(wbuf)?;
+        if wbuf[0] == Reg::FifoCtrl as u8 {
+            let ctrl = FifoCtrl::from_bits(sim.regs.reg(Reg::FifoCtrl));
+            if ctrl.fmode() == Fmode::BypassMode as u8 {
+                sim.fifo.clear();
+            }
+            sim.fifo_ctrl_writes.push((ctrl.fmode(), ctrl.fth()));
+        }
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        match wbuf[0] {
+            // `diff` in bits 5..0, no overrun
+            reg if reg == Reg::FifoSamples as u8 => {
+                sim.fifo_samples_reads += 1;
+                // Counted as a bus access
+                sim.regs.write_byte_read_bytes(wbuf, rbuf)?;
+                rbuf.fill(sim.fifo.len() as u8);
+            }
+            reg if reg == Reg::OutXL as u8 => {
+                for sample in rbuf.chunks_exact_mut(6) {
+                    sample.copy_from_slice(&sim.fifo.pop_front().unwrap());
+                }
+            }
+            _ => return sim.regs.write_byte_read_bytes(wbuf, rbuf),
+        }
+        Ok(())
+    }
+}
+
+/// Delay of the driver: each wait is a data period of the simulated sensor.
+struct SimDelay(Rc<RefCell<Sim>>);
+
+impl DelayNs for SimDelay {
+    fn delay_ns(&mut self, _ns: u32) {
+        let mut sim = self.0.borrow_mut();
+        sim.waits += 1;
+        let routes = sim.regs.reg(Reg::Ctrl4Int1PadCtrl);
+        if routes == Ctrl4Int1PadCtrl::new().with_int1_fth(1).into_bits() {
+            sim.fth_routed_alone = true;
+        }
+        let fifo_mode =
+            FifoCtrl::from_bits(sim.regs.reg(Reg::FifoCtrl)).fmode() == Fmode::FifoMode as u8;
+        if fifo_mode && !sim.stalled && sim.fifo.len() < 32 {
+            let sample = sim.constant.unwrap_or(ramp(sim.collected));
+            sim.collected += 1;
+            sim.fifo.push_back(sample_bytes(sample));
+        }
+    }
+}
+
+/// MCU input wired to INT1.
+struct SimPin(Rc<RefCell<Sim>>);
+
+impl ErrorType for SimPin {
+    type Error = Infallible;
+}
+
+impl InputPin for SimPin {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        let sim = self.0.borrow();
+        let routed =
+            Ctrl4Int1PadCtrl::from_bits(sim.regs.reg(Reg::Ctrl4Int1PadCtrl)).int1_fth() != 0;
+        let active_low = Ctrl3::from_bits(sim.regs.reg(Reg::Ctrl3)).h_lactive() != 0;
+        Ok((routed && sim.threshold_reached()) != active_low)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        self.is_high().map(|high| !high)
+    }
+}
@@ -0,0 +1,334 @@
+use std::future::Future;
+use std::pin::pin;
+use std::process::ExitCode;
+use std::task::{Context, Poll, Waker};
+
+use iis2dlpc_rs::event::Event;
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::remap::{AxisRemap, SignedAxis};
+use iis2dlpc_rs::stream::{Iis2dlpcStream, Item};
+use iis2dlpc_rs::Iis2dlpc;
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// 1 g at ±2 g, as a 14-bit output.
+const ONE_G_LSB: i16 = 4098;
+
+const AXES: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        if !ok {
+            println!("FAIL {name}");
+            failures += 1;
+        }
+    };
+
+    check(
+        "identity by default",
+        AxisRemap::default() == AxisRemap::IDENTITY,
+    );
+    check(
+        "a sensor axis used twice is rejected",
+        AxisRemap::new([SignedAxis::PosX, SignedAxis::NegX, SignedAxis::PosZ]).is_none(),
+    );
+    check("24 rotations", AxisRemap::rotations().count() == 24);
+
+    // Sensor rotated by 90° around Z, written out by hand
+    let z90 = AxisRemap::new([SignedAxis::PosY, SignedAxis::NegX, SignedAxis::PosZ]).unwrap();
+    let mut sensor = new_sensor(z90);
+    set_output(&mut sensor, [300, -600, 900]);
+    check(
+        "90° around Z, raw",
+        sensor.acceleration_raw_get().unwrap() == [-600, -300, 900],
+    );
+    check(
+        "90° around Z, raw in the sensor frame",
+        sensor.acceleration_raw_sensor_get().unwrap() == [300, -600, 900],
+    );
+
+    let mirrored = [
+        [SignedAxis::NegX, SignedAxis::PosY, SignedAxis::PosZ],
+        [SignedAxis::PosY, SignedAxis::PosX, SignedAxis::PosZ],
+        [SignedAxis::NegX, SignedAxis::NegY, SignedAxis::NegZ],
+        [SignedAxis::PosZ, SignedAxis::NegY, SignedAxis::PosX],
+    ]
+    .map(|axes| AxisRemap::new(axes).unwrap());
+
+    let mut remaps = 0;
+    for remap in AxisRemap::rotations().chain(mirrored) {
+        let name = format!("{:?}", remap.board_axes());
+        let mut sensor = new_sensor(remap);
+        let mut board_frame = [[0.0; 3]; 3];
+
+        for (sensor_axis, frame) in AXES.into_iter().zip(&mut board_frame) {
+            for negative in [false, true] {
+                let case = format!("{name}, gravity on {}", label(sensor_axis, negative));
+                let board = gravity(&mut sensor, sensor_axis, negative);
+                if !negative {
+                    *frame = board.map(|mg| (mg / 1000.0).round());
+                }
+                let Some((board_axis, board_negative)) = dominant(board) else {
+                    check(&format!("{case}: one board axis reads 1 g"), false);
+                    continue;
+                };
+
+                check(
+                    &format!("{case}: FIFO drain agrees with the output registers"),
+                    fifo_sample(&mut sensor) == sensor.acceleration_raw_get().unwrap(),
+                );
+                check(
+                    &format!("{case}: 6D flag on the same board axis"),
+                    sixd(&mut sensor, remap, sensor_axis, negative)
+                        == [(board_axis, board_negative)],
+                );
+                check(
+                    &format!("{case}: tap on the same board axis"),
+                    tap(&mut sensor, sensor_axis, negative) == (board_axis, board_negative),
+                );
+                check(
+                    &format!("{case}: wake-up flag on the same board axis"),
+                    wake(remap, sensor_axis) == [board_axis],
+                );
+                check(
+                    &format!("{case}: stream tap on the same board axis"),
+                    stream_event(remap, &[(Reg::TapSrc, tap_src(sensor_axis, negative))]).and_then(
+                        |event| match event {
+                            Event::Tap(tap) => Some((tap.axis?, tap.negative)),
+                            _ => None,
+                        },
+                    ) == Some((board_axis, board_negative)),
+                );
+                check(
+                    &format!("{case}: stream wake-up on the same board axis"),
+                    stream_event(remap, &wake_up_regs(sensor_axis)).and_then(|event| match event {
+                        Event::WakeUp { axes } => Some(flagged(axes)),
+                        _ => None,
+                    }) == Some(vec![board_axis]),
+                );
+                check(
+                    &format!("{case}: offset of the board axis lands on the sensor axis"),
+                    offset(
+                        &mut sensor,
+                        board_axis,
+                        sensor_axis,
+                        negative != board_negative,
+                    ),
+                );
+            }
+        }
+
+        // A rotation keeps the frame right-handed
+        let handed = det(board_frame);
+        check(
+            &format!("{name}: handedness"),
+            handed == if remap.is_rotation() { 1.0 } else { -1.0 },
+        );
+        remaps += 1;
+    }
+
+    if failures == 0 {
+        println!("PASS {remaps} remappings, 6 gravity directions each");
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn new_sensor(remap: AxisRemap) -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.full_scale_set(Fs::_2g).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.axis_remap_set(remap);
+    sensor
+}
+
+/// Write raw 14-bit values in the output registers, left-aligned.
+fn set_output(sensor: &mut Sensor, raw: [i16; 3]) {
+    let regs = [
+        Reg::OutXL,
+        Reg::OutXH,
+        Reg::OutYL,
+        Reg::OutYH,
+        Reg::OutZL,
+        Reg::OutZH,
+    ];
+    let bytes = raw.map(|lsb| (lsb << 2).to_le_bytes());
+    for (reg, byte) in regs.into_iter().zip(bytes.into_iter().flatten()) {
+        sensor.bus.set_reg(reg, byte);
+    }
+}
+
+/// Put 1 g on a sensor axis and read it in the board frame.
+fn gravity(sensor: &mut Sensor, axis: Axis, negative: bool) -> [f32; 3] {
+    let lsb = if negative { -ONE_G_LSB } else { ONE_G_LSB };
+    set_output(sensor, AXES.map(|a| if a == axis { lsb } else { 0 }));
+    sensor.acceleration_mg_get().unwrap()
+}
+
+/// The only board axis reading about 1 g, with its sign.
+fn dominant(mg: [f32; 3]) -> Option<(Axis, bool)> {
+    let mut found = AXES.into_iter().zip(mg).filter(|(_, mg)| *mg != 0.0);
+    let (axis, mg) = found.next()?;
+    let one_g = (mg.abs() - 1000.0).abs() < 1.0;
+    (found.next().is_none() && one_g).then_some((axis, mg < 0.0))
+}
+
+/// Drain the sample of the output registers through the FIFO path.
+fn fifo_sample(sensor: &mut Sensor) -> [i16; 3] {
+    sensor.bus.set_reg(Reg::FifoSamples, 1);
+    let mut out = [[0; 3]];
+    sensor.fifo_drain_exact(1, &mut out).unwrap();
+    out[0]
+}
+
+/// Raise the 6D flag of a sensor direction and list the flagged board directions.
+fn sixd(sensor: &mut Sensor, remap: AxisRemap, axis: Axis, negative: bool) -> Vec<(Axis, bool)> {
+    let flag = match (axis, negative) {
+        (Axis::X, false) => bit(|b| SixdSrc::from_bits(b).xh()),
+        (Axis::X, true) => bit(|b| SixdSrc::from_bits(b).xl()),
+        (Axis::Y, false) => bit(|b| SixdSrc::from_bits(b).yh()),
+        (Axis::Y, true) => bit(|b| SixdSrc::from_bits(b).yl()),
+        (Axis::Z, false) => bit(|b| SixdSrc::from_bits(b).zh()),
+        (Axis::Z, true) => bit(|b| SixdSrc::from_bits(b).zl()),
+    };
+    sensor.bus.set_reg(Reg::SixdSrc, flag);
+    let src = remap.sixd_src(sensor.sixd_src_get().unwrap());
+    [
+        (Axis::X, false, src.xh()),
+        (Axis::X, true, src.xl()),
+        (Axis::Y, false, src.yh()),
+        (Axis::Y, true, src.yl()),
+        (Axis::Z, false, src.zh()),
+        (Axis::Z, true, src.zl()),
+    ]
+    .into_iter()
+    .filter(|(_, _, flag)| *flag == 1)
+    .map(|(axis, negative, _)| (axis, negative))
+    .collect()
+}
+
+/// `TAP_SRC` of a single tap on a sensor axis.
+fn tap_src(axis: Axis, negative: bool) -> u8 {
+    let mut src = bit(|b| TapSrc::from_bits(b).single_tap())
+        | match axis {
+            Axis::X => bit(|b| TapSrc::from_bits(b).x_tap()),
+            Axis::Y => bit(|b| TapSrc::from_bits(b).y_tap()),
+            Axis::Z => bit(|b| TapSrc::from_bits(b).z_tap()),
+        };
+    if negative {
+        src |= bit(|b| TapSrc::from_bits(b).tap_sign());
+    }
+    src
+}
+
+/// Raise a single tap on a sensor axis and decode it.
+fn tap(sensor: &mut Sensor, axis: Axis, negative: bool) -> (Axis, bool) {
+    sensor.bus.set_reg(Reg::TapSrc, tap_src(axis, negative));
+    let tap = sensor.tap_event_get().unwrap().next().unwrap();
+    (tap.axis.unwrap(), tap.negative)
+}
+
+/// `WAKE_UP_SRC` flag of a sensor axis, with the summary flag in `ALL_INT_SRC`.
+fn wake_up_regs(axis: Axis) -> [(Reg, u8); 2] {
+    let flag = match axis {
+        Axis::X => bit(|b| WakeUpSrc::from_bits(b).x_wu()),
+        Axis::Y => bit(|b| WakeUpSrc::from_bits(b).y_wu()),
+        Axis::Z => bit(|b| WakeUpSrc::from_bits(b).z_wu()),
+    };
+    [
+        (
+            Reg::WakeUpSrc,
+            flag | bit(|b| WakeUpSrc::from_bits(b).wu_ia()),
+        ),
+        (Reg::AllIntSrc, bit(|b| AllIntSrc::from_bits(b).wu_ia())),
+    ]
+}
+
+/// Raise the event sources of a remapped sensor and take the first item of a stream over it.
+///
+/// A sample is flagged ready as well, so that a stream dropping the event returns the sample
+/// rather than waiting.
+fn stream_event(remap: AxisRemap, regs: &[(Reg, u8)]) -> Option<Event> {
+    let mut sensor = new_sensor(remap);
+    for (reg, val) in regs {
+        sensor.bus.set_reg(*reg, *val);
+    }
+    sensor
+        .bus
+        .set_reg(Reg::StatusDup, bit(|b| StatusDup::from_bits(b).drdy()));
+    // The stream runs on the async driver, the remap carried over
+    let sensor = sensor.into_async(|bus| bus);
+    let mut stream = block_on(Iis2dlpcStream::polling(sensor, NoDelay)).unwrap();
+    match block_on(stream.next()).unwrap() {
+        Item::Event(event) => Some(event),
+        Item::Sample(_) => None,
+    }
+}
+
+/// Convert the wake-up flag of a sensor axis and list the flagged board axes.
+fn wake(remap: AxisRemap, axis: Axis) -> Vec<Axis> {
+    let flag = match axis {
+        Axis::X => bit(|b| WakeUpSrc::from_bits(b).x_wu()),
+        Axis::Y => bit(|b| WakeUpSrc::from_bits(b).y_wu()),
+        Axis::Z => bit(|b| WakeUpSrc::from_bits(b).z_wu()),
+    };
+    let src = remap.wake_up_src(WakeUpSrc::from_bits(flag));
+    flagged([src.x_wu() == 1, src.y_wu() == 1, src.z_wu() == 1])
+}
+
+/// The axes flagged, in X, Y, Z order.
+fn flagged(axes: [bool; 3]) -> Vec<Axis> {
+    AXES.into_iter()
+        .zip(axes)
+        .filter(|(_, flag)| *flag)
+        .map(|(axis, _)| axis)
+        .collect()
+}
+
+/// Write an offset on a board axis and check the sensor registers and the read back.
+fn offset(sensor: &mut Sensor, board: Axis, sensor_axis: Axis, flipped: bool) -> bool {
+    let val = 25;
+    let board_offsets = AXES.map(|a| if a == board { val } else { 0 });
+    sensor.usr_offsets_set(board_offsets).unwrap();
+    let regs = [Reg::XOfsUsr, Reg::YOfsUsr, Reg::ZOfsUsr].map(|reg| sensor.bus.reg(reg) as i8);
+    let expected = if flipped { -val } else { val };
+    let written = AXES
+        .into_iter()
+        .zip(regs)
+        .all(|(a, reg)| reg == if a == sensor_axis { expected } else { 0 });
+
+    let single = match board {
+        Axis::X => sensor.usr_offset_x_get(),
+        Axis::Y => sensor.usr_offset_y_get(),
+        Axis::Z => sensor.usr_offset_z_get(),
+    };
+    written && sensor.usr_offsets_get().unwrap() == board_offsets && single.unwrap() == val
+}
+
+fn det([a, b, c]: [[f32; 3]; 3]) -> f32 {
+    a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+        + a[2] * (b[0] * c[1] - b[1] * c[0])
+}
+
+fn label(axis: Axis, negative: bool) -> String {
+    format!("{}{axis:?}", if negative { "-" } else { "+" })
+}
+
+/// Find the bit of a 1-bit field of a read-only register.
+fn bit(get: impl Fn(u8) -> u8) -> u8 {
+    (0..8).map(|i| 1 << i).find(|b| get(*b) != 0).unwrap()
+}
+
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::remap::{AxisRemap, SignedAxis};
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, NoDelay>;
+
+/// Samples with the extreme 14-bit values and garbage in the 2 unused bits.
+const SAMPLES: [[u16; 3]; 4] = [
+    [0x7FFF, 0x8000, 0x0000],
+    [0xFFFC, 0x0007, 0x4001],
+    [0x1234, 0xEDCB, 0xC002],
+    [0x0003, 0xFFFF, 0x3FFE],
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // One transaction per sample
+    let mut sensor = new_sensor();
+    sensor.bus.set_output(SAMPLES[0]);
+    sensor.acceleration_raw_get().unwrap();
+    check(
+        "one 6-byte read from OUT_X_L",
+        sensor.bus.output_reads == [(Reg::OutXL as u8, 6)],
+    );
+
+    // Same decoding as the per-register path
+    let mut same = true;
+    for fs in [Fs::_2g, Fs::_4g, Fs::_8g, Fs::_16g] {
+        for mode in [Mode::HighPerformance, Mode::ContLowPwr12bit] {
+            let mut sensor = new_sensor();
+            sensor.full_scale_set(fs).unwrap();
+            sensor.power_mode_set(mode).unwrap();
+            for sample in SAMPLES {
+                sensor.bus.set_output(sample);
+                same &= sensor.acceleration_raw_get() == Ok(per_register(&mut sensor));
+            }
+        }
+    }
+    check(
+        "all full scales and resolutions: same values as the per-register reads",
+        same,
+    );
+    let mut sensor = new_sensor();
+    sensor.bus.set_output(SAMPLES[0]);
+    check(
+        "14-bit values shifted by 2, unused bits dropped",
+        sensor.acceleration_raw_get() == Ok([0x1FFF, -0x2000, 0]),
+    );
+
+    // A conversion completing between two transactions, without block data update
+    let mut sensor = new_sensor();
+    sensor.bus.set_output(SAMPLES[1]);
+    sensor.bus.next = Some(SAMPLES[2]);
+    check(
+        "conversion during the read: the burst returns a single sample",
+        sensor.acceleration_raw_get() == Ok(decode(SAMPLES[1])),
+    );
+    sensor.bus.set_output(SAMPLES[1]);
+    sensor.bus.next = Some(SAMPLES[2]);
+    let [x, ..] = decode(SAMPLES[1]);
+    let [_, y, z] = decode(SAMPLES[2]);
+    check(
+        "conversion during the read: the per-register reads mix two samples",
+        per_register(&mut sensor) == [x, y, z],
+    );
+
+    // Axis remapping and the fixed-tick read
+    let mut sensor = new_sensor();
+    sensor.bus.set_output(SAMPLES[2]);
+    let [x, y, z] = decode(SAMPLES[2]);
+    let remap = AxisRemap::new([SignedAxis::PosY, SignedAxis::NegX, SignedAxis::PosZ]).unwrap();
+    sensor.axis_remap_set(remap);
+    check(
+        "remapped in the board frame",
+        sensor.acceleration_raw_get() == Ok([y, -x, z]),
+    );
+    check(
+        "sensor frame kept by acceleration_raw_sensor_get",
+        sensor.acceleration_raw_sensor_get() == Ok([x, y, z]),
+    );
+    sensor.bus.output_reads.clear();
+    check(
+        "acceleration_raw_unchecked_get: same sample, same single read",
+        sensor.acceleration_raw_unchecked_get() == Ok([y, -x, z])
+            && sensor.bus.output_reads == [(Reg::OutXL as u8, 6)],
+    );
+
+    // FIFO path active
+    let mut sensor = new_sensor();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.bus.output_reads.clear();
+    check(
+        "FIFO path active: rejected, nothing read",
+        sensor.acceleration_raw_get() == Err(Error::InvalidConfig(ConfigError::FifoPathActive))
+            && sensor.bus.output_reads.is_empty(),
+    );
+    check(
+        "FIFO path active: acceleration_raw_unchecked_get reads",
+        sensor.acceleration_raw_unchecked_get().is_ok()
+            && sensor.bus.output_reads == [(Reg::OutXL as u8, 6)],
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn new_sensor() -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(SimBus::default(), NoDelay);
+    sensor.data_rate_set(Odr::_200hz).unwrap();
+    sensor
+}
+
+/// Read the three axes one output register pair at a time, as the driver used to.
+fn per_register(sensor: &mut Sensor) -> [i16; 3] {
+    let mut read = |reg: Reg| {
+        let mut buf = [0; 2];
+        sensor.read_from_register(reg as u8, &mut buf).unwrap();
+        buf
+    };
+    [
+        OutX::from_le_bytes(read(Reg::OutXL)).x(),
+        OutY::from_le_bytes(read(Reg::OutYL)).y(),
+        OutZ::from_le_bytes(read(Reg::OutZL)).z(),
+    ]
+}
+
+/// The 14-bit values of a sample, with the sign.
+fn decode(sample: [u16; 3]) -> [i16; 3] {
+    sample.map(|word| word as i16 >> 2)
+}
+
+/// Register map whose output registers can be refreshed by a conversion completing after a read.
+#[derive(Default)]
+struct SimBus {
+    regs: MockBus,
+    /// Sample stored after the next read of the output registers.
+    next: Option<[u16; 3]>,
+    /// First register and length of the reads of the output registers.
+    output_reads: Vec<(u8, usize)>,
+}
+
+impl SimBus {
+    fn set_output(&mut self, sample: [u16; 3]) {
+        let regs = [
+            Reg::OutXL,
+            Reg::OutXH,
+            Reg::OutYL,
+            Reg::OutYH,
+            Reg::OutZL,
+            Reg::OutZH,
+        ];
+        let bytes = sample.map(u16::to_le_bytes).concat();
+        for (reg, byte) in regs.into_iter().zip(bytes) {
+            self.regs.set_reg(reg, byte);
+        }
+    }
+}
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.regs.write_byte_read_bytes(wbuf, rbuf)?;
+        if (Reg::OutXL as u8..=Reg::OutZH as u8).contains(&wbuf[0]) {
+            self.output_reads.push((wbuf[0], rbuf.len()));
+            if let Some(sample) = self.next.take() {
+                self.set_output(sample);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,351 @@
+use std::cell::RefCell;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use embedded_hal::i2c::{self, I2c, Operation as I2cOperation, SevenBitAddress};
+use embedded_hal::spi::{self, Operation as SpiOperation, SpiDevice};
+use iis2dlpc_rs::bus_contract::{
+    self, ContractReport, LoopbackModel, MockLoopback, Outcome, Property,
+};
+use iis2dlpc_rs::mock::MockBus;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::I2CAddress;
+use st_mems_bus::i2c::I2cBus;
+use st_mems_bus::spi::SpiBus;
+use st_mems_bus::BusOperation;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, report: ContractReport, expected: Option<Property>| {
+        let ok = match expected {
+            Some(property) => report.outcome(property) == Outcome::Fail,
+            None => report.is_ok(),
+        };
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok || expected.is_none() {
+            for line in report.to_string().lines() {
+                println!("  {line}");
+            }
+        }
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Buses honoring the contract
+    let report = bus_contract::check(&mut MockBus::new(), &mut MockLoopback);
+    check("mock::MockBus", report, None);
+
+    let device = Device::shared();
+    let mut bus = I2cBus::new(FakeI2c(device.clone()), I2CAddress::I2cAddL as u8);
+    check(
+        "st_mems_bus::i2c::I2cBus",
+        bus_contract::check(&mut bus, &mut Loopback(device)),
+        None,
+    );
+
+    let device = Device::shared();
+    let mut bus = SpiBus::new(FakeSpi(device.clone()));
+    check(
+        "st_mems_bus::spi::SpiBus",
+        bus_contract::check(&mut bus, &mut Loopback(device)),
+        None,
+    );
+
+    // Buses getting a detail wrong, each caught by its property
+    let device = Device::shared();
+    let mut bus = Broken {
+        spi: FakeSpi(device.clone()),
+        flaw: Flaw::NoReadBit,
+    };
+    check(
+        "SPI read bit not set: single read fails",
+        bus_contract::check(&mut bus, &mut Loopback(device)),
+        Some(Property::SingleRead),
+    );
+
+    for (name, flaw, property) in [
+        (
+            "bridge reading byte by byte without increment: burst read fails",
+            Flaw::NoIncrement,
+            Property::BurstRead,
+        ),
+        (
+            "bridge swapping the bytes of 16-bit words: pair assembly fails",
+            Flaw::WordSwap,
+            Property::PairAssembly,
+        ),
+        (
+            "bridge writing the first byte only: burst write fails",
+            Flaw::FirstByteOnly,
+            Property::BurstWrite,
+        ),
+        (
+            "bridge truncating transfers to 64 bytes: max-length read fails",
+            Flaw::Truncate64,
+            Property::MaxLengthRead,
+        ),
+        (
+            "bridge swallowing errors: error propagation fails",
+            Flaw::SwallowErrors,
+            Property::ErrorPropagation,
+        ),
+    ] {
+        let device = Device::shared();
+        let mut bus = Broken {
+            spi: FakeSpi(device.clone()),
+            flaw,
+        };
+        check(
+            name,
+            bus_contract::check(&mut bus, &mut Loopback(device)),
+            Some(property),
+        );
+    }
+
+    // The checks leave the registers as they found them
+    let device = Device::shared();
+    let before = device.borrow().regs;
+    let mut bus = SpiBus::new(FakeSpi(device.clone()));
+    bus_contract::check(&mut bus, &mut Loopback(device.clone()));
+    let ok = device.borrow().regs == before;
+    println!("{} registers put back", if ok { "PASS" } else { "FAIL" });
+    if !ok {
+        failures += 1;
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Simulated device: the register file with the address auto-increment, and a fault injector.
+struct Device {
+    regs: [u8; 0x40],
+    addr: u8,
+    fail_next: bool,
+}
+
+impl Device {
+    fn shared() -> Rc<RefCell<Device>> {
+        let mut regs = [0; 0x40];
+        // Reset values of WHO_AM_I and CTRL2 (IF_ADD_INC)
+        regs[Reg::WhoAmI as usize] = 0x44;
+        regs[Reg::Ctrl2 as usize] = 0x04;
+        Rc::new(RefCell::new(Device {
+            regs,
+            addr: 0,
+            fail_next: false,
+        }))
+    }
+
+    fn fault(&mut self) -> bool {
+        std::mem::take(&mut self.fail_next)
+    }
+
+    fn next_addr(&self) -> u8 {
+        let fifo_on = FifoCtrl::from_bits(self.regs[Reg::FifoCtrl as usize]).fmode()
+            != Fmode::BypassMode as u8;
+        match self.addr {
+            a if fifo_on && a == Reg::OutZH as u8 => Reg::OutXL as u8,
+            a => (a + 1) % 0x40,
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) {
+        for byte in buf {
+            *byte = self.regs[self.addr as usize];
+            self.addr = self.next_addr();
+        }
+    }
+
+    fn write(&mut self, buf: &[u8]) {
+        for byte in buf {
+            self.regs[self.addr as usize] = *byte;
+            self.addr = self.next_addr();
+        }
+    }
+}
+
+/// Model reaching the shared device, whatever the bus.
+struct Loopback(Rc<RefCell<Device>>);
+
+impl<B> LoopbackModel<B> for Loopback {
+    fn peek(&mut self, _bus: &mut B, reg: Reg) -> u8 {
+        self.0.borrow().regs[reg as usize]
+    }
+
+    fn poke(&mut self, _bus: &mut B, reg: Reg, val: u8) {
+        self.0.borrow_mut().regs[reg as usize] = val;
+    }
+
+    fn fail_next(&mut self, _bus: &mut B) -> bool {
+        self.0.borrow_mut().fail_next = true;
+        true
+    }
+}
+
+#[derive(Debug)]
+struct Nack;
+
+impl i2c::Error for Nack {
+    fn kind(&self) -> i2c::ErrorKind {
+        i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Data)
+    }
+}
+
+impl spi::Error for Nack {
+    fn kind(&self) -> spi::ErrorKind {
+        spi::ErrorKind::Other
+    }
+}
+
+/// I2C peripheral wired to the device: a write sets the address then writes, a read reads on.
+struct FakeI2c(Rc<RefCell<Device>>);
+
+impl i2c::ErrorType for FakeI2c {
+    type Error = Nack;
+}
+
+impl I2c for FakeI2c {
+    fn transaction(
+        &mut self,
+        _address: SevenBitAddress,
+        operations: &mut [I2cOperation<'_>],
+    ) -> Result<(), Nack> {
+        let mut device = self.0.borrow_mut();
+        if device.fault() {
+            return Err(Nack);
+        }
+        for operation in operations {
+            match operation {
+                I2cOperation::Write(bytes) => {
+                    if let Some((reg, data)) = bytes.split_first() {
+                        device.addr = *reg;
+                        device.write(data);
+                    }
+                }
+                I2cOperation::Read(buf) => device.read(buf),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// SPI device wired to the sensor: the first byte is the address, bit 7 set for a read; the
+/// sensor does not drive MISO during a write.
+struct FakeSpi(Rc<RefCell<Device>>);
+
+impl spi::ErrorType for FakeSpi {
+    type Error = Nack;
+}
+
+impl SpiDevice for FakeSpi {
+    fn transaction(&mut self, operations: &mut [SpiOperation<'_, u8>]) -> Result<(), Nack> {
+        let mut device = self.0.borrow_mut();
+        if device.fault() {
+            return Err(Nack);
+        }
+        let mut read = None;
+        for operation in operations {
+            match operation {
+                SpiOperation::Write(bytes) => {
+                    let mut bytes = bytes.iter();
+                    if read.is_none() {
+                        let Some(first) = bytes.next() else { continue };
+                        device.addr = first & 0x7F;
+                        read = Some(first & 0x80 != 0);
+                    }
+                    let data: Vec<u8> = bytes.copied().collect();
+                    if read == Some(false) {
+                        device.write(&data);
+                    }
+                }
+                SpiOperation::Read(buf) => {
+                    if read == Some(true) {
+                        device.read(buf);
+                    } else {
+                        buf.fill(0xFF);
+                    }
+                }
+                _ => unimplemented!(),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Detail a hand-written bus gets wrong.
+#[derive(Clone, Copy)]
+enum Flaw {
+    NoReadBit,
+    NoIncrement,
+    WordSwap,
+    FirstByteOnly,
+    Truncate64,
+    SwallowErrors,
+}
+
+/// Hand-written SPI bus with a flaw.
+struct Broken {
+    spi: FakeSpi,
+    flaw: Flaw,
+}
+
+impl BusOperation for Broken {
+    type Error = Nack;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Nack> {
+        self.spi.read(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Nack> {
+        let wbuf = match self.flaw {
+            Flaw::FirstByteOnly => &wbuf[..wbuf.len().min(2)],
+            Flaw::Truncate64 => &wbuf[..wbuf.len().min(64)],
+            _ => wbuf,
+        };
+        let result = self.spi.write(wbuf);
+        match self.flaw {
+            Flaw::SwallowErrors => Ok(()),
+            _ => result,
+        }
+    }
+
+    fn write_byte_read_bytes(&mut self, wbuf: &[u8; 1], rbuf: &mut [u8]) -> Result<(), Nack> {
+        let reg = match self.flaw {
+            Flaw::NoReadBit => wbuf[0],
+            _ => wbuf[0] | 0x80,
+        };
+        let result = match self.flaw {
+            Flaw::NoIncrement => rbuf.iter_mut().try_for_each(|byte| {
+                self.spi.transaction(&mut [
+                    SpiOperation::Write(&[reg]),
+                    SpiOperation::Read(std::slice::from_mut(byte)),
+                ])
+            }),
+            Flaw::Truncate64 => {
+                let len = rbuf.len().min(64);
+                self.spi.transaction(&mut [
+                    SpiOperation::Write(&[reg]),
+                    SpiOperation::Read(&mut rbuf[..len]),
+                ])
+            }
+            _ => self
+                .spi
+                .transaction(&mut [SpiOperation::Write(&[reg]), SpiOperation::Read(rbuf)]),
+        };
+        match self.flaw {
+            Flaw::WordSwap => {
+                for word in rbuf.chunks_exact_mut(2) {
+                    word.swap(0, 1);
+                }
+                result
+            }
+            Flaw::SwallowErrors => Ok(()),
+            _ => result,
+        }
+    }
+}
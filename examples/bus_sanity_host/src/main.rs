@@ -0,0 +1,176 @@
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{BusSanity, DecodeRetry, Error, Iis2dlpc};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Healthy bus
+    let mut sensor = new_sensor();
+    check(
+        "healthy bus: Ok in two reads",
+        sensor.bus_sanity_check() == Ok(BusSanity::Ok) && sensor.bus.reads == 2,
+    );
+    check(
+        "healthy bus: running configuration",
+        sensor.brownout_check() == Ok(false),
+    );
+    sensor.bus.regs.set_reg(Reg::Ctrl1, 0x00);
+    check(
+        "healthy bus, CTRL1 at its default: brown-out",
+        sensor.brownout_check() == Ok(true),
+    );
+
+    // Another device
+    let mut sensor = new_sensor();
+    sensor.bus.regs.set_reg(Reg::WhoAmI, 0x33);
+    check(
+        "another identifier: WhoAmIError",
+        sensor.bus_sanity_check() == Err(Error::WhoAmIError(0x33)),
+    );
+
+    // Every bit reads 1
+    let mut sensor = new_sensor();
+    sensor.bus.stuck = Some(0xFF);
+    check(
+        "stuck high: classified",
+        sensor.bus_sanity_check() == Ok(BusSanity::StuckHigh),
+    );
+    sensor.bus.reads = 0;
+    check(
+        "stuck high: power_mode_get reports the code 0x1F, 4 reads",
+        sensor.power_mode_get() == Err(Error::UnexpectedValue(0x1F)) && sensor.bus.reads == 4,
+    );
+    check(
+        "stuck high: data_rate_get reports the code 0x3F",
+        sensor.data_rate_get() == Err(Error::UnexpectedValue(0x3F)),
+    );
+    check(
+        "stuck high: tap_axis_priority_get reports the code 7",
+        sensor.tap_axis_priority_get() == Err(Error::UnexpectedValue(7)),
+    );
+    check(
+        "stuck high: not counted as transient",
+        sensor.transient_decode_errors() == 0,
+    );
+    sensor.decode_retry_set(DecodeRetry::Never);
+    sensor.bus.reads = 0;
+    check(
+        "stuck high, no retry: power_mode_get in 2 reads",
+        sensor.power_mode_get() == Err(Error::UnexpectedValue(0x1F)) && sensor.bus.reads == 2,
+    );
+    check(
+        "stuck high: brownout_check reports 0xFF",
+        sensor.brownout_check() == Err(Error::UnexpectedValue(0xFF)),
+    );
+
+    // Every bit reads 0
+    let mut sensor = new_sensor();
+    sensor.bus.stuck = Some(0x00);
+    check(
+        "stuck low: classified",
+        sensor.bus_sanity_check() == Ok(BusSanity::StuckLow),
+    );
+    // Before any read of CTRL1: the 0x00 read by power_mode_get also sets the data rate last known
+    // to the driver, which brownout_check then takes as powered down
+    check(
+        "stuck low: brownout_check reports 0x00, not a reset",
+        sensor.brownout_check() == Err(Error::UnexpectedValue(0x00)),
+    );
+    check(
+        "stuck low: power_mode_get decodes the reset value",
+        sensor.power_mode_get() == Ok(Mode::ContLowPwr12bit),
+    );
+    check(
+        "stuck low: brownout_check skips a data rate known off",
+        sensor.brownout_check() == Ok(false),
+    );
+
+    // A reserved data rate alone, and a transient reserved mode
+    let mut sensor = new_sensor();
+    sensor
+        .bus
+        .regs
+        .set_reg(Reg::Ctrl1, Ctrl1::new().with_odr(0x0A).into_bits());
+    check(
+        "reserved odr code: reported",
+        sensor.data_rate_get() == Err(Error::UnexpectedValue(0x0A)),
+    );
+    let mut sensor = new_sensor();
+    sensor.bus.glitches = 1;
+    check(
+        "one corrupted CTRL1 read: mode recovered, counted",
+        sensor.power_mode_get() == Ok(Mode::HighPerformance)
+            && sensor.transient_decode_errors() == 1,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A sensor in high-performance mode at 100 Hz.
+fn new_sensor() -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(SimBus::default(), NoDelay);
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.bus.reads = 0;
+    sensor
+}
+
+/// Register map whose reads can be forced to a constant, as on a stuck data line.
+#[derive(Default)]
+struct SimBus {
+    regs: MockBus,
+    /// Value of every byte read.
+    stuck: Option<u8>,
+    /// Number of register reads.
+    reads: u32,
+    /// Number of `CTRL1` reads returning the reserved `mode` code.
+    glitches: u32,
+}
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        if self.stuck.is_some() {
+            return Ok(());
+        }
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.reads += 1;
+        self.regs.write_byte_read_bytes(wbuf, rbuf)?;
+        if let Some(val) = self.stuck {
+            rbuf.fill(val);
+        } else if wbuf[0] == Reg::Ctrl1 as u8 && self.glitches > 0 {
+            self.glitches -= 1;
+            rbuf[0] = Ctrl1::from_bits(rbuf[0]).with_mode(0x03).into_bits();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::blob::{crc16, BlobError, ConfigBlob, BLOB_LEN, BLOB_VERSION};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::snapshot::{ConfigSnapshot, CONFIG_REGS};
+use iis2dlpc_rs::{Error, Iis2dlpc};
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    check(
+        "CRC-16/CCITT-FALSE check value",
+        crc16(b"123456789") == 0x29B1,
+    );
+
+    // Sender side: a profile configured on a mock device
+    let profile = profile();
+    let mut blob = [0; BLOB_LEN];
+    let len = ConfigBlob::encode(&profile, &mut blob).unwrap();
+    println!("Profile blob: {}", hex(&blob[..len]));
+    check(
+        "encode then decode",
+        ConfigBlob::decode(&blob) == Ok(profile),
+    );
+    check(
+        "encode into a short buffer",
+        ConfigBlob::encode(&profile, &mut [0; BLOB_LEN - 1])
+            == Err(BlobError::Length(BLOB_LEN - 1)),
+    );
+
+    // Every value of every byte but the right one is caught
+    let caught = (0..BLOB_LEN).all(|pos| {
+        (0..=u8::MAX).filter(|val| *val != blob[pos]).all(|val| {
+            let mut corrupted = blob;
+            corrupted[pos] = val;
+            matches!(ConfigBlob::decode(&corrupted), Err(BlobError::Crc { .. }))
+        })
+    });
+    check("corruption at every byte position", caught);
+
+    let truncated =
+        (0..BLOB_LEN).all(|len| ConfigBlob::decode(&blob[..len]) == Err(BlobError::Length(len)));
+    check("truncation at every length", truncated);
+
+    // A newer layout, with a valid CRC
+    let mut newer = blob;
+    newer[2] = BLOB_VERSION + 1;
+    let crc = crc16(&newer[..BLOB_LEN - 2]).to_le_bytes();
+    newer[BLOB_LEN - 2..].copy_from_slice(&crc);
+    check(
+        "version mismatch",
+        ConfigBlob::decode(&newer) == Err(BlobError::Version(BLOB_VERSION + 1)),
+    );
+
+    // Receiver side: a fresh device takes the profile
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    let rejected = sensor.apply_blob(&newer);
+    check(
+        "rejected blob writes nothing",
+        rejected == Err(Error::Blob(BlobError::Version(BLOB_VERSION + 1)))
+            && sensor.config_snapshot_get().unwrap() != profile,
+    );
+    sensor.apply_blob(&blob).unwrap();
+    let applied = CONFIG_REGS
+        .iter()
+        .all(|reg| sensor.bus.reg(*reg) == profile.get(*reg).unwrap());
+    check("encode, decode and apply round trip", applied);
+    check(
+        "data rate applied",
+        sensor.data_rate_get().unwrap() == Odr::_100hz,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A wake-up profile: ±4 g, 100 Hz high-performance, wake-up on INT1.
+fn profile() -> ConfigSnapshot {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.block_data_update_set(true).unwrap();
+    sensor.full_scale_set(Fs::_4g).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.wkup_threshold_set(4).unwrap();
+    sensor
+        .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_wu(1))
+        .unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.config_snapshot_get().unwrap()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,323 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::snapshot::{ConfigDelta, ConfigSnapshot, CONFIG_REGS};
+use iis2dlpc_rs::{Error, Iis2dlpc, PROPERTY_ENABLE};
+use st_mems_bus::BusOperation;
+
+const ODRS: [Odr; 4] = [Odr::Off, Odr::_12_5hz, Odr::_100hz, Odr::_200hz];
+const MODES: [Mode; 4] = [
+    Mode::ContLowPwr12bit,
+    Mode::ContLowPwrLowNoise2,
+    Mode::HighPerformance,
+    Mode::HighPerformanceLowNoise,
+];
+const SCALES: [Fs; 4] = [Fs::_2g, Fs::_4g, Fs::_8g, Fs::_16g];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // The tricky cases, by their write sequence
+    let base = config(Odr::_100hz, Mode::HighPerformance, Fs::_2g);
+    let off = config(Odr::Off, Mode::HighPerformance, Fs::_2g);
+    let cases = [
+        ("nothing changes", &base, &base, vec![]),
+        (
+            "only the data rate changes",
+            &base,
+            &config(Odr::_200hz, Mode::HighPerformance, Fs::_2g),
+            vec![Reg::Ctrl1],
+        ),
+        (
+            "only the full scale changes, running",
+            &base,
+            &config(Odr::_100hz, Mode::HighPerformance, Fs::_8g),
+            vec![Reg::Ctrl1, Reg::Ctrl6, Reg::Ctrl1],
+        ),
+        (
+            "only the full scale changes, powered down",
+            &off,
+            &config(Odr::Off, Mode::HighPerformance, Fs::_8g),
+            vec![Reg::Ctrl6],
+        ),
+        (
+            "full scale and data rate change",
+            &base,
+            &config(Odr::_12_5hz, Mode::HighPerformance, Fs::_16g),
+            vec![Reg::Ctrl1, Reg::Ctrl6, Reg::Ctrl1],
+        ),
+        (
+            "only the mode changes, running",
+            &base,
+            &config(Odr::_100hz, Mode::ContLowPwr12bit, Fs::_2g),
+            vec![Reg::Ctrl1, Reg::Ctrl1],
+        ),
+        (
+            "only the low-noise setting changes, running",
+            &base,
+            &config(Odr::_100hz, Mode::HighPerformanceLowNoise, Fs::_2g),
+            vec![Reg::Ctrl1, Reg::Ctrl6, Reg::Ctrl1],
+        ),
+        (
+            "full scale changes while powering down",
+            &base,
+            &config(Odr::Off, Mode::HighPerformance, Fs::_4g),
+            vec![Reg::Ctrl1, Reg::Ctrl6],
+        ),
+        (
+            "powered down to running with a new full scale",
+            &off,
+            &config(Odr::_100hz, Mode::HighPerformance, Fs::_4g),
+            vec![Reg::Ctrl6, Reg::Ctrl1],
+        ),
+    ];
+    for (name, source, target, expected) in cases {
+        let delta = source.diff(target);
+        let regs = regs_of(&delta);
+        let mut sensor = device(source);
+        let applied = sensor.apply_delta(&delta);
+        check(
+            &format!("{name}: {regs:?}"),
+            regs == expected
+                && applied.is_ok()
+                && sensor.bus.writes.len() == expected.len()
+                && sensor.config_snapshot_get().as_ref() == Ok(target),
+        );
+    }
+
+    // Profiles touching the embedded functions: thresholds, scale, routing, CTRL1 last
+    let shipping = config(Odr::_12_5hz, Mode::ContLowPwr12bit, Fs::_2g);
+    let active = profile(Odr::_100hz, Mode::HighPerformance, Fs::_4g, |sensor| {
+        sensor.wkup_threshold_set(8).unwrap();
+        sensor.wkup_dur_set(2).unwrap();
+        sensor
+            .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_wu(PROPERTY_ENABLE))
+            .unwrap();
+    });
+    let delta = shipping.diff(&active);
+    let regs = regs_of(&delta);
+    check(
+        &format!("shipping to active: {regs:?}"),
+        regs == [
+            Reg::Ctrl1,
+            Reg::WakeUpThs,
+            Reg::WakeUpDur,
+            Reg::Ctrl6,
+            Reg::Ctrl7,
+            Reg::Ctrl4Int1PadCtrl,
+            Reg::Ctrl1,
+        ] && delta.stops_data_rate(),
+    );
+    check(
+        "active to shipping: the reverse registers",
+        regs_of(&active.diff(&shipping)) == regs,
+    );
+
+    // Every pair of data rate, mode and full scale
+    let configs: Vec<ConfigSnapshot> = ODRS
+        .iter()
+        .flat_map(|odr| MODES.iter().map(move |mode| (*odr, *mode)))
+        .flat_map(|(odr, mode)| SCALES.iter().map(move |fs| config(odr, mode, *fs)))
+        .collect();
+    let mut pairs = 0;
+    let mut violations = Vec::new();
+    for source in &configs {
+        for target in &configs {
+            pairs += 1;
+            if let Err(why) = check_pair(source, target) {
+                violations.push(why);
+            }
+        }
+    }
+    check(
+        &format!("{pairs} pairs: reach the target, never rescale while running, minimal"),
+        violations.is_empty(),
+    );
+    for why in violations.iter().take(5) {
+        println!("  {why}");
+    }
+
+    // Rollback on a failure at every write
+    let target = config(Odr::_12_5hz, Mode::ContLowPwrLowNoise2, Fs::_16g);
+    let delta = base.diff(&target);
+    let rolled_back = (0..delta.writes().len()).all(|fail_at| {
+        let mut sensor = device(&base);
+        sensor.bus.fail_at = Some(fail_at);
+        let err = sensor.apply_delta(&delta).unwrap_err();
+        let ctrl1_rollbacks = sensor.bus.writes[fail_at + 1..]
+            .iter()
+            .filter(|(reg, _)| *reg == Reg::Ctrl1 as u8)
+            .count();
+        err.is_rolled_back()
+            && sensor.config_snapshot_get().as_ref() == Ok(&base)
+            && ctrl1_rollbacks == 1
+            && sensor.bus.writes.last().map(|(reg, _)| *reg) == Some(Reg::Ctrl1 as u8)
+    });
+    check(
+        "failure at every write: source restored, CTRL1 rolled back once and last",
+        rolled_back,
+    );
+
+    // The scaling lock refuses the full scale write
+    let mut sensor = device(&base);
+    sensor.strict_scaling_lock(true).unwrap();
+    let err = sensor
+        .apply_delta(&base.diff(&config(Odr::_100hz, Mode::HighPerformance, Fs::_8g)))
+        .unwrap_err();
+    check(
+        "scaling lock: refused, data rate restored",
+        err.error == Error::ScalingLocked
+            && err.is_rolled_back()
+            && sensor.config_snapshot_get().as_ref() == Ok(&base),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Apply the delta from `source` to `target` on a device and check the write sequence.
+fn check_pair(source: &ConfigSnapshot, target: &ConfigSnapshot) -> Result<(), String> {
+    let delta = source.diff(target);
+    let mut sensor = device(source);
+    sensor
+        .apply_delta(&delta)
+        .map_err(|err| format!("apply failed: {err:?}"))?;
+    if sensor.config_snapshot_get().as_ref() != Ok(target) {
+        return Err(format!("target not reached: {source:?} -> {target:?}"));
+    }
+
+    // Replay the writes on the device state
+    let mut ctrl1 = Ctrl1::from_bits(source.get(Reg::Ctrl1).unwrap());
+    let mut ctrl6 = Ctrl6::from_bits(source.get(Reg::Ctrl6).unwrap());
+    for (reg, val) in &sensor.bus.writes {
+        let running = ctrl1.odr() != 0;
+        if *reg == Reg::Ctrl1 as u8 {
+            let new = Ctrl1::from_bits(*val);
+            if running && (new.mode(), new.lp_mode()) != (ctrl1.mode(), ctrl1.lp_mode()) {
+                return Err(format!("mode changed while running: {delta:?}"));
+            }
+            ctrl1 = new;
+        } else if *reg == Reg::Ctrl6 as u8 {
+            let new = Ctrl6::from_bits(*val);
+            if running && (new.fs(), new.low_noise()) != (ctrl6.fs(), ctrl6.low_noise()) {
+                return Err(format!("scaling changed while running: {delta:?}"));
+            }
+            ctrl6 = new;
+        }
+    }
+
+    // Minimal: each differing register once, CTRL1 once more for the power-down, no no-op write
+    let differing = CONFIG_REGS
+        .iter()
+        .filter(|reg| **reg != Reg::Ctrl1 && source.get(**reg) != target.get(**reg))
+        .count();
+    let others = delta.writes().iter().filter(|(reg, _)| *reg != Reg::Ctrl1);
+    let ctrl1_writes: Vec<u8> = delta
+        .writes()
+        .iter()
+        .filter(|(reg, _)| *reg == Reg::Ctrl1)
+        .map(|(_, val)| *val)
+        .collect();
+    let mut held = source.get(Reg::Ctrl1).unwrap();
+    let no_op = ctrl1_writes.iter().any(|val| {
+        let same = *val == held;
+        held = *val;
+        same
+    });
+    if others.count() != differing
+        || no_op
+        || ctrl1_writes.len() > 1 + usize::from(delta.stops_data_rate())
+    {
+        return Err(format!("not minimal: {delta:?}"));
+    }
+    let needs_stop = source.data_rate() != Odr::Off
+        && (source.get(Reg::Ctrl6) != target.get(Reg::Ctrl6)
+            || Ctrl1::from_bits(source.get(Reg::Ctrl1).unwrap()).with_odr(0)
+                != Ctrl1::from_bits(target.get(Reg::Ctrl1).unwrap()).with_odr(0));
+    if delta.stops_data_rate() != needs_stop {
+        return Err(format!("needless or missing power-down: {delta:?}"));
+    }
+    Ok(())
+}
+
+/// Registers written by a delta, in order.
+fn regs_of(delta: &ConfigDelta) -> Vec<Reg> {
+    delta.writes().iter().map(|(reg, _)| *reg).collect()
+}
+
+/// Configuration with the default embedded functions.
+fn config(odr: Odr, mode: Mode, fs: Fs) -> ConfigSnapshot {
+    profile(odr, mode, fs, |_| {})
+}
+
+/// Configuration set up on a mock device.
+fn profile(
+    odr: Odr,
+    mode: Mode,
+    fs: Fs,
+    setup: impl FnOnce(&mut Iis2dlpc<MockBus, NoDelay>),
+) -> ConfigSnapshot {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.power_mode_set(mode).unwrap();
+    sensor.full_scale_set(fs).unwrap();
+    setup(&mut sensor);
+    sensor.data_rate_set(odr).unwrap();
+    sensor.config_snapshot_get().unwrap()
+}
+
+/// A device holding `snapshot`, with the write log cleared.
+fn device(snapshot: &ConfigSnapshot) -> Iis2dlpc<LogBus, NoDelay> {
+    let mut sensor = Iis2dlpc::from_bus(LogBus::default(), NoDelay);
+    sensor.config_snapshot_apply(snapshot).unwrap();
+    sensor.bus.writes.clear();
+    sensor
+}
+
+/// Mock bus logging the register writes, failing the one at `fail_at` without taking effect.
+#[derive(Default)]
+struct LogBus {
+    regs: MockBus,
+    writes: Vec<(u8, u8)>,
+    fail_at: Option<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Nack;
+
+impl BusOperation for LogBus {
+    type Error = Nack;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf).map_err(|_| Nack)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        if self.fail_at == Some(self.writes.len()) {
+            self.fail_at = None;
+            self.writes.push((wbuf[0], wbuf[1]));
+            return Err(Nack);
+        }
+        self.writes.push((wbuf[0], wbuf[1]));
+        self.regs.write_bytes(wbuf).map_err(|_| Nack)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.regs
+            .write_byte_read_bytes(wbuf, rbuf)
+            .map_err(|_| Nack)
+    }
+}
@@ -0,0 +1,148 @@
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{DecodeRetry, Error, Iis2dlpc};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<GlitchBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let mut sensor = new_sensor();
+    let prior = sensor.tap_axis_priority_get();
+    check(
+        "clean read: decoded in one read",
+        prior == Ok(TapPrior::Zyx)
+            && sensor.bus.reads == 1
+            && sensor.transient_decode_errors() == 0,
+    );
+
+    let mut sensor = new_sensor();
+    sensor.bus.glitches = 1;
+    let prior = sensor.tap_axis_priority_get();
+    check(
+        "one corrupted read: recovered silently, counted",
+        prior == Ok(TapPrior::Zyx)
+            && sensor.bus.reads == 2
+            && sensor.transient_decode_errors() == 1,
+    );
+
+    sensor.bus.glitches = 1;
+    sensor.bus.set_tap_src_single_tap_on_y();
+    let tap = sensor.tap_event_get().map(|mut taps| taps.next());
+    check(
+        "one corrupted read during a tap decode: recovered, counted",
+        tap.is_ok_and(|tap| tap.is_some_and(|tap| tap.axis == Some(Axis::Y)))
+            && sensor.transient_decode_errors() == 2,
+    );
+
+    sensor.transient_decode_errors_reset();
+    check("counter reset", sensor.transient_decode_errors() == 0);
+
+    let mut sensor = new_sensor();
+    sensor.bus.glitches = 2;
+    let prior = sensor.tap_axis_priority_get();
+    check(
+        "two corrupted reads: error, not counted",
+        prior == Err(Error::UnexpectedValue(7))
+            && sensor.bus.reads == 2
+            && sensor.transient_decode_errors() == 0,
+    );
+
+    let mut sensor = new_sensor();
+    sensor.decode_retry_set(DecodeRetry::Never);
+    sensor.bus.glitches = 1;
+    let prior = sensor.tap_axis_priority_get();
+    check(
+        "retry disabled: error after one read",
+        prior == Err(Error::UnexpectedValue(7))
+            && sensor.bus.reads == 1
+            && sensor.transient_decode_errors() == 0,
+    );
+
+    // A read-modify-write cycle reads once and is not retried
+    let mut sensor = new_sensor();
+    sensor.bus.glitches = 1;
+    sensor.tap_axis_priority_set(TapPrior::Xyz).unwrap();
+    check(
+        "read-modify-write: single read, no retry",
+        sensor.bus.reads == 1 && sensor.transient_decode_errors() == 0,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A sensor with the tap axis priority set to Z, Y, X, and the read counter cleared.
+fn new_sensor() -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(GlitchBus::default(), NoDelay);
+    sensor.tap_axis_priority_set(TapPrior::Zyx).unwrap();
+    sensor.bus.reads = 0;
+    sensor
+}
+
+/// Mock bus corrupting the next reads of `TAP_THS_Y` into a reserved axis priority.
+#[derive(Default)]
+struct GlitchBus {
+    regs: MockBus,
+    /// Number of reads of `TAP_THS_Y` left to corrupt.
+    glitches: u32,
+    /// Number of reads of `TAP_THS_Y`.
+    reads: u32,
+}
+
+impl GlitchBus {
+    fn set_tap_src_single_tap_on_y(&mut self) {
+        let bit = |get: fn(TapSrc) -> u8| {
+            (0..8)
+                .map(|i| 1u8 << i)
+                .find(|b| get(TapSrc::from_bits(*b)) != 0)
+                .unwrap()
+        };
+        self.regs.set_reg(
+            Reg::TapSrc,
+            bit(|src| src.single_tap()) | bit(|src| src.y_tap()) | bit(|src| src.tap_ia()),
+        );
+    }
+}
+
+impl BusOperation for GlitchBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.regs.write_byte_read_bytes(wbuf, rbuf)?;
+        if wbuf[0] == Reg::TapThsY as u8 {
+            self.reads += 1;
+            if self.glitches > 0 {
+                self.glitches -= 1;
+                // Reserved code: bit flip on the priority field
+                rbuf[0] = TapThsY::from_bits(rbuf[0]).with_tap_prior(7).into_bits();
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::Iis2dlpc;
+
+/// `CTRL2` with `sim`, `i2c_disable`, `if_add_inc` and `cs_pu_disc` set, `bdu` clear.
+const CTRL2: u8 = 0x17;
+/// The `bdu` bit of `CTRL2`.
+const BDU: u8 = 0x08;
+/// The `if_add_inc` bit of `CTRL2`.
+const IF_ADD_INC: u8 = 0x04;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+
+    // CTRL2 through the driver: each switch touches its own bit
+    sensor.bus.set_reg(Reg::Ctrl2, CTRL2);
+    sensor.block_data_update_set(true).unwrap();
+    check(
+        "block_data_update_set(true): bdu alone set",
+        sensor.bus.reg(Reg::Ctrl2) == CTRL2 | BDU && sensor.block_data_update_get() == Ok(true),
+    );
+    sensor.auto_increment_set(false).unwrap();
+    check(
+        "auto_increment_set(false): if_add_inc alone cleared",
+        sensor.bus.reg(Reg::Ctrl2) == (CTRL2 | BDU) & !IF_ADD_INC
+            && sensor.auto_increment_get() == Ok(false),
+    );
+    sensor.block_data_update_set(false).unwrap();
+    sensor.auto_increment_set(true).unwrap();
+    check(
+        "both switches back: CTRL2 as preset",
+        sensor.bus.reg(Reg::Ctrl2) == CTRL2,
+    );
+
+    // CTRL2 through the register struct: an out-of-range value is rejected
+    let mut ctrl2 = Ctrl2::from_bits(CTRL2);
+    check(
+        "Ctrl2::set_bdu_checked(2): rejected, CTRL2 unchanged",
+        ctrl2.set_bdu_checked(2).is_err() && ctrl2.into_bits() == CTRL2,
+    );
+    check(
+        "Ctrl2::set_if_add_inc_checked(0xFF): rejected, CTRL2 unchanged",
+        ctrl2.set_if_add_inc_checked(0xFF).is_err() && ctrl2.into_bits() == CTRL2,
+    );
+    check(
+        "Ctrl2::with_bdu_checked(1): bdu alone set",
+        ctrl2.with_bdu_checked(1).map(Ctrl2::into_bits) == Ok(CTRL2 | BDU),
+    );
+
+    // Tap axes, next to the 5-bit threshold of TAP_THS_Z
+    sensor.tap_threshold_z_set(9).unwrap();
+    sensor.tap_detection_on_x_set(true).unwrap();
+    sensor.tap_detection_on_y_set(true).unwrap();
+    sensor.tap_detection_on_z_set(true).unwrap();
+    check(
+        "tap_detection_on_x/y/z_set(true): threshold kept",
+        sensor.tap_threshold_z_get() == Ok(9)
+            && sensor.tap_detection_on_x_get() == Ok(true)
+            && sensor.tap_detection_on_y_get() == Ok(true)
+            && sensor.tap_detection_on_z_get() == Ok(true),
+    );
+    sensor.tap_detection_on_y_set(false).unwrap();
+    check(
+        "tap_detection_on_y_set(false): the other axes kept",
+        sensor.tap_detection_on_x_get() == Ok(true)
+            && sensor.tap_detection_on_y_get() == Ok(false)
+            && sensor.tap_detection_on_z_get() == Ok(true),
+    );
+
+    // 4D, next to the 6D threshold of TAP_THS_X
+    sensor.tap_threshold_x_set(12).unwrap();
+    sensor.fourd_mode_set(true).unwrap();
+    check(
+        "fourd_mode_set(true): threshold kept",
+        sensor.fourd_mode_get() == Ok(true) && sensor.tap_threshold_x_get() == Ok(12),
+    );
+
+    // CTRL7 switches
+    sensor.all_on_int1_set(true).unwrap();
+    sensor.reference_mode_set(true).unwrap();
+    check(
+        "all_on_int1_set, reference_mode_set: both read back",
+        sensor.all_on_int1_get() == Ok(true) && sensor.reference_mode_get() == Ok(true),
+    );
+    sensor.reference_mode_set(false).unwrap();
+    check(
+        "reference_mode_set(false): all_on_int1 kept",
+        sensor.all_on_int1_get() == Ok(true) && sensor.reference_mode_get() == Ok(false),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,147 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::event::{Event, TapEvent, TapKind};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::remap::{AxisRemap, SignedAxis};
+use iis2dlpc_rs::Iis2dlpc;
+use st_mems_bus::BusOperation;
+
+/// `TAP_SRC`: double tap on Z, negative (`z_tap`, `tap_sign`, `double_tap`, `tap_ia`).
+const TAP_SRC_DOUBLE_Z_NEG: u8 = 0x59;
+/// `WAKE_UP_SRC`: wake-up on X (`x_wu`, `wu_ia`).
+const WAKE_UP_SRC_X: u8 = 0x0C;
+/// `ALL_INT_SRC`: wake-up and double tap (`wu_ia`, `double_tap`).
+const ALL_INT_SRC_WU_DOUBLE: u8 = 0x0A;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let double_tap = Event::Tap(TapEvent {
+        kind: TapKind::Double,
+        axis: Some(Axis::Z),
+        negative: true,
+        raw: TAP_SRC_DOUBLE_Z_NEG,
+    });
+    let wake_up_x = Event::WakeUp {
+        axes: [true, false, false],
+    };
+
+    // Latched interrupts: the read of ALL_INT_SRC clears the flags
+    let mut sensor = Iis2dlpc::from_bus(LatchBus::default(), NoDelay);
+    check("no event flagged: None", sensor.event_get() == Ok(None));
+    flag_tap_and_wake_up(&mut sensor.bus.regs);
+    let reads = sensor.bus.reads;
+    check(
+        "first call: the double tap, one source burst and the tap priority read",
+        sensor.event_get() == Ok(Some(double_tap)) && sensor.bus.reads == reads + 2,
+    );
+    check(
+        "read of ALL_INT_SRC: latched flags cleared",
+        [Reg::WakeUpSrc, Reg::TapSrc, Reg::SixdSrc, Reg::AllIntSrc]
+            .iter()
+            .all(|reg| sensor.bus.regs.reg(*reg) == 0),
+    );
+    let reads = sensor.bus.reads;
+    check(
+        "second call: the wake-up on X, kept from the same read",
+        sensor.event_get() == Ok(Some(wake_up_x)) && sensor.bus.reads == reads,
+    );
+    check(
+        "third call: None, each latched event returned once",
+        sensor.event_get() == Ok(None)
+            && sensor.event_counters().wake_up == 1
+            && sensor.event_counters().double_tap == 1,
+    );
+
+    // Pulsed interrupts: an event still flagged is returned by every read
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.set_reg(Reg::WakeUpSrc, WAKE_UP_SRC_X);
+    sensor.bus.set_reg(Reg::AllIntSrc, 0x02);
+    check(
+        "pulsed wake-up: returned by every read",
+        sensor.event_get() == Ok(Some(wake_up_x)) && sensor.event_get() == Ok(Some(wake_up_x)),
+    );
+
+    // Board frame, sensor X read by the board Y axis
+    let swap = AxisRemap::new([
+        SignedAxis::new(Axis::Y, false),
+        SignedAxis::new(Axis::X, false),
+        SignedAxis::new(Axis::Z, false),
+    ])
+    .unwrap();
+    sensor.axis_remap_set(swap);
+    check(
+        "axis remap: the wake-up axes permuted",
+        sensor.event_get()
+            == Ok(Some(Event::WakeUp {
+                axes: [false, true, false],
+            })),
+    );
+
+    // Free-fall and sleep change, in decoding order
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    // `sleep_state_ia` and `ff_ia`; `ff_ia` and `sleep_change_ia`
+    sensor.bus.set_reg(Reg::WakeUpSrc, 0x30);
+    sensor.bus.set_reg(Reg::AllIntSrc, 0x21);
+    check(
+        "free-fall then sleep change",
+        sensor.event_get() == Ok(Some(Event::FreeFall))
+            && sensor.event_get() == Ok(Some(Event::SleepChange { sleeping: true })),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Flag a double tap on Z and a wake-up on X.
+fn flag_tap_and_wake_up(regs: &mut MockBus) {
+    regs.set_reg(Reg::TapSrc, TAP_SRC_DOUBLE_Z_NEG);
+    regs.set_reg(Reg::WakeUpSrc, WAKE_UP_SRC_X);
+    regs.set_reg(Reg::AllIntSrc, ALL_INT_SRC_WU_DOUBLE);
+}
+
+/// Mock bus with latched interrupts: a read covering `ALL_INT_SRC` clears the source registers, as the device
+/// does. Counts the reads.
+#[derive(Default)]
+struct LatchBus {
+    regs: MockBus,
+    reads: usize,
+}
+
+impl BusOperation for LatchBus {
+    type Error = <MockBus as BusOperation>::Error;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.reads += 1;
+        self.regs.write_byte_read_bytes(wbuf, rbuf)?;
+        let covered = usize::from(wbuf[0])..usize::from(wbuf[0]) + rbuf.len();
+        if covered.contains(&usize::from(Reg::AllIntSrc as u8)) {
+            for reg in [Reg::WakeUpSrc, Reg::TapSrc, Reg::SixdSrc, Reg::AllIntSrc] {
+                self.regs.set_reg(reg, 0);
+            }
+        }
+        Ok(())
+    }
+}
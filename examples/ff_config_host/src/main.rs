@@ -0,0 +1,98 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Raw code split over two registers
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    let round_trip = (0..=0x3F).all(|code| {
+        sensor.ff_dur_set(code).is_ok()
+            && sensor.ff_dur_get() == Ok(code)
+            && WakeUpDur::from_bits(sensor.bus.reg(Reg::WakeUpDur)).ff_dur() == code >> 5
+            && FreeFall::from_bits(sensor.bus.reg(Reg::FreeFall)).ff_dur() == code & 0x1F
+    });
+    check(
+        "ff_dur_set/get: every code, bit 5 and bits 4..0",
+        round_trip,
+    );
+    let before = (
+        sensor.bus.reg(Reg::WakeUpDur),
+        sensor.bus.reg(Reg::FreeFall),
+    );
+    check(
+        "ff_dur_set(0x40): invalid argument, nothing written",
+        sensor.ff_dur_set(0x40) == Err(Error::InvalidArgument)
+            && (
+                sensor.bus.reg(Reg::WakeUpDur),
+                sensor.bus.reg(Reg::FreeFall),
+            ) == before,
+    );
+
+    // In milliseconds
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    check(
+        "data rate off: not running",
+        sensor.ff_config_set(FfThs::_10Lsb, 50)
+            == Err(Error::InvalidConfig(ConfigError::DataRateNotRunning)),
+    );
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.wkup_dur_set(3).unwrap();
+    sensor.act_sleep_dur_set(9).unwrap();
+    check(
+        "100 Hz, 50 ms: code 5, 50 ms",
+        sensor.ff_config_set(FfThs::_10Lsb, 50) == Ok(50) && sensor.ff_dur_get() == Ok(5),
+    );
+    check(
+        "100 Hz, 400 ms: code 40, bit 5 in WAKE_UP_DUR",
+        sensor.ff_config_set(FfThs::_13Lsb, 400) == Ok(400)
+            && WakeUpDur::from_bits(sensor.bus.reg(Reg::WakeUpDur)).ff_dur() == 1
+            && FreeFall::from_bits(sensor.bus.reg(Reg::FreeFall)).ff_dur() == 8,
+    );
+    check(
+        "ff_config_get: threshold and 400 ms",
+        sensor.ff_config_get() == Ok((FfThs::_13Lsb, 400)),
+    );
+    check(
+        "wake-up and sleep durations kept",
+        sensor.wkup_dur_get() == Ok(3) && sensor.act_sleep_dur_get() == Ok(9),
+    );
+    check(
+        "100 Hz, 634 ms: code 63, 630 ms",
+        sensor.ff_config_set(FfThs::_5Lsb, 634) == Ok(630) && sensor.ff_dur_get() == Ok(63),
+    );
+    check(
+        "100 Hz, 800 ms: invalid argument, nothing written",
+        sensor.ff_config_set(FfThs::_8Lsb, 800) == Err(Error::InvalidArgument)
+            && sensor.ff_config_get() == Ok((FfThs::_5Lsb, 630)),
+    );
+    sensor.bus.expect(&[]);
+    check(
+        "60 s: beyond any data rate, nothing accessed",
+        sensor.ff_config_set(FfThs::_8Lsb, 60_000) == Err(Error::InvalidArgument)
+            && sensor.bus.expectations_check() == Ok(()),
+    );
+    sensor.data_rate_set(Odr::_400hz).unwrap();
+    check(
+        "400 Hz, 100 ms: code 40",
+        sensor.ff_config_set(FfThs::_5Lsb, 100) == Ok(100) && sensor.ff_dur_get() == Ok(40),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
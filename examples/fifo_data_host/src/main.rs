@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::fifo::FIFO_DEPTH;
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::remap::{AxisRemap, SignedAxis};
+use iis2dlpc_rs::{from_fs2_lp1_to_mg, from_fs4_to_mg, Iis2dlpc};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+    let mut out = [[0; 3]; 40];
+
+    // Empty FIFO
+    let mut sensor = new_sensor(Fmode::StreamMode);
+    check(
+        "empty FIFO: 0 samples, only FIFO_SAMPLES read",
+        sensor.fifo_data_get(&mut out) == Ok(0)
+            && sensor.bus.fifo_samples_reads == 1
+            && sensor.bus.bursts.is_empty(),
+    );
+
+    // Samples stored
+    let mut sensor = new_sensor(Fmode::StreamMode);
+    sensor.bus.push(10);
+    check(
+        "10 samples: all returned, oldest first",
+        sensor.fifo_data_get(&mut out) == Ok(10) && out[..10] == ramp(0..10)[..],
+    );
+    check(
+        "10 samples: a single 60-byte burst",
+        sensor.bus.bursts == [60] && sensor.bus.fifo.is_empty(),
+    );
+
+    // Clamped to the buffer and to the FIFO depth
+    let mut sensor = new_sensor(Fmode::StreamMode);
+    sensor.bus.push(10);
+    check(
+        "4-sample buffer: 4 returned, 6 left",
+        sensor.fifo_data_get(&mut out[..4]) == Ok(4) && sensor.bus.fifo.len() == 6,
+    );
+    check(
+        "then the next 6",
+        sensor.fifo_data_get(&mut out) == Ok(6) && out[..6] == ramp(4..10)[..],
+    );
+    check(
+        "empty buffer: 0, nothing drained",
+        sensor.fifo_data_get(&mut []) == Ok(0),
+    );
+    let mut sensor = new_sensor(Fmode::FifoMode);
+    sensor.bus.push(40);
+    check(
+        "full FIFO, 40-sample buffer: FIFO_DEPTH returned",
+        sensor.fifo_data_get(&mut out) == Ok(FIFO_DEPTH) && sensor.bus.bursts == [192],
+    );
+
+    // Every FIFO mode
+    let mut all = true;
+    for mode in [
+        Fmode::FifoMode,
+        Fmode::StreamToFifoMode,
+        Fmode::BypassToStreamMode,
+        Fmode::StreamMode,
+    ] {
+        let mut sensor = new_sensor(mode);
+        sensor.bus.push(5);
+        all &= sensor.fifo_data_get(&mut out) == Ok(5);
+    }
+    check("FIFO, stream-to-FIFO, bypass-to-stream, stream modes", all);
+    let mut sensor = new_sensor(Fmode::BypassMode);
+    sensor.bus.push(5);
+    check(
+        "bypass mode: nothing stored, 0",
+        sensor.fifo_data_get(&mut out) == Ok(0),
+    );
+
+    // Axis remapping
+    let mut sensor = new_sensor(Fmode::StreamMode);
+    sensor.axis_remap_set(
+        AxisRemap::new([SignedAxis::NegY, SignedAxis::PosX, SignedAxis::PosZ]).unwrap(),
+    );
+    sensor.bus.push(3);
+    let [x, y, z] = ramp(2..3)[0];
+    check(
+        "remapped in the board frame",
+        sensor.fifo_data_get(&mut out) == Ok(3) && out[2] == [-y, x, z],
+    );
+
+    // In mg
+    let mut sensor = new_sensor(Fmode::StreamMode);
+    sensor.full_scale_set(Fs::_4g).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.bus.push(4);
+    let mut mg = [[0.0; 3]; 8];
+    check(
+        "mg, 14-bit, 4 g: scaled as acceleration_mg_get",
+        sensor.fifo_data_mg_get(&mut mg) == Ok(4)
+            && mg[..4]
+                .iter()
+                .zip(ramp(0..4))
+                .all(|(mg, raw)| *mg == raw.map(from_fs4_to_mg)),
+    );
+    let mut sensor = new_sensor(Fmode::StreamMode);
+    sensor.power_mode_set(Mode::ContLowPwr12bit).unwrap();
+    sensor.axis_remap_set(
+        AxisRemap::new([SignedAxis::NegY, SignedAxis::PosX, SignedAxis::PosZ]).unwrap(),
+    );
+    sensor.bus.push(4);
+    let [x, y, z] = ramp(3..4)[0].map(|lsb| from_fs2_lp1_to_mg(lsb >> 2));
+    check(
+        "mg, 12-bit, remapped",
+        sensor.fifo_data_mg_get(&mut mg[..4]) == Ok(4) && mg[3] == [-y, x, z],
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn new_sensor(mode: Fmode) -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(SimBus::default(), NoDelay);
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.fifo_mode_set(mode).unwrap();
+    sensor
+}
+
+/// Samples `range` of the ramp collected by the simulated sensor, 14-bit values.
+fn ramp(range: std::ops::Range<i16>) -> Vec<[i16; 3]> {
+    range.map(|k| [100 * k, -50 * k, 1000 + k]).collect()
+}
+
+/// Register map with a FIFO holding samples of a ramp, drained by the burst reads of `OUT_X_L`.
+#[derive(Default)]
+struct SimBus {
+    regs: MockBus,
+    fifo: VecDeque<[i16; 3]>,
+    /// Number of samples collected since the start.
+    collected: i16,
+    /// Number of reads of `FIFO_SAMPLES`.
+    fifo_samples_reads: u32,
+    /// Length of the burst reads of the output registers.
+    bursts: Vec<usize>,
+}
+
+impl SimBus {
+    /// Collect `n` samples, keeping the latest `FIFO_DEPTH`; nothing is collected in bypass mode.
+    fn push(&mut self, n: usize) {
+        let fmode = FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fmode();
+        if fmode == Fmode::BypassMode as u8 {
+            return;
+        }
+        for _ in 0..n {
+            if self.fifo.len() == FIFO_DEPTH {
+                self.fifo.pop_front();
+            }
+            self.fifo.extend(ramp(self.collected..self.collected + 1));
+            self.collected += 1;
+        }
+    }
+}
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        match wbuf[0] {
+            reg if reg == Reg::FifoSamples as u8 => {
+                self.fifo_samples_reads += 1;
+                // `diff` in bits 5..0
+                rbuf.fill(self.fifo.len() as u8);
+            }
+            reg if reg == Reg::OutXL as u8 => {
+                self.bursts.push(rbuf.len());
+                for sample in rbuf.chunks_exact_mut(6) {
+                    let raw = self.fifo.pop_front().unwrap_or_default();
+                    for (dst, val) in sample.chunks_exact_mut(2).zip(raw) {
+                        dst.copy_from_slice(&(val << 2).to_le_bytes());
+                    }
+                }
+            }
+            _ => return self.regs.write_byte_read_bytes(wbuf, rbuf),
+        }
+        Ok(())
+    }
+}
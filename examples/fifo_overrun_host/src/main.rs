@@ -0,0 +1,244 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::fifo::{FifoFlags, FifoIntegrity, FIFO_DEPTH};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::Iis2dlpc;
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, NoDelay>;
+
+const WATERMARK: u8 = 16;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+    let mut buf = [0; FIFO_DEPTH * 6];
+
+    // Flags
+    let mut sensor = new_sensor();
+    check(
+        "empty FIFO: no flag, in a single read",
+        sensor.fifo_flags_get() == Ok(FifoFlags::default()) && sensor.bus.fifo_samples_reads == 1,
+    );
+    sensor.bus.push(20);
+    check(
+        "20 samples: level and threshold",
+        sensor.fifo_flags_get()
+            == Ok(FifoFlags {
+                level: 20,
+                wtm: true,
+                ..Default::default()
+            }),
+    );
+    sensor.bus.push(12);
+    check(
+        "32 samples: full, no overrun",
+        sensor.fifo_flags_get()
+            == Ok(FifoFlags {
+                level: 32,
+                wtm: true,
+                ovr: false,
+                full: true,
+            })
+            && sensor.fifo_full_flag_get() == Ok(1),
+    );
+    sensor.bus.push(8);
+    check(
+        "40 samples: full and overrun",
+        sensor.fifo_flags_get()
+            == Ok(FifoFlags {
+                level: 32,
+                wtm: true,
+                ovr: true,
+                full: true,
+            })
+            && sensor.fifo_ovr_flag_get() == Ok(1),
+    );
+
+    // Overrun before the drain: the latest 32 samples, still consecutive
+    let report = sensor.fifo_drain(&mut buf, false).unwrap();
+    check(
+        "overrun before the drain: the latest 32 samples, overrun",
+        report.samples == FIFO_DEPTH
+            && report.integrity == FifoIntegrity::Overrun
+            && buf == bytes(8..40)[..],
+    );
+    check(
+        "then empty, no flag",
+        sensor.fifo_flags_get() == Ok(FifoFlags::default()),
+    );
+
+    // Overrun during the drain
+    let mut sensor = new_sensor();
+    sensor.bus.push(32);
+    sensor.bus.during_burst = 4;
+    let report = sensor.fifo_drain(&mut buf, false).unwrap();
+    check(
+        "overrun during the drain: compromised, not retried",
+        report.integrity == FifoIntegrity::Compromised && !report.retried,
+    );
+
+    let mut sensor = new_sensor();
+    sensor.bus.push(32);
+    sensor.bus.during_burst = 4;
+    let writes = sensor.bus.fifo_ctrl_writes.len();
+    let report = sensor.fifo_drain(&mut buf, true).unwrap();
+    check(
+        "with recovery: flushed through bypass mode, stream mode back",
+        sensor.bus.fifo_ctrl_writes[writes..] == [Fmode::BypassMode as u8, Fmode::StreamMode as u8],
+    );
+    check(
+        "with recovery: retried, intact, empty",
+        report.retried && report.integrity == FifoIntegrity::Intact && report.samples == 0,
+    );
+    check(
+        "with recovery: flags clear, watermark kept",
+        sensor.fifo_flags_get() == Ok(FifoFlags::default())
+            && sensor.fifo_watermark_get() == Ok(WATERMARK)
+            && sensor.fifo_mode_get() == Ok(Fmode::StreamMode),
+    );
+
+    // Resumed collection
+    let next = sensor.bus.collected;
+    sensor.bus.push(5);
+    let report = sensor.fifo_drain(&mut buf, true).unwrap();
+    check(
+        "resumed: 5 consecutive samples, intact",
+        report.samples == 5
+            && report.integrity == FifoIntegrity::Intact
+            && !report.retried
+            && buf[..30] == bytes(next..next + 5)[..],
+    );
+
+    // Flush after an overrun
+    let mut sensor = new_sensor();
+    sensor.bus.push(40);
+    sensor.fifo_flush().unwrap();
+    check(
+        "fifo_flush after an overrun: empty, no flag, stream mode",
+        sensor.fifo_flags_get() == Ok(FifoFlags::default())
+            && sensor.fifo_mode_get() == Ok(Fmode::StreamMode),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn new_sensor() -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(SimBus::default(), NoDelay);
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.fifo_watermark_set(WATERMARK).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor
+}
+
+/// Sample `k` of the ramp collected by the simulated sensor, 14-bit values.
+fn sample(k: i16) -> [i16; 3] {
+    [100 * k, -50 * k, 1000 + k]
+}
+
+/// Output register bytes of the samples `range` of the ramp.
+fn bytes(range: std::ops::Range<i16>) -> Vec<u8> {
+    range
+        .flat_map(sample)
+        .flat_map(|val| (val << 2).to_le_bytes())
+        .collect()
+}
+
+/// Register map with a stream mode FIFO holding samples of a ramp, drained by the burst reads of `OUT_X_L`.
+#[derive(Default)]
+struct SimBus {
+    regs: MockBus,
+    fifo: VecDeque<[i16; 3]>,
+    /// Number of samples collected since the start.
+    collected: i16,
+    /// Overrun flag, set when a sample is overwritten and cleared by a read.
+    ovr: bool,
+    /// Number of samples collected while the next burst read runs.
+    during_burst: usize,
+    /// Number of reads of `FIFO_SAMPLES`.
+    fifo_samples_reads: u32,
+    /// `fmode` values written to `FIFO_CTRL`.
+    fifo_ctrl_writes: Vec<u8>,
+}
+
+impl SimBus {
+    /// Collect `n` samples, keeping the latest `FIFO_DEPTH`; nothing is collected in bypass mode.
+    fn push(&mut self, n: usize) {
+        let fmode = FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fmode();
+        if fmode == Fmode::BypassMode as u8 {
+            return;
+        }
+        for _ in 0..n {
+            if self.fifo.len() == FIFO_DEPTH {
+                self.fifo.pop_front();
+                self.ovr = true;
+            }
+            self.fifo.push_back(sample(self.collected));
+            self.collected += 1;
+        }
+    }
+}
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.regs.write_bytes(wbuf)?;
+        if wbuf.first() == Some(&(Reg::FifoCtrl as u8)) {
+            let fmode = FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fmode();
+            self.fifo_ctrl_writes.push(fmode);
+            // Bypass mode empties the FIFO and clears the flags
+            if fmode == Fmode::BypassMode as u8 {
+                self.fifo.clear();
+                self.ovr = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        match wbuf[0] {
+            reg if reg == Reg::FifoSamples as u8 => {
+                self.fifo_samples_reads += 1;
+                let fth = FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fth() as usize;
+                let fth_flag = self.fifo.len() >= fth && !self.fifo.is_empty();
+                // `diff` in bits 5..0, `fifo_ovr` in bit 6, `fifo_fth` in bit 7
+                rbuf.fill(self.fifo.len() as u8 | (self.ovr as u8) << 6 | (fth_flag as u8) << 7);
+            }
+            reg if reg == Reg::OutXL as u8 => {
+                // Reading frees space: the overrun flag clears unless samples are overwritten meanwhile
+                self.ovr = false;
+                let during = std::mem::take(&mut self.during_burst);
+                self.push(during);
+                for sample in rbuf.chunks_exact_mut(6) {
+                    let raw = self.fifo.pop_front().unwrap_or_default();
+                    for (dst, val) in sample.chunks_exact_mut(2).zip(raw) {
+                        dst.copy_from_slice(&(val << 2).to_le_bytes());
+                    }
+                }
+            }
+            _ => return self.regs.write_byte_read_bytes(wbuf, rbuf),
+        }
+        Ok(())
+    }
+}
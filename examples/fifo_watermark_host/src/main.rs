@@ -0,0 +1,311 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use iis2dlpc_rs::fifo::{FifoIntegrity, FifoWatermarkReport, FIFO_DEPTH};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::Iis2dlpc;
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, NoDelay>;
+
+const WATERMARK: u8 = 8;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+    let mut out = [[0; 3]; FIFO_DEPTH];
+
+    // Spurious interrupt: the FIFO is empty
+    let (mut sensor, sim) = new_sensor();
+    let report = sensor.fifo_drain_watermark(&mut out);
+    check(
+        "empty FIFO: no sample, rearmed",
+        report
+            == Ok(FifoWatermarkReport {
+                rearmed: true,
+                ..Default::default()
+            }),
+    );
+    check(
+        "empty FIFO: FIFO_SAMPLES read alone",
+        sim.borrow().fifo_samples_reads == 1 && sim.borrow().burst_reads == 0,
+    );
+
+    // Interrupt latched during the previous drain: fewer samples than the watermark
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(3);
+    let report = sensor.fifo_drain_watermark(&mut out).unwrap();
+    check(
+        "3 samples below the watermark: drained, no error",
+        report.samples == 3 && report.integrity == FifoIntegrity::Intact && report.rearmed,
+    );
+    check(
+        "3 samples below the watermark: oldest first",
+        out[..3] == ramp(0..3)[..],
+    );
+
+    // Interrupt served on time
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(8);
+    let report = sensor.fifo_drain_watermark(&mut out).unwrap();
+    check(
+        "8 samples at the watermark: one burst read, FIFO empty",
+        report.samples == 8
+            && report.start_diff == 8
+            && report.end_diff == 0
+            && sim.borrow().burst_reads == 1,
+    );
+
+    // Samples coming in during the burst reads
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(10);
+    sim.borrow_mut().arrivals.extend([9, 2]);
+    let report = sensor.fifo_drain_watermark(&mut out).unwrap();
+    check(
+        "9 then 2 arrivals: drained again until below the watermark",
+        report.samples == 19 && report.end_diff == 2 && report.rearmed,
+    );
+    check(
+        "9 then 2 arrivals: consecutive samples, 2 burst reads",
+        out[..19] == ramp(0..19)[..] && sim.borrow().burst_reads == 2,
+    );
+
+    // The same race with an exact drain leaves the threshold high
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(10);
+    sim.borrow_mut().arrivals.extend([9]);
+    sensor.fifo_drain_exact(WATERMARK, &mut out).unwrap();
+    check(
+        "exact drain with 9 arrivals: threshold still high",
+        sensor.fifo_wtm_flag_get() == Ok(1),
+    );
+
+    // Buffer full before the level goes below the watermark
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(20);
+    let report = sensor.fifo_drain_watermark(&mut out[..5]).unwrap();
+    check(
+        "5-sample buffer, 20 stored: not rearmed, 15 left",
+        report.samples == 5 && report.end_diff == 15 && !report.rearmed,
+    );
+
+    // Overrun before the drain, in stream mode
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(40);
+    let report = sensor.fifo_drain_watermark(&mut out).unwrap();
+    check(
+        "40 samples collected: overrun, the 32 latest consecutive",
+        report.samples == 32
+            && report.integrity == FifoIntegrity::Overrun
+            && out[..] == ramp(8..40)[..],
+    );
+
+    // Overrun during the drain, then flush
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(30);
+    sim.borrow_mut().arrivals.extend([33]);
+    let report = sensor.fifo_drain_watermark(&mut out).unwrap();
+    check(
+        "overrun during the drain: compromised",
+        report.integrity == FifoIntegrity::Compromised,
+    );
+    sensor.fifo_flush().unwrap();
+    let ctrl = FifoCtrl::from_bits(sim.borrow().regs.reg(Reg::FifoCtrl));
+    check(
+        "flush: FIFO empty, stream mode and watermark kept",
+        sim.borrow().fifo.is_empty()
+            && ctrl.fmode() == Fmode::StreamMode as u8
+            && ctrl.fth() == WATERMARK,
+    );
+    let report = sensor.fifo_drain_watermark(&mut out).unwrap();
+    check(
+        "after the flush: intact, rearmed",
+        report.samples == 0 && report.integrity == FifoIntegrity::Intact && report.rearmed,
+    );
+
+    // The recovering drain goes through the same flush
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().push(30);
+    sim.borrow_mut().arrivals.extend([33]);
+    let mut buf = [0; FIFO_DEPTH * 6];
+    let report = sensor.fifo_drain(&mut buf, true).unwrap();
+    check(
+        "fifo_drain with recovery: flushed and retried",
+        report.retried && report.samples == 0 && report.integrity == FifoIntegrity::Intact,
+    );
+
+    // Edge-triggered interrupt loop, served 4 samples late, half a sample coming in per sample read
+    let total = run_edge_loop(|sensor, out| {
+        sensor
+            .fifo_drain_watermark(out)
+            .map_or(0, |report| report.samples)
+    });
+    check(
+        "edge-triggered loop: the watermark drain keeps the interrupts coming",
+        total >= 180,
+    );
+    let total = run_edge_loop(|sensor, out| {
+        sensor
+            .fifo_drain_exact(WATERMARK, out)
+            .map_or(0, |_| WATERMARK as usize)
+    });
+    check(
+        "edge-triggered loop: the exact drain stalls after the first interrupt",
+        total == WATERMARK as usize,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A sensor with the FIFO in stream mode at the watermark, and the simulation it runs on.
+fn new_sensor() -> (Sensor, Rc<RefCell<Sim>>) {
+    let sim = Rc::new(RefCell::new(Sim::default()));
+    let mut sensor = Iis2dlpc::from_bus(SimBus(sim.clone()), NoDelay);
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.fifo_watermark_set(WATERMARK).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sim.borrow_mut().fifo_samples_reads = 0;
+    (sensor, sim)
+}
+
+/// Run 200 data periods of an interrupt loop woken by the rising edges of the threshold signal.
+///
+/// ### Returns
+/// - `usize`: The number of samples drained.
+fn run_edge_loop(mut drain: impl FnMut(&mut Sensor, &mut [[i16; 3]]) -> usize) -> usize {
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().arrive_half = true;
+    let mut out = [[0; 3]; FIFO_DEPTH];
+    let mut level = false;
+    let mut total = 0;
+    for _ in 0..200 {
+        sim.borrow_mut().push(1);
+        let high = sim.borrow().threshold_reached();
+        if high && !level {
+            // Interrupt latency
+            sim.borrow_mut().push(4);
+            total += drain(&mut sensor, &mut out);
+        }
+        level = sim.borrow().threshold_reached();
+    }
+    total
+}
+
+/// Samples `range` of the ramp collected by the simulated sensor.
+fn ramp(range: std::ops::Range<i16>) -> Vec<[i16; 3]> {
+    range.map(|k| [k, -k, 2 * k]).collect()
+}
+
+/// Simulated sensor: the registers, and a FIFO in which samples of a ramp are pushed explicitly or
+/// come in during the burst reads.
+#[derive(Default)]
+struct Sim {
+    regs: MockBus,
+    fifo: VecDeque<[i16; 3]>,
+    /// Number of samples collected since the start.
+    collected: i16,
+    /// `fifo_ovr` flag.
+    overrun: bool,
+    /// Samples coming in during each of the next burst reads.
+    arrivals: VecDeque<usize>,
+    /// Half a sample comes in per sample read, once `arrivals` is empty.
+    arrive_half: bool,
+    /// Number of reads of `FIFO_SAMPLES`.
+    fifo_samples_reads: u32,
+    /// Number of burst reads of the output registers.
+    burst_reads: u32,
+}
+
+impl Sim {
+    /// Collect `n` samples, overwriting the oldest ones in stream mode.
+    fn push(&mut self, n: usize) {
+        let fmode = FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fmode();
+        if fmode == Fmode::BypassMode as u8 {
+            return;
+        }
+        for _ in 0..n {
+            if self.fifo.len() == FIFO_DEPTH {
+                self.fifo.pop_front();
+                self.overrun = true;
+            }
+            let k = self.collected;
+            self.fifo.push_back([k, -k, 2 * k]);
+            self.collected += 1;
+        }
+    }
+
+    fn threshold_reached(&self) -> bool {
+        self.fifo.len() >= FifoCtrl::from_bits(self.regs.reg(Reg::FifoCtrl)).fth() as usize
+    }
+}
+
+struct SimBus(Rc<RefCell<Sim>>);
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.borrow_mut().regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        sim.regs.write_bytes(wbuf)?;
+        let fmode = FifoCtrl::from_bits(sim.regs.reg(Reg::FifoCtrl)).fmode();
+        if wbuf[0] == Reg::FifoCtrl as u8 && fmode == Fmode::BypassMode as u8 {
+            sim.fifo.clear();
+            sim.overrun = false;
+        }
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        match wbuf[0] {
+            reg if reg == Reg::FifoSamples as u8 => {
+                sim.fifo_samples_reads += 1;
+                // `fifo_fth` in bit 7, `fifo_ovr` in bit 6, `diff` in bits 5..0
+                let samples = (sim.threshold_reached() as u8) << 7
+                    | (sim.overrun as u8) << 6
+                    | sim.fifo.len() as u8;
+                rbuf.fill(samples);
+            }
+            reg if reg == Reg::OutXL as u8 => {
+                sim.burst_reads += 1;
+                let read = rbuf.len() / 6;
+                for sample in rbuf.chunks_exact_mut(6) {
+                    let raw = sim.fifo.pop_front().unwrap();
+                    for (dst, val) in sample.chunks_exact_mut(2).zip(raw) {
+                        dst.copy_from_slice(&(val << 2).to_le_bytes());
+                    }
+                }
+                sim.overrun = false;
+                let arriving = match sim.arrivals.pop_front() {
+                    Some(n) => n,
+                    None if sim.arrive_half => read / 2,
+                    None => 0,
+                };
+                sim.push(arriving);
+            }
+            _ => return sim.regs.write_byte_read_bytes(wbuf, rbuf),
+        }
+        Ok(())
+    }
+}
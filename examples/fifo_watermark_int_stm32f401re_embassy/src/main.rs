@@ -0,0 +1,194 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::Pull;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::mode::Async;
+use embassy_stm32::time::khz;
+use embassy_stm32::usart::{self, DataBits, Parity, UartTx};
+use embassy_stm32::{bind_interrupts, peripherals};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Delay;
+use embedded_hal::delay::DelayNs;
+use heapless::String;
+use heapless::spsc::{Consumer, Producer, Queue};
+use iis2dlpc_rs::fifo::{FIFO_DEPTH, FifoIntegrity};
+use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
+use iis2dlpc_rs::{PROPERTY_ENABLE, from_fs2_to_mg, prelude::*};
+use static_cell::StaticCell;
+
+use {defmt_rtt as _, panic_probe as _};
+
+#[defmt::panic_handler]
+fn panic() -> ! {
+    core::panic!("panic via `defmt::panic!")
+}
+
+bind_interrupts!(struct Irqs {
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+/// FIFO threshold: one interrupt every 16 samples, 320 ms at 50 Hz.
+const WATERMARK: u8 = 16;
+
+/// Queue between the two tasks; a `heapless` queue of `N` slots holds `N - 1` samples.
+const QUEUE_LEN: usize = 129;
+
+type SampleQueue = Queue<[i16; 3], QUEUE_LEN>;
+
+static QUEUE: StaticCell<SampleQueue> = StaticCell::new();
+/// Raised by the drain task when samples were queued.
+static QUEUED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+/// Drains that lost samples to a FIFO overrun before the burst read.
+static OVERRUNS: AtomicU32 = AtomicU32::new(0);
+/// Drains discarded because of an overrun during the burst read.
+static FLUSHES: AtomicU32 = AtomicU32::new(0);
+/// Samples dropped because the queue was full.
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_stm32::init(Default::default());
+
+    let mut usart_cfg = usart::Config::default();
+    usart_cfg.baudrate = 115200;
+    usart_cfg.data_bits = DataBits::DataBits8;
+    usart_cfg.parity = Parity::ParityNone;
+
+    let tx = UartTx::new(p.USART2, p.PA2, p.DMA1_CH6, usart_cfg).unwrap();
+
+    let i2c = I2c::new(
+        p.I2C1,
+        p.PB8,
+        p.PB9,
+        Irqs,
+        p.DMA1_CH7,
+        p.DMA1_CH5,
+        khz(100),
+        Default::default(),
+    );
+
+    // INT1 of the sensor on D2
+    let int1 = ExtiInput::new(p.PA10, p.EXTI10, Pull::Down);
+
+    let (producer, consumer) = QUEUE.init(Queue::new()).split();
+
+    spawner.spawn(drain_task(i2c, int1, producer)).unwrap();
+    spawner.spawn(consumer_task(consumer, tx)).unwrap();
+}
+
+/// Configure the sensor, then drain the FIFO on every watermark interrupt into the queue.
+#[embassy_executor::task]
+async fn drain_task(
+    i2c: I2c<'static, Async>,
+    mut int1: ExtiInput<'static>,
+    mut producer: Producer<'static, [i16; 3], QUEUE_LEN>,
+) {
+    let mut delay = Delay;
+    delay.delay_ms(10);
+
+    let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay);
+
+    if sensor.device_id_get().unwrap() != iis2dlpc_rs::ID {
+        panic!("Invalid sensor ID")
+    }
+
+    // Restore default configuration; this also empties a FIFO left full by a previous run
+    sensor.reset_set().unwrap();
+    while sensor.reset_get().unwrap() == 1 {}
+
+    // Enable Block Data Update
+    sensor.block_data_update_set(true).unwrap();
+    // Set Full scale
+    sensor.full_scale_set(Fs::_2g).unwrap();
+    // Configure power mode
+    sensor.power_mode_set(Mode::ContLowPwrLowNoise2).unwrap();
+    // Route the FIFO threshold to the INT1 pin
+    let mut int_route = sensor.pin_int1_route_get().unwrap();
+    int_route.set_int1_fth(PROPERTY_ENABLE);
+    sensor.pin_int1_route_set(&int_route).unwrap();
+    // Stream mode: the FIFO keeps the latest samples, overwriting the oldest ones when full
+    sensor.fifo_watermark_set(WATERMARK).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    // Set Output Data Rate last, once the FIFO and the pin are ready
+    sensor.data_rate_set(Odr::_50hz).unwrap();
+
+    let mut buf = [[0; 3]; FIFO_DEPTH];
+    loop {
+        // Drain before the first wait as well: an edge raised during the configuration is gone,
+        // and the level only goes low again once the FIFO is below the watermark
+        let report = sensor.fifo_drain_watermark(&mut buf).unwrap();
+        match report.integrity {
+            // The ordering of the samples is lost: restart from an empty FIFO
+            FifoIntegrity::Compromised => {
+                sensor.fifo_flush().unwrap();
+                FLUSHES.fetch_add(1, Ordering::Relaxed);
+            }
+            integrity => {
+                if integrity == FifoIntegrity::Overrun {
+                    OVERRUNS.fetch_add(1, Ordering::Relaxed);
+                }
+                for sample in buf.iter().take(report.samples) {
+                    if producer.enqueue(*sample).is_err() {
+                        DROPPED.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                if report.samples > 0 {
+                    QUEUED.signal(());
+                }
+            }
+        }
+
+        // Not rearmed: the buffer filled first and the level is still high, drain again at once.
+        // A spurious interrupt only costs a short or empty drain.
+        if report.rearmed {
+            int1.wait_for_high().await;
+        }
+    }
+}
+
+/// Report the mean of the queued samples and the drain counters over UART.
+#[embassy_executor::task]
+async fn consumer_task(
+    mut consumer: Consumer<'static, [i16; 3], QUEUE_LEN>,
+    mut tx: UartTx<'static, Async>,
+) {
+    let mut msg = String::<160>::new();
+    loop {
+        QUEUED.wait().await;
+
+        let mut sums = [0i32; 3];
+        let mut count = 0;
+        while let Some(sample) = consumer.dequeue() {
+            for (sum, val) in sums.iter_mut().zip(sample) {
+                *sum += val as i32;
+            }
+            count += 1;
+        }
+        if count == 0 {
+            continue;
+        }
+
+        let mean_mg = sums.map(|sum| from_fs2_to_mg((sum / count) as i16));
+        msg.clear();
+        writeln!(
+            &mut msg,
+            "{count} samples, mean [mg]: {:4.2}\t{:4.2}\t{:4.2}\toverruns {} flushes {} dropped {}",
+            mean_mg[0],
+            mean_mg[1],
+            mean_mg[2],
+            OVERRUNS.load(Ordering::Relaxed),
+            FLUSHES.load(Ordering::Relaxed),
+            DROPPED.load(Ordering::Relaxed),
+        )
+        .unwrap();
+        tx.write(msg.as_bytes()).await.unwrap();
+    }
+}
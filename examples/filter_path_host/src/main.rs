@@ -0,0 +1,69 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{DecodeRetry, Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // The four combinations of `fds` and `usr_off_on_out`, both retry policies
+    let expected = [
+        ((0, 0), Ok(Fds::LpfOnOut)),
+        ((0, 1), Ok(Fds::UserOffsetOnOut)),
+        ((1, 0), Ok(Fds::HighPassOnOut)),
+        ((1, 1), Err(Error::UnexpectedValue(0x11))),
+    ];
+    for &((fds, usr_off_on_out), ref path) in &expected {
+        for retry in [DecodeRetry::Once, DecodeRetry::Never] {
+            let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+            sensor.decode_retry_set(retry);
+            set_bits(&mut sensor, fds, usr_off_on_out);
+            check(
+                &format!("fds {fds}, usr_off_on_out {usr_off_on_out}, {retry:?}: {path:?}"),
+                sensor.filter_path_get() == *path,
+            );
+        }
+    }
+
+    // Every path set from every combination: the other bit cleared
+    for &((fds, usr_off_on_out), _) in &expected {
+        let ok = [Fds::LpfOnOut, Fds::UserOffsetOnOut, Fds::HighPassOnOut]
+            .iter()
+            .all(|&path| {
+                let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+                set_bits(&mut sensor, fds, usr_off_on_out);
+                sensor.filter_path_set(path).unwrap();
+                sensor.filter_path_get() == Ok(path)
+                    && Ctrl6::from_bits(sensor.bus.reg(Reg::Ctrl6)).fds() == path.fds()
+                    && Ctrl7::from_bits(sensor.bus.reg(Reg::Ctrl7)).usr_off_on_out()
+                        == path.usr_off_on_out()
+            });
+        check(
+            &format!("filter_path_set from fds {fds}, usr_off_on_out {usr_off_on_out}: both bits written"),
+            ok,
+        );
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Preset the `fds` field of `CTRL6` and the `usr_off_on_out` field of `CTRL7`.
+fn set_bits(sensor: &mut Sensor, fds: u8, usr_off_on_out: u8) {
+    let ctrl6 = Ctrl6::from_bits(sensor.bus.reg(Reg::Ctrl6)).with_fds(fds);
+    let ctrl7 = Ctrl7::from_bits(sensor.bus.reg(Reg::Ctrl7)).with_usr_off_on_out(usr_off_on_out);
+    sensor.bus.set_reg(Reg::Ctrl6, ctrl6.into_bits());
+    sensor.bus.set_reg(Reg::Ctrl7, ctrl7.into_bits());
+}
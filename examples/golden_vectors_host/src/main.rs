@@ -0,0 +1,110 @@
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use embedded_hal::delay::DelayNs;
+use iis2dlpc_rs::prelude::{Fs, Reg};
+use iis2dlpc_rs::*;
+use st_mems_bus::BusOperation;
+
+mod vectors;
+
+/// Accepted relative difference from the C driver: both sides compute in `f32` with
+/// constants scaled by a power of two, so only rounding differs.
+const TOLERANCE: f32 = 1e-6;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+
+    for (raw, fs, low_power_1, expected) in vectors::ACCELERATION {
+        let mg = if low_power_1 {
+            // 12 significant bits
+            match fs {
+                Fs::_2g => from_fs2_lp1_to_mg(raw >> 4),
+                Fs::_4g => from_fs4_lp1_to_mg(raw >> 4),
+                Fs::_8g => from_fs8_lp1_to_mg(raw >> 4),
+                Fs::_16g => from_fs16_lp1_to_mg(raw >> 4),
+            }
+        } else {
+            // 14 significant bits
+            match fs {
+                Fs::_2g => from_fs2_to_mg(raw >> 2),
+                Fs::_4g => from_fs4_to_mg(raw >> 2),
+                Fs::_8g => from_fs8_to_mg(raw >> 2),
+                Fs::_16g => from_fs16_to_mg(raw >> 2),
+            }
+        };
+        if !matches(mg, expected) {
+            println!(
+                "acceleration {raw} {fs:?} lp1={low_power_1}: {mg} mg, expected {expected} mg"
+            );
+            failures += 1;
+        }
+    }
+
+    for (raw, expected) in vectors::TEMPERATURE {
+        // 12 significant bits
+        let celsius = from_lsb_to_celsius(raw >> 4);
+        if !matches(celsius, expected) {
+            println!("temperature {raw}: {celsius} °C, expected {expected} °C");
+            failures += 1;
+        }
+    }
+
+    // Whole driver path: register bytes, temperature_raw_get, from_lsb_to_celsius
+    let mut sensor = Iis2dlpc::from_bus(OutTBus::default(), NoDelay);
+    for (raw, expected) in vectors::TEMPERATURE {
+        sensor.bus.out_t = raw.to_le_bytes();
+        let lsb = sensor.temperature_raw_get().unwrap();
+        let celsius = sensor.temperature_celsius_get().unwrap();
+        if lsb != raw >> 4 || !matches(celsius, expected) {
+            println!("driver temperature {raw}: {lsb} LSB, {celsius} °C, expected {expected} °C");
+            failures += 1;
+        }
+    }
+
+    let total = vectors::ACCELERATION.len() + 2 * vectors::TEMPERATURE.len();
+    println!("{} of {total} vectors match", total - failures);
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Bus answering the `OUT_T_L`, `OUT_T_H` burst read, low byte first as the device does.
+#[derive(Default)]
+struct OutTBus {
+    out_t: [u8; 2],
+}
+
+impl BusOperation for OutTBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, _rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, _wbuf: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        assert_eq!(wbuf[0], Reg::OutTL as u8);
+        rbuf.copy_from_slice(&self.out_t);
+        Ok(())
+    }
+}
+
+struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+fn matches(value: f32, expected: f32) -> bool {
+    (value - expected).abs() <= TOLERANCE * expected.abs().max(1.0)
+}
@@ -0,0 +1,92 @@
+// Generated by tools/golden/gen_vectors.c from the reference C driver, do not edit.
+
+use iis2dlpc_rs::prelude::Fs;
+
+/// (left-aligned raw word, full scale, low-power mode 1, mg)
+pub const ACCELERATION: [(i16, Fs, bool, f32); 72] = [
+    (0, Fs::_2g, false, 0.0),
+    (0, Fs::_2g, true, 0.0),
+    (16, Fs::_2g, false, 0.976),
+    (16, Fs::_2g, true, 0.976),
+    (-16, Fs::_2g, false, -0.976),
+    (-16, Fs::_2g, true, -0.976),
+    (4656, Fs::_2g, false, 284.016),
+    (4656, Fs::_2g, true, 284.016),
+    (-4672, Fs::_2g, false, -284.992),
+    (-4672, Fs::_2g, true, -284.992),
+    (16384, Fs::_2g, false, 999.424),
+    (16384, Fs::_2g, true, 999.424),
+    (-16384, Fs::_2g, false, -999.424),
+    (-16384, Fs::_2g, true, -999.424),
+    (32752, Fs::_2g, false, 1997.8721),
+    (32752, Fs::_2g, true, 1997.8721),
+    (-32768, Fs::_2g, false, -1998.848),
+    (-32768, Fs::_2g, true, -1998.848),
+    (0, Fs::_4g, false, 0.0),
+    (0, Fs::_4g, true, 0.0),
+    (16, Fs::_4g, false, 1.952),
+    (16, Fs::_4g, true, 1.952),
+    (-16, Fs::_4g, false, -1.952),
+    (-16, Fs::_4g, true, -1.952),
+    (4656, Fs::_4g, false, 568.032),
+    (4656, Fs::_4g, true, 568.032),
+    (-4672, Fs::_4g, false, -569.984),
+    (-4672, Fs::_4g, true, -569.984),
+    (16384, Fs::_4g, false, 1998.848),
+    (16384, Fs::_4g, true, 1998.848),
+    (-16384, Fs::_4g, false, -1998.848),
+    (-16384, Fs::_4g, true, -1998.848),
+    (32752, Fs::_4g, false, 3995.7441),
+    (32752, Fs::_4g, true, 3995.7441),
+    (-32768, Fs::_4g, false, -3997.696),
+    (-32768, Fs::_4g, true, -3997.696),
+    (0, Fs::_8g, false, 0.0),
+    (0, Fs::_8g, true, 0.0),
+    (16, Fs::_8g, false, 3.904),
+    (16, Fs::_8g, true, 3.904),
+    (-16, Fs::_8g, false, -3.904),
+    (-16, Fs::_8g, true, -3.904),
+    (4656, Fs::_8g, false, 1136.064),
+    (4656, Fs::_8g, true, 1136.064),
+    (-4672, Fs::_8g, false, -1139.968),
+    (-4672, Fs::_8g, true, -1139.968),
+    (16384, Fs::_8g, false, 3997.696),
+    (16384, Fs::_8g, true, 3997.696),
+    (-16384, Fs::_8g, false, -3997.696),
+    (-16384, Fs::_8g, true, -3997.696),
+    (32752, Fs::_8g, false, 7991.4883),
+    (32752, Fs::_8g, true, 7991.4883),
+    (-32768, Fs::_8g, false, -7995.392),
+    (-32768, Fs::_8g, true, -7995.392),
+    (0, Fs::_16g, false, 0.0),
+    (0, Fs::_16g, true, 0.0),
+    (16, Fs::_16g, false, 7.808),
+    (16, Fs::_16g, true, 7.808),
+    (-16, Fs::_16g, false, -7.808),
+    (-16, Fs::_16g, true, -7.808),
+    (4656, Fs::_16g, false, 2272.128),
+    (4656, Fs::_16g, true, 2272.128),
+    (-4672, Fs::_16g, false, -2279.936),
+    (-4672, Fs::_16g, true, -2279.936),
+    (16384, Fs::_16g, false, 7995.392),
+    (16384, Fs::_16g, true, 7995.392),
+    (-16384, Fs::_16g, false, -7995.392),
+    (-16384, Fs::_16g, true, -7995.392),
+    (32752, Fs::_16g, false, 15982.977),
+    (32752, Fs::_16g, true, 15982.977),
+    (-32768, Fs::_16g, false, -15990.784),
+    (-32768, Fs::_16g, true, -15990.784),
+];
+
+/// (left-aligned raw word, degrees Celsius)
+pub const TEMPERATURE: [(i16, f32); 9] = [
+    (0, 25.0),
+    (16, 25.0625),
+    (-16, 24.9375),
+    (256, 26.0),
+    (-256, 24.0),
+    (4096, 41.0),
+    (-4096, 9.0),
+    (32752, 152.9375),
+    (-32768, -103.0),
+];
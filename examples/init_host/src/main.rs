@@ -0,0 +1,235 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use iis2dlpc_rs::config::Config;
+use iis2dlpc_rs::mock::MockBus;
+use iis2dlpc_rs::phase::is_phase_ordered;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc, RESET_MAX_POLLS, RESET_POLL_US};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, SimDelay>;
+
+const CONFIG: Config = Config {
+    odr: Odr::_200hz,
+    fs: Fs::_4g,
+    mode: Mode::ContLowPwrLowNoise12bit,
+    bdu: true,
+    filter_path: Fds::UserOffsetOnOut,
+    bandwidth: BwFilt::OdrDiv4,
+};
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Start-up from a configured device
+    let (mut sensor, sim) = new_sensor();
+    sensor.apply_config(&CONFIG).unwrap();
+    sensor.fifo_watermark_set(10).unwrap();
+    sim.borrow_mut().reset_polls = 3;
+    check("init: Ok", sensor.init() == Ok(()));
+    let ctrl2 = Ctrl2::from_bits(sim.borrow().regs.reg(Reg::Ctrl2));
+    check(
+        "init: reset done, BDU and auto-increment set",
+        ctrl2.soft_reset() == 0 && ctrl2.bdu() == 1 && ctrl2.if_add_inc() == 1,
+    );
+    check(
+        "init: reset values, data rate at power-down",
+        sim.borrow().regs.reg(Reg::FifoCtrl) == 0 && sim.borrow().regs.reg(Reg::Ctrl1) == 0,
+    );
+    check(
+        "init: 3 polls still in reset, 3 waits of RESET_POLL_US",
+        sim.borrow().waits == [RESET_POLL_US * 1000; 3],
+    );
+    check(
+        "init: no brown-out reported for the reset",
+        sensor.brownout_check() == Ok(false),
+    );
+
+    // Bounded reset wait
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().reset_polls = u32::MAX;
+    check(
+        "reset never over: Timeout after RESET_MAX_POLLS polls",
+        sensor.init() == Err(Error::Timeout)
+            && sim.borrow().waits.len() == RESET_MAX_POLLS as usize - 1,
+    );
+
+    // Another device, a stuck bus
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().regs.set_reg(Reg::WhoAmI, 0x33);
+    check(
+        "another identifier: WhoAmIError, nothing written",
+        sensor.init() == Err(Error::WhoAmIError(0x33)) && sim.borrow().writes.is_empty(),
+    );
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().stuck = Some(0xFF);
+    check(
+        "bus stuck high: UnexpectedValue(0xFF), nothing written",
+        sensor.init() == Err(Error::UnexpectedValue(0xFF)) && sim.borrow().writes.is_empty(),
+    );
+
+    // The configuration, against the individual setters
+    let (mut sensor, sim) = new_sensor();
+    sensor.init().unwrap();
+    sim.borrow_mut().writes.clear();
+    sensor.apply_config(&CONFIG).unwrap();
+    let applied = sensor.config_snapshot_get().unwrap();
+    let writes = sim.borrow().writes.clone();
+    let (mut reference, _) = new_sensor();
+    reference.init().unwrap();
+    reference.full_scale_set(CONFIG.fs).unwrap();
+    reference.filter_path_set(CONFIG.filter_path).unwrap();
+    reference.filter_bandwidth_set(CONFIG.bandwidth).unwrap();
+    reference.power_mode_set(CONFIG.mode).unwrap();
+    reference.data_rate_set(CONFIG.odr).unwrap();
+    check(
+        "apply_config: same registers as the setters",
+        reference.config_snapshot_get() == Ok(applied),
+    );
+    check(
+        "apply_config: phase order, CTRL6 and CTRL7 then CTRL1 last",
+        is_phase_ordered(writes.iter().copied()) && writes == [Reg::Ctrl6, Reg::Ctrl7, Reg::Ctrl1],
+    );
+    check(
+        "apply_config: the getters read the configuration back",
+        sensor.data_rate_get() == Ok(CONFIG.odr)
+            && sensor.full_scale_get() == Ok(CONFIG.fs)
+            && sensor.power_mode_get() == Ok(CONFIG.mode)
+            && sensor.filter_path_get() == Ok(CONFIG.filter_path)
+            && sensor.filter_bandwidth_get() == Ok(CONFIG.bandwidth)
+            && sensor.block_data_update_get() == Ok(true),
+    );
+    sim.borrow_mut().writes.clear();
+    sensor.apply_config(&CONFIG).unwrap();
+    check(
+        "same configuration again: nothing written",
+        sim.borrow().writes.is_empty(),
+    );
+    sensor
+        .apply_config(&Config {
+            bdu: false,
+            ..CONFIG
+        })
+        .unwrap();
+    check(
+        "BDU off: CTRL2 alone",
+        sim.borrow().writes == [Reg::Ctrl2] && sensor.block_data_update_get() == Ok(false),
+    );
+
+    // Inconsistent configuration
+    let (mut sensor, sim) = new_sensor();
+    let config = Config {
+        mode: Mode::SingleLowPwr12bit,
+        ..CONFIG
+    };
+    check(
+        "single conversion mode at 200 Hz: rejected, nothing accessed",
+        matches!(
+            sensor.apply_config(&config),
+            Err(Error::InvalidConfig(
+                ConfigError::SingleModeWithContinuousOdr { .. }
+            ))
+        ) && sim.borrow().writes.is_empty()
+            && sim.borrow().reads == 0,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn new_sensor() -> (Sensor, Rc<RefCell<Sim>>) {
+    let sim = Rc::new(RefCell::new(Sim::default()));
+    let sensor = Iis2dlpc::from_bus(SimBus(sim.clone()), SimDelay(sim.clone()));
+    (sensor, sim)
+}
+
+#[derive(Default)]
+struct Sim {
+    regs: MockBus,
+    /// Reads of `CTRL2` with `soft_reset` still set after a reset.
+    reset_polls: u32,
+    /// Value every read returns, writes being lost.
+    stuck: Option<u8>,
+    /// Registers written, in order.
+    writes: Vec<Reg>,
+    /// Number of read transactions.
+    reads: u32,
+    /// Waits of the driver, in nanoseconds.
+    waits: Vec<u32>,
+}
+
+struct SimBus(Rc<RefCell<Sim>>);
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.borrow_mut().regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        if sim.stuck.is_some() {
+            return Ok(());
+        }
+        if let Ok(reg) = Reg::try_from(wbuf[0]) {
+            sim.writes.push(reg);
+        }
+        sim.regs.write_bytes(wbuf)?;
+        // The mock bus resets at once: keep `soft_reset` set for the next polls
+        let ctrl2 = Ctrl2::from_bits(sim.regs.reg(Reg::Ctrl2));
+        if wbuf[0] == Reg::Ctrl2 as u8 && Ctrl2::from_bits(wbuf[1]).soft_reset() == 1 {
+            let polls = sim.reset_polls;
+            if polls > 0 {
+                sim.regs
+                    .set_reg(Reg::Ctrl2, ctrl2.with_soft_reset(1).into_bits());
+            }
+        }
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        sim.reads += 1;
+        if let Some(val) = sim.stuck {
+            rbuf.fill(val);
+            return Ok(());
+        }
+        sim.regs.write_byte_read_bytes(wbuf, rbuf)?;
+        if wbuf[0] == Reg::Ctrl2 as u8 && Ctrl2::from_bits(rbuf[0]).soft_reset() == 1 {
+            sim.reset_polls = sim.reset_polls.saturating_sub(1);
+            if sim.reset_polls == 0 {
+                let ctrl2 = Ctrl2::from_bits(sim.regs.reg(Reg::Ctrl2));
+                sim.regs
+                    .set_reg(Reg::Ctrl2, ctrl2.with_soft_reset(0).into_bits());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Delay of the driver, recording the waits.
+struct SimDelay(Rc<RefCell<Sim>>);
+
+impl DelayNs for SimDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.borrow_mut().waits.push(ns);
+    }
+}
@@ -0,0 +1,154 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::interrupt::InterruptConfig;
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::sample::TempPolicy;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc, PROPERTY_ENABLE};
+use st_mems_bus::BusOperation;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let mut sensor = Iis2dlpc::from_bus(LogBus::default(), NoDelay);
+    check(
+        "interrupt_config_get: the default at reset",
+        sensor.interrupt_config_get() == Ok(InterruptConfig::default()),
+    );
+
+    // Every setting at once, next to fields of CTRL3 and CTRL7 set beforehand
+    sensor.reference_mode_set(true).unwrap();
+    sensor.self_test_set(St::Positive).unwrap();
+    sensor.bus.writes.clear();
+    let cfg = InterruptConfig {
+        int1_routes: Ctrl4Int1PadCtrl::new()
+            .with_int1_wu(PROPERTY_ENABLE)
+            .with_int1_single_tap(PROPERTY_ENABLE),
+        int2_routes: Ctrl5Int2PadCtrl::new().with_int2_drdy(PROPERTY_ENABLE),
+        int2_on_int1: true,
+        latch: Lir::Latched,
+        polarity: HLactive::ActiveLow,
+        pin_mode: PpOd::OpenDrain,
+    };
+    sensor.interrupt_config_set(&cfg).unwrap();
+    let writes = &sensor.bus.writes;
+    check(
+        "interrupt_config_set: CTRL3, CTRL7, CTRL4, CTRL5 then CTRL7",
+        writes.iter().map(|(reg, _)| *reg).collect::<Vec<_>>()
+            == [
+                Reg::Ctrl3,
+                Reg::Ctrl7,
+                Reg::Ctrl4Int1PadCtrl,
+                Reg::Ctrl5Int2PadCtrl,
+                Reg::Ctrl7,
+            ]
+            .map(|reg| reg as u8),
+    );
+    check(
+        "interrupt_config_set: interrupts disabled until the routing is written",
+        Ctrl7::from_bits(writes[1].1).interrupts_enable() == 0
+            && Ctrl7::from_bits(writes[4].1).interrupts_enable() == 1,
+    );
+    check(
+        "interrupt_config_get: the configuration set",
+        sensor.interrupt_config_get() == Ok(cfg),
+    );
+    check(
+        "interrupt_config_set: other fields of CTRL3 and CTRL7 kept",
+        sensor.reference_mode_get() == Ok(true) && sensor.self_test_get() == Ok(St::Positive),
+    );
+    check(
+        "interrupt_config_set: route copies valid",
+        sensor.int1_routes_shadow().shadow_valid
+            && sensor.int1_routes_shadow().routes == cfg.int1_routes
+            && sensor.int2_routes_shadow().shadow_valid
+            && sensor.int2_routes_shadow().routes == cfg.int2_routes,
+    );
+
+    // Interrupts enable, from both routings
+    let sleep_state = InterruptConfig {
+        int2_routes: Ctrl5Int2PadCtrl::new().with_int2_sleep_state(PROPERTY_ENABLE),
+        ..Default::default()
+    };
+    sensor.interrupt_config_set(&sleep_state).unwrap();
+    check(
+        "sleep state on INT2, nothing on INT1: interrupts enabled",
+        sleep_state.interrupts_enable() && interrupts_enable(&mut sensor) == 1,
+    );
+    let data_ready = InterruptConfig {
+        int1_routes: Ctrl4Int1PadCtrl::new().with_int1_drdy(PROPERTY_ENABLE),
+        ..Default::default()
+    };
+    sensor.interrupt_config_set(&data_ready).unwrap();
+    check(
+        "data-ready alone: interrupts disabled",
+        !data_ready.interrupts_enable() && interrupts_enable(&mut sensor) == 0,
+    );
+    sensor
+        .interrupt_config_set(&InterruptConfig::default())
+        .unwrap();
+    check(
+        "default configuration: back to the reset values",
+        sensor.interrupt_config_get() == Ok(InterruptConfig::default())
+            && interrupts_enable(&mut sensor) == 0,
+    );
+
+    // Temperature data-ready while the temperature is ignored
+    sensor.temp_policy_set(TempPolicy::Ignore);
+    sensor.bus.writes.clear();
+    let temperature = InterruptConfig {
+        int2_routes: Ctrl5Int2PadCtrl::new().with_int2_drdy_t(PROPERTY_ENABLE),
+        ..Default::default()
+    };
+    check(
+        "int2_drdy_t with TempPolicy::Ignore: rejected, nothing written",
+        sensor.interrupt_config_set(&temperature)
+            == Err(Error::InvalidConfig(ConfigError::TemperatureIgnored))
+            && sensor.bus.writes.is_empty(),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// The `interrupts_enable` field of the `CTRL7` register.
+fn interrupts_enable(sensor: &mut Iis2dlpc<LogBus, NoDelay>) -> u8 {
+    Ctrl7::read(sensor).unwrap().interrupts_enable()
+}
+
+/// Mock bus logging the register writes.
+#[derive(Default)]
+struct LogBus {
+    regs: MockBus,
+    writes: Vec<(u8, u8)>,
+}
+
+impl BusOperation for LogBus {
+    type Error = <MockBus as BusOperation>::Error;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push((wbuf[0], wbuf[1]));
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.regs.write_byte_read_bytes(wbuf, rbuf)
+    }
+}
@@ -0,0 +1,204 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{Access, ExpectationError, MockBus, NoDelay, MAX_EXPECTATIONS};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Iis2dlpc, ID};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+const MODES: [Mode; 18] = [
+    Mode::HighPerformance,
+    Mode::ContLowPwr4,
+    Mode::ContLowPwr3,
+    Mode::ContLowPwr2,
+    Mode::ContLowPwr12bit,
+    Mode::SingleLowPwr4,
+    Mode::SingleLowPwr3,
+    Mode::SingleLowPwr2,
+    Mode::SingleLowPwr12bit,
+    Mode::HighPerformanceLowNoise,
+    Mode::ContLowPwrLowNoise4,
+    Mode::ContLowPwrLowNoise3,
+    Mode::ContLowPwrLowNoise2,
+    Mode::ContLowPwrLowNoise12bit,
+    Mode::SingleLowPwrLowNoise4,
+    Mode::SingleLowPwrLowNoise3,
+    Mode::SingleLowPwrLowNoise2,
+    Mode::SingleLowLowNoisePwr12bit,
+];
+
+/// Data rates of the high-performance mode.
+const CONTINUOUS_ODRS: [Odr; 9] = [
+    Odr::Off,
+    Odr::_12_5hz,
+    Odr::_25hz,
+    Odr::_50hz,
+    Odr::_100hz,
+    Odr::_200hz,
+    Odr::_400hz,
+    Odr::_800hz,
+    Odr::_1_6khz,
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Register map
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    check("WHO_AM_I: the identifier", sensor.device_id_get() == Ok(ID));
+    sensor.bus.set_reg(Reg::Ctrl1, 0x11);
+    sensor.bus.set_reg(Reg::Ctrl2, 0x04);
+    sensor.bus.set_reg(Reg::Ctrl3, 0x33);
+    let mut buf = [0; 3];
+    sensor
+        .read_from_register(Reg::Ctrl1 as u8, &mut buf)
+        .unwrap();
+    check(
+        "if_add_inc set: consecutive registers",
+        buf == [0x11, 0x04, 0x33],
+    );
+    sensor.bus.set_reg(Reg::Ctrl2, 0x00);
+    sensor
+        .read_from_register(Reg::Ctrl1 as u8, &mut buf)
+        .unwrap();
+    check("if_add_inc cleared: the same register", buf == [0x11; 3]);
+
+    // Round trips
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    let modes = MODES
+        .iter()
+        .all(|&mode| sensor.power_mode_set(mode).is_ok() && sensor.power_mode_get() == Ok(mode));
+    check("power_mode_set/get: every mode", modes);
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    let odrs = CONTINUOUS_ODRS
+        .iter()
+        .all(|&odr| sensor.data_rate_set(odr).is_ok() && sensor.data_rate_get() == Ok(odr));
+    check("data_rate_set/get: every high-performance data rate", odrs);
+    check(
+        "data_rate_set: mode kept",
+        sensor.power_mode_get() == Ok(Mode::HighPerformance),
+    );
+    check(
+        "1.6 Hz: refused in high-performance mode",
+        sensor.data_rate_set(Odr::_1_6hzLpOnly).is_err(),
+    );
+    check(
+        "1.6 Hz: set and read back with a low-power mode",
+        sensor
+            .odr_mode_set(Mode::ContLowPwr4, Odr::_1_6hzLpOnly)
+            .is_ok()
+            && sensor.data_rate_get() == Ok(Odr::_1_6hzLpOnly),
+    );
+
+    // Free-fall duration split over two registers
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.set_reg(Reg::FreeFall, 0x03);
+    sensor.bus.expect(&[
+        Access::read(Reg::WakeUpDur),
+        Access::read(Reg::FreeFall),
+        Access::write(Reg::WakeUpDur, WakeUpDur::new().with_ff_dur(1).into_bits()),
+        Access::write(
+            Reg::FreeFall,
+            FreeFall::new().with_ff_dur(0x0A).with_ff_ths(3).into_bits(),
+        ),
+    ]);
+    sensor.ff_dur_set(0x2A).unwrap();
+    check(
+        "ff_dur_set(0x2A): bit 5 in WAKE_UP_DUR, bits 4..0 in FREE_FALL",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+    check(
+        "ff_dur_get: 0x2A, threshold kept",
+        sensor.ff_dur_get() == Ok(0x2A) && sensor.bus.reg(Reg::FreeFall) & 0x07 == 0x03,
+    );
+
+    // Interrupt enable derived from the INT1 routing
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    let routes = Ctrl4Int1PadCtrl::new().with_int1_wu(1);
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl5Int2PadCtrl),
+        Access::read(Reg::Ctrl7),
+        Access::write(Reg::Ctrl4Int1PadCtrl, routes.into_bits()),
+        Access::write(
+            Reg::Ctrl7,
+            Ctrl7::new().with_interrupts_enable(1).into_bits(),
+        ),
+    ]);
+    sensor.pin_int1_route_set(&routes).unwrap();
+    check(
+        "pin_int1_route_set, wake-up: interrupts enabled",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+    let routes = Ctrl4Int1PadCtrl::new().with_int1_drdy(1);
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl5Int2PadCtrl),
+        Access::read(Reg::Ctrl7),
+        Access::write(Reg::Ctrl4Int1PadCtrl, routes.into_bits()),
+        Access::write(Reg::Ctrl7, Ctrl7::new().into_bits()),
+    ]);
+    sensor.pin_int1_route_set(&routes).unwrap();
+    check(
+        "pin_int1_route_set, data-ready only: interrupts disabled",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+
+    // Expectation failures
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::write(Reg::Ctrl1, 0x00)]);
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    check(
+        "mismatch: the first differing access",
+        matches!(
+            sensor.bus.expectations_check(),
+            Err(ExpectationError::Mismatch { index: 1, .. })
+        ),
+    );
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::WhoAmI), Access::read(Reg::WhoAmI)]);
+    sensor.device_id_get().unwrap();
+    check(
+        "missing: the access that did not happen",
+        sensor.bus.expectations_check()
+            == Err(ExpectationError::Missing {
+                index: 1,
+                expected: Access::read(Reg::WhoAmI),
+            }),
+    );
+    sensor.bus.expect(&[]);
+    sensor.device_id_get().unwrap();
+    check(
+        "unexpected: the access past the expected ones",
+        sensor.bus.expectations_check()
+            == Err(ExpectationError::Unexpected {
+                index: 0,
+                found: Access::read(Reg::WhoAmI),
+            }),
+    );
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::WhoAmI); MAX_EXPECTATIONS + 1]);
+    check(
+        "overflow: reported",
+        sensor.bus.expectations_check() == Err(ExpectationError::Overflow),
+    );
+    sensor.device_id_get().unwrap();
+    check(
+        "after a check: nothing expected, nothing reported",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,225 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::errata::Workarounds;
+use iis2dlpc_rs::mock::{Access, MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::settle::SettleHint;
+use iis2dlpc_rs::{mode_odr_check, ConfigError, Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+const MODES: [Mode; 18] = [
+    Mode::HighPerformance,
+    Mode::ContLowPwr4,
+    Mode::ContLowPwr3,
+    Mode::ContLowPwr2,
+    Mode::ContLowPwr12bit,
+    Mode::SingleLowPwr4,
+    Mode::SingleLowPwr3,
+    Mode::SingleLowPwr2,
+    Mode::SingleLowPwr12bit,
+    Mode::HighPerformanceLowNoise,
+    Mode::ContLowPwrLowNoise4,
+    Mode::ContLowPwrLowNoise3,
+    Mode::ContLowPwrLowNoise2,
+    Mode::ContLowPwrLowNoise12bit,
+    Mode::SingleLowPwrLowNoise4,
+    Mode::SingleLowPwrLowNoise3,
+    Mode::SingleLowPwrLowNoise2,
+    Mode::SingleLowLowNoisePwr12bit,
+];
+
+const ODRS: [Odr; 12] = [
+    Odr::Off,
+    Odr::_1_6hzLpOnly,
+    Odr::_12_5hz,
+    Odr::_25hz,
+    Odr::_50hz,
+    Odr::_100hz,
+    Odr::_200hz,
+    Odr::_400hz,
+    Odr::_800hz,
+    Odr::_1_6khz,
+    Odr::SetSwTrig,
+    Odr::SetPinTrig,
+];
+
+/// Modes running in high-performance, continuous.
+const HIGH_PERFORMANCE: [Mode; 2] = [Mode::HighPerformance, Mode::HighPerformanceLowNoise];
+
+/// Modes converting on demand.
+const SINGLE: [Mode; 8] = [
+    Mode::SingleLowPwr4,
+    Mode::SingleLowPwr3,
+    Mode::SingleLowPwr2,
+    Mode::SingleLowPwr12bit,
+    Mode::SingleLowPwrLowNoise4,
+    Mode::SingleLowPwrLowNoise3,
+    Mode::SingleLowPwrLowNoise2,
+    Mode::SingleLowLowNoisePwr12bit,
+];
+
+/// Modes with a 12-bit output.
+const LOW_POWER_1: [Mode; 4] = [
+    Mode::ContLowPwr12bit,
+    Mode::SingleLowPwr12bit,
+    Mode::ContLowPwrLowNoise12bit,
+    Mode::SingleLowLowNoisePwr12bit,
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Every pair, against the datasheet rules
+    let mut invalid = Vec::new();
+    let mut agree = true;
+    let mut rejected_untouched = true;
+    let mut accepted_read_back = true;
+    for mode in MODES {
+        for odr in ODRS {
+            if expected_invalid(mode, odr) {
+                invalid.push((mode, odr));
+            }
+            agree &= mode_odr_check::<()>(mode, odr).is_err() == expected_invalid(mode, odr);
+
+            let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+            if expected_invalid(mode, odr) {
+                sensor.bus.expect(&[]);
+                rejected_untouched &= sensor.odr_mode_set(mode, odr) == Err(Error::InvalidArgument)
+                    && sensor.bus.expectations_check() == Ok(());
+            } else {
+                accepted_read_back &= sensor.odr_mode_set(mode, odr).is_ok()
+                    && sensor.power_mode_get() == Ok(mode)
+                    && sensor.data_rate_get() == Ok(odr)
+                    && sensor.config_consistency_check() == Ok(());
+            }
+        }
+    }
+    // Continuous modes with a triggered rate: 10 x 2; single modes with a free-running rate: 8 x 9;
+    // 1.6 Hz in high performance: 2; 400 Hz to 1.6 kHz in continuous low power: 8 x 3
+    check(
+        "118 invalid pairs out of 216",
+        invalid.len() == 20 + 72 + 2 + 24,
+    );
+    check("mode_odr_check: the invalid pairs", agree);
+    check(
+        "odr_mode_set: invalid pairs refused with InvalidArgument, nothing accessed",
+        rejected_untouched,
+    );
+    check(
+        "odr_mode_set: valid pairs read back, consistent",
+        accepted_read_back,
+    );
+
+    // The separate setters
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_1_6khz).unwrap();
+    check(
+        "data_rate_set: 1.6 Hz refused in high-performance mode",
+        sensor.data_rate_set(Odr::_1_6hzLpOnly) == Err(Error::InvalidArgument),
+    );
+    check(
+        "power_mode_set: low-power mode refused at 1.6 kHz",
+        sensor.power_mode_set(Mode::ContLowPwr4) == Err(Error::InvalidArgument),
+    );
+
+    // A pair written around the setters is reported with its inconsistency
+    let mut raw = Sensor::from_bus(MockBus::new(), NoDelay);
+    let ctrl1 = Ctrl1::new()
+        .with_mode(Mode::ContLowPwr4.mode())
+        .with_lp_mode(Mode::ContLowPwr4.lp_mode())
+        .with_odr(Odr::_1_6khz.odr());
+    raw.bus.set_reg(Reg::Ctrl1, ctrl1.into_bits());
+    check(
+        "config_consistency_check: low-power mode at 1.6 kHz",
+        raw.config_consistency_check()
+            == Err(Error::InvalidConfig(
+                ConfigError::LowPowerWithHighPerformanceOnlyOdr {
+                    mode: Mode::ContLowPwr4,
+                    odr: Odr::_1_6khz,
+                },
+            )),
+    );
+
+    // Down from 1.6 kHz in two calls: the data rate first, then the mode
+    let mut stepped = Sensor::from_bus(MockBus::new(), NoDelay);
+    stepped.odr_mode_set(Mode::HighPerformance, Odr::_1_6khz).unwrap();
+    check(
+        "data_rate_set then power_mode_set: low power at 200 Hz",
+        stepped.data_rate_set(Odr::_200hz).is_ok()
+            && stepped.power_mode_set(Mode::ContLowPwr4).is_ok()
+            && stepped.power_mode_get() == Ok(Mode::ContLowPwr4),
+    );
+
+    // From 1.6 kHz in high performance to 1.6 Hz in low power, in one CTRL1 write
+    sensor.workarounds_set(Workarounds::NONE);
+    let ctrl1 = Ctrl1::from_bits(sensor.bus.reg(Reg::Ctrl1));
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl6),
+        Access::write(Reg::Ctrl6, sensor.bus.reg(Reg::Ctrl6)),
+        Access::read(Reg::Ctrl3),
+        Access::write(Reg::Ctrl3, sensor.bus.reg(Reg::Ctrl3)),
+        Access::read(Reg::Ctrl1),
+        Access::write(
+            Reg::Ctrl1,
+            ctrl1
+                .with_mode(Mode::ContLowPwr4.mode())
+                .with_lp_mode(Mode::ContLowPwr4.lp_mode())
+                .with_odr(Odr::_1_6hzLpOnly.odr())
+                .into_bits(),
+        ),
+    ]);
+    let hint = sensor.odr_mode_set(Mode::ContLowPwr4, Odr::_1_6hzLpOnly);
+    check(
+        "odr_mode_set: CTRL6, CTRL3, then CTRL1 in a single write",
+        hint.is_ok() && sensor.bus.expectations_check() == Ok(()),
+    );
+    check(
+        "odr_mode_set: filter settling at 1.6 Hz",
+        hint == Ok(SettleHint::DurationUs(2 * 625_000)),
+    );
+    check(
+        "odr_mode_set: mode change alone, one pending sample",
+        sensor.odr_mode_set(Mode::ContLowPwr2, Odr::_1_6hzLpOnly) == Ok(SettleHint::Samples(1)),
+    );
+    check(
+        "odr_mode_set: nothing changed, no wait",
+        sensor.odr_mode_set(Mode::ContLowPwr2, Odr::_1_6hzLpOnly) == Ok(SettleHint::None),
+    );
+
+    // Resolution
+    check(
+        "resolution_bits: 12 in low-power mode 1, 14 otherwise",
+        MODES.iter().all(|mode| {
+            let bits = if LOW_POWER_1.contains(mode) { 12 } else { 14 };
+            mode.resolution_bits() == bits
+        }),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Whether the datasheet rules out a pair: the device would run it at another rate, or not at all.
+fn expected_invalid(mode: Mode, odr: Odr) -> bool {
+    let triggered = matches!(odr, Odr::SetSwTrig | Odr::SetPinTrig);
+    let high_performance = HIGH_PERFORMANCE.contains(&mode);
+    match odr {
+        Odr::Off => false,
+        _ if SINGLE.contains(&mode) => !triggered,
+        _ if triggered => true,
+        Odr::_1_6hzLpOnly => high_performance,
+        Odr::_400hz | Odr::_800hz | Odr::_1_6khz => !high_performance,
+        _ => false,
+    }
+}
@@ -0,0 +1,142 @@
+#![no_main]
+#![no_std]
+
+use core::cell::RefCell;
+use core::fmt::Write;
+
+use embedded_hal_bus::i2c::RefCellDevice;
+use iis2dlpc_rs::mux::{probe_who_am_i_muxed, MuxedI2cBus, CHANNELS};
+use iis2dlpc_rs::{from_fs2_to_mg, prelude::*};
+use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
+
+use panic_itm as _;
+
+use cortex_m_rt::entry;
+use stm32f4xx_hal::{
+    hal::delay::DelayNs,
+    i2c::{DutyCycle, I2c, Mode as I2cMode},
+    pac,
+    prelude::*,
+    serial::Config,
+};
+
+const SYSCLK_HZ: u32 = 48_000_000;
+
+/// Address of the PCA9548 with A0, A1 and A2 tied low.
+const PCA9548_ADDR: u8 = 0x70;
+
+/// Channels of the two sensors of the rig.
+const RIG: [u8; 2] = [0, 1];
+
+// Busy-wait delay: each sensor owns one.
+#[derive(Clone, Copy)]
+struct CycleDelay;
+
+impl DelayNs for CycleDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        let cycles = (ns as u64 * SYSCLK_HZ as u64).div_ceil(1_000_000_000);
+        cortex_m::asm::delay(cycles as u32);
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let dp = pac::Peripherals::take().unwrap();
+
+    let rcc = dp.RCC.constrain();
+    let clocks = rcc.cfgr.use_hse(8.MHz()).sysclk(SYSCLK_HZ.Hz()).freeze();
+
+    let gpiob = dp.GPIOB.split();
+    let gpioa = dp.GPIOA.split();
+
+    let scl = gpiob.pb8;
+    let sda = gpiob.pb9;
+
+    let i2c = I2c::new(
+        dp.I2C1,
+        (scl, sda),
+        I2cMode::Fast {
+            frequency: 400.kHz(),
+            duty_cycle: DutyCycle::Ratio2to1,
+        },
+        &clocks,
+    );
+    let i2c = RefCell::new(i2c);
+
+    let tx_pin = gpioa.pa2.into_alternate();
+    let mut tx = dp
+        .USART2
+        .tx(
+            tx_pin,
+            Config::default()
+                .baudrate(115200.bps())
+                .wordlength_8()
+                .parity_none(),
+            &clocks,
+        )
+        .unwrap();
+
+    // Scan the channels of the switch
+    for channel in 0..CHANNELS {
+        let id = probe_who_am_i_muxed(
+            &mut RefCellDevice::new(&i2c),
+            PCA9548_ADDR,
+            channel,
+            I2CAddress::I2cAddH,
+        );
+        match id {
+            Ok(iis2dlpc_rs::ID) => writeln!(tx, "Channel {channel}: IIS2DLPC").unwrap(),
+            Ok(value) => writeln!(tx, "Channel {channel}: unknown device {value:#04x}").unwrap(),
+            Err(_) => writeln!(tx, "Channel {channel}: empty").unwrap(),
+        }
+    }
+
+    // Every sensor has the same address: the switch channel tells them apart
+    let mut sensors = RIG.map(|channel| {
+        let bus = MuxedI2cBus::new(
+            RefCellDevice::new(&i2c),
+            PCA9548_ADDR,
+            channel,
+            I2CAddress::I2cAddH,
+        )
+        .unwrap();
+        // The bus selects the channel once before every transaction, bursts included
+        Iis2dlpc::from_bus(bus, CycleDelay)
+    });
+
+    for sensor in sensors.iter_mut() {
+        if sensor.device_id_get().unwrap() != iis2dlpc_rs::ID {
+            panic!("Invalid sensor ID")
+        }
+        sensor.tim.delay_ms(25);
+
+        // Restore default configuration
+        sensor.reset_set().unwrap();
+        while sensor.reset_get().unwrap() == 1 {}
+
+        sensor.block_data_update_set(true).unwrap();
+        sensor.full_scale_set(Fs::_2g).unwrap();
+        sensor.power_mode_set(Mode::HighPerformance).unwrap();
+        sensor.data_rate_set(Odr::_25hz).unwrap();
+    }
+
+    // Read the sensors in turn: each read selects its channel first
+    loop {
+        for (channel, sensor) in RIG.iter().zip(sensors.iter_mut()) {
+            match sensor.flag_data_ready_get() {
+                Ok(1) => {
+                    let acceleration_mg =
+                        sensor.acceleration_raw_get().unwrap().map(from_fs2_to_mg);
+                    writeln!(
+                        tx,
+                        "Channel {channel} acceleration [mg]: {:4.2}\t{:4.2}\t{:4.2}",
+                        acceleration_mg[0], acceleration_mg[1], acceleration_mg[2]
+                    )
+                    .unwrap();
+                }
+                Ok(_) => {}
+                Err(e) => writeln!(tx, "Channel {channel} read failed: {e:?}").unwrap(),
+            }
+        }
+    }
+}
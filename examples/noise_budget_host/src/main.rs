@@ -0,0 +1,160 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::noise::{mode_for_budget, CONTINUOUS_MODES};
+use iis2dlpc_rs::prelude::*;
+
+const ODRS: [Odr; 12] = [
+    Odr::Off,
+    Odr::_1_6hzLpOnly,
+    Odr::_12_5hz,
+    Odr::_25hz,
+    Odr::_50hz,
+    Odr::_100hz,
+    Odr::_200hz,
+    Odr::_400hz,
+    Odr::_800hz,
+    Odr::_1_6khz,
+    Odr::SetSwTrig,
+    Odr::SetPinTrig,
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Datasheet values
+    check(
+        "high-performance noise: 90 µg/√Hz with low noise",
+        Mode::HighPerformanceLowNoise.noise_ug_per_sqrt_hz() == 90,
+    );
+    check(
+        "low-power mode 1 noise: 1300 µg/√Hz",
+        Mode::ContLowPwr12bit.noise_ug_per_sqrt_hz() == 1300,
+    );
+    check(
+        "single and continuous modes: same noise",
+        Mode::SingleLowPwrLowNoise3.noise_ug_per_sqrt_hz()
+            == Mode::ContLowPwrLowNoise3.noise_ug_per_sqrt_hz(),
+    );
+    check(
+        "high-performance current: 90 µA at 100 Hz",
+        Mode::HighPerformance.typical_current_ua(Odr::_100hz) == Some(90.0),
+    );
+    check(
+        "low-power mode 1 current: 0.38 µA at 1.6 Hz",
+        Mode::ContLowPwr12bit.typical_current_ua(Odr::_1_6hzLpOnly) == Some(0.38),
+    );
+    check(
+        "low noise: same current",
+        Mode::ContLowPwrLowNoise4.typical_current_ua(Odr::_50hz)
+            == Mode::ContLowPwr4.typical_current_ua(Odr::_50hz),
+    );
+    check(
+        "no entry: low-power mode 4 at 400 Hz, high-performance at 1.6 Hz, off",
+        Mode::ContLowPwr4.typical_current_ua(Odr::_400hz).is_none()
+            && Mode::HighPerformance
+                .typical_current_ua(Odr::_1_6hzLpOnly)
+                .is_none()
+            && Mode::HighPerformance.typical_current_ua(Odr::Off).is_none(),
+    );
+
+    // Tables ordered: the current grows with the power level, the noise decreases
+    let levels = [
+        Mode::ContLowPwr12bit,
+        Mode::ContLowPwr2,
+        Mode::ContLowPwr3,
+        Mode::ContLowPwr4,
+        Mode::HighPerformance,
+    ];
+    let ordered = levels.windows(2).all(|pair| {
+        pair[0].noise_ug_per_sqrt_hz() > pair[1].noise_ug_per_sqrt_hz()
+            && ODRS.iter().all(|odr| {
+                match (
+                    pair[0].typical_current_ua(*odr),
+                    pair[1].typical_current_ua(*odr),
+                ) {
+                    (Some(low), Some(high)) => low < high,
+                    _ => true,
+                }
+            })
+    });
+    check("tables ordered by power level", ordered);
+
+    // Selector
+    check(
+        "no constraint: low-power mode 1 with low noise",
+        mode_for_budget(u32::MAX, u32::MAX, Odr::_100hz) == Some(Mode::ContLowPwrLowNoise12bit),
+    );
+    check(
+        "600 µg/√Hz at 100 Hz: low-power mode 3 with low noise",
+        mode_for_budget(600, 100, Odr::_100hz) == Some(Mode::ContLowPwrLowNoise3),
+    );
+    check(
+        "100 µg/√Hz: high-performance with low noise",
+        mode_for_budget(100, 100, Odr::_50hz) == Some(Mode::HighPerformanceLowNoise),
+    );
+    check(
+        "800 Hz: only high-performance",
+        mode_for_budget(u32::MAX, u32::MAX, Odr::_800hz) == Some(Mode::HighPerformanceLowNoise),
+    );
+    check(
+        "100 µg/√Hz within 50 µA: no solution",
+        mode_for_budget(100, 50, Odr::_50hz).is_none(),
+    );
+    check(
+        "off or triggered: no solution",
+        [Odr::Off, Odr::SetSwTrig, Odr::SetPinTrig]
+            .iter()
+            .all(|odr| mode_for_budget(u32::MAX, u32::MAX, *odr).is_none()),
+    );
+
+    // Exhaustive: the selection meets the budgets, no other mode draws less, and at equal current
+    // none is quieter
+    let mut cases = 0;
+    let mut ok = true;
+    for odr in ODRS {
+        for max_noise in [50, 90, 100, 400, 500, 700, 1000, 1300, u32::MAX] {
+            for max_current in [0, 1, 2, 5, 10, 20, 50, 90, u32::MAX] {
+                let fits = |mode: &Mode| {
+                    mode.noise_ug_per_sqrt_hz() <= max_noise
+                        && mode
+                            .typical_current_ua(odr)
+                            .is_some_and(|ua| ua <= max_current as f32)
+                };
+                let key = |mode: &Mode| {
+                    (
+                        mode.typical_current_ua(odr).unwrap_or(f32::MAX),
+                        mode.noise_ug_per_sqrt_hz(),
+                    )
+                };
+                let best = CONTINUOUS_MODES
+                    .iter()
+                    .filter(|mode| fits(mode))
+                    .map(key)
+                    .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+                let selected = mode_for_budget(max_noise, max_current, odr);
+                ok &= match (selected, best) {
+                    (Some(mode), Some(best)) => fits(&mode) && key(&mode) == best,
+                    (None, None) => true,
+                    _ => false,
+                };
+                cases += 1;
+            }
+        }
+    }
+    check(
+        &format!("{cases} budgets: lowest current within budget"),
+        ok,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
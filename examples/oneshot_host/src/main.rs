@@ -0,0 +1,311 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use iis2dlpc_rs::mock::MockBus;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc, DEFAULT_ONESHOT_MAX_POLLS};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<SimBus, SimDelay>;
+
+const SAMPLE: [i16; 3] = [120, -340, 4000];
+const STALE: [i16; 3] = [1, 2, 3];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Continuous mode
+    let (mut sensor, sim) = new_sensor();
+    sensor.data_rate_set(Odr::Off).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sim.borrow_mut().ctrl3_writes.clear();
+    check(
+        "continuous mode: rejected, CTRL3 untouched",
+        sensor.acceleration_oneshot_get(SlpModeSel::SwTrigger)
+            == Err(Error::InvalidConfig(ConfigError::OnDemandRequired))
+            && sim.borrow().ctrl3_writes.is_empty(),
+    );
+
+    // Software trigger
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().conversion_waits = 3;
+    let sample = sensor.acceleration_oneshot_get(SlpModeSel::SwTrigger);
+    check("software trigger: sample read", sample == Ok(SAMPLE));
+    check(
+        "software trigger: one CTRL3 write, slp_mode_sel and slp_mode_1 set",
+        sim.borrow().ctrl3_writes == [0x03],
+    );
+    check(
+        "software trigger: polled until ready",
+        sim.borrow().waits == 3 && sim.borrow().status_reads == 5,
+    );
+
+    // Sample left unread by a previous conversion
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().set_output(STALE);
+    sim.borrow_mut().conversion_waits = 1;
+    let sample = sensor.acceleration_oneshot_get(SlpModeSel::SwTrigger);
+    check(
+        "stale sample: discarded, new sample returned",
+        sample == Ok(SAMPLE),
+    );
+
+    // No conversion
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().stalled = true;
+    let sample = sensor.acceleration_oneshot_get(SlpModeSel::SwTrigger);
+    check(
+        "stalled sensor: timeout after the default polls",
+        sample == Err(Error::Timeout)
+            && sim.borrow().status_reads == 1 + DEFAULT_ONESHOT_MAX_POLLS as u32
+            && sim.borrow().waits == DEFAULT_ONESHOT_MAX_POLLS as u32 - 1,
+    );
+    sensor.oneshot_max_polls_set(5);
+    sim.borrow_mut().status_reads = 0;
+    let sample = sensor.acceleration_oneshot_get(SlpModeSel::SwTrigger);
+    check(
+        "stalled sensor, called again with the conversion pending: timeout after 5 polls",
+        sample == Err(Error::Timeout) && sim.borrow().status_reads == 6,
+    );
+    sensor.oneshot_max_polls_set(0);
+    check("0 polls taken as 1", sensor.oneshot_max_polls_get() == 1);
+
+    // Pin trigger
+    let (mut sensor, sim) = new_sensor();
+    sim.borrow_mut().edge_after = Some(4);
+    let sample = sensor.acceleration_oneshot_get(SlpModeSel::PinTrigger);
+    check(
+        "pin trigger: sample read after the edge, CTRL3 untouched",
+        sample == Ok(SAMPLE) && sim.borrow().ctrl3_writes.is_empty(),
+    );
+    let (mut sensor, _sim) = new_sensor();
+    let sample = sensor.acceleration_oneshot_get(SlpModeSel::PinTrigger);
+    check(
+        "pin trigger, no edge: timeout",
+        sample == Err(Error::Timeout),
+    );
+
+    // Trigger helpers, with the other CTRL3 bits set
+    let (mut sensor, sim) = new_sensor();
+    check(
+        "data_rate_set(Odr::SetSwTrig): slp_mode_sel set, no conversion",
+        sim.borrow().regs.reg(Reg::Ctrl3) == 0x02 && sim.borrow().pending.is_none(),
+    );
+    sensor.pin_polarity_set(HLactive::ActiveLow).unwrap();
+    sensor.int_notification_set(Lir::Latched).unwrap();
+    sim.borrow_mut().ctrl3_writes.clear();
+    sim.borrow_mut().conversion_waits = 1;
+    sensor.sw_trigger().unwrap();
+    check(
+        "sw_trigger: one CTRL3 write of 0x1B",
+        sim.borrow().ctrl3_writes == [0x1B],
+    );
+    check(
+        "sw_trigger: data rate still read as the software trigger",
+        sensor.data_rate_get() == Ok(Odr::SetSwTrig),
+    );
+    let mut drdy = Vec::new();
+    for _ in 0..4 {
+        let status = sensor.status_reg_get().unwrap();
+        drdy.push(status.drdy());
+        if status.drdy() == 1 {
+            sensor.acceleration_raw_get().unwrap();
+        }
+    }
+    check(
+        "sw_trigger: exactly one sample",
+        drdy == [0, 1, 0, 0] && sim.borrow().regs.reg(Reg::Ctrl3) == 0x1A,
+    );
+    sim.borrow_mut().ctrl3_writes.clear();
+    sensor.trigger_mode_set(SlpModeSel::PinTrigger).unwrap();
+    check(
+        "trigger_mode_set(PinTrigger): one CTRL3 write of 0x19, no conversion",
+        sim.borrow().ctrl3_writes == [0x19]
+            && sim.borrow().pending.is_none()
+            && sensor.trigger_mode_get() == Ok(SlpModeSel::PinTrigger)
+            && sensor.data_rate_get() == Ok(Odr::SetPinTrig),
+    );
+    sensor.trigger_mode_set(SlpModeSel::SwTrigger).unwrap();
+    check(
+        "trigger_mode_set(SwTrigger): CTRL3 write of 0x1A",
+        sim.borrow().ctrl3_writes == [0x19, 0x1A]
+            && sensor.trigger_mode_get() == Ok(SlpModeSel::SwTrigger),
+    );
+    sensor.data_rate_set(Odr::Off).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sim.borrow_mut().ctrl3_writes.clear();
+    check(
+        "continuous mode: sw_trigger and trigger_mode_set rejected, CTRL3 untouched",
+        sensor.sw_trigger() == Err(Error::InvalidConfig(ConfigError::OnDemandRequired))
+            && sensor.trigger_mode_set(SlpModeSel::SwTrigger)
+                == Err(Error::InvalidConfig(ConfigError::OnDemandRequired))
+            && sim.borrow().ctrl3_writes.is_empty(),
+    );
+
+    // FIFO path
+    let (mut sensor, sim) = new_sensor();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sim.borrow_mut().ctrl3_writes.clear();
+    check(
+        "FIFO path active: rejected, CTRL3 untouched",
+        sensor.acceleration_oneshot_get(SlpModeSel::SwTrigger)
+            == Err(Error::InvalidConfig(ConfigError::FifoPathActive))
+            && sim.borrow().ctrl3_writes.is_empty(),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A sensor in single low-power mode 4, triggered on demand, and the simulation it runs on.
+fn new_sensor() -> (Sensor, Rc<RefCell<Sim>>) {
+    let sim = Rc::new(RefCell::new(Sim::default()));
+    let mut sensor = Iis2dlpc::from_bus(SimBus(sim.clone()), SimDelay(sim.clone()));
+    sensor.power_mode_set(Mode::SingleLowPwr4).unwrap();
+    sensor.data_rate_set(Odr::SetSwTrig).unwrap();
+    let mut sim_ref = sim.borrow_mut();
+    sim_ref.pending = None;
+    sim_ref.ctrl3_writes.clear();
+    sim_ref.status_reads = 0;
+    drop(sim_ref);
+    (sensor, sim)
+}
+
+/// Simulated sensor: a conversion completes a number of waits after its trigger.
+#[derive(Default)]
+struct Sim {
+    regs: MockBus,
+    /// Waits of a conversion.
+    conversion_waits: u32,
+    /// Waits left before the conversion in progress completes.
+    pending: Option<u32>,
+    /// Waits before a rising edge on INT2.
+    edge_after: Option<u32>,
+    /// No conversion completes.
+    stalled: bool,
+    /// Waits of the driver.
+    waits: u32,
+    /// Values written to `CTRL3`.
+    ctrl3_writes: Vec<u8>,
+    /// Number of reads of `STATUS`.
+    status_reads: u32,
+}
+
+impl Sim {
+    /// Store a sample in the output registers and set the `drdy` flag.
+    fn set_output(&mut self, sample: [i16; 3]) {
+        let regs = [
+            Reg::OutXL,
+            Reg::OutXH,
+            Reg::OutYL,
+            Reg::OutYH,
+            Reg::OutZL,
+            Reg::OutZH,
+        ];
+        let bytes = sample.map(|val| (val << 2).to_le_bytes()).concat();
+        for (reg, byte) in regs.into_iter().zip(bytes) {
+            self.regs.set_reg(reg, byte);
+        }
+        let status = self.regs.reg(Reg::Status) | 0x01;
+        self.regs.set_reg(Reg::Status, status);
+    }
+
+    /// Complete the conversion in progress once its waits are over.
+    fn tick(&mut self) {
+        if self.stalled {
+            return;
+        }
+        match self.pending {
+            Some(0) => {
+                self.pending = None;
+                self.set_output(SAMPLE);
+                // slp_mode_1 clears itself with the software trigger only
+                let ctrl3 = self.regs.reg(Reg::Ctrl3);
+                if ctrl3 & 0x02 != 0 {
+                    self.regs.set_reg(Reg::Ctrl3, ctrl3 & !0x01);
+                }
+            }
+            Some(n) => self.pending = Some(n - 1),
+            None => {}
+        }
+    }
+}
+
+struct SimBus(Rc<RefCell<Sim>>);
+
+impl BusOperation for SimBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.borrow_mut().regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        sim.regs.write_bytes(wbuf)?;
+        if wbuf[0] == Reg::Ctrl3 as u8 {
+            let ctrl3 = sim.regs.reg(Reg::Ctrl3);
+            sim.ctrl3_writes.push(ctrl3);
+            // slp_mode_sel and slp_mode_1
+            if ctrl3 & 0x03 == 0x03 {
+                sim.pending = Some(sim.conversion_waits);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let mut sim = self.0.borrow_mut();
+        if wbuf[0] == Reg::Status as u8 {
+            sim.status_reads += 1;
+            sim.tick();
+        }
+        sim.regs.write_byte_read_bytes(wbuf, rbuf)?;
+        // Reading the Z axis, alone or at the end of a burst, clears the data-ready flag
+        let first = wbuf[0] as usize;
+        if (Reg::OutXL as usize..=Reg::OutZL as usize).contains(&first)
+            && first + rbuf.len() > Reg::OutZL as usize
+        {
+            let status = sim.regs.reg(Reg::Status) & !0x01;
+            sim.regs.set_reg(Reg::Status, status);
+        }
+        Ok(())
+    }
+}
+
+/// Delay of the driver, counting the waits and delivering the INT2 edge.
+struct SimDelay(Rc<RefCell<Sim>>);
+
+impl DelayNs for SimDelay {
+    fn delay_ns(&mut self, _ns: u32) {
+        let mut sim = self.0.borrow_mut();
+        sim.waits += 1;
+        match sim.edge_after {
+            Some(0) => {
+                sim.edge_after = None;
+                let waits = sim.conversion_waits;
+                sim.pending = Some(waits);
+            }
+            Some(n) => sim.edge_after = Some(n - 1),
+            None => {}
+        }
+    }
+}
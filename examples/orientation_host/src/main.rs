@@ -0,0 +1,142 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::event::Orientation;
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::remap::{AxisRemap, SignedAxis};
+use iis2dlpc_rs::{Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// `SIXD_SRC` flags, from bit 0 to bit 5, with the orientation they name.
+const FLAGS: [(u8, Orientation); 6] = [
+    (0x01, Orientation::XDown),
+    (0x02, Orientation::XUp),
+    (0x04, Orientation::YDown),
+    (0x08, Orientation::YUp),
+    (0x10, Orientation::ZDown),
+    (0x20, Orientation::ZUp),
+];
+
+/// `six_d_ia` in `SIXD_SRC`.
+const SIX_D_IA: u8 = 0x40;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Every SIXD_SRC content, in 6D and 4D mode
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    for four_d in [false, true] {
+        sensor.fourd_mode_set(four_d).unwrap();
+        let all = (0..=u8::MAX).all(|raw| {
+            sensor.bus.set_reg(Reg::SixdSrc, raw);
+            let decoded = sensor.orientation_get();
+            decoded == Ok(expected(raw, four_d))
+                && Orientation::decode(SixdSrc::from_bits(raw), four_d) == expected(raw, four_d)
+        });
+        let mode = if four_d { "4D" } else { "6D" };
+        check(
+            &format!("orientation_get, {mode}: all 256 SIXD_SRC contents"),
+            all,
+        );
+    }
+    let z_states = (0..=u8::MAX).any(|raw| {
+        matches!(
+            Orientation::decode(SixdSrc::from_bits(raw), true),
+            Some(Orientation::ZUp | Orientation::ZDown)
+        )
+    });
+    check("4D mode: no Z state", !z_states);
+    sensor.fourd_mode_set(false).unwrap();
+    sensor.bus.set_reg(Reg::SixdSrc, 0x00);
+    check("no 6D event: None", sensor.orientation_get() == Ok(None));
+    sensor.bus.set_reg(Reg::SixdSrc, SIX_D_IA | 0x06);
+    check(
+        "two flags, X up and Y down: None",
+        sensor.orientation_get() == Ok(None),
+    );
+    sensor.bus.set_reg(Reg::SixdSrc, SIX_D_IA | 0x20);
+    let before = sensor.event_counters().six_d;
+    check(
+        "Z up: counted as a 6D event",
+        sensor.orientation_get() == Ok(Some(Orientation::ZUp))
+            && sensor.event_counters().six_d == before + 1,
+    );
+
+    // Board frame
+    let remap = AxisRemap::new([SignedAxis::PosY, SignedAxis::NegX, SignedAxis::PosZ]).unwrap();
+    sensor.axis_remap_set(remap);
+    let board = [
+        (0x02, Orientation::YDown),
+        (0x01, Orientation::YUp),
+        (0x08, Orientation::XUp),
+        (0x04, Orientation::XDown),
+        (0x20, Orientation::ZUp),
+    ];
+    check(
+        "rotated by 90 degrees around Z: board axes",
+        board.iter().all(|&(flag, orientation)| {
+            sensor.bus.set_reg(Reg::SixdSrc, SIX_D_IA | flag);
+            sensor.orientation_get() == Ok(Some(orientation))
+        }),
+    );
+    let flipped = AxisRemap::new([SignedAxis::PosX, SignedAxis::NegY, SignedAxis::NegZ]).unwrap();
+    sensor.axis_remap_set(flipped);
+    sensor.bus.set_reg(Reg::SixdSrc, SIX_D_IA | 0x20);
+    check(
+        "upside down: Z up reads Z down",
+        sensor.orientation_get() == Ok(Some(Orientation::ZDown)),
+    );
+
+    // Threshold in degrees
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor.fourd_mode_set(true).unwrap();
+    let angles = [(80, 0), (70, 1), (60, 2), (50, 3)];
+    check(
+        "sixd_threshold_degrees_set: 80, 70, 60 and 50 degrees",
+        angles.iter().all(|&(deg, code)| {
+            sensor.sixd_threshold_degrees_set(deg).is_ok()
+                && sensor.sixd_threshold_get() == Ok(code)
+                && sensor.sixd_threshold_degrees_get() == Ok(deg)
+        }),
+    );
+    check("4D mode kept", sensor.fourd_mode_get() == Ok(true));
+    let before = sensor.bus.reg(Reg::TapThsX);
+    check(
+        "unsupported angles: invalid argument, nothing written",
+        [0, 45, 55, 65, 75, 90, 255]
+            .iter()
+            .all(|&deg| sensor.sixd_threshold_degrees_set(deg) == Err(Error::InvalidArgument))
+            && sensor.bus.reg(Reg::TapThsX) == before,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Orientation named by a `SIXD_SRC` content: `six_d_ia` set with exactly one flag, Z flags
+/// ignored in 4D mode.
+fn expected(raw: u8, four_d: bool) -> Option<Orientation> {
+    if raw & SIX_D_IA == 0 {
+        return None;
+    }
+    let mut flagged = FLAGS
+        .iter()
+        .filter(|&&(flag, orientation)| {
+            raw & flag != 0 && !(four_d && orientation.axis() == Axis::Z)
+        })
+        .map(|&(_, orientation)| orientation);
+    match (flagged.next(), flagged.next()) {
+        (Some(orientation), None) => Some(orientation),
+        _ => None,
+    }
+}
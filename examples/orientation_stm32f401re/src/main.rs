@@ -78,7 +78,7 @@ fn main() -> ! {
         .power_mode_set(Mode::ContLowPwrLowNoise12bit)
         .unwrap();
     // Set threshold to 60 degrees
-    sensor.sixd_threshold_set(0x02).unwrap();
+    sensor.sixd_threshold_degrees_set(60).unwrap();
     // LPF2 on 6D function selection
     sensor.sixd_feed_data_set(LpassOn6d::Lpf2Feed).unwrap();
     // Enable interrupt generation on 6D INT1 pin
@@ -91,35 +91,8 @@ fn main() -> ! {
     // Wait Events
     loop {
         // Check 6D Orientation events
-        let all_sources = sensor.all_sources_get().unwrap();
-        if all_sources.sixd_src.six_d_ia() == 1 {
-            write!(tx, "6D or. switched to ").unwrap();
-
-            if all_sources.sixd_src.xh() == 1 {
-                write!(tx, "XH").unwrap();
-            }
-
-            if all_sources.sixd_src.xl() == 1 {
-                write!(tx, "XL").unwrap();
-            }
-
-            if all_sources.sixd_src.yh() == 1 {
-                write!(tx, "YH").unwrap();
-            }
-
-            if all_sources.sixd_src.yl() == 1 {
-                write!(tx, "YL").unwrap();
-            }
-
-            if all_sources.sixd_src.zh() == 1 {
-                write!(tx, "ZH").unwrap();
-            }
-
-            if all_sources.sixd_src.zl() == 1 {
-                write!(tx, "ZL").unwrap();
-            }
-
-            writeln!(tx, "").unwrap();
+        if let Some(orientation) = sensor.orientation_get().unwrap() {
+            writeln!(tx, "6D or. switched to {orientation:?}").unwrap();
         }
     }
 }
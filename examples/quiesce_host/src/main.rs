@@ -0,0 +1,185 @@
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Iis2dlpc, PROPERTY_ENABLE};
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<LogBus, NoDelay>;
+
+const PADS: [Reg; 3] = [Reg::Ctrl4Int1PadCtrl, Reg::Ctrl5Int2PadCtrl, Reg::Ctrl7];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Wake-up and data-ready on INT1, sleep change on INT2, user offsets on the outputs
+    let mut sensor = new_sensor();
+    let original = pads(&sensor);
+    let guard = sensor.interrupts_quiesce().unwrap();
+    check(
+        "quiesce: routing saved from the shadows, only CTRL7 read",
+        sensor.bus.reads == [Reg::Ctrl7 as u8]
+            && guard.interrupts_enabled()
+            && guard.int1_routes().into_bits() == original[0]
+            && guard.int2_routes().into_bits() == original[1],
+    );
+    check(
+        "quiesce: CTRL7 enable cleared first, then both routings",
+        sensor.bus.written_regs() == [Reg::Ctrl7 as u8, PADS[0] as u8, PADS[1] as u8],
+    );
+    let ctrl7 = Ctrl7::from_bits(sensor.bus.regs.reg(Reg::Ctrl7));
+    check(
+        "quiesce: pads silent, other CTRL7 fields kept",
+        pads(&sensor)[..2] == [0, 0]
+            && ctrl7.interrupts_enable() == 0
+            && ctrl7.usr_off_on_out() == 1,
+    );
+    check(
+        "quiesce: shadows follow",
+        sensor.int1_routes_shadow().shadow_valid
+            && sensor.int1_routes_shadow().routes.into_bits() == 0
+            && sensor.int2_routes_shadow().routes.into_bits() == 0,
+    );
+
+    sensor.bus.clear_log();
+    sensor.interrupts_restore(guard).unwrap();
+    check(
+        "restore: routings, then CTRL7",
+        sensor.bus.written_regs() == [PADS[0] as u8, PADS[1] as u8, Reg::Ctrl7 as u8],
+    );
+    check("restore: exact previous state", pads(&sensor) == original);
+    check(
+        "restore: shadows follow",
+        sensor.int1_routes_shadow().routes.into_bits() == original[0]
+            && sensor.int2_routes_shadow().routes.into_bits() == original[1],
+    );
+
+    // Quiesce twice: the second guard is the first one
+    let mut sensor = new_sensor();
+    let original = pads(&sensor);
+    let first = sensor.interrupts_quiesce().unwrap();
+    let second = sensor.interrupts_quiesce().unwrap();
+    check(
+        "quiesce twice: idempotent, same guard",
+        first == second && pads(&sensor)[..2] == [0, 0],
+    );
+    sensor.interrupts_restore(second).unwrap();
+    check(
+        "quiesce twice: second guard restores the original",
+        pads(&sensor) == original,
+    );
+    let third = sensor.interrupts_quiesce().unwrap();
+    check("after a restore: saved again", third == first);
+    sensor.interrupts_restore(third).unwrap();
+
+    // Routing written raw: the shadows are not trusted
+    let mut sensor = new_sensor();
+    sensor
+        .write_to_register(
+            Reg::Ctrl4Int1PadCtrl as u8,
+            &[Ctrl4Int1PadCtrl::new().with_int1_6d(1).into_bits()],
+        )
+        .unwrap();
+    let original = pads(&sensor);
+    sensor.bus.clear_log();
+    let guard = sensor.interrupts_quiesce().unwrap();
+    check(
+        "invalid INT1 shadow: CTRL4 read",
+        sensor.bus.reads == [Reg::Ctrl7 as u8, Reg::Ctrl4Int1PadCtrl as u8]
+            && guard.int1_routes().int1_6d() == 1,
+    );
+    sensor.interrupts_restore(guard).unwrap();
+    check("invalid INT1 shadow: restored", pads(&sensor) == original);
+
+    // Embedded functions disabled: the enable bit stays cleared
+    let mut sensor = Iis2dlpc::from_bus(LogBus::default(), NoDelay);
+    sensor
+        .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_drdy(PROPERTY_ENABLE))
+        .unwrap();
+    let original = pads(&sensor);
+    let guard = sensor.interrupts_quiesce().unwrap();
+    sensor.interrupts_restore(guard).unwrap();
+    check(
+        "data-ready only: enable bit restored cleared",
+        !guard.interrupts_enabled() && pads(&sensor) == original,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// A sensor routing wake-up and data-ready on INT1, sleep change on INT2, with the user offsets
+/// on the outputs, and the bus log cleared.
+fn new_sensor() -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(LogBus::default(), NoDelay);
+    sensor
+        .pin_int1_route_set(
+            &Ctrl4Int1PadCtrl::new()
+                .with_int1_wu(PROPERTY_ENABLE)
+                .with_int1_drdy(PROPERTY_ENABLE),
+        )
+        .unwrap();
+    sensor
+        .pin_int2_route_set(&Ctrl5Int2PadCtrl::new().with_int2_sleep_chg(PROPERTY_ENABLE))
+        .unwrap();
+    let ctrl7 = Ctrl7::read(&mut sensor).unwrap();
+    ctrl7.with_usr_off_on_out(1).write(&mut sensor).unwrap();
+    sensor.bus.clear_log();
+    sensor
+}
+
+/// Content of `CTRL4_INT1_PAD_CTRL`, `CTRL5_INT2_PAD_CTRL` and `CTRL7`.
+fn pads(sensor: &Sensor) -> [u8; 3] {
+    PADS.map(|reg| sensor.bus.regs.reg(reg))
+}
+
+/// Mock bus logging the registers read and written.
+#[derive(Default)]
+struct LogBus {
+    regs: MockBus,
+    reads: Vec<u8>,
+    writes: Vec<(u8, u8)>,
+}
+
+impl LogBus {
+    fn clear_log(&mut self) {
+        self.reads.clear();
+        self.writes.clear();
+    }
+
+    fn written_regs(&self) -> Vec<u8> {
+        self.writes.iter().map(|(reg, _)| *reg).collect()
+    }
+}
+
+impl BusOperation for LogBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push((wbuf[0], wbuf[1]));
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.reads.push(wbuf[0]);
+        self.regs.write_byte_read_bytes(wbuf, rbuf)
+    }
+}
@@ -8,11 +8,11 @@ use embassy_stm32::i2c::{self, I2c};
 use embassy_stm32::time::khz;
 use embassy_stm32::usart::{self, BufferedInterruptHandler, DataBits, Parity, UartTx};
 use embassy_stm32::{bind_interrupts, peripherals, peripherals::USART2};
-use embassy_time::Delay;
-use embedded_hal::delay::DelayNs;
+use embassy_time::{Delay, Timer};
 use heapless::String;
-use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
-use iis2dlpc_rs::{PROPERTY_ENABLE, from_fs8_to_mg, prelude::*};
+use iis2dlpc_rs::I2CAddress;
+use iis2dlpc_rs::asynch::{I2cBusAsync, Iis2dlpcAsync};
+use iis2dlpc_rs::{from_fs8_to_mg, prelude::*};
 
 use {defmt_rtt as _, panic_probe as _};
 
@@ -49,14 +49,15 @@ async fn main(_spawner: Spawner) {
         Default::default(),
     );
 
-    let mut delay = Delay;
     let mut msg = String::<64>::new();
 
-    delay.delay_ms(10);
+    Timer::after_millis(10).await;
 
-    let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay.clone());
+    // The bus transactions are awaited: the executor runs other tasks meanwhile
+    let bus = I2cBusAsync::new(i2c, I2CAddress::I2cAddH as u8);
+    let mut sensor = Iis2dlpcAsync::from_bus(bus, Delay);
 
-    match sensor.device_id_get() {
+    match sensor.device_id_get().await {
         Ok(value) => {
             if value != iis2dlpc_rs::ID {
                 panic!("Invalid sensor ID")
@@ -68,34 +69,29 @@ async fn main(_spawner: Spawner) {
             msg.clear();
         }
     }
-    sensor.tim.delay_ms(25);
+    Timer::after_millis(25).await;
 
-    // Restore default configuration
-    sensor.reset_set().unwrap();
-    while sensor.reset_get().unwrap() == 1 {}
-
-    // Enable Block Data Update
-    sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+    // Restore default configuration, enable Block Data Update and the address auto-increment
+    sensor.init().await.unwrap();
     // Set Full scale
-    sensor.full_scale_set(Fs::_8g).unwrap();
-
-    // Configure filtering chain
-
-    // Accelerometer - filter path / bandwidth
-    sensor.filter_path_set(Fds::LpfOnOut).unwrap();
-    sensor.filter_bandwidth_set(BwFilt::OdrDiv4).unwrap();
+    sensor.full_scale_set(Fs::_8g).await.unwrap();
 
     // Configure power mode
     sensor
         .power_mode_set(Mode::ContLowPwrLowNoise12bit)
+        .await
         .unwrap();
     // Set Output Data Rate
-    sensor.data_rate_set(Odr::_25hz).unwrap();
+    sensor.data_rate_set(Odr::_25hz).await.unwrap();
 
     // Read samples in polling mode (no int)
     loop {
-        if sensor.flag_data_ready_get().unwrap() == 1 {
-            let acceleration_mg = sensor.acceleration_raw_get().unwrap().map(from_fs8_to_mg);
+        if sensor.status_reg_get().await.unwrap().drdy() == 1 {
+            let acceleration_mg = sensor
+                .acceleration_raw_get()
+                .await
+                .unwrap()
+                .map(from_fs8_to_mg);
 
             msg.clear();
             writeln!(
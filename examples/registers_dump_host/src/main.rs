@@ -0,0 +1,159 @@
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::phase::REG_PHASES;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::snapshot::{ConfigSnapshot, CONFIG_REGS};
+use iis2dlpc_rs::Iis2dlpc;
+use st_mems_bus::BusOperation;
+
+type Sensor = Iis2dlpc<LogBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Source device: every register holds a distinct value, FIFO in stream mode
+    let mut source = Sensor::from_bus(LogBus::default(), NoDelay);
+    for reg in Reg::ALL {
+        source.bus.regs.set_reg(reg, reg as u8 ^ 0xA5);
+    }
+    let ctrl2 = Ctrl2::new().with_if_add_inc(1).with_bdu(1).with_boot(1);
+    let ctrl3 = Ctrl3::new().with_lir(1).with_slp_mode(0x03);
+    source.bus.regs.set_reg(Reg::Ctrl2, ctrl2.into_bits());
+    source.bus.regs.set_reg(Reg::Ctrl3, ctrl3.into_bits());
+    source.bus.regs.set_reg(
+        Reg::FifoCtrl,
+        FifoCtrl::new()
+            .with_fmode(Fmode::StreamMode as u8)
+            .with_fth(12)
+            .into_bits(),
+    );
+    let registers = Reg::ALL.map(|reg| (reg, source.bus.regs.reg(reg)));
+
+    // Dump
+    let mut image = [0xEE; REG_MAP_LEN];
+    check(
+        "registers_dump: Ok",
+        source.registers_dump(&mut image).is_ok(),
+    );
+    check(
+        "registers_dump: every register at its address",
+        registers
+            .iter()
+            .all(|&(reg, val)| image[reg as usize] == val),
+    );
+    check(
+        "registers_dump: reserved addresses cleared",
+        (0..REG_MAP_LEN)
+            .filter(|addr| Reg::ALL.iter().all(|reg| *reg as usize != *addr))
+            .all(|addr| image[addr] == 0),
+    );
+    check(
+        "registers_dump: three bursts, split at OUT_Z_H",
+        source.bus.reads
+            == [
+                (Reg::OutTL as u8, 3),
+                (Reg::Ctrl1 as u8, 14),
+                (Reg::FifoCtrl as u8, 18),
+            ],
+    );
+    check(
+        "registers_dump: nothing written",
+        source.bus.writes.is_empty(),
+    );
+    check(
+        "ConfigSnapshot::from_image: the configuration registers",
+        Ok(ConfigSnapshot::from_image(&image)) == source.config_snapshot_get(),
+    );
+
+    // Restore on a device whose read-only registers hold a sentinel
+    let mut target = Sensor::from_bus(LogBus::default(), NoDelay);
+    let read_only: Vec<Reg> = Reg::ALL
+        .into_iter()
+        .filter(|reg| !CONFIG_REGS.contains(reg))
+        .collect();
+    for reg in &read_only {
+        target.bus.regs.set_reg(*reg, 0x5A);
+    }
+    check(
+        "registers_restore: Ok",
+        target.registers_restore(&image).is_ok(),
+    );
+    let order: Vec<u8> = REG_PHASES.iter().map(|(reg, _)| *reg as u8).collect();
+    check(
+        "registers_restore: configuration registers only, CTRL2 first, CTRL1 last",
+        target.bus.writes.iter().map(|(addr, _)| *addr).eq(order)
+            && target.bus.writes.first().map(|w| w.0) == Some(Reg::Ctrl2 as u8)
+            && target.bus.writes.last().map(|w| w.0) == Some(Reg::Ctrl1 as u8),
+    );
+    check(
+        "registers_restore: read-only registers never written",
+        target
+            .bus
+            .writes
+            .iter()
+            .all(|(addr, _)| read_only.iter().all(|reg| *reg as u8 != *addr))
+            && read_only
+                .iter()
+                .all(|reg| target.bus.regs.reg(*reg) == 0x5A),
+    );
+    check(
+        "registers_restore: BOOT and SLP_MODE_1 not written back",
+        target.bus.regs.reg(Reg::Ctrl2) == ctrl2.with_boot(0).into_bits()
+            && target.bus.regs.reg(Reg::Ctrl3) == ctrl3.with_slp_mode(0x02).into_bits(),
+    );
+    check(
+        "registers_restore: other configuration registers as dumped",
+        CONFIG_REGS
+            .iter()
+            .filter(|reg| !matches!(reg, Reg::Ctrl2 | Reg::Ctrl3))
+            .all(|reg| target.bus.regs.reg(*reg) == image[*reg as usize]),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Register map logging the register accesses.
+#[derive(Default)]
+struct LogBus {
+    regs: MockBus,
+    /// Start address and length of every read.
+    reads: Vec<(u8, usize)>,
+    /// Start address and data of every write.
+    writes: Vec<(u8, Vec<u8>)>,
+}
+
+impl BusOperation for LogBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        if let Some((addr, data)) = wbuf.split_first() {
+            self.writes.push((*addr, data.to_vec()));
+        }
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.reads.push((wbuf[0], rbuf.len()));
+        self.regs.write_byte_read_bytes(wbuf, rbuf)
+    }
+}
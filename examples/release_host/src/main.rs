@@ -0,0 +1,121 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Error, Iis2dlpc};
+use st_mems_bus::BusOperation;
+
+/// Configuration registers other than `CTRL1`.
+const OTHER_REGS: [Reg; 7] = [
+    Reg::Ctrl2,
+    Reg::Ctrl3,
+    Reg::Ctrl4Int1PadCtrl,
+    Reg::Ctrl5Int2PadCtrl,
+    Reg::Ctrl6,
+    Reg::Ctrl7,
+    Reg::FifoCtrl,
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Power-down
+    let sensor = new_sensor(FailBus::default());
+    let other = OTHER_REGS.map(|reg| sensor.bus.regs.reg(reg));
+    let mode = Ctrl1::from_bits(sensor.bus.regs.reg(Reg::Ctrl1)).mode();
+    let (bus, tim, powered_down) = sensor.release();
+    let ctrl1 = Ctrl1::from_bits(bus.regs.reg(Reg::Ctrl1));
+    check(
+        "release: Ok, data rate off, mode kept",
+        powered_down.is_ok() && ctrl1.odr() == 0 && ctrl1.mode() == mode,
+    );
+    check(
+        "release: no other register written",
+        OTHER_REGS.map(|reg| bus.regs.reg(reg)) == other,
+    );
+    let mut sensor = Iis2dlpc::from_bus(bus, tim);
+    check(
+        "new driver on the bus: powered down, full scale kept",
+        sensor.data_rate_get() == Ok(Odr::Off) && sensor.full_scale_get() == Ok(Fs::_8g),
+    );
+
+    // Without power-down
+    let sensor = new_sensor(FailBus::default());
+    let ctrl1 = sensor.bus.regs.reg(Reg::Ctrl1);
+    let before = sensor.bus.regs.transactions();
+    let (bus, _) = sensor.release_without_powerdown();
+    check(
+        "release_without_powerdown: no transaction, still converting",
+        bus.regs.transactions() == before && bus.regs.reg(Reg::Ctrl1) == ctrl1,
+    );
+
+    // Bus error during the power-down
+    let mut sensor = new_sensor(FailBus::default());
+    sensor.bus.fail_writes = true;
+    let (mut bus, _, powered_down) = sensor.release();
+    check(
+        "bus error: bus given back with the error",
+        powered_down == Err(Error::Bus(BusFault)),
+    );
+    bus.fail_writes = false;
+    let mut sensor = Iis2dlpc::from_bus(bus, NoDelay);
+    check(
+        "bus error: still at 100 Hz, power-down on a new driver",
+        sensor.data_rate_get() == Ok(Odr::_100hz) && sensor.release().2.is_ok(),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn new_sensor(bus: FailBus) -> Iis2dlpc<FailBus, NoDelay> {
+    let mut sensor = Iis2dlpc::from_bus(bus, NoDelay);
+    sensor.full_scale_set(Fs::_8g).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct BusFault;
+
+/// Register map whose writes fail on demand.
+#[derive(Default)]
+struct FailBus {
+    regs: MockBus,
+    fail_writes: bool,
+}
+
+impl BusOperation for FailBus {
+    type Error = BusFault;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf).map_err(|never| match never {})
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        if self.fail_writes {
+            return Err(BusFault);
+        }
+        self.regs.write_bytes(wbuf).map_err(|never| match never {})
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.regs
+            .write_byte_read_bytes(wbuf, rbuf)
+            .map_err(|never| match never {})
+    }
+}
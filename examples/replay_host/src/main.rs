@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::process::ExitCode;
+
+use iis2dlpc_rs::dsp::{AxisFilter, AxisStats, GoertzelBank, ALPHA_ONE};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::replay::{replay_fifo_bytes, SampleSink};
+use iis2dlpc_rs::Iis2dlpc;
+use st_mems_bus::BusOperation;
+
+/// 256 samples recorded at 100 Hz: device lying flat, 10 Hz vibration on X, 25 Hz on Z.
+const RECORDING: &[u8] = include_bytes!("../recording.bin");
+
+/// Samples collected by the FIFO between two drains, in turn.
+const COLLECTED: [usize; 5] = [20, 7, 32, 1, 13];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+
+    for (fs, mode) in [
+        (Fs::_4g, Mode::HighPerformance),
+        (Fs::_8g, Mode::ContLowPwr12bit),
+    ] {
+        let offline = replay(fs, mode);
+        let production = drain(fs, mode);
+        let ok = offline == production;
+        println!(
+            "{} {fs:?} {mode:?}: {} samples, mean {:?} mg, 10 Hz on X {:?} mg², 25 Hz on Z {:?} mg²",
+            if ok { "PASS" } else { "FAIL" },
+            offline.stats.count(),
+            offline.stats.mean().unwrap_or_default(),
+            offline.x_bank.energies(),
+            offline.z_bank.energies(),
+        );
+        if !ok {
+            println!("  production: {production:?}");
+            failures += 1;
+        }
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Every sink used in tuning, fed by either path.
+#[derive(PartialEq, Debug)]
+struct Sinks {
+    stats: AxisStats,
+    filter: AxisFilter,
+    x_bank: GoertzelBank<1>,
+    z_bank: GoertzelBank<1>,
+}
+
+impl Sinks {
+    fn new() -> Self {
+        Self {
+            stats: AxisStats::new(),
+            filter: AxisFilter::new([ALPHA_ONE / 8; 3]),
+            x_bank: GoertzelBank::new(&[(10.0, 5.0)], 100.0).unwrap(),
+            z_bank: GoertzelBank::new(&[(25.0, 5.0)], 100.0).unwrap(),
+        }
+    }
+
+    fn with<R>(&mut self, f: impl FnOnce(&mut [&mut dyn SampleSink]) -> R) -> R {
+        f(&mut [
+            &mut self.stats,
+            &mut self.filter,
+            &mut self.x_bank.on_axis(Axis::X),
+            &mut self.z_bank.on_axis(Axis::Z),
+        ])
+    }
+}
+
+/// Host side: the recording replayed without a sensor.
+fn replay(fs: Fs, mode: Mode) -> Sinks {
+    let mut sinks = Sinks::new();
+    let fed = sinks.with(|s| replay_fifo_bytes(RECORDING, fs, mode, s)).unwrap();
+    assert_eq!(fed, RECORDING.len() / 6);
+    sinks
+}
+
+/// Production side: the recording collected by a simulated FIFO and drained by the driver.
+fn drain(fs: Fs, mode: Mode) -> Sinks {
+    let mut sensor = Iis2dlpc::from_bus(FifoBus::default(), NoDelay);
+    sensor.full_scale_set(fs).unwrap();
+    sensor.power_mode_set(mode).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+
+    let mut sinks = Sinks::new();
+    let mut recording = RECORDING.chunks_exact(6);
+    for collected in COLLECTED.iter().cycle() {
+        sensor.bus.fifo.extend(
+            recording
+                .by_ref()
+                .take(*collected)
+                .map(|s| <[u8; 6]>::try_from(s).unwrap()),
+        );
+        if sensor.bus.fifo.is_empty() {
+            break;
+        }
+        sinks.with(|s| sensor.fifo_drain_into(s)).unwrap();
+    }
+    sinks
+}
+
+/// Mock bus whose FIFO holds queued samples: `FIFO_SAMPLES` reports them and a burst read from
+/// `OUT_X_L` pops them.
+#[derive(Default)]
+struct FifoBus {
+    regs: MockBus,
+    fifo: VecDeque<[u8; 6]>,
+}
+
+impl BusOperation for FifoBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.regs.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.regs.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        match wbuf[0] {
+            // `diff` in bits 5..0, no overrun
+            reg if reg == Reg::FifoSamples as u8 => rbuf.fill(self.fifo.len().min(32) as u8),
+            reg if reg == Reg::OutXL as u8 => {
+                for sample in rbuf.chunks_exact_mut(6) {
+                    sample.copy_from_slice(&self.fifo.pop_front().unwrap());
+                }
+            }
+            _ => return self.regs.write_byte_read_bytes(wbuf, rbuf),
+        }
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@
 
 use core::{fmt::Write, ops::RangeInclusive};
 
-use iis2dlpc_rs::{from_fs4_to_mg, prelude::*, PROPERTY_ENABLE};
+use iis2dlpc_rs::prelude::*;
 use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
 
 use panic_itm as _;
@@ -77,7 +77,7 @@ fn main() -> ! {
         sensor.reset_set().unwrap();
         while sensor.reset_get().unwrap() == 1 {}
 
-        sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+        sensor.block_data_update_set(true).unwrap();
         sensor.full_scale_set(Fs::_4g).unwrap();
         sensor.power_mode_set(Mode::HighPerformance).unwrap();
         sensor.data_rate_set(Odr::_50hz).unwrap();
@@ -91,7 +91,7 @@ fn main() -> ! {
         loop {
             let status = sensor.status_reg_get().unwrap();
             if status.drdy() == 1 {
-                let acceleration_mg = sensor.acceleration_raw_get().unwrap().map(from_fs4_to_mg);
+                let acceleration_mg = sensor.acceleration_mg_get().unwrap();
 
                 (0..3).for_each(|i| media[i] += acceleration_mg[i]);
                 i += 1;
@@ -112,7 +112,7 @@ fn main() -> ! {
         loop {
             let status = sensor.status_reg_get().unwrap();
             if status.drdy() == 1 {
-                let acceleration_mg = sensor.acceleration_raw_get().unwrap().map(from_fs4_to_mg);
+                let acceleration_mg = sensor.acceleration_mg_get().unwrap();
 
                 (0..3).for_each(|i| media_st[i] += acceleration_mg[i]);
                 i += 1;
@@ -12,7 +12,7 @@ use embassy_stm32::{bind_interrupts, peripherals, peripherals::USART2};
 use embassy_time::Delay;
 use embedded_hal::delay::DelayNs;
 use heapless::String;
-use iis2dlpc_rs::{I2CAddress, Iis2dlpc, PROPERTY_ENABLE, from_fs4_to_mg, prelude::*};
+use iis2dlpc_rs::{I2CAddress, Iis2dlpc, prelude::*};
 
 use st_mems_bus::BusOperation;
 
@@ -80,7 +80,7 @@ async fn main(_spawner: Spawner) {
         sensor.reset_set().unwrap();
         while sensor.reset_get().unwrap() == 1 {}
 
-        sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+        sensor.block_data_update_set(true).unwrap();
         sensor.full_scale_set(Fs::_4g).unwrap();
         sensor.power_mode_set(Mode::HighPerformance).unwrap();
         sensor.data_rate_set(Odr::_50hz).unwrap();
@@ -94,7 +94,7 @@ async fn main(_spawner: Spawner) {
         loop {
             let status = sensor.status_reg_get().unwrap();
             if status.drdy() == 1 {
-                let acceleration_mg = sensor.acceleration_raw_get().unwrap().map(from_fs4_to_mg);
+                let acceleration_mg = sensor.acceleration_mg_get().unwrap();
 
                 (0..3).for_each(|j| media[j] += acceleration_mg[j]);
 
@@ -117,7 +117,7 @@ async fn main(_spawner: Spawner) {
         loop {
             let status = sensor.status_reg_get().unwrap();
             if status.drdy() == 1 {
-                let acceleration_mg = sensor.acceleration_raw_get().unwrap().map(from_fs4_to_mg);
+                let acceleration_mg = sensor.acceleration_mg_get().unwrap();
 
                 (0..3).for_each(|j| media_st[j] += acceleration_mg[j]);
                 i += 1;
@@ -0,0 +1,141 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{
+    from_fs16_lp1_to_mg, from_fs16_to_mg, from_fs2_lp1_to_mg, from_fs2_to_mg, from_fs4_lp1_to_mg,
+    from_fs4_to_mg, from_fs8_lp1_to_mg, from_fs8_to_mg, sensitivity_mg_per_lsb, Iis2dlpc,
+};
+
+const FULL_SCALES: [Fs; 4] = [Fs::_2g, Fs::_4g, Fs::_8g, Fs::_16g];
+
+/// The modes with a 12-bit resolution, low-power mode 1.
+const LOW_POWER_1: [Mode; 4] = [
+    Mode::ContLowPwr12bit,
+    Mode::SingleLowPwr12bit,
+    Mode::ContLowPwrLowNoise12bit,
+    Mode::SingleLowLowNoisePwr12bit,
+];
+
+/// A 14-bit sample whose two least significant bits are set, as the outputs of a noisy device.
+const SAMPLE: [i16; 3] = [1001, -2003, 8191];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Every mode code that decodes
+    let modes: Vec<Mode> = (0..=0x1F)
+        .filter_map(|code| Mode::try_from(code).ok())
+        .collect();
+    check("18 operating modes", modes.len() == 18);
+
+    let mut helper_mismatches = Vec::new();
+    let mut sensitivity_mismatches = Vec::new();
+    for fs in FULL_SCALES {
+        for mode in modes.iter().copied() {
+            let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+            sensor.full_scale_set(fs).unwrap();
+            sensor.power_mode_set(mode).unwrap();
+            for (k, val) in SAMPLE.iter().enumerate() {
+                let [low, high] = (val << 2).to_le_bytes();
+                sensor.bus.set_reg(OUT_REGS[2 * k], low);
+                sensor.bus.set_reg(OUT_REGS[2 * k + 1], high);
+            }
+
+            let mg = sensor.acceleration_mg_get().unwrap();
+            let raw = sensor.acceleration_raw_get().unwrap();
+            let low_power_1 = LOW_POWER_1.contains(&mode);
+            if mg != raw.map(helper(fs, low_power_1)) {
+                helper_mismatches.push((fs, mode));
+            }
+            let shift = if low_power_1 { 2 } else { 0 };
+            let sensitivity = sensitivity_mg_per_lsb(fs, mode);
+            if mg != raw.map(|lsb| (lsb >> shift) as f32 * sensitivity) {
+                sensitivity_mismatches.push((fs, mode));
+            }
+        }
+    }
+    if !helper_mismatches.is_empty() {
+        println!("{helper_mismatches:?}");
+    }
+    check(
+        "every Fs x mode: acceleration_mg_get matches raw data and the conversion helper",
+        helper_mismatches.is_empty(),
+    );
+    if !sensitivity_mismatches.is_empty() {
+        println!("{sensitivity_mismatches:?}");
+    }
+    check(
+        "every Fs x mode: acceleration_mg_get matches raw data and sensitivity_mg_per_lsb",
+        sensitivity_mismatches.is_empty(),
+    );
+
+    // The datasheet sensitivities
+    let expected = [
+        (Fs::_2g, 0.244, 0.976),
+        (Fs::_4g, 0.488, 1.952),
+        (Fs::_8g, 0.976, 3.904),
+        (Fs::_16g, 1.952, 7.808),
+    ];
+    check(
+        "sensitivity_mg_per_lsb: datasheet values, 14-bit and 12-bit",
+        expected.iter().all(|&(fs, high, low_power_1)| {
+            sensitivity_mg_per_lsb(fs, Mode::HighPerformance) == high
+                && sensitivity_mg_per_lsb(fs, Mode::ContLowPwr12bit) == low_power_1
+        }),
+    );
+
+    // 1 g at ±2g in high-performance mode
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    let [low, high] = (4098_i16 << 2).to_le_bytes();
+    sensor.bus.set_reg(Reg::OutZL, low);
+    sensor.bus.set_reg(Reg::OutZH, high);
+    let mg = sensor.acceleration_mg_get().unwrap();
+    check(
+        "1 g on Z at ±2g: about 1000 mg",
+        (mg[2] - 1000.0).abs() < 1.0,
+    );
+    // The same output in low-power mode 1 is not 4 times smaller
+    sensor.power_mode_set(Mode::ContLowPwr12bit).unwrap();
+    let mg = sensor.acceleration_mg_get().unwrap();
+    check(
+        "1 g on Z at ±2g in low-power mode 1: about 1000 mg",
+        (mg[2] - 1000.0).abs() < 1.0,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+const OUT_REGS: [Reg; 6] = [
+    Reg::OutXL,
+    Reg::OutXH,
+    Reg::OutYL,
+    Reg::OutYH,
+    Reg::OutZL,
+    Reg::OutZH,
+];
+
+/// The conversion helper for a full scale and a resolution, on the 14-bit raw values.
+fn helper(fs: Fs, low_power_1: bool) -> impl Fn(i16) -> f32 {
+    move |lsb| match (fs, low_power_1) {
+        (Fs::_2g, false) => from_fs2_to_mg(lsb),
+        (Fs::_4g, false) => from_fs4_to_mg(lsb),
+        (Fs::_8g, false) => from_fs8_to_mg(lsb),
+        (Fs::_16g, false) => from_fs16_to_mg(lsb),
+        (Fs::_2g, true) => from_fs2_lp1_to_mg(lsb >> 2),
+        (Fs::_4g, true) => from_fs4_lp1_to_mg(lsb >> 2),
+        (Fs::_8g, true) => from_fs8_lp1_to_mg(lsb >> 2),
+        (Fs::_16g, true) => from_fs16_lp1_to_mg(lsb >> 2),
+    }
+}
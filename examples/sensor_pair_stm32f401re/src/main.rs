@@ -0,0 +1,135 @@
+#![no_main]
+#![no_std]
+
+use core::cell::RefCell;
+use core::fmt::Write;
+
+use embedded_hal_bus::i2c::RefCellDevice;
+use iis2dlpc_rs::pair::SensorPair;
+use iis2dlpc_rs::{from_fs2_to_mg, prelude::*};
+use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
+
+use panic_itm as _;
+
+use cortex_m_rt::entry;
+use stm32f4xx_hal::{
+    hal::delay::DelayNs,
+    i2c::{DutyCycle, I2c, Mode as I2cMode},
+    pac,
+    prelude::*,
+    serial::Config,
+};
+
+const SYSCLK_HZ: u32 = 48_000_000;
+
+// Busy-wait delay: the two sensors and the pair each own one.
+#[derive(Clone, Copy)]
+struct CycleDelay;
+
+impl DelayNs for CycleDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        let cycles = (ns as u64 * SYSCLK_HZ as u64).div_ceil(1_000_000_000);
+        cortex_m::asm::delay(cycles as u32);
+    }
+}
+
+#[entry]
+fn main() -> ! {
+    let dp = pac::Peripherals::take().unwrap();
+
+    let rcc = dp.RCC.constrain();
+    let clocks = rcc.cfgr.use_hse(8.MHz()).sysclk(SYSCLK_HZ.Hz()).freeze();
+
+    let gpiob = dp.GPIOB.split();
+    let gpioa = dp.GPIOA.split();
+
+    let scl = gpiob.pb8;
+    let sda = gpiob.pb9;
+
+    let i2c = I2c::new(
+        dp.I2C1,
+        (scl, sda),
+        I2cMode::Fast {
+            frequency: 400.kHz(),
+            duty_cycle: DutyCycle::Ratio2to1,
+        },
+        &clocks,
+    );
+    let i2c = RefCell::new(i2c);
+
+    let tx_pin = gpioa.pa2.into_alternate();
+    let mut tx = dp
+        .USART2
+        .tx(
+            tx_pin,
+            Config::default()
+                .baudrate(115200.bps())
+                .wordlength_8()
+                .parity_none(),
+            &clocks,
+        )
+        .unwrap();
+
+    // Both sensors share I2C1: the machine sensor has SA0 low, the frame sensor has SA0 high
+    let mut machine = Iis2dlpc::new_i2c(RefCellDevice::new(&i2c), I2CAddress::I2cAddL, CycleDelay);
+    let mut frame = Iis2dlpc::new_i2c(RefCellDevice::new(&i2c), I2CAddress::I2cAddH, CycleDelay);
+
+    for (name, id) in [
+        ("machine", machine.device_id_get()),
+        ("frame", frame.device_id_get()),
+    ] {
+        match id {
+            Ok(value) => {
+                if value != iis2dlpc_rs::ID {
+                    panic!("Invalid {name} sensor ID")
+                }
+            }
+            Err(e) => {
+                writeln!(tx, "An error occured while reading {name} sensor ID: {e:?}").unwrap()
+            }
+        }
+    }
+    machine.tim.delay_ms(25);
+
+    // Restore default configuration
+    machine.reset_set().unwrap();
+    while machine.reset_get().unwrap() == 1 {}
+    frame.reset_set().unwrap();
+    while frame.reset_get().unwrap() == 1 {}
+
+    // Configure the first sensor for single data conversion on demand
+    machine.block_data_update_set(true).unwrap();
+    machine.full_scale_set(Fs::_2g).unwrap();
+    machine.filter_path_set(Fds::LpfOnOut).unwrap();
+    machine.filter_bandwidth_set(BwFilt::OdrDiv4).unwrap();
+    machine.power_mode_set(Mode::SingleLowPwr4).unwrap();
+    machine.data_rate_set(Odr::SetSwTrig).unwrap();
+
+    // Copy the configuration to both sensors
+    let config = machine.config_snapshot_get().unwrap();
+    // About 70 us: one register write at 400 kHz separates the two triggers
+    let mut pair = SensorPair::new(machine, frame, CycleDelay, 70);
+    pair.sync_configure(&config).unwrap();
+
+    // Read paired samples and print the difference between the two sensors
+    loop {
+        match pair.sample_pair_get() {
+            Ok(sample) => {
+                let machine_mg = sample.a.map(from_fs2_to_mg);
+                let frame_mg = sample.b.map(from_fs2_to_mg);
+
+                writeln!(
+                    tx,
+                    "Differential [mg]: {:4.2}\t{:4.2}\t{:4.2}\tskew [us]: {}",
+                    machine_mg[0] - frame_mg[0],
+                    machine_mg[1] - frame_mg[1],
+                    machine_mg[2] - frame_mg[2],
+                    sample.skew_us
+                )
+                .unwrap();
+            }
+            Err(e) => writeln!(tx, "Paired acquisition failed: {e:?}").unwrap(),
+        }
+        pair.tim.delay_ms(10);
+    }
+}
@@ -0,0 +1,216 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::summary::SensorState;
+use iis2dlpc_rs::timing::{FfDuration, FreeFallTiming};
+use iis2dlpc_rs::Iis2dlpc;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let mut reset = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    let default = reset.sensor_state_get().unwrap();
+
+    let mut sensor = Iis2dlpc::from_bus(image(), NoDelay);
+    sensor.bus.cost_reset();
+    let state = sensor.sensor_state_get().unwrap();
+    println!("{state}");
+
+    check("three transactions", sensor.bus.transactions() == 3);
+    check("decode of the image", state == expected());
+    check(
+        "every field differs from the reset state",
+        differing_fields(&state, &default) == FIELDS,
+    );
+
+    let ok = [
+        state.mode == sensor.power_mode_get().unwrap(),
+        state.odr == sensor.data_rate_get().unwrap(),
+        state.fs == sensor.full_scale_get().unwrap(),
+        state.bdu == sensor.block_data_update_get().unwrap(),
+        state.filter_path == sensor.filter_path_get().unwrap(),
+        state.bandwidth == sensor.filter_bandwidth_get().unwrap(),
+        state.fifo_mode == sensor.fifo_mode_get().unwrap(),
+        state.fifo_watermark == sensor.fifo_watermark_get().unwrap(),
+        state.wkup_threshold == sensor.wkup_threshold_get().unwrap(),
+        state.wkup_dur == sensor.wkup_dur_get().unwrap(),
+        state.sleep_dur == sensor.act_sleep_dur_get().unwrap(),
+        state.act_mode == sensor.act_mode_get().unwrap(),
+        state.free_fall == sensor.free_fall_timing_get().unwrap(),
+        state.tap_axes
+            == [
+                sensor.tap_detection_on_x_get().unwrap(),
+                sensor.tap_detection_on_y_get().unwrap(),
+                sensor.tap_detection_on_z_get().unwrap(),
+            ],
+        state.tap_mode == sensor.tap_mode_get().unwrap(),
+        state.int1_routes == sensor.pin_int1_route_get().unwrap(),
+        state.int2_routes == sensor.pin_int2_route_get().unwrap(),
+        state.polarity == sensor.pin_polarity_get().unwrap(),
+        state.latch == sensor.int_notification_get().unwrap(),
+        state.pin_mode == sensor.pin_mode_get().unwrap(),
+    ];
+    check("same as the individual getters", ok.iter().all(|ok| *ok));
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Number of fields of `SensorState`.
+const FIELDS: usize = 22;
+
+/// Register image with a non-default value in every field of the summary.
+fn image() -> MockBus {
+    let mut bus = MockBus::new();
+    // Low-power mode 3, low noise
+    bus.set_reg(
+        Reg::Ctrl1,
+        Ctrl1::new()
+            .with_odr(0x05)
+            .with_mode(0)
+            .with_lp_mode(2)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::Ctrl2,
+        Ctrl2::new().with_if_add_inc(1).with_bdu(1).into_bits(),
+    );
+    bus.set_reg(
+        Reg::Ctrl3,
+        Ctrl3::new()
+            .with_h_lactive(1)
+            .with_lir(1)
+            .with_pp_od(1)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::Ctrl4Int1PadCtrl,
+        Ctrl4Int1PadCtrl::new()
+            .with_int1_wu(1)
+            .with_int1_fth(1)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::Ctrl5Int2PadCtrl,
+        Ctrl5Int2PadCtrl::new()
+            .with_int2_drdy(1)
+            .with_int2_sleep_chg(1)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::Ctrl6,
+        Ctrl6::new()
+            .with_bw_filt(2)
+            .with_fs(2)
+            .with_fds(1)
+            .with_low_noise(1)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::Ctrl7,
+        Ctrl7::new().with_interrupts_enable(1).into_bits(),
+    );
+    bus.set_reg(
+        Reg::FifoCtrl,
+        FifoCtrl::new().with_fmode(6).with_fth(12).into_bits(),
+    );
+    bus.set_reg(
+        Reg::TapThsZ,
+        TapThsZ::new().with_tap_x_en(1).with_tap_z_en(1).into_bits(),
+    );
+    bus.set_reg(
+        Reg::WakeUpThs,
+        WakeUpThs::new()
+            .with_wk_ths(9)
+            .with_sleep_on(1)
+            .with_single_double_tap(1)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::WakeUpDur,
+        WakeUpDur::new()
+            .with_sleep_dur(3)
+            .with_wake_dur(2)
+            .with_ff_dur(1)
+            .into_bits(),
+    );
+    bus.set_reg(
+        Reg::FreeFall,
+        FreeFall::new().with_ff_ths(5).with_ff_dur(4).into_bits(),
+    );
+    bus
+}
+
+/// Decode of [`image`], written out by hand.
+fn expected() -> SensorState {
+    SensorState {
+        mode: Mode::ContLowPwrLowNoise3,
+        odr: Odr::_100hz,
+        fs: Fs::_8g,
+        bdu: true,
+        filter_path: Fds::HighPassOnOut,
+        bandwidth: BwFilt::OdrDiv10,
+        low_noise: true,
+        fifo_mode: Fmode::StreamMode,
+        fifo_watermark: 12,
+        wkup_threshold: 9,
+        wkup_dur: 2,
+        sleep_dur: 3,
+        act_mode: SleepOn::ActInact,
+        free_fall: FreeFallTiming {
+            duration: FfDuration::from_lsb(36).unwrap().with_odr(Odr::_100hz),
+            threshold: FfThs::try_from(5).unwrap(),
+        },
+        tap_axes: [true, false, true],
+        tap_mode: SingleDoubleTap::BothSingleDouble,
+        int1_routes: Ctrl4Int1PadCtrl::new().with_int1_wu(1).with_int1_fth(1),
+        int2_routes: Ctrl5Int2PadCtrl::new()
+            .with_int2_drdy(1)
+            .with_int2_sleep_chg(1),
+        interrupts_enable: true,
+        polarity: HLactive::ActiveLow,
+        latch: Lir::Latched,
+        pin_mode: PpOd::OpenDrain,
+    }
+}
+
+/// Count the fields of `a` that differ from `b`.
+fn differing_fields(a: &SensorState, b: &SensorState) -> usize {
+    [
+        a.mode != b.mode,
+        a.odr != b.odr,
+        a.fs != b.fs,
+        a.bdu != b.bdu,
+        a.filter_path != b.filter_path,
+        a.bandwidth != b.bandwidth,
+        a.low_noise != b.low_noise,
+        a.fifo_mode != b.fifo_mode,
+        a.fifo_watermark != b.fifo_watermark,
+        a.wkup_threshold != b.wkup_threshold,
+        a.wkup_dur != b.wkup_dur,
+        a.sleep_dur != b.sleep_dur,
+        a.act_mode != b.act_mode,
+        a.free_fall != b.free_fall,
+        a.tap_axes != b.tap_axes,
+        a.tap_mode != b.tap_mode,
+        a.int1_routes != b.int1_routes,
+        a.int2_routes != b.int2_routes,
+        a.interrupts_enable != b.interrupts_enable,
+        a.polarity != b.polarity,
+        a.latch != b.latch,
+        a.pin_mode != b.pin_mode,
+    ]
+    .iter()
+    .filter(|differs| **differs)
+    .count()
+}
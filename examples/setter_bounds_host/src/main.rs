@@ -0,0 +1,151 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+type Result<T> = core::result::Result<T, Error<core::convert::Infallible>>;
+
+/// Setter of a register field, its getter and the highest code of the field.
+struct Field {
+    name: &'static str,
+    max: u8,
+    set: fn(&mut Sensor, u8) -> Result<()>,
+    get: fn(&mut Sensor) -> Result<u8>,
+}
+
+const FIELDS: [Field; 12] = [
+    Field {
+        name: "fifo_watermark_set",
+        max: 31,
+        set: Sensor::fifo_watermark_set,
+        get: Sensor::fifo_watermark_get,
+    },
+    Field {
+        name: "wkup_threshold_set",
+        max: 63,
+        set: Sensor::wkup_threshold_set,
+        get: Sensor::wkup_threshold_get,
+    },
+    Field {
+        name: "wkup_dur_set",
+        max: 3,
+        set: Sensor::wkup_dur_set,
+        get: Sensor::wkup_dur_get,
+    },
+    Field {
+        name: "act_sleep_dur_set",
+        max: 15,
+        set: Sensor::act_sleep_dur_set,
+        get: Sensor::act_sleep_dur_get,
+    },
+    Field {
+        name: "tap_threshold_x_set",
+        max: 31,
+        set: Sensor::tap_threshold_x_set,
+        get: Sensor::tap_threshold_x_get,
+    },
+    Field {
+        name: "tap_threshold_y_set",
+        max: 31,
+        set: Sensor::tap_threshold_y_set,
+        get: Sensor::tap_threshold_y_get,
+    },
+    Field {
+        name: "tap_threshold_z_set",
+        max: 31,
+        set: Sensor::tap_threshold_z_set,
+        get: Sensor::tap_threshold_z_get,
+    },
+    Field {
+        name: "tap_dur_set",
+        max: 15,
+        set: Sensor::tap_dur_set,
+        get: Sensor::tap_dur_get,
+    },
+    Field {
+        name: "tap_quiet_set",
+        max: 3,
+        set: Sensor::tap_quiet_set,
+        get: Sensor::tap_quiet_get,
+    },
+    Field {
+        name: "tap_shock_set",
+        max: 3,
+        set: Sensor::tap_shock_set,
+        get: Sensor::tap_shock_get,
+    },
+    Field {
+        name: "sixd_threshold_set",
+        max: 3,
+        set: Sensor::sixd_threshold_set,
+        get: Sensor::sixd_threshold_get,
+    },
+    Field {
+        name: "ff_dur_set",
+        max: 63,
+        set: Sensor::ff_dur_set,
+        get: Sensor::ff_dur_get,
+    },
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Highest code, then one past it, then 0xFF
+    for field in &FIELDS {
+        let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+        let max = field.max;
+        let valid = (field.set)(&mut sensor, max).is_ok() && (field.get)(&mut sensor) == Ok(max);
+        sensor.bus.expect(&[]);
+        let rejected = [max + 1, u8::MAX]
+            .iter()
+            .all(|&val| (field.set)(&mut sensor, val) == Err(Error::InvalidArgument));
+        check(
+            &format!(
+                "{}: {max} set, {} and 255 invalid, nothing accessed",
+                field.name,
+                max + 1
+            ),
+            valid && rejected && sensor.bus.expectations_check() == Ok(()),
+        );
+    }
+
+    // Arguments that are not a range
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    let before = sensor.config_snapshot_get();
+    check(
+        "wkup_threshold_mg_set(NaN): invalid argument",
+        sensor.wkup_threshold_mg_set(f32::NAN) == Err(Error::InvalidArgument),
+    );
+    check(
+        "sixd_threshold_degrees_set(65): invalid argument",
+        sensor.sixd_threshold_degrees_set(65) == Err(Error::InvalidArgument),
+    );
+    check(
+        "invalid arguments: nothing written",
+        sensor.config_snapshot_get() == before,
+    );
+    check(
+        "odr_calibration_set: 0 Hz and NaN invalid, 30 % off out of range",
+        sensor.odr_calibration_set(0.0, Odr::_100hz) == Err(Error::InvalidArgument)
+            && sensor.odr_calibration_set(f32::NAN, Odr::_100hz) == Err(Error::InvalidArgument)
+            && matches!(
+                sensor.odr_calibration_set(130.0, Odr::_100hz),
+                Err(Error::OutOfRange { .. })
+            ),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,163 @@
+use std::cell::Cell;
+use std::process::ExitCode;
+
+use embedded_hal::delay::DelayNs;
+use iis2dlpc_rs::errata::Workarounds;
+use iis2dlpc_rs::mock::MockBus;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::settle::{filter_settling_samples, SettleHint, FILTER_SETTLING_SAMPLES};
+use iis2dlpc_rs::Iis2dlpc;
+
+/// Data rates the hints are checked at: the slowest, a middle and the fastest high-performance one.
+const ODRS: [Odr; 3] = [Odr::_12_5hz, Odr::_100hz, Odr::_1_6khz];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // The settling table is the filter model rounded up to whole samples
+    for (bw, samples) in FILTER_SETTLING_SAMPLES {
+        let exact = bw.settling_ms(1000.0);
+        check(
+            &format!("{bw:?} settles in {samples} samples ({exact:.2} periods)"),
+            samples as f32 >= exact && exact > (samples - 1) as f32,
+        );
+    }
+
+    for odr in ODRS {
+        let period = odr.period_us().unwrap_or_default();
+        let spun = Cell::new(0);
+        let mut sensor = Iis2dlpc::from_bus(MockBus::new(), SpinClock { spun: &spun });
+        sensor.workarounds_set(Workarounds::NONE);
+
+        // Device off: nothing to settle
+        let off = [
+            sensor.power_mode_set(Mode::HighPerformance).unwrap(),
+            sensor.full_scale_set(Fs::_4g).unwrap(),
+            sensor.filter_bandwidth_set(BwFilt::OdrDiv4).unwrap().0,
+            sensor.filter_path_set(Fds::HighPassOnOut).unwrap(),
+            sensor.reference_mode_set(true).unwrap(),
+        ];
+        check(
+            &format!("{odr:?}: off, every hint is None"),
+            off.iter().all(|hint| *hint == SettleHint::None),
+        );
+        sensor.filter_path_set(Fds::LpfOnOut).unwrap();
+        sensor.reference_mode_set(false).unwrap();
+
+        // Starting the data rate restarts the filter, at the new period
+        let hint = sensor.data_rate_set(odr).unwrap();
+        check(
+            &format!("{odr:?}: data rate start, {hint:?}"),
+            hint == SettleHint::DurationUs(3 * period),
+        );
+
+        let hint = sensor.filter_bandwidth_set(BwFilt::OdrDiv20).unwrap().0;
+        check(
+            &format!("{odr:?}: bandwidth ODR/20, {hint:?}"),
+            hint == SettleHint::DurationUs(15 * period),
+        );
+        let hint = sensor.filter_path_set(Fds::HighPassOnOut).unwrap();
+        check(
+            &format!("{odr:?}: high-pass path, {hint:?}"),
+            hint == SettleHint::DurationUs(15 * period),
+        );
+        sensor.filter_path_set(Fds::LpfOnOut).unwrap();
+        let hint = sensor.filter_path_set(Fds::UserOffsetOnOut).unwrap();
+        check(
+            &format!("{odr:?}: user offset on output, {hint:?}"),
+            hint == SettleHint::Samples(1),
+        );
+
+        let samples = [
+            sensor.full_scale_set(Fs::_8g).unwrap(),
+            sensor
+                .power_mode_set(Mode::HighPerformanceLowNoise)
+                .unwrap(),
+            sensor.reference_mode_set(true).unwrap(),
+        ];
+        check(
+            &format!("{odr:?}: scale, mode and reference, one sample each"),
+            samples.iter().all(|hint| *hint == SettleHint::Samples(1)),
+        );
+
+        // Writing the value already set changes nothing
+        let same = [
+            sensor.data_rate_set(odr).unwrap(),
+            sensor.filter_bandwidth_set(BwFilt::OdrDiv20).unwrap().0,
+            sensor.filter_path_set(Fds::UserOffsetOnOut).unwrap(),
+            sensor.full_scale_set(Fs::_8g).unwrap(),
+            sensor
+                .power_mode_set(Mode::HighPerformanceLowNoise)
+                .unwrap(),
+            sensor.reference_mode_set(true).unwrap(),
+        ];
+        check(
+            &format!("{odr:?}: unchanged values, every hint is None"),
+            same.iter().all(|hint| *hint == SettleHint::None),
+        );
+
+        // The waits of the workarounds are deducted
+        sensor.workarounds_set(Workarounds::ALL);
+        let discard = sensor.filter_bandwidth_set(BwFilt::OdrDiv10).unwrap().0;
+        let fast = sensor.filter_bandwidth_set(BwFilt::OdrDiv2).unwrap().0;
+        let mode = sensor.power_mode_set(Mode::HighPerformance).unwrap();
+        check(
+            &format!("{odr:?}: with workarounds, {discard:?}, {fast:?}, {mode:?}"),
+            discard == SettleHint::DurationUs(6 * period)
+                && fast == SettleHint::None
+                && mode == SettleHint::None,
+        );
+
+        // settle_for waits the hint, samples counted at the current data rate
+        let mut ok = true;
+        for (hint, us) in [
+            (SettleHint::None, 0),
+            (SettleHint::Samples(3), 3 * period),
+            (SettleHint::DurationUs(12_345), 12_345),
+        ] {
+            spun.set(0);
+            sensor.settle_for(hint).unwrap();
+            ok &= spun.get() == u64::from(us) * 1000;
+        }
+        check(&format!("{odr:?}: settle_for waits the hint"), ok);
+
+        // Stopping the data rate leaves nothing to settle, and samples are not waited
+        let hint = sensor.data_rate_set(Odr::Off).unwrap();
+        spun.set(0);
+        sensor.settle_for(SettleHint::Samples(3)).unwrap();
+        check(
+            &format!("{odr:?}: power-down, {hint:?}, no wait"),
+            hint == SettleHint::None && spun.get() == 0,
+        );
+    }
+
+    check(
+        "table lookup",
+        filter_settling_samples(BwFilt::OdrDiv10) == 8
+            && SettleHint::Samples(2).duration_us(Odr::_50hz) == 40_000
+            && SettleHint::Samples(2).duration_us(Odr::Off) == 0,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Delay that only accounts for the time it would spin.
+struct SpinClock<'a> {
+    spun: &'a Cell<u64>,
+}
+
+impl DelayNs for SpinClock<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.spun.set(self.spun.get() + u64::from(ns));
+    }
+}
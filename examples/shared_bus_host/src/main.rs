@@ -0,0 +1,183 @@
+use std::cell::RefCell;
+use std::process::ExitCode;
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Error, I2CAddress, Iis2dlpc, ID};
+use st_mems_bus::BusOperation;
+
+const LOW: u8 = I2CAddress::I2cAddL as u8;
+const HIGH: u8 = I2CAddress::I2cAddH as u8;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Borrowed peripheral
+    let mut i2c = SimI2c::new(&[LOW, HIGH]);
+    let id = Iis2dlpc::new_i2c(&mut i2c, I2CAddress::I2cAddH, NoDelay).device_id_get();
+    check(
+        "&mut I2C: the identifier at the high address",
+        id == Ok(ID) && i2c.log == [HIGH],
+    );
+    let init = Iis2dlpc::new_i2c(&mut i2c, I2CAddress::I2cAddL, NoDelay).init();
+    check(
+        "&mut I2C: peripheral kept, init at the low address",
+        init.is_ok() && i2c.log[1..].iter().all(|&address| address == LOW),
+    );
+    let mut i2c = SimI2c::new(&[HIGH]);
+    check(
+        "absent device: no acknowledge",
+        Iis2dlpc::new_i2c(&mut i2c, I2CAddress::I2cAddL, NoDelay).device_id_get()
+            == Err(Error::Bus(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address,
+            ))),
+    );
+
+    // Two drivers on one bus
+    let i2c = RefCell::new(SimI2c::new(&[LOW, HIGH]));
+    let mut machine = Iis2dlpc::new_i2c(Shared(&i2c), I2CAddress::I2cAddL, NoDelay);
+    let mut frame = Iis2dlpc::new_i2c(Shared(&i2c), I2CAddress::I2cAddH, NoDelay);
+    check(
+        "shared: both drivers initialized",
+        machine.init().is_ok() && frame.init().is_ok(),
+    );
+    machine.full_scale_set(Fs::_8g).unwrap();
+    frame.full_scale_set(Fs::_2g).unwrap();
+    machine.data_rate_set(Odr::_100hz).unwrap();
+    frame.data_rate_set(Odr::_25hz).unwrap();
+    check(
+        "shared: each configuration in its own device",
+        (machine.full_scale_get(), machine.data_rate_get()) == (Ok(Fs::_8g), Ok(Odr::_100hz))
+            && (frame.full_scale_get(), frame.data_rate_get()) == (Ok(Fs::_2g), Ok(Odr::_25hz)),
+    );
+    check(
+        "shared: different CTRL1 and CTRL6 contents",
+        [Reg::Ctrl1, Reg::Ctrl6].iter().all(|&reg| {
+            let i2c = i2c.borrow();
+            i2c.device(LOW).reg(reg) != i2c.device(HIGH).reg(reg)
+        }),
+    );
+
+    // Interleaved reads
+    let samples = [[120, -40, 4000], [-8, 16, -4096]];
+    for (address, sample) in [LOW, HIGH].into_iter().zip(samples) {
+        let mut i2c = i2c.borrow_mut();
+        let device = i2c.device_mut(address);
+        for (k, val) in sample.into_iter().enumerate() {
+            let [low, high] = i16::to_le_bytes(val << 2);
+            device.set_reg(out_reg(2 * k), low);
+            device.set_reg(out_reg(2 * k + 1), high);
+        }
+    }
+    let start = i2c.borrow().log.len();
+    check(
+        "interleaved reads: each driver its own sample",
+        machine.acceleration_raw_get() == Ok(samples[0])
+            && frame.acceleration_raw_get() == Ok(samples[1])
+            && machine.acceleration_raw_get() == Ok(samples[0]),
+    );
+    check(
+        "interleaved reads: one transaction each, to its own address",
+        i2c.borrow().log[start..] == [LOW, HIGH, LOW],
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Output register `k`, from `OUT_X_L` (0) to `OUT_Z_H` (5).
+fn out_reg(k: usize) -> Reg {
+    [
+        Reg::OutXL,
+        Reg::OutXH,
+        Reg::OutYL,
+        Reg::OutYH,
+        Reg::OutZL,
+        Reg::OutZH,
+    ][k]
+}
+
+/// I2C peripheral with a simulated IIS2DLPC at each address, logging the address of every transaction.
+struct SimI2c {
+    devices: Vec<(u8, MockBus)>,
+    log: Vec<u8>,
+}
+
+impl SimI2c {
+    fn new(addresses: &[u8]) -> Self {
+        SimI2c {
+            devices: addresses
+                .iter()
+                .map(|&address| (address, MockBus::new()))
+                .collect(),
+            log: Vec::new(),
+        }
+    }
+
+    fn device(&self, address: u8) -> &MockBus {
+        let (_, device) = self.devices.iter().find(|(a, _)| *a == address).unwrap();
+        device
+    }
+
+    fn device_mut(&mut self, address: u8) -> &mut MockBus {
+        let (_, device) = self
+            .devices
+            .iter_mut()
+            .find(|(a, _)| *a == address)
+            .unwrap();
+        device
+    }
+}
+
+impl ErrorType for SimI2c {
+    type Error = ErrorKind;
+}
+
+impl I2c for SimI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.log.push(address);
+        let Some((_, device)) = self.devices.iter_mut().find(|(a, _)| *a == address) else {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        };
+        let result = match operations {
+            [Operation::Write(wbuf), Operation::Read(rbuf)] => {
+                device.write_byte_read_bytes(&[wbuf[0]], rbuf)
+            }
+            [Operation::Write(wbuf)] => device.write_bytes(wbuf),
+            [Operation::Read(rbuf)] => device.read_bytes(rbuf),
+            _ => return Err(ErrorKind::Other),
+        };
+        result.map_err(|never| match never {})
+    }
+}
+
+/// Borrow of the bus for one transaction at a time, as the `RefCellDevice` of `embedded-hal-bus`.
+struct Shared<'a>(&'a RefCell<SimI2c>);
+
+impl ErrorType for Shared<'_> {
+    type Error = ErrorKind;
+}
+
+impl I2c for Shared<'_> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::process::ExitCode;
+
+use embedded_hal::spi::{ErrorKind, ErrorType, Operation, SpiDevice};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{Iis2dlpc, ID};
+use st_mems_bus::BusOperation;
+
+/// Read bit of the SPI address byte.
+const SPI_READ: u8 = 0x80;
+/// `CTRL2` at reset with `sim` set.
+const CTRL2_3WIRE: u8 = 0x05;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // The 4-wire bus on a 3-wire wiring
+    let spi = RefCell::new(Sim3WireSpi::new());
+    let id = Iis2dlpc::new_spi(Shared(&spi), NoDelay).device_id_get();
+    check(
+        "new_spi on a 3-wire wiring: idle data line read",
+        id == Ok(0xFF),
+    );
+
+    // The 3-wire bus
+    let spi = RefCell::new(Sim3WireSpi::new());
+    let mut sensor = Iis2dlpc::new_spi_3wire(Shared(&spi), NoDelay);
+    check(
+        "new_spi_3wire: WHO_AM_I right after construction",
+        sensor.device_id_get() == Ok(ID),
+    );
+    check(
+        "new_spi_3wire: CTRL2 written with sim set before the first read",
+        spi.borrow().log
+            == [
+                vec![Reg::Ctrl2 as u8, CTRL2_3WIRE],
+                vec![Reg::WhoAmI as u8 | SPI_READ],
+            ],
+    );
+    check(
+        "next read: no further CTRL2 write",
+        sensor.device_id_get() == Ok(ID) && spi.borrow().log.len() == 3,
+    );
+
+    spi.borrow_mut().log.clear();
+    let init = sensor.init();
+    let ctrl2 = Ctrl2::from_bits(spi.borrow().device.reg(Reg::Ctrl2));
+    check(
+        "init: Ok, sim set, block data update and auto-increment on",
+        init.is_ok() && ctrl2.sim() == 1 && ctrl2.bdu() == 1 && ctrl2.if_add_inc() == 1,
+    );
+    check(
+        "init: sim written again after the software reset",
+        sim_writes(&spi.borrow().log) == 1,
+    );
+
+    check(
+        "spi_mode_set 4-wire: sim kept, the device still answers",
+        sensor.spi_mode_set(Sim::Spi4Wire).is_ok()
+            && sensor.spi_mode_get() == Ok(Sim::Spi3Wire)
+            && sensor.device_id_get() == Ok(ID),
+    );
+
+    let write = sensor
+        .bus
+        .write_to_register(Reg::Ctrl1 as u8, &[0x50, 0x0C, 0x00]);
+    let spanned = [Reg::Ctrl1, Reg::Ctrl2, Reg::Ctrl3].map(|reg| spi.borrow().device.reg(reg));
+    check(
+        "write spanning CTRL2: sim kept, other bytes as written",
+        write.is_ok() && spanned == [0x50, 0x0D, 0x00],
+    );
+
+    let samples = [120, -40, 4000];
+    for (k, val) in samples.into_iter().enumerate() {
+        let [low, high] = i16::to_le_bytes(val << 2);
+        let mut spi = spi.borrow_mut();
+        spi.device.set_reg(out_reg(2 * k), low);
+        spi.device.set_reg(out_reg(2 * k + 1), high);
+    }
+    check(
+        "acceleration_raw_get over 3 wires",
+        sensor.acceleration_raw_get() == Ok(samples),
+    );
+
+    // Power cycle of the device, unseen by the bus
+    spi.borrow_mut().device = MockBus::new();
+    let lost = sensor.device_id_get();
+    sensor.bus.resync();
+    check(
+        "power cycle: idle line, then WHO_AM_I after resync",
+        lost == Ok(0xFF) && sensor.device_id_get() == Ok(ID),
+    );
+
+    check(
+        "half-duplex transactions only",
+        spi.borrow().full_duplex == 0,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Number of writes setting `sim` in `CTRL2` alone.
+fn sim_writes(log: &[Vec<u8>]) -> usize {
+    log.iter()
+        .filter(|bytes| **bytes == [Reg::Ctrl2 as u8, CTRL2_3WIRE])
+        .count()
+}
+
+/// Output register `k`, from `OUT_X_L` (0) to `OUT_Z_H` (5).
+fn out_reg(k: usize) -> Reg {
+    [
+        Reg::OutXL,
+        Reg::OutXH,
+        Reg::OutYL,
+        Reg::OutYH,
+        Reg::OutZL,
+        Reg::OutZH,
+    ][k]
+}
+
+/// SPI device with a simulated IIS2DLPC wired in the 3-wire mode, logging the bytes written in
+/// every transaction.
+///
+/// The device receives on the data line in both modes, but only drives it with `sim` set in
+/// `CTRL2`; otherwise a read returns the idle line, `0xFF`.
+struct Sim3WireSpi {
+    device: MockBus,
+    log: Vec<Vec<u8>>,
+    full_duplex: u32,
+}
+
+impl Sim3WireSpi {
+    fn new() -> Self {
+        Sim3WireSpi {
+            device: MockBus::new(),
+            log: Vec::new(),
+            full_duplex: 0,
+        }
+    }
+}
+
+impl ErrorType for Sim3WireSpi {
+    type Error = ErrorKind;
+}
+
+impl SpiDevice for Sim3WireSpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let mut written = Vec::new();
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => written.extend_from_slice(bytes),
+                Operation::Read(buf) => {
+                    let [addr] = written[..] else {
+                        return Err(ErrorKind::Other);
+                    };
+                    if addr & SPI_READ == 0 {
+                        return Err(ErrorKind::Other);
+                    }
+                    if Ctrl2::from_bits(self.device.reg(Reg::Ctrl2)).sim() == 1 {
+                        let result = self.device.write_byte_read_bytes(&[addr & !SPI_READ], buf);
+                        result.map_err(|never| match never {})?;
+                    } else {
+                        buf.fill(0xFF);
+                    }
+                    self.log.push(written);
+                    return Ok(());
+                }
+                Operation::Transfer(..) | Operation::TransferInPlace(..) => {
+                    self.full_duplex += 1;
+                    return Err(ErrorKind::Other);
+                }
+                Operation::DelayNs(_) => {}
+            }
+        }
+        let result = self.device.write_bytes(&written);
+        self.log.push(written);
+        result.map_err(|never| match never {})
+    }
+}
+
+/// Borrow of the SPI device for one transaction at a time, as the `RefCellDevice` of
+/// `embedded-hal-bus`.
+struct Shared<'a>(&'a RefCell<Sim3WireSpi>);
+
+impl ErrorType for Shared<'_> {
+    type Error = ErrorKind;
+}
+
+impl SpiDevice for Shared<'_> {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        self.0.borrow_mut().transaction(operations)
+    }
+}
@@ -0,0 +1,122 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::Iis2dlpc;
+
+/// Flags of `STATUS` with their counterpart in `STATUS_DUP`.
+const SHARED: [(&str, fn(u8) -> u8, fn(u8) -> u8); 6] = [
+    (
+        "drdy",
+        |b| Status::from_bits(b).drdy(),
+        |b| StatusDup::from_bits(b).drdy(),
+    ),
+    (
+        "ff_ia",
+        |b| Status::from_bits(b).ff_ia(),
+        |b| StatusDup::from_bits(b).ff_ia(),
+    ),
+    (
+        "six_d_ia",
+        |b| Status::from_bits(b).six_d_ia(),
+        |b| StatusDup::from_bits(b).six_d_ia(),
+    ),
+    (
+        "single_tap",
+        |b| Status::from_bits(b).single_tap(),
+        |b| StatusDup::from_bits(b).single_tap(),
+    ),
+    (
+        "double_tap",
+        |b| Status::from_bits(b).double_tap(),
+        |b| StatusDup::from_bits(b).double_tap(),
+    ),
+    (
+        "sleep_state",
+        |b| Status::from_bits(b).sleep_state(),
+        |b| StatusDup::from_bits(b).sleep_state_ia(),
+    ),
+];
+
+/// Flags specific to one of the registers.
+const OWN: [(&str, fn(u8) -> u8, fn(u8) -> u8); 2] = [
+    (
+        "wu_ia / drdy_t",
+        |b| Status::from_bits(b).wu_ia(),
+        |b| StatusDup::from_bits(b).drdy_t(),
+    ),
+    (
+        "fifo_ths / ovr",
+        |b| Status::from_bits(b).fifo_ths(),
+        |b| StatusDup::from_bits(b).ovr(),
+    ),
+];
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let status_shared = SHARED
+        .iter()
+        .fold(0, |acc, (_, status, _)| acc | bit(*status));
+    let dup_shared = SHARED.iter().fold(0, |acc, (_, _, dup)| acc | bit(*dup));
+
+    for (name, status, dup) in SHARED {
+        let (status, dup) = (bit(status), bit(dup));
+        check(
+            &format!("{name} set in STATUS only: transition flagged"),
+            coherent(status, 0) == (status, 0, true),
+        );
+        check(
+            &format!("{name} set in STATUS_DUP only: transition flagged"),
+            coherent(0, dup) == (0, dup, true),
+        );
+        check(
+            &format!("{name} set in both: no transition"),
+            coherent(status, dup) == (status, dup, false),
+        );
+        check(
+            &format!("{name} cleared in STATUS only among all shared flags: transition flagged"),
+            coherent(status_shared & !status, dup_shared).2,
+        );
+    }
+
+    for (name, status, dup) in OWN {
+        let (status, dup) = (bit(status), bit(dup));
+        check(
+            &format!("{name} differing: no transition"),
+            !coherent(status | status_shared, dup_shared).2
+                && !coherent(status_shared, dup | dup_shared).2,
+        );
+    }
+
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.cost_reset();
+    sensor.status_coherent_get().unwrap();
+    check("two transactions", sensor.bus.transactions() == 2);
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Read synthetic `STATUS` and `STATUS_DUP` values through the driver.
+fn coherent(status: u8, dup: u8) -> (u8, u8, bool) {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.set_reg(Reg::Status, status);
+    sensor.bus.set_reg(Reg::StatusDup, dup);
+    let (status, dup, changed) = sensor.status_coherent_get().unwrap();
+    (status.into_bits(), dup.into_bits(), changed)
+}
+
+/// Find the bit of a 1-bit field of a read-only register.
+fn bit(get: fn(u8) -> u8) -> u8 {
+    (0..8).map(|i| 1 << i).find(|b| get(*b) != 0).unwrap()
+}
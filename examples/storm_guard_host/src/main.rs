@@ -0,0 +1,182 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::event::{Event, EventRoute, StormPolicy, MAX_EVENTS, STORM_POLLS};
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::Iis2dlpc;
+
+/// Number of simulated samples of each scenario, one drain per sample.
+const SAMPLES: u32 = 2000;
+
+/// Wake-up threshold LSB at ±2 g: full scale / 64, in mg.
+const WK_THS_LSB_MG: f32 = 2000.0 / 64.0;
+
+/// Wake-up threshold of the healthy configuration: 2 LSB, 62.5 mg.
+const WK_THS_HEALTHY: u8 = 2;
+
+/// A knock lasts 2 samples, every 250 samples.
+const KNOCK_PERIOD: u32 = 250;
+
+/// The device is turned over every 700 samples, raising a 6D event.
+const TURN_PERIOD: u32 = 700;
+
+/// Rate limit of the guard: every drain with an event counts towards a storm.
+///
+/// Drained on every sample, a healthy configuration raises events on isolated drains only.
+const LIMIT: u16 = 0;
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Zero threshold: the wake-up event fires at every sample
+    let outcome = run(0, StormPolicy::ReportOnly);
+    check(
+        "zero wake threshold trips the guard",
+        outcome.first_throttled == Some(u32::from(STORM_POLLS) - 1),
+    );
+    check(
+        "report-only policy leaves the routing",
+        outcome.disabled.is_empty() && outcome.int1_wu == 1,
+    );
+
+    let outcome = run(0, StormPolicy::DisableRoute);
+    check(
+        "disable policy removes the wake-up route once",
+        outcome.disabled == [(u32::from(STORM_POLLS) - 1, EventRoute::WakeUp)],
+    );
+    check("wake-up route cleared on the device", outcome.int1_wu == 0);
+    check("6D route kept on the device", outcome.int1_6d == 1);
+
+    // Healthy threshold: only knocks and turns raise events
+    let outcome = run(WK_THS_HEALTHY, StormPolicy::DisableRoute);
+    check("healthy configuration raises events", outcome.events > 0);
+    check(
+        "healthy configuration never trips the guard",
+        outcome.first_throttled.is_none() && outcome.disabled.is_empty(),
+    );
+    check(
+        "healthy configuration keeps the routing",
+        outcome.int1_wu == 1 && outcome.int1_6d == 1,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Outcome of a simulated scenario.
+struct Outcome {
+    /// Number of events drained.
+    events: usize,
+    /// First drain flagged as throttled.
+    first_throttled: Option<u32>,
+    /// Drains that disabled a route, with the route.
+    disabled: Vec<(u32, EventRoute)>,
+    /// Final `int1_wu` routing on the device.
+    int1_wu: u8,
+    /// Final `int1_6d` routing on the device.
+    int1_6d: u8,
+}
+
+/// Run `SAMPLES` samples with a wake-up threshold, draining the events after each sample.
+fn run(wk_ths: u8, policy: StormPolicy) -> Outcome {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.wkup_threshold_set(wk_ths).unwrap();
+    sensor
+        .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_wu(1).with_int1_6d(1))
+        .unwrap();
+    sensor.events_rate_limit_set(LIMIT);
+    sensor.events_storm_policy_set(policy);
+
+    let mut outcome = Outcome {
+        events: 0,
+        first_throttled: None,
+        disabled: Vec::new(),
+        int1_wu: 0,
+        int1_6d: 0,
+    };
+    let mut noise = Noise(1);
+    let mut previous = [0.0, 0.0, 1000.0];
+    for n in 0..SAMPLES {
+        let turned = (n / TURN_PERIOD) % 2 == 1;
+        let knock = if n % KNOCK_PERIOD < 2 { 300.0 } else { 0.0 };
+        let z = if turned { -1000.0 } else { 1000.0 };
+        let sample = [noise.mg(), noise.mg(), z + knock + noise.mg()];
+        simulate(
+            &mut sensor,
+            &previous,
+            &sample,
+            n > 0 && n % TURN_PERIOD == 0,
+        );
+        previous = sample;
+
+        let mut events = [Event::FreeFall; MAX_EVENTS];
+        let report = sensor.events_drain_report(&mut events).unwrap();
+        outcome.events += report.count;
+        if report.throttled && outcome.first_throttled.is_none() {
+            outcome.first_throttled = Some(n);
+        }
+        if let Some(route) = report.disabled {
+            outcome.disabled.push((n, route));
+        }
+    }
+
+    let routes = Ctrl4Int1PadCtrl::from_bits(sensor.bus.reg(Reg::Ctrl4Int1PadCtrl));
+    outcome.int1_wu = routes.int1_wu();
+    outcome.int1_6d = routes.int1_6d();
+    outcome
+}
+
+/// Update the source registers for a new sample, as latched interrupts do.
+///
+/// The wake-up function compares the slope filter output, the difference between consecutive
+/// samples, with the threshold on every axis: a threshold of 0 is met by every sample.
+fn simulate(sensor: &mut Sensor, previous: &[f32; 3], sample: &[f32; 3], turned: bool) {
+    let wk_ths = WakeUpThs::from_bits(sensor.bus.reg(Reg::WakeUpThs)).wk_ths();
+    let threshold = f32::from(wk_ths) * WK_THS_LSB_MG;
+    let wake = previous
+        .iter()
+        .zip(sample)
+        .any(|(a, b)| (b - a).abs() >= threshold);
+
+    let wu_ia = flag(|bits| WakeUpSrc::from_bits(bits).wu_ia());
+    let six_d_ia = flag(|bits| SixdSrc::from_bits(bits).six_d_ia());
+    let all_wu_ia = flag(|bits| AllIntSrc::from_bits(bits).wu_ia());
+    let all_six_d_ia = flag(|bits| AllIntSrc::from_bits(bits).six_d_ia());
+
+    let pick = |set: bool, bits: u8| if set { bits } else { 0 };
+    sensor.bus.set_reg(Reg::WakeUpSrc, pick(wake, wu_ia));
+    sensor.bus.set_reg(Reg::SixdSrc, pick(turned, six_d_ia));
+    sensor.bus.set_reg(
+        Reg::AllIntSrc,
+        pick(wake, all_wu_ia) | pick(turned, all_six_d_ia),
+    );
+}
+
+/// Find the bit of a read-only source flag, which has no setter.
+fn flag(get: impl Fn(u8) -> u8) -> u8 {
+    (0..8)
+        .map(|bit| 1 << bit)
+        .find(|&bits| get(bits) == 1)
+        .unwrap()
+}
+
+/// Deterministic accelerometer noise, within ±4 mg.
+struct Noise(u32);
+
+impl Noise {
+    fn mg(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        ((self.0 >> 8) as f32 / (1 << 24) as f32 - 0.5) * 8.0
+    }
+}
@@ -0,0 +1,127 @@
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+
+use embassy_executor::Spawner;
+use embassy_stm32::exti::ExtiInput;
+use embassy_stm32::gpio::Pull;
+use embassy_stm32::i2c::{self, I2c};
+use embassy_stm32::time::khz;
+use embassy_stm32::usart::{self, BufferedInterruptHandler, DataBits, Parity, UartTx};
+use embassy_stm32::{bind_interrupts, peripherals, peripherals::USART2};
+use embassy_time::{Delay, Timer};
+use heapless::String;
+use iis2dlpc_rs::asynch::{I2cBusAsync, Iis2dlpcAsync};
+use iis2dlpc_rs::event::Event;
+use iis2dlpc_rs::stream::{Iis2dlpcStream, Item};
+use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
+use iis2dlpc_rs::{PROPERTY_ENABLE, from_fs2_to_mg, prelude::*};
+
+use {defmt_rtt as _, panic_probe as _};
+
+#[defmt::panic_handler]
+fn panic() -> ! {
+    core::panic!("panic via `defmt::panic!")
+}
+
+bind_interrupts!(struct Irqs {
+    USART2 => BufferedInterruptHandler<USART2>;
+    I2C1_EV => i2c::EventInterruptHandler<peripherals::I2C1>;
+    I2C1_ER => i2c::ErrorInterruptHandler<peripherals::I2C1>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_stm32::init(Default::default());
+
+    let mut usart_cfg = usart::Config::default();
+    usart_cfg.baudrate = 115200;
+    usart_cfg.data_bits = DataBits::DataBits8;
+    usart_cfg.parity = Parity::ParityNone;
+
+    let mut tx = UartTx::new(p.USART2, p.PA2, p.DMA1_CH6, usart_cfg).unwrap();
+
+    let mut i2c = I2c::new(
+        p.I2C1,
+        p.PB8,
+        p.PB9,
+        Irqs,
+        p.DMA1_CH7,
+        p.DMA1_CH5,
+        khz(100),
+        Default::default(),
+    );
+
+    // INT1 of the sensor on D2
+    let int1 = ExtiInput::new(p.PA10, p.EXTI10, Pull::Down);
+
+    let mut msg = String::<128>::new();
+
+    Timer::after_millis(10).await;
+
+    // Configure the device with the blocking driver, on a borrow of the peripheral
+    let mut sensor = Iis2dlpc::new_i2c(&mut i2c, I2CAddress::I2cAddH, Delay);
+
+    match sensor.device_id_get() {
+        Ok(value) => {
+            if value != iis2dlpc_rs::ID {
+                panic!("Invalid sensor ID")
+            }
+        }
+        Err(e) => {
+            writeln!(&mut msg, "An error occured while reading sensor ID: {e:?}").unwrap();
+            tx.blocking_write(msg.as_bytes()).unwrap();
+            msg.clear();
+        }
+    }
+    Timer::after_millis(25).await;
+
+    // Restore default configuration
+    sensor.reset_set().unwrap();
+    while sensor.reset_get().unwrap() == 1 {}
+
+    // Enable Block Data Update
+    sensor.block_data_update_set(true).unwrap();
+    // Set Full scale
+    sensor.full_scale_set(Fs::_2g).unwrap();
+    // Configure power mode
+    sensor
+        .power_mode_set(Mode::ContLowPwrLowNoise12bit)
+        .unwrap();
+    // Set Output Data Rate
+    sensor.data_rate_set(Odr::_25hz).unwrap();
+    // Set wake-up threshold: 1 Lsb corresponds to FS_XL/2^6
+    sensor.wkup_threshold_set(2).unwrap();
+    // Latch the events until the source registers are read
+    sensor.int_notification_set(Lir::Latched).unwrap();
+    // Route data-ready and Wake-Up to the INT1 pin
+    let mut int_route = sensor.pin_int1_route_get().unwrap();
+    int_route.set_int1_drdy(PROPERTY_ENABLE);
+    int_route.set_int1_wu(PROPERTY_ENABLE);
+    sensor.pin_int1_route_set(&int_route).unwrap();
+
+    // The stream runs on the async driver: the bus transactions are awaited too
+    let bus = I2cBusAsync::new(i2c, I2CAddress::I2cAddH as u8);
+    let sensor = Iis2dlpcAsync::from_bus(bus, Delay);
+    let mut stream = Iis2dlpcStream::with_pin(sensor, int1).await.unwrap();
+
+    // Single driver loop: events first, then samples
+    loop {
+        msg.clear();
+        match stream.next().await.unwrap() {
+            Item::Sample(raw) => {
+                let acceleration_mg = raw.map(from_fs2_to_mg);
+                writeln!(
+                    &mut msg,
+                    "Acceleration [mg]: {:4.2}\t{:4.2}\t{:4.2}",
+                    acceleration_mg[0], acceleration_mg[1], acceleration_mg[2]
+                )
+                .unwrap();
+            }
+            Item::Event(Event::WakeUp { .. }) => writeln!(&mut msg, "Wake-Up event").unwrap(),
+            Item::Event(event) => writeln!(&mut msg, "Event: {event:?}").unwrap(),
+        }
+        tx.write(msg.as_bytes()).await.unwrap();
+    }
+}
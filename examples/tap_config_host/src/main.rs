@@ -0,0 +1,147 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{Access, MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::tap::TapConfig;
+use iis2dlpc_rs::{Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let cfg = TapConfig {
+        thresholds: [12, 9, 31],
+        axes: [true, false, true],
+        shock: 3,
+        quiet: 2,
+        latency: 7,
+        priority: TapPrior::Zyx,
+        mode: SingleDoubleTap::BothSingleDouble,
+    };
+
+    // One write per register, 6D and wake-up settings kept
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    let six_d = TapThsX::new().with_six_d_ths(2).with_four_d_en(1);
+    let wake_up = WakeUpThs::new().with_wk_ths(10).with_sleep_on(1);
+    sensor.bus.set_reg(Reg::TapThsX, six_d.into_bits());
+    sensor.bus.set_reg(Reg::WakeUpThs, wake_up.into_bits());
+    sensor.bus.expect(&[
+        Access::read(Reg::TapThsX),
+        Access::read(Reg::WakeUpThs),
+        Access::write(Reg::TapThsX, six_d.with_tap_thsx(12).into_bits()),
+        Access::write(
+            Reg::TapThsY,
+            TapThsY::new()
+                .with_tap_thsy(9)
+                .with_tap_prior(3)
+                .into_bits(),
+        ),
+        Access::write(
+            Reg::TapThsZ,
+            TapThsZ::new()
+                .with_tap_thsz(31)
+                .with_tap_x_en(1)
+                .with_tap_z_en(1)
+                .into_bits(),
+        ),
+        Access::write(
+            Reg::IntDur,
+            IntDur::new()
+                .with_shock(3)
+                .with_quiet(2)
+                .with_latency(7)
+                .into_bits(),
+        ),
+        Access::write(
+            Reg::WakeUpThs,
+            wake_up.with_single_double_tap(1).into_bits(),
+        ),
+    ]);
+    check("tap_config_set: Ok", sensor.tap_config_set(&cfg).is_ok());
+    check(
+        "tap_config_set: two reads, one write per register",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+    check(
+        "tap_config_get: the configuration set",
+        sensor.tap_config_get() == Ok(cfg),
+    );
+    check(
+        "individual getters: the configuration set",
+        sensor.tap_threshold_x_get() == Ok(12)
+            && sensor.tap_threshold_y_get() == Ok(9)
+            && sensor.tap_threshold_z_get() == Ok(31)
+            && sensor.tap_detection_on_y_get() == Ok(false)
+            && sensor.tap_dur_get() == Ok(7)
+            && sensor.tap_axis_priority_get() == Ok(TapPrior::Zyx)
+            && sensor.tap_mode_get() == Ok(SingleDoubleTap::BothSingleDouble),
+    );
+
+    // Out of range fields
+    let out_of_range = [
+        TapConfig {
+            thresholds: [32, 0, 0],
+            ..cfg
+        },
+        TapConfig {
+            thresholds: [0, 0, 32],
+            ..cfg
+        },
+        TapConfig { shock: 4, ..cfg },
+        TapConfig { quiet: 4, ..cfg },
+        TapConfig { latency: 16, ..cfg },
+    ];
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.expect(&[]);
+    check(
+        "threshold, shock, quiet or latency too large: invalid argument",
+        out_of_range
+            .iter()
+            .all(|cfg| sensor.tap_config_set(cfg) == Err(Error::InvalidArgument)),
+    );
+    check(
+        "invalid argument: nothing read or written",
+        sensor.bus.expectations_check() == Ok(()),
+    );
+
+    // Reserved axis priority
+    sensor
+        .bus
+        .set_reg(Reg::TapThsY, TapThsY::new().with_tap_prior(7).into_bits());
+    check(
+        "tap_config_get: reserved priority",
+        sensor.tap_config_get() == Err(Error::UnexpectedValue(7)),
+    );
+
+    // Thresholds in mg
+    let cfg = TapConfig::default().with_thresholds_mg([750.0, 2000.0, -10.0], Fs::_2g);
+    check(
+        "with_thresholds_mg at 2 g: nearest code, clamped",
+        cfg.thresholds == [12, 31, 0],
+    );
+    check(
+        "thresholds_mg at 2 g and 4 g",
+        cfg.thresholds_mg(Fs::_2g) == [750.0, 1937.5, 0.0]
+            && cfg.thresholds_mg(Fs::_4g) == [1500.0, 3875.0, 0.0],
+    );
+    check(
+        "with_thresholds_mg: not a number gives code 0",
+        TapConfig::default()
+            .with_thresholds_mg([f32::NAN; 3], Fs::_16g)
+            .thresholds
+            == [0; 3],
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
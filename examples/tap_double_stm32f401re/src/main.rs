@@ -3,12 +3,14 @@
 
 use core::fmt::Write;
 
+use iis2dlpc_rs::config::Config as SensorConfig;
+use iis2dlpc_rs::event::{Event, TapKind};
+use iis2dlpc_rs::tap::TapConfig;
 use iis2dlpc_rs::{prelude::*, I2CAddress, Iis2dlpc, PROPERTY_ENABLE};
 
 use panic_itm as _;
 
 use cortex_m_rt::entry;
-use heapless::String;
 use stm32f4xx_hal::{
     hal::delay::DelayNs,
     i2c::{DutyCycle, I2c, Mode as I2cMode},
@@ -58,105 +60,50 @@ fn main() -> ! {
 
     let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay);
 
-    match sensor.device_id_get() {
-        Ok(value) => {
-            if value != iis2dlpc_rs::ID {
-                panic!("Invalid sensor ID")
-            }
-        }
-        Err(e) => writeln!(tx, "An error occured while reading sensor ID: {e:?}").unwrap(),
-    }
     sensor.tim.delay_ms(25);
 
-    // Restore default configuration
-    sensor.reset_set().unwrap();
-    while sensor.reset_get().unwrap() == 1 {}
-
-    // Set Full scale
-    sensor.full_scale_set(Fs::_2g).unwrap();
-    // Configure power mode
+    // Check the device identifier, restore default configuration and enable Block Data Update
+    sensor.init().unwrap();
+    // Set Full scale, power mode and Output Data Rate
     sensor
-        .power_mode_set(Mode::ContLowPwrLowNoise12bit)
+        .apply_config(&SensorConfig {
+            odr: Odr::_200hz,
+            fs: Fs::_2g,
+            mode: Mode::ContLowPwrLowNoise12bit,
+            ..Default::default()
+        })
         .unwrap();
-    // Set Output Data Rate
-    sensor.data_rate_set(Odr::_400hz).unwrap();
-    // Enable Tap detection on X, Y, Z
-    sensor.tap_detection_on_z_set(PROPERTY_ENABLE).unwrap();
-    sensor.tap_detection_on_y_set(PROPERTY_ENABLE).unwrap();
-    sensor.tap_detection_on_x_set(PROPERTY_ENABLE).unwrap();
-    // Set Tap threshold on all axis
-    sensor.tap_threshold_x_set(12).unwrap();
-    sensor.tap_threshold_y_set(12).unwrap();
-    sensor.tap_threshold_z_set(12).unwrap();
-    // Configure Double Tap parameter
-    sensor.tap_dur_set(7).unwrap();
-    sensor.tap_quiet_set(3).unwrap();
-    sensor.tap_shock_set(3).unwrap();
-    // Enable double tap detection only
+    // Enable single and double tap detection on X, Y, Z with a threshold of 12 (750 mg at 2 g),
+    // shock, quiet and latency in one call
     sensor
-        .tap_mode_set(SingleDoubleTap::BothSingleDouble)
+        .tap_config_set(&TapConfig {
+            thresholds: [12; 3],
+            axes: [true; 3],
+            shock: 3,
+            quiet: 3,
+            latency: 7,
+            mode: SingleDoubleTap::BothSingleDouble,
+            ..Default::default()
+        })
         .unwrap();
     // Enable single tap detection interrupt
     let mut int_route = sensor.pin_int1_route_get().unwrap();
     int_route.set_int1_tap(PROPERTY_ENABLE);
     sensor.pin_int1_route_set(&int_route).unwrap();
 
-    let mut tx_buff = String::<40>::new();
     // Wait Events
     loop {
-        let all_sources = sensor.all_sources_get().unwrap();
-        // Check Double Tap events
-        if all_sources.tap_src.double_tap() == 1 {
-            tx_buff.clear();
-            write!(
-                &mut tx_buff,
-                "Double Tap Detected: Sign {}",
-                if all_sources.tap_src.tap_sign() == 1 {
-                    "positive"
-                } else {
-                    "negative"
-                }
-            )
-            .unwrap();
-
-            if all_sources.tap_src.x_tap() == 1 {
-                writeln!(tx, "{} on X axis", tx_buff).unwrap();
-            }
-
-            if all_sources.wake_up_src.y_wu() == 1 {
-                writeln!(tx, "{} on Y axis", tx_buff).unwrap();
-            }
-
-            if all_sources.wake_up_src.z_wu() == 1 {
-                writeln!(tx, "{} on Z axis", tx_buff).unwrap();
+        if let Some(Event::Tap(tap)) = sensor.event_get().unwrap() {
+            let kind = match tap.kind {
+                TapKind::Single => "Tap",
+                TapKind::Double => "Double Tap",
+            };
+            let sign = if tap.negative { "negative" } else { "positive" };
+            match tap.axis {
+                Some(axis) => writeln!(tx, "{kind} Detected: Sign {sign} on {axis:?} axis"),
+                None => writeln!(tx, "{kind} Detected: Sign {sign}"),
             }
-        }
-
-        // Check Single Tap events
-        if all_sources.tap_src.single_tap() == 1 {
-            tx_buff.clear();
-            write!(
-                &mut tx_buff,
-                "Tap Detected: Sign {}",
-                if all_sources.tap_src.tap_sign() == 1 {
-                    "positive"
-                } else {
-                    "negative"
-                }
-            )
             .unwrap();
-
-            if all_sources.tap_src.x_tap() == 1 {
-                writeln!(tx, "{} on X axis", tx_buff).unwrap();
-            }
-
-            if all_sources.wake_up_src.y_wu() == 1 {
-                writeln!(tx, "{} on Y axis", tx_buff).unwrap();
-            }
-
-            if all_sources.wake_up_src.z_wu() == 1 {
-                writeln!(tx, "{} on Z axis", tx_buff).unwrap();
-            }
         }
     }
 }
@@ -11,6 +11,9 @@ use embassy_stm32::{bind_interrupts, peripherals, peripherals::USART2};
 use embassy_time::Delay;
 use embedded_hal::delay::DelayNs;
 use heapless::String;
+use iis2dlpc_rs::config::Config;
+use iis2dlpc_rs::event::{Event, TapKind};
+use iis2dlpc_rs::tap::TapConfig;
 use iis2dlpc_rs::{I2CAddress, Iis2dlpc};
 use iis2dlpc_rs::{PROPERTY_ENABLE, prelude::*};
 
@@ -56,121 +59,52 @@ async fn main(_spawner: Spawner) {
 
     let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay.clone());
 
-    match sensor.device_id_get() {
-        Ok(value) => {
-            if value != iis2dlpc_rs::ID {
-                panic!("Invalid sensor ID")
-            }
-        }
-        Err(e) => {
-            writeln!(&mut msg, "An error occured while reading sensor ID: {e:?}").unwrap();
-            tx.blocking_write(msg.as_bytes()).unwrap();
-            msg.clear();
-        }
-    }
     sensor.tim.delay_ms(25);
 
-    // Restore default configuration
-    sensor.reset_set().unwrap();
-    while sensor.reset_get().unwrap() == 1 {}
-
-    // Set Full scale
-    sensor.full_scale_set(Fs::_2g).unwrap();
-    // Configure power mode
+    // Check the device identifier, restore default configuration and enable Block Data Update
+    sensor.init().unwrap();
+    // Set Full scale, power mode and Output Data Rate
     sensor
-        .power_mode_set(Mode::ContLowPwrLowNoise12bit)
+        .apply_config(&Config {
+            odr: Odr::_200hz,
+            fs: Fs::_2g,
+            mode: Mode::ContLowPwrLowNoise12bit,
+            ..Default::default()
+        })
         .unwrap();
-    // Set Output Data Rate
-    sensor.data_rate_set(Odr::_400hz).unwrap();
-    // Enable Tap detection on X, Y, Z
-    sensor.tap_detection_on_z_set(PROPERTY_ENABLE).unwrap();
-    sensor.tap_detection_on_y_set(PROPERTY_ENABLE).unwrap();
-    sensor.tap_detection_on_x_set(PROPERTY_ENABLE).unwrap();
-    // Set Tap threshold on all axis
-    sensor.tap_threshold_x_set(12).unwrap();
-    sensor.tap_threshold_y_set(12).unwrap();
-    sensor.tap_threshold_z_set(12).unwrap();
-    // Configure Single Tap parameter
-    sensor.tap_dur_set(7).unwrap();
-    sensor.tap_quiet_set(3).unwrap();
-    sensor.tap_shock_set(3).unwrap();
-    // Enable Double Tap detection only
+    // Enable single and double tap detection on X, Y, Z with a threshold of 12 (750 mg at 2 g),
+    // shock, quiet and latency in one call
     sensor
-        .tap_mode_set(SingleDoubleTap::BothSingleDouble)
+        .tap_config_set(&TapConfig {
+            thresholds: [12; 3],
+            axes: [true; 3],
+            shock: 3,
+            quiet: 3,
+            latency: 7,
+            mode: SingleDoubleTap::BothSingleDouble,
+            ..Default::default()
+        })
         .unwrap();
     // Enable single tap detection interrupt
     let mut int_route = sensor.pin_int1_route_get().unwrap();
     int_route.set_int1_tap(PROPERTY_ENABLE);
     sensor.pin_int1_route_set(&int_route).unwrap();
 
-    let mut tx_buff = String::<40>::new();
     // Wait Events
     loop {
-        let all_sources = sensor.all_sources_get().unwrap();
-        // Check Double Tap events
-        if all_sources.tap_src.double_tap() == 1 {
-            tx_buff.clear();
-            write!(
-                &mut tx_buff,
-                "Double Tap Detected: Sign {}",
-                if all_sources.tap_src.tap_sign() == 1 {
-                    "positive"
-                } else {
-                    "negative"
-                }
-            )
-            .unwrap();
-
+        if let Some(Event::Tap(tap)) = sensor.event_get().unwrap() {
+            let kind = match tap.kind {
+                TapKind::Single => "Tap",
+                TapKind::Double => "Double Tap",
+            };
+            let sign = if tap.negative { "negative" } else { "positive" };
             msg.clear();
-            if all_sources.tap_src.x_tap() == 1 {
-                writeln!(&mut msg, "{} on X axis", tx_buff).unwrap();
-                tx.blocking_write(msg.as_bytes()).unwrap();
+            match tap.axis {
+                Some(axis) => writeln!(&mut msg, "{kind} Detected: Sign {sign} on {axis:?} axis"),
+                None => writeln!(&mut msg, "{kind} Detected: Sign {sign}"),
             }
-
-            msg.clear();
-            if all_sources.wake_up_src.y_wu() == 1 {
-                writeln!(&mut msg, "{} on Y axis", tx_buff).unwrap();
-                tx.blocking_write(msg.as_bytes()).unwrap();
-            }
-
-            msg.clear();
-            if all_sources.wake_up_src.z_wu() == 1 {
-                writeln!(&mut msg, "{} on Z axis", tx_buff).unwrap();
-                tx.blocking_write(msg.as_bytes()).unwrap();
-            }
-        }
-
-        // Check Single Tap events
-        if all_sources.tap_src.single_tap() == 1 {
-            tx_buff.clear();
-            write!(
-                &mut tx_buff,
-                "Tap Detected: Sign {}",
-                if all_sources.tap_src.tap_sign() == 1 {
-                    "positive"
-                } else {
-                    "negative"
-                }
-            )
             .unwrap();
-
-            msg.clear();
-            if all_sources.tap_src.x_tap() == 1 {
-                writeln!(&mut msg, "{} on X axis", tx_buff).unwrap();
-                tx.blocking_write(msg.as_bytes()).unwrap();
-            }
-
-            msg.clear();
-            if all_sources.wake_up_src.y_wu() == 1 {
-                writeln!(&mut msg, "{} on Y axis", tx_buff).unwrap();
-                tx.blocking_write(msg.as_bytes()).unwrap();
-            }
-
-            msg.clear();
-            if all_sources.wake_up_src.z_wu() == 1 {
-                writeln!(&mut msg, "{} on Z axis", tx_buff).unwrap();
-                tx.blocking_write(msg.as_bytes()).unwrap();
-            }
+            tx.blocking_write(msg.as_bytes()).unwrap();
         }
     }
 }
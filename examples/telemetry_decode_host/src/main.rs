@@ -0,0 +1,46 @@
+use iis2dlpc_rs::telemetry::{telemetry_decode, FRAME_LEN};
+
+/// Frame as received from a sensor node: double tap on X, 1 g on Z at ±2g.
+const SAMPLE_FRAME: [u8; FRAME_LEN] = [
+    0x11, 0x00, 0x54, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x10,
+];
+
+fn main() {
+    // Frames can be passed as hex strings, e.g. `110054000800000000004010`.
+    let frames: Vec<Vec<u8>> = match std::env::args().skip(1).collect::<Vec<_>>() {
+        args if args.is_empty() => vec![SAMPLE_FRAME.to_vec()],
+        args => args.iter().map(|arg| parse_hex(arg)).collect(),
+    };
+
+    for bytes in frames {
+        match telemetry_decode(&bytes) {
+            Ok(frame) => {
+                let [x, y, z] = frame.mg();
+                println!("Acceleration [mg]: {x:4.2}\t{y:4.2}\t{z:4.2}");
+
+                let int_src = frame.sources.all_int_src;
+                if int_src.double_tap() == 1 {
+                    println!("Double tap");
+                } else if int_src.single_tap() == 1 {
+                    println!("Single tap");
+                }
+                if int_src.wu_ia() == 1 {
+                    println!("Wake-up");
+                }
+                if int_src.ff_ia() == 1 {
+                    println!("Free fall");
+                }
+                if int_src.six_d_ia() == 1 {
+                    println!("Orientation change: {:#04x}", frame.sources.sixd_src.into_bits());
+                }
+            }
+            Err(e) => println!("Invalid frame: {e:?}"),
+        }
+    }
+}
+
+fn parse_hex(s: &str) -> Vec<u8> {
+    (0..s.len() / 2)
+        .filter_map(|i| u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok())
+        .collect()
+}
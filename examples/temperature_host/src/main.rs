@@ -0,0 +1,84 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::sample::TempPolicy;
+use iis2dlpc_rs::{from_lsb8_to_celsius, ConfigError, Error, Iis2dlpc};
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+
+    // Temperature data-ready flag
+    check(
+        "flag_temp_data_ready_get: 0 at reset",
+        sensor.flag_temp_data_ready_get() == Ok(0),
+    );
+    // `drdy_t` alone, the acceleration `drdy` clear
+    sensor.bus.set_reg(Reg::StatusDup, 0x40);
+    check(
+        "flag_temp_data_ready_get: drdy_t of STATUS_DUP",
+        sensor.flag_temp_data_ready_get() == Ok(1) && sensor.flag_data_ready_get() == Ok(0),
+    );
+
+    // 35.5 °C: 168 LSB above 25 °C at 16 LSB/°C, left-justified in the 16-bit word
+    set_temperature(&mut sensor.bus, 168);
+    check(
+        "temperature_celsius_get: 35.5 °C",
+        sensor.temperature_celsius_get() == Ok(35.5),
+    );
+    check(
+        "temperature_8bit_get: the high byte, 35 °C",
+        sensor.temperature_8bit_get() == Ok(10)
+            && from_lsb8_to_celsius(10) == 35.0
+            && sensor.temperature_raw_get().map(|raw| raw >> 4) == Ok(10),
+    );
+
+    // -5 °C, 30 °C below the offset
+    set_temperature(&mut sensor.bus, -30 * 16);
+    let before = sensor.bus.transactions();
+    let raw = sensor.temperature_8bit_get();
+    check(
+        "temperature_8bit_get: -5 °C, in a single-byte read",
+        raw == Ok(-30)
+            && raw.map(from_lsb8_to_celsius) == Ok(-5.0)
+            && sensor.bus.transactions() == before + 1,
+    );
+    check(
+        "temperature_celsius_get: -5 °C",
+        sensor.temperature_celsius_get() == Ok(-5.0),
+    );
+
+    // Temperature ignored
+    sensor.temp_policy_set(TempPolicy::Ignore);
+    let before = sensor.bus.transactions();
+    let ignored = || Some(Error::InvalidConfig(ConfigError::TemperatureIgnored));
+    check(
+        "ignored temperature: every read rejected, nothing read",
+        sensor.flag_temp_data_ready_get().err() == ignored()
+            && sensor.temperature_8bit_get().err() == ignored()
+            && sensor.temperature_celsius_get().err() == ignored()
+            && sensor.bus.transactions() == before,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Set the 12-bit temperature `lsb` in `OUT_T_L` and `OUT_T_H`, and its high byte in `OUT_T`, as the device does.
+fn set_temperature(bus: &mut MockBus, lsb: i16) {
+    let [low, high] = (lsb << 4).to_le_bytes();
+    bus.set_reg(Reg::OutTL, low);
+    bus.set_reg(Reg::OutTH, high);
+    bus.set_reg(Reg::OutT, high);
+}
@@ -0,0 +1,106 @@
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::Iis2dlpc;
+
+/// Number of samples read by each strategy.
+const SAMPLES: usize = 1000;
+
+/// Bus time model: name, fixed cost per transaction and cost per byte, in microseconds.
+///
+/// The fixed cost covers the start, device address and stop conditions of an I2C transaction
+/// (9 bit times each) or the chip-select setup and hold of an SPI transaction.
+const BUSES: [(&str, f32, f32); 3] = [
+    ("I2C 100 kHz", 110.0, 90.0),
+    ("I2C 400 kHz", 27.5, 22.5),
+    ("SPI 8 MHz", 2.0, 1.0),
+];
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// Read `SAMPLES` samples with a strategy and return the number of samples actually read.
+type Strategy = fn(&mut Sensor) -> usize;
+
+const STRATEGIES: [(&str, Strategy); 5] = [
+    ("drdy + acceleration_raw_get", polled),
+    ("acceleration_raw_unchecked_get", unchecked),
+    ("drdy + sample_get", sample),
+    ("FIFO drain, watermark 16", fifo_16),
+    ("FIFO drain, watermark 31", fifo_31),
+];
+
+fn main() {
+    print!("| {:<38}", "Strategy");
+    for (bus, _, _) in BUSES {
+        print!(" | {bus:>11}");
+    }
+    println!(" |");
+    print!("|{:-<40}", "");
+    for _ in BUSES {
+        print!("|{:->13}", ":");
+    }
+    println!("|");
+
+    for (name, strategy) in STRATEGIES {
+        print!("| {name:<38}");
+        for (_, fixed_us, per_byte_us) in BUSES {
+            let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+            // Data ready is always set: the bus is the only limit
+            sensor.bus.set_reg(Reg::Status, 0x01);
+            sensor.bus.set_transaction_cost(fixed_us, per_byte_us);
+
+            let samples = strategy(&mut sensor);
+            let rate = samples as f64 * 1e6 / sensor.bus.elapsed_us();
+            print!(" | {rate:>11.0}");
+        }
+        println!(" |");
+    }
+}
+
+fn polled(sensor: &mut Sensor) -> usize {
+    for _ in 0..SAMPLES {
+        while sensor.flag_data_ready_get().unwrap() == 0 {}
+        sensor.acceleration_raw_get().unwrap();
+    }
+    SAMPLES
+}
+
+/// Read at a fixed tick without checking data-ready, repeats accepted.
+fn unchecked(sensor: &mut Sensor) -> usize {
+    for _ in 0..SAMPLES {
+        sensor.acceleration_raw_unchecked_get().unwrap();
+    }
+    SAMPLES
+}
+
+fn sample(sensor: &mut Sensor) -> usize {
+    for _ in 0..SAMPLES {
+        while sensor.flag_data_ready_get().unwrap() == 0 {}
+        sensor.sample_get().unwrap();
+    }
+    SAMPLES
+}
+
+fn fifo_16(sensor: &mut Sensor) -> usize {
+    fifo(sensor, 16)
+}
+
+fn fifo_31(sensor: &mut Sensor) -> usize {
+    fifo(sensor, 31)
+}
+
+/// Drain the FIFO each time the watermark is reached; the watermark is signalled by an
+/// interrupt, so no bus traffic is spent waiting for it.
+fn fifo(sensor: &mut Sensor, watermark: u8) -> usize {
+    sensor.fifo_watermark_set(watermark).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    // `DIFF` is the low 6 bits of `FIFO_SAMPLES`
+    sensor.bus.set_reg(Reg::FifoSamples, watermark);
+    sensor.bus.cost_reset();
+
+    let mut buf = [0; 32 * 6];
+    let mut samples = 0;
+    while samples < SAMPLES {
+        samples += sensor.fifo_drain(&mut buf, false).unwrap().samples;
+    }
+    samples
+}
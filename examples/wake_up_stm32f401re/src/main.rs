@@ -3,6 +3,7 @@
 
 use core::fmt::Write;
 
+use iis2dlpc_rs::config::Config as SensorConfig;
 use iis2dlpc_rs::{prelude::*, I2CAddress, Iis2dlpc, PROPERTY_ENABLE};
 
 use panic_itm as _;
@@ -57,28 +58,19 @@ fn main() -> ! {
 
     let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay);
 
-    match sensor.device_id_get() {
-        Ok(value) => {
-            if value != iis2dlpc_rs::ID {
-                panic!("Invalid sensor ID")
-            }
-        }
-        Err(e) => writeln!(tx, "An error occured while reading sensor ID: {e:?}").unwrap(),
-    }
     sensor.tim.delay_ms(25);
 
-    // Restore default configuration
-    sensor.reset_set().unwrap();
-    while sensor.reset_get().unwrap() == 1 {}
-
-    // Set Full scale
-    sensor.full_scale_set(Fs::_2g).unwrap();
-    // Configure power mode
+    // Check the device identifier, restore default configuration and enable Block Data Update
+    sensor.init().unwrap();
+    // Set Full scale, power mode and Output Data Rate
     sensor
-        .power_mode_set(Mode::ContLowPwrLowNoise12bit)
+        .apply_config(&SensorConfig {
+            odr: Odr::_200hz,
+            fs: Fs::_2g,
+            mode: Mode::ContLowPwrLowNoise12bit,
+            ..Default::default()
+        })
         .unwrap();
-    // Set Output Data Rate
-    sensor.data_rate_set(Odr::_200hz).unwrap();
     // Apply hogh-pass digital filter on Wake-Up function
     // Duration time is set to zero so Wake-Up interrupt signal
     // is generated for each X,Y,Z filtered data exceeding the
@@ -0,0 +1,92 @@
+use std::process::ExitCode;
+
+use iis2dlpc_rs::mock::{MockBus, NoDelay};
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::{ConfigError, Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    // Threshold, 1 LSB = FS / 64
+    let mut sensor = Sensor::from_bus(MockBus::new(), NoDelay);
+    check(
+        "2 g, 500 mg: code 16, 500 mg",
+        sensor.wkup_threshold_mg_set(500.0) == Ok(500.0) && sensor.wkup_threshold_get() == Ok(16),
+    );
+    check(
+        "2 g, 510 mg: nearest code 16, 500 mg",
+        sensor.wkup_threshold_mg_set(510.0) == Ok(500.0) && sensor.wkup_threshold_get() == Ok(16),
+    );
+    check(
+        "2 g, 5000 mg: clamped to code 63, 1968.75 mg",
+        sensor.wkup_threshold_mg_set(5000.0) == Ok(1968.75)
+            && sensor.wkup_threshold_get() == Ok(63),
+    );
+    check(
+        "negative: clamped to code 0",
+        sensor.wkup_threshold_mg_set(-40.0) == Ok(0.0) && sensor.wkup_threshold_get() == Ok(0),
+    );
+    sensor.wkup_threshold_set(5).unwrap();
+    check(
+        "NaN: invalid argument, nothing written",
+        sensor.wkup_threshold_mg_set(f32::NAN) == Err(Error::InvalidArgument)
+            && sensor.wkup_threshold_get() == Ok(5),
+    );
+    sensor.full_scale_set(Fs::_8g).unwrap();
+    check(
+        "8 g, 1000 mg: code 8, 1000 mg",
+        sensor.wkup_threshold_mg_set(1000.0) == Ok(1000.0) && sensor.wkup_threshold_get() == Ok(8),
+    );
+    check(
+        "wkup_threshold_mg_get: 1000 mg",
+        sensor.wkup_threshold_mg_get() == Ok(1000.0),
+    );
+    sensor.full_scale_set(Fs::_2g).unwrap();
+    check(
+        "full scale changed to 2 g: same code, 250 mg",
+        sensor.wkup_threshold_mg_get() == Ok(250.0),
+    );
+
+    // Durations, 100 Hz
+    check(
+        "data rate off: durations not defined",
+        sensor.wkup_dur_ms_get() == Err(Error::InvalidConfig(ConfigError::DataRateNotRunning)),
+    );
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.wkup_dur_set(2).unwrap();
+    check(
+        "wkup_dur_ms_get, code 2: 20 ms",
+        sensor.wkup_dur_ms_get() == Ok(20),
+    );
+    check(
+        "wkup_dur_ms_set(30): 30 ms, read back",
+        sensor.wkup_dur_ms_set(30) == Ok(30) && sensor.wkup_dur_ms_get() == Ok(30),
+    );
+    sensor.act_sleep_dur_set(0).unwrap();
+    let code_0 = sensor.act_sleep_dur_ms_get();
+    sensor.act_sleep_dur_set(1).unwrap();
+    check(
+        "act_sleep_dur_ms_get: 160 ms for code 0, 5120 ms for code 1",
+        code_0 == Ok(160) && sensor.act_sleep_dur_ms_get() == Ok(5120),
+    );
+    check(
+        "act_sleep_dur_ms_set(10240): 10240 ms, read back",
+        sensor.act_sleep_dur_ms_set(10240) == Ok(10240)
+            && sensor.act_sleep_dur_ms_get() == Ok(10240),
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
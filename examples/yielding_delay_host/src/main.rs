@@ -0,0 +1,140 @@
+use std::cell::Cell;
+use std::process::ExitCode;
+
+use embedded_hal::delay::DelayNs;
+use iis2dlpc_rs::errata::{Workarounds, FILTER_DISCARD_SAMPLES};
+use iis2dlpc_rs::mock::MockBus;
+use iis2dlpc_rs::prelude::*;
+use iis2dlpc_rs::selftest::SelfTestConfig;
+use iis2dlpc_rs::wait::{WaitStrategy, YieldingDelay};
+use iis2dlpc_rs::Iis2dlpc;
+
+/// Longest spin between two yields, in microseconds.
+const SLICE_US: u32 = 1000;
+
+/// Data period at 100 Hz, in microseconds.
+const PERIOD_US: u32 = 10_000;
+
+fn main() -> ExitCode {
+    let mut failures = 0;
+    let mut check = |name: &str, ok: bool| {
+        println!("{} {name}", if ok { "PASS" } else { "FAIL" });
+        if !ok {
+            failures += 1;
+        }
+    };
+
+    for (us, yields) in [(0, 0), (1, 1), (999, 1), (1000, 1), (1001, 2), (25_500, 26)] {
+        let (spun, yielded) = wait(|delay| delay.wait_us(us));
+        check(
+            &format!("wait of {us} µs: {yielded} yields, {spun} ns spun"),
+            yielded == yields && spun == u64::from(us) * 1000,
+        );
+    }
+    let (spun, yielded) = wait(|delay| delay.wait_ms(20));
+    check(
+        &format!("wait of 20 ms: {yielded} yields"),
+        yielded == 20 && spun == 20_000_000,
+    );
+
+    // Settle after a mode switch: one data period
+    let (spun, yielded) = settle(Workarounds::MODE_SWITCH_SETTLE, |sensor| {
+        sensor.power_mode_set(Mode::ContLowPwr4).unwrap();
+    });
+    check(
+        &format!("mode switch settle: {yielded} yields"),
+        yielded == PERIOD_US / SLICE_US && spun == u64::from(PERIOD_US) * 1000,
+    );
+
+    // Discarded samples after a filter change
+    let (spun, yielded) = settle(Workarounds::FILTER_DISCARD, |sensor| {
+        sensor.filter_bandwidth_set(BwFilt::OdrDiv10).unwrap();
+    });
+    let discard_us = PERIOD_US * FILTER_DISCARD_SAMPLES;
+    check(
+        &format!("filter change settle: {yielded} yields"),
+        yielded == discard_us / SLICE_US && spun == u64::from(discard_us) * 1000,
+    );
+
+    // Self-test: each of the 3 phases settles, then waits a 50 Hz period before every sample
+    let cfg = SelfTestConfig::default();
+    let (spun, yielded) = settle(Workarounds::NONE, |sensor| {
+        sensor.self_test_full(&cfg).unwrap();
+    });
+    let phase_us = cfg.settle_ms * 1000 + u32::from(cfg.discard + cfg.samples) * 20_000;
+    check(
+        &format!("self-test settle: {yielded} yields"),
+        yielded == 3 * phase_us / SLICE_US && spun == 3 * u64::from(phase_us) * 1000,
+    );
+
+    // Without workaround nothing waits
+    let (spun, yielded) = settle(Workarounds::NONE, |sensor| {
+        sensor.power_mode_set(Mode::ContLowPwr4).unwrap();
+        sensor.filter_bandwidth_set(BwFilt::OdrDiv10).unwrap();
+    });
+    check("no workaround: no wait", yielded == 0 && spun == 0);
+
+    // The default strategy blocks for the same time, in one spin
+    let spun = Cell::new(0);
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), SpinClock { spun: &spun });
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.workarounds_set(Workarounds::MODE_SWITCH_SETTLE);
+    sensor.power_mode_set(Mode::ContLowPwr4).unwrap();
+    check(
+        "blocking delay: same settle time",
+        spun.get() == u64::from(PERIOD_US) * 1000,
+    );
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Run `f` on a yielding delay and return the time spun, in nanoseconds, and the number of yields.
+fn wait(f: impl FnOnce(&mut YieldingDelay<SpinClock<'_>, &mut dyn FnMut()>)) -> (u64, u32) {
+    let spun = Cell::new(0);
+    let yielded = Cell::new(0);
+    let mut yield_now = || yielded.set(yielded.get() + 1);
+    let mut delay = YieldingDelay::new(
+        SpinClock { spun: &spun },
+        SLICE_US,
+        &mut yield_now as &mut dyn FnMut(),
+    );
+    f(&mut delay);
+    (spun.get(), yielded.get())
+}
+
+/// Run a driver call at 100 Hz with a workaround and return the time spun and the number of yields.
+fn settle(
+    workarounds: Workarounds,
+    f: impl FnOnce(&mut Iis2dlpc<MockBus, YieldingDelay<SpinClock<'_>, &mut dyn FnMut()>>),
+) -> (u64, u32) {
+    let spun = Cell::new(0);
+    let yielded = Cell::new(0);
+    let mut yield_now = || yielded.set(yielded.get() + 1);
+    let delay = YieldingDelay::new(
+        SpinClock { spun: &spun },
+        SLICE_US,
+        &mut yield_now as &mut dyn FnMut(),
+    );
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), delay);
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.workarounds_set(workarounds);
+    spun.set(0);
+    yielded.set(0);
+    f(&mut sensor);
+    (spun.get(), yielded.get())
+}
+
+/// Delay that only accounts for the time it would spin.
+struct SpinClock<'a> {
+    spun: &'a Cell<u64>,
+}
+
+impl DelayNs for SpinClock<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.spun.set(self.spun.get() + u64::from(ns));
+    }
+}
@@ -0,0 +1,127 @@
+//! Activity and inactivity detection in a single call.
+//!
+//! [`ActivityConfig`] gathers the settings of the sleep and wake-up chain that the individual
+//! setters split over `WAKE_UP_THS`, `WAKE_UP_DUR` and `CTRL7`;
+//! [`crate::Iis2dlpc::activity_config_set`] writes each register once, and
+//! [`crate::Iis2dlpc::activity_transition_get`] reports the changes of state:
+//!
+//! ```ignore
+//! sensor.activity_config_set(
+//!     &ActivityConfig {
+//!         mode: SleepOn::ActInact,
+//!         wake_dur: 2,
+//!         sleep_dur: 2,
+//!         sleep_change_on_int2: true,
+//!         ..Default::default()
+//!     }
+//!     .with_wake_threshold_mg(62.5, Fs::_2g),
+//! )?;
+//! match sensor.activity_transition_get()? {
+//!     Some(ActivityTransition::ToInactive) => { /* asleep */ }
+//!     Some(ActivityTransition::ToActive) => { /* awake */ }
+//!     None => {}
+//! }
+//! ```
+//!
+//! The durations are codes counted in ODR periods, see [`crate::timing::DurationField`].
+
+use crate::prelude::*;
+
+/// Highest code of the 6-bit wake-up threshold.
+const THRESHOLD_MAX: u8 = 0x3F;
+
+/// Activity detection settings written by [`crate::Iis2dlpc::activity_config_set`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ActivityConfig {
+    /// Detection mode: `sleep_on` in `WAKE_UP_THS` and `stationary` in `WAKE_UP_DUR`.
+    pub mode: SleepOn,
+    /// Wake-up threshold, `wk_ths` in `WAKE_UP_THS`: 1 LSB = FS / 64.
+    pub wake_threshold: u8,
+    /// Wake-up duration, `wake_dur` in `WAKE_UP_DUR`: 1 LSB = 1 / ODR.
+    pub wake_dur: u8,
+    /// Duration to go to sleep, `sleep_dur` in `WAKE_UP_DUR`: 0 = 16 / ODR, otherwise
+    /// 1 LSB = 512 / ODR.
+    pub sleep_dur: u8,
+    /// Data sent to the wake-up function, `usr_off_on_wu` in `CTRL7`.
+    pub feed: UsrOffOnWu,
+    /// Route the sleep change event to the INT2 pad, `int2_sleep_chg` in `CTRL5_INT2_PAD_CTRL`;
+    /// `false` leaves the routing as it is.
+    pub sleep_change_on_int2: bool,
+}
+
+impl ActivityConfig {
+    /// Set the wake-up threshold in mg for a full scale.
+    ///
+    /// The threshold becomes the nearest code, 1 LSB = FS / 64, clamped to the 6-bit field: a
+    /// request above 63 / 64 of the full scale gives code 63, a negative one or not a number
+    /// gives code 0. See [`Self::wake_threshold_mg`] for the threshold actually programmed.
+    ///
+    /// ### Arguments
+    /// - `mg`: The wake-up threshold in mg.
+    /// - `fs`: The full scale the sensor runs at.
+    pub fn with_wake_threshold_mg(mut self, mg: f32, fs: Fs) -> Self {
+        // Nearest code; the cast saturates a negative request and not a number to 0
+        self.wake_threshold =
+            ((mg / fs.wkup_threshold_mg_per_lsb() + 0.5) as u8).min(THRESHOLD_MAX);
+        self
+    }
+
+    /// Get the wake-up threshold in mg for a full scale.
+    ///
+    /// ### Arguments
+    /// - `fs`: The full scale the sensor runs at.
+    ///
+    /// ### Returns
+    /// - `f32`: The wake-up threshold in mg.
+    pub fn wake_threshold_mg(&self, fs: Fs) -> f32 {
+        self.wake_threshold as f32 * fs.wkup_threshold_mg_per_lsb()
+    }
+}
+
+/// State of the activity detection, see [`crate::Iis2dlpc::activity_state_get`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ActivityState {
+    /// Motion detected, or no detection configured.
+    Active,
+    /// No motion for the sleep duration.
+    Inactive,
+}
+
+/// Change of the activity state, see [`crate::Iis2dlpc::activity_transition_get`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ActivityTransition {
+    /// The device left the sleep state.
+    ToActive,
+    /// The device entered the sleep state.
+    ToInactive,
+}
+
+impl ActivityTransition {
+    /// Decode the change of state of a read of every source register.
+    ///
+    /// ### Arguments
+    /// - `sources`: The source registers, see [`crate::Iis2dlpc::all_sources_get`].
+    ///
+    /// ### Returns
+    /// - `Some(ActivityTransition)`: If `sleep_change_ia` is set in `ALL_INT_SRC`; the direction is
+    ///   the `sleep_state_ia` field in `WAKE_UP_SRC`.
+    /// - `None`: If the state did not change.
+    pub fn decode(sources: &AllSources) -> Option<Self> {
+        if sources.all_int_src.sleep_change_ia() == 0 {
+            return None;
+        }
+        Some(if sources.wake_up_src.sleep_state_ia() == 1 {
+            Self::ToInactive
+        } else {
+            Self::ToActive
+        })
+    }
+
+    /// Get the state the device changed to.
+    pub fn state(&self) -> ActivityState {
+        match self {
+            Self::ToActive => ActivityState::Active,
+            Self::ToInactive => ActivityState::Inactive,
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Data rate switching on motion.
+//!
+//! An [`AdaptiveRate`] runs the device at a high data rate while it moves and at a low one while
+//! it is still. The device detects the stationary state with the stationary/motion function
+//! ([`SleepOn::StatMotion`]), which leaves the data rate alone; the helper reads the sleep state
+//! from `STATUS_DUP` and switches the data rate with
+//! [`crate::Iis2dlpc::data_rate_set_retuned`], so that the wake-up and sleep durations keep their
+//! length in milliseconds.
+//!
+//! ```ignore
+//! let mut adaptive = AdaptiveRate::new(AdaptiveRateConfig {
+//!     active_odr: Odr::_400hz,
+//!     inactive_odr: Odr::_12_5hz,
+//!     wake_threshold: 2,
+//!     sleep_dur: 1,
+//!     hysteresis: 3,
+//! });
+//! adaptive.start(&mut sensor)?;
+//! loop {
+//!     if let Some(change) = adaptive.service(&mut sensor)? { /* .. */ }
+//!     // ..
+//! }
+//! ```
+//!
+//! The helper owns no bus: it only keeps the current data rate and a hysteresis counter.
+
+use crate::prelude::*;
+use crate::timing::RetuneReport;
+use crate::{BusOperation, ConfigError, DelayNs, Error, Iis2dlpc, field_check};
+
+/// Configuration of an [`AdaptiveRate`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AdaptiveRateConfig {
+    /// Data rate while the device moves.
+    pub active_odr: Odr,
+    /// Data rate while the device is still.
+    pub inactive_odr: Odr,
+    /// Motion threshold, `wk_ths` code (1 LSB = FS / 64), see [`crate::Iis2dlpc::wkup_threshold_set`].
+    pub wake_threshold: u8,
+    /// Time without motion before the still state, `sleep_dur` code, see [`crate::Iis2dlpc::act_sleep_dur_set`].
+    pub sleep_dur: u8,
+    /// Number of consecutive [`AdaptiveRate::service`] calls that must see the new state before switching;
+    /// `0` and `1` switch at once.
+    pub hysteresis: u8,
+}
+
+/// Data rate change performed by [`AdaptiveRate::service`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct RateChange {
+    /// Data rate before the change.
+    pub from: Odr,
+    /// Data rate after the change.
+    pub to: Odr,
+    /// `true` if the device entered the still state.
+    pub sleeping: bool,
+    /// Retuning of the duration fields.
+    pub retune: RetuneReport,
+}
+
+/// Data rate switching on motion.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AdaptiveRate {
+    config: AdaptiveRateConfig,
+    current: Odr,
+    pending: u8,
+}
+
+impl AdaptiveRate {
+    /// Create the helper; nothing is written before [`Self::start`].
+    pub fn new(config: AdaptiveRateConfig) -> Self {
+        Self {
+            config,
+            current: config.active_odr,
+            pending: 0,
+        }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &AdaptiveRateConfig {
+        &self.config
+    }
+
+    /// Get the data rate the helper last programmed.
+    pub fn current_odr(&self) -> Odr {
+        self.current
+    }
+
+    /// Configure the device and start at the active data rate.
+    ///
+    /// This function enables the stationary/motion detection, writes the motion threshold and the sleep
+    /// duration, then the active data rate.
+    ///
+    /// ### Arguments
+    /// - `sensor`: The driver.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidConfig)`: If a configured data rate is off or triggered on demand, or cannot be used
+    ///   with the current mode; nothing is written.
+    /// - `Err(Error::InvalidArgument)`: If the threshold or the duration code does not fit in its field; nothing is
+    ///   accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn start<B: BusOperation, T: DelayNs>(
+        &mut self,
+        sensor: &mut Iis2dlpc<B, T>,
+    ) -> Result<(), Error<B::Error>> {
+        let AdaptiveRateConfig {
+            active_odr,
+            inactive_odr,
+            ..
+        } = self.config;
+        if active_odr.period_us().is_none() || inactive_odr.period_us().is_none() {
+            return Err(Error::InvalidConfig(ConfigError::DataRateNotRunning));
+        }
+        field_check(self.config.wake_threshold, 6)?;
+        field_check(self.config.sleep_dur, 4)?;
+
+        sensor.act_mode_set(SleepOn::StatMotion)?;
+        sensor.wkup_threshold_set(self.config.wake_threshold)?;
+        sensor.act_sleep_dur_set(self.config.sleep_dur)?;
+        sensor.data_rate_set(active_odr)?;
+
+        self.current = active_odr;
+        self.pending = 0;
+        Ok(())
+    }
+
+    /// Switch the data rate if the motion state changed.
+    ///
+    /// This function reads the `sleep_state_ia` field in the `STATUS_DUP` register, which reflects the current
+    /// state and clears no latched interrupt, so it can be called from an event loop that also drains events.
+    /// The data rate changes once the new state has been seen by [`AdaptiveRateConfig::hysteresis`]
+    /// consecutive calls; a state that flips back earlier resets the count.
+    ///
+    /// ### Arguments
+    /// - `sensor`: The driver.
+    ///
+    /// ### Returns
+    /// - `Ok(Some(RateChange))`: The data rate change written.
+    /// - `Ok(None)`: If the data rate is unchanged.
+    /// - `Err(Error::InvalidConfig)`: If the new data rate cannot be used with the current mode; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn service<B: BusOperation, T: DelayNs>(
+        &mut self,
+        sensor: &mut Iis2dlpc<B, T>,
+    ) -> Result<Option<RateChange>, Error<B::Error>> {
+        let sleeping = StatusDup::read(sensor)?.sleep_state_ia() == 1;
+        let target = if sleeping {
+            self.config.inactive_odr
+        } else {
+            self.config.active_odr
+        };
+
+        if target == self.current {
+            self.pending = 0;
+            return Ok(None);
+        }
+        self.pending = self.pending.saturating_add(1);
+        if self.pending < self.config.hysteresis {
+            return Ok(None);
+        }
+
+        let retune = sensor.data_rate_set_retuned(target)?;
+        let change = RateChange {
+            from: self.current,
+            to: target,
+            sleeping,
+            retune,
+        };
+        self.current = target;
+        self.pending = 0;
+        Ok(Some(change))
+    }
+}
@@ -0,0 +1,955 @@
+//! Async driver sharing its state with the blocking one.
+//!
+//! [`Iis2dlpcAsync`] performs the register accesses through an [`AsyncBusOperation`] and keeps
+//! the same driver-side state as [`Iis2dlpc`]: settings, register copies, configuration epoch,
+//! counters and hooks. The conversions between the two drivers carry that state over
+//! unchanged, so that a configuration applied by a blocking stage goes on in an async one:
+//!
+//! ```ignore
+//! // Blocking stage
+//! let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay);
+//! sensor.config_snapshot_apply(&CONFIG)?;
+//!
+//! // Async stage: the async peripheral takes over the same device
+//! let mut sensor = sensor.into_async(|_| I2cBusAsync::new(async_i2c, I2CAddress::I2cAddH as u8));
+//! let id = sensor.device_id_get().await?;
+//! ```
+//!
+//! The async driver goes through the same write and read choke points: the scaling lock, the
+//! data-path tracking, the pad routing copies and the hooks behave as with
+//! [`Iis2dlpc::write_to_register`] and [`Iis2dlpc::read_from_register`].
+//!
+//! The data path is mirrored with the same checks and return values as the blocking functions:
+//! start-up ([`Iis2dlpcAsync::init`], the software reset), operating mode, data rate, full scale,
+//! output and source registers, and the FIFO. The waits, of [`Iis2dlpcAsync::init`] and of the
+//! mode switch workaround, go through an [`embedded_hal_async::delay::DelayNs`] timer:
+//!
+//! ```ignore
+//! let mut sensor = Iis2dlpcAsync::from_bus(I2cBusAsync::new(i2c, I2CAddress::I2cAddH as u8), Delay);
+//! sensor.init().await?;
+//! sensor.power_mode_set(Mode::ContLowPwrLowNoise12bit).await?;
+//! sensor.data_rate_set(Odr::_25hz).await?;
+//! let raw = sensor.acceleration_raw_get().await?;
+//! ```
+
+use embedded_hal_async::delay::DelayNs as AsyncDelayNs;
+use embedded_hal_async::i2c::{I2c, Operation as I2cOperation, SevenBitAddress};
+use embedded_hal_async::spi::{Operation as SpiOperation, SpiDevice};
+
+#[cfg(feature = "events")]
+use crate::RouteShadow;
+use crate::errata::Workarounds;
+#[cfg(feature = "events")]
+use crate::event::{self, Event, EventCounters, EventsDrainReport, PendingEvents};
+#[cfg(feature = "storm-guard")]
+use crate::event::{EventRoute, StormPolicy};
+#[cfg(feature = "fifo")]
+use crate::field_check;
+use crate::fifo::DataPath;
+#[cfg(feature = "fifo")]
+use crate::fifo::{FIFO_DEPTH, FifoFlags};
+use crate::prelude::*;
+use crate::settle::{self, SettleHint};
+use crate::snapshot::{CONFIG_REGS, ConfigSnapshot};
+use crate::transaction::TransactionKind;
+use crate::{
+    BusOperation, BusSanity, DecodeRetry, DriverState, Error, Iis2dlpc, PROPERTY_ENABLE,
+    RESET_MAX_POLLS, RESET_POLL_US, batch, mode_odr_check,
+};
+#[cfg(feature = "storm-guard")]
+use crate::{ConfigError, PROPERTY_DISABLE, TempPolicy, interrupts_enable_for};
+
+/// Register access over an async bus.
+#[allow(async_fn_in_trait)]
+pub trait AsyncBusOperation {
+    type Error;
+
+    /// Read consecutive registers starting at `reg`.
+    async fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Write consecutive registers starting at `reg`.
+    async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Async I²C bus of the device.
+#[derive(Debug)]
+pub struct I2cBusAsync<P> {
+    instance: P,
+    address: SevenBitAddress,
+}
+
+impl<P: I2c> I2cBusAsync<P> {
+    /// Wrap an async I²C peripheral.
+    ///
+    /// ### Arguments
+    /// - `instance`: The I²C peripheral.
+    /// - `address`: The 7-bit address of the device, see [`crate::I2CAddress`].
+    pub fn new(instance: P, address: SevenBitAddress) -> Self {
+        Self { instance, address }
+    }
+
+    /// Give back the I²C peripheral.
+    pub fn release(self) -> P {
+        self.instance
+    }
+}
+
+impl<P: I2c> AsyncBusOperation for I2cBusAsync<P> {
+    type Error = P::Error;
+
+    async fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.instance.write_read(self.address, &[reg], buf).await
+    }
+
+    async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        // Adjacent writes of a transaction are sent as a single one
+        self.instance
+            .transaction(
+                self.address,
+                &mut [I2cOperation::Write(&[reg]), I2cOperation::Write(buf)],
+            )
+            .await
+    }
+}
+
+/// Async SPI bus of the device.
+#[derive(Debug)]
+pub struct SpiBusAsync<P> {
+    instance: P,
+}
+
+impl<P: SpiDevice> SpiBusAsync<P> {
+    /// Wrap an async SPI device.
+    pub fn new(instance: P) -> Self {
+        Self { instance }
+    }
+
+    /// Give back the SPI device.
+    pub fn release(self) -> P {
+        self.instance
+    }
+}
+
+impl<P: SpiDevice> AsyncBusOperation for SpiBusAsync<P> {
+    type Error = P::Error;
+
+    async fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.instance
+            .transaction(&mut [SpiOperation::Write(&[reg | 0x80]), SpiOperation::Read(buf)])
+            .await
+    }
+
+    async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        self.instance
+            .transaction(&mut [SpiOperation::Write(&[reg]), SpiOperation::Write(buf)])
+            .await
+    }
+}
+
+/// The Iis2dlpc async driver struct.
+#[derive(Debug)]
+pub struct Iis2dlpcAsync<B, T> {
+    /// The bus driver.
+    pub bus: B,
+    pub tim: T,
+    state: DriverState,
+}
+
+impl<B: AsyncBusOperation, T> Iis2dlpcAsync<B, T> {
+    /// Create the driver with a fresh driver-side state.
+    ///
+    /// ### Arguments
+    /// - `bus`: The async bus of the device.
+    /// - `tim`: The timer.
+    pub fn from_bus(bus: B, tim: T) -> Self {
+        Self::from_parts(bus, tim, DriverState::new())
+    }
+
+    pub(crate) fn from_parts(bus: B, tim: T, state: DriverState) -> Self {
+        Self { bus, tim, state }
+    }
+
+    /// Convert into the blocking driver, keeping the driver-side state.
+    ///
+    /// This is the inverse of [`Iis2dlpc::into_async`]. The blocking driver starts without a bus prelude, see
+    /// [`Iis2dlpc::bus_prelude_set`]: a bus selecting its multiplexer channel by itself, as
+    /// [`crate::mux::MuxedI2cBus`] does, keeps the channel across the conversion.
+    ///
+    /// ### Arguments
+    /// - `f`: The conversion of the bus.
+    ///
+    /// ### Returns
+    /// - `Iis2dlpc<BB, T>`: The blocking driver, with the same timer.
+    pub fn into_blocking<BB: BusOperation>(self, f: impl FnOnce(B) -> BB) -> Iis2dlpc<BB, T> {
+        Iis2dlpc {
+            bus: f(self.bus),
+            tim: self.tim,
+            state: self.state,
+            bus_prelude: None,
+        }
+    }
+
+    /// Power the device down and give back the bus and the timer, see [`Iis2dlpc::release`].
+    ///
+    /// ### Returns
+    /// - `(B, T, Result<(), Error>)`: The bus, the timer and the outcome of the power-down, as returned by
+    ///   `data_rate_set`; on an error the device may still be converting.
+    pub async fn release(mut self) -> (B, T, Result<(), Error<B::Error>>) {
+        let powered_down = self.data_rate_set(Odr::Off).await.map(|_| ());
+        let (bus, tim) = self.release_without_powerdown();
+        (bus, tim, powered_down)
+    }
+
+    /// Give back the bus and the timer, leaving the device as configured, see
+    /// [`Iis2dlpc::release_without_powerdown`].
+    pub fn release_without_powerdown(self) -> (B, T) {
+        (self.bus, self.tim)
+    }
+
+    pub async fn read_from_register(
+        &mut self,
+        reg: u8,
+        buf: &mut [u8],
+    ) -> Result<(), Error<B::Error>> {
+        let info = self
+            .state
+            .transaction_begin(TransactionKind::Read, reg, buf)?;
+        let result = self.bus.read_from_register(reg, buf).await;
+        self.state.transaction_end(&info, buf, result)
+    }
+
+    pub async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Error<B::Error>> {
+        let info = self
+            .state
+            .transaction_begin(TransactionKind::Write, reg, buf)?;
+        let result = self.bus.write_to_register(reg, buf).await;
+        self.state.transaction_end(&info, buf, result)
+    }
+
+    /// Get the device ID.
+    ///
+    /// This function retrieves the device ID from the `WHO_AM_I` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The device ID (expected value: [`crate::ID`]).
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn device_id_get(&mut self) -> Result<u8, Error<B::Error>> {
+        let mut buf = [0];
+        self.read_from_register(Reg::WhoAmI as u8, &mut buf).await?;
+        Ok(buf[0])
+    }
+
+    /// Check that the bus reaches the device, see [`Iis2dlpc::bus_sanity_check`].
+    ///
+    /// ### Returns
+    /// - `Ok(BusSanity)`: The device answers with its identifier, or every register reads `0xFF` or `0x00`.
+    /// - `Err(Error::WhoAmIError)`: If `WHO_AM_I` holds another value: another device, or a partly stuck bus.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn bus_sanity_check(&mut self) -> Result<BusSanity, Error<B::Error>> {
+        let id = self.device_id_get().await?;
+        let ctrl2 = self.reg_get(Reg::Ctrl2).await?;
+        BusSanity::classify(id, ctrl2)
+    }
+
+    /// Perform a software reset, see [`Iis2dlpc::reset_set`].
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn reset_set(&mut self) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::from_bits(self.reg_get(Reg::Ctrl2).await?);
+        ctrl2.set_soft_reset(PROPERTY_ENABLE);
+        self.reg_set(Reg::Ctrl2, ctrl2.into_bits()).await
+    }
+
+    /// Get the software reset status, see [`Iis2dlpc::reset_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current value of the `soft_reset` field, `1` while the reset is in progress.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn reset_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(Ctrl2::from_bits(self.reg_get(Reg::Ctrl2).await?).soft_reset())
+    }
+
+    /// Get the accelerometer operating mode, see [`Iis2dlpc::power_mode_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(Mode)`: The current operating mode.
+    /// - `Err(Error::UnexpectedValue)`: If the `mode` field holds the reserved code (3), after the retry of
+    ///   [`Iis2dlpc::decode_retry_set`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn power_mode_get(&mut self) -> Result<Mode, Error<B::Error>> {
+        self.read_decoded_with(
+            async |sensor: &mut Self| {
+                let ctrl1 = Ctrl1::from_bits(sensor.reg_get(Reg::Ctrl1).await?);
+                let ctrl6 = Ctrl6::from_bits(sensor.reg_get(Reg::Ctrl6).await?);
+                Ok(Mode::code(ctrl1.mode(), ctrl1.lp_mode(), ctrl6.low_noise()))
+            },
+            |raw| Mode::try_from(raw).ok(),
+        )
+        .await
+    }
+
+    /// Set the accelerometer data rate, see [`Iis2dlpc::data_rate_set`].
+    ///
+    /// ### Arguments
+    /// - `val`: A [`Odr`] value representing the desired data rate and sleep mode configuration.
+    ///
+    /// ### Returns
+    /// - `Ok(SettleHint)`: The wait before the output is filtered at the new data rate, see [`settle`].
+    /// - `Err(Error::InvalidArgument)`: If the data rate cannot be used with the current mode; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn data_rate_set(&mut self, val: Odr) -> Result<SettleHint, Error<B::Error>> {
+        mode_odr_check(self.power_mode_get().await?, val)?;
+
+        let mut ctrl1 = Ctrl1::from_bits(self.reg_get(Reg::Ctrl1).await?);
+        let mut changed = ctrl1.odr() != val.odr();
+        ctrl1.set_odr(val.odr());
+        self.reg_set(Reg::Ctrl1, ctrl1.into_bits()).await?;
+
+        let mut ctrl3 = Ctrl3::from_bits(self.reg_get(Reg::Ctrl3).await?);
+        changed |= ctrl3.slp_mode() != val.slp_mode();
+        ctrl3.set_slp_mode(val.slp_mode());
+        self.reg_set(Reg::Ctrl3, ctrl3.into_bits()).await?;
+
+        match val.period_us() {
+            Some(period_us) if changed => {
+                let ctrl6 = Ctrl6::from_bits(self.reg_get(Reg::Ctrl6).await?);
+                let bw = BwFilt::try_from(ctrl6.bw_filt()).unwrap_or_default();
+                Ok(settle::filter_hint(bw, period_us, 0))
+            }
+            _ => Ok(SettleHint::None),
+        }
+    }
+
+    /// Get the accelerometer data rate, see [`Iis2dlpc::data_rate_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(Odr)`: The current data rate.
+    /// - `Err(Error::UnexpectedValue)`: If the `odr` field holds a reserved code (10 to 15), after the retry of
+    ///   [`Iis2dlpc::decode_retry_set`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn data_rate_get(&mut self) -> Result<Odr, Error<B::Error>> {
+        self.read_decoded_with(
+            async |sensor: &mut Self| {
+                let ctrl1 = Ctrl1::from_bits(sensor.reg_get(Reg::Ctrl1).await?);
+                let ctrl3 = Ctrl3::from_bits(sensor.reg_get(Reg::Ctrl3).await?);
+                Ok(Odr::code(ctrl1.odr(), ctrl3.slp_mode()))
+            },
+            Odr::decode,
+        )
+        .await
+    }
+
+    /// Set the accelerometer full-scale selection, see [`Iis2dlpc::full_scale_set`].
+    ///
+    /// ### Arguments
+    /// - `val`: A [`Fs`] value representing the desired full-scale range.
+    ///
+    /// ### Returns
+    /// - `Ok(SettleHint)`: The wait before the samples are converted with the new scale, see [`settle`].
+    /// - `Err(Error::ScalingLocked)`: If the scaling is locked, see [`Iis2dlpc::strict_scaling_lock`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn full_scale_set(&mut self, val: Fs) -> Result<SettleHint, Error<B::Error>> {
+        let mut ctrl6 = Ctrl6::from_bits(self.reg_get(Reg::Ctrl6).await?);
+        let changed = ctrl6.fs() != val as u8;
+        ctrl6.set_fs(val as u8);
+        self.reg_set(Reg::Ctrl6, ctrl6.into_bits()).await?;
+
+        self.pending_samples_hint(changed).await
+    }
+
+    /// Get the accelerometer full-scale selection, see [`Iis2dlpc::full_scale_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(Fs)`: The current full-scale range.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn full_scale_get(&mut self) -> Result<Fs, Error<B::Error>> {
+        let ctrl6 = Ctrl6::from_bits(self.reg_get(Reg::Ctrl6).await?);
+        Ok(Fs::try_from(ctrl6.fs()).unwrap_or_default())
+    }
+
+    /// Get the raw acceleration data, see [`Iis2dlpc::acceleration_raw_get`].
+    ///
+    /// The six output registers are read with a single 6-byte burst and the axes are remapped to the board
+    /// frame, see [`Iis2dlpc::axis_remap_set`].
+    ///
+    /// ### Returns
+    /// - `Ok([i16; 3])`: The raw acceleration data for the X, Y, and Z axes.
+    /// - `Err(Error::InvalidConfig)`: If the FIFO path is active, see [`Iis2dlpc::allow_mixed_data_paths`];
+    ///   nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn acceleration_raw_get(&mut self) -> Result<[i16; 3], Error<B::Error>> {
+        self.state.direct_path_check()?;
+        let mut buf = [0; batch::SAMPLE_BYTES];
+        self.read_from_register(Reg::OutXL as u8, &mut buf).await?;
+        Ok(self.state.axis_remap.raw(batch::raw_from_bytes(&buf)))
+    }
+
+    /// Get the status register, see [`Iis2dlpc::status_reg_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(Status)`: The current value of the `STATUS` register.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn status_reg_get(&mut self) -> Result<Status, Error<B::Error>> {
+        Ok(Status::from_bits(self.reg_get(Reg::Status).await?))
+    }
+
+    /// Save the device configuration.
+    ///
+    /// This function reads every configuration register listed in [`CONFIG_REGS`], as
+    /// [`Iis2dlpc::config_snapshot_get`] does.
+    ///
+    /// ### Returns
+    /// - `Ok(ConfigSnapshot)`: The saved configuration.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn config_snapshot_get(&mut self) -> Result<ConfigSnapshot, Error<B::Error>> {
+        let mut snapshot = ConfigSnapshot::default();
+        for (reg, val) in CONFIG_REGS.iter().zip(snapshot.regs.iter_mut()) {
+            let mut buf = [0];
+            self.read_from_register(*reg as u8, &mut buf).await?;
+            *val = buf[0];
+        }
+        Ok(snapshot)
+    }
+
+    /// Restore a saved device configuration.
+    ///
+    /// This function writes back every register of the snapshot phase by phase, see [`crate::phase`], as
+    /// [`Iis2dlpc::config_snapshot_apply`] does, without the rollback: a failed write leaves the registers
+    /// written so far.
+    ///
+    /// ### Arguments
+    /// - `snapshot`: The configuration to restore.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::ScalingLocked)`: If the snapshot changes the locked scaling, see
+    ///   [`Iis2dlpc::strict_scaling_lock`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
+    pub async fn config_snapshot_apply(
+        &mut self,
+        snapshot: &ConfigSnapshot,
+    ) -> Result<(), Error<B::Error>> {
+        for (reg, val) in snapshot.writes() {
+            self.write_to_register(reg as u8, &[val]).await?;
+        }
+        self.state.snapshot_applied(snapshot);
+        Ok(())
+    }
+
+    /// Get the path the samples are read through, see [`Iis2dlpc::data_path_get`].
+    pub fn data_path_get(&self) -> DataPath {
+        self.state.data_path
+    }
+
+    /// Set the FIFO mode, see [`Iis2dlpc::fifo_mode_set`].
+    ///
+    /// ### Arguments
+    /// - `val`: A [`Fmode`] value representing the desired FIFO mode.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn fifo_mode_set(&mut self, val: Fmode) -> Result<(), Error<B::Error>> {
+        let mut reg = FifoCtrl::from_bits(self.reg_get(Reg::FifoCtrl).await?);
+        reg.set_fmode(val as u8);
+        self.reg_set(Reg::FifoCtrl, reg.into_bits()).await
+    }
+
+    /// Get the FIFO mode, see [`Iis2dlpc::fifo_mode_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(Fmode)`: The current FIFO mode.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_mode_get(&mut self) -> Result<Fmode, Error<B::Error>> {
+        let reg = FifoCtrl::from_bits(self.reg_get(Reg::FifoCtrl).await?);
+        Ok(Fmode::try_from(reg.fmode()).unwrap_or_default())
+    }
+
+    /// Flush the FIFO, see [`Iis2dlpc::fifo_flush`].
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn fifo_flush(&mut self) -> Result<(), Error<B::Error>> {
+        let mode = self.fifo_mode_get().await?;
+        self.fifo_mode_set(Fmode::BypassMode).await?;
+        self.fifo_mode_set(mode).await
+    }
+
+    /// Get the configuration epoch, see [`Iis2dlpc::config_epoch`].
+    pub fn config_epoch(&self) -> u32 {
+        self.state.config_epoch
+    }
+
+    /// Check whether the measurement scaling is locked, see [`Iis2dlpc::strict_scaling_lock`].
+    pub fn scaling_locked(&self) -> bool {
+        self.state.scaling_lock.is_some()
+    }
+
+    /// Read a single register.
+    async fn reg_get(&mut self, reg: Reg) -> Result<u8, Error<B::Error>> {
+        let mut buf = [0];
+        self.read_from_register(reg as u8, &mut buf).await?;
+        Ok(buf[0])
+    }
+
+    /// Write a single register.
+    async fn reg_set(&mut self, reg: Reg, val: u8) -> Result<(), Error<B::Error>> {
+        self.write_to_register(reg as u8, &[val]).await
+    }
+
+    /// Read a register field and decode it with `decode`, retrying once as [`Iis2dlpc::decode_retry_set`] tells.
+    ///
+    /// The error of a field that does not decode twice holds the value of the second read.
+    async fn read_decoded_with<E>(
+        &mut self,
+        field: impl AsyncFn(&mut Self) -> Result<u8, Error<B::Error>>,
+        decode: impl Fn(u8) -> Option<E>,
+    ) -> Result<E, Error<B::Error>> {
+        let raw = field(self).await?;
+        if let Some(val) = decode(raw) {
+            return Ok(val);
+        }
+        if self.state.decode_retry == DecodeRetry::Never {
+            return Err(Error::UnexpectedValue(raw));
+        }
+        let raw = field(self).await?;
+        let val = decode(raw).ok_or(Error::UnexpectedValue(raw))?;
+        self.state.transient_decode_errors = self.state.transient_decode_errors.saturating_add(1);
+        Ok(val)
+    }
+
+    /// Get the data period of the running data stream.
+    async fn running_period_us(&mut self) -> Result<Option<u32>, Error<B::Error>> {
+        if self.state.odr_running() == Some(false) {
+            return Ok(None);
+        }
+        Ok(self.data_rate_get().await?.period_us())
+    }
+
+    /// Get the hint of a change applied from the next conversion, see [`settle::PENDING_SAMPLES`].
+    async fn pending_samples_hint(&mut self, changed: bool) -> Result<SettleHint, Error<B::Error>> {
+        if !changed || self.running_period_us().await?.is_none() {
+            return Ok(SettleHint::None);
+        }
+        Ok(SettleHint::Samples(settle::PENDING_SAMPLES))
+    }
+}
+
+#[cfg(feature = "events")]
+impl<B: AsyncBusOperation, T> Iis2dlpcAsync<B, T> {
+    /// Get all interrupt and status flags of the device, see [`Iis2dlpc::all_sources_get`].
+    ///
+    /// The five source registers are read in a single burst, the summary register last, and the flagged events
+    /// are added to the event counters.
+    ///
+    /// ### Returns
+    /// - `Ok(AllSources)`: The values of the `STATUS_DUP`, `WAKE_UP_SRC`, `TAP_SRC`, `SIXD_SRC` and `ALL_INT_SRC`
+    ///   registers.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn all_sources_get(&mut self) -> Result<AllSources, Error<B::Error>> {
+        let mut buf = [0; AllSources::BYTES];
+        self.read_from_register(Reg::StatusDup as u8, &mut buf)
+            .await?;
+        let sources = AllSources::from_bytes(buf);
+        self.state.event_counters.count_sources(&sources);
+        Ok(sources)
+    }
+
+    /// Get the tap axis priority, see [`Iis2dlpc::tap_axis_priority_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(TapPrior)`: The axis priority.
+    /// - `Err(Error::UnexpectedValue)`: If the `tap_prior` field holds a reserved code, after the retry of
+    ///   [`Iis2dlpc::decode_retry_set`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn tap_axis_priority_get(&mut self) -> Result<TapPrior, Error<B::Error>> {
+        self.read_decoded_with(
+            async |sensor: &mut Self| {
+                Ok(TapThsY::from_bits(sensor.reg_get(Reg::TapThsY).await?).tap_prior())
+            },
+            |raw| TapPrior::try_from(raw).ok(),
+        )
+        .await
+    }
+
+    /// Drain the pending events, see [`Iis2dlpc::events_drain`].
+    ///
+    /// ### Arguments
+    /// - `out`: Destination of the events; a buffer of [`event::MAX_EVENTS`] always holds every event of a read.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of events written to `out`.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn events_drain(&mut self, out: &mut [Event]) -> Result<usize, Error<B::Error>> {
+        Ok(self.events_drain_report(out).await?.count)
+    }
+
+    /// Get the next pending event, see [`Iis2dlpc::event_get`].
+    ///
+    /// The events kept for the next calls are shared with the blocking driver.
+    ///
+    /// ### Returns
+    /// - `Ok(Some(Event))`: The next event, converted to the board frame, see [`Iis2dlpc::axis_remap_set`].
+    /// - `Ok(None)`: If no event is flagged.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn event_get(&mut self) -> Result<Option<Event>, Error<B::Error>> {
+        if let Some(event) = self.event_pending_take() {
+            return Ok(Some(event));
+        }
+        self.events_enqueue().await?;
+        Ok(self.event_pending_take())
+    }
+
+    /// Take the next event kept by [`Self::event_get`], without a bus access.
+    pub(crate) fn event_pending_take(&mut self) -> Option<Event> {
+        self.state.pending_events.next()
+    }
+
+    /// Drain the pending events and report the state of the interrupt storm guard, see
+    /// [`Iis2dlpc::events_drain_report`].
+    ///
+    /// ### Arguments
+    /// - `out`: Destination of the events; a buffer of [`event::MAX_EVENTS`] always holds every event of a read.
+    ///
+    /// ### Returns
+    /// - `Ok(EventsDrainReport)`: The number of events written to `out`, the guard state and the disabled route.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn events_drain_report(
+        &mut self,
+        out: &mut [Event],
+    ) -> Result<EventsDrainReport, Error<B::Error>> {
+        Ok(self.events_drain_sources(out).await?.0)
+    }
+
+    /// Drain the source registers into the events returned by [`Self::event_get`].
+    ///
+    /// ### Returns
+    /// - `Ok((usize, AllSources))`: The number of events queued, and the sources read for the data-ready flag of
+    ///   the same burst.
+    pub(crate) async fn events_enqueue(&mut self) -> Result<(usize, AllSources), Error<B::Error>> {
+        let mut buf = [Event::FreeFall; event::MAX_EVENTS];
+        let (report, sources) = self.events_drain_sources(&mut buf).await?;
+        self.state.pending_events = PendingEvents::new(buf.get(..report.count).unwrap_or_default());
+        Ok((report.count, sources))
+    }
+
+    /// Drain the pending events as [`Self::events_drain_report`] does, also returning the sources read.
+    async fn events_drain_sources(
+        &mut self,
+        out: &mut [Event],
+    ) -> Result<(EventsDrainReport, AllSources), Error<B::Error>> {
+        let sources = self.all_sources_get().await?;
+        let priority = self.tap_axis_priority_get().await?;
+        let mut events = [Event::FreeFall; event::MAX_EVENTS];
+        let decoded = sources.decode_events(self.state.tap_policy, priority, &mut events);
+        let events = events.get(..decoded).unwrap_or_default();
+
+        let mut count = 0;
+        for (slot, event) in out.iter_mut().zip(events) {
+            *slot = self.state.axis_remap.event(*event);
+            count += 1;
+        }
+
+        #[cfg(feature = "storm-guard")]
+        let (throttled, disabled) = {
+            let (throttled, offender) = self.state.storm_guard.account(events);
+            let disabled = match offender {
+                Some(route) if self.state.storm_guard.policy == StormPolicy::DisableRoute => {
+                    self.event_route_disable(route).await?.then_some(route)
+                }
+                _ => None,
+            };
+            (throttled, disabled)
+        };
+        #[cfg(not(feature = "storm-guard"))]
+        let (throttled, disabled) = (false, None);
+        let report = EventsDrainReport {
+            count,
+            decoded,
+            throttled,
+            disabled,
+        };
+        Ok((report, sources))
+    }
+
+    /// Remove an event from the pad routing, as the interrupt storm guard does, see
+    /// [`Iis2dlpc::events_drain_report`].
+    ///
+    /// ### Returns
+    /// - `Ok(bool)`: `true` if the event was routed and is now removed.
+    #[cfg(feature = "storm-guard")]
+    async fn event_route_disable(&mut self, route: EventRoute) -> Result<bool, Error<B::Error>> {
+        if !self.state.int1_shadow.shadow_valid {
+            self.reg_get(Reg::Ctrl4Int1PadCtrl).await?;
+        }
+        if !self.state.int2_shadow.shadow_valid {
+            self.reg_get(Reg::Ctrl5Int2PadCtrl).await?;
+        }
+        if !route.is_routed(
+            &self.state.int1_shadow.routes,
+            &self.state.int2_shadow.routes,
+        ) {
+            return Ok(false);
+        }
+
+        let mut ctrl4 = Ctrl4Int1PadCtrl::from_bits(self.reg_get(Reg::Ctrl4Int1PadCtrl).await?);
+        let mut ctrl5 = Ctrl5Int2PadCtrl::from_bits(self.reg_get(Reg::Ctrl5Int2PadCtrl).await?);
+        let ctrl7 = Ctrl7::from_bits(self.reg_get(Reg::Ctrl7).await?);
+        if route == EventRoute::SleepChange {
+            route.clear_int2(&mut ctrl5);
+            if ctrl5.int2_drdy_t() == 1 && self.state.temp_policy == TempPolicy::Ignore {
+                return Err(Error::InvalidConfig(ConfigError::TemperatureIgnored));
+            }
+        } else {
+            route.clear_int1(&mut ctrl4);
+        }
+
+        let disabled = ctrl7.with_interrupts_enable(PROPERTY_DISABLE);
+        self.reg_set(Reg::Ctrl7, disabled.into_bits()).await?;
+        if route == EventRoute::SleepChange {
+            self.reg_set(Reg::Ctrl5Int2PadCtrl, ctrl5.into_bits())
+                .await?;
+            self.state.int2_shadow = RouteShadow {
+                routes: ctrl5,
+                shadow_valid: true,
+            };
+        } else {
+            self.reg_set(Reg::Ctrl4Int1PadCtrl, ctrl4.into_bits())
+                .await?;
+            self.state.int1_shadow = RouteShadow {
+                routes: ctrl4,
+                shadow_valid: true,
+            };
+        }
+        let enabled = ctrl7.with_interrupts_enable(interrupts_enable_for(&ctrl4, &ctrl5));
+        self.reg_set(Reg::Ctrl7, enabled.into_bits()).await?;
+        Ok(true)
+    }
+
+    /// Set the interrupt storm guard limit, see [`Iis2dlpc::events_rate_limit_set`].
+    ///
+    /// ### Arguments
+    /// - `max_events_per_poll`: The highest number of events of a drain that does not count towards a storm.
+    #[cfg(feature = "storm-guard")]
+    pub fn events_rate_limit_set(&mut self, max_events_per_poll: u16) {
+        self.state.storm_guard.limit_set(Some(max_events_per_poll));
+    }
+
+    /// Disable the interrupt storm guard, see [`Iis2dlpc::events_rate_limit_set`].
+    #[cfg(feature = "storm-guard")]
+    pub fn events_rate_limit_clear(&mut self) {
+        self.state.storm_guard.limit_set(None);
+    }
+
+    /// Set the action of the interrupt storm guard, see [`Iis2dlpc::events_storm_policy_set`].
+    ///
+    /// ### Arguments
+    /// - `policy`: The action taken on the drain that trips the guard.
+    #[cfg(feature = "storm-guard")]
+    pub fn events_storm_policy_set(&mut self, policy: StormPolicy) {
+        self.state.storm_guard.policy = policy;
+    }
+
+    /// Get the signals routed on the INT1 pad, as last known by the driver, see [`Iis2dlpc::int1_routes_shadow`].
+    pub fn int1_routes_shadow(&self) -> RouteShadow<Ctrl4Int1PadCtrl> {
+        self.state.int1_shadow
+    }
+
+    /// Get the signals routed on the INT2 pad, as last known by the driver, see [`Iis2dlpc::int2_routes_shadow`].
+    pub fn int2_routes_shadow(&self) -> RouteShadow<Ctrl5Int2PadCtrl> {
+        self.state.int2_shadow
+    }
+
+    /// Get the cumulative event counters, see [`Iis2dlpc::event_counters`].
+    pub fn event_counters(&self) -> EventCounters {
+        self.state.event_counters
+    }
+}
+
+#[cfg(feature = "fifo")]
+impl<B: AsyncBusOperation, T> Iis2dlpcAsync<B, T> {
+    /// Set the FIFO watermark level, see [`Iis2dlpc::fifo_watermark_set`].
+    ///
+    /// ### Arguments
+    /// - `val`: The FIFO watermark level, from 0 to 31.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 5-bit `fth` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn fifo_watermark_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 5)?;
+        let mut reg = FifoCtrl::from_bits(self.reg_get(Reg::FifoCtrl).await?);
+        reg.set_fth(val);
+        self.reg_set(Reg::FifoCtrl, reg.into_bits()).await
+    }
+
+    /// Get the FIFO watermark level, see [`Iis2dlpc::fifo_watermark_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current FIFO watermark level.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_watermark_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoCtrl::from_bits(self.reg_get(Reg::FifoCtrl).await?).fth())
+    }
+
+    /// Get the number of unread samples stored in the FIFO, see [`Iis2dlpc::fifo_data_level_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The number of unread samples in the FIFO.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_data_level_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoSamples::from_bits(self.reg_get(Reg::FifoSamples).await?).diff())
+    }
+
+    /// Get the FIFO overrun status, see [`Iis2dlpc::fifo_ovr_flag_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: `1` if the FIFO has overwritten old data.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_ovr_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoSamples::from_bits(self.reg_get(Reg::FifoSamples).await?).fifo_ovr())
+    }
+
+    /// Get the FIFO threshold status flag, see [`Iis2dlpc::fifo_wtm_flag_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: `1` if the FIFO filling has reached or exceeded the threshold level.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_wtm_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoSamples::from_bits(self.reg_get(Reg::FifoSamples).await?).fifo_fth())
+    }
+
+    /// Get the FIFO full status flag, see [`Iis2dlpc::fifo_full_flag_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: `1` if the FIFO holds [`FIFO_DEPTH`] samples.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_full_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(self.fifo_flags_get().await?.full as u8)
+    }
+
+    /// Get the FIFO level and status flags in a single read, see [`Iis2dlpc::fifo_flags_get`].
+    ///
+    /// ### Returns
+    /// - `Ok(FifoFlags)`: The FIFO level and the threshold, overrun and full flags.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_flags_get(&mut self) -> Result<FifoFlags, Error<B::Error>> {
+        let reg = FifoSamples::from_bits(self.reg_get(Reg::FifoSamples).await?);
+        Ok(FifoFlags::from(reg))
+    }
+
+    /// Get the samples stored in the FIFO, see [`Iis2dlpc::fifo_data_get`].
+    ///
+    /// This function reads the `diff` field in the `FIFO_SAMPLES` register and burst reads as many samples as
+    /// are stored and fit in `out`, at most [`FIFO_DEPTH`], in a single bus transaction.
+    ///
+    /// ### Arguments
+    /// - `out`: The destination of the raw samples, oldest first and in the board frame.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of samples written to `out`; `0` with an empty FIFO, in which case only
+    ///   `FIFO_SAMPLES` was read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn fifo_data_get(&mut self, out: &mut [[i16; 3]]) -> Result<usize, Error<B::Error>> {
+        let diff = self.fifo_data_level_get().await?;
+        let n = (diff as usize).min(out.len()).min(FIFO_DEPTH);
+        if n == 0 {
+            return Ok(0);
+        }
+        let mut buf = [0; FIFO_DEPTH * batch::SAMPLE_BYTES];
+        let dst = buf.get_mut(..n * batch::SAMPLE_BYTES).unwrap_or_default();
+        self.read_from_register(Reg::OutXL as u8, dst).await?;
+
+        let remap = self.state.axis_remap;
+        for (sample, bytes) in out.iter_mut().zip(dst.chunks_exact(batch::SAMPLE_BYTES)) {
+            if let Ok(bytes) = bytes.try_into() {
+                *sample = remap.raw(batch::raw_from_bytes(bytes));
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<B: AsyncBusOperation, T: AsyncDelayNs> Iis2dlpcAsync<B, T> {
+    /// Bring the device to a known configuration, see [`Iis2dlpc::init`].
+    ///
+    /// This function checks the bus and the device identifier, performs a software reset and awaits the end of
+    /// the reset, polling every [`RESET_POLL_US`] up to [`RESET_MAX_POLLS`] times, then sets the `bdu` and
+    /// `if_add_inc` fields in the `CTRL2` register.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::WhoAmIError)`: If `WHO_AM_I` holds another value than [`crate::ID`]; nothing is written.
+    /// - `Err(Error::UnexpectedValue)`: If the bus is stuck, with the value every register reads; nothing is written.
+    /// - `Err(Error::Timeout)`: If the reset is still in progress after the last poll.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn init(&mut self) -> Result<(), Error<B::Error>> {
+        self.bus_sanity_check().await?.require()?;
+
+        self.reset_set().await?;
+        let mut polls = 1;
+        while self.reset_get().await? != 0 {
+            if polls >= RESET_MAX_POLLS {
+                return Err(Error::Timeout);
+            }
+            self.tim.delay_us(RESET_POLL_US).await;
+            polls += 1;
+        }
+
+        let mut ctrl2 = Ctrl2::from_bits(self.reg_get(Reg::Ctrl2).await?);
+        ctrl2.set_bdu(PROPERTY_ENABLE);
+        ctrl2.set_if_add_inc(PROPERTY_ENABLE);
+        self.reg_set(Reg::Ctrl2, ctrl2.into_bits()).await
+    }
+
+    /// Set the accelerometer operating mode, see [`Iis2dlpc::power_mode_set`].
+    ///
+    /// The mode is checked against the current data rate with [`mode_odr_check`]. Only the registers that change
+    /// are written; with [`Workarounds::MODE_SWITCH_SETTLE`], a mode change and a running data rate, the function
+    /// then awaits one data period.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`Mode`] value representing the desired operating mode.
+    ///
+    /// ### Returns
+    /// - `Ok(SettleHint)`: The wait before the samples are converted in the new mode, see [`settle`].
+    /// - `Err(Error::InvalidArgument)`: If the mode cannot be used with the current data rate; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn power_mode_set(&mut self, val: Mode) -> Result<SettleHint, Error<B::Error>> {
+        let odr = self.data_rate_get().await?;
+        mode_odr_check(val, odr)?;
+
+        let ctrl1 = Ctrl1::from_bits(self.reg_get(Reg::Ctrl1).await?);
+        let ctrl1_set = ctrl1.with_mode(val.mode()).with_lp_mode(val.lp_mode());
+        if ctrl1_set != ctrl1 {
+            self.reg_set(Reg::Ctrl1, ctrl1_set.into_bits()).await?;
+        }
+
+        let ctrl6 = Ctrl6::from_bits(self.reg_get(Reg::Ctrl6).await?);
+        let ctrl6_set = ctrl6.with_low_noise(val.low_noise());
+        if ctrl6_set != ctrl6 {
+            self.reg_set(Reg::Ctrl6, ctrl6_set.into_bits()).await?;
+        }
+
+        let changed = ctrl1_set != ctrl1 || ctrl6_set != ctrl6;
+        let Some(period_us) = odr.period_us().filter(|_| changed) else {
+            return Ok(SettleHint::None);
+        };
+        if self
+            .state
+            .workarounds
+            .contains(Workarounds::MODE_SWITCH_SETTLE)
+        {
+            self.tim.delay_us(period_us).await;
+            return Ok(SettleHint::None);
+        }
+        Ok(SettleHint::Samples(settle::PENDING_SAMPLES))
+    }
+}
@@ -0,0 +1,147 @@
+//! Zero-copy decoding of raw acceleration bursts.
+//!
+//! A burst read of `OUT_X_L` .. `OUT_Z_H` (or a FIFO drain) yields 6 bytes per sample.
+//! [`SampleBatch`] wraps such a buffer, for example one filled by DMA outside the driver,
+//! and decodes it with the same code path used by [`crate::Iis2dlpc::acceleration_raw_get`].
+
+use crate::prelude::*;
+use crate::{
+    from_fs2_lp1_to_mg, from_fs2_to_mg, from_fs4_lp1_to_mg, from_fs4_to_mg, from_fs8_lp1_to_mg,
+    from_fs8_to_mg, from_fs16_lp1_to_mg, from_fs16_to_mg,
+};
+
+/// Number of bytes of a single X/Y/Z sample.
+pub const SAMPLE_BYTES: usize = 6;
+
+/// Error returned when a buffer is not a whole number of samples.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MisalignedBatch {
+    /// Length of the rejected buffer, in bytes.
+    pub len: usize,
+}
+
+/// Read-only view over raw acceleration samples.
+///
+/// Each sample is laid out as `OUT_X_L`, `OUT_X_H`, `OUT_Y_L`, `OUT_Y_H`, `OUT_Z_L`, `OUT_Z_H`.
+#[derive(Clone, Copy, Debug)]
+pub struct SampleBatch<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SampleBatch<'a> {
+    /// Wrap a raw buffer.
+    ///
+    /// ### Arguments
+    /// - `data`: The raw bytes; the length must be a multiple of 6.
+    ///
+    /// ### Returns
+    /// - `Ok(SampleBatch)`: The view over `data`.
+    /// - `Err(MisalignedBatch)`: If the length is not a multiple of 6.
+    pub fn new(data: &'a [u8]) -> Result<Self, MisalignedBatch> {
+        if !data.len().is_multiple_of(SAMPLE_BYTES) {
+            return Err(MisalignedBatch { len: data.len() });
+        }
+        Ok(Self { data })
+    }
+
+    /// Get the number of samples in the batch.
+    pub fn len(&self) -> usize {
+        self.data.len() / SAMPLE_BYTES
+    }
+
+    /// Check whether the batch holds no samples.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Get the raw X, Y, Z values of sample `i`.
+    ///
+    /// The values are the same as the ones returned by [`crate::Iis2dlpc::acceleration_raw_get`].
+    ///
+    /// ### Returns
+    /// - `Some([i16; 3])`: The raw values.
+    /// - `None`: If `i` is not lower than [`Self::len`].
+    pub fn raw(&self, i: usize) -> Option<[i16; 3]> {
+        self.iter().nth(i)
+    }
+
+    /// Get the X, Y, Z values of sample `i` in mg.
+    ///
+    /// ### Arguments
+    /// - `i`: The sample index.
+    /// - `fs`: The full scale the samples were acquired with.
+    /// - `mode`: The operating mode the samples were acquired with; 12-bit modes are scaled accordingly.
+    ///
+    /// ### Returns
+    /// - `Some([f32; 3])`: The values in mg.
+    /// - `None`: If `i` is not lower than [`Self::len`].
+    pub fn mg(&self, i: usize, fs: Fs, mode: Mode) -> Option<[f32; 3]> {
+        self.raw(i)
+            .map(|raw| raw.map(|lsb| raw_to_mg(lsb, fs, mode.is_low_power_1())))
+    }
+
+    /// Iterate over the raw X, Y, Z values of every sample.
+    pub fn iter(&self) -> SampleBatchIter<'a> {
+        SampleBatchIter {
+            chunks: self.data.chunks_exact(SAMPLE_BYTES),
+        }
+    }
+}
+
+impl<'a> IntoIterator for SampleBatch<'a> {
+    type Item = [i16; 3];
+    type IntoIter = SampleBatchIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the raw samples of a [`SampleBatch`].
+#[derive(Clone, Debug)]
+pub struct SampleBatchIter<'a> {
+    chunks: core::slice::ChunksExact<'a, u8>,
+}
+
+impl Iterator for SampleBatchIter<'_> {
+    type Item = [i16; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next()?.try_into().ok().map(raw_from_bytes)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.chunks.nth(n)?.try_into().ok().map(raw_from_bytes)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for SampleBatchIter<'_> {}
+
+/// Decode one raw sample, as read from `OUT_X_L` .. `OUT_Z_H`.
+pub(crate) fn raw_from_bytes(bytes: &[u8; SAMPLE_BYTES]) -> [i16; 3] {
+    [
+        OutX::from_le_bytes([bytes[0], bytes[1]]).x(),
+        OutY::from_le_bytes([bytes[2], bytes[3]]).y(),
+        OutZ::from_le_bytes([bytes[4], bytes[5]]).z(),
+    ]
+}
+
+/// Convert a raw axis value to mg for the given full scale.
+///
+/// In low-power mode 1 only the 12 most significant bits are meaningful.
+pub(crate) fn raw_to_mg(lsb: i16, fs: Fs, low_power_1: bool) -> f32 {
+    match (fs, low_power_1) {
+        (Fs::_2g, false) => from_fs2_to_mg(lsb),
+        (Fs::_4g, false) => from_fs4_to_mg(lsb),
+        (Fs::_8g, false) => from_fs8_to_mg(lsb),
+        (Fs::_16g, false) => from_fs16_to_mg(lsb),
+        (Fs::_2g, true) => from_fs2_lp1_to_mg(lsb >> 2),
+        (Fs::_4g, true) => from_fs4_lp1_to_mg(lsb >> 2),
+        (Fs::_8g, true) => from_fs8_lp1_to_mg(lsb >> 2),
+        (Fs::_16g, true) => from_fs16_lp1_to_mg(lsb >> 2),
+    }
+}
@@ -0,0 +1,143 @@
+//! CRC-protected binary encoding of configuration snapshots.
+//!
+//! A [`ConfigBlob`] carries a [`ConfigSnapshot`] as [`BLOB_LEN`] opaque bytes, for example a sensing
+//! profile delivered over the air: the sender encodes it with [`ConfigBlob::encode`], the device
+//! checks and writes it with [`crate::Iis2dlpc::apply_blob`]. The format does not depend on `serde`
+//! and needs no allocation.
+//!
+//! Layout (version 1), multi-byte fields little-endian:
+//!
+//! | Byte    | Content                                                               |
+//! |---------|-----------------------------------------------------------------------|
+//! | 0..=1   | Magic, ASCII `DL`                                                     |
+//! | 2       | Layout version                                                        |
+//! | 3       | Number of registers, 18                                               |
+//! | 4..=21  | Register values, in the order of [`CONFIG_REGS`]                      |
+//! | 22..=23 | CRC-16 of bytes 0..=21                                                |
+//!
+//! The CRC is CRC-16/CCITT-FALSE: polynomial `0x1021`, initial value `0xFFFF`, no reflection, no
+//! final XOR; the check value of the ASCII string `123456789` is `0x29B1`. It detects any error
+//! confined to 16 consecutive bits, so every corrupted byte.
+
+use crate::snapshot::{CONFIG_REGS, ConfigSnapshot};
+
+/// Length of a configuration blob, in bytes.
+pub const BLOB_LEN: usize = 24;
+
+/// Layout version written in the blob.
+pub const BLOB_VERSION: u8 = 1;
+
+/// Magic bytes at the start of a blob.
+pub const BLOB_MAGIC: [u8; 2] = *b"DL";
+
+const HEADER_LEN: usize = 4;
+const CRC_OFFSET: usize = HEADER_LEN + CONFIG_REGS.len();
+
+/// Configuration blob decoding and encoding errors.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BlobError {
+    /// The buffer is shorter than [`BLOB_LEN`]; holds the actual length.
+    Length(usize),
+    /// The CRC does not match the content: the blob is corrupted.
+    Crc { stored: u16, computed: u16 },
+    /// The blob does not start with [`BLOB_MAGIC`].
+    Magic,
+    /// The blob was encoded with an unknown layout version.
+    Version(u8),
+    /// The number of registers does not match the layout version; holds the stored number.
+    RegisterCount(u8),
+}
+
+/// Encoder and decoder of configuration blobs, see the [module](self) documentation.
+#[derive(Debug)]
+pub struct ConfigBlob;
+
+impl ConfigBlob {
+    /// Encode a configuration snapshot.
+    ///
+    /// ### Arguments
+    /// - `snapshot`: The configuration.
+    /// - `buf`: The destination; only the first [`BLOB_LEN`] bytes are written.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of bytes written ([`BLOB_LEN`]).
+    /// - `Err(BlobError::Length)`: If the buffer is shorter than [`BLOB_LEN`]; nothing is written.
+    pub fn encode(snapshot: &ConfigSnapshot, buf: &mut [u8]) -> Result<usize, BlobError> {
+        let len = buf.len();
+        let dst = buf.get_mut(..BLOB_LEN).ok_or(BlobError::Length(len))?;
+
+        let mut blob = [0; BLOB_LEN];
+        let (content, crc) = blob.split_at_mut(CRC_OFFSET);
+        let (header, regs) = content.split_at_mut(HEADER_LEN);
+        header.copy_from_slice(&[
+            BLOB_MAGIC[0],
+            BLOB_MAGIC[1],
+            BLOB_VERSION,
+            CONFIG_REGS.len() as u8,
+        ]);
+        regs.copy_from_slice(&snapshot.regs);
+        crc.copy_from_slice(&crc16(content).to_le_bytes());
+        dst.copy_from_slice(&blob);
+        Ok(BLOB_LEN)
+    }
+
+    /// Decode a configuration blob.
+    ///
+    /// The checks run in this order: length, CRC, magic, version and number of registers, so that a corrupted
+    /// header is reported as a CRC error.
+    ///
+    /// ### Arguments
+    /// - `buf`: The received bytes; only the first [`BLOB_LEN`] are used.
+    ///
+    /// ### Returns
+    /// - `Ok(ConfigSnapshot)`: The decoded configuration.
+    /// - `Err(BlobError)`: If the blob is truncated, corrupted or of an unknown layout.
+    pub fn decode(buf: &[u8]) -> Result<ConfigSnapshot, BlobError> {
+        let blob: &[u8; BLOB_LEN] = buf
+            .get(..BLOB_LEN)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(BlobError::Length(buf.len()))?;
+
+        let (content, crc) = blob.split_at(CRC_OFFSET);
+        let stored = u16::from_le_bytes([
+            crc.first().copied().unwrap_or_default(),
+            crc.get(1).copied().unwrap_or_default(),
+        ]);
+        let computed = crc16(content);
+        if stored != computed {
+            return Err(BlobError::Crc { stored, computed });
+        }
+
+        let (header, regs) = content.split_at(HEADER_LEN);
+        let [m0, m1, version, count] = header else {
+            return Err(BlobError::Length(buf.len()));
+        };
+        if [*m0, *m1] != BLOB_MAGIC {
+            return Err(BlobError::Magic);
+        }
+        if *version != BLOB_VERSION {
+            return Err(BlobError::Version(*version));
+        }
+        if usize::from(*count) != CONFIG_REGS.len() {
+            return Err(BlobError::RegisterCount(*count));
+        }
+
+        let mut snapshot = ConfigSnapshot::default();
+        snapshot.regs.copy_from_slice(regs);
+        Ok(snapshot)
+    }
+}
+
+/// Compute the CRC-16/CCITT-FALSE of `data`.
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, byte| {
+        (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
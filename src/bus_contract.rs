@@ -0,0 +1,341 @@
+//! Executable specification of the bus contract.
+//!
+//! The driver reaches the device through two functions of [`BusOperation`] only, and relies on
+//! the following:
+//!
+//! 1. [`BusOperation::read_from_register`] reads `buf.len()` consecutive registers starting at
+//!    `reg` in a single transaction: on I2C a write of `reg`, a repeated start and a read; on SPI
+//!    `reg` with the read bit (bit 7) set, then the data. The device increments the address
+//!    itself (`IF_ADD_INC` in `CTRL2`, set at reset); the bus must not split the transfer, which
+//!    would break the block data update of the output registers.
+//! 2. [`BusOperation::write_to_register`] writes `reg`, with the SPI read bit cleared, then the
+//!    bytes of `buf`, in a single transaction.
+//! 3. The bytes are in bus order: a 16-bit output is read low byte first.
+//! 4. A read may be as long as a full FIFO drain, [`FIFO_DEPTH`] samples of
+//!    [`SAMPLE_BYTES`] bytes from `OUT_X_L`; in FIFO mode the device rolls the address back from
+//!    `OUT_Z_H` to `OUT_X_L`. A write may span several registers.
+//! 5. A failed transfer returns an error, which the driver wraps in [`crate::Error::Bus`]; it
+//!    must neither panic nor return stale data as a success.
+//!
+//! A bus implementation over bit-banged GPIOs or an RTOS bridge can get any of them subtly
+//! wrong, and the driver then fails in confusing ways. [`check`] runs the implementation against
+//! a [`LoopbackModel`], the register file of a simulated device on the other side of the bus,
+//! and tells which [`Property`] fails:
+//!
+//! ```ignore
+//! let report = bus_contract::check(&mut bus, &mut model);
+//! assert!(report.is_ok(), "{report}");
+//! ```
+//!
+//! The model starts from the reset values of the device; the registers the checks set are put
+//! back afterwards.
+
+use core::fmt;
+
+use crate::batch::SAMPLE_BYTES;
+use crate::fifo::FIFO_DEPTH;
+use crate::prelude::*;
+use st_mems_bus::BusOperation;
+
+/// Register file of the simulated device behind a bus under test.
+///
+/// The functions take the bus too, so that a model living inside it, e.g. the fake peripheral
+/// wrapped by the bus, can be reached.
+pub trait LoopbackModel<B> {
+    /// Get the content of a register, without a bus transfer.
+    fn peek(&mut self, bus: &mut B, reg: Reg) -> u8;
+
+    /// Set the content of a register, without a bus transfer.
+    fn poke(&mut self, bus: &mut B, reg: Reg, val: u8);
+
+    /// Make the next transfer of `bus` fail.
+    ///
+    /// Returns `false` if the model cannot inject faults, the default: [`Property::ErrorPropagation`] is then
+    /// skipped.
+    fn fail_next(&mut self, bus: &mut B) -> bool {
+        let _ = bus;
+        false
+    }
+}
+
+/// Property of the bus contract, see the [module](self) documentation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Property {
+    /// A one-byte read returns the register content.
+    SingleRead,
+    /// A one-byte write sets the register and only it.
+    SingleWrite,
+    /// A read of `STATUS` .. `OUT_Z_H` (0x27 to 0x2D) returns the 7 registers in order.
+    BurstRead,
+    /// A read of `OUT_X_L` and `OUT_X_H` assembles into the 16-bit output, low byte first.
+    PairAssembly,
+    /// A write of `X_OFS_USR` .. `Z_OFS_USR` sets the 3 registers in order.
+    BurstWrite,
+    /// A read of a full FIFO drain from `OUT_X_L` returns every sample.
+    MaxLengthRead,
+    /// A failed read or write returns an error, and the next transfer succeeds.
+    ErrorPropagation,
+}
+
+impl Property {
+    /// Every property, in the order they are checked.
+    pub const ALL: [Property; 7] = [
+        Property::SingleRead,
+        Property::SingleWrite,
+        Property::BurstRead,
+        Property::PairAssembly,
+        Property::BurstWrite,
+        Property::MaxLengthRead,
+        Property::ErrorPropagation,
+    ];
+
+    /// Get the expected behavior, in words.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Property::SingleRead => "one-byte read returns the register content",
+            Property::SingleWrite => "one-byte write sets the register and only it",
+            Property::BurstRead => "burst read of 0x27..0x2D returns the registers in order",
+            Property::PairAssembly => "16-bit output assembles low byte first",
+            Property::BurstWrite => "burst write sets consecutive registers in order",
+            Property::MaxLengthRead => "full FIFO drain of 192 bytes returns every sample",
+            Property::ErrorPropagation => "failed transfers return an error, the bus recovers",
+        }
+    }
+}
+
+/// Outcome of a property check.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Outcome {
+    /// The property holds.
+    Pass,
+    /// The property does not hold, or the bus returned an unexpected error.
+    Fail,
+    /// The model cannot check the property.
+    Skipped,
+}
+
+/// Outcome of every property, see [`check`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ContractReport {
+    outcomes: [Outcome; Property::ALL.len()],
+}
+
+impl ContractReport {
+    /// Get the outcome of a property.
+    pub fn outcome(&self, property: Property) -> Outcome {
+        self.outcomes
+            .get(property as usize)
+            .copied()
+            .unwrap_or(Outcome::Skipped)
+    }
+
+    /// Iterate over the properties and their outcomes, in the order they are checked.
+    pub fn iter(&self) -> impl Iterator<Item = (Property, Outcome)> + '_ {
+        Property::ALL.into_iter().zip(self.outcomes)
+    }
+
+    /// Get the first property that does not hold.
+    pub fn first_failure(&self) -> Option<Property> {
+        self.iter()
+            .find(|(_, outcome)| *outcome == Outcome::Fail)
+            .map(|(property, _)| property)
+    }
+
+    /// Check that no property fails; skipped ones are accepted.
+    pub fn is_ok(&self) -> bool {
+        self.first_failure().is_none()
+    }
+}
+
+impl fmt::Display for ContractReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (property, outcome) in self.iter() {
+            let tag = match outcome {
+                Outcome::Pass => "PASS",
+                Outcome::Fail => "FAIL",
+                Outcome::Skipped => "SKIP",
+            };
+            writeln!(f, "{tag} {property:?}: {}", property.description())?;
+        }
+        Ok(())
+    }
+}
+
+/// Check a bus implementation against the contract of the driver.
+///
+/// This function runs every [`Property`] in turn through `bus`, setting and reading the registers of the device
+/// through `model`, and puts the registers it set back afterwards.
+///
+/// ### Arguments
+/// - `bus`: The implementation under test, connected to the simulated device.
+/// - `model`: The register file of the simulated device.
+///
+/// ### Returns
+/// - `ContractReport`: The outcome of every property.
+pub fn check<B: BusOperation, M: LoopbackModel<B>>(bus: &mut B, model: &mut M) -> ContractReport {
+    let mut outcomes = [Outcome::Skipped; Property::ALL.len()];
+    for (property, outcome) in Property::ALL.into_iter().zip(outcomes.iter_mut()) {
+        *outcome = match property {
+            Property::ErrorPropagation => error_propagation(bus, model),
+            property => {
+                let holds = with_saved(bus, model, saved_regs(property), |bus, model| {
+                    holds(property, bus, model)
+                });
+                if holds { Outcome::Pass } else { Outcome::Fail }
+            }
+        };
+    }
+    ContractReport { outcomes }
+}
+
+/// Patterns written and read back, covering every bit at both levels.
+const PATTERNS: [u8; 4] = [0x00, 0xFF, 0xA5, 0x5A];
+
+/// Registers of `STATUS` .. `OUT_Z_H`.
+const BURST_REGS: [Reg; 7] = [
+    Reg::Status,
+    Reg::OutXL,
+    Reg::OutXH,
+    Reg::OutYL,
+    Reg::OutYH,
+    Reg::OutZL,
+    Reg::OutZH,
+];
+
+/// Registers of `X_OFS_USR` .. `Z_OFS_USR`.
+const OFFSET_REGS: [Reg; 3] = [Reg::XOfsUsr, Reg::YOfsUsr, Reg::ZOfsUsr];
+
+/// Registers set by the check of a property.
+fn saved_regs(property: Property) -> &'static [Reg] {
+    match property {
+        Property::SingleRead | Property::SingleWrite | Property::BurstWrite => &OFFSET_REGS,
+        Property::BurstRead | Property::PairAssembly => &BURST_REGS,
+        Property::MaxLengthRead => &[
+            Reg::FifoCtrl,
+            Reg::OutXL,
+            Reg::OutXH,
+            Reg::OutYL,
+            Reg::OutYH,
+            Reg::OutZL,
+            Reg::OutZH,
+        ],
+        Property::ErrorPropagation => &[],
+    }
+}
+
+/// Run `f` and set the registers of `regs` back to their content before.
+fn with_saved<B, M: LoopbackModel<B>>(
+    bus: &mut B,
+    model: &mut M,
+    regs: &[Reg],
+    f: impl FnOnce(&mut B, &mut M) -> bool,
+) -> bool {
+    let mut saved = [0; BURST_REGS.len()];
+    for (reg, val) in regs.iter().zip(saved.iter_mut()) {
+        *val = model.peek(bus, *reg);
+    }
+    let holds = f(bus, model);
+    for (reg, val) in regs.iter().zip(saved) {
+        model.poke(bus, *reg, val);
+    }
+    holds
+}
+
+fn holds<B: BusOperation, M: LoopbackModel<B>>(
+    property: Property,
+    bus: &mut B,
+    model: &mut M,
+) -> bool {
+    match property {
+        Property::SingleRead => PATTERNS.iter().all(|pattern| {
+            model.poke(bus, Reg::XOfsUsr, *pattern);
+            let mut buf = [!*pattern];
+            bus.read_from_register(Reg::XOfsUsr as u8, &mut buf).is_ok() && buf == [*pattern]
+        }),
+        Property::SingleWrite => PATTERNS.iter().all(|pattern| {
+            let neighbor = !*pattern;
+            model.poke(bus, Reg::YOfsUsr, neighbor);
+            bus.write_to_register(Reg::XOfsUsr as u8, &[*pattern])
+                .is_ok()
+                && model.peek(bus, Reg::XOfsUsr) == *pattern
+                && model.peek(bus, Reg::YOfsUsr) == neighbor
+        }),
+        Property::BurstRead => PATTERNS.iter().all(|pattern| {
+            let mut expected = [0; BURST_REGS.len()];
+            for ((reg, val), i) in BURST_REGS.iter().zip(expected.iter_mut()).zip(1u8..) {
+                *val = pattern.wrapping_add(i.wrapping_mul(0x11));
+                model.poke(bus, *reg, *val);
+            }
+            let mut buf = [0; BURST_REGS.len()];
+            bus.read_from_register(Reg::Status as u8, &mut buf).is_ok() && buf == expected
+        }),
+        Property::PairAssembly => [0x1234_u16 as i16, 0x9281_u16 as i16, -1, 0x00FF]
+            .iter()
+            .all(|val| {
+                let [low, high] = val.to_le_bytes();
+                model.poke(bus, Reg::OutXL, low);
+                model.poke(bus, Reg::OutXH, high);
+                let mut buf = [0; 2];
+                bus.read_from_register(Reg::OutXL as u8, &mut buf).is_ok()
+                    && i16::from_le_bytes(buf) == *val
+            }),
+        Property::BurstWrite => PATTERNS.iter().all(|pattern| {
+            let vals = [
+                *pattern,
+                pattern.wrapping_add(0x11),
+                pattern.wrapping_add(0x22),
+            ];
+            bus.write_to_register(Reg::XOfsUsr as u8, &vals).is_ok()
+                && OFFSET_REGS
+                    .iter()
+                    .zip(vals)
+                    .all(|(reg, val)| model.peek(bus, *reg) == val)
+        }),
+        Property::MaxLengthRead => {
+            let fifo_ctrl = FifoCtrl::new().with_fmode(Fmode::FifoMode as u8);
+            model.poke(bus, Reg::FifoCtrl, fifo_ctrl.into_bits());
+            let sample = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
+            for (reg, val) in BURST_REGS.iter().skip(1).zip(sample) {
+                model.poke(bus, *reg, val);
+            }
+            let mut buf = [0; FIFO_DEPTH * SAMPLE_BYTES];
+            bus.read_from_register(Reg::OutXL as u8, &mut buf).is_ok()
+                && buf.chunks_exact(SAMPLE_BYTES).all(|chunk| chunk == sample)
+        }
+        Property::ErrorPropagation => false,
+    }
+}
+
+fn error_propagation<B: BusOperation, M: LoopbackModel<B>>(bus: &mut B, model: &mut M) -> Outcome {
+    if !model.fail_next(bus) {
+        return Outcome::Skipped;
+    }
+    let read_fails = bus.read_from_register(Reg::WhoAmI as u8, &mut [0]).is_err();
+    let offset = model.peek(bus, Reg::XOfsUsr);
+    let write_fails = model.fail_next(bus)
+        && bus
+            .write_to_register(Reg::XOfsUsr as u8, &[offset])
+            .is_err();
+    let recovers = bus.read_from_register(Reg::WhoAmI as u8, &mut [0]).is_ok();
+    if read_fails && write_fails && recovers {
+        Outcome::Pass
+    } else {
+        Outcome::Fail
+    }
+}
+
+/// Loopback model of [`crate::mock::MockBus`], whose register map is the model; it injects no fault.
+#[cfg(feature = "mock")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct MockLoopback;
+
+#[cfg(feature = "mock")]
+impl LoopbackModel<crate::mock::MockBus> for MockLoopback {
+    fn peek(&mut self, bus: &mut crate::mock::MockBus, reg: Reg) -> u8 {
+        bus.reg(reg)
+    }
+
+    fn poke(&mut self, bus: &mut crate::mock::MockBus, reg: Reg, val: u8) {
+        bus.set_reg(reg, val);
+    }
+}
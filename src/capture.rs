@@ -0,0 +1,42 @@
+//! Pin-triggered capture sessions.
+//!
+//! In single data conversion mode with the INT2 pin as trigger, every rising edge on INT2 starts
+//! one conversion, for example on a camera shutter signal. A session started with
+//! [`crate::Iis2dlpc::triggered_capture_begin`] counts the samples read with
+//! [`crate::Iis2dlpc::triggered_capture_poll`], so that
+//! [`crate::Iis2dlpc::triggered_capture_end`] can compare them with the number of triggers
+//! the application issued.
+//!
+//! A trigger whose sample is overwritten by the next one before it is polled is not counted: the
+//! device keeps a single output sample, so the poll rate must exceed the trigger rate.
+
+/// Result of a pin-triggered capture session.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct CaptureStats {
+    /// Number of triggers the application expected to issue.
+    pub expected: u16,
+    /// Number of samples read during the session.
+    pub captured: u16,
+}
+
+impl CaptureStats {
+    /// Get the number of expected samples that were not captured.
+    pub fn missed(&self) -> u16 {
+        self.expected.saturating_sub(self.captured)
+    }
+
+    /// Check that every expected sample was captured, and no more.
+    pub fn is_complete(&self) -> bool {
+        self.captured == self.expected
+    }
+}
+
+/// State of an open capture session.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct CaptureSession {
+    /// `CTRL1` before the session.
+    pub(crate) ctrl1: u8,
+    /// `CTRL3` before the session.
+    pub(crate) ctrl3: u8,
+    pub(crate) stats: CaptureStats,
+}
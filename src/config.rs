@@ -0,0 +1,49 @@
+//! Configuration of the data path in a single call.
+//!
+//! [`Config`] gathers the settings every application writes after the start-up sequence of
+//! [`crate::Iis2dlpc::init`]; [`crate::Iis2dlpc::apply_config`] writes them phase by phase, see
+//! [`crate::phase`], so that the data rate starts last:
+//!
+//! ```ignore
+//! sensor.init()?;
+//! sensor.apply_config(&Config {
+//!     odr: Odr::_200hz,
+//!     mode: Mode::ContLowPwrLowNoise12bit,
+//!     ..Default::default()
+//! })?;
+//! ```
+//!
+//! The embedded functions, the interrupt routing and the FIFO keep their own setters.
+
+use crate::prelude::*;
+
+/// Data path settings written by [`crate::Iis2dlpc::apply_config`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Config {
+    /// Output data rate and trigger of the conversions, `odr` in `CTRL1` and `slp_mode` in `CTRL3`.
+    pub odr: Odr,
+    /// Full scale, `fs` in `CTRL6`.
+    pub fs: Fs,
+    /// Operating mode, `mode` and `lp_mode` in `CTRL1` and `low_noise` in `CTRL6`.
+    pub mode: Mode,
+    /// Block data update, `bdu` in `CTRL2`.
+    pub bdu: bool,
+    /// Filter on the outputs, `fds` in `CTRL6` and `usr_off_on_out` in `CTRL7`.
+    pub filter_path: Fds,
+    /// Filter bandwidth, `bw_filt` in `CTRL6`.
+    pub bandwidth: BwFilt,
+}
+
+impl Default for Config {
+    /// The reset configuration, with block data update enabled as [`crate::Iis2dlpc::init`] leaves it.
+    fn default() -> Self {
+        Self {
+            odr: Odr::Off,
+            fs: Fs::_2g,
+            mode: Mode::default(),
+            bdu: true,
+            filter_path: Fds::LpfOnOut,
+            bandwidth: BwFilt::OdrDiv2,
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Line-oriented register console.
+//!
+//! [`process_line`] runs a single command typed on a UART console, or any other text channel,
+//! against the device and writes the answer to a [`core::fmt::Write`] sink:
+//!
+//! | Command                  | Action                                                 |
+//! |--------------------------|--------------------------------------------------------|
+//! | `dump <addr> <len>`      | Read `len` registers from `addr`, 8 per output line    |
+//! | `read <reg-name>`        | Read a register, e.g. `read CTRL1`                     |
+//! | `write <reg-name> <hex>` | Write a register, e.g. `write CTRL6 0x10`              |
+//! | `id`                     | Read `WHO_AM_I`                                        |
+//! | `reset`                  | Software reset, then wait for its completion           |
+//!
+//! Numbers are hexadecimal with a `0x` prefix or decimal, the value of `write` is always
+//! hexadecimal; register names are the datasheet names, case insensitive. Writes to read-only
+//! registers and malformed commands are answered with an `error:` line and never reach the bus.
+//!
+//! Reading a source register (`WAKE_UP_SRC`, `TAP_SRC`, `SIXD_SRC`, `ALL_INT_SRC`) clears the
+//! latched interrupts, as any other read does.
+
+use core::fmt::Write;
+
+use crate::prelude::*;
+use crate::wait::WaitStrategy;
+use crate::{BusOperation, DelayNs, Error, Iis2dlpc};
+
+/// Registers known to the console: datasheet name, register and `true` if writable.
+const REGISTERS: [(&str, Reg, bool); 37] = [
+    ("OUT_T_L", Reg::OutTL, false),
+    ("OUT_T_H", Reg::OutTH, false),
+    ("WHO_AM_I", Reg::WhoAmI, false),
+    ("CTRL1", Reg::Ctrl1, true),
+    ("CTRL2", Reg::Ctrl2, true),
+    ("CTRL3", Reg::Ctrl3, true),
+    ("CTRL4_INT1_PAD_CTRL", Reg::Ctrl4Int1PadCtrl, true),
+    ("CTRL5_INT2_PAD_CTRL", Reg::Ctrl5Int2PadCtrl, true),
+    ("CTRL6", Reg::Ctrl6, true),
+    ("OUT_T", Reg::OutT, false),
+    ("STATUS", Reg::Status, false),
+    ("OUT_X_L", Reg::OutXL, false),
+    ("OUT_X_H", Reg::OutXH, false),
+    ("OUT_Y_L", Reg::OutYL, false),
+    ("OUT_Y_H", Reg::OutYH, false),
+    ("OUT_Z_L", Reg::OutZL, false),
+    ("OUT_Z_H", Reg::OutZH, false),
+    ("FIFO_CTRL", Reg::FifoCtrl, true),
+    ("FIFO_SAMPLES", Reg::FifoSamples, false),
+    ("TAP_THS_X", Reg::TapThsX, true),
+    ("TAP_THS_Y", Reg::TapThsY, true),
+    ("TAP_THS_Z", Reg::TapThsZ, true),
+    ("INT_DUR", Reg::IntDur, true),
+    ("WAKE_UP_THS", Reg::WakeUpThs, true),
+    ("WAKE_UP_DUR", Reg::WakeUpDur, true),
+    ("FREE_FALL", Reg::FreeFall, true),
+    ("STATUS_DUP", Reg::StatusDup, false),
+    ("WAKE_UP_SRC", Reg::WakeUpSrc, false),
+    ("TAP_SRC", Reg::TapSrc, false),
+    ("SIXD_SRC", Reg::SixdSrc, false),
+    ("ALL_INT_SRC", Reg::AllIntSrc, false),
+    ("X_OFS_USR", Reg::XOfsUsr, true),
+    ("Y_OFS_USR", Reg::YOfsUsr, true),
+    ("Z_OFS_USR", Reg::ZOfsUsr, true),
+    ("CTRL7", Reg::Ctrl7, true),
+    // Short aliases of the pad control registers
+    ("CTRL4", Reg::Ctrl4Int1PadCtrl, true),
+    ("CTRL5", Reg::Ctrl5Int2PadCtrl, true),
+];
+
+/// Status polls after a software reset before the console reports a timeout.
+const RESET_POLLS: u8 = 10;
+
+/// Process a console command line.
+///
+/// Bus errors are returned, while rejected commands are reported on `out` with an `error:` line.
+/// Errors of `out` itself are ignored: the console output is best effort.
+///
+/// ### Arguments
+/// - `sensor`: The driver.
+/// - `line`: The command line, leading and trailing whitespace ignored.
+/// - `out`: The sink of the answer.
+///
+/// ### Returns
+/// - `Ok(())`: If the command was processed or rejected.
+/// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+pub fn process_line<B: BusOperation, T: DelayNs>(
+    sensor: &mut Iis2dlpc<B, T>,
+    line: &str,
+    out: &mut impl Write,
+) -> Result<(), Error<B::Error>> {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else {
+        return Ok(());
+    };
+    let args = (words.next(), words.next(), words.next());
+
+    match (command, args) {
+        ("dump", (Some(addr), Some(len), None)) => {
+            let (Some(addr), Some(len)) = (parse_number(addr), parse_number(len)) else {
+                let _ = writeln!(out, "error: malformed number");
+                return Ok(());
+            };
+            if len == 0 || addr as usize + len as usize > REG_MAP_LEN {
+                let _ = writeln!(out, "error: range outside 0x00..0x{REG_MAP_LEN:02x}");
+                return Ok(());
+            }
+            dump(sensor, addr, len, out)
+        }
+        ("read", (Some(name), None, None)) => {
+            let Some((name, reg, _)) = lookup(name) else {
+                let _ = writeln!(out, "error: unknown register {name}");
+                return Ok(());
+            };
+            let mut buf = [0];
+            sensor.read_from_register(reg as u8, &mut buf)?;
+            let _ = writeln!(out, "{name} (0x{:02x}) = 0x{:02x}", reg as u8, buf[0]);
+            Ok(())
+        }
+        ("write", (Some(name), Some(val), None)) => {
+            let Some((name, reg, writable)) = lookup(name) else {
+                let _ = writeln!(out, "error: unknown register {name}");
+                return Ok(());
+            };
+            let Some(val) = parse_hex(val) else {
+                let _ = writeln!(out, "error: malformed value {val}");
+                return Ok(());
+            };
+            if !writable {
+                let _ = writeln!(out, "error: {name} is read-only");
+                return Ok(());
+            }
+            sensor.write_to_register(reg as u8, &[val])?;
+            let _ = writeln!(out, "{name} (0x{:02x}) <- 0x{val:02x}", reg as u8);
+            Ok(())
+        }
+        ("id", (None, None, None)) => {
+            let id = sensor.device_id_get()?;
+            let _ = writeln!(out, "WHO_AM_I = 0x{id:02x}");
+            Ok(())
+        }
+        ("reset", (None, None, None)) => {
+            sensor.reset_set()?;
+            for _ in 0..RESET_POLLS {
+                if sensor.reset_get()? == 0 {
+                    let _ = writeln!(out, "reset done");
+                    return Ok(());
+                }
+                sensor.tim.wait_us(10);
+            }
+            let _ = writeln!(out, "error: reset timeout");
+            Ok(())
+        }
+        ("dump" | "read" | "write" | "id" | "reset", _) => {
+            let _ = writeln!(out, "error: wrong arguments for {command}");
+            Ok(())
+        }
+        _ => {
+            let _ = writeln!(out, "error: unknown command {command}");
+            Ok(())
+        }
+    }
+}
+
+/// Read registers one at a time, so that the dump does not depend on the auto-increment and
+/// FIFO roll-back settings.
+fn dump<B: BusOperation, T: DelayNs>(
+    sensor: &mut Iis2dlpc<B, T>,
+    addr: u8,
+    len: u8,
+    out: &mut impl Write,
+) -> Result<(), Error<B::Error>> {
+    for (i, reg) in (addr..addr + len).enumerate() {
+        let mut buf = [0];
+        sensor.read_from_register(reg, &mut buf)?;
+        let _ = match i % 8 {
+            0 if i > 0 => write!(out, "\n{reg:02x}: {:02x}", buf[0]),
+            0 => write!(out, "{reg:02x}: {:02x}", buf[0]),
+            _ => write!(out, " {:02x}", buf[0]),
+        };
+    }
+    let _ = writeln!(out);
+    Ok(())
+}
+
+fn lookup(name: &str) -> Option<(&'static str, Reg, bool)> {
+    REGISTERS
+        .iter()
+        .find(|(known, _, _)| known.eq_ignore_ascii_case(name))
+        .copied()
+}
+
+fn parse_number(s: &str) -> Option<u8> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u8::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn parse_hex(s: &str) -> Option<u8> {
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    u8::from_str_radix(hex, 16).ok()
+}
@@ -0,0 +1,740 @@
+use crate::event::{self, Event, EventCounters, EventsDrainReport, PendingEvents, TapPolicy};
+#[cfg(feature = "storm-guard")]
+use crate::event::{EventRoute, StormPolicy};
+use crate::interrupt::InterruptConfig;
+#[cfg(feature = "heapless")]
+use crate::owned;
+use crate::prelude::*;
+use crate::{
+    BusOperation, DelayNs, Error, Iis2dlpc, PROPERTY_DISABLE, PROPERTY_ENABLE, QuiesceGuard,
+    RouteShadow, interrupts_enable_for,
+};
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// Set the data-ready interrupt mode.
+    ///
+    /// This function configures the data-ready interrupt mode by updating the `drdy_pulsed` field in the `CTRL7` register.
+    /// The data-ready interrupt can be configured as either latched or pulsed mode.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`DrdyPulsed`] value representing the desired data-ready interrupt mode:
+    ///   - `Latched`: Latched mode (default).
+    ///   - `Pulsed`: Pulsed mode.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn data_ready_mode_set(&mut self, val: DrdyPulsed) -> Result<(), Error<B::Error>> {
+        let mut ctrl7 = Ctrl7::read(self)?;
+        ctrl7.set_drdy_pulsed(val as u8);
+        ctrl7.write(self)
+    }
+
+    /// Get the data-ready interrupt mode.
+    ///
+    /// This function retrieves the current data-ready interrupt mode from the `drdy_pulsed` field in the `CTRL7` register.
+    ///
+    /// ### Returns
+    /// - `Ok(DrdyPulsed)`: The current data-ready interrupt mode as a [`DrdyPulsed`] value:
+    ///   - `Latched`: Latched mode (default).
+    ///   - `Pulsed`: Pulsed mode.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn data_ready_mode_get(&mut self) -> Result<DrdyPulsed, Error<B::Error>> {
+        Ok(DrdyPulsed::try_from(Ctrl7::read(self)?.drdy_pulsed()).unwrap_or_default())
+    }
+
+    /// Get the signals routed on the INT1 pad, as last known by the driver.
+    ///
+    /// This function performs no bus access: the copy is reloaded by every read of the `CTRL4_INT1_PAD_CTRL`
+    /// register, e.g. [`Self::pin_int1_route_get`] or [`Self::config_snapshot_get`], and updated by the route
+    /// setters and [`Self::config_snapshot_apply`]. A raw write of the register, see [`Self::write_to_register`],
+    /// a software reset, a reboot, a detected brown-out or [`Self::invalidate_cache`] mark it invalid.
+    ///
+    /// ### Returns
+    /// - `RouteShadow<Ctrl4Int1PadCtrl>`: The last known routing and whether it still holds.
+    pub fn int1_routes_shadow(&self) -> RouteShadow<Ctrl4Int1PadCtrl> {
+        self.state.int1_shadow
+    }
+
+    /// Get the signals routed on the INT2 pad, as last known by the driver.
+    ///
+    /// This function is the INT2 counterpart of [`Self::int1_routes_shadow`], for the `CTRL5_INT2_PAD_CTRL` register.
+    ///
+    /// ### Returns
+    /// - `RouteShadow<Ctrl5Int2PadCtrl>`: The last known routing and whether it still holds.
+    pub fn int2_routes_shadow(&self) -> RouteShadow<Ctrl5Int2PadCtrl> {
+        self.state.int2_shadow
+    }
+
+    /// Get all interrupt and status flags of the device.
+    ///
+    /// This function retrieves the status of all interrupt and status flags by reading the following registers
+    /// in a single burst, in this order:
+    /// - `STATUS_DUP`
+    /// - `WAKE_UP_SRC`
+    /// - `TAP_SRC`
+    /// - `SIXD_SRC`
+    /// - `ALL_INT_SRC`
+    ///
+    /// The summary register comes last, so that the latched detail flags are read before the summary read
+    /// clears them; see [`AllSources::is_consistent`]. `STATUS_DUP` is the source of the event flags and the sleep
+    /// state, see [`Self::status_reg_get`]. The burst read needs the register address auto-increment,
+    /// see [`Self::auto_increment_set`]. The flagged events are added to the event counters, see [`Self::event_counters`].
+    ///
+    /// ### Returns
+    /// - `Ok(AllSources)`: A struct containing the values of all the above registers.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn all_sources_get(&mut self) -> Result<AllSources, Error<B::Error>> {
+        Ok(AllSources::from_bytes(self.all_sources_bytes_get()?))
+    }
+
+    /// Get all interrupt and status flags of the device, undecoded.
+    ///
+    /// This function performs the read of [`Self::all_sources_get`], with the same order and the same update of
+    /// the event counters, and returns the raw registers: an interrupt handler can queue them and leave the
+    /// decoding to [`AllSources::from_bytes`] in task context.
+    ///
+    /// ### Returns
+    /// - `Ok([u8; AllSources::BYTES])`: The `STATUS_DUP`, `WAKE_UP_SRC`, `TAP_SRC`, `SIXD_SRC` and `ALL_INT_SRC` registers.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn all_sources_bytes_get(&mut self) -> Result<[u8; AllSources::BYTES], Error<B::Error>> {
+        let mut buf = [0; AllSources::BYTES];
+        self.read_from_register(Reg::StatusDup as u8, &mut buf)?;
+        self.state
+            .event_counters
+            .count_sources(&AllSources::from_bytes(buf));
+        Ok(buf)
+    }
+
+    /// Get the cumulative event counters.
+    ///
+    /// The counters cover every source register read by the driver since its creation or the last
+    /// [`Self::event_counters_reset`], see [`EventCounters`]. No bus access is performed.
+    ///
+    /// ### Returns
+    /// - `EventCounters`: The current counters.
+    pub fn event_counters(&self) -> EventCounters {
+        self.state.event_counters
+    }
+
+    /// Clear the cumulative event counters.
+    pub fn event_counters_reset(&mut self) {
+        self.state.event_counters = EventCounters::default();
+    }
+
+    /// Interrupt active-high/low.
+    ///
+    /// # Arguments
+    ///
+    /// * `val`: change the values of h_lactive in reg CTRL3.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `()`
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn pin_polarity_set(&mut self, val: HLactive) -> Result<(), Error<B::Error>> {
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_h_lactive(val as u8);
+        ctrl3.write(self)
+    }
+
+    /// Interrupt active-high/low.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `HLactive`: Get the values of h_lactive in reg CTRL3.
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn pin_polarity_get(&mut self) -> Result<HLactive, Error<B::Error>> {
+        Ok(HLactive::try_from(Ctrl3::read(self)?.h_lactive()).unwrap_or_default())
+    }
+
+    /// Latched/pulsed interrupt.
+    ///
+    /// # Arguments
+    ///
+    /// * `val`: change the values of lir in reg CTRL3.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `()`
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn int_notification_set(&mut self, val: Lir) -> Result<(), Error<B::Error>> {
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_lir(val as u8);
+        ctrl3.write(self)
+    }
+
+    /// Latched/pulsed interrupt.
+    ///
+    /// # Arguments
+    ///
+    /// * `val`: Get the values of lir in reg CTRL3.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `()`
+    pub fn int_notification_get(&mut self) -> Result<Lir, Error<B::Error>> {
+        Ok(Lir::try_from(Ctrl3::read(self)?.lir()).unwrap_or_default())
+    }
+
+    /// Push-pull/open drain selection on interrupt pads.
+    ///
+    /// # Arguments
+    ///
+    /// * `val`: change the values of pp_od in reg CTRL3.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `()`
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn pin_mode_set(&mut self, val: PpOd) -> Result<(), Error<B::Error>> {
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_pp_od(val as u8);
+        ctrl3.write(self)
+    }
+
+    /// Push-pull/open drain selection on interrupt pads.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `PpOd`: Get the values of pp_od in reg CTRL3.
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn pin_mode_get(&mut self) -> Result<PpOd, Error<B::Error>> {
+        Ok(PpOd::try_from(Ctrl3::read(self)?.pp_od()).unwrap_or_default())
+    }
+
+    /// Select the signal that need to route on int1 pad.
+    pub fn pin_int1_route_set(&mut self, val: &Ctrl4Int1PadCtrl) -> Result<(), Error<B::Error>> {
+        let ctrl5 = Ctrl5Int2PadCtrl::read(self)?;
+        let mut ctrl7: Ctrl7 = Ctrl7::read(self)?;
+        ctrl7.set_interrupts_enable(interrupts_enable_for(val, &ctrl5));
+
+        val.write(self)?;
+        self.state.int1_shadow = RouteShadow {
+            routes: *val,
+            shadow_valid: true,
+        };
+        ctrl7.write(self)
+    }
+
+    /// Update the signals routed on the INT1 pad without a window for spurious events.
+    ///
+    /// This function reads the `CTRL4_INT1_PAD_CTRL`, `CTRL5_INT2_PAD_CTRL` and `CTRL7` registers once, applies
+    /// `f` to the INT1 routing and computes the `interrupts_enable` field in the `CTRL7` register, then writes:
+    /// 1. `CTRL7` with `interrupts_enable` cleared, which stops every embedded function event from reaching the pads;
+    /// 2. `CTRL4_INT1_PAD_CTRL` with the new routing;
+    /// 3. `CTRL7` with the final `interrupts_enable`.
+    ///
+    /// At no point is an embedded function event routed with a routing and an enable bit that do not belong
+    /// together, which makes the function usable from the handler of the interrupt it reconfigures.
+    /// [`Self::pin_int1_route_set`] writes the routing first, so an event can fire on the new routing before
+    /// `CTRL7` is updated. The data-ready and FIFO signals do not depend on `interrupts_enable` and switch
+    /// with the second write.
+    ///
+    /// ### Arguments
+    /// - `f`: The change of the INT1 routing.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn int1_route_update_critical(
+        &mut self,
+        f: impl FnOnce(&mut Ctrl4Int1PadCtrl),
+    ) -> Result<(), Error<B::Error>> {
+        let mut ctrl4 = Ctrl4Int1PadCtrl::read(self)?;
+        let ctrl5 = Ctrl5Int2PadCtrl::read(self)?;
+        let ctrl7 = Ctrl7::read(self)?;
+        f(&mut ctrl4);
+
+        ctrl7.with_interrupts_enable(PROPERTY_DISABLE).write(self)?;
+        ctrl4.write(self)?;
+        self.state.int1_shadow = RouteShadow {
+            routes: ctrl4,
+            shadow_valid: true,
+        };
+        ctrl7
+            .with_interrupts_enable(interrupts_enable_for(&ctrl4, &ctrl5))
+            .write(self)
+    }
+
+    /// Select the signal that need to route on int1 pad.
+    pub fn pin_int1_route_get(&mut self) -> Result<Ctrl4Int1PadCtrl, Error<B::Error>> {
+        Ctrl4Int1PadCtrl::read(self)
+    }
+
+    /// Select the signal that need to route on int2 pad.
+    ///
+    /// Routing `int2_drdy_t` with the temperature policy set to [`crate::sample::TempPolicy::Ignore`] returns
+    /// `Err(Error::InvalidConfig)` and writes nothing.
+    pub fn pin_int2_route_set(&mut self, val: &Ctrl5Int2PadCtrl) -> Result<(), Error<B::Error>> {
+        if val.int2_drdy_t() == 1 {
+            self.temp_policy_check()?;
+        }
+        let ctrl4 = Ctrl4Int1PadCtrl::read(self)?;
+        let mut ctrl7 = Ctrl7::read(self)?;
+        ctrl7.set_interrupts_enable(interrupts_enable_for(&ctrl4, val));
+
+        val.write(self)?;
+        self.state.int2_shadow = RouteShadow {
+            routes: *val,
+            shadow_valid: true,
+        };
+        ctrl7.write(self)
+    }
+
+    /// Update the signals routed on the INT2 pad without a window for spurious events.
+    ///
+    /// This function is the INT2 counterpart of [`Self::int1_route_update_critical`], with the same write order:
+    /// `CTRL7` with `interrupts_enable` cleared, `CTRL5_INT2_PAD_CTRL`, then `CTRL7` with the final value.
+    /// Routing `int2_drdy_t` with the temperature policy set to [`crate::sample::TempPolicy::Ignore`] returns
+    /// `Err(Error::InvalidConfig)` and writes nothing.
+    ///
+    /// ### Arguments
+    /// - `f`: The change of the INT2 routing.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidConfig)`: If `int2_drdy_t` is routed while the temperature is ignored; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn int2_route_update_critical(
+        &mut self,
+        f: impl FnOnce(&mut Ctrl5Int2PadCtrl),
+    ) -> Result<(), Error<B::Error>> {
+        let ctrl4 = Ctrl4Int1PadCtrl::read(self)?;
+        let mut ctrl5 = Ctrl5Int2PadCtrl::read(self)?;
+        let ctrl7 = Ctrl7::read(self)?;
+        f(&mut ctrl5);
+        if ctrl5.int2_drdy_t() == 1 {
+            self.temp_policy_check()?;
+        }
+
+        ctrl7.with_interrupts_enable(PROPERTY_DISABLE).write(self)?;
+        ctrl5.write(self)?;
+        self.state.int2_shadow = RouteShadow {
+            routes: ctrl5,
+            shadow_valid: true,
+        };
+        ctrl7
+            .with_interrupts_enable(interrupts_enable_for(&ctrl4, &ctrl5))
+            .write(self)
+    }
+
+    /// Select the signal that need to route on int2 pad.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `Ctrl5Int2PadCtrl`: register CTRL5_INT2_PAD_CTRL.
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn pin_int2_route_get(&mut self) -> Result<Ctrl5Int2PadCtrl, Error<B::Error>> {
+        Ctrl5Int2PadCtrl::read(self)
+    }
+
+    /// Set the routing and the electrical settings of both interrupt pads.
+    ///
+    /// This function writes the whole [`InterruptConfig`] in the phase order of [`crate::phase`], with the sequence of
+    /// [`Self::int1_route_update_critical`] for the routing:
+    /// 1. `CTRL3` with the notification, the polarity and the pad drive;
+    /// 2. `CTRL7` with `interrupts_enable` cleared and the new `int2_on_int1`;
+    /// 3. `CTRL4_INT1_PAD_CTRL` and `CTRL5_INT2_PAD_CTRL` with the new routing;
+    /// 4. `CTRL7` with `interrupts_enable` set if an embedded function event is routed on either pad, see
+    ///    [`InterruptConfig::interrupts_enable`].
+    ///
+    /// The enable bit is derived from both routings at once, so that the result does not depend on the order
+    /// of the calls as with [`Self::pin_int1_route_set`] and [`Self::pin_int2_route_set`]: the sleep state
+    /// routed on INT2 alone enables the interrupts. The other fields of `CTRL3` and `CTRL7` are kept.
+    ///
+    /// ### Arguments
+    /// - `cfg`: The interrupt pads settings.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidConfig)`: If `int2_drdy_t` is routed while the temperature is ignored; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn interrupt_config_set(&mut self, cfg: &InterruptConfig) -> Result<(), Error<B::Error>> {
+        if cfg.int2_routes.int2_drdy_t() == 1 {
+            self.temp_policy_check()?;
+        }
+        let mut ctrl3 = Ctrl3::read(self)?;
+        let mut ctrl7 = Ctrl7::read(self)?;
+        ctrl3.set_lir(cfg.latch as u8);
+        ctrl3.set_h_lactive(cfg.polarity as u8);
+        ctrl3.set_pp_od(cfg.pin_mode as u8);
+        ctrl7.set_int2_on_int1(cfg.int2_on_int1 as u8);
+
+        ctrl3.write(self)?;
+        ctrl7.with_interrupts_enable(PROPERTY_DISABLE).write(self)?;
+        cfg.int1_routes.write(self)?;
+        self.state.int1_shadow = RouteShadow {
+            routes: cfg.int1_routes,
+            shadow_valid: true,
+        };
+        cfg.int2_routes.write(self)?;
+        self.state.int2_shadow = RouteShadow {
+            routes: cfg.int2_routes,
+            shadow_valid: true,
+        };
+        ctrl7
+            .with_interrupts_enable(cfg.interrupts_enable() as u8)
+            .write(self)
+    }
+
+    /// Get the routing and the electrical settings of both interrupt pads.
+    ///
+    /// This function reads the `CTRL3`, `CTRL4_INT1_PAD_CTRL`, `CTRL5_INT2_PAD_CTRL` and `CTRL7` registers,
+    /// see [`Self::interrupt_config_set`].
+    ///
+    /// ### Returns
+    /// - `Ok(InterruptConfig)`: The interrupt pads settings.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn interrupt_config_get(&mut self) -> Result<InterruptConfig, Error<B::Error>> {
+        let ctrl3 = Ctrl3::read(self)?;
+        let ctrl7 = Ctrl7::read(self)?;
+        Ok(InterruptConfig {
+            int1_routes: Ctrl4Int1PadCtrl::read(self)?,
+            int2_routes: Ctrl5Int2PadCtrl::read(self)?,
+            int2_on_int1: ctrl7.int2_on_int1() == PROPERTY_ENABLE,
+            latch: Lir::try_from(ctrl3.lir()).unwrap_or_default(),
+            polarity: HLactive::try_from(ctrl3.h_lactive()).unwrap_or_default(),
+            pin_mode: PpOd::try_from(ctrl3.pp_od()).unwrap_or_default(),
+        })
+    }
+
+    /// Silence the interrupt pads, e.g. for a firmware update window.
+    ///
+    /// This function saves the INT1 and INT2 routing, from the driver-side copies when they are valid (see
+    /// [`Self::int1_routes_shadow`]) or from the `CTRL4_INT1_PAD_CTRL` and `CTRL5_INT2_PAD_CTRL` registers otherwise,
+    /// and the `interrupts_enable` field in the `CTRL7` register, then clears `interrupts_enable` and both routing
+    /// registers, in this order. The pads stay silent until [`Self::interrupts_restore`] is called: a guard
+    /// cannot restore the device when dropped, since that needs the bus.
+    ///
+    /// Calling this function again before the restore silences the pads again and returns the guard of the first
+    /// call, so that any of the guards restores the configuration from before the first call.
+    ///
+    /// ### Returns
+    /// - `Ok(QuiesceGuard)`: The saved interrupt output.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation; the pads may
+    ///   be partly silenced, call this function again.
+    pub fn interrupts_quiesce(&mut self) -> Result<QuiesceGuard, Error<B::Error>> {
+        let ctrl7 = Ctrl7::read(self)?;
+        let guard = match self.state.quiesced {
+            Some(guard) => guard,
+            None => {
+                if !self.state.int1_shadow.shadow_valid {
+                    Ctrl4Int1PadCtrl::read(self)?;
+                }
+                if !self.state.int2_shadow.shadow_valid {
+                    Ctrl5Int2PadCtrl::read(self)?;
+                }
+                QuiesceGuard {
+                    int1_routes: self.state.int1_shadow.routes,
+                    int2_routes: self.state.int2_shadow.routes,
+                    interrupts_enable: ctrl7.interrupts_enable(),
+                }
+            }
+        };
+        self.state.quiesced = Some(guard);
+
+        ctrl7.with_interrupts_enable(PROPERTY_DISABLE).write(self)?;
+        Ctrl4Int1PadCtrl::new().write(self)?;
+        self.state.int1_shadow = RouteShadow {
+            routes: Ctrl4Int1PadCtrl::new(),
+            shadow_valid: true,
+        };
+        Ctrl5Int2PadCtrl::new().write(self)?;
+        self.state.int2_shadow = RouteShadow {
+            routes: Ctrl5Int2PadCtrl::new(),
+            shadow_valid: true,
+        };
+        Ok(guard)
+    }
+
+    /// Restore the interrupt output saved by [`Self::interrupts_quiesce`].
+    ///
+    /// This function writes the saved routing to the `CTRL4_INT1_PAD_CTRL` and `CTRL5_INT2_PAD_CTRL` registers,
+    /// then the saved `interrupts_enable` field in the `CTRL7` register, whose other fields are kept.
+    ///
+    /// ### Arguments
+    /// - `guard`: The guard returned by [`Self::interrupts_quiesce`].
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful; the next [`Self::interrupts_quiesce`] saves the output again.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation; call this
+    ///   function again with a copy of the guard.
+    pub fn interrupts_restore(&mut self, guard: QuiesceGuard) -> Result<(), Error<B::Error>> {
+        guard.int1_routes.write(self)?;
+        self.state.int1_shadow = RouteShadow {
+            routes: guard.int1_routes,
+            shadow_valid: true,
+        };
+        guard.int2_routes.write(self)?;
+        self.state.int2_shadow = RouteShadow {
+            routes: guard.int2_routes,
+            shadow_valid: true,
+        };
+        Ctrl7::read(self)?
+            .with_interrupts_enable(guard.interrupts_enable)
+            .write(self)?;
+        self.state.quiesced = None;
+        Ok(())
+    }
+
+    /// All interrupt signals become available on INT1 pin.
+    ///
+    /// # Arguments
+    ///
+    /// * `val`: `true` to route all the interrupt signals to INT1, `int2_on_int1` in reg CTRL_REG7.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `()`
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn all_on_int1_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut reg = Ctrl7::read(self)?;
+        reg.set_int2_on_int1(val as u8);
+        reg.write(self)
+    }
+
+    /// All interrupt signals become available on INT1 pin.
+    ///
+    /// # Returns
+    ///
+    /// * `Result`
+    ///     * `bool`: the value of int2_on_int1 in reg CTRL_REG7.
+    ///     * `Err`: Returns an error if the operation fails.
+    pub fn all_on_int1_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(Ctrl7::read(self)?.int2_on_int1() == PROPERTY_ENABLE)
+    }
+
+    /// Get the axis priority for tap detection.
+    ///
+    /// This function retrieves the current axis priority for tap detection from the `tap_prior` field in the `TAP_THS_Y` register.
+    ///
+    /// ### Returns
+    /// - `Ok(TapPrior)`: The current axis priority as a [`TapPrior`] value.
+    /// - `Err(Error::UnexpectedValue)`: If the field holds one of the reserved codes (4 or 7), after the retry of
+    ///   [`Self::decode_retry_set`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_axis_priority_get(&mut self) -> Result<TapPrior, Error<B::Error>> {
+        self.read_decoded(|sensor| Ok(TapThsY::read(sensor)?.tap_prior()))
+    }
+
+    /// Set how a single tap flagged together with a double tap is reported.
+    ///
+    /// When both single and double tap are enabled ([`SingleDoubleTap::BothSingleDouble`]), the first tap of a
+    /// double tap can also raise `SINGLE_TAP` in the same `TAP_SRC` read. [`TapPolicy::PreferDouble`] (default)
+    /// reports only the double tap; [`TapPolicy::ReportBoth`] reports both flags as read.
+    /// The policy applies to [`Self::tap_event_get`] and [`Self::events_drain`].
+    ///
+    /// ### Arguments
+    /// - `val`: The [`TapPolicy`] to use.
+    pub fn tap_policy_set(&mut self, val: TapPolicy) {
+        self.state.tap_policy = val;
+    }
+
+    /// Get how a single tap flagged together with a double tap is reported.
+    ///
+    /// ### Returns
+    /// - `TapPolicy`: The current [`TapPolicy`].
+    pub fn tap_policy_get(&self) -> TapPolicy {
+        self.state.tap_policy
+    }
+
+    /// Drain the pending events.
+    ///
+    /// This function reads all the source registers, see [`Self::all_sources_get`] for the read order, and decodes
+    /// every flagged event.
+    /// With latched interrupts the read also clears them. The temperature data-ready flag (`drdy_t`) is not an event.
+    /// The drain counts towards the interrupt storm guard, see [`Self::events_drain_report`].
+    ///
+    /// ### Arguments
+    /// - `out`: Destination of the events; a buffer of [`event::MAX_EVENTS`] always holds every event of a read.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of events written to `out`.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn events_drain(&mut self, out: &mut [Event]) -> Result<usize, Error<B::Error>> {
+        Ok(self.events_drain_report(out)?.count)
+    }
+
+    /// Get the next pending event.
+    ///
+    /// This function returns the events of [`Self::events_drain`] one at a time: when the events of the previous
+    /// read are all returned, it drains the source registers again, and keeps the other events of that read for the
+    /// next calls, which perform no bus access. A polling loop can thus handle every event with a single match:
+    ///
+    /// ```ignore
+    /// while let Some(event) = sensor.event_get()? {
+    ///     match event {
+    ///         Event::Tap(tap) => { /* tap.kind, tap.axis, tap.negative */ }
+    ///         Event::WakeUp { axes } => { /* ... */ }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The read is the single burst of [`Self::all_sources_get`], which fetches the detail registers before
+    /// `ALL_INT_SRC`: with latched interrupts, reading `ALL_INT_SRC` clears every flag, the axes and the sign of the
+    /// detail registers included, so the summary cannot be read first. The read clears the latched interrupts, which
+    /// are then returned once. With pulsed interrupts, an event still flagged is returned again by the next read.
+    /// [`Self::events_drain`] does not return the events kept by this function.
+    ///
+    /// ### Returns
+    /// - `Ok(Some(Event))`: The next event, converted to the board frame, see [`Self::axis_remap_set`].
+    /// - `Ok(None)`: If no event is flagged.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn event_get(&mut self) -> Result<Option<Event>, Error<B::Error>> {
+        if let Some(event) = self.state.pending_events.next() {
+            return Ok(Some(event));
+        }
+        let mut buf = [Event::FreeFall; event::MAX_EVENTS];
+        let count = self.events_drain(&mut buf)?;
+        self.state.pending_events = PendingEvents::new(buf.get(..count).unwrap_or_default());
+        Ok(self.state.pending_events.next())
+    }
+
+    /// Drain the pending events and report the state of the interrupt storm guard.
+    ///
+    /// This function is [`Self::events_drain`] with the outcome of the guard, see `Self::events_rate_limit_set`;
+    /// without the `storm-guard` feature the report is never throttled.
+    /// With `StormPolicy::DisableRoute`, the drain that trips the guard also removes the most frequent event of
+    /// the storm from the pad routing, as [`Self::int1_route_update_critical`] or
+    /// [`Self::int2_route_update_critical`] do; the routing is taken from the driver copy, see
+    /// [`Self::int1_routes_shadow`], and read first if the copy is not valid. An event that is not routed is not
+    /// written again.
+    ///
+    /// ### Arguments
+    /// - `out`: Destination of the events; a buffer of [`event::MAX_EVENTS`] always holds every event of a read.
+    ///
+    /// ### Returns
+    /// - `Ok(EventsDrainReport)`: The number of events written to `out`, the guard state and the disabled route.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn events_drain_report(
+        &mut self,
+        out: &mut [Event],
+    ) -> Result<EventsDrainReport, Error<B::Error>> {
+        let sources = self.all_sources_get()?;
+        let priority = self.tap_axis_priority_get()?;
+        let mut events = [Event::FreeFall; event::MAX_EVENTS];
+        let decoded = sources.decode_events(self.state.tap_policy, priority, &mut events);
+        let events = events.get(..decoded).unwrap_or_default();
+
+        let mut count = 0;
+        for (slot, event) in out.iter_mut().zip(events) {
+            *slot = self.state.axis_remap.event(*event);
+            count += 1;
+        }
+
+        #[cfg(feature = "storm-guard")]
+        let (throttled, disabled) = {
+            let (throttled, offender) = self.state.storm_guard.account(events);
+            let disabled = match offender {
+                Some(route) if self.state.storm_guard.policy == StormPolicy::DisableRoute => {
+                    self.event_route_disable(route)?.then_some(route)
+                }
+                _ => None,
+            };
+            (throttled, disabled)
+        };
+        #[cfg(not(feature = "storm-guard"))]
+        let (throttled, disabled) = (false, None);
+        Ok(EventsDrainReport {
+            count,
+            decoded,
+            throttled,
+            disabled,
+        })
+    }
+
+    /// Set the interrupt storm guard limit.
+    ///
+    /// Every call of [`Self::events_drain_report`], and of the functions built on it, counts the decoded events; once
+    /// the count exceeds `max_events_per_poll` for [`event::STORM_POLLS`] consecutive calls, the report is flagged
+    /// as throttled, until a call stays within the limit. A single read decodes at most [`event::MAX_EVENTS`]
+    /// events, so `0` flags an event on every drain as a storm, and [`event::MAX_EVENTS`] or more never trips.
+    /// Setting the limit restarts the detection. No bus access is performed.
+    ///
+    /// With latched interrupts, drained from the interrupt handler, a healthy configuration flags an event on a
+    /// few drains at most; a wake-up threshold of 0 flags one on every drain.
+    ///
+    /// ### Arguments
+    /// - `max_events_per_poll`: The highest number of events of a drain that does not count towards a storm.
+    #[cfg(feature = "storm-guard")]
+    pub fn events_rate_limit_set(&mut self, max_events_per_poll: u16) {
+        self.state.storm_guard.limit_set(Some(max_events_per_poll));
+    }
+
+    /// Disable the interrupt storm guard, see [`Self::events_rate_limit_set`].
+    #[cfg(feature = "storm-guard")]
+    pub fn events_rate_limit_clear(&mut self) {
+        self.state.storm_guard.limit_set(None);
+    }
+
+    /// Set the action of the interrupt storm guard, see [`Self::events_rate_limit_set`].
+    ///
+    /// ### Arguments
+    /// - `policy`: The action taken on the drain that trips the guard.
+    #[cfg(feature = "storm-guard")]
+    pub fn events_storm_policy_set(&mut self, policy: StormPolicy) {
+        self.state.storm_guard.policy = policy;
+    }
+
+    /// Get the action of the interrupt storm guard.
+    #[cfg(feature = "storm-guard")]
+    pub fn events_storm_policy_get(&self) -> StormPolicy {
+        self.state.storm_guard.policy
+    }
+
+    /// Remove an event from the pad routing; returns `false` if it was not routed.
+    #[cfg(feature = "storm-guard")]
+    fn event_route_disable(&mut self, route: EventRoute) -> Result<bool, Error<B::Error>> {
+        if !self.state.int1_shadow.shadow_valid {
+            Ctrl4Int1PadCtrl::read(self)?;
+        }
+        if !self.state.int2_shadow.shadow_valid {
+            Ctrl5Int2PadCtrl::read(self)?;
+        }
+        if !route.is_routed(
+            &self.state.int1_shadow.routes,
+            &self.state.int2_shadow.routes,
+        ) {
+            return Ok(false);
+        }
+        if route == EventRoute::SleepChange {
+            self.int2_route_update_critical(|int2| route.clear_int2(int2))?;
+        } else {
+            self.int1_route_update_critical(|int1| route.clear_int1(int1))?;
+        }
+        Ok(true)
+    }
+
+    /// Drain the pending events into a `heapless::Vec`.
+    ///
+    /// This function is [`Self::events_drain`] with an owned result: events that do not fit in `N` are dropped, a
+    /// vector of [`event::MAX_EVENTS`] always holds every event of a read. `N` must not be 0.
+    ///
+    /// ### Returns
+    /// - `Ok(heapless::Vec<Event, N>)`: The events.
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    #[cfg(feature = "heapless")]
+    pub fn events_drain_vec<const N: usize>(
+        &mut self,
+    ) -> Result<heapless::Vec<Event, N>, Error<B::Error>> {
+        const { assert!(N >= 1, "the vector must hold at least one event") };
+        let mut buf = [Event::FreeFall; event::MAX_EVENTS];
+        let len = N.min(buf.len());
+        let count = self.events_drain(buf.get_mut(..len).unwrap_or_default())?;
+        Ok(owned(buf.get(..count).unwrap_or_default()))
+    }
+}
@@ -0,0 +1,591 @@
+#[cfg(feature = "events")]
+use core::convert::Infallible;
+#[cfg(feature = "events")]
+use embedded_hal::digital::InputPin;
+
+#[cfg(feature = "events")]
+use crate::PROPERTY_ENABLE;
+use crate::batch;
+#[cfg(feature = "dsp")]
+use crate::dsp;
+use crate::fifo::{FIFO_DEPTH, FifoDrainReport, FifoFlags, FifoIntegrity, FifoWatermarkReport};
+use crate::prelude::*;
+use crate::remap::AxisRemap;
+#[cfg(feature = "replay")]
+use crate::replay;
+use crate::wait::WaitStrategy;
+use crate::{
+    BusOperation, ConfigError, DelayNs, Error, Iis2dlpc, field_check, fifo, sensitivity_mg_per_lsb,
+};
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// Set the FIFO watermark level.
+    ///
+    /// This function configures the FIFO watermark level by updating the `fth` field in the `FIFO_CTRL` register.
+    /// The watermark level determines the threshold at which the FIFO generates an interrupt when the number of unread samples reaches the specified level.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired FIFO watermark level.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 5-bit `fth` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn fifo_watermark_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 5)?;
+        let mut reg = FifoCtrl::read(self)?;
+        reg.set_fth(val);
+        reg.write(self)
+    }
+
+    /// Get the FIFO watermark level.
+    ///
+    /// This function retrieves the current FIFO watermark level from the `fth` field in the `FIFO_CTRL` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current FIFO watermark level.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_watermark_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoCtrl::read(self)?.fth())
+    }
+
+    /// Get the number of unread samples stored in the FIFO.
+    ///
+    /// This function retrieves the number of unread samples currently stored in the FIFO buffer from the `diff` field in the `FIFO_SAMPLES` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The number of unread samples in the FIFO.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_data_level_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoSamples::read(self)?.diff())
+    }
+
+    /// Get the FIFO overrun status.
+    ///
+    /// This function retrieves the FIFO overrun status from the `fifo_ovr` field in the `FIFO_SAMPLES` register.
+    /// The overrun status indicates whether the FIFO buffer has overwritten old data due to being full.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current FIFO overrun status:
+    ///   - `0`: No overrun has occurred.
+    ///   - `1`: FIFO has overwritten old data.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_ovr_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoSamples::read(self)?.fifo_ovr())
+    }
+
+    /// Get the FIFO threshold status flag.
+    ///
+    /// This function retrieves the FIFO threshold status flag from the `fifo_fth` field in the `FIFO_SAMPLES` register.
+    /// The threshold status indicates whether the number of unread samples in the FIFO has reached the configured watermark level.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current FIFO threshold status flag:
+    ///   - `0`: FIFO filling is below the threshold level.
+    ///   - `1`: FIFO filling has reached or exceeded the threshold level.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_wtm_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(FifoSamples::read(self)?.fifo_fth())
+    }
+
+    /// Get the FIFO full status flag.
+    ///
+    /// This function reads the `diff` field in the `FIFO_SAMPLES` register: the FIFO is full when it holds
+    /// [`fifo::FIFO_DEPTH`] samples, the condition routed to the pads by `int1_diff5` and `int2_diff5`.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current FIFO full status flag:
+    ///   - `0`: FIFO is not full.
+    ///   - `1`: FIFO holds 32 samples; the next one overwrites the oldest in stream mode.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_full_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(self.fifo_flags_get()?.full as u8)
+    }
+
+    /// Get the FIFO level and status flags in a single read.
+    ///
+    /// This function reads the `FIFO_SAMPLES` register once, so that the level, the threshold, overrun and full
+    /// flags belong to the same FIFO state. After an overrun, [`Self::fifo_flush`] empties the FIFO and clears
+    /// the flag.
+    ///
+    /// ### Returns
+    /// - `Ok(FifoFlags)`: The FIFO level and the threshold, overrun and full flags.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_flags_get(&mut self) -> Result<FifoFlags, Error<B::Error>> {
+        Ok(FifoFlags::from(FifoSamples::read(self)?))
+    }
+
+    /// Get the samples stored in the FIFO.
+    ///
+    /// This function reads the `diff` field in the `FIFO_SAMPLES` register, see [`Self::fifo_data_level_get`], and
+    /// burst reads as many samples as are stored and fit in `out`, at most [`fifo::FIFO_DEPTH`], in a single bus
+    /// transaction. It works in every FIFO mode: in bypass mode no sample is stored. The overrun flag is not
+    /// checked, see [`Self::fifo_drain`] for a drain reporting the integrity of the samples.
+    ///
+    /// ### Arguments
+    /// - `out`: The destination of the raw samples, oldest first and in the board frame (see
+    ///   [`Self::axis_remap_set`]).
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of samples written to `out`; `0` with an empty FIFO, in which case only
+    ///   `FIFO_SAMPLES` was read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_data_get(&mut self, out: &mut [[i16; 3]]) -> Result<usize, Error<B::Error>> {
+        self.fifo_data_read(out, self.state.axis_remap)
+    }
+
+    /// Get the samples stored in the FIFO in mg.
+    ///
+    /// This function reads the full scale and the operating mode, then the samples as [`Self::fifo_data_get`]
+    /// does, and scales them as [`Self::acceleration_mg_get`] does (12-bit resolution in low-power mode 1).
+    ///
+    /// ### Arguments
+    /// - `out`: The destination of the acceleration on the X, Y and Z axes in mg, oldest first and in the board
+    ///   frame.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of samples written to `out`.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_data_mg_get(&mut self, out: &mut [[f32; 3]]) -> Result<usize, Error<B::Error>> {
+        let fs = self.full_scale_get()?;
+        let low_power_1 = self.power_mode_get()?.is_low_power_1();
+        let mut raw = [[0; 3]; FIFO_DEPTH];
+        let len = out.len().min(FIFO_DEPTH);
+        // Scaled in the sensor frame, as by `acceleration_mg_get`
+        let n = self.fifo_data_read(raw.get_mut(..len).unwrap_or_default(), AxisRemap::IDENTITY)?;
+
+        for (mg, sample) in out.iter_mut().zip(raw.iter().take(n)) {
+            let sensor = sample.map(|lsb| batch::raw_to_mg(lsb, fs, low_power_1));
+            *mg = self.state.axis_remap.mg(sensor);
+        }
+        Ok(n)
+    }
+
+    /// Read the samples stored in the FIFO that fit in `out`, see [`Self::fifo_data_get`].
+    fn fifo_data_read(
+        &mut self,
+        out: &mut [[i16; 3]],
+        remap: AxisRemap,
+    ) -> Result<usize, Error<B::Error>> {
+        let diff = FifoSamples::read(self)?.diff();
+        let n = (diff as usize).min(out.len()).min(FIFO_DEPTH);
+        if n == 0 {
+            return Ok(0);
+        }
+        self.fifo_burst_read(out.get_mut(..n).unwrap_or_default(), remap)?;
+        Ok(n)
+    }
+
+    /// Burst read `out.len()` samples from the FIFO, in the frame of `remap`.
+    fn fifo_burst_read(
+        &mut self,
+        out: &mut [[i16; 3]],
+        remap: AxisRemap,
+    ) -> Result<(), Error<B::Error>> {
+        let mut buf = [0; FIFO_DEPTH * batch::SAMPLE_BYTES];
+        let Some(dst) = buf.get_mut(..out.len() * batch::SAMPLE_BYTES) else {
+            return Err(Error::OutOfRange {
+                requested: out.len() as u32,
+                max: FIFO_DEPTH as u32,
+            });
+        };
+        self.read_from_register(Reg::OutXL as u8, dst)?;
+
+        for (sample, bytes) in out.iter_mut().zip(dst.chunks_exact(batch::SAMPLE_BYTES)) {
+            if let Ok(bytes) = bytes.try_into() {
+                *sample = remap.raw(batch::raw_from_bytes(bytes));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drain the FIFO into a buffer and check it for overruns.
+    ///
+    /// This function reads the `FIFO_SAMPLES` register, burst reads as many samples as are stored and fit in
+    /// `buf`, then reads `FIFO_SAMPLES` again: the `fifo_ovr` flag of the two reads tells whether samples
+    /// were overwritten before or during the drain, see [`fifo`] for the residual race window.
+    ///
+    /// With `auto_recover`, a compromised drain is followed by a FIFO flush (bypass mode, then the previous
+    /// FIFO mode) and a single new drain, which only returns the samples collected since the flush.
+    ///
+    /// ### Arguments
+    /// - `buf`: The destination, 6 bytes per sample laid out as in [`batch::SampleBatch`].
+    /// - `auto_recover`: Flush and drain again once if the drain is compromised.
+    ///
+    /// ### Returns
+    /// - `Ok(FifoDrainReport)`: The number of samples read, their integrity and the FIFO level before the drain.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn fifo_drain(
+        &mut self,
+        buf: &mut [u8],
+        auto_recover: bool,
+    ) -> Result<FifoDrainReport, Error<B::Error>> {
+        let report = self.fifo_drain_once(buf)?;
+        if !auto_recover || report.integrity != FifoIntegrity::Compromised {
+            return Ok(report);
+        }
+
+        self.fifo_flush()?;
+        Ok(FifoDrainReport {
+            retried: true,
+            ..self.fifo_drain_once(buf)?
+        })
+    }
+
+    /// Drain the FIFO into a `heapless::Vec`.
+    ///
+    /// This function is [`Self::fifo_drain`] without recovery, with the raw samples decoded into an owned vector:
+    /// at most `N` samples are read, and the samples left in the FIFO show as
+    /// [`FifoDrainReport::start_diff`] above [`FifoDrainReport::samples`]. `N` must not be 0.
+    ///
+    /// ### Returns
+    /// - `Ok((FifoSampleVec<N>, FifoDrainReport))`: The raw samples, oldest first and in the board frame (see
+    ///   [`Self::axis_remap_set`]), and the drain report.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    #[cfg(feature = "heapless")]
+    pub fn fifo_drain_vec<const N: usize>(
+        &mut self,
+    ) -> Result<(fifo::FifoSampleVec<N>, FifoDrainReport), Error<B::Error>> {
+        const { assert!(N >= 1, "the vector must hold at least one sample") };
+        let mut buf = [0; FIFO_DEPTH * batch::SAMPLE_BYTES];
+        let len = N.min(FIFO_DEPTH) * batch::SAMPLE_BYTES;
+        let report = self.fifo_drain(buf.get_mut(..len).unwrap_or_default(), false)?;
+
+        let samples = buf
+            .get(..report.samples * batch::SAMPLE_BYTES)
+            .unwrap_or_default();
+        let mut vec = heapless::Vec::new();
+        for sample in batch::SampleBatch::new(samples).into_iter().flatten() {
+            let _ = vec.push(self.state.axis_remap.raw(sample));
+        }
+        Ok((vec, report))
+    }
+
+    /// Drain the FIFO into sample sinks.
+    ///
+    /// This function reads the full scale and the operating mode, drains the FIFO as [`Self::fifo_drain`] does
+    /// without recovery and feeds the samples to `sinks` as [`replay::replay_fifo_bytes_remapped`] does with the
+    /// axis remapping, oldest first: a recording of the drained bytes replayed on a host gives the sinks the same
+    /// values.
+    ///
+    /// ### Arguments
+    /// - `sinks`: The sinks to feed, see [`replay::SampleSink`].
+    ///
+    /// ### Returns
+    /// - `Ok(FifoDrainReport)`: The number of samples fed, their integrity and the FIFO level before the drain.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    #[cfg(feature = "replay")]
+    pub fn fifo_drain_into(
+        &mut self,
+        sinks: &mut [&mut dyn replay::SampleSink],
+    ) -> Result<FifoDrainReport, Error<B::Error>> {
+        let fs = self.full_scale_get()?;
+        let mode = self.power_mode_get()?;
+        let mut buf = [0; FIFO_DEPTH * batch::SAMPLE_BYTES];
+        let report = self.fifo_drain(&mut buf, false)?;
+
+        let samples = buf
+            .get(..report.samples * batch::SAMPLE_BYTES)
+            .unwrap_or_default();
+        // Whole samples by construction
+        let _ = replay::replay_fifo_bytes_remapped(samples, fs, mode, self.state.axis_remap, sinks);
+        Ok(report)
+    }
+
+    /// Drain the FIFO into a Goertzel bank.
+    ///
+    /// This function is [`Self::fifo_drain_into`] with `axis` of the samples, in mg, as the only sink.
+    ///
+    /// ### Arguments
+    /// - `bank`: The bank, created with the output data rate as sample rate.
+    /// - `axis`: The axis fed to the bank.
+    ///
+    /// ### Returns
+    /// - `Ok(FifoDrainReport)`: The number of samples fed, their integrity and the FIFO level before the drain.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    #[cfg(feature = "dsp")]
+    pub fn fifo_drain_into_goertzel<const N: usize>(
+        &mut self,
+        bank: &mut dsp::GoertzelBank<N>,
+        axis: Axis,
+    ) -> Result<FifoDrainReport, Error<B::Error>> {
+        self.fifo_drain_into(&mut [&mut bank.on_axis(axis)])
+    }
+
+    /// Drain exactly `n` samples from the FIFO.
+    ///
+    /// This function reads the `FIFO_SAMPLES` register and, only if at least `n` samples are stored, burst reads
+    /// `n` samples and reads `FIFO_SAMPLES` again to check for overruns, as [`Self::fifo_drain`] does. With fewer
+    /// samples nothing else is read, so that every successful call consumes the same number of samples: see the
+    /// [`fifo`] module for the ping-pong pattern. An overrun does not stop the drain, it is reported instead.
+    ///
+    /// ### Arguments
+    /// - `n`: The number of samples to drain, typically the FIFO watermark.
+    /// - `out`: The destination of the raw samples, in the board frame (see [`Self::axis_remap_set`]); the first
+    ///   `n` entries are written.
+    ///
+    /// ### Returns
+    /// - `Ok(FifoIntegrity)`: The integrity of the `n` drained samples.
+    /// - `Err(Error::FifoUnderrun)`: If fewer than `n` samples are stored; only `FIFO_SAMPLES` was read.
+    /// - `Err(Error::OutOfRange)`: If `n` exceeds the FIFO depth or the length of `out`; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_drain_exact(
+        &mut self,
+        n: u8,
+        out: &mut [[i16; 3]],
+    ) -> Result<FifoIntegrity, Error<B::Error>> {
+        let max = out.len().min(FIFO_DEPTH);
+        let Some(out) = out.get_mut(..n as usize).filter(|_| n as usize <= max) else {
+            return Err(Error::OutOfRange {
+                requested: n as u32,
+                max: max as u32,
+            });
+        };
+
+        let before = FifoSamples::read(self)?;
+        if before.diff() < n {
+            return Err(Error::FifoUnderrun {
+                available: before.diff(),
+                requested: n,
+            });
+        }
+        if n == 0 {
+            return Ok(FifoIntegrity::from_flags(before.fifo_ovr(), 0));
+        }
+
+        self.fifo_burst_read(out, self.state.axis_remap)?;
+        let after = FifoSamples::read(self)?;
+        Ok(FifoIntegrity::from_flags(
+            before.fifo_ovr(),
+            after.fifo_ovr(),
+        ))
+    }
+
+    /// Drain the FIFO after a watermark interrupt.
+    ///
+    /// This function reads the `FIFO_SAMPLES` register, burst reads as many samples as are stored and fit in `out`,
+    /// and reads `FIFO_SAMPLES` again; as long as the `fifo_fth` flag of that read is set and `out` has room, it
+    /// drains again the samples stored in the meantime. The FIFO is left below the watermark unless `out` is full,
+    /// so that the threshold signal goes low and rises again at the next watermark: see the [`fifo`] module for the
+    /// interrupt loop. A spurious interrupt, with fewer samples than the watermark stored or none, is not an error:
+    /// the samples stored are returned, and with none only `FIFO_SAMPLES` is read. An overrun does not stop the
+    /// drain, it is reported instead.
+    ///
+    /// ### Arguments
+    /// - `out`: The destination of the raw samples, oldest first and in the board frame (see
+    ///   [`Self::axis_remap_set`]); a length of [`fifo::FIFO_DEPTH`] or more always rearms the threshold signal
+    ///   while the drain keeps up with the data rate.
+    ///
+    /// ### Returns
+    /// - `Ok(FifoWatermarkReport)`: The number of samples written to `out`, their integrity, the FIFO levels before
+    ///   and after the drain and whether the threshold signal is low again.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_drain_watermark(
+        &mut self,
+        out: &mut [[i16; 3]],
+    ) -> Result<FifoWatermarkReport, Error<B::Error>> {
+        let start = FifoSamples::read(self)?;
+        let mut status = start;
+        let mut integrity = FifoIntegrity::from_flags(start.fifo_ovr(), 0);
+        let mut samples = 0;
+
+        while status.fifo_fth() != 0 || samples == 0 {
+            let free = out.get_mut(samples..).unwrap_or_default();
+            let n = (status.diff() as usize).min(free.len()).min(FIFO_DEPTH);
+            if n == 0 {
+                break;
+            }
+
+            self.fifo_burst_read(free.get_mut(..n).unwrap_or_default(), self.state.axis_remap)?;
+            let after = FifoSamples::read(self)?;
+            integrity = integrity.max(FifoIntegrity::from_flags(
+                status.fifo_ovr(),
+                after.fifo_ovr(),
+            ));
+            samples += n;
+            status = after;
+        }
+
+        Ok(FifoWatermarkReport {
+            samples,
+            integrity,
+            start_diff: start.diff(),
+            end_diff: status.diff(),
+            rearmed: status.fifo_fth() == 0,
+        })
+    }
+
+    /// Sum `n` consecutive samples collected by the FIFO.
+    ///
+    /// This function flushes the FIFO, sets the `fmode` field in the `FIFO_CTRL` register to FIFO mode and the
+    /// `fth` field to `n`, polls the `FIFO_SAMPLES` register once per data period until `n` samples are stored,
+    /// drains them as [`Self::fifo_drain_exact`] does and writes the previous `FIFO_CTRL` content back, through a
+    /// flush. The previous content is restored whatever the outcome once the FIFO was reconfigured; the samples
+    /// stored before the call are discarded.
+    ///
+    /// The sums are kept as integers so that no float is needed: divide them by `n` for the mean, or see
+    /// [`Self::averaged_mg_get`]. The wait is bounded to `2 * n + 2` data periods, which covers a slow oscillator.
+    ///
+    /// ### Arguments
+    /// - `n`: The number of samples, from 1 to [`fifo::AVERAGE_MAX_SAMPLES`].
+    ///
+    /// ### Returns
+    /// - `Ok([i32; 3])`: The sums of the raw samples on the X, Y and Z axes, in the board frame (see
+    ///   [`Self::axis_remap_set`]).
+    /// - `Err(Error::OutOfRange)`: If `n` is 0 or above [`fifo::AVERAGE_MAX_SAMPLES`]; nothing is read.
+    /// - `Err(Error::InvalidConfig)`: If the accelerometer is off or triggered on demand; nothing is written.
+    /// - `Err(Error::FifoUnderrun)`: If fewer than `n` samples are stored at the end of the wait.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn averaged_sample_get(&mut self, n: u8) -> Result<[i32; 3], Error<B::Error>> {
+        let period_us = self.averaged_sample_period_us(n)?;
+        self.averaged_sample_run(n, period_us, |sensor| {
+            Ok(FifoSamples::read(sensor)?.diff() >= n)
+        })
+    }
+
+    /// Sum `n` consecutive samples collected by the FIFO, waiting on the INT1 pin.
+    ///
+    /// This function is [`Self::averaged_sample_get`] with the FIFO threshold signal routed alone to the INT1
+    /// pad during the call: the wait polls `int1`, with the polarity of the `h_lactive` field in the `CTRL3`
+    /// register, instead of the `FIFO_SAMPLES` register, so the bus stays idle and a [`crate::wait::YieldingDelay`] lets
+    /// the core sleep until the samples are collected. The previous INT1 routing is restored as the `FIFO_CTRL`
+    /// register is, see [`Self::int1_route_update_critical`] for the order of the writes.
+    ///
+    /// ### Arguments
+    /// - `n`: The number of samples, from 1 to [`fifo::AVERAGE_MAX_SAMPLES`].
+    /// - `int1`: The MCU input wired to the INT1 pad.
+    ///
+    /// ### Returns
+    /// - `Ok([i32; 3])`: The sums of the raw samples on the X, Y and Z axes, in the board frame.
+    /// - `Err(Error::OutOfRange)`: If `n` is 0 or above [`fifo::AVERAGE_MAX_SAMPLES`]; nothing is read.
+    /// - `Err(Error::InvalidConfig)`: If the accelerometer is off or triggered on demand; nothing is written.
+    /// - `Err(Error::FifoUnderrun)`: If fewer than `n` samples are stored at the end of the wait.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    #[cfg(feature = "events")]
+    pub fn averaged_sample_pin_get<P>(
+        &mut self,
+        n: u8,
+        int1: &mut P,
+    ) -> Result<[i32; 3], Error<B::Error>>
+    where
+        P: InputPin<Error = Infallible>,
+    {
+        let period_us = self.averaged_sample_period_us(n)?;
+        let active_low = self.pin_polarity_get()? == HLactive::ActiveLow;
+        let routes = self.pin_int1_route_get()?;
+        self.int1_route_update_critical(|r| {
+            *r = Ctrl4Int1PadCtrl::new().with_int1_fth(PROPERTY_ENABLE)
+        })?;
+
+        let sums = self.averaged_sample_run(n, period_us, |_| {
+            let Ok(high) = int1.is_high();
+            Ok(high != active_low)
+        });
+        let restored = self.int1_route_update_critical(|r| *r = routes);
+        let sums = sums?;
+        restored?;
+        Ok(sums)
+    }
+
+    /// Get the mean of `n` consecutive samples collected by the FIFO, in mg.
+    ///
+    /// This function reads the sums with [`Self::averaged_sample_get`], then the full scale, and scales the means
+    /// with the 14-bit sensitivity: in low-power mode 1 the two least significant bits of the samples are zero and
+    /// the mean keeps the fraction of the 12-bit LSB recovered by averaging.
+    ///
+    /// ### Arguments
+    /// - `n`: The number of samples, from 1 to [`fifo::AVERAGE_MAX_SAMPLES`].
+    ///
+    /// ### Returns
+    /// - `Ok([f32; 3])`: The mean acceleration on the X, Y and Z axes in mg, in the board frame.
+    /// - `Err(Error::OutOfRange)`: If `n` is 0 or above [`fifo::AVERAGE_MAX_SAMPLES`]; nothing is read.
+    /// - `Err(Error::InvalidConfig)`: If the accelerometer is off or triggered on demand; nothing is written.
+    /// - `Err(Error::FifoUnderrun)`: If fewer than `n` samples are stored at the end of the wait.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn averaged_mg_get(&mut self, n: u8) -> Result<[f32; 3], Error<B::Error>> {
+        let sums = self.averaged_sample_get(n)?;
+        let mg_per_lsb = sensitivity_mg_per_lsb(self.full_scale_get()?, Mode::HighPerformance);
+        Ok(sums.map(|sum| sum as f32 * mg_per_lsb / f32::from(n)))
+    }
+
+    fn averaged_sample_period_us(&mut self, n: u8) -> Result<u32, Error<B::Error>> {
+        if n == 0 || n > fifo::AVERAGE_MAX_SAMPLES {
+            return Err(Error::OutOfRange {
+                requested: n as u32,
+                max: fifo::AVERAGE_MAX_SAMPLES as u32,
+            });
+        }
+        self.data_rate_get()?
+            .period_us()
+            .ok_or(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    }
+
+    fn averaged_sample_run(
+        &mut self,
+        n: u8,
+        period_us: u32,
+        ready: impl FnMut(&mut Self) -> Result<bool, Error<B::Error>>,
+    ) -> Result<[i32; 3], Error<B::Error>> {
+        let saved = FifoCtrl::read(self)?;
+        let sums = self.averaged_sample_collect(n, period_us, ready);
+        // Flush whatever the outcome, so that the restored mode starts empty
+        let flushed = FifoCtrl::new()
+            .with_fmode(Fmode::BypassMode as u8)
+            .write(self);
+        let restored = flushed.and_then(|()| saved.write(self));
+        let sums = sums?;
+        restored?;
+        Ok(sums)
+    }
+
+    fn averaged_sample_collect(
+        &mut self,
+        n: u8,
+        period_us: u32,
+        mut ready: impl FnMut(&mut Self) -> Result<bool, Error<B::Error>>,
+    ) -> Result<[i32; 3], Error<B::Error>> {
+        FifoCtrl::new()
+            .with_fmode(Fmode::BypassMode as u8)
+            .write(self)?;
+        FifoCtrl::new()
+            .with_fmode(Fmode::FifoMode as u8)
+            .with_fth(n)
+            .write(self)?;
+
+        for _ in 0..2 * u32::from(n) + 2 {
+            if ready(self)? {
+                break;
+            }
+            self.tim.wait_us(period_us);
+        }
+
+        // In FIFO mode the collection stops when the FIFO is full: no overrun
+        let mut samples = [[0; 3]; fifo::AVERAGE_MAX_SAMPLES as usize];
+        self.fifo_drain_exact(n, &mut samples)?;
+        let mut sums = [0; 3];
+        for sample in samples.iter().take(n as usize) {
+            for (sum, val) in sums.iter_mut().zip(sample) {
+                *sum += i32::from(*val);
+            }
+        }
+        Ok(sums)
+    }
+
+    fn fifo_drain_once(&mut self, buf: &mut [u8]) -> Result<FifoDrainReport, Error<B::Error>> {
+        let before = FifoSamples::read(self)?;
+        let samples = (before.diff() as usize).min(buf.len() / batch::SAMPLE_BYTES);
+        if let Some(dst) = buf.get_mut(..samples * batch::SAMPLE_BYTES) {
+            if !dst.is_empty() {
+                self.read_from_register(Reg::OutXL as u8, dst)?;
+            }
+        }
+        let after = FifoSamples::read(self)?;
+
+        Ok(FifoDrainReport {
+            samples,
+            integrity: FifoIntegrity::from_flags(before.fifo_ovr(), after.fifo_ovr()),
+            retried: false,
+            start_diff: before.diff(),
+        })
+    }
+}
@@ -0,0 +1,181 @@
+use crate::prelude::*;
+use crate::timing::{DurationField, FfDuration, FreeFallTiming};
+use crate::{BusOperation, DelayNs, Error, Iis2dlpc, field_check};
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// Set the free-fall duration.
+    ///
+    /// This function configures the minimum duration of the free-fall condition by updating the `ff_dur` field in the `WAKE_UP_DUR` and `FREE_FALL` registers.
+    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    /// The field is 6 bits wide: bit 5 is stored in `WAKE_UP_DUR`, bits 4..0 in `FREE_FALL`.
+    /// See [`Self::free_fall_timing_set`] for the typed counterpart.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired free-fall duration value.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 6-bit `ff_dur` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn ff_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 6)?;
+        let mut wake_up_dur = WakeUpDur::read(self)?;
+        let mut free_fall = FreeFall::read(self)?;
+
+        wake_up_dur.set_ff_dur((val & 0x20) >> 5);
+        free_fall.set_ff_dur(val & 0x1F);
+
+        wake_up_dur.write(self)?;
+        free_fall.write(self)
+    }
+
+    /// Get the free-fall duration.
+    ///
+    /// This function retrieves the minimum duration of the free-fall condition from the `ff_dur` field in the `WAKE_UP_DUR` and `FREE_FALL` registers.
+    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current free-fall duration value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn ff_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
+        let wake_up_dur = WakeUpDur::read(self)?;
+        let free_fall = FreeFall::read(self)?;
+
+        Ok((wake_up_dur.ff_dur() << 5) + free_fall.ff_dur())
+    }
+
+    /// Set the free-fall threshold and the duration in milliseconds.
+    ///
+    /// This function converts `duration_ms` to the nearest `ff_dur` code for the current output data rate,
+    /// corrected with the calibration (see [`Self::odr_calibration_set`]), then writes the code, split across the
+    /// `WAKE_UP_DUR` and `FREE_FALL` registers, and the threshold as [`Self::free_fall_timing_set`] does.
+    /// 1 LSB = 1 / ODR, so the longest duration is 63 ODR periods (630 ms at 100 Hz).
+    ///
+    /// ### Arguments
+    /// - `threshold`: The free-fall threshold.
+    /// - `duration_ms`: The minimum duration of the free-fall condition in milliseconds.
+    ///
+    /// ### Returns
+    /// - `Ok(u32)`: The duration actually programmed, in milliseconds.
+    /// - `Err(Error::InvalidArgument)`: If the nearest code misses `duration_ms` by more than the duration tolerance,
+    ///   e.g. beyond 63 ODR periods. A duration out of reach at any data rate, beyond 63 periods at 1.6 Hz, is refused
+    ///   before any bus access; otherwise only the data rate is read, and nothing is written.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn ff_config_set(
+        &mut self,
+        threshold: FfThs,
+        duration_ms: u32,
+    ) -> Result<u32, Error<B::Error>> {
+        if !self.duration_reachable_ms(duration_ms, DurationField::FfDur) {
+            return Err(Error::InvalidArgument);
+        }
+        let (code, achieved) = self
+            .duration_code_for_ms(duration_ms, DurationField::FfDur)
+            .map_err(|err| match err {
+                Error::OutOfRange { .. } | Error::Inexact { .. } => Error::InvalidArgument,
+                err => err,
+            })?;
+        self.free_fall_timing_set(FreeFallTiming {
+            duration: FfDuration::from_lsb(code).unwrap_or_default(),
+            threshold,
+        })?;
+        Ok(achieved)
+    }
+
+    /// Get the free-fall threshold and the duration in milliseconds.
+    ///
+    /// This function reads the `ff_dur` field, split across the `WAKE_UP_DUR` and `FREE_FALL` registers, and the
+    /// `ff_ths` field, and converts the duration with the current output data rate, corrected with the calibration.
+    ///
+    /// ### Returns
+    /// - `Ok((FfThs, u32))`: The free-fall threshold and the duration, rounded to the millisecond.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn ff_config_get(&mut self) -> Result<(FfThs, u32), Error<B::Error>> {
+        let timing = self.free_fall_timing_get()?;
+        let duration_ms = self.duration_ms_for_code(timing.duration.lsb(), DurationField::FfDur)?;
+        Ok((timing.threshold, duration_ms))
+    }
+
+    /// Set the free-fall duration and threshold.
+    ///
+    /// This function writes the `ff_dur` field, split across the `WAKE_UP_DUR` and `FREE_FALL` registers, and the
+    /// `ff_ths` field in the `FREE_FALL` register, each register once. The code of the duration is written as is:
+    /// a duration built with [`FfDuration::from_ms`] for another data rate keeps its code, not its length.
+    ///
+    /// ### Arguments
+    /// - `val`: The free-fall duration and threshold.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn free_fall_timing_set(&mut self, val: FreeFallTiming) -> Result<(), Error<B::Error>> {
+        let mut wake_up_dur = WakeUpDur::read(self)?;
+        let mut free_fall = FreeFall::read(self)?;
+
+        let lsb = val.duration.lsb();
+        wake_up_dur.set_ff_dur((lsb & 0x20) >> 5);
+        free_fall.set_ff_dur(lsb & 0x1F);
+        free_fall.set_ff_ths(val.threshold as u8);
+
+        wake_up_dur.write(self)?;
+        free_fall.write(self)
+    }
+
+    /// Get the free-fall duration and threshold.
+    ///
+    /// This function reads the `ff_dur` field from the `WAKE_UP_DUR` and `FREE_FALL` registers, the `ff_ths` field
+    /// and the data rate, which is attached to the duration so that it displays in milliseconds too.
+    ///
+    /// ### Returns
+    /// - `Ok(FreeFallTiming)`: The free-fall duration and threshold.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn free_fall_timing_get(&mut self) -> Result<FreeFallTiming, Error<B::Error>> {
+        let wake_up_dur = WakeUpDur::read(self)?;
+        let free_fall = FreeFall::read(self)?;
+        let odr = self.data_rate_get()?;
+
+        let lsb = (wake_up_dur.ff_dur() << 5) + free_fall.ff_dur();
+        Ok(FreeFallTiming {
+            duration: FfDuration::from_lsb(lsb).unwrap_or_default().with_odr(odr),
+            threshold: FfThs::try_from(free_fall.ff_ths()).unwrap_or_default(),
+        })
+    }
+
+    /// Set the free-fall threshold.
+    ///
+    /// This function configures the free-fall threshold by updating the `ff_ths` field in the `FREE_FALL` register.
+    /// The threshold determines the sensitivity of the free-fall detection.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`FfThs`] value representing the desired free-fall threshold:
+    ///   - `_5Lsb`: 5 LSB @ ±2g (default).
+    ///   - `_7Lsb`: 7 LSB @ ±2g.
+    ///   - `_8Lsb`: 8 LSB @ ±2g.
+    ///   - `_10Lsb`: 10 LSB @ ±2g.
+    ///   - `_11Lsb`: 11 LSB @ ±2g.
+    ///   - `_13Lsb`: 13 LSB @ ±2g.
+    ///   - `_15Lsb`: 15 LSB @ ±2g.
+    ///   - `_16Lsb`: 16 LSB @ ±2g.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn ff_threshold_set(&mut self, val: FfThs) -> Result<(), Error<B::Error>> {
+        let mut reg = FreeFall::read(self)?;
+        reg.set_ff_ths(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the free-fall threshold.
+    ///
+    /// This function retrieves the current free-fall threshold from the `ff_ths` field in the `FREE_FALL` register.
+    ///
+    /// ### Returns
+    /// - `Ok(FfThs)`: The current free-fall threshold as a [`FfThs`] value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn ff_threshold_get(&mut self) -> Result<FfThs, Error<B::Error>> {
+        Ok(FfThs::try_from(FreeFall::read(self)?.ff_ths()).unwrap_or_default())
+    }
+}
@@ -0,0 +1,17 @@
+//! Driver functions of the optional features.
+//!
+//! Each module adds an `impl` block to [`crate::Iis2dlpc`] and is compiled with the cargo feature of the same
+//! name; the functions shared by every build stay in the crate root.
+
+#[cfg(feature = "events")]
+mod events;
+#[cfg(feature = "fifo")]
+mod fifo;
+#[cfg(feature = "freefall")]
+mod freefall;
+#[cfg(feature = "sixd")]
+mod sixd;
+#[cfg(feature = "tap")]
+mod tap;
+#[cfg(feature = "wakeup")]
+mod wakeup;
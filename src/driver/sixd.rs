@@ -0,0 +1,206 @@
+use crate::event::Orientation;
+use crate::prelude::*;
+use crate::{BusOperation, ConfigError, DelayNs, Error, Iis2dlpc, PROPERTY_ENABLE, field_check};
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// Set the threshold for 4D/6D orientation detection.
+    ///
+    /// This function configures the threshold for 4D/6D orientation detection by updating the `6d_ths` field in the `TAP_THS_X` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired threshold value for 4D/6D orientation detection.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 2-bit `six_d_ths` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn sixd_threshold_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 2)?;
+        let mut reg = TapThsX::read(self)?;
+        reg.set_six_d_ths(val);
+        reg.write(self)
+    }
+
+    /// Get the threshold for 4D/6D orientation detection.
+    ///
+    /// This function retrieves the current threshold for 4D/6D orientation detection from the `6d_ths` field in the `TAP_THS_X` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current threshold value for 4D/6D orientation detection.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn sixd_threshold_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(TapThsX::read(self)?.six_d_ths())
+    }
+
+    /// Set the threshold for 4D/6D orientation detection in degrees.
+    ///
+    /// This function writes the [`SixdThs`] of the angle to the `6d_ths` field in the `TAP_THS_X` register,
+    /// see [`Self::sixd_threshold_set`].
+    ///
+    /// ### Arguments
+    /// - `deg`: The minimum tilt angle that changes the orientation: 80, 70, 60 or 50 degrees.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `deg` is not one of the four supported angles; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn sixd_threshold_degrees_set(&mut self, deg: u8) -> Result<(), Error<B::Error>> {
+        let ths = SixdThs::from_degrees(deg).ok_or(Error::InvalidArgument)?;
+        self.sixd_threshold_set(ths as u8)
+    }
+
+    /// Get the threshold for 4D/6D orientation detection in degrees.
+    ///
+    /// This function reads the `6d_ths` field in the `TAP_THS_X` register, see [`SixdThs`].
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The minimum tilt angle that changes the orientation, in degrees.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn sixd_threshold_degrees_get(&mut self) -> Result<u8, Error<B::Error>> {
+        let ths = TapThsX::read(self)?.six_d_ths();
+        Ok(SixdThs::try_from(ths).unwrap_or_default().degrees())
+    }
+
+    /// Enable or disable 4D orientation detection.
+    ///
+    /// This function configures the 4D orientation detection by updating the `4d_en` field in the `TAP_THS_X` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired value for the `4d_en` field:
+    ///   - `false`: Disable 4D orientation detection.
+    ///   - `true`: Enable 4D orientation detection.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn fourd_mode_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut reg = TapThsX::read(self)?;
+        reg.set_four_d_en(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the 4D orientation detection status.
+    ///
+    /// This function retrieves the current status of 4D orientation detection from the `4d_en` field in the `TAP_THS_X` register.
+    ///
+    /// ### Returns
+    /// - `Ok(bool)`: The current value of the `4d_en` field:
+    ///   - `false`: 4D orientation detection is disabled.
+    ///   - `true`: 4D orientation detection is enabled.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fourd_mode_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(TapThsX::read(self)?.four_d_en() == PROPERTY_ENABLE)
+    }
+
+    /// Read the 6D tap source register.
+    ///
+    /// This function retrieves the 6D tap source information from the `SIXD_SRC` register.
+    /// The `SIXD_SRC` register provides details about the 6D orientation events, such as axis-specific thresholds and event detection.
+    /// A flagged orientation change is added to the event counters, see [`Self::event_counters`].
+    /// The content is returned in the sensor frame, see [`crate::remap::AxisRemap::sixd_src`] to convert it.
+    ///
+    /// ### Returns
+    /// - `Ok(SixdSrc)`: The 6D source information as a [`SixdSrc`] struct.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn sixd_src_get(&mut self) -> Result<SixdSrc, Error<B::Error>> {
+        let src = SixdSrc::read(self)?;
+        self.state.event_counters.count_sixd_src(src);
+        Ok(src)
+    }
+
+    /// Get the orientation of the device.
+    ///
+    /// This function reads the `4d_en` field in the `TAP_THS_X` register, then the `SIXD_SRC` register, see
+    /// [`Self::sixd_src_get`], and decodes the orientation flags, see [`Orientation::decode`]. In 4D mode only
+    /// the X and Y states of the sensor frame are reported. The orientation is in the board frame, see
+    /// [`Self::axis_remap_set`].
+    ///
+    /// ### Returns
+    /// - `Ok(Some(Orientation))`: The orientation flagged with `six_d_ia`.
+    /// - `Ok(None)`: If no orientation change is flagged, or the flags do not name a single orientation.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn orientation_get(&mut self) -> Result<Option<Orientation>, Error<B::Error>> {
+        let four_d = self.fourd_mode_get()?;
+        let src = self.sixd_src_get()?;
+        Ok(Orientation::decode(src, four_d)
+            .map(|orientation| self.state.axis_remap.orientation(orientation)))
+    }
+
+    /// Configure a debounced 6D orientation detection.
+    ///
+    /// This function feeds the 6D function with the LPF2 output (`lpass_on6d` field in the `CTRL7` register) and
+    /// selects, in the `bw_filt` field of the `CTRL6` register, the lowest bandwidth whose settling time, see
+    /// [`BwFilt::settling_ms`], is within `settle_ms` at the current data rate: the lower the bandwidth, the better
+    /// a bounce is filtered out. It then writes the threshold in the `TAP_THS_X` register and routes the 6D
+    /// interrupt to the INT1 pad.
+    ///
+    /// `bw_filt` also sets the bandwidth of the output data and, with the high-pass path selected, the high-pass
+    /// cutoff; check the wake-up function with [`Self::wakeup_high_pass_check`] if it is in use.
+    ///
+    /// ### Arguments
+    /// - `min_angle`: The orientation threshold.
+    /// - `settle_ms`: The longest accepted settling time of the filter, in milliseconds.
+    ///
+    /// ### Returns
+    /// - `Ok(BwFilt)`: The selected bandwidth.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand, or no bandwidth settles within
+    ///   `settle_ms`; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn sixd_stable_config(
+        &mut self,
+        min_angle: SixdThs,
+        settle_ms: u32,
+    ) -> Result<BwFilt, Error<B::Error>> {
+        let odr_hz = self.odr_hz()?;
+        let bw = [
+            BwFilt::OdrDiv20,
+            BwFilt::OdrDiv10,
+            BwFilt::OdrDiv4,
+            BwFilt::OdrDiv2,
+        ]
+        .into_iter()
+        .find(|bw| bw.settling_ms(odr_hz) <= settle_ms as f32)
+        .ok_or(Error::InvalidConfig(ConfigError::SettleTimeUnreachable {
+            // Rounded up, `f32::ceil` is not in core
+            min_ms: BwFilt::OdrDiv2.settling_ms(odr_hz) as u32 + 1,
+        }))?;
+
+        self.sixd_feed_data_set(LpassOn6d::Lpf2Feed)?;
+        self.filter_bandwidth_set(bw)?;
+        self.sixd_threshold_set(min_angle as u8)?;
+        let mut route = self.pin_int1_route_get()?;
+        route.set_int1_6d(PROPERTY_ENABLE);
+        self.pin_int1_route_set(&route)?;
+        Ok(bw)
+    }
+
+    /// Set the data source for the 6D interrupt function.
+    ///
+    /// This function configures the data source for the 6D interrupt function by updating the `lpass_on6d` field in the `CTRL7` register.
+    /// The data source can be either ODR/2 low-pass filtered data or LPF2 output data.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`LpassOn6d`] value representing the desired data source:
+    ///   - `OdrDiv2Feed`: ODR/2 low-pass filtered data (default).
+    ///   - `Lpf2Feed`: LPF2 output data.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn sixd_feed_data_set(&mut self, val: LpassOn6d) -> Result<(), Error<B::Error>> {
+        let mut reg = Ctrl7::read(self)?;
+        reg.set_lpass_on6d(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the data source for the 6D interrupt function.
+    ///
+    /// This function retrieves the current data source for the 6D interrupt function from the `lpass_on6d` field in the `CTRL7` register.
+    ///
+    /// ### Returns
+    /// - `Ok(LpassOn6d)`: The current data source as a [`LpassOn6d`] value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn sixd_feed_data_get(&mut self) -> Result<LpassOn6d, Error<B::Error>> {
+        Ok(LpassOn6d::try_from(Ctrl7::read(self)?.lpass_on6d()).unwrap_or_default())
+    }
+}
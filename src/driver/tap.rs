@@ -0,0 +1,458 @@
+use crate::event::TapEvents;
+use crate::prelude::*;
+use crate::tap::TapConfig;
+use crate::{BusOperation, DelayNs, Error, Iis2dlpc, PROPERTY_ENABLE, field_check};
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// Set the threshold for tap recognition on the X-axis.
+    ///
+    /// This function configures the tap threshold for the X-axis by updating the `tap_thsx` field in the `TAP_THS_X` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired tap threshold value for the X-axis.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 5-bit `tap_thsx` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_threshold_x_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 5)?;
+        let mut reg = TapThsX::read(self)?;
+        reg.set_tap_thsx(val);
+        reg.write(self)
+    }
+
+    /// Get the threshold for tap recognition on the X-axis.
+    ///
+    /// This function retrieves the current tap threshold for the X-axis from the `tap_thsx` field in the `TAP_THS_X` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current tap threshold value for the X-axis.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_threshold_x_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(TapThsX::read(self)?.tap_thsx())
+    }
+
+    /// Set the threshold for tap recognition on the Y-axis.
+    ///
+    /// This function configures the tap threshold for the Y-axis by updating the `tap_thsy` field in the `TAP_THS_Y` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired tap threshold value for the Y-axis.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 5-bit `tap_thsy` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_threshold_y_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 5)?;
+        let mut reg = TapThsY::read(self)?;
+        reg.set_tap_thsy(val);
+        reg.write(self)
+    }
+
+    /// Get the threshold for tap recognition on the Y-axis.
+    ///
+    /// This function retrieves the current tap threshold for the Y-axis from the `tap_thsy` field in the `TAP_THS_Y` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current tap threshold value for the Y-axis.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_threshold_y_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(TapThsY::read(self)?.tap_thsy())
+    }
+
+    /// Set the axis priority for tap detection.
+    ///
+    /// This function configures the axis priority for tap detection by updating the `tap_prior` field in the `TAP_THS_Y` register.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`TapPrior`] value representing the desired axis priority:
+    ///   - `Xyz`: X > Y > Z (default).
+    ///   - `Yxz`: Y > X > Z.
+    ///   - `Xzy`: X > Z > Y.
+    ///   - `Zyx`: Z > Y > X.
+    ///   - `Yzx`: Y > Z > X.
+    ///   - `Zxy`: Z > X > Y.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_axis_priority_set(&mut self, val: TapPrior) -> Result<(), Error<B::Error>> {
+        let mut reg = TapThsY::read(self)?;
+        reg.set_tap_prior(val as u8);
+        reg.write(self)
+    }
+
+    /// Set the threshold for tap recognition on the Z-axis.
+    ///
+    /// This function configures the tap threshold for the Z-axis by updating the `tap_thsz` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired tap threshold value for the Z-axis.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 5-bit `tap_thsz` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_threshold_z_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 5)?;
+        let mut reg = TapThsZ::read(self)?;
+        reg.set_tap_thsz(val);
+        reg.write(self)
+    }
+
+    /// Get the threshold for tap recognition on the Z-axis.
+    ///
+    /// This function retrieves the current tap threshold for the Z-axis from the `tap_thsz` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current tap threshold value for the Z-axis.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_threshold_z_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(TapThsZ::read(self)?.tap_thsz())
+    }
+
+    /// Enable Z direction in tap recognition.
+    ///
+    /// This function enables or disables tap recognition on the Z-axis by updating the `tap_z_en` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired value for the `tap_z_en` field:
+    ///   - `false`: Disable Z-axis tap recognition.
+    ///   - `true`: Enable Z-axis tap recognition.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_detection_on_z_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut reg = TapThsZ::read(self)?;
+        reg.set_tap_z_en(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the Z direction tap recognition status.
+    ///
+    /// This function retrieves the current status of tap recognition on the Z-axis from the `tap_z_en` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Returns
+    /// - `Ok(bool)`: The current value of the `tap_z_en` field:
+    ///   - `false`: Z-axis tap recognition is disabled.
+    ///   - `true`: Z-axis tap recognition is enabled.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_detection_on_z_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(TapThsZ::read(self)?.tap_z_en() == PROPERTY_ENABLE)
+    }
+
+    /// Enable Y direction in tap recognition.
+    ///
+    /// This function enables or disables tap recognition on the Y-axis by updating the `tap_y_en` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired value for the `tap_y_en` field:
+    ///   - `false`: Disable Y-axis tap recognition.
+    ///   - `true`: Enable Y-axis tap recognition.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_detection_on_y_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut reg = TapThsZ::read(self)?;
+        reg.set_tap_y_en(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the Y direction tap recognition status.
+    ///
+    /// This function retrieves the current status of tap recognition on the Y-axis from the `tap_y_en` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Returns
+    /// - `Ok(bool)`: The current value of the `tap_y_en` field:
+    ///   - `false`: Y-axis tap recognition is disabled.
+    ///   - `true`: Y-axis tap recognition is enabled.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_detection_on_y_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(TapThsZ::read(self)?.tap_y_en() == PROPERTY_ENABLE)
+    }
+
+    /// Enable X direction in tap recognition.
+    ///
+    /// This function enables or disables tap recognition on the X-axis by updating the `tap_x_en` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired value for the `tap_x_en` field:
+    ///   - `false`: Disable X-axis tap recognition.
+    ///   - `true`: Enable X-axis tap recognition.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_detection_on_x_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut reg = TapThsZ::read(self)?;
+        reg.set_tap_x_en(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the X direction tap recognition status.
+    ///
+    /// This function retrieves the current status of tap recognition on the X-axis from the `tap_x_en` field in the `TAP_THS_Z` register.
+    ///
+    /// ### Returns
+    /// - `Ok(bool)`: The current value of the `tap_x_en` field:
+    ///   - `false`: X-axis tap recognition is disabled.
+    ///   - `true`: X-axis tap recognition is enabled.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_detection_on_x_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(TapThsZ::read(self)?.tap_x_en() == PROPERTY_ENABLE)
+    }
+
+    /// Set the maximum duration for tap recognition.
+    ///
+    /// This function configures the maximum time an over-threshold signal is detected to be recognized as a tap event.
+    /// The duration is set in the `shock` field of the `INT_DUR` register.
+    /// - The default value (`00b`) corresponds to `4 * ODR_XL` time.
+    /// - If the `shock` bits are set to a different value, 1 LSB corresponds to `8 * ODR_XL` time.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired maximum duration value for tap recognition.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 2-bit `shock` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_shock_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 2)?;
+        let mut reg = IntDur::read(self)?;
+        reg.set_shock(val);
+        reg.write(self)
+    }
+
+    /// Get the maximum duration for tap recognition.
+    ///
+    /// This function retrieves the current maximum time an over-threshold signal is detected to be recognized as a tap event.
+    /// The duration is stored in the `shock` field of the `INT_DUR` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current maximum duration value for tap recognition.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_shock_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(IntDur::read(self)?.shock())
+    }
+
+    /// Set the quiet time for tap recognition.
+    ///
+    /// This function configures the quiet time after the first detected tap during which no over-threshold event should occur.
+    /// The quiet time is set in the `quiet` field of the `INT_DUR` register.
+    /// - The default value (`00b`) corresponds to `2 * ODR_XL` time.
+    /// - If the `quiet` bits are set to a different value, 1 LSB corresponds to `4 * ODR_XL` time.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired quiet time value.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 2-bit `quiet` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_quiet_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 2)?;
+        let mut reg = IntDur::read(self)?;
+        reg.set_quiet(val);
+        reg.write(self)
+    }
+
+    /// Get the quiet time for tap recognition.
+    ///
+    /// This function retrieves the current quiet time after the first detected tap during which no over-threshold event should occur.
+    /// The quiet time is stored in the `quiet` field of the `INT_DUR` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current quiet time value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_quiet_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(IntDur::read(self)?.quiet())
+    }
+
+    /// Set the maximum duration for double-tap recognition.
+    ///
+    /// This function configures the maximum time between two consecutive detected taps to determine a double-tap event.
+    /// The duration is set in the `latency` field of the `INT_DUR` register.
+    /// - The default value (`0000b`) corresponds to `16 * ODR_XL` time.
+    /// - If the `latency` bits are set to a different value, 1 LSB corresponds to `32 * ODR_XL` time.
+    ///
+    /// ### Arguments
+    /// - `val`: The desired maximum duration value for double-tap recognition.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 4-bit `latency` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 4)?;
+        let mut reg = IntDur::read(self)?;
+        reg.set_latency(val);
+        reg.write(self)
+    }
+
+    /// Get the maximum duration for double-tap recognition.
+    ///
+    /// This function retrieves the current maximum time between two consecutive detected taps to determine a double-tap event.
+    /// The duration is stored in the `latency` field of the `INT_DUR` register.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current maximum duration value for double-tap recognition.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(IntDur::read(self)?.latency())
+    }
+
+    /// Enable or disable single/double-tap event detection.
+    ///
+    /// This function configures the single/double-tap event detection by updating the `single_double_tap` field in the `WAKE_UP_THS` register.
+    /// The mode determines whether only single-tap events or both single- and double-tap events are detected.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`SingleDoubleTap`] value representing the desired tap mode:
+    ///   - `OnlySingle`: Detect only single-tap events (default).
+    ///   - `BothSingleDouble`: Detect both single- and double-tap events.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_mode_set(&mut self, val: SingleDoubleTap) -> Result<(), Error<B::Error>> {
+        let mut reg = WakeUpThs::read(self)?;
+        reg.set_single_double_tap(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the single/double-tap event detection mode.
+    ///
+    /// This function retrieves the current single/double-tap event detection mode from the `single_double_tap` field in the `WAKE_UP_THS` register.
+    ///
+    /// ### Returns
+    /// - `Ok(SingleDoubleTap)`: The current tap mode as a [`SingleDoubleTap`] value:
+    ///   - `OnlySingle`: Detect only single-tap events (default).
+    ///   - `BothSingleDouble`: Detect both single- and double-tap events.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_mode_get(&mut self) -> Result<SingleDoubleTap, Error<B::Error>> {
+        Ok(
+            SingleDoubleTap::try_from(WakeUpThs::read(self)?.single_double_tap())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Set the whole tap detection configuration.
+    ///
+    /// This function checks every field of the [`TapConfig`], then writes the `TAP_THS_X`, `TAP_THS_Y`,
+    /// `TAP_THS_Z`, `INT_DUR` and `WAKE_UP_THS` registers once each, in this order, instead of the read-modify-write
+    /// of every individual setter. The 6D settings of `TAP_THS_X` and the wake-up threshold and sleep enable of
+    /// `WAKE_UP_THS` are kept.
+    ///
+    /// ### Arguments
+    /// - `cfg`: The tap detection settings.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If a threshold does not fit in its 5-bit field, `shock` or `quiet` in its
+    ///   2-bit field, or `latency` in its 4-bit field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn tap_config_set(&mut self, cfg: &TapConfig) -> Result<(), Error<B::Error>> {
+        let [ths_x, ths_y, ths_z] = cfg.thresholds;
+        let [x_en, y_en, z_en] = cfg.axes;
+        for ths in cfg.thresholds {
+            field_check(ths, 5)?;
+        }
+        field_check(cfg.shock, 2)?;
+        field_check(cfg.quiet, 2)?;
+        field_check(cfg.latency, 4)?;
+
+        let tap_ths_y = TapThsY::new()
+            .with_tap_prior(cfg.priority as u8)
+            .with_tap_thsy(ths_y);
+        let tap_ths_z = TapThsZ::new()
+            .with_tap_x_en(x_en as u8)
+            .with_tap_y_en(y_en as u8)
+            .with_tap_z_en(z_en as u8)
+            .with_tap_thsz(ths_z);
+        let int_dur = IntDur::new()
+            .with_shock(cfg.shock)
+            .with_quiet(cfg.quiet)
+            .with_latency(cfg.latency);
+
+        let mut tap_ths_x = TapThsX::read(self)?;
+        let mut wake_up_ths = WakeUpThs::read(self)?;
+        tap_ths_x.set_tap_thsx(ths_x);
+        wake_up_ths.set_single_double_tap(cfg.mode as u8);
+
+        tap_ths_x.write(self)?;
+        tap_ths_y.write(self)?;
+        tap_ths_z.write(self)?;
+        int_dur.write(self)?;
+        wake_up_ths.write(self)
+    }
+
+    /// Get the whole tap detection configuration.
+    ///
+    /// This function reads the `TAP_THS_X`, `TAP_THS_Y`, `TAP_THS_Z`, `INT_DUR` and `WAKE_UP_THS` registers,
+    /// see [`Self::tap_config_set`].
+    ///
+    /// ### Returns
+    /// - `Ok(TapConfig)`: The tap detection settings.
+    /// - `Err(Error::UnexpectedValue)`: If the axis priority holds one of the reserved codes (4 or 7), after the
+    ///   retry of [`Self::decode_retry_set`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_config_get(&mut self) -> Result<TapConfig, Error<B::Error>> {
+        let tap_ths_x = TapThsX::read(self)?;
+        let tap_ths_y = TapThsY::read(self)?;
+        let priority = self.tap_axis_priority_get()?;
+        let tap_ths_z = TapThsZ::read(self)?;
+        let int_dur = IntDur::read(self)?;
+        let wake_up_ths = WakeUpThs::read(self)?;
+        Ok(TapConfig {
+            thresholds: [
+                tap_ths_x.tap_thsx(),
+                tap_ths_y.tap_thsy(),
+                tap_ths_z.tap_thsz(),
+            ],
+            axes: [
+                tap_ths_z.tap_x_en() == PROPERTY_ENABLE,
+                tap_ths_z.tap_y_en() == PROPERTY_ENABLE,
+                tap_ths_z.tap_z_en() == PROPERTY_ENABLE,
+            ],
+            shock: int_dur.shock(),
+            quiet: int_dur.quiet(),
+            latency: int_dur.latency(),
+            priority,
+            mode: SingleDoubleTap::try_from(wake_up_ths.single_double_tap()).unwrap_or_default(),
+        })
+    }
+
+    /// Read the tap/double-tap source register.
+    ///
+    /// This function retrieves the tap/double-tap source information from the `TAP_SRC` register.
+    /// The `TAP_SRC` register provides details about the tap events, such as the axis of detection and the type of tap event.
+    /// The flagged taps are added to the event counters, see [`Self::event_counters`].
+    ///
+    /// ### Returns
+    /// - `Ok(TapSrc)`: The tap source information as a [`TapSrc`] struct.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_src_get(&mut self) -> Result<TapSrc, Error<B::Error>> {
+        let src = TapSrc::read(self)?;
+        self.state.event_counters.count_tap_src(src);
+        Ok(src)
+    }
+
+    /// Get the tap events.
+    ///
+    /// This function reads the `TAP_SRC` register and decodes it according to the tap policy
+    /// and the axis priority in the `TAP_THS_Y` register.
+    ///
+    /// ### Returns
+    /// - `Ok(TapEvents)`: An iterator over the detected tap events; empty if no tap was detected.
+    /// - `Err(Error::UnexpectedValue)`: If the axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn tap_event_get(&mut self) -> Result<TapEvents, Error<B::Error>> {
+        let src = self.tap_src_get()?;
+        let priority = self.tap_axis_priority_get()?;
+        Ok(TapEvents::decode(src, self.state.tap_policy, priority).remapped(self.state.axis_remap))
+    }
+}
@@ -0,0 +1,512 @@
+use crate::activity::{ActivityConfig, ActivityState, ActivityTransition};
+use crate::prelude::*;
+use crate::timing::DurationField;
+#[cfg(feature = "freefall")]
+use crate::timing::{ActivityTiming, FreeFallTiming};
+use crate::{
+    BusOperation, DelayNs, Error, Iis2dlpc, MIN_WAKEUP_HP_CUTOFF_HZ, PROPERTY_ENABLE, WakeupLevel,
+    Warning, field_check,
+};
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// Set the wake-up threshold.
+    ///
+    /// This function configures the wake-up threshold by updating the `wk_ths` field in the `WAKE_UP_THS` register.
+    /// The threshold is expressed in LSB, where 1 LSB = FS_XL / 64.
+    /// With [`UsrOffOnWu::UserOffsetFeed`] the threshold is relative to the user offsets, see [`Self::wakeup_effective_threshold_mg`].
+    ///
+    /// ### Arguments
+    /// - `val`: The desired wake-up threshold value.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 6-bit `wk_ths` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn wkup_threshold_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 6)?;
+        let mut reg = WakeUpThs::read(self)?;
+        reg.set_wk_ths(val);
+        reg.write(self)
+    }
+
+    /// Get the wake-up threshold.
+    ///
+    /// This function retrieves the current wake-up threshold from the `wk_ths` field in the `WAKE_UP_THS` register.
+    /// The threshold is expressed in LSB, where 1 LSB = FS_XL / 64.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current wake-up threshold value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wkup_threshold_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(WakeUpThs::read(self)?.wk_ths())
+    }
+
+    /// Set the wake-up threshold in mg.
+    ///
+    /// This function reads the full scale and writes the nearest `wk_ths` code, 1 LSB = FS / 64, to the
+    /// `WAKE_UP_THS` register. The 6-bit field reaches 63 / 64 of the full scale: a larger request clamps to code 63,
+    /// a negative one to code 0, and the returned threshold shows it. A change of the full scale rescales the
+    /// threshold; set it again afterwards.
+    ///
+    /// ### Arguments
+    /// - `mg`: The desired wake-up threshold in mg.
+    ///
+    /// ### Returns
+    /// - `Ok(f32)`: The threshold actually programmed, in mg.
+    /// - `Err(Error::InvalidArgument)`: If `mg` is not a number; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn wkup_threshold_mg_set(&mut self, mg: f32) -> Result<f32, Error<B::Error>> {
+        if mg.is_nan() {
+            return Err(Error::InvalidArgument);
+        }
+        let mg_per_lsb = self.full_scale_get()?.wkup_threshold_mg_per_lsb();
+        // Nearest code; the cast saturates a negative request to 0
+        let code = ((mg / mg_per_lsb + 0.5) as u8).min(0x3F);
+        self.wkup_threshold_set(code)?;
+        Ok(code as f32 * mg_per_lsb)
+    }
+
+    /// Get the wake-up threshold in mg.
+    ///
+    /// This function reads the `wk_ths` field in the `WAKE_UP_THS` register and the full scale, 1 LSB = FS / 64.
+    /// See [`Self::wakeup_effective_threshold_mg`] for the levels with the user offsets.
+    ///
+    /// ### Returns
+    /// - `Ok(f32)`: The current wake-up threshold in mg.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wkup_threshold_mg_get(&mut self) -> Result<f32, Error<B::Error>> {
+        let ths = WakeUpThs::read(self)?.wk_ths();
+        Ok(ths as f32 * self.full_scale_get()?.wkup_threshold_mg_per_lsb())
+    }
+
+    /// Get the acceleration levels at which the wake-up function fires.
+    ///
+    /// This function combines the `wk_ths` field in the `WAKE_UP_THS` register, the `fs` field in the `CTRL6` register,
+    /// the `usr_off_on_wu` and `usr_off_w` fields in the `CTRL7` register and the user offsets.
+    /// 1 LSB of the threshold = FS / 64. With [`UsrOffOnWu::HpFeed`] the threshold applies to high-pass filtered data,
+    /// with [`UsrOffOnWu::UserOffsetFeed`] it applies to data with the user offset subtracted, so the levels are centered on the offset.
+    ///
+    /// ### Returns
+    /// - `Ok([WakeupLevel; 3])`: The levels for the X, Y and Z axes of the board frame, see [`Self::axis_remap_set`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wakeup_effective_threshold_mg(&mut self) -> Result<[WakeupLevel; 3], Error<B::Error>> {
+        let ths = WakeUpThs::read(self)?.wk_ths();
+        let fs = self.full_scale_get()?;
+        let ctrl7 = Ctrl7::read(self)?;
+
+        let offsets_mg = if ctrl7.usr_off_on_wu() == UsrOffOnWu::UserOffsetFeed as u8 {
+            let weight = UsrOffW::try_from(ctrl7.usr_off_w())
+                .unwrap_or_default()
+                .mg_per_lsb();
+            self.usr_offsets_get()?.map(|ofs| ofs as f32 * weight)
+        } else {
+            [0.0; 3]
+        };
+
+        Ok(offsets_mg.map(|ofs| WakeupLevel::new(ths, fs, ofs)))
+    }
+
+    /// Set the wake-up duration event.
+    ///
+    /// This function configures the wake-up duration by updating the `wake_dur` field in the `WAKE_UP_DUR` register.
+    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    /// The field is 2 bits wide: at high data rates it covers only a few milliseconds, see [`Self::wkup_dur_ms_set`].
+    ///
+    /// ### Arguments
+    /// - `val`: The desired wake-up duration value.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 2-bit `wake_dur` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn wkup_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 2)?;
+        let mut reg = WakeUpDur::read(self)?;
+        reg.set_wake_dur(val);
+        reg.write(self)
+    }
+
+    /// Get the wake-up duration event.
+    ///
+    /// This function retrieves the current wake-up duration from the `wake_dur` field in the `WAKE_UP_DUR` register.
+    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current wake-up duration value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wkup_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(WakeUpDur::read(self)?.wake_dur())
+    }
+
+    /// Set the data sent to the wake-up interrupt function.
+    ///
+    /// This function configures the data source for the wake-up interrupt function by updating the `usr_off_on_wu` field in the `CTRL7` register.
+    /// The data source can be either high-pass filtered data or user offset data.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`UsrOffOnWu`] value representing the desired data source:
+    ///   - `HpFeed`: High-pass filtered data (default).
+    ///   - `UserOffsetFeed`: User offset data.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn wkup_feed_data_set(&mut self, val: UsrOffOnWu) -> Result<(), Error<B::Error>> {
+        let mut reg = Ctrl7::read(self)?;
+        reg.set_usr_off_on_wu(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the data sent to the wake-up interrupt function.
+    ///
+    /// This function retrieves the current data source for the wake-up interrupt function from the `usr_off_on_wu` field in the `CTRL7` register.
+    ///
+    /// ### Returns
+    /// - `Ok(UsrOffOnWu)`: The current data source as a [`UsrOffOnWu`] value:
+    ///   - `HpFeed`: High-pass filtered data (default).
+    ///   - `UserOffsetFeed`: User offset data.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wkup_feed_data_get(&mut self) -> Result<UsrOffOnWu, Error<B::Error>> {
+        Ok(UsrOffOnWu::try_from(Ctrl7::read(self)?.usr_off_on_wu()).unwrap_or_default())
+    }
+
+    /// Configure activity/inactivity or stationary/motion detection.
+    ///
+    /// This function configures the activity/inactivity or stationary/motion detection by updating the `sleep_on` field in the `WAKE_UP_THS` register
+    /// and the `stationary` field in the `WAKE_UP_DUR` register.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`SleepOn`] value representing the desired detection mode:
+    ///   - `NoDetection`: No detection (default).
+    ///   - `DetectActInact`: Detect activity/inactivity.
+    ///   - `DetectStatMotion`: Detect stationary/motion.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn act_mode_set(&mut self, val: SleepOn) -> Result<(), Error<B::Error>> {
+        let mut wake_up_ths = WakeUpThs::read(self)?;
+        let mut wake_up_dur: WakeUpDur = WakeUpDur::read(self)?;
+
+        wake_up_ths.set_sleep_on(val.sleep_on());
+        wake_up_dur.set_stationary(val.stationary());
+
+        wake_up_ths.write(self)?;
+        wake_up_dur.write(self)
+    }
+
+    /// Get the activity/inactivity or stationary/motion detection configuration.
+    ///
+    /// This function retrieves the current detection mode by reading the `sleep_on` field from the `WAKE_UP_THS` register
+    /// and the `stationary` field from the `WAKE_UP_DUR` register.
+    ///
+    /// ### Returns
+    /// - `Ok(SleepOn)`: The current detection mode as a [`SleepOn`] value:
+    ///   - `NoDetection`: No detection (default).
+    ///   - `DetectActInact`: Detect activity/inactivity.
+    ///   - `DetectStatMotion`: Detect stationary/motion.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn act_mode_get(&mut self) -> Result<SleepOn, Error<B::Error>> {
+        let wake_up_ths = WakeUpThs::read(self)?;
+        let wake_up_dur: WakeUpDur = WakeUpDur::read(self)?;
+
+        Ok(SleepOn::new(
+            wake_up_ths.sleep_on(),
+            wake_up_dur.stationary(),
+        ))
+    }
+
+    /// Set the duration to enter sleep mode.
+    ///
+    /// This function configures the duration required to enter sleep mode by updating the `sleep_dur` field in the `WAKE_UP_DUR` register.
+    /// The duration is expressed in LSB, where 1 LSB = 512 / ODR.
+    /// The field is 4 bits wide, so the steps are coarse at high data rates, see [`Self::act_sleep_dur_ms_set`].
+    ///
+    /// ### Arguments
+    /// - `val`: The desired sleep duration value.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If `val` does not fit in the 4-bit `sleep_dur` field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn act_sleep_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
+        field_check(val, 4)?;
+        let mut reg = WakeUpDur::read(self)?;
+        reg.set_sleep_dur(val);
+        reg.write(self)
+    }
+
+    /// Get the duration to enter sleep mode.
+    ///
+    /// This function retrieves the current sleep duration from the `sleep_dur` field in the `WAKE_UP_DUR` register.
+    /// The duration is expressed in LSB, where 1 LSB = 512 / ODR.
+    ///
+    /// ### Returns
+    /// - `Ok(u8)`: The current sleep duration value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn act_sleep_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(WakeUpDur::read(self)?.sleep_dur())
+    }
+
+    /// Set the wake-up duration event in milliseconds.
+    ///
+    /// This function converts `ms` to the nearest `wake_dur` code for the current output data rate,
+    /// corrected with the calibration (see [`Self::odr_calibration_set`]),
+    /// and writes it to the `WAKE_UP_DUR` register. 1 LSB = 1 / ODR, so the representable range is only
+    /// 0 to 3 ODR periods (about 1.9 ms at 1.6 kHz, 1.875 s at 1.6 Hz).
+    ///
+    /// ### Arguments
+    /// - `ms`: The desired wake-up duration in milliseconds.
+    ///
+    /// ### Returns
+    /// - `Ok(u32)`: The duration actually programmed, in milliseconds.
+    /// - `Err(Error::OutOfRange)`: If `ms` is past the longest code by more than the duration tolerance; `max` is
+    ///   the longest duration, rounded as the achieved one. Nothing is written.
+    /// - `Err(Error::Inexact)`: If `ms` falls between two codes and the nearest one, `nearest`, misses it by
+    ///   more than the duration tolerance; nothing is written.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn wkup_dur_ms_set(&mut self, ms: u32) -> Result<u32, Error<B::Error>> {
+        let (code, achieved) = self.duration_code_for_ms(ms, DurationField::WakeDur)?;
+        self.wkup_dur_set(code)?;
+        Ok(achieved)
+    }
+
+    /// Get the wake-up duration event in milliseconds.
+    ///
+    /// This function reads the `wake_dur` field in the `WAKE_UP_DUR` register and converts it with the current
+    /// output data rate, corrected with the calibration (see [`Self::odr_calibration_set`]). 1 LSB = 1 / ODR.
+    ///
+    /// ### Returns
+    /// - `Ok(u32)`: The current wake-up duration, rounded to the millisecond.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wkup_dur_ms_get(&mut self) -> Result<u32, Error<B::Error>> {
+        let code = self.wkup_dur_get()?;
+        self.duration_ms_for_code(code, DurationField::WakeDur)
+    }
+
+    /// Configure the activity and the free-fall detection together.
+    ///
+    /// The `ff_dur` field spills its bit 5 into the `WAKE_UP_DUR` register, next to the `wake_dur`, `sleep_dur`
+    /// and `stationary` fields, and the detection mode also sets `sleep_on` in the `WAKE_UP_THS` register.
+    /// This function validates every field first, then reads the `WAKE_UP_THS`, `WAKE_UP_DUR` and `FREE_FALL`
+    /// registers, computes their new content and writes each of them once. A `None` argument leaves the fields
+    /// of that function as they are, so configuring the two functions in two calls, in either order, gives the
+    /// same register content as a single call. The function needs the `freefall` feature next to `wakeup`.
+    ///
+    /// The fields do not overlap, but the functions interact: with [`SleepOn::ActInact`] the device switches to
+    /// 12.5 Hz in low-power mode 1 while asleep, and `ff_dur`, counted in ODR periods, lasts longer until the
+    /// next wake-up. The function then returns [`Warning::FreeFallDurationStretched`] if `ff_dur` is not 0.
+    /// [`SleepOn::StatMotion`] keeps the data rate and does not affect free-fall.
+    ///
+    /// ### Arguments
+    /// - `activity`: The activity detection mode and durations, or `None` to keep them.
+    /// - `ff`: The free-fall duration and threshold, or `None` to keep them.
+    ///
+    /// ### Returns
+    /// - `Ok(None)`: If the operation is successful.
+    /// - `Ok(Some(Warning::FreeFallDurationStretched))`: If the free-fall duration changes while asleep.
+    /// - `Err(Error::InvalidArgument)`: If an activity duration code does not fit in its field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    #[cfg(feature = "freefall")]
+    pub fn wake_and_ff_config_set(
+        &mut self,
+        activity: Option<ActivityTiming>,
+        ff: Option<FreeFallTiming>,
+    ) -> Result<Option<Warning>, Error<B::Error>> {
+        if let Some(activity) = activity {
+            field_check(activity.wake_dur, 2)?;
+            field_check(activity.sleep_dur, 4)?;
+        }
+
+        let mut wake_up_ths = WakeUpThs::read(self)?;
+        let mut wake_up_dur = WakeUpDur::read(self)?;
+        let mut free_fall = FreeFall::read(self)?;
+
+        if let Some(activity) = activity {
+            wake_up_ths.set_sleep_on(activity.mode.sleep_on());
+            wake_up_dur.set_stationary(activity.mode.stationary());
+            wake_up_dur.set_wake_dur(activity.wake_dur);
+            wake_up_dur.set_sleep_dur(activity.sleep_dur);
+        }
+        if let Some(ff) = ff {
+            wake_up_dur.set_ff_dur((ff.duration.lsb() & 0x20) >> 5);
+            free_fall.set_ff_dur(ff.duration.lsb() & 0x1F);
+            free_fall.set_ff_ths(ff.threshold as u8);
+        }
+
+        wake_up_ths.write(self)?;
+        wake_up_dur.write(self)?;
+        free_fall.write(self)?;
+
+        let mode = SleepOn::new(wake_up_ths.sleep_on(), wake_up_dur.stationary());
+        let ff_dur = (wake_up_dur.ff_dur() << 5) + free_fall.ff_dur();
+        Ok(
+            (mode == SleepOn::ActInact && ff_dur != 0)
+                .then_some(Warning::FreeFallDurationStretched),
+        )
+    }
+
+    /// Set the duration to enter sleep mode in milliseconds.
+    ///
+    /// This function converts `ms` to the nearest `sleep_dur` code for the current output data rate,
+    /// corrected with the calibration (see [`Self::odr_calibration_set`]),
+    /// and writes it to the `WAKE_UP_DUR` register. Code 0 is 16 / ODR, any other code is 512 / ODR per LSB,
+    /// so the steps are coarse (320 ms at 1.6 kHz, 320 s at 1.6 Hz).
+    ///
+    /// ### Arguments
+    /// - `ms`: The desired sleep duration in milliseconds.
+    ///
+    /// ### Returns
+    /// - `Ok(u32)`: The duration actually programmed, in milliseconds.
+    /// - `Err(Error::OutOfRange)`: If `ms` is past the longest code by more than the duration tolerance; `max` is
+    ///   the longest duration, rounded as the achieved one. Nothing is written.
+    /// - `Err(Error::Inexact)`: If `ms` falls between two codes and the nearest one, `nearest`, misses it by
+    ///   more than the duration tolerance; nothing is written.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn act_sleep_dur_ms_set(&mut self, ms: u32) -> Result<u32, Error<B::Error>> {
+        let (code, achieved) = self.duration_code_for_ms(ms, DurationField::SleepDur)?;
+        self.act_sleep_dur_set(code)?;
+        Ok(achieved)
+    }
+
+    /// Get the duration to enter sleep mode in milliseconds.
+    ///
+    /// This function reads the `sleep_dur` field in the `WAKE_UP_DUR` register and converts it with the current
+    /// output data rate, corrected with the calibration (see [`Self::odr_calibration_set`]). Code 0 is 16 / ODR,
+    /// any other code is 512 / ODR per LSB.
+    ///
+    /// ### Returns
+    /// - `Ok(u32)`: The current sleep duration, rounded to the millisecond.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn act_sleep_dur_ms_get(&mut self) -> Result<u32, Error<B::Error>> {
+        let code = self.act_sleep_dur_get()?;
+        self.duration_ms_for_code(code, DurationField::SleepDur)
+    }
+
+    /// Configure the activity detection chain in a single call.
+    ///
+    /// This function validates every field first, then reads the `WAKE_UP_THS`, `WAKE_UP_DUR` and `CTRL7`
+    /// registers and writes each of them once with the detection mode, the wake-up threshold and duration, the
+    /// sleep duration and the data feed. The other fields of the registers, e.g. the tap and free-fall settings,
+    /// are kept. With [`ActivityConfig::sleep_change_on_int2`], the sleep change event is then routed to the INT2
+    /// pad as [`Self::int2_route_update_critical`] does, which also enables the embedded function interrupts.
+    ///
+    /// ### Arguments
+    /// - `cfg`: The activity detection settings, see [`ActivityConfig`].
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidArgument)`: If a threshold or duration code does not fit in its field; nothing is accessed.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn activity_config_set(&mut self, cfg: &ActivityConfig) -> Result<(), Error<B::Error>> {
+        field_check(cfg.wake_threshold, 6)?;
+        field_check(cfg.wake_dur, 2)?;
+        field_check(cfg.sleep_dur, 4)?;
+
+        let mut wake_up_ths = WakeUpThs::read(self)?;
+        let mut wake_up_dur = WakeUpDur::read(self)?;
+        let mut ctrl7 = Ctrl7::read(self)?;
+
+        wake_up_ths.set_wk_ths(cfg.wake_threshold);
+        wake_up_dur.set_wake_dur(cfg.wake_dur);
+        wake_up_dur.set_sleep_dur(cfg.sleep_dur);
+        wake_up_ths.set_sleep_on(cfg.mode.sleep_on());
+        wake_up_dur.set_stationary(cfg.mode.stationary());
+        ctrl7.set_usr_off_on_wu(cfg.feed as u8);
+
+        wake_up_ths.write(self)?;
+        wake_up_dur.write(self)?;
+        ctrl7.write(self)?;
+
+        if cfg.sleep_change_on_int2 {
+            self.int2_route_update_critical(|routes| routes.set_int2_sleep_chg(PROPERTY_ENABLE))?;
+        }
+        Ok(())
+    }
+
+    /// Get the activity detection chain configuration.
+    ///
+    /// This function reads the `WAKE_UP_THS`, `WAKE_UP_DUR`, `CTRL5_INT2_PAD_CTRL` and `CTRL7` registers.
+    ///
+    /// ### Returns
+    /// - `Ok(ActivityConfig)`: The current settings; `sleep_change_on_int2` tells whether the sleep change event
+    ///   is routed to the INT2 pad.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn activity_config_get(&mut self) -> Result<ActivityConfig, Error<B::Error>> {
+        let wake_up_ths = WakeUpThs::read(self)?;
+        let wake_up_dur = WakeUpDur::read(self)?;
+        let ctrl5 = Ctrl5Int2PadCtrl::read(self)?;
+        let ctrl7 = Ctrl7::read(self)?;
+
+        Ok(ActivityConfig {
+            mode: SleepOn::new(wake_up_ths.sleep_on(), wake_up_dur.stationary()),
+            wake_threshold: wake_up_ths.wk_ths(),
+            wake_dur: wake_up_dur.wake_dur(),
+            sleep_dur: wake_up_dur.sleep_dur(),
+            feed: UsrOffOnWu::try_from(ctrl7.usr_off_on_wu()).unwrap_or_default(),
+            sleep_change_on_int2: ctrl5.int2_sleep_chg() == PROPERTY_ENABLE,
+        })
+    }
+
+    /// Get the state of the activity detection.
+    ///
+    /// This function reads the `sleep_state` field in the `STATUS` register, which mirrors the `sleep_state_ia`
+    /// field of the `WAKE_UP_SRC` register without clearing the latched wake-up event that a read of
+    /// `WAKE_UP_SRC` clears.
+    ///
+    /// ### Returns
+    /// - `Ok(ActivityState)`: [`ActivityState::Inactive`] while the device is in the sleep state,
+    ///   [`ActivityState::Active`] otherwise, also when no detection is configured.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn activity_state_get(&mut self) -> Result<ActivityState, Error<B::Error>> {
+        Ok(if self.status_reg_get()?.sleep_state() == 1 {
+            ActivityState::Inactive
+        } else {
+            ActivityState::Active
+        })
+    }
+
+    /// Get the change of the activity state since the last read, if any.
+    ///
+    /// This function reads every source register as [`Self::all_sources_get`] does, which counts the events and
+    /// clears the latched ones, and decodes the `sleep_change_ia` field of `ALL_INT_SRC` with
+    /// [`ActivityTransition::decode`]. An application handling other events as well decodes the result of
+    /// [`Self::all_sources_get`] instead.
+    ///
+    /// ### Returns
+    /// - `Ok(Some(ActivityTransition))`: The state the device changed to.
+    /// - `Ok(None)`: If the state did not change.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn activity_transition_get(
+        &mut self,
+    ) -> Result<Option<ActivityTransition>, Error<B::Error>> {
+        Ok(ActivityTransition::decode(&self.all_sources_get()?))
+    }
+
+    /// Check that the high-pass filter feeding the wake-up function settles quickly enough.
+    ///
+    /// With [`UsrOffOnWu::HpFeed`] (default) the wake-up function works on high-pass filtered data. A low data rate
+    /// combined with a high divider (for example 12.5 Hz and `OdrDiv20`) puts the corner below
+    /// [`MIN_WAKEUP_HP_CUTOFF_HZ`]: the filter then needs seconds to settle after every change and wake-up events are missed.
+    ///
+    /// ### Returns
+    /// - `Ok(None)`: If the configuration is fine, or the wake-up function does not use the high-pass filter.
+    /// - `Ok(Some(Warning::WakeupHighPassSlow))`: If the cutoff is too low for the wake-up function.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn wakeup_high_pass_check(&mut self) -> Result<Option<Warning>, Error<B::Error>> {
+        if self.wkup_feed_data_get()? != UsrOffOnWu::HpFeed {
+            return Ok(None);
+        }
+        let odr = self.data_rate_get()?;
+        let slow = self
+            .filter_bandwidth_get()?
+            .cutoff_hz(odr)
+            .is_some_and(|hz| hz < MIN_WAKEUP_HP_CUTOFF_HZ);
+
+        Ok(slow.then_some(Warning::WakeupHighPassSlow))
+    }
+}
@@ -0,0 +1,563 @@
+//! Software signal processing helpers.
+//!
+//! The helpers of this module own no bus access: they are fed with samples from any read path
+//! (single reads, FIFO drains, [`crate::batch::SampleBatch`]), except
+//! [`crate::Iis2dlpc::fifo_drain_into_goertzel`] which drains the FIFO into a [`GoertzelBank`].
+//! Every helper is a [`SampleSink`], so that recorded data can be replayed into it on a host, see
+//! [`crate::replay`].
+
+use crate::prelude::*;
+use crate::replay::SampleSink;
+
+/// Fixed-point representation of an EMA coefficient of 1.0 (Q16).
+pub const ALPHA_ONE: u32 = 1 << 16;
+
+/// Per-axis exponential moving average.
+///
+/// Each axis is smoothed with its own coefficient `alpha`, expressed in Q16 ([`ALPHA_ONE`] = 1.0):
+/// `y[n] = y[n-1] + alpha * (x[n] - y[n-1])`. [`ALPHA_ONE`] passes the samples through,
+/// lower values average more. The first sample after creation or [`AxisFilter::reset`]
+/// initializes the state, so there is no ramp from zero.
+///
+/// The computation uses integers only; the state keeps 16 fractional bits and the output
+/// is rounded to the nearest LSB.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AxisFilter {
+    alpha: [u32; 3],
+    state: [i32; 3],
+    ready: bool,
+}
+
+impl AxisFilter {
+    /// Create a filter.
+    ///
+    /// ### Arguments
+    /// - `alpha`: The X, Y and Z coefficients in Q16; values above [`ALPHA_ONE`] are clamped.
+    pub fn new(alpha: [u32; 3]) -> Self {
+        Self {
+            alpha: alpha.map(|a| a.min(ALPHA_ONE)),
+            state: [0; 3],
+            ready: false,
+        }
+    }
+
+    /// Feed a sample.
+    ///
+    /// ### Arguments
+    /// - `sample`: The X, Y and Z values, usually raw LSB.
+    pub fn feed(&mut self, sample: [i16; 3]) {
+        for ((state, alpha), x) in self.state.iter_mut().zip(self.alpha).zip(sample) {
+            let x = (x as i32) << 16;
+            if self.ready {
+                let delta = (x as i64 - *state as i64) * alpha as i64;
+                *state += ((delta + (1 << 15)) >> 16) as i32;
+            } else {
+                *state = x;
+            }
+        }
+        self.ready = true;
+    }
+
+    /// Get the filtered value of an axis.
+    ///
+    /// ### Returns
+    /// - `Some(i16)`: The filtered value.
+    /// - `None`: If no sample was fed since the creation or the last reset.
+    pub fn value(&self, axis: Axis) -> Option<i16> {
+        let state = match axis {
+            Axis::X => self.state[0],
+            Axis::Y => self.state[1],
+            Axis::Z => self.state[2],
+        };
+        self.ready.then_some(((state + (1 << 15)) >> 16) as i16)
+    }
+
+    /// Get the filtered values of the three axes.
+    ///
+    /// ### Returns
+    /// - `Some([i16; 3])`: The filtered X, Y and Z values.
+    /// - `None`: If no sample was fed since the creation or the last reset.
+    pub fn values(&self) -> Option<[i16; 3]> {
+        Some([
+            self.value(Axis::X)?,
+            self.value(Axis::Y)?,
+            self.value(Axis::Z)?,
+        ])
+    }
+
+    /// Reset the filter: the next sample initializes the state again.
+    pub fn reset(&mut self) {
+        self.state = [0; 3];
+        self.ready = false;
+    }
+}
+
+impl SampleSink for AxisFilter {
+    /// Feed the raw values, see [`AxisFilter::feed`].
+    fn feed(&mut self, raw: [i16; 3], _mg: [f32; 3]) {
+        AxisFilter::feed(self, raw);
+    }
+}
+
+/// Per-axis running statistics of the acceleration.
+///
+/// The mean and the variance are updated with Welford's method, which keeps them accurate over long
+/// recordings without storing the samples.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct AxisStats {
+    count: u32,
+    min: [f32; 3],
+    max: [f32; 3],
+    mean: [f32; 3],
+    m2: [f32; 3],
+}
+
+impl AxisStats {
+    /// Create empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a sample.
+    ///
+    /// ### Arguments
+    /// - `sample_mg`: The X, Y and Z values, usually in mg.
+    pub fn feed(&mut self, sample_mg: [f32; 3]) {
+        self.count = self.count.saturating_add(1);
+        let n = self.count as f32;
+        for (i, x) in sample_mg.into_iter().enumerate() {
+            let (Some(min), Some(max), Some(mean), Some(m2)) = (
+                self.min.get_mut(i),
+                self.max.get_mut(i),
+                self.mean.get_mut(i),
+                self.m2.get_mut(i),
+            ) else {
+                continue;
+            };
+            if self.count == 1 {
+                (*min, *max) = (x, x);
+            } else {
+                *min = min.min(x);
+                *max = max.max(x);
+            }
+            let delta = x - *mean;
+            *mean += delta / n;
+            *m2 += delta * (x - *mean);
+        }
+    }
+
+    /// Get the number of samples fed since the creation or the last reset.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Get the lowest value of every axis.
+    ///
+    /// ### Returns
+    /// - `Some([f32; 3])`: The lowest X, Y and Z values.
+    /// - `None`: If no sample was fed.
+    pub fn min(&self) -> Option<[f32; 3]> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// Get the highest value of every axis.
+    ///
+    /// ### Returns
+    /// - `Some([f32; 3])`: The highest X, Y and Z values.
+    /// - `None`: If no sample was fed.
+    pub fn max(&self) -> Option<[f32; 3]> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    /// Get the mean of every axis.
+    ///
+    /// ### Returns
+    /// - `Some([f32; 3])`: The X, Y and Z means.
+    /// - `None`: If no sample was fed.
+    pub fn mean(&self) -> Option<[f32; 3]> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Get the population variance of every axis, in the square of the input unit.
+    ///
+    /// ### Returns
+    /// - `Some([f32; 3])`: The X, Y and Z variances.
+    /// - `None`: If no sample was fed.
+    pub fn variance(&self) -> Option<[f32; 3]> {
+        let n = self.count as f32;
+        (self.count > 0).then_some(self.m2.map(|m2| m2 / n))
+    }
+
+    /// Reset the statistics.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl SampleSink for AxisStats {
+    /// Feed the values in mg, see [`AxisStats::feed`].
+    fn feed(&mut self, _raw: [i16; 3], mg: [f32; 3]) {
+        AxisStats::feed(self, mg);
+    }
+}
+
+/// Bin of a [`GoertzelBank`].
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+struct GoertzelBin {
+    coeff: f32,
+    len: u32,
+    count: u32,
+    s1: f32,
+    s2: f32,
+    energy: f32,
+}
+
+/// Band energy estimator built on Goertzel filters.
+///
+/// Each bin evaluates the spectrum at its center frequency over a block of `sample_rate_hz / bandwidth_hz`
+/// samples, rounded: the frequency resolution of a block is the sample rate divided by its length, so the block
+/// length sets the bandwidth. At the end of each block the bin stores the mean-square energy of the input at the
+/// center frequency, normalized so that a sinusoid of amplitude `A` on the center gives `A² / 2` (a constant `A`
+/// gives `2 · A²` on a 0 Hz bin), and starts a new block. A bank costs one multiplication and two additions per
+/// bin and sample, far less than a full FFT when only a few bands matter.
+///
+/// Numeric stability: the recursion runs in `f32` and its state grows linearly with the block length for an input
+/// on the center frequency, and with the DC content of the input for the bins close to 0 Hz. Over long blocks
+/// (thousands of samples) the rounding errors add up and the energies lose precision: prefer blocks of at most a
+/// few thousand samples and remove the gravity component before feeding, e.g. with a high-pass filter.
+///
+/// ```ignore
+/// // Bearing-fault band monitoring at 800 Hz: 50 Hz wide bins at 100 Hz and 150 Hz
+/// let mut bank = GoertzelBank::<2>::new(&[(100.0, 50.0), (150.0, 50.0)], 800.0).unwrap();
+/// sensor.fifo_drain_into_goertzel(&mut bank, Axis::Z)?;
+/// let [e100, e150] = bank.energies();
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GoertzelBank<const N: usize> {
+    bins: [GoertzelBin; N],
+}
+
+impl<const N: usize> GoertzelBank<N> {
+    /// Create a bank.
+    ///
+    /// ### Arguments
+    /// - `bins`: The center frequency and the bandwidth of each bin, in Hz; at most `N` bins, the remaining slots
+    ///   report no energy.
+    /// - `sample_rate_hz`: The rate of the samples fed, usually the output data rate.
+    ///
+    /// ### Returns
+    /// - `Some(GoertzelBank)`: The bank.
+    /// - `None`: If there are more than `N` bins, the sample rate is not positive, a center frequency is outside
+    ///   0 Hz to the Nyquist frequency or a bandwidth is not positive.
+    pub fn new(bins: &[(f32, f32)], sample_rate_hz: f32) -> Option<Self> {
+        if bins.len() > N || !sample_rate_hz.is_finite() || sample_rate_hz <= 0.0 {
+            return None;
+        }
+        let mut bank = Self {
+            bins: [GoertzelBin::default(); N],
+        };
+        for (bin, (center_hz, bandwidth_hz)) in bank.bins.iter_mut().zip(bins) {
+            if !(0.0..=sample_rate_hz / 2.0).contains(center_hz)
+                || !bandwidth_hz.is_finite()
+                || *bandwidth_hz <= 0.0
+            {
+                return None;
+            }
+            let omega = 2.0 * core::f32::consts::PI * center_hz / sample_rate_hz;
+            bin.coeff = 2.0 * cos(omega);
+            bin.len = ((sample_rate_hz / bandwidth_hz + 0.5) as u32).max(1);
+        }
+        Some(bank)
+    }
+
+    /// Feed a sample.
+    ///
+    /// Feed one axis per bank; use one bank per axis to monitor several.
+    ///
+    /// ### Arguments
+    /// - `sample_mg`: The acceleration, usually in mg.
+    pub fn feed(&mut self, sample_mg: f32) {
+        for bin in self.bins.iter_mut().filter(|bin| bin.len > 0) {
+            let s0 = sample_mg + bin.coeff * bin.s1 - bin.s2;
+            bin.s2 = bin.s1;
+            bin.s1 = s0;
+            bin.count += 1;
+            if bin.count == bin.len {
+                let power = bin.s1 * bin.s1 + bin.s2 * bin.s2 - bin.coeff * bin.s1 * bin.s2;
+                let len = bin.len as f32;
+                bin.energy = 2.0 * power / (len * len);
+                bin.s1 = 0.0;
+                bin.s2 = 0.0;
+                bin.count = 0;
+            }
+        }
+    }
+
+    /// Get the energy of every bin.
+    ///
+    /// ### Returns
+    /// - `[f32; N]`: The energy of the last completed block of each bin, in the square of the input unit; `0.0`
+    ///   before the first block completes and for the unused slots.
+    pub fn energies(&self) -> [f32; N] {
+        self.bins.map(|bin| bin.energy)
+    }
+
+    /// Get the block length of every bin.
+    ///
+    /// ### Returns
+    /// - `[u32; N]`: The number of samples per block of each bin; `0` for the unused slots.
+    pub fn block_lengths(&self) -> [u32; N] {
+        self.bins.map(|bin| bin.len)
+    }
+
+    /// Feed one axis of the samples given to the returned sink, in mg.
+    ///
+    /// ### Arguments
+    /// - `axis`: The axis fed to the bank.
+    pub fn on_axis(&mut self, axis: Axis) -> GoertzelSink<'_, N> {
+        GoertzelSink { bank: self, axis }
+    }
+
+    /// Reset the bank: the blocks in progress and the energies are cleared.
+    pub fn reset(&mut self) {
+        for bin in self.bins.iter_mut() {
+            *bin = GoertzelBin {
+                coeff: bin.coeff,
+                len: bin.len,
+                ..GoertzelBin::default()
+            };
+        }
+    }
+}
+
+/// One axis of the samples feeding a [`GoertzelBank`], see [`GoertzelBank::on_axis`].
+#[derive(Debug)]
+pub struct GoertzelSink<'a, const N: usize> {
+    bank: &'a mut GoertzelBank<N>,
+    axis: Axis,
+}
+
+impl<const N: usize> SampleSink for GoertzelSink<'_, N> {
+    fn feed(&mut self, _raw: [i16; 3], [x, y, z]: [f32; 3]) {
+        self.bank.feed(match self.axis {
+            Axis::X => x,
+            Axis::Y => y,
+            Axis::Z => z,
+        });
+    }
+}
+
+/// Cosine of an angle between 0 and π, without the floating point functions of `std`.
+///
+/// The angle is folded to 0 .. π/2 and the Taylor series is evaluated up to the 12th order, with an error below
+/// 1e-7.
+fn cos(x: f32) -> f32 {
+    let (x, sign) = if x > core::f32::consts::FRAC_PI_2 {
+        (core::f32::consts::PI - x, -1.0)
+    } else {
+        (x, 1.0)
+    };
+    let x2 = x * x;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..=6 {
+        let n = n as f32;
+        term *= -x2 / ((2.0 * n - 1.0) * (2.0 * n));
+        sum += term;
+    }
+    sign * sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Q16 coefficients of 1/2, 1/4, 1/16 and about 0.1.
+    const ALPHAS: [u32; 4] = [ALPHA_ONE / 2, ALPHA_ONE / 4, ALPHA_ONE / 16, 6554];
+
+    /// Feed a step from 0 to `step` and compare every output with `step * (1 - (1 - alpha)^n)`.
+    fn step_response(alpha: u32, step: i16) {
+        let mut filter = AxisFilter::new([alpha; 3]);
+        filter.feed([0; 3]);
+        let decay = 1.0 - alpha as f64 / ALPHA_ONE as f64;
+        let mut remaining = 1.0;
+        for n in 1..=200 {
+            filter.feed([step; 3]);
+            remaining *= decay;
+            let expected = step as f64 * (1.0 - remaining);
+            let value = filter.value(Axis::X).unwrap();
+            // The state rounds by half a Q16 LSB per sample, the output by half an LSB
+            let bound = 0.5 + n as f64 / (2.0 * ALPHA_ONE as f64);
+            assert!(
+                (value as f64 - expected).abs() <= bound,
+                "alpha {alpha}, sample {n}: {value} for {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn step_response_follows_the_closed_form() {
+        for alpha in ALPHAS {
+            step_response(alpha, 1000);
+            step_response(alpha, -1000);
+            step_response(alpha, i16::MAX);
+        }
+    }
+
+    #[test]
+    fn first_sample_initializes_the_state() {
+        let mut filter = AxisFilter::new([ALPHA_ONE / 16; 3]);
+        assert_eq!(filter.values(), None);
+        filter.feed([100, -200, 300]);
+        assert_eq!(filter.values(), Some([100, -200, 300]));
+
+        filter.reset();
+        assert_eq!(filter.value(Axis::Z), None);
+        filter.feed([-7, 8, 9]);
+        assert_eq!(filter.values(), Some([-7, 8, 9]));
+    }
+
+    #[test]
+    fn each_axis_uses_its_own_coefficient() {
+        // Above one is clamped: the sample passes through
+        let mut filter = AxisFilter::new([ALPHA_ONE, 2 * ALPHA_ONE, ALPHA_ONE / 2]);
+        filter.feed([0; 3]);
+        filter.feed([1000; 3]);
+        assert_eq!(filter.values(), Some([1000, 1000, 500]));
+        filter.feed([1000; 3]);
+        assert_eq!(filter.values(), Some([1000, 1000, 750]));
+    }
+
+    #[test]
+    fn constant_input_stays_exact() {
+        for alpha in ALPHAS {
+            let mut filter = AxisFilter::new([alpha; 3]);
+            for _ in 0..100 {
+                filter.feed([i16::MIN, -1, i16::MAX]);
+            }
+            assert_eq!(filter.values(), Some([i16::MIN, -1, i16::MAX]));
+        }
+    }
+
+    const RATE_HZ: f32 = 800.0;
+
+    /// Bins of 50 Hz at 100 Hz and 150 Hz: blocks of 16 samples, holding 2 and 3 periods of the centers.
+    fn bearing_bank() -> GoertzelBank<3> {
+        GoertzelBank::new(&[(100.0, 50.0), (150.0, 50.0)], RATE_HZ).unwrap()
+    }
+
+    /// Feed `len` samples of a sinusoid of amplitude `amplitude` at `hz`, starting at phase 0.
+    fn feed_sine(bank: &mut GoertzelBank<3>, amplitude: f64, hz: f32, len: usize) {
+        let omega = 2.0 * core::f32::consts::PI * hz / RATE_HZ;
+        // sin(ω) = cos(π/2 - ω), with the rotation computed in f64 to keep the amplitude
+        let (c, s) = (
+            cos(omega) as f64,
+            cos(core::f32::consts::FRAC_PI_2 - omega) as f64,
+        );
+        let (mut re, mut im) = (1.0, 0.0);
+        for _ in 0..len {
+            bank.feed((amplitude * im) as f32);
+            (re, im) = (re * c - im * s, re * s + im * c);
+        }
+    }
+
+    /// Whether `value` matches `expected` within `tolerance`, relative.
+    fn close(value: f32, expected: f32, tolerance: f32) -> bool {
+        (value - expected).abs() <= expected * tolerance
+    }
+
+    #[test]
+    fn cosine_matches_the_known_values() {
+        let pi = core::f32::consts::PI;
+        for (x, expected) in [
+            (0.0, 1.0),
+            (pi / 3.0, 0.5),
+            (pi / 2.0, 0.0),
+            (2.0 * pi / 3.0, -0.5),
+            (pi, -1.0),
+        ] {
+            assert!((cos(x) - expected).abs() < 1e-6, "cos({x}) = {}", cos(x));
+        }
+    }
+
+    #[test]
+    fn bins_are_sized_by_their_bandwidth() {
+        let bank = GoertzelBank::<3>::new(&[(100.0, 50.0), (10.0, 3.0)], RATE_HZ).unwrap();
+        // 800 / 3 rounds to 267 samples, the unused slot is empty
+        assert_eq!(bank.block_lengths(), [16, 267, 0]);
+        assert_eq!(bank.energies(), [0.0; 3]);
+    }
+
+    #[test]
+    fn invalid_bins_are_refused() {
+        let too_many = [(100.0, 50.0); 4];
+        assert_eq!(GoertzelBank::<3>::new(&too_many, RATE_HZ), None);
+        for bin in [(401.0, 50.0), (-1.0, 50.0), (100.0, 0.0), (100.0, f32::NAN)] {
+            assert_eq!(GoertzelBank::<3>::new(&[bin], RATE_HZ), None, "{bin:?}");
+        }
+        for rate in [0.0, -800.0, f32::INFINITY] {
+            assert_eq!(GoertzelBank::<3>::new(&[(1.0, 1.0)], rate), None);
+        }
+    }
+
+    #[test]
+    fn sinusoid_on_a_center_fills_its_bin_only() {
+        let mut bank = bearing_bank();
+        feed_sine(&mut bank, 100.0, 100.0, 16);
+        let [on, off, unused] = bank.energies();
+        // A² / 2
+        assert!(close(on, 5_000.0, 1e-3), "{on}");
+        assert!(off < on * 1e-4, "{off}");
+        assert_eq!(unused, 0.0);
+
+        let mut bank = bearing_bank();
+        feed_sine(&mut bank, 100.0, 150.0, 16);
+        let [off, on, _] = bank.energies();
+        assert!(close(on, 5_000.0, 1e-3), "{on}");
+        assert!(off < on * 1e-4, "{off}");
+    }
+
+    #[test]
+    fn sinusoid_between_the_centers_leaks_into_both() {
+        // 125 Hz is half a bin away from both centers
+        let mut bank = bearing_bank();
+        feed_sine(&mut bank, 100.0, 125.0, 16);
+        for energy in bank.energies().into_iter().take(2) {
+            assert!(energy > 0.2 * 5_000.0 && energy < 0.6 * 5_000.0, "{energy}");
+        }
+
+        // 300 Hz completes 6 periods per block: no energy in either bin
+        let mut bank = bearing_bank();
+        feed_sine(&mut bank, 100.0, 300.0, 16);
+        for energy in bank.energies() {
+            assert!(energy < 5_000.0 * 1e-4, "{energy}");
+        }
+    }
+
+    #[test]
+    fn constant_input_on_the_zero_bin() {
+        let mut bank = GoertzelBank::<1>::new(&[(0.0, 50.0)], RATE_HZ).unwrap();
+        for _ in 0..16 {
+            bank.feed(10.0);
+        }
+        // 2 · A²
+        assert!(close(bank.energies()[0], 200.0, 1e-5));
+    }
+
+    #[test]
+    fn energy_updates_once_per_block() {
+        let mut bank = bearing_bank();
+        feed_sine(&mut bank, 100.0, 100.0, 15);
+        assert_eq!(bank.energies(), [0.0; 3]);
+        feed_sine(&mut bank, 100.0, 100.0, 1);
+        assert!(bank.energies()[0] > 0.0);
+
+        // The next block replaces the energy
+        feed_sine(&mut bank, 10.0, 100.0, 16);
+        assert!(close(bank.energies()[0], 50.0, 1e-3));
+
+        bank.reset();
+        assert_eq!(bank.energies(), [0.0; 3]);
+        assert_eq!(bank.block_lengths(), [16, 16, 0]);
+    }
+}
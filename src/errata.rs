@@ -0,0 +1,103 @@
+//! Software workarounds for behavioral quirks of the part.
+//!
+//! A few configuration changes take effect on a running data stream without the output telling
+//! the samples produced before and after the change apart. [`Workarounds`] lists the driver
+//! functions that compensate for them; each flag documents the symptom and the workaround applied.
+//!
+//! The driver starts with [`Workarounds::RECOMMENDED`], see [`crate::Iis2dlpc::workarounds_set`]
+//! to trade the extra delays for latency. Every workaround only applies while the data rate is
+//! running continuously: with the device off or triggered on demand nothing is inserted. The
+//! settling left once the workaround is over is returned by the setters, see [`crate::settle`].
+
+use core::ops::{BitOr, BitOrAssign};
+
+/// Number of samples discarded after a filter bandwidth change by [`Workarounds::FILTER_DISCARD`].
+pub const FILTER_DISCARD_SAMPLES: u32 = 2;
+
+/// Set of software workarounds applied by the driver.
+///
+/// The set is a bit mask: flags are combined with `|` and tested with [`Self::contains`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Workarounds(u8);
+
+impl Workarounds {
+    /// No workaround.
+    pub const NONE: Self = Self(0);
+
+    /// Discard the samples filtered with a mix of the old and new bandwidth.
+    ///
+    /// Symptom: after a `bw_filt` change on a running data stream, the next samples still carry the
+    /// response of the previous bandwidth and a step shows in the output while the digital filter
+    /// restarts.
+    ///
+    /// Workaround: [`crate::Iis2dlpc::filter_bandwidth_set`] waits [`FILTER_DISCARD_SAMPLES`] data
+    /// periods, then reads the output registers once to drop the pending sample, so that the next
+    /// data-ready sample is filtered with the new bandwidth. With the FIFO collecting samples the
+    /// output registers are not read, since the read would pop the oldest FIFO sample.
+    pub const FILTER_DISCARD: Self = Self(1 << 0);
+
+    /// Wait one data period after an operating mode change.
+    ///
+    /// Symptom: after switching between low-power and high-performance, or toggling `low_noise`, on
+    /// a running data stream, the first sample is produced with the noise and resolution of the
+    /// previous mode.
+    ///
+    /// Workaround: [`crate::Iis2dlpc::power_mode_set`] waits one data period after the last write.
+    pub const MODE_SWITCH_SETTLE: Self = Self(1 << 1);
+
+    /// Every workaround known to the driver.
+    pub const ALL: Self = Self(Self::FILTER_DISCARD.0 | Self::MODE_SWITCH_SETTLE.0);
+
+    /// Workarounds applied by default.
+    pub const RECOMMENDED: Self = Self::ALL;
+
+    /// Build a set from its bit mask, dropping the unknown bits.
+    pub const fn from_bits_truncate(bits: u8) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// Get the bit mask of the set.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Check whether every flag of `other` is in the set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check whether the set is empty.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Add the flags of `other` to the set.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Remove the flags of `other` from the set.
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl Default for Workarounds {
+    fn default() -> Self {
+        Self::RECOMMENDED
+    }
+}
+
+impl BitOr for Workarounds {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Workarounds {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
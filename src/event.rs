@@ -0,0 +1,574 @@
+//! Decoding of the embedded event sources.
+//!
+//! The functions of this module turn the raw source registers into [`Event`] values.
+//! [`crate::Iis2dlpc::tap_event_get`] and [`crate::Iis2dlpc::events_drain`] read the registers and use them.
+//!
+//! Every read of the source registers, [`crate::Iis2dlpc::all_sources_get`] and the functions built on it,
+//! follows the same order: a single burst from `STATUS_DUP` to `ALL_INT_SRC`, that is the detail registers
+//! (`WAKE_UP_SRC`, `TAP_SRC`, `SIXD_SRC`) first and the `ALL_INT_SRC` summary last. An event asserting
+//! during the burst can only be missing from the detail registers, which
+//! [`AllSources::is_consistent`] detects.
+//!
+//! The driver counts the events flagged by every source register it reads, see [`EventCounters`].
+//!
+//! # Interrupt storm guard
+//!
+//! A misconfigured detection, e.g. a wake-up threshold of 0, flags an event at every sample and the
+//! interrupt fires at the data rate. With a limit set by `Iis2dlpc::events_rate_limit_set`,
+//! [`crate::Iis2dlpc::events_drain_report`] counts the events decoded by each call; once the count
+//! exceeds the limit for `STORM_POLLS` consecutive calls, the report is flagged as
+//! [`EventsDrainReport::throttled`] until a call stays within the limit. With
+//! `StormPolicy::DisableRoute` the most frequent event of the storm is also removed from the pad
+//! routing, see [`EventsDrainReport::disabled`]; the event flags keep being set, but no longer
+//! raise the interrupt.
+//!
+//! The guard is compiled in with the `storm-guard` feature, on by default; without it no report is
+//! ever throttled.
+
+use crate::prelude::*;
+use crate::remap::AxisRemap;
+
+/// Maximum number of events decoded from a single read of the source registers.
+pub const MAX_EVENTS: usize = 6;
+
+/// Number of consecutive drains over the limit after which the interrupt storm guard trips.
+#[cfg(feature = "storm-guard")]
+pub const STORM_POLLS: u8 = 4;
+
+/// Kind of tap event.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TapKind {
+    /// Single tap.
+    Single,
+    /// Double tap.
+    Double,
+}
+
+/// Decoded tap event.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TapEvent {
+    /// Single or double tap.
+    pub kind: TapKind,
+    /// Axis on which the tap was detected; the highest priority one, see [`TapPrior`], if several are flagged.
+    pub axis: Option<Axis>,
+    /// `true` if the acceleration that triggered the tap was negative.
+    pub negative: bool,
+    /// Raw `TAP_SRC` content, including every flagged axis.
+    pub raw: u8,
+}
+
+impl TapEvent {
+    /// Check that no reserved bit is set in the raw `TAP_SRC` content.
+    ///
+    /// ### Returns
+    /// - `true` if every set bit has a known meaning.
+    pub fn is_fully_decoded(&self) -> bool {
+        self.raw & AllSources::TAP_SRC_RESERVED == 0
+    }
+}
+
+/// Reporting policy when both single- and double-tap flags are set in the same read.
+///
+/// With [`SingleDoubleTap::BothSingleDouble`] the first tap of a double tap can also raise
+/// the single-tap flag, depending on timing.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TapPolicy {
+    /// Report both a single- and a double-tap event, as flagged by the device.
+    ReportBoth,
+
+    /// Suppress the single-tap event when the double-tap flag is set (default).
+    #[default]
+    PreferDouble,
+}
+
+/// Event decoded from the source registers.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Event {
+    /// Single- or double-tap event.
+    Tap(TapEvent),
+    /// Wake-up event; `axes` flags the X, Y and Z axes that exceeded the threshold, in either direction.
+    WakeUp { axes: [bool; 3] },
+    /// Free-fall event.
+    FreeFall,
+    /// Change between the sleep and the active state; `sleeping` is the new state.
+    SleepChange { sleeping: bool },
+    /// 6D/4D orientation change, with the raw `SIXD_SRC` content.
+    SixD(SixdSrc),
+}
+
+/// Orientation of the device decoded from `SIXD_SRC`: the axis pointing up or down.
+///
+/// In 4D mode only the portrait and landscape states, on the X and Y axes, are detected.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Orientation {
+    /// The X axis points up, `xh`.
+    XUp,
+    /// The X axis points down, `xl`.
+    XDown,
+    /// The Y axis points up, `yh`.
+    YUp,
+    /// The Y axis points down, `yl`.
+    YDown,
+    /// The Z axis points up, `zh`; not in 4D mode.
+    ZUp,
+    /// The Z axis points down, `zl`; not in 4D mode.
+    ZDown,
+}
+
+impl Orientation {
+    /// Create an orientation.
+    ///
+    /// ### Arguments
+    /// - `axis`: The axis pointing up or down.
+    /// - `down`: `true` if the axis points down.
+    pub fn new(axis: Axis, down: bool) -> Self {
+        match (axis, down) {
+            (Axis::X, false) => Orientation::XUp,
+            (Axis::X, true) => Orientation::XDown,
+            (Axis::Y, false) => Orientation::YUp,
+            (Axis::Y, true) => Orientation::YDown,
+            (Axis::Z, false) => Orientation::ZUp,
+            (Axis::Z, true) => Orientation::ZDown,
+        }
+    }
+
+    /// Get the axis pointing up or down.
+    pub fn axis(&self) -> Axis {
+        match self {
+            Orientation::XUp | Orientation::XDown => Axis::X,
+            Orientation::YUp | Orientation::YDown => Axis::Y,
+            Orientation::ZUp | Orientation::ZDown => Axis::Z,
+        }
+    }
+
+    /// Check whether the axis points down.
+    pub fn is_down(&self) -> bool {
+        matches!(
+            self,
+            Orientation::XDown | Orientation::YDown | Orientation::ZDown
+        )
+    }
+
+    /// Decode the orientation flags of a `SIXD_SRC` content, in the sensor frame.
+    ///
+    /// In 4D mode the `zh` and `zl` flags are ignored, so a Z state is never reported.
+    ///
+    /// ### Arguments
+    /// - `src`: The `SIXD_SRC` content.
+    /// - `four_d`: `true` if 4D mode is enabled, `4d_en` in `TAP_THS_X`.
+    ///
+    /// ### Returns
+    /// - `Some(Orientation)`: The orientation, if `six_d_ia` is set with exactly one of the considered flags.
+    /// - `None`: If `six_d_ia` is not set, or the flags do not name a single orientation.
+    pub fn decode(src: SixdSrc, four_d: bool) -> Option<Self> {
+        if src.six_d_ia() == 0 {
+            return None;
+        }
+        let z = u8::from(!four_d);
+        let mut flagged = [
+            (src.xh(), Orientation::XUp),
+            (src.xl(), Orientation::XDown),
+            (src.yh(), Orientation::YUp),
+            (src.yl(), Orientation::YDown),
+            (src.zh() & z, Orientation::ZUp),
+            (src.zl() & z, Orientation::ZDown),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| *flag == 1)
+        .map(|(_, orientation)| orientation);
+        match (flagged.next(), flagged.next()) {
+            (Some(orientation), None) => Some(orientation),
+            _ => None,
+        }
+    }
+}
+
+/// Cumulative count of the events flagged in the source registers read by the driver.
+///
+/// Every driver function that reads a source register updates the counters:
+/// [`crate::Iis2dlpc::all_sources_get`] and the functions built on it, [`crate::Iis2dlpc::tap_src_get`],
+/// [`crate::Iis2dlpc::tap_event_get`] and [`crate::Iis2dlpc::sixd_src_get`]. An event flagged in both a
+/// detail and the summary register of the same read is counted once. Taps are counted as flagged by the
+/// device, regardless of the [`TapPolicy`]. The `STATUS` and `STATUS_DUP` flags are not counted.
+///
+/// With latched interrupts a read clears the flags, so each event is counted once; with pulsed interrupts
+/// an event is counted by every read that sees it. The counters saturate at `u32::MAX`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct EventCounters {
+    /// Single-tap events.
+    pub single_tap: u32,
+    /// Double-tap events.
+    pub double_tap: u32,
+    /// Wake-up events.
+    pub wake_up: u32,
+    /// Free-fall events.
+    pub free_fall: u32,
+    /// 6D/4D orientation changes.
+    pub six_d: u32,
+    /// Changes between the sleep and the active state.
+    pub sleep_change: u32,
+}
+
+impl EventCounters {
+    /// Count the events of a read of every source register.
+    pub(crate) fn count_sources(&mut self, sources: &AllSources) {
+        let summary = sources.all_int_src;
+        let tap = sources.tap_src;
+        count(
+            &mut self.single_tap,
+            tap.single_tap() | summary.single_tap(),
+        );
+        count(
+            &mut self.double_tap,
+            tap.double_tap() | summary.double_tap(),
+        );
+        count(
+            &mut self.wake_up,
+            sources.wake_up_src.wu_ia() | summary.wu_ia(),
+        );
+        count(
+            &mut self.free_fall,
+            sources.wake_up_src.ff_ia() | summary.ff_ia(),
+        );
+        count(
+            &mut self.six_d,
+            sources.sixd_src.six_d_ia() | summary.six_d_ia(),
+        );
+        count(&mut self.sleep_change, summary.sleep_change_ia());
+    }
+
+    /// Count the events of a `TAP_SRC` read.
+    #[cfg(feature = "tap")]
+    pub(crate) fn count_tap_src(&mut self, src: TapSrc) {
+        count(&mut self.single_tap, src.single_tap());
+        count(&mut self.double_tap, src.double_tap());
+    }
+
+    /// Count the events of a `SIXD_SRC` read.
+    #[cfg(feature = "sixd")]
+    pub(crate) fn count_sixd_src(&mut self, src: SixdSrc) {
+        count(&mut self.six_d, src.six_d_ia());
+    }
+}
+
+fn count(counter: &mut u32, flag: u8) {
+    if flag == 1 {
+        *counter = counter.saturating_add(1);
+    }
+}
+
+/// Action taken by the interrupt storm guard when it trips, see [`crate::Iis2dlpc::events_storm_policy_set`].
+#[cfg(feature = "storm-guard")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum StormPolicy {
+    /// Only flag the report as throttled (default).
+    #[default]
+    ReportOnly,
+    /// Also remove the most frequent event of the storm from the pad routing.
+    DisableRoute,
+}
+
+/// Pad routing of an event source.
+///
+/// The sleep change is routed on INT2 (`int2_sleep_chg` field in the `CTRL5_INT2_PAD_CTRL` register), every
+/// other event on INT1 (`CTRL4_INT1_PAD_CTRL` register).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EventRoute {
+    /// Single tap, `int1_single_tap`.
+    SingleTap,
+    /// Double tap, `int1_tap`.
+    DoubleTap,
+    /// Wake-up, `int1_wu`.
+    WakeUp,
+    /// Free-fall, `int1_ff`.
+    FreeFall,
+    /// 6D/4D orientation change, `int1_6d`.
+    SixD,
+    /// Change between the sleep and the active state, `int2_sleep_chg`.
+    SleepChange,
+}
+
+impl EventRoute {
+    /// Every route, in decoding order.
+    pub const ALL: [Self; MAX_EVENTS] = [
+        Self::SingleTap,
+        Self::DoubleTap,
+        Self::WakeUp,
+        Self::FreeFall,
+        Self::SixD,
+        Self::SleepChange,
+    ];
+
+    /// Check whether the route is set in a pad routing.
+    #[cfg(feature = "storm-guard")]
+    pub(crate) fn is_routed(self, int1: &Ctrl4Int1PadCtrl, int2: &Ctrl5Int2PadCtrl) -> bool {
+        let bit = match self {
+            Self::SingleTap => int1.int1_single_tap(),
+            Self::DoubleTap => int1.int1_tap(),
+            Self::WakeUp => int1.int1_wu(),
+            Self::FreeFall => int1.int1_ff(),
+            Self::SixD => int1.int1_6d(),
+            Self::SleepChange => int2.int2_sleep_chg(),
+        };
+        bit == 1
+    }
+
+    /// Clear the route in the INT1 routing; the sleep change is left to [`Self::clear_int2`].
+    #[cfg(feature = "storm-guard")]
+    pub(crate) fn clear_int1(self, int1: &mut Ctrl4Int1PadCtrl) {
+        match self {
+            Self::SingleTap => int1.set_int1_single_tap(0),
+            Self::DoubleTap => int1.set_int1_tap(0),
+            Self::WakeUp => int1.set_int1_wu(0),
+            Self::FreeFall => int1.set_int1_ff(0),
+            Self::SixD => int1.set_int1_6d(0),
+            Self::SleepChange => {}
+        }
+    }
+
+    /// Clear the route in the INT2 routing; only the sleep change is routed there.
+    #[cfg(feature = "storm-guard")]
+    pub(crate) fn clear_int2(self, int2: &mut Ctrl5Int2PadCtrl) {
+        if self == Self::SleepChange {
+            int2.set_int2_sleep_chg(0);
+        }
+    }
+
+    #[cfg(feature = "storm-guard")]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl From<Event> for EventRoute {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::Tap(TapEvent {
+                kind: TapKind::Single,
+                ..
+            }) => Self::SingleTap,
+            Event::Tap(_) => Self::DoubleTap,
+            Event::WakeUp { .. } => Self::WakeUp,
+            Event::FreeFall => Self::FreeFall,
+            Event::SixD(_) => Self::SixD,
+            Event::SleepChange { .. } => Self::SleepChange,
+        }
+    }
+}
+
+/// Result of [`crate::Iis2dlpc::events_drain_report`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct EventsDrainReport {
+    /// Number of events written to the buffer.
+    pub count: usize,
+    /// Number of events decoded from the read, including those that did not fit in the buffer.
+    pub decoded: usize,
+    /// The decoded events exceeded the rate limit for `STORM_POLLS` or more consecutive drains; always `false`
+    /// without the `storm-guard` feature.
+    pub throttled: bool,
+    /// Route removed by `StormPolicy::DisableRoute` on this drain; set once, on the drain that trips the guard.
+    pub disabled: Option<EventRoute>,
+}
+
+/// State of the interrupt storm guard.
+#[cfg(feature = "storm-guard")]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct StormGuard {
+    pub(crate) limit: Option<u16>,
+    pub(crate) policy: StormPolicy,
+    streak: u8,
+    counts: [u16; MAX_EVENTS],
+}
+
+#[cfg(feature = "storm-guard")]
+impl StormGuard {
+    /// Set the limit, restarting the detection.
+    pub(crate) fn limit_set(&mut self, limit: Option<u16>) {
+        *self = Self {
+            limit,
+            policy: self.policy,
+            ..Self::default()
+        };
+    }
+
+    /// Account the events decoded by a drain.
+    ///
+    /// Returns whether the guard is tripped and, on the drain that trips it, the most frequent route of the
+    /// storm; ties go to the first route in decoding order.
+    pub(crate) fn account(&mut self, events: &[Event]) -> (bool, Option<EventRoute>) {
+        let Some(limit) = self.limit else {
+            return (false, None);
+        };
+        if events.len() <= usize::from(limit) {
+            self.limit_set(self.limit);
+            return (false, None);
+        }
+
+        for event in events {
+            if let Some(count) = self.counts.get_mut(EventRoute::from(*event).index()) {
+                *count = count.saturating_add(1);
+            }
+        }
+        self.streak = self.streak.saturating_add(1);
+
+        let offender = (self.streak == STORM_POLLS)
+            .then(|| {
+                EventRoute::ALL
+                    .into_iter()
+                    .zip(self.counts)
+                    .fold(
+                        None,
+                        |best: Option<(EventRoute, u16)>, (route, count)| match best {
+                            Some((_, max)) if max >= count => best,
+                            _ if count > 0 => Some((route, count)),
+                            _ => best,
+                        },
+                    )
+                    .map(|(route, _)| route)
+            })
+            .flatten();
+        (self.streak >= STORM_POLLS, offender)
+    }
+}
+
+/// Tap events decoded from a single `TAP_SRC` read.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TapEvents {
+    events: [Option<TapEvent>; 2],
+    pos: usize,
+}
+
+impl TapEvents {
+    /// Decode the content of the `TAP_SRC` register.
+    ///
+    /// ### Arguments
+    /// - `src`: The `TAP_SRC` register content.
+    /// - `policy`: How to report a single tap flagged together with a double tap.
+    /// - `priority`: The configured axis priority, used when several axes are flagged.
+    pub fn decode(src: TapSrc, policy: TapPolicy, priority: TapPrior) -> Self {
+        let axis = priority.ordering().into_iter().find(|axis| match axis {
+            Axis::X => src.x_tap() == 1,
+            Axis::Y => src.y_tap() == 1,
+            Axis::Z => src.z_tap() == 1,
+        });
+        let event = |kind| TapEvent {
+            kind,
+            axis,
+            negative: src.tap_sign() == 1,
+            raw: src.into_bits(),
+        };
+
+        let double = src.double_tap() == 1;
+        let single = src.single_tap() == 1 && !(double && policy == TapPolicy::PreferDouble);
+
+        Self {
+            events: [
+                single.then(|| event(TapKind::Single)),
+                double.then(|| event(TapKind::Double)),
+            ],
+            pos: 0,
+        }
+    }
+
+    /// Convert the events to the board frame, see [`AxisRemap::tap_event`].
+    pub fn remapped(self, remap: AxisRemap) -> Self {
+        Self {
+            events: self
+                .events
+                .map(|event| event.map(|tap| remap.tap_event(tap))),
+            ..self
+        }
+    }
+}
+
+impl Iterator for TapEvents {
+    type Item = TapEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(slot) = self.events.get(self.pos) {
+            self.pos += 1;
+            if slot.is_some() {
+                return *slot;
+            }
+        }
+        None
+    }
+}
+
+/// Events decoded by a read of the source registers and not yet returned by [`crate::Iis2dlpc::event_get`].
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PendingEvents {
+    events: [Option<Event>; MAX_EVENTS],
+    pos: usize,
+}
+
+impl PendingEvents {
+    /// Hold the events of a read, in decoding order.
+    pub(crate) fn new(events: &[Event]) -> Self {
+        let mut pending = Self::default();
+        for (slot, event) in pending.events.iter_mut().zip(events) {
+            *slot = Some(*event);
+        }
+        pending
+    }
+}
+
+impl Iterator for PendingEvents {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.events.get_mut(self.pos)?.take();
+        self.pos += 1;
+        event
+    }
+}
+
+impl AllSources {
+    /// Decode every event flagged in the source registers.
+    ///
+    /// ### Arguments
+    /// - `policy`: How to report a single tap flagged together with a double tap.
+    /// - `priority`: The configured tap axis priority.
+    /// - `out`: Destination of the events; events that do not fit are dropped, [`MAX_EVENTS`] always fit.
+    ///
+    /// ### Returns
+    /// - `usize`: The number of events written to `out`.
+    pub fn decode_events(&self, policy: TapPolicy, priority: TapPrior, out: &mut [Event]) -> usize {
+        let mut events = [None; MAX_EVENTS];
+
+        let mut taps = TapEvents::decode(self.tap_src, policy, priority);
+        events[0] = taps.next().map(Event::Tap);
+        events[1] = taps.next().map(Event::Tap);
+        events[2] = (self.all_int_src.wu_ia() == 1).then_some(Event::WakeUp {
+            axes: [
+                self.wake_up_src.x_wu() == 1,
+                self.wake_up_src.y_wu() == 1,
+                self.wake_up_src.z_wu() == 1,
+            ],
+        });
+        events[3] = (self.all_int_src.ff_ia() == 1).then_some(Event::FreeFall);
+        events[4] = (self.all_int_src.sleep_change_ia() == 1).then_some(Event::SleepChange {
+            sleeping: self.wake_up_src.sleep_state_ia() == 1,
+        });
+        events[5] = (self.all_int_src.six_d_ia() == 1).then_some(Event::SixD(self.sixd_src));
+
+        let mut count = 0;
+        for (slot, event) in out.iter_mut().zip(events.into_iter().flatten()) {
+            *slot = event;
+            count += 1;
+        }
+        count
+    }
+}
+
+// The decoded sources and events are handed from interrupt handlers to other contexts: keep them plain values.
+const _: () = {
+    const fn deferrable<T: Copy + PartialEq + Send + Sync + 'static>() {}
+    deferrable::<AllSources>();
+    deferrable::<[u8; AllSources::BYTES]>();
+    deferrable::<Event>();
+    deferrable::<TapEvent>();
+    deferrable::<TapEvents>();
+    deferrable::<EventsDrainReport>();
+    deferrable::<EventCounters>();
+};
@@ -0,0 +1,258 @@
+//! FIFO drain integrity.
+//!
+//! [`crate::Iis2dlpc::fifo_drain`] reads `FIFO_SAMPLES` before and after the burst read of the
+//! samples, so that an overrun occurring while the MCU is draining is detected at the cost of a
+//! single extra byte read.
+//!
+//! The check has a residual race window: an overrun occurring after the final `FIFO_SAMPLES`
+//! read is only reported by the next drain, and the position of the overwritten sample within
+//! a compromised drain is unknown.
+//!
+//! The report also keeps the FIFO level read before the drain, from which the age of every drained
+//! sample follows without extra bus traffic, see [`FifoDrainReport::sample_age`].
+//!
+//! # Ping-pong draining
+//!
+//! [`crate::Iis2dlpc::fifo_drain_exact`] drains a fixed number of samples or nothing, so that a
+//! buffer split in two halves of `watermark` samples is always filled completely. A telemetry
+//! bridge routes the FIFO threshold to an interrupt pin and, on each interrupt, drains into the
+//! free half and hands the other one to a DMA transfer:
+//!
+//! ```ignore
+//! static mut HALVES: [[[i16; 3]; WATERMARK as usize]; 2] = ..;
+//!
+//! // FTH interrupt handler
+//! match sensor.fifo_drain_exact(WATERMARK, &mut halves[next]) {
+//!     Ok(integrity) => {
+//!         uart_dma_start(&halves[next], integrity);
+//!         next ^= 1;
+//!     }
+//!     // Spurious interrupt: nothing was consumed, the half stays free
+//!     Err(Error::FifoUnderrun { .. }) => {}
+//!     Err(e) => return Err(e),
+//! }
+//! ```
+//!
+//! The drain must complete before the FIFO holds `watermark` more samples, and the DMA transfer
+//! before the next drain reuses its half.
+//!
+//! # Watermark interrupts
+//!
+//! The FIFO threshold signal is a level: it stays high while the FIFO holds `watermark` samples
+//! or more. An edge-triggered MCU input only sees a new interrupt once the level went low, so a
+//! drain leaving `watermark` samples behind, because samples kept coming in during the burst
+//! read, stops the interrupts for good. Conversely an interrupt latched during a drain, or left
+//! pending by the configuration, fires with fewer samples than the watermark stored, possibly
+//! none.
+//!
+//! [`crate::Iis2dlpc::fifo_drain_watermark`] handles both: it drains whatever is stored, no
+//! error when it is below the watermark, and drains again until the level read after the burst
+//! read is below the watermark, so that [`FifoWatermarkReport::rearmed`] tells that the next
+//! rising edge will come:
+//!
+//! ```ignore
+//! loop {
+//!     let report = sensor.fifo_drain_watermark(&mut buf)?;
+//!     if report.integrity == FifoIntegrity::Compromised {
+//!         // Ordering lost: drop the samples and start from an empty FIFO
+//!         sensor.fifo_flush()?;
+//!     } else {
+//!         consume(&buf[..report.samples]);
+//!     }
+//!     if report.rearmed {
+//!         int1.wait_for_high().await?;
+//!     }
+//! }
+//! ```
+//!
+//! Waiting for a high level rather than for a rising edge also covers an edge occurring between
+//! the last `FIFO_SAMPLES` read and the start of the wait.
+//!
+//! # Averaged samples
+//!
+//! [`crate::Iis2dlpc::averaged_sample_get`] uses the FIFO as an averaging buffer: it collects `n`
+//! consecutive samples in FIFO mode, drains them at once and returns the integer sums, then puts
+//! the previous FIFO configuration back. [`crate::Iis2dlpc::averaged_sample_pin_get`] waits on the
+//! INT1 pin instead of polling the FIFO level, and [`crate::Iis2dlpc::averaged_mg_get`] returns
+//! the means in mg.
+//!
+//! # Data paths
+//!
+//! Block data update protects the output registers only; the FIFO is consistent by design, since
+//! each sample is read from the output registers as a whole. Once the FIFO collects samples, the
+//! output registers are its read port: a direct read of the "latest" sample in the same loop as
+//! the drains pops the oldest stored sample instead, which is lost to the next drain and shows
+//! up as a duplicate of the history. The driver tracks the [`DataPath`] from the writes of the
+//! `fmode` field and, while the FIFO path is active, the direct read functions return
+//! [`crate::ConfigError::FifoPathActive`]; [`crate::Iis2dlpc::allow_mixed_data_paths`] lifts the
+//! guard.
+
+#[cfg(feature = "fifo")]
+use crate::prelude::*;
+
+/// Path of the acceleration samples, see [`crate::Iis2dlpc::data_path_get`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum DataPath {
+    /// The FIFO is in bypass mode: the output registers hold the latest sample.
+    #[default]
+    Direct,
+    /// The FIFO collects samples: reading the output registers pops the oldest FIFO sample.
+    Fifo,
+}
+
+/// Number of samples the FIFO holds.
+pub const FIFO_DEPTH: usize = 32;
+
+/// Largest number of samples of [`crate::Iis2dlpc::averaged_sample_get`], the largest `fth` value.
+#[cfg(feature = "fifo")]
+pub const AVERAGE_MAX_SAMPLES: u8 = 31;
+
+// The sums of the averaged samples cannot overflow
+#[cfg(feature = "fifo")]
+const _: () = assert!(AVERAGE_MAX_SAMPLES as i64 * i16::MIN as i64 >= i32::MIN as i64);
+
+/// Raw X, Y, Z samples returned by [`crate::Iis2dlpc::fifo_drain_vec`], oldest first.
+#[cfg(all(feature = "fifo", feature = "heapless"))]
+pub type FifoSampleVec<const N: usize> = heapless::Vec<[i16; 3], N>;
+
+/// Integrity of the samples read by a FIFO drain.
+///
+/// The variants are ordered from the best to the worst, so that the integrity of several drains is
+/// their maximum.
+#[cfg(feature = "fifo")]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum FifoIntegrity {
+    /// No overrun: the samples are consecutive, oldest first.
+    #[default]
+    Intact,
+    /// An overrun occurred before the drain: samples older than the first one were lost, the
+    /// drained samples are still consecutive.
+    Overrun,
+    /// An overrun occurred during the drain: the sample ordering cannot be trusted.
+    Compromised,
+}
+
+/// FIFO status of the `FIFO_SAMPLES` register, see [`crate::Iis2dlpc::fifo_flags_get`].
+#[cfg(feature = "fifo")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FifoFlags {
+    /// Number of unread samples, the `diff` field.
+    pub level: u8,
+    /// The level reached the watermark, the `fifo_fth` field.
+    pub wtm: bool,
+    /// At least one sample was overwritten, the `fifo_ovr` field.
+    pub ovr: bool,
+    /// The FIFO holds [`FIFO_DEPTH`] samples, the condition routed by `int1_diff5` and `int2_diff5`.
+    pub full: bool,
+}
+
+/// Report of [`crate::Iis2dlpc::fifo_drain`].
+#[cfg(feature = "fifo")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FifoDrainReport {
+    /// Number of samples written to the buffer.
+    pub samples: usize,
+    /// Integrity of the drained samples.
+    pub integrity: FifoIntegrity,
+    /// `true` if the FIFO was flushed and drained again after a compromised drain.
+    pub retried: bool,
+    /// FIFO level (`diff` field of `FIFO_SAMPLES`) read before the drain.
+    pub start_diff: u8,
+}
+
+/// Report of [`crate::Iis2dlpc::fifo_drain_watermark`].
+#[cfg(feature = "fifo")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FifoWatermarkReport {
+    /// Number of samples written to the buffer, `0` for a spurious interrupt.
+    pub samples: usize,
+    /// Integrity of the drained samples, the worst of the burst reads.
+    pub integrity: FifoIntegrity,
+    /// FIFO level (`diff` field of `FIFO_SAMPLES`) read before the drain.
+    pub start_diff: u8,
+    /// FIFO level read after the last burst read.
+    pub end_diff: u8,
+    /// `true` if the FIFO level was below the watermark after the last burst read: the threshold
+    /// signal went low and its next rising edge reports the next watermark. `false` if the buffer
+    /// filled first; drain again without waiting for the pin.
+    pub rearmed: bool,
+}
+
+#[cfg(feature = "fifo")]
+impl FifoDrainReport {
+    /// Get the age of a drained sample at read time, in samples.
+    ///
+    /// The sample at `index` had `start_diff - index` samples, itself included, stored after it: the oldest
+    /// sample of a full drain is `start_diff` samples old, the newest one `1`.
+    ///
+    /// ### Arguments
+    /// - `index`: The index of the sample in the drain buffer.
+    ///
+    /// ### Returns
+    /// - `Some(u8)`: The age in samples.
+    /// - `None`: If `index` is not a drained sample.
+    pub fn sample_age(&self, index: usize) -> Option<u8> {
+        if index >= self.samples {
+            return None;
+        }
+        self.start_diff.checked_sub(u8::try_from(index).ok()?)
+    }
+
+    /// Get the age of a drained sample at read time, in microseconds.
+    ///
+    /// ### Arguments
+    /// - `index`: The index of the sample in the drain buffer.
+    /// - `period_us`: The data period, for example from [`crate::Iis2dlpc::odr_period_us`], which applies the
+    ///   data rate calibration.
+    ///
+    /// ### Returns
+    /// - `Some(u64)`: The approximate age, [`Self::sample_age`] data periods.
+    /// - `None`: If `index` is not a drained sample.
+    pub fn sample_age_us(&self, index: usize, period_us: u64) -> Option<u64> {
+        self.sample_age(index)
+            .map(|age| (age as u64).saturating_mul(period_us))
+    }
+
+    /// Fill a slice with the age, in samples, of every drained sample, in buffer order.
+    ///
+    /// ### Arguments
+    /// - `out`: The destination, parallel to the drain buffer.
+    ///
+    /// ### Returns
+    /// - `usize`: The number of ages written, at most [`Self::samples`].
+    pub fn sample_ages(&self, out: &mut [u8]) -> usize {
+        let mut count = 0;
+        for (index, slot) in out.iter_mut().enumerate() {
+            let Some(age) = self.sample_age(index) else {
+                break;
+            };
+            *slot = age;
+            count += 1;
+        }
+        count
+    }
+}
+
+#[cfg(feature = "fifo")]
+impl From<FifoSamples> for FifoFlags {
+    fn from(reg: FifoSamples) -> Self {
+        FifoFlags {
+            level: reg.diff(),
+            wtm: reg.fifo_fth() == 1,
+            ovr: reg.fifo_ovr() == 1,
+            full: reg.diff() as usize >= FIFO_DEPTH,
+        }
+    }
+}
+
+#[cfg(feature = "fifo")]
+impl FifoIntegrity {
+    /// Classify a drain from the `fifo_ovr` flag read before and after it.
+    pub(crate) fn from_flags(before: u8, after: u8) -> Self {
+        match (before, after) {
+            (_, 1) => FifoIntegrity::Compromised,
+            (1, _) => FifoIntegrity::Overrun,
+            _ => FifoIntegrity::Intact,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Interrupt pads configuration in a single call.
+//!
+//! [`InterruptConfig`] gathers the routing of both pads and their electrical settings, which the
+//! individual setters split over `CTRL3`, `CTRL4_INT1_PAD_CTRL`, `CTRL5_INT2_PAD_CTRL` and `CTRL7`;
+//! [`crate::Iis2dlpc::interrupt_config_set`] writes them together and derives the
+//! `interrupts_enable` field from the routing:
+//!
+//! ```ignore
+//! sensor.interrupt_config_set(&InterruptConfig {
+//!     int1_routes: Ctrl4Int1PadCtrl::new().with_int1_wu(1),
+//!     int2_routes: Ctrl5Int2PadCtrl::new().with_int2_sleep_state(1),
+//!     latch: Lir::Latched,
+//!     ..Default::default()
+//! })?;
+//! ```
+//!
+//! The embedded functions signal on INT1 only, except the sleep change and state which signal on
+//! INT2 only; [`InterruptConfig::int2_on_int1`] also brings every INT2 signal to INT1.
+
+use crate::prelude::*;
+use crate::{PROPERTY_ENABLE, interrupts_enable_for};
+
+/// Interrupt pads settings written by [`crate::Iis2dlpc::interrupt_config_set`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct InterruptConfig {
+    /// Signals routed on the INT1 pad, `CTRL4_INT1_PAD_CTRL`.
+    pub int1_routes: Ctrl4Int1PadCtrl,
+    /// Signals routed on the INT2 pad, `CTRL5_INT2_PAD_CTRL`.
+    pub int2_routes: Ctrl5Int2PadCtrl,
+    /// Signals of the INT2 pad also routed on the INT1 pad, `int2_on_int1` in `CTRL7`.
+    pub int2_on_int1: bool,
+    /// Latched or pulsed interrupts, `lir` in `CTRL3`.
+    pub latch: Lir,
+    /// Polarity of the interrupt pads, `h_lactive` in `CTRL3`.
+    pub polarity: HLactive,
+    /// Push-pull or open-drain pads, `pp_od` in `CTRL3`.
+    pub pin_mode: PpOd,
+}
+
+impl InterruptConfig {
+    /// Get whether the routing needs the `interrupts_enable` field of `CTRL7`: an embedded function
+    /// event is routed on either pad. The data-ready and FIFO signals do not depend on it.
+    pub fn interrupts_enable(&self) -> bool {
+        interrupts_enable_for(&self.int1_routes, &self.int2_routes) == PROPERTY_ENABLE
+    }
+}
@@ -1,4 +1,10 @@
 #![no_std]
+#![deny(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::panic
+)]
 #![doc = include_str!("../README.md")]
 
 use core::fmt::Debug;
@@ -7,26 +13,652 @@ use embedded_hal::i2c::{I2c, SevenBitAddress};
 use embedded_hal::spi::SpiDevice;
 use st_mems_bus::BusOperation;
 
+use blob::{BlobError, ConfigBlob};
+use capture::{CaptureSession, CaptureStats};
+use config::Config;
+use errata::Workarounds;
+#[cfg(feature = "storm-guard")]
+use event::StormGuard;
+#[cfg(feature = "events")]
+use event::{EventCounters, PendingEvents, TapPolicy};
+use fifo::DataPath;
+use phase::Phase;
 use prelude::*;
-
+use remap::AxisRemap;
+use sample::{Sample, TempPolicy};
+use selftest::{SelfTestConfig, SelfTestReport};
+use settle::SettleHint;
+use snapshot::{ApplyError, CONFIG_REGS, ConfigDelta, ConfigSnapshot, RegList};
+use timing::{DurationField, FieldRetune, RetuneReport};
+#[cfg(feature = "hooks")]
+use transaction::TransactionHooks;
+use transaction::{BusPrelude, TransactionInfo, TransactionKind};
+use wait::WaitStrategy;
+
+#[cfg(feature = "wakeup")]
+pub mod activity;
+#[cfg(feature = "wakeup")]
+pub mod adaptive;
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod batch;
+pub mod blob;
+#[cfg(feature = "test-utils")]
+pub mod bus_contract;
+pub mod capture;
+pub mod config;
+#[cfg(feature = "console")]
+pub mod console;
+mod driver;
+#[cfg(feature = "dsp")]
+pub mod dsp;
+pub mod errata;
+#[cfg(feature = "events")]
+pub mod event;
+pub mod fifo;
+#[cfg(feature = "events")]
+pub mod interrupt;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod mux;
+pub mod noise;
+pub mod pair;
+pub mod phase;
 pub mod prelude;
 pub mod register;
+pub mod remap;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod sample;
+pub mod selftest;
+pub mod settle;
+pub mod snapshot;
+pub mod spi3wire;
+#[cfg(all(feature = "async", feature = "events"))]
+pub mod stream;
+pub mod summary;
+#[cfg(feature = "tap")]
+pub mod tap;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+pub mod timing;
+pub mod transaction;
+pub mod wait;
+
+#[cfg(all(test, not(feature = "bit_order_msb")))]
+mod tests;
 
 /// The Iis2dlpc generic driver struct.
-pub struct Iis2dlpc<B, T> {
+#[derive(Debug)]
+pub struct Iis2dlpc<B: BusOperation, T> {
     /// The bus driver.
     pub bus: B,
     pub tim: T,
+    state: DriverState,
+    bus_prelude: Option<BusPrelude<B>>,
 }
 
-/// Driver errors.
+/// Driver-side settings, register copies and counters, kept across bus accesses.
+///
+/// The blocking and the async drivers share this state, so that the conversions between them carry it over
+/// unchanged, see [`Iis2dlpc::into_async`].
 #[derive(Debug)]
+struct DriverState {
+    duration_tolerance: u8,
+    odr_calibration_ppm: i32,
+    #[cfg(feature = "events")]
+    tap_policy: TapPolicy,
+    #[cfg(feature = "events")]
+    event_counters: EventCounters,
+    temp_policy: TempPolicy,
+    ctrl1_odr: Option<u8>,
+    brownout_recovery: Option<ConfigSnapshot>,
+    #[cfg(feature = "hooks")]
+    hooks: TransactionHooks,
+    capture: Option<CaptureSession>,
+    #[cfg(feature = "events")]
+    int1_shadow: RouteShadow<Ctrl4Int1PadCtrl>,
+    #[cfg(feature = "events")]
+    int2_shadow: RouteShadow<Ctrl5Int2PadCtrl>,
+    data_path: DataPath,
+    mixed_data_paths: bool,
+    workarounds: Workarounds,
+    scaling_lock: Option<ScalingLock>,
+    config_epoch: u32,
+    #[cfg(feature = "storm-guard")]
+    storm_guard: StormGuard,
+    #[cfg(feature = "events")]
+    pending_events: PendingEvents,
+    axis_remap: AxisRemap,
+    decode_retry: DecodeRetry,
+    transient_decode_errors: u32,
+    #[cfg(feature = "events")]
+    quiesced: Option<QuiesceGuard>,
+    oneshot_max_polls: u16,
+}
+
+/// Driver errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<B> {
-    Bus(B),          // Error at the bus level
-    WhoAmIError(u8), // Incorrect Iis2dlpc identifier
-    UnexpectedValue, // Unexpected value read from a register
+    Bus(B),                                        // Error at the bus level
+    WhoAmIError(u8),                               // Incorrect Iis2dlpc identifier
+    UnexpectedValue(u8),                           // Unexpected value read from a register, as read
+    OutOfRange { requested: u32, max: u32 },       // Requested value above the largest accepted
+    Inexact { requested: u32, nearest: u32 },      // Requested value too far from the nearest code
+    InvalidArgument,                               // Argument not a number or not an accepted value
+    InvalidConfig(ConfigError),                    // Not allowed by the current configuration
+    FifoUnderrun { available: u8, requested: u8 }, // Fewer samples stored than requested
+    Timeout,         // Device not ready within the bounded number of polls
+    ScalingLocked,   // Scaling change refused by the strict scaling lock
+    Blob(BlobError), // Configuration blob rejected, see blob::ConfigBlob::decode
+}
+
+/// Configuration problems reported through [`Error::InvalidConfig`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// The operation needs a free-running output data rate, but the accelerometer is off or triggered on demand.
+    DataRateNotRunning,
+    /// A single data conversion mode is combined with a free-running data rate.
+    SingleModeWithContinuousOdr { mode: Mode, odr: Odr },
+    /// A continuous mode is combined with an on-demand (triggered) data rate.
+    ContinuousModeWithTriggeredOdr { mode: Mode, odr: Odr },
+    /// The 1.6 Hz data rate is only available in the low-power modes.
+    HighPerformanceWithLowPowerOnlyOdr { mode: Mode, odr: Odr },
+    /// The data rates above 200 Hz are only available in the high-performance mode: the low-power modes run at
+    /// 200 Hz instead.
+    LowPowerWithHighPerformanceOnlyOdr { mode: Mode, odr: Odr },
+    /// The operation needs a single data conversion mode and a data rate other than `Off`.
+    OnDemandRequired,
+    /// The operation needs a pin-triggered capture session, see [`Iis2dlpc::triggered_capture_begin`].
+    CaptureNotActive,
+    /// The temperature is unused, see [`Iis2dlpc::temp_policy_set`].
+    TemperatureIgnored,
+    /// No filter bandwidth settles within the requested time at the current data rate; `min_ms` is the fastest.
+    SettleTimeUnreachable { min_ms: u32 },
+    /// The FIFO collects samples, so a direct read would pop one; see [`Iis2dlpc::allow_mixed_data_paths`].
+    FifoPathActive,
+    /// The register is not a configuration register, see [`phase::REG_PHASES`].
+    NotConfigRegister { reg: Reg },
+}
+
+/// Retry policy of the getters decoding a register field into an enum, see [`Iis2dlpc::decode_retry_set`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum DecodeRetry {
+    /// Return [`Error::UnexpectedValue`] on the first reserved code.
+    Never,
+    /// Read the register a second time before returning [`Error::UnexpectedValue`] (default).
+    #[default]
+    Once,
+}
+
+/// Outcome of [`Iis2dlpc::bus_sanity_check`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BusSanity {
+    /// The device answers with its identifier.
+    Ok,
+    /// Every bit reads `1`: data line stuck high, pulled up with no device driving it.
+    StuckHigh,
+    /// Every bit reads `0`: data line stuck low or shorted.
+    StuckLow,
+}
+
+impl BusSanity {
+    /// Classify the bus from the values read in the `WHO_AM_I` and `CTRL2` registers.
+    fn classify<E>(id: u8, ctrl2: u8) -> Result<Self, Error<E>> {
+        match (id, ctrl2) {
+            (ID, _) => Ok(Self::Ok),
+            (0xFF, 0xFF) => Ok(Self::StuckHigh),
+            (0x00, 0x00) => Ok(Self::StuckLow),
+            (id, _) => Err(Error::WhoAmIError(id)),
+        }
+    }
+
+    /// Report a stuck bus with the value every register reads.
+    fn require<E>(self) -> Result<(), Error<E>> {
+        match self {
+            Self::Ok => Ok(()),
+            Self::StuckHigh => Err(Error::UnexpectedValue(0xFF)),
+            Self::StuckLow => Err(Error::UnexpectedValue(0x00)),
+        }
+    }
+}
+
+/// Check that an operating mode and a data rate can be used together.
+///
+/// [`Odr::Off`] is consistent with every mode: to switch between a continuous and a single data conversion
+/// configuration, power down first, then set the mode and the new data rate, or use
+/// [`Iis2dlpc::odr_mode_set`]. The device would run the rates reserved to one kind of mode at another rate:
+/// 1.6 Hz in the high-performance mode at 12.5 Hz, 400 Hz to 1.6 kHz in the low-power modes at 200 Hz.
+///
+/// [`Iis2dlpc::power_mode_set`], [`Iis2dlpc::data_rate_set`] and [`Iis2dlpc::odr_mode_set`] refuse an invalid
+/// pair with the error of this function; [`Iis2dlpc::config_consistency_check`] and [`Iis2dlpc::apply_config`]
+/// report the inconsistency itself, see [`ConfigError`].
+///
+/// ### Arguments
+/// - `mode`: The operating mode.
+/// - `odr`: The data rate.
+///
+/// ### Returns
+/// - `Ok(())`: If the combination is consistent.
+/// - `Err(Error::InvalidArgument)`: If the mode cannot be used with the data rate.
+pub fn mode_odr_check<E>(mode: Mode, odr: Odr) -> Result<(), Error<E>> {
+    mode_odr_conflict(mode, odr).map_err(|_| Error::InvalidArgument)
+}
+
+/// Find the inconsistency of an operating mode and a data rate, see [`mode_odr_check`].
+fn mode_odr_conflict(mode: Mode, odr: Odr) -> Result<(), ConfigError> {
+    let single = mode.mode() == 0x02;
+    let triggered = odr.slp_mode() != 0;
+    match odr {
+        Odr::Off => Ok(()),
+        _ if single && !triggered => Err(ConfigError::SingleModeWithContinuousOdr { mode, odr }),
+        _ if !single && triggered => Err(ConfigError::ContinuousModeWithTriggeredOdr { mode, odr }),
+        Odr::_1_6hzLpOnly if mode.mode() == 0x01 => {
+            Err(ConfigError::HighPerformanceWithLowPowerOnlyOdr { mode, odr })
+        }
+        Odr::_400hz | Odr::_800hz | Odr::_1_6khz if mode.mode() != 0x01 => {
+            Err(ConfigError::LowPowerWithHighPerformanceOnlyOdr { mode, odr })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Compute the `interrupts_enable` field of `CTRL7` needed by the embedded function events routed on the pads.
+#[cfg(feature = "events")]
+fn interrupts_enable_for(ctrl4: &Ctrl4Int1PadCtrl, ctrl5: &Ctrl5Int2PadCtrl) -> u8 {
+    if (ctrl5.int2_sleep_state()
+        | ctrl5.int2_sleep_chg()
+        | ctrl4.int1_tap()
+        | ctrl4.int1_ff()
+        | ctrl4.int1_wu()
+        | ctrl4.int1_single_tap()
+        | ctrl4.int1_6d())
+        != 0
+    {
+        PROPERTY_ENABLE
+    } else {
+        PROPERTY_DISABLE
+    }
+}
+
+/// Get the byte of `target` in a transfer of `buf` starting at register `reg`, if the transfer covers it.
+fn register_in(reg: u8, buf: &[u8], target: Reg) -> Option<u8> {
+    buf.get(usize::from((target as u8).checked_sub(reg)?))
+        .copied()
+}
+
+/// Bits of `CTRL1` setting the measurement scaling: `mode` and `lp_mode`.
+const CTRL1_SCALING: u8 = Ctrl1::new().with_mode(0x3).with_lp_mode(0x3).into_bits();
+
+/// Bits of `CTRL6` setting the measurement scaling: `fs` and `low_noise`.
+const CTRL6_SCALING: u8 = Ctrl6::new().with_fs(0x3).with_low_noise(0x1).into_bits();
+
+/// Scaling configuration held by the strict scaling lock, see [`Iis2dlpc::strict_scaling_lock`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct ScalingLock {
+    ctrl1: u8,
+    ctrl6: u8,
+}
+
+impl ScalingLock {
+    /// Check whether a transfer of `buf` starting at register `reg` keeps the locked scaling.
+    ///
+    /// A software reset or a reboot restores the default scaling, so it is refused too.
+    fn allows(&self, reg: u8, buf: &[u8]) -> bool {
+        let keeps = |target, locked: u8, mask: u8| {
+            register_in(reg, buf, target).is_none_or(|val| (val ^ locked) & mask == 0)
+        };
+        let reset = register_in(reg, buf, Reg::Ctrl2)
+            .map(Ctrl2::from_bits)
+            .is_some_and(|ctrl2| ctrl2.soft_reset() | ctrl2.boot() != 0);
+        !reset
+            && keeps(Reg::Ctrl1, self.ctrl1, CTRL1_SCALING)
+            && keeps(Reg::Ctrl6, self.ctrl6, CTRL6_SCALING)
+    }
+}
+
+/// Copy the items of a slice into a `heapless::Vec`, dropping those that do not fit.
+#[cfg(feature = "heapless")]
+fn owned<T: Copy, const N: usize>(items: &[T]) -> heapless::Vec<T, N> {
+    let mut vec = heapless::Vec::new();
+    for item in items {
+        let _ = vec.push(*item);
+    }
+    vec
+}
+
+/// Check that `val` fits in a register field of `bits` bits, before any bus access.
+///
+/// The bitfield setters panic on a value too large for the field: the setters taking a raw field value call
+/// this function first and return [`Error::InvalidArgument`] instead.
+#[cfg(any(
+    feature = "fifo",
+    feature = "tap",
+    feature = "wakeup",
+    feature = "sixd",
+    feature = "freefall"
+))]
+fn field_check<E>(val: u8, bits: u32) -> Result<(), Error<E>> {
+    if u32::from(val) >> bits == 0 {
+        Ok(())
+    } else {
+        Err(Error::InvalidArgument)
+    }
 }
 
+/// Convert a duration to milliseconds, rounded to the nearest.
+#[cfg(any(feature = "wakeup", feature = "freefall"))]
+fn us_to_ms_rounded(us: u64) -> u32 {
+    ((us + 500) / 1000) as u32
+}
+
+/// Non-fatal conditions reported by some setters.
+///
+/// The write has been performed, but the resulting configuration likely needs attention.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Warning {
+    /// The wake-up function is fed with offset-corrected data, so the trigger point moved: see
+    /// [`Iis2dlpc::wakeup_effective_threshold_mg`].
+    WakeupThresholdShifted,
+    /// The wake-up function is fed through the high-pass filter with a cutoff below
+    /// [`MIN_WAKEUP_HP_CUTOFF_HZ`]: the filter takes seconds to settle and wake-up detection becomes unreliable.
+    WakeupHighPassSlow,
+    /// The free-fall function shares the activity/inactivity detection: while asleep the device runs at 12.5 Hz,
+    /// so `ff_dur` counts 80 ms periods, see [`Iis2dlpc::wake_and_ff_config_set`].
+    FreeFallDurationStretched,
+}
+
+/// Driver-side copy of a pad routing register, see [`Iis2dlpc::int1_routes_shadow`].
+#[cfg(feature = "events")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct RouteShadow<R> {
+    /// The routing last read from or written to the device.
+    pub routes: R,
+    /// `false` if the device may hold another routing: the register has not been read or written through the
+    /// driver yet, or it was written raw, or the device was reset, since.
+    pub shadow_valid: bool,
+}
+
+/// Interrupt output saved by [`Iis2dlpc::interrupts_quiesce`], to give back to [`Iis2dlpc::interrupts_restore`].
+#[cfg(feature = "events")]
+#[must_use = "the interrupt pads stay silent until the guard is given to `interrupts_restore`"]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct QuiesceGuard {
+    int1_routes: Ctrl4Int1PadCtrl,
+    int2_routes: Ctrl5Int2PadCtrl,
+    interrupts_enable: u8,
+}
+
+#[cfg(feature = "events")]
+impl QuiesceGuard {
+    /// Get the saved INT1 routing.
+    pub fn int1_routes(&self) -> Ctrl4Int1PadCtrl {
+        self.int1_routes
+    }
+
+    /// Get the saved INT2 routing.
+    pub fn int2_routes(&self) -> Ctrl5Int2PadCtrl {
+        self.int2_routes
+    }
+
+    /// Check whether the embedded function events were enabled, the `interrupts_enable` field in the `CTRL7`
+    /// register.
+    pub fn interrupts_enabled(&self) -> bool {
+        self.interrupts_enable != 0
+    }
+}
+
+impl DriverState {
+    fn new() -> Self {
+        Self {
+            duration_tolerance: DEFAULT_DURATION_TOLERANCE,
+            odr_calibration_ppm: 0,
+            #[cfg(feature = "events")]
+            tap_policy: TapPolicy::PreferDouble,
+            #[cfg(feature = "events")]
+            event_counters: EventCounters::default(),
+            temp_policy: TempPolicy::Enabled,
+            ctrl1_odr: None,
+            brownout_recovery: None,
+            #[cfg(feature = "hooks")]
+            hooks: TransactionHooks::default(),
+            capture: None,
+            #[cfg(feature = "events")]
+            int1_shadow: RouteShadow::default(),
+            #[cfg(feature = "events")]
+            int2_shadow: RouteShadow::default(),
+            data_path: DataPath::Direct,
+            mixed_data_paths: false,
+            workarounds: Workarounds::RECOMMENDED,
+            scaling_lock: None,
+            config_epoch: 0,
+            #[cfg(feature = "storm-guard")]
+            storm_guard: StormGuard::default(),
+            #[cfg(feature = "events")]
+            pending_events: PendingEvents::default(),
+            axis_remap: AxisRemap::IDENTITY,
+            decode_retry: DecodeRetry::Once,
+            transient_decode_errors: 0,
+            #[cfg(feature = "events")]
+            quiesced: None,
+            oneshot_max_polls: DEFAULT_ONESHOT_MAX_POLLS,
+        }
+    }
+
+    /// Open a bus transaction of `buf` starting at register `reg`, shared by the blocking and the async driver.
+    ///
+    /// A write updates the state first, see [`Self::write_begin`]; the `before` hook is then called.
+    ///
+    /// ### Returns
+    /// - `Ok(TransactionInfo)`: The transaction, to close with [`Self::transaction_end`].
+    /// - `Err(Error::ScalingLocked)`: If the write breaks the scaling lock; no hook is called.
+    fn transaction_begin<E>(
+        &mut self,
+        kind: TransactionKind,
+        reg: u8,
+        buf: &[u8],
+    ) -> Result<TransactionInfo, Error<E>> {
+        if kind == TransactionKind::Write && !self.write_begin(reg, buf) {
+            return Err(Error::ScalingLocked);
+        }
+        let info = TransactionInfo {
+            kind,
+            reg,
+            len: buf.len(),
+        };
+        #[cfg(feature = "hooks")]
+        if let Some(before) = self.hooks.before {
+            before(&info);
+        }
+        Ok(info)
+    }
+
+    /// Close a bus transaction opened with [`Self::transaction_begin`], given the outcome of the bus access.
+    ///
+    /// The `after` hook is called whether the access succeeded or not; a successful read then reloads the
+    /// register copies, see [`Self::read_done`].
+    fn transaction_end<E>(
+        &mut self,
+        info: &TransactionInfo,
+        buf: &[u8],
+        result: Result<(), E>,
+    ) -> Result<(), Error<E>> {
+        #[cfg(feature = "hooks")]
+        if let Some(after) = self.hooks.after {
+            after(info);
+        }
+        result.map_err(Error::Bus)?;
+        if info.kind == TransactionKind::Read {
+            self.read_done(info.reg, buf);
+        }
+        Ok(())
+    }
+
+    /// Update the state before a write of `buf` starting at register `reg`.
+    ///
+    /// Returns `false`, leaving the state unchanged, if the write breaks the scaling lock, see
+    /// [`Iis2dlpc::strict_scaling_lock`].
+    fn write_begin(&mut self, reg: u8, buf: &[u8]) -> bool {
+        if self.scaling_lock.is_some_and(|lock| !lock.allows(reg, buf)) {
+            return false;
+        }
+        // Written values are not trusted: the route setters validate the shadows again once done
+        let reset = register_in(reg, buf, Reg::Ctrl2)
+            .map(Ctrl2::from_bits)
+            .is_some_and(|ctrl2| ctrl2.soft_reset() | ctrl2.boot() != 0);
+        if reset {
+            self.invalidate_cache();
+            self.data_path = DataPath::Direct;
+        }
+        if let Some(val) = register_in(reg, buf, Reg::Ctrl1) {
+            self.ctrl1_odr = Some(Ctrl1::from_bits(val).odr());
+        }
+        if register_in(reg, buf, Reg::Ctrl2)
+            .is_some_and(|val| Ctrl2::from_bits(val).soft_reset() != 0)
+        {
+            self.ctrl1_odr = Some(0);
+        }
+        if let Some(val) = register_in(reg, buf, Reg::FifoCtrl) {
+            self.data_path = if FifoCtrl::from_bits(val).fmode() == Fmode::BypassMode as u8 {
+                DataPath::Direct
+            } else {
+                DataPath::Fifo
+            };
+        }
+        #[cfg(feature = "events")]
+        if register_in(reg, buf, Reg::Ctrl4Int1PadCtrl).is_some() {
+            self.int1_shadow.shadow_valid = false;
+        }
+        #[cfg(feature = "events")]
+        if register_in(reg, buf, Reg::Ctrl5Int2PadCtrl).is_some() {
+            self.int2_shadow.shadow_valid = false;
+        }
+        true
+    }
+
+    /// Reload the register copies covered by a successful read of `buf` starting at register `reg`.
+    fn read_done(&mut self, reg: u8, buf: &[u8]) {
+        if let Some(val) = register_in(reg, buf, Reg::Ctrl1) {
+            self.ctrl1_odr = Some(Ctrl1::from_bits(val).odr());
+        }
+        #[cfg(feature = "events")]
+        if let Some(val) = register_in(reg, buf, Reg::Ctrl4Int1PadCtrl) {
+            self.int1_shadow = RouteShadow {
+                routes: Ctrl4Int1PadCtrl::from_bits(val),
+                shadow_valid: true,
+            };
+        }
+        #[cfg(feature = "events")]
+        if let Some(val) = register_in(reg, buf, Reg::Ctrl5Int2PadCtrl) {
+            self.int2_shadow = RouteShadow {
+                routes: Ctrl5Int2PadCtrl::from_bits(val),
+                shadow_valid: true,
+            };
+        }
+    }
+
+    /// Update the state once every register of `snapshot` is written.
+    fn snapshot_applied(&mut self, snapshot: &ConfigSnapshot) {
+        self.ctrl1_odr = Some(snapshot.data_rate().odr());
+        #[cfg(feature = "events")]
+        if let Some(val) = snapshot.get(Reg::Ctrl4Int1PadCtrl) {
+            self.int1_shadow = RouteShadow {
+                routes: Ctrl4Int1PadCtrl::from_bits(ConfigSnapshot::restore_value(
+                    Reg::Ctrl4Int1PadCtrl,
+                    val,
+                )),
+                shadow_valid: true,
+            };
+        }
+        #[cfg(feature = "events")]
+        if let Some(val) = snapshot.get(Reg::Ctrl5Int2PadCtrl) {
+            self.int2_shadow = RouteShadow {
+                routes: Ctrl5Int2PadCtrl::from_bits(ConfigSnapshot::restore_value(
+                    Reg::Ctrl5Int2PadCtrl,
+                    val,
+                )),
+                shadow_valid: true,
+            };
+        }
+    }
+
+    fn invalidate_cache(&mut self) {
+        #[cfg(feature = "events")]
+        {
+            self.int1_shadow.shadow_valid = false;
+            self.int2_shadow.shadow_valid = false;
+        }
+    }
+
+    /// Whether the `odr` field of `CTRL1` last written or read through the driver is other than power-down; `None`
+    /// until the register goes through the driver.
+    fn odr_running(&self) -> Option<bool> {
+        self.ctrl1_odr.map(|odr| odr != 0)
+    }
+
+    /// Check that the output registers can be read directly, see [`Iis2dlpc::allow_mixed_data_paths`].
+    fn direct_path_check<E>(&self) -> Result<(), Error<E>> {
+        match (self.data_path, self.mixed_data_paths) {
+            (DataPath::Fifo, false) => Err(Error::InvalidConfig(ConfigError::FifoPathActive)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Acceleration levels, in mg, at which the wake-up function fires on one axis.
+#[cfg(feature = "wakeup")]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WakeupLevel {
+    /// The function fires when the acceleration rises above this level.
+    pub above_mg: f32,
+    /// The function fires when the acceleration falls below this level.
+    pub below_mg: f32,
+}
+
+#[cfg(feature = "wakeup")]
+impl WakeupLevel {
+    /// Compute the levels of a wake-up threshold, without a bus access.
+    ///
+    /// ### Arguments
+    /// - `wk_ths`: The content of the `wk_ths` field, 1 LSB = FS / 64.
+    /// - `fs`: The full scale.
+    /// - `offset_mg`: The user offset the levels are centered on, `0.0` unless the wake-up function is fed with
+    ///   [`UsrOffOnWu::UserOffsetFeed`].
+    pub fn new(wk_ths: u8, fs: Fs, offset_mg: f32) -> Self {
+        let ths_mg = wk_ths as f32 * fs.wkup_threshold_mg_per_lsb();
+        Self {
+            above_mg: offset_mg + ths_mg,
+            below_mg: offset_mg - ths_mg,
+        }
+    }
+}
+
+/// Lowest high-pass cutoff, in Hz, that keeps the wake-up function responsive.
+#[cfg(feature = "wakeup")]
+pub const MIN_WAKEUP_HP_CUTOFF_HZ: f32 = 1.0;
+
+/// Default relative error, in percent, accepted by the millisecond-based duration setters.
+pub const DEFAULT_DURATION_TOLERANCE: u8 = 10;
+
+/// Interval between two data-ready polls of [`Iis2dlpc::acceleration_oneshot_get`], in microseconds.
+pub const ONESHOT_POLL_US: u32 = 100;
+
+/// Default number of data-ready polls of [`Iis2dlpc::acceleration_oneshot_get`], 10 ms in all.
+pub const DEFAULT_ONESHOT_MAX_POLLS: u16 = 100;
+
+/// Interval between two polls of the end of the software reset in [`Iis2dlpc::init`], in microseconds.
+pub const RESET_POLL_US: u32 = 10;
+
+/// Number of polls of the end of the software reset in [`Iis2dlpc::init`], 1 ms in all.
+pub const RESET_MAX_POLLS: u16 = 100;
+
+/// Largest data rate correction, in parts per million, accepted by [`Iis2dlpc::odr_calibration_set`].
+///
+/// The internal oscillator is specified within ±10%; a larger deviation points to a wrong measurement.
+pub const MAX_ODR_CALIBRATION_PPM: u32 = 200_000;
+
 impl<P, T> Iis2dlpc<st_mems_bus::i2c::I2cBus<P>, T>
 where
     P: I2c,
@@ -34,6 +666,10 @@ where
 {
     /// Constructor method for using the I2C bus.
     ///
+    /// The driver owns `i2c` for its lifetime, but `P` can be a borrow, `&mut I2C`, for which embedded-hal
+    /// implements [`I2c`], or a bus-sharing device such as the `RefCellDevice` and `CriticalSectionDevice` of
+    /// `embedded-hal-bus`, so that several drivers use the same peripheral, each with its own address.
+    ///
     /// # Arguments
     ///
     /// * `i2c`: The I2C peripheral.
@@ -47,7 +683,7 @@ where
     pub fn new_i2c(i2c: P, address: I2CAddress, tim: T) -> Self {
         // Initialize the I2C bus with the Iis2dlpc address
         let bus = st_mems_bus::i2c::I2cBus::new(i2c, address as SevenBitAddress);
-        Self { bus, tim }
+        Self::from_bus(bus, tim)
     }
 }
 
@@ -58,6 +694,9 @@ where
 {
     /// Constructor method for using the SPI bus.
     ///
+    /// As for [`Iis2dlpc::new_i2c`], `P` can be a borrow, `&mut SPI`, or a device of `embedded-hal-bus` sharing
+    /// the bus with other chip selects.
+    ///
     /// # Arguments
     ///
     /// * `spi`: The SPI peripheral.
@@ -70,32 +709,508 @@ where
     pub fn new_spi(spi: P, tim: T) -> Self {
         // Initialize the SPI bus
         let bus = st_mems_bus::spi::SpiBus::new(spi);
-        Self { bus, tim }
+        Self::from_bus(bus, tim)
+    }
+}
+
+impl<P, T> Iis2dlpc<spi3wire::Spi3WireBus<P>, T>
+where
+    P: SpiDevice,
+    T: DelayNs,
+{
+    /// Constructor method for using the SPI bus in the 3-wire mode.
+    ///
+    /// The device only answers on the shared data line once `sim` is set in `CTRL2`: the bus writes it before the
+    /// first register read, so that `device_id_get` or `init` can follow the construction directly, and again after a
+    /// software reset. See [`spi3wire`] for the ordering and the configuration of the SPI peripheral.
+    ///
+    /// # Arguments
+    ///
+    /// * `spi`: The SPI peripheral, configured for a bidirectional data line.
+    ///
+    /// # Returns
+    ///
+    /// * `Self`: Returns an instance of `Iis2dlpc`.
+    pub fn new_spi_3wire(spi: P, tim: T) -> Self {
+        Self::from_bus(spi3wire::Spi3WireBus::new(spi), tim)
+    }
+}
+
+/// Read the device identifier over I2C without constructing the driver.
+///
+/// This function performs exactly one bus transaction, a write of the `WHO_AM_I` address followed by a
+/// repeated-start read of one byte, through a borrow of `i2c`: the caller keeps the peripheral, can retry
+/// until the device answers and construct the driver with [`Iis2dlpc::new_i2c`] afterwards.
+///
+/// ### Arguments
+/// - `i2c`: The I2C peripheral.
+/// - `addr`: The I2C address of the device.
+///
+/// ### Returns
+/// - `Ok(u8)`: The content of `WHO_AM_I`, [`ID`] for an IIS2DLPC.
+/// - `Err(P::Error)`: If there is an error at the bus level.
+pub fn probe_who_am_i<P: I2c>(i2c: &mut P, addr: I2CAddress) -> Result<u8, P::Error> {
+    let mut bus = st_mems_bus::i2c::I2cBus::new(i2c, addr as SevenBitAddress);
+    let mut buf = [0];
+    bus.read_from_register(Reg::WhoAmI as u8, &mut buf)?;
+    Ok(buf[0])
+}
+
+/// Read the device identifier over SPI without constructing the driver.
+///
+/// This function is the SPI counterpart of [`probe_who_am_i`]: it performs exactly one SPI transaction, the
+/// `WHO_AM_I` address with the read bit set followed by a one byte read, through a borrow of `spi`.
+///
+/// ### Arguments
+/// - `spi`: The SPI device.
+///
+/// ### Returns
+/// - `Ok(u8)`: The content of `WHO_AM_I`, [`ID`] for an IIS2DLPC.
+/// - `Err(P::Error)`: If there is an error at the bus level.
+pub fn probe_who_am_i_spi<P: SpiDevice>(spi: &mut P) -> Result<u8, P::Error> {
+    let mut bus = st_mems_bus::spi::SpiBus::new(spi);
+    let mut buf = [0];
+    bus.read_from_register(Reg::WhoAmI as u8, &mut buf)?;
+    Ok(buf[0])
+}
+
+impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
+    /// # Arguments
+    ///
+    /// * `bus`: The bus that implements BusOperation.
+    /// * `tim`: The timer of the COMPONENT sensor.
+    ///
+    /// # Returns
+    ///
+    /// * `Self`: Returns an instance of `Iis2mdc`.
+    #[inline]
+    pub fn from_bus(bus: B, tim: T) -> Self {
+        Self {
+            bus,
+            tim,
+            state: DriverState::new(),
+            bus_prelude: None,
+        }
+    }
+
+    /// Convert into the async driver, keeping the driver-side state.
+    ///
+    /// The settings, the register copies, the counters and the hooks carry over unchanged, so that
+    /// a configuration applied by a blocking stage, e.g. a bootloader, goes on with the async driver. See
+    /// [`asynch::Iis2dlpcAsync::into_blocking`] for the way back. The bus prelude, typed on the blocking bus, is
+    /// dropped, see [`Self::bus_prelude_set`]: a bus selecting its multiplexer channel by itself, as
+    /// [`mux::MuxedI2cBus`] does, keeps the channel across the conversion.
+    ///
+    /// ### Arguments
+    /// - `f`: The conversion of the bus, e.g. wrapping the async peripheral of the same device.
+    ///
+    /// ### Returns
+    /// - `Iis2dlpcAsync<BA, T>`: The async driver, with the same timer.
+    #[cfg(feature = "async")]
+    pub fn into_async<BA: asynch::AsyncBusOperation>(
+        self,
+        f: impl FnOnce(B) -> BA,
+    ) -> asynch::Iis2dlpcAsync<BA, T> {
+        asynch::Iis2dlpcAsync::from_parts(f(self.bus), self.tim, self.state)
+    }
+
+    /// Power the device down and give back the bus and the timer.
+    ///
+    /// This function sets the data rate to [`Odr::Off`], see [`Self::data_rate_set`], then consumes the driver, so
+    /// that the bus can serve another device or a new driver, e.g. with another address after a hardware reset.
+    /// The peripheral stays in the bus of `new_i2c` and `new_spi`: build the driver on a borrow, `&mut i2c`, to
+    /// keep it, see [`Iis2dlpc::new_i2c`].
+    ///
+    /// ### Returns
+    /// - `(B, T, Result<(), Error>)`: The bus, the timer and the outcome of the power-down, as returned by
+    ///   `data_rate_set`; on an error the device may still be converting.
+    pub fn release(mut self) -> (B, T, Result<(), Error<B::Error>>) {
+        let powered_down = self.data_rate_set(Odr::Off).map(|_| ());
+        let (bus, tim) = self.release_without_powerdown();
+        (bus, tim, powered_down)
+    }
+
+    /// Give back the bus and the timer, leaving the device as configured.
+    ///
+    /// This function consumes the driver without a bus transaction: the device keeps converting at the
+    /// configured data rate, see [`Self::release`] for a power-down first.
+    ///
+    /// ### Returns
+    /// - `(B, T)`: The bus and the timer.
+    pub fn release_without_powerdown(self) -> (B, T) {
+        (self.bus, self.tim)
+    }
+
+    #[inline]
+    pub fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Error<B::Error>> {
+        let info = self
+            .state
+            .transaction_begin(TransactionKind::Read, reg, buf)?;
+        let result = self.transaction(|bus| bus.read_from_register(reg, buf));
+        self.state.transaction_end(&info, buf, result)
+    }
+
+    #[inline]
+    pub fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Error<B::Error>> {
+        let info = self
+            .state
+            .transaction_begin(TransactionKind::Write, reg, buf)?;
+        let result = self.transaction(|bus| bus.write_to_register(reg, buf));
+        self.state.transaction_end(&info, buf, result)
+    }
+
+    /// Get the path of the acceleration samples.
+    ///
+    /// The path follows the writes of the `fmode` field in the `FIFO_CTRL` register through the driver, see the
+    /// [`fifo`] module: any mode other than bypass selects [`DataPath::Fifo`]. A software reset selects
+    /// [`DataPath::Direct`].
+    ///
+    /// ### Returns
+    /// - `DataPath`: The current path.
+    pub fn data_path_get(&self) -> DataPath {
+        self.state.data_path
+    }
+
+    /// Allow or forbid the direct reads of the output registers while the FIFO collects samples.
+    ///
+    /// By default [`Self::acceleration_raw_get`], the functions built on it and `Self::telemetry_frame` return
+    /// [`ConfigError::FifoPathActive`] on the [`DataPath::Fifo`] path, since each of their reads pops a FIFO
+    /// sample; [`Self::acceleration_raw_unchecked_get`] reads on both paths.
+    ///
+    /// ### Arguments
+    /// - `allow`: `true` to read the output registers on both paths.
+    pub fn allow_mixed_data_paths(&mut self, allow: bool) {
+        self.state.mixed_data_paths = allow;
+    }
+
+    /// Set the FIFO mode.
+    ///
+    /// This function configures the FIFO operating mode by updating the `fmode` field in the `FIFO_CTRL` register.
+    /// The FIFO mode determines how data is managed in the FIFO buffer.
+    ///
+    /// ### Arguments
+    /// - `val`: A [`Fmode`] value representing the desired FIFO mode:
+    ///   - `BypassMode`: FIFO is disabled (default).
+    ///   - `FifoMode`: FIFO stops collecting data when full.
+    ///   - `StreamToFifoMode`: Stream mode until a trigger event, then FIFO mode.
+    ///   - `BypassToStreamMode`: Bypass mode until a trigger event, then stream mode.
+    ///   - `StreamMode`: Continuously updates FIFO, overwriting old data when full.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn fifo_mode_set(&mut self, val: Fmode) -> Result<(), Error<B::Error>> {
+        let mut reg = FifoCtrl::read(self)?;
+        reg.set_fmode(val as u8);
+        reg.write(self)
+    }
+
+    /// Get the FIFO mode.
+    ///
+    /// This function retrieves the current FIFO operating mode from the `fmode` field in the `FIFO_CTRL` register.
+    ///
+    /// ### Returns
+    /// - `Ok(Fmode)`: The current FIFO mode as a [`Fmode`] value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn fifo_mode_get(&mut self) -> Result<Fmode, Error<B::Error>> {
+        Ok(Fmode::try_from(FifoCtrl::read(self)?.fmode()).unwrap_or_default())
+    }
+
+    /// Flush the FIFO.
+    ///
+    /// This function sets the `fmode` field in the `FIFO_CTRL` register to bypass mode, which discards the stored
+    /// samples and clears the overrun and threshold flags, then writes the previous FIFO mode back: the FIFO
+    /// collects again from empty, with the same watermark. This is the recovery from an overrun, see
+    /// [`Self::fifo_flags_get`] and the `auto_recover` argument of [`Self::fifo_drain`].
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn fifo_flush(&mut self) -> Result<(), Error<B::Error>> {
+        let mode = self.fifo_mode_get()?;
+        self.fifo_mode_set(Fmode::BypassMode)?;
+        self.fifo_mode_set(mode)
+    }
+
+    /// Select the software workarounds applied by the driver.
+    ///
+    /// The driver starts with [`Workarounds::RECOMMENDED`]; see [`errata`] for the symptom behind every flag.
+    ///
+    /// ### Arguments
+    /// - `val`: The workarounds to apply.
+    pub fn workarounds_set(&mut self, val: Workarounds) {
+        self.state.workarounds = val;
+    }
+
+    /// Get the software workarounds applied by the driver.
+    ///
+    /// ### Returns
+    /// - `Workarounds`: The current set, see [`Self::workarounds_set`].
+    pub fn workarounds_get(&self) -> Workarounds {
+        self.state.workarounds
+    }
+
+    /// Select how the getters handle a reserved code in an enum field.
+    ///
+    /// A single corrupted bit on a long bus line can turn a field into a reserved code. With
+    /// [`DecodeRetry::Once`], the default, a getter reading such a code reads the register once more and only
+    /// returns [`Error::UnexpectedValue`] if the second read is reserved too; a recovered read is counted, see
+    /// [`Self::transient_decode_errors`]. The retry only applies to the getters: a read-modify-write cycle writes
+    /// back the field as read.
+    ///
+    /// ### Arguments
+    /// - `val`: The retry policy.
+    pub fn decode_retry_set(&mut self, val: DecodeRetry) {
+        self.state.decode_retry = val;
+    }
+
+    /// Get how the getters handle a reserved code in an enum field.
+    ///
+    /// ### Returns
+    /// - `DecodeRetry`: The current policy, see [`Self::decode_retry_set`].
+    pub fn decode_retry_get(&self) -> DecodeRetry {
+        self.state.decode_retry
+    }
+
+    /// Get the number of reads recovered by a retry.
+    ///
+    /// The counter covers every getter read decoded at the second attempt, see [`Self::decode_retry_set`], since the
+    /// driver creation or the last [`Self::transient_decode_errors_reset`]. No bus access is performed.
+    ///
+    /// ### Returns
+    /// - `u32`: The number of recovered reads, saturating.
+    pub fn transient_decode_errors(&self) -> u32 {
+        self.state.transient_decode_errors
+    }
+
+    /// Clear the number of reads recovered by a retry.
+    pub fn transient_decode_errors_reset(&mut self) {
+        self.state.transient_decode_errors = 0;
+    }
+
+    /// Read a register field and decode it into an enum, with the retry policy of [`Self::decode_retry_set`].
+    ///
+    /// Only the getters use this helper: it returns the decoded value, never the register, so that a
+    /// read-modify-write cycle cannot write back a value read twice.
+    fn read_decoded<E: TryFrom<u8>>(
+        &mut self,
+        field: impl Fn(&mut Self) -> Result<u8, Error<B::Error>>,
+    ) -> Result<E, Error<B::Error>> {
+        self.read_decoded_with(field, |raw| E::try_from(raw).ok())
+    }
+
+    /// Read a register field and decode it with `decode`, as [`Self::read_decoded`] does.
+    ///
+    /// The error of a field that does not decode twice holds the value of the second read.
+    fn read_decoded_with<E>(
+        &mut self,
+        field: impl Fn(&mut Self) -> Result<u8, Error<B::Error>>,
+        decode: impl Fn(u8) -> Option<E>,
+    ) -> Result<E, Error<B::Error>> {
+        let raw = field(self)?;
+        if let Some(val) = decode(raw) {
+            return Ok(val);
+        }
+        if self.state.decode_retry == DecodeRetry::Never {
+            return Err(Error::UnexpectedValue(raw));
+        }
+        let raw = field(self)?;
+        let val = decode(raw).ok_or(Error::UnexpectedValue(raw))?;
+        self.state.transient_decode_errors = self.state.transient_decode_errors.saturating_add(1);
+        Ok(val)
+    }
+
+    /// Get the data period of the running data stream, the one settling and workaround waits are counted in.
+    fn running_period_us(&mut self) -> Result<Option<u32>, Error<B::Error>> {
+        if self.state.odr_running() == Some(false) {
+            return Ok(None);
+        }
+        Ok(self.data_rate_get()?.period_us())
+    }
+
+    /// Get the hint of a change applied from the next conversion, see [`settle::PENDING_SAMPLES`].
+    fn pending_samples_hint(&mut self, changed: bool) -> Result<SettleHint, Error<B::Error>> {
+        if !changed || self.running_period_us()?.is_none() {
+            return Ok(SettleHint::None);
+        }
+        Ok(SettleHint::Samples(settle::PENDING_SAMPLES))
+    }
+
+    /// Wait for a configuration change to settle.
+    ///
+    /// This function waits as long as told by the [`SettleHint`] returned by a setter of the signal path, see
+    /// [`settle`]. A number of samples is counted at the current data rate; with the data rate off or triggered on
+    /// demand nothing is waited.
+    ///
+    /// ### Arguments
+    /// - `hint`: The hint returned by the setter.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the wait is over.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level while reading the data rate.
+    pub fn settle_for(&mut self, hint: SettleHint) -> Result<(), Error<B::Error>> {
+        let us = match hint {
+            SettleHint::Samples(n) => self
+                .running_period_us()?
+                .map_or(0, |period_us| period_us.saturating_mul(n)),
+            hint => hint.duration_us(Odr::Off),
+        };
+        if us > 0 {
+            self.tim.wait_us(us);
+        }
+        Ok(())
+    }
+
+    fn direct_path_check(&self) -> Result<(), Error<B::Error>> {
+        self.state.direct_path_check()
+    }
+
+    /// Forget the register values kept by the driver.
+    ///
+    /// The driver keeps a copy of the pad routing registers, see [`Self::int1_routes_shadow`], updated by every
+    /// access through the driver. Call this function after accessing the device through [`Self::bus`] directly
+    /// or after a reset the driver did not perform, e.g. a power cycle.
+    pub fn invalidate_cache(&mut self) {
+        self.state.invalidate_cache();
+    }
+
+    /// Lock or unlock the measurement scaling.
+    ///
+    /// While locked, every write changing the `mode` or `lp_mode` fields in the `CTRL1` register, or the `fs` or
+    /// `low_noise` fields in the `CTRL6` register, returns [`Error::ScalingLocked`] without touching the bus; so does
+    /// a software reset or a reboot. The check runs on every write of the driver, [`Self::write_to_register`] and
+    /// [`Self::config_snapshot_apply`] included, while writes keeping the locked values go through.
+    /// Use [`Self::scaling_unlock_and_apply`] to change the scaling while locked.
+    ///
+    /// ### Arguments
+    /// - `locked`: `true` to lock the current scaling, read from the device.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation; the lock is unchanged.
+    pub fn strict_scaling_lock(&mut self, locked: bool) -> Result<(), Error<B::Error>> {
+        self.state.scaling_lock = if locked {
+            Some(self.scaling_capture()?)
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Check whether the measurement scaling is locked, see [`Self::strict_scaling_lock`].
+    pub fn scaling_locked(&self) -> bool {
+        self.state.scaling_lock.is_some()
+    }
+
+    /// Change the measurement scaling while it is locked.
+    ///
+    /// This function lifts the lock of [`Self::strict_scaling_lock`], runs `f`, increments the configuration epoch,
+    /// see [`Self::config_epoch`], and locks the resulting scaling again. With `flush_fifo` and the FIFO
+    /// collecting samples, the FIFO is then flushed (bypass mode, then the previous FIFO mode) so that it holds no
+    /// sample acquired with the previous scaling. Without a lock, `f` runs the same way and no lock is set.
+    ///
+    /// ### Arguments
+    /// - `flush_fifo`: Flush the FIFO once `f` is done.
+    /// - `f`: The configuration change.
+    ///
+    /// ### Returns
+    /// - `Ok(R)`: The result of `f`.
+    /// - `Err(Error)`: The error of `f`, of the FIFO flush or of the read of the new scaling; the epoch is
+    ///   incremented anyway. If the new scaling cannot be read, the previous one stays locked.
+    pub fn scaling_unlock_and_apply<R>(
+        &mut self,
+        flush_fifo: bool,
+        f: impl FnOnce(&mut Self) -> Result<R, Error<B::Error>>,
+    ) -> Result<R, Error<B::Error>> {
+        let previous = self.state.scaling_lock.take();
+        let result = f(self);
+        self.state.config_epoch = self.state.config_epoch.wrapping_add(1);
+
+        let flushed = if flush_fifo && self.state.data_path == DataPath::Fifo {
+            self.fifo_flush()
+        } else {
+            Ok(())
+        };
+        if previous.is_some() {
+            match self.scaling_capture() {
+                Ok(lock) => self.state.scaling_lock = Some(lock),
+                Err(error) => {
+                    self.state.scaling_lock = previous;
+                    return Err(error);
+                }
+            }
+        }
+        let value = result?;
+        flushed?;
+        Ok(value)
+    }
+
+    /// Get the configuration epoch.
+    ///
+    /// The epoch starts at `0` and is incremented by every [`Self::scaling_unlock_and_apply`], wrapping around: a
+    /// consumer saving the epoch with its samples tells whether the scaling changed since. No bus access is
+    /// performed.
+    ///
+    /// ### Returns
+    /// - `u32`: The current epoch.
+    pub fn config_epoch(&self) -> u32 {
+        self.state.config_epoch
+    }
+
+    /// Read the current scaling configuration.
+    fn scaling_capture(&mut self) -> Result<ScalingLock, Error<B::Error>> {
+        Ok(ScalingLock {
+            ctrl1: Ctrl1::read(self)?.into_bits(),
+            ctrl6: Ctrl6::read(self)?.into_bits(),
+        })
+    }
+
+    /// Run a bus transaction after the bus prelude.
+    ///
+    /// Every bus access of the driver goes through this function, between [`DriverState::transaction_begin`] and
+    /// [`DriverState::transaction_end`].
+    #[inline]
+    fn transaction(
+        &mut self,
+        f: impl FnOnce(&mut B) -> Result<(), B::Error>,
+    ) -> Result<(), B::Error> {
+        if let Some(prelude) = self.bus_prelude {
+            prelude(&mut self.bus)?;
+        }
+        f(&mut self.bus)
     }
-}
 
-impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
-    /// # Arguments
+    /// Set the hooks called around every bus transaction.
     ///
-    /// * `bus`: The bus that implements BusOperation.
-    /// * `tim`: The timer of the COMPONENT sensor.
-    ///
-    /// # Returns
+    /// The hooks receive the register address, the direction and the length of each transaction,
+    /// so that an application can defer other traffic on a shared bus. See the [`transaction`] module.
     ///
-    /// * `Self`: Returns an instance of `Iis2mdc`.
-    #[inline]
-    pub fn from_bus(bus: B, tim: T) -> Self {
-        Self { bus, tim }
+    /// ### Arguments
+    /// - `hooks`: The hooks; [`TransactionHooks::default`] disables them (default).
+    #[cfg(feature = "hooks")]
+    pub fn transaction_hooks_set(&mut self, hooks: TransactionHooks) {
+        self.state.hooks = hooks;
     }
 
-    #[inline]
-    pub fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Error<B::Error>> {
-        self.bus.read_from_register(reg, buf).map_err(Error::Bus)
+    /// Set the command issued on the bus before every transaction.
+    ///
+    /// The prelude runs once per transaction of the driver, right before it and after the `before` hook, see
+    /// `Self::transaction_hooks_set`: a burst read of any length, e.g. a FIFO drain, is a single transaction and
+    /// runs it once. It is meant for an address pre-command, or the channel selection of a multiplexer without
+    /// a bus of its own; [`mux::MuxedI2cBus`] selects its channel by itself. If the prelude fails, the transaction
+    /// is skipped and its error is returned as [`Error::Bus`]; the `after` hook is still called.
+    ///
+    /// The prelude belongs to the bus type: the conversion into the async driver drops it.
+    ///
+    /// ### Arguments
+    /// - `f`: The command.
+    pub fn bus_prelude_set(&mut self, f: BusPrelude<B>) {
+        self.bus_prelude = Some(f);
     }
 
-    #[inline]
-    pub fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Error<B::Error>> {
-        self.bus.write_to_register(reg, buf).map_err(Error::Bus)
+    /// Remove the command issued on the bus before every transaction, see [`Self::bus_prelude_set`].
+    pub fn bus_prelude_clear(&mut self) {
+        self.bus_prelude = None;
     }
 
     /// Set the accelerometer operating mode.
@@ -109,18 +1224,46 @@ impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
     ///   - `lp_mode`: Low-power mode configuration.
     ///   - `low_noise`: Low-noise mode configuration.
     ///
+    /// The mode is checked against the current data rate with [`mode_odr_check`]: a low-power mode is refused
+    /// while the data rate is above 200 Hz, so lower the data rate first or set both with [`Self::odr_mode_set`].
+    /// Only the registers that change are written. With [`Workarounds::MODE_SWITCH_SETTLE`], a mode change and a
+    /// running data rate, the function then waits one data period; setting the current mode again never waits.
+    ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SettleHint)`: The wait before the samples are converted in the new mode, see [`settle`]:
+    ///   [`settle::PENDING_SAMPLES`] on a mode change at a running data rate, unless the workaround already waited
+    ///   them.
+    /// - `Err(Error::InvalidArgument)`: If the mode cannot be used with the current data rate; nothing is written.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn power_mode_set(&mut self, val: Mode) -> Result<(), Error<B::Error>> {
-        let mut ctrl1 = Ctrl1::read(self)?;
-        ctrl1.set_mode(val.mode());
-        ctrl1.set_lp_mode(val.lp_mode());
-        ctrl1.write(self)?;
+    pub fn power_mode_set(&mut self, val: Mode) -> Result<SettleHint, Error<B::Error>> {
+        let odr = self.data_rate_get()?;
+        mode_odr_check(val, odr)?;
 
-        let mut ctrl6 = Ctrl6::read(self)?;
-        ctrl6.set_low_noise(val.low_noise());
-        ctrl6.write(self)
+        let ctrl1 = Ctrl1::read(self)?;
+        let ctrl1_set = ctrl1.with_mode(val.mode()).with_lp_mode(val.lp_mode());
+        if ctrl1_set != ctrl1 {
+            ctrl1_set.write(self)?;
+        }
+
+        let ctrl6 = Ctrl6::read(self)?;
+        let ctrl6_set = ctrl6.with_low_noise(val.low_noise());
+        if ctrl6_set != ctrl6 {
+            ctrl6_set.write(self)?;
+        }
+
+        let changed = ctrl1_set != ctrl1 || ctrl6_set != ctrl6;
+        let Some(period_us) = odr.period_us().filter(|_| changed) else {
+            return Ok(SettleHint::None);
+        };
+        if self
+            .state
+            .workarounds
+            .contains(Workarounds::MODE_SWITCH_SETTLE)
+        {
+            self.tim.wait_us(period_us);
+            return Ok(SettleHint::None);
+        }
+        Ok(SettleHint::Samples(settle::PENDING_SAMPLES))
     }
 
     /// Get the accelerometer operating mode.
@@ -130,12 +1273,16 @@ impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
     ///
     /// ### Returns
     /// - `Ok(Mode)`: The current operating mode, represented as a [`Mode`] value.
+    /// - `Err(Error::UnexpectedValue)`: If the `mode` field holds the reserved code (3), after the retry of
+    ///   [`Self::decode_retry_set`]; the payload is the combined code `low_noise << 4 | mode << 2 | lp_mode`, `0x1F`
+    ///   on a bus reading all ones.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
     pub fn power_mode_get(&mut self) -> Result<Mode, Error<B::Error>> {
-        let ctrl1 = Ctrl1::read(self)?;
-        let ctrl6 = Ctrl6::read(self)?;
-
-        Ok(Mode::new(ctrl1.mode(), ctrl1.lp_mode(), ctrl6.low_noise()))
+        self.read_decoded(|sensor| {
+            let ctrl1 = Ctrl1::read(sensor)?;
+            let ctrl6 = Ctrl6::read(sensor)?;
+            Ok(Mode::code(ctrl1.mode(), ctrl1.lp_mode(), ctrl6.low_noise()))
+        })
     }
 
     /// Set the accelerometer data rate.
@@ -146,17 +1293,34 @@ impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
     /// ### Arguments
     /// - `val`: A [`Odr`] value representing the desired data rate and sleep mode configuration.
     ///
+    /// The data rate is checked against the current operating mode with [`mode_odr_check`]: a rate above 200 Hz
+    /// needs the high-performance mode, so select the mode first or set both with [`Self::odr_mode_set`].
+    ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SettleHint)`: The wait before the output is filtered at the new data rate, see [`settle`]: the
+    ///   settling of the bandwidth in the `bw_filt` field of the `CTRL6` register, at the new data rate.
+    /// - `Err(Error::InvalidArgument)`: If the data rate cannot be used with the current mode; nothing is written.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn data_rate_set(&mut self, val: Odr) -> Result<(), Error<B::Error>> {
+    pub fn data_rate_set(&mut self, val: Odr) -> Result<SettleHint, Error<B::Error>> {
+        mode_odr_check(self.power_mode_get()?, val)?;
+
         let mut ctrl1 = Ctrl1::read(self)?;
+        let mut changed = ctrl1.odr() != val.odr();
         ctrl1.set_odr(val.odr());
         ctrl1.write(self)?;
 
         let mut ctrl3 = Ctrl3::read(self)?;
+        changed |= ctrl3.slp_mode() != val.slp_mode();
         ctrl3.set_slp_mode(val.slp_mode());
-        ctrl3.write(self)
+        ctrl3.write(self)?;
+
+        match val.period_us() {
+            Some(period_us) if changed => {
+                let bw = BwFilt::try_from(Ctrl6::read(self)?.bw_filt()).unwrap_or_default();
+                Ok(settle::filter_hint(bw, period_us, 0))
+            }
+            _ => Ok(SettleHint::None),
+        }
     }
 
     /// Get the accelerometer data rate.
@@ -166,1597 +1330,2315 @@ impl<B: BusOperation, T: DelayNs> Iis2dlpc<B, T> {
     ///
     /// ### Returns
     /// - `Ok(Odr)`: The current data rate, represented as an [`Odr`] value.
+    /// - `Err(Error::UnexpectedValue)`: If the `odr` field holds a reserved code (10 to 15), after the retry of
+    ///   [`Self::decode_retry_set`]; the payload is the combined code `slp_mode << 4 | odr`, `0x3F` on a bus reading
+    ///   all ones.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
     pub fn data_rate_get(&mut self) -> Result<Odr, Error<B::Error>> {
-        let ctrl1 = Ctrl1::read(self)?;
-        let ctrl3 = Ctrl3::read(self)?;
-
-        Ok(Odr::new(ctrl1.odr(), ctrl3.slp_mode()))
+        self.read_decoded_with(
+            |sensor| {
+                let ctrl1 = Ctrl1::read(sensor)?;
+                let ctrl3 = Ctrl3::read(sensor)?;
+                Ok(Odr::code(ctrl1.odr(), ctrl3.slp_mode()))
+            },
+            Odr::decode,
+        )
     }
 
-    /// Set the block data update (BDU) configuration.
+    /// Set the accelerometer operating mode and data rate together.
     ///
-    /// This function configures the block data update (BDU) setting by updating the `bdu` field in the `CTRL2` register.
-    /// When BDU is enabled, the output registers are not updated until both the high and low parts are read, ensuring data consistency.
+    /// This function checks the combination with [`mode_odr_check`], then writes the `low_noise` field in the
+    /// `CTRL6` register, the `slp_mode` field in the `CTRL3` register and, last, the `mode`, `lp_mode` and `odr`
+    /// fields in the `CTRL1` register in a single write: unlike [`Self::power_mode_set`] followed by
+    /// [`Self::data_rate_set`], it switches between a continuous and a single data conversion configuration, or
+    /// between the high-performance and the low-power data rates, without going through an inconsistent one.
+    /// With [`Workarounds::MODE_SWITCH_SETTLE`], a mode change and a running data rate, it then waits one data period.
     ///
     /// ### Arguments
-    /// - `val`: The desired BDU value:
-    ///   - `0`: Continuous update.
-    ///   - `1`: Output registers not updated until MSB and LSB are read.
+    /// - `mode`: The operating mode, see [`Mode`].
+    /// - `odr`: The data rate, see [`Odr`].
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SettleHint)`: The wait before the output is filtered at the new data rate, see [`settle`]: the filter
+    ///   settling on a data rate change, [`settle::PENDING_SAMPLES`] on a mode change alone.
+    /// - `Err(Error::InvalidArgument)`: If the mode cannot be used with the data rate, see [`mode_odr_check`];
+    ///   nothing is accessed.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn block_data_update_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_bdu(val);
-        ctrl2.write(self)
-    }
-
-    /// Get the block data update (BDU) configuration.
-    ///
-    /// This function retrieves the current block data update (BDU) setting from the `CTRL2` register.
-    ///
-    /// ### Returns
-    /// - `Ok(u8)`: The current BDU value:
-    ///   - `0`: Continuous update.
-    ///   - `1`: Output registers not updated until MSB and LSB are read.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn block_data_update_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(Ctrl2::read(self)?.bdu())
-    }
+    pub fn odr_mode_set(&mut self, mode: Mode, odr: Odr) -> Result<SettleHint, Error<B::Error>> {
+        mode_odr_check(mode, odr)?;
 
-    /// Set the accelerometer full-scale selection.
-    ///
-    /// This function configures the full-scale range of the accelerometer by updating the `fs` field in the `CTRL6` register.
-    /// The full-scale range determines the maximum measurable acceleration.
-    ///
-    /// ### Arguments
-    /// - `val`: A [`Fs`] value representing the desired full-scale range:
-    ///   - `Fs2g`: ±2g (default).
-    ///   - `Fs4g`: ±4g.
-    ///   - `Fs8g`: ±8g.
-    ///   - `Fs16g`: ±16g.
-    ///
-    /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn full_scale_set(&mut self, val: Fs) -> Result<(), Error<B::Error>> {
         let mut ctrl6 = Ctrl6::read(self)?;
-        ctrl6.set_fs(val as u8);
-        ctrl6.write(self)
-    }
+        let mut mode_changed = ctrl6.low_noise() != mode.low_noise();
+        ctrl6.set_low_noise(mode.low_noise());
+        ctrl6.write(self)?;
 
-    /// Get the accelerometer full-scale selection.
-    ///
-    /// This function retrieves the current full-scale range of the accelerometer from the `CTRL6` register.
-    ///
-    /// ### Returns
-    /// - `Ok(Fs)`: The current full-scale range as a [`Fs`] value.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn full_scale_get(&mut self) -> Result<Fs, Error<B::Error>> {
-        Ok(Fs::try_from(Ctrl6::read(self)?.fs()).unwrap_or_default())
-    }
+        let mut ctrl3 = Ctrl3::read(self)?;
+        let mut odr_changed = ctrl3.slp_mode() != odr.slp_mode();
+        ctrl3.set_slp_mode(odr.slp_mode());
+        ctrl3.write(self)?;
 
-    /// Get the status register.
-    ///
-    /// This function retrieves the current status of the device by reading the `STATUS` register.
-    /// The `STATUS` register provides information about various events, such as data-ready, free-fall detection, and tap detection.
-    ///
-    /// ### Returns
-    /// - `Ok(Status)`: The current status as a [`Status`] struct, which represents the union of registers from `STATUS`.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn status_reg_get(&mut self) -> Result<Status, Error<B::Error>> {
-        Status::read(self)
-    }
+        let mut ctrl1 = Ctrl1::read(self)?;
+        mode_changed |= ctrl1.mode() != mode.mode() || ctrl1.lp_mode() != mode.lp_mode();
+        odr_changed |= ctrl1.odr() != odr.odr();
+        ctrl1.set_mode(mode.mode());
+        ctrl1.set_lp_mode(mode.lp_mode());
+        ctrl1.set_odr(odr.odr());
+        ctrl1.write(self)?;
 
-    /// Get the accelerometer new data availability flag.
-    ///
-    /// This function checks whether new accelerometer data is available by reading the `drdy` field in the `STATUS` register.
-    ///
-    /// ### Returns
-    /// - `Ok(u8)`: The value of the `drdy` field:
-    ///   - `0`: No new data available.
-    ///   - `1`: New data is available.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation..
-    pub fn flag_data_ready_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(self.status_reg_get()?.drdy())
+        let Some(period_us) = odr.period_us() else {
+            return Ok(SettleHint::None);
+        };
+        let waited = if mode_changed
+            && self
+                .state
+                .workarounds
+                .contains(Workarounds::MODE_SWITCH_SETTLE)
+        {
+            self.tim.wait_us(period_us);
+            settle::PENDING_SAMPLES
+        } else {
+            0
+        };
+        if odr_changed {
+            let bw = BwFilt::try_from(ctrl6.bw_filt()).unwrap_or_default();
+            Ok(settle::filter_hint(bw, period_us, waited))
+        } else if mode_changed && waited == 0 {
+            Ok(SettleHint::Samples(settle::PENDING_SAMPLES))
+        } else {
+            Ok(SettleHint::None)
+        }
     }
 
-    /// Get all interrupt and status flags of the device.
+    /// Check the consistency of the programmed operating mode and data rate.
     ///
-    /// This function retrieves the status of all interrupt and status flags by reading the following registers:
-    /// - `STATUS_DUP`
-    /// - `WAKE_UP_SRC`
-    /// - `TAP_SRC`
-    /// - `SIXD_SRC`
-    /// - `ALL_INT_SRC`
+    /// This function reads the `CTRL1`, `CTRL3` and `CTRL6` registers and checks them as [`mode_odr_check`] does,
+    /// for configurations written without [`Self::power_mode_set`] and [`Self::data_rate_set`].
     ///
     /// ### Returns
-    /// - `Ok(AllSources)`: A struct containing the values of all the above registers.
+    /// - `Ok(())`: If the configuration is consistent.
+    /// - `Err(Error::InvalidConfig)`: The inconsistency found, see [`ConfigError`].
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn all_sources_get(&mut self) -> Result<AllSources, Error<B::Error>> {
-        Ok(AllSources {
-            status_dup: StatusDup::read(self)?,
-            wake_up_src: WakeUpSrc::read(self)?,
-            tap_src: TapSrc::read(self)?,
-            sixd_src: SixdSrc::read(self)?,
-            all_int_src: AllIntSrc::read(self)?,
-        })
+    pub fn config_consistency_check(&mut self) -> Result<(), Error<B::Error>> {
+        let mode = self.power_mode_get()?;
+        let odr = self.data_rate_get()?;
+        mode_odr_conflict(mode, odr).map_err(Error::InvalidConfig)
     }
 
-    /// Set the X-axis user offset correction.
+    /// Start a single data conversion from the serial interface.
     ///
-    /// This function configures the X-axis user offset correction value in the `X_OFS_USR` register.
-    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
-    ///
-    /// ### Arguments
-    /// - `val`: The X-axis user offset correction value to set.
-    ///
-    /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
-    pub fn usr_offset_x_set(&mut self, val: i8) -> Result<(), Error<B::Error>> {
-        XOfsUsr::from_bits(val.cast_unsigned()).write(self)
-    }
-
-    /// Get the X-axis user offset correction.
+    /// This function sets the self-clearing `slp_mode_1` bit of the `slp_mode` field in the `CTRL3` register,
+    /// with `slp_mode_sel` set, keeping the other bits of the register. The device clears `slp_mode_1` once the
+    /// sample is in the output registers; each call starts one conversion, so that
+    /// `data_rate_set(Odr::SetSwTrig)` followed by this function gives exactly one sample. See
+    /// [`Self::acceleration_oneshot_get`] to wait for the sample and read it.
     ///
-    /// This function retrieves the X-axis user offset correction value from the `X_OFS_USR` register.
-    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// The software trigger is selected as well if the INT2 pin was, see [`Self::trigger_mode_set`].
     ///
     /// ### Returns
-    /// - `Ok(i8)`: The X-axis user offset correction value.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn usr_offset_x_get(&mut self) -> Result<i8, Error<B::Error>> {
-        Ok(XOfsUsr::read(self)?.x_ofs_usr())
+    /// - `Ok(())`: If the conversion is started.
+    /// - `Err(Error::InvalidConfig)`: If the mode is not a single data conversion mode or the data rate is `Off`;
+    ///   nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn sw_trigger(&mut self) -> Result<(), Error<B::Error>> {
+        self.on_demand_check()?;
+        self.single_conversion_trigger()
     }
 
-    /// Set the Y-axis user offset correction.
+    /// Set the trigger of the single data conversions.
     ///
-    /// This function configures the Y-axis user offset correction value in the `Y_OFS_USR` register.
-    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// This function writes the `slp_mode` field in the `CTRL3` register as [`Self::data_rate_set`] does for
+    /// [`Odr::SetSwTrig`] and [`Odr::SetPinTrig`]: `slp_mode_sel` set and `slp_mode_1` cleared for the software
+    /// trigger; `slp_mode_sel` cleared and `slp_mode_1` set for the INT2 pin, which then is an input and must
+    /// have no interrupt routed to it.
     ///
     /// ### Arguments
-    /// - `val`: The Y-axis user offset correction value to set.
+    /// - `val`: The trigger, a [`SlpModeSel`] value.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
-    pub fn usr_offset_y_set(&mut self, val: i8) -> Result<(), Error<B::Error>> {
-        YOfsUsr::from_bits(val.cast_unsigned()).write(self)
+    /// - `Err(Error::InvalidConfig)`: If the mode is not a single data conversion mode or the data rate is `Off`;
+    ///   nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn trigger_mode_set(&mut self, val: SlpModeSel) -> Result<(), Error<B::Error>> {
+        self.on_demand_check()?;
+        let odr = match val {
+            SlpModeSel::SwTrigger => Odr::SetSwTrig,
+            SlpModeSel::PinTrigger => Odr::SetPinTrig,
+        };
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_slp_mode(odr.slp_mode());
+        ctrl3.write(self)
     }
 
-    /// Get the Y-axis user offset correction.
+    /// Get the trigger of the single data conversions.
     ///
-    /// This function retrieves the Y-axis user offset correction value from the `Y_OFS_USR` register.
-    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// This function reads the `slp_mode_sel` bit of the `slp_mode` field in the `CTRL3` register.
     ///
     /// ### Returns
-    /// - `Ok(i8)`: The Y-axis user offset correction value.
+    /// - `Ok(SlpModeSel)`: The trigger; meaningful in a single data conversion mode only.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn usr_offset_y_get(&mut self) -> Result<i8, Error<B::Error>> {
-        Ok(YOfsUsr::read(self)?.y_ofs_usr())
+    pub fn trigger_mode_get(&mut self) -> Result<SlpModeSel, Error<B::Error>> {
+        let ctrl3 = Ctrl3::read(self)?;
+        Ok(SlpModeSel::try_from(ctrl3.slp_mode() >> 1).unwrap_or_default())
     }
 
-    /// Set the Z-axis user offset correction.
-    ///
-    /// This function configures the Z-axis user offset correction value in the `Z_OFS_USR` register.
-    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
-    ///
-    /// ### Arguments
-    /// - `val`: The Z-axis user offset correction value to set.
+    /// Start a single data conversion from the serial interface, without checking the mode.
     ///
-    /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
-    pub fn usr_offset_z_set(&mut self, val: i8) -> Result<(), Error<B::Error>> {
-        ZOfsUsr::from_bits(val.cast_unsigned()).write(self)
+    /// Sets `SLP_MODE_SEL` and the self-clearing `SLP_MODE_1` bit in the `CTRL3` register.
+    pub(crate) fn single_conversion_trigger(&mut self) -> Result<(), Error<B::Error>> {
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_slp_mode(0x03);
+        ctrl3.write(self)
     }
 
-    /// Get the Z-axis user offset correction.
+    /// Check that a single data conversion mode is set, with a data rate selected.
     ///
-    /// This function retrieves the Z-axis user offset correction value from the `Z_OFS_USR` register.
-    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
-    ///
-    /// ### Returns
-    /// - `Ok(i8)`: The Z-axis user offset correction value.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn usr_offset_z_get(&mut self) -> Result<i8, Error<B::Error>> {
-        Ok(ZOfsUsr::read(self)?.z_ofs_usr())
+    /// The trigger source is not checked: [`Self::single_conversion_trigger`] selects the software
+    /// trigger itself. The `odr` field is checked as read, whatever the `slp_mode` field holds.
+    pub(crate) fn on_demand_check(&mut self) -> Result<(), Error<B::Error>> {
+        let mode = self.power_mode_get()?;
+        if mode.mode() != 0x02 || Ctrl1::read(self)?.odr() == 0 {
+            return Err(Error::InvalidConfig(ConfigError::OnDemandRequired));
+        }
+        Ok(())
     }
 
-    /// Set the weight of XL user offset bits.
+    /// Acquire one sample in single data conversion mode.
     ///
-    /// This function configures the weight of the user offset bits in the `X_OFS_USR`, `Y_OFS_USR`, and `Z_OFS_USR` registers by updating the `usr_off_w` field in the `CTRL7` register.
+    /// This function checks that a single data conversion mode is set, reads and discards a sample left unread by
+    /// a previous conversion, then starts the conversion: with [`SlpModeSel::SwTrigger`] it sets the `slp_mode`
+    /// field in the `CTRL3` register to the software trigger with the self-clearing `slp_mode_1` bit; with
+    /// [`SlpModeSel::PinTrigger`] nothing is written and the conversion starts at the next rising edge on the INT2
+    /// pin, which must be selected as trigger, see [`Self::trigger_mode_set`]. It then polls the `drdy` flag of the `STATUS` register every
+    /// [`ONESHOT_POLL_US`], up to [`Self::oneshot_max_polls_set`] times, and reads the sample as
+    /// [`Self::acceleration_raw_get`] does.
     ///
     /// ### Arguments
-    /// - `val`: A [`UsrOffW`] value representing the desired weight:
-    ///   - `Lsb977ug`: 977 μg/LSB (default).
-    ///   - `Lsb15mg6`: 15.6 mg/LSB.
+    /// - `trigger`: The trigger of the conversion.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation..
-    pub fn offset_weight_set(&mut self, val: UsrOffW) -> Result<(), Error<B::Error>> {
-        let mut ctrl7 = Ctrl7::read(self)?;
-        ctrl7.set_usr_off_w(val as u8);
-        ctrl7.write(self)
-    }
+    /// - `Ok([i16; 3])`: The raw acceleration on the X, Y and Z axes, in the board frame (see
+    ///   [`Self::axis_remap_set`]).
+    /// - `Err(Error::Timeout)`: If the `drdy` flag is not set after the last poll.
+    /// - `Err(Error::InvalidConfig)`: If the mode is not a single data conversion mode or the data rate is `Off`,
+    ///   or the FIFO path is active, see [`Self::allow_mixed_data_paths`]; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn acceleration_oneshot_get(
+        &mut self,
+        trigger: SlpModeSel,
+    ) -> Result<[i16; 3], Error<B::Error>> {
+        self.direct_path_check()?;
+        self.on_demand_check()?;
+
+        if self.status_reg_get()?.drdy() == 1 {
+            self.acceleration_raw_get()?;
+        }
+        if trigger == SlpModeSel::SwTrigger {
+            self.single_conversion_trigger()?;
+        }
 
-    /// Get the weight of XL user offset bits.
-    ///
-    /// This function retrieves the weight of the user offset bits from the `usr_off_w` field in the `CTRL7` register.
-    ///
-    /// ### Returns
-    /// - `Ok(UsrOffW)`: The current weight of the user offset bits:
-    ///   - `Lsb977ug`: 977 μg/LSB (default).
-    ///   - `Lsb15mg6`: 15.6 mg/LSB.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation..
-    pub fn offset_weight_get(&mut self) -> Result<UsrOffW, Error<B::Error>> {
-        Ok(UsrOffW::try_from(Ctrl7::read(self)?.usr_off_w()).unwrap_or_default())
+        for poll in 0..self.state.oneshot_max_polls {
+            if poll > 0 {
+                self.tim.wait_us(ONESHOT_POLL_US);
+            }
+            if self.status_reg_get()?.drdy() == 1 {
+                return self.acceleration_raw_get();
+            }
+        }
+        Err(Error::Timeout)
     }
 
-    /// Get the raw temperature data.
+    /// Set the number of data-ready polls of [`Self::acceleration_oneshot_get`].
     ///
-    /// This function retrieves the raw temperature data from the `OUT_T_L` and `OUT_T_H` registers.
-    /// The value is expressed as a 16-bit word in two's complement format.
+    /// The polls are [`ONESHOT_POLL_US`] apart; with a pin trigger, the count bounds the wait for the edge as well.
     ///
-    /// ### Returns
-    /// - `Ok(i16)`: The raw temperature data.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn temperature_raw_get(&mut self) -> Result<i16, Error<B::Error>> {
-        Ok(OutT::read(self)?.temp())
+    /// ### Arguments
+    /// - `polls`: The number of polls, [`DEFAULT_ONESHOT_MAX_POLLS`] by default; `0` is taken as `1`.
+    pub fn oneshot_max_polls_set(&mut self, polls: u16) {
+        self.state.oneshot_max_polls = polls.max(1);
     }
 
-    /// Get the raw acceleration data.
-    ///
-    /// This function retrieves the raw acceleration data for the X, Y, and Z axes from the `OUT_X_L`, `OUT_X_H`, `OUT_Y_L`, `OUT_Y_H`, `OUT_Z_L`, and `OUT_Z_H` registers.
-    /// The values are expressed as 16-bit words in two's complement format.
+    /// Get the number of data-ready polls of [`Self::acceleration_oneshot_get`].
     ///
     /// ### Returns
-    /// - `Ok([i16; 3])`: An array containing the raw acceleration data for the X, Y, and Z axes.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn acceleration_raw_get(&mut self) -> Result<[i16; 3], Error<B::Error>> {
-        Ok([
-            OutX::read(self)?.x(),
-            OutY::read(self)?.y(),
-            OutZ::read(self)?.z(),
-        ])
+    /// - `u16`: The current number, see [`Self::oneshot_max_polls_set`].
+    pub fn oneshot_max_polls_get(&self) -> u16 {
+        self.state.oneshot_max_polls
     }
 
-    /// Get the device ID.
-    ///
-    /// This function retrieves the device ID from the `WHO_AM_I` register.
-    /// The device ID is a fixed value that identifies the IIS2DLPC sensor.
+    /// Start a pin-triggered capture session.
     ///
-    /// ### Returns
-    /// - `Ok(u8)`: The device ID (expected value: `0x44`).
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation..
-    pub fn device_id_get(&mut self) -> Result<u8, Error<B::Error>> {
-        let mut buff: [u8; 1] = [0];
-        self.read_from_register(Reg::WhoAmI as u8, &mut buff)?;
-        Ok(buff[0])
-    }
-
-    /// Enable or disable automatic register address increment.
+    /// This function saves the `CTRL1` and `CTRL3` registers, then configures single data conversion mode
+    /// (`mode` field in `CTRL1`, keeping the low-power mode) triggered by the INT2 pin (`slp_mode` field in
+    /// `CTRL3`) and resets the sample counter. A pending sample is read and discarded, so that it is not counted.
+    /// INT2 is an input for the whole session: no interrupt must be routed to it.
     ///
-    /// This function configures the automatic register address increment feature by updating the `if_add_inc` field in the `CTRL2` register.
-    /// When enabled, the register address is automatically incremented during multiple-byte access.
+    /// Calling this function again during a session resets the counter and keeps the registers saved by the
+    /// first call.
     ///
     /// ### Arguments
-    /// - `val`: The desired value for the `if_add_inc` field:
-    ///   - `0`: Disable automatic increment.
-    ///   - `1`: Enable automatic increment.
+    /// - `expected`: The number of triggers the application is going to issue.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn auto_increment_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_if_add_inc(val);
-        ctrl2.write(self)
-    }
+    pub fn triggered_capture_begin(&mut self, expected: u16) -> Result<(), Error<B::Error>> {
+        let mut ctrl1 = Ctrl1::read(self)?;
+        let mut ctrl3 = Ctrl3::read(self)?;
+        let (saved_ctrl1, saved_ctrl3) = match self.state.capture {
+            Some(session) => (session.ctrl1, session.ctrl3),
+            None => (ctrl1.into_bits(), ctrl3.into_bits()),
+        };
+
+        // SLP_MODE_SEL = 0: a rising edge on INT2 starts a conversion
+        ctrl3.set_slp_mode(0x01);
+        ctrl3.write(self)?;
+        ctrl1.set_mode(0x02);
+        ctrl1.set_odr(Odr::_12_5hz.odr());
+        ctrl1.write(self)?;
 
-    /// Get the automatic register address increment configuration.
-    ///
-    /// This function retrieves the current value of the `if_add_inc` field from the `CTRL2` register.
-    ///
-    /// ### Returns
-    /// - `Ok(u8)`: The current value of the `if_add_inc` field:
-    ///   - `0`: Automatic increment is disabled.
-    ///   - `1`: Automatic increment is enabled.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn auto_increment_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(Ctrl2::read(self)?.if_add_inc())
-    }
+        if self.status_reg_get()?.drdy() == 1 {
+            self.acceleration_raw_get()?;
+        }
 
-    /// Perform a software reset.
-    ///
-    /// This function performs a software reset by updating the `soft_reset` field in the `CTRL2` register.
-    /// A software reset restores the default values in all user registers.
-    ///
-    /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn reset_set(&mut self) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_soft_reset(PROPERTY_ENABLE);
-        ctrl2.write(self)
+        self.state.capture = Some(CaptureSession {
+            ctrl1: saved_ctrl1,
+            ctrl3: saved_ctrl3,
+            stats: CaptureStats {
+                expected,
+                captured: 0,
+            },
+        });
+        Ok(())
     }
 
-    /// Get the software reset status.
+    /// Get the sample of a pin-triggered capture session, if one is ready.
     ///
-    /// This function retrieves the current value of the `soft_reset` field from the `CTRL2` register.
-    /// The value indicates whether a software reset has been performed.
+    /// This function reads the `drdy` field in the `STATUS` register and, when it is set, the raw acceleration
+    /// (see [`Self::acceleration_raw_get`]) and counts the sample.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current value of the `soft_reset` field:
-    ///   - `0`: No reset in progress.
-    ///   - `1`: Reset in progress.
+    /// - `Ok(Some([i16; 3]))`: The raw acceleration on the X, Y and Z axes.
+    /// - `Ok(None)`: If no conversion completed since the last sample.
+    /// - `Err(Error::InvalidConfig)`: If no capture session is open.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn reset_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(Ctrl2::read(self)?.soft_reset())
+    pub fn triggered_capture_poll(&mut self) -> Result<Option<[i16; 3]>, Error<B::Error>> {
+        if self.state.capture.is_none() {
+            return Err(Error::InvalidConfig(ConfigError::CaptureNotActive));
+        }
+        if self.status_reg_get()?.drdy() == 0 {
+            return Ok(None);
+        }
+        let raw = self.acceleration_raw_get()?;
+        if let Some(session) = self.state.capture.as_mut() {
+            session.stats.captured = session.stats.captured.saturating_add(1);
+        }
+        Ok(Some(raw))
     }
 
-    /// Reboot memory content and reload calibration parameters.
+    /// End a pin-triggered capture session.
     ///
-    /// This function triggers a reboot of the device's memory content by updating the `boot` field in the `CTRL2` register.
-    /// The reboot operation reloads the calibration parameters from non-volatile memory.
+    /// This function writes back the `CTRL3` and `CTRL1` registers saved by [`Self::triggered_capture_begin`],
+    /// `CTRL1` last, and closes the session.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn boot_set(&mut self) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_boot(PROPERTY_ENABLE);
-        ctrl2.write(self)
+    /// - `Ok(CaptureStats)`: The number of samples captured and expected.
+    /// - `Err(Error::InvalidConfig)`: If no capture session is open.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation; the session stays open.
+    pub fn triggered_capture_end(&mut self) -> Result<CaptureStats, Error<B::Error>> {
+        let Some(session) = self.state.capture else {
+            return Err(Error::InvalidConfig(ConfigError::CaptureNotActive));
+        };
+        let ctrl1 = Ctrl1::from_bits(session.ctrl1);
+        let ctrl3 = Ctrl3::from_bits(session.ctrl3);
+        ctrl3.write(self)?;
+        ctrl1.write(self)?;
+
+        self.state.capture = None;
+        Ok(session.stats)
     }
 
-    /// Get the reboot memory content status.
+    /// Set the accelerometer data rate, preserving the duration settings in milliseconds.
     ///
-    /// This function retrieves the current value of the `boot` field from the `CTRL2` register.
-    /// The value indicates whether a reboot operation is in progress.
+    /// The wake-up, sleep, tap and free-fall durations are expressed in ODR periods, so changing the data rate
+    /// with [`Self::data_rate_set`] also changes their real-world length. This function reads the `WAKE_UP_DUR`,
+    /// `INT_DUR` and `FREE_FALL` registers, converts every duration field with the current data rate, switches to
+    /// `new_odr` and rewrites each field with the code nearest to its previous duration.
+    ///
+    /// ### Arguments
+    /// - `new_odr`: The new data rate.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current value of the `boot` field:
-    ///   - `0`: No reboot in progress.
-    ///   - `1`: Reboot in progress.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn boot_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(Ctrl2::read(self)?.boot())
+    /// - `Ok(RetuneReport)`: The old and new code and duration of every field, with the fields clamped to their range.
+    /// - `Err(Error::InvalidConfig)`: If the current or the new data rate is off or triggered on demand; nothing is written.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn data_rate_set_retuned(&mut self, new_odr: Odr) -> Result<RetuneReport, Error<B::Error>> {
+        let (Some(old_period_us), Some(new_period_us)) =
+            (self.data_rate_get()?.period_us(), new_odr.period_us())
+        else {
+            return Err(Error::InvalidConfig(ConfigError::DataRateNotRunning));
+        };
+        let (old_period_us, new_period_us) = (old_period_us as u64, new_period_us as u64);
+
+        let mut wake_up_dur = WakeUpDur::read(self)?;
+        let mut int_dur = IntDur::read(self)?;
+        let mut free_fall = FreeFall::read(self)?;
+
+        let retune = |field, code| FieldRetune::new(field, code, old_period_us, new_period_us);
+        let report = RetuneReport {
+            wake_dur: retune(DurationField::WakeDur, wake_up_dur.wake_dur()),
+            sleep_dur: retune(DurationField::SleepDur, wake_up_dur.sleep_dur()),
+            shock: retune(DurationField::Shock, int_dur.shock()),
+            quiet: retune(DurationField::Quiet, int_dur.quiet()),
+            latency: retune(DurationField::Latency, int_dur.latency()),
+            ff_dur: retune(
+                DurationField::FfDur,
+                (wake_up_dur.ff_dur() << 5) + free_fall.ff_dur(),
+            ),
+        };
+
+        self.data_rate_set(new_odr)?;
+
+        wake_up_dur.set_wake_dur(report.wake_dur.new_code);
+        wake_up_dur.set_sleep_dur(report.sleep_dur.new_code);
+        wake_up_dur.set_ff_dur((report.ff_dur.new_code & 0x20) >> 5);
+        free_fall.set_ff_dur(report.ff_dur.new_code & 0x1F);
+        int_dur.set_shock(report.shock.new_code);
+        int_dur.set_quiet(report.quiet.new_code);
+        int_dur.set_latency(report.latency.new_code);
+
+        wake_up_dur.write(self)?;
+        int_dur.write(self)?;
+        free_fall.write(self)?;
+
+        Ok(report)
     }
 
-    /// Enable or disable the sensor self-test.
+    /// Set the block data update (BDU) configuration.
     ///
-    /// This function configures the self-test mode of the sensor by updating the `st` field in the `CTRL3` register.
-    /// The self-test mode allows verifying the functionality of the sensor without external stimuli.
+    /// This function configures the block data update (BDU) setting by updating the `bdu` field in the `CTRL2` register.
+    /// When BDU is enabled, the output registers are not updated until both the high and low parts are read, ensuring data consistency.
     ///
     /// ### Arguments
-    /// - `val`: A [`St`] value representing the desired self-test mode:
-    ///   - `XlStDisable`: Self-test disabled (default).
-    ///   - `XlStPositive`: Positive sign self-test.
-    ///   - `XlStNegative`: Negative sign self-test.
+    /// - `val`: The desired BDU value:
+    ///   - `false`: Continuous update.
+    ///   - `true`: Output registers not updated until MSB and LSB are read.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn self_test_set(&mut self, val: St) -> Result<(), Error<B::Error>> {
-        let mut ctrl3 = Ctrl3::read(self)?;
-        ctrl3.set_st(val as u8);
-        ctrl3.write(self)
+    pub fn block_data_update_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_bdu(val as u8);
+        ctrl2.write(self)
     }
 
-    /// Get the sensor self-test mode.
+    /// Get the block data update (BDU) configuration.
     ///
-    /// This function retrieves the current self-test mode of the sensor from the `st` field in the `CTRL3` register.
+    /// This function retrieves the current block data update (BDU) setting from the `CTRL2` register.
     ///
     /// ### Returns
-    /// - `Ok(St)`: The current self-test mode as a [`St`] value:
-    ///   - `XlStDisable`: Self-test disabled (default).
-    ///   - `XlStPositive`: Positive sign self-test.
-    ///   - `XlStNegative`: Negative sign self-test.
+    /// - `Ok(bool)`: The current BDU value:
+    ///   - `false`: Continuous update.
+    ///   - `true`: Output registers not updated until MSB and LSB are read.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn self_test_get(&mut self) -> Result<St, Error<B::Error>> {
-        Ok(St::try_from(Ctrl3::read(self)?.st()).unwrap_or_default())
+    pub fn block_data_update_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(Ctrl2::read(self)?.bdu() == PROPERTY_ENABLE)
     }
 
-    /// Set the data-ready interrupt mode.
+    /// Set the accelerometer full-scale selection.
     ///
-    /// This function configures the data-ready interrupt mode by updating the `drdy_pulsed` field in the `CTRL7` register.
-    /// The data-ready interrupt can be configured as either latched or pulsed mode.
+    /// This function configures the full-scale range of the accelerometer by updating the `fs` field in the `CTRL6` register.
+    /// The full-scale range determines the maximum measurable acceleration.
     ///
     /// ### Arguments
-    /// - `val`: A [`DrdyPulsed`] value representing the desired data-ready interrupt mode:
-    ///   - `Latched`: Latched mode (default).
-    ///   - `Pulsed`: Pulsed mode.
+    /// - `val`: A [`Fs`] value representing the desired full-scale range:
+    ///   - `Fs2g`: ±2g (default).
+    ///   - `Fs4g`: ±4g.
+    ///   - `Fs8g`: ±8g.
+    ///   - `Fs16g`: ±16g.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SettleHint)`: The wait before the samples are converted with the new scale, see [`settle`]:
+    ///   [`settle::PENDING_SAMPLES`] on a running data rate.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn data_ready_mode_set(&mut self, val: DrdyPulsed) -> Result<(), Error<B::Error>> {
-        let mut ctrl7 = Ctrl7::read(self)?;
-        ctrl7.set_drdy_pulsed(val as u8);
-        ctrl7.write(self)
+    pub fn full_scale_set(&mut self, val: Fs) -> Result<SettleHint, Error<B::Error>> {
+        let mut ctrl6 = Ctrl6::read(self)?;
+        let changed = ctrl6.fs() != val as u8;
+        ctrl6.set_fs(val as u8);
+        ctrl6.write(self)?;
+
+        self.pending_samples_hint(changed)
     }
 
-    /// Get the data-ready interrupt mode.
+    /// Get the accelerometer full-scale selection.
     ///
-    /// This function retrieves the current data-ready interrupt mode from the `drdy_pulsed` field in the `CTRL7` register.
+    /// This function retrieves the current full-scale range of the accelerometer from the `CTRL6` register.
     ///
     /// ### Returns
-    /// - `Ok(DrdyPulsed)`: The current data-ready interrupt mode as a [`DrdyPulsed`] value:
-    ///   - `Latched`: Latched mode (default).
-    ///   - `Pulsed`: Pulsed mode.
+    /// - `Ok(Fs)`: The current full-scale range as a [`Fs`] value.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn data_ready_mode_get(&mut self) -> Result<DrdyPulsed, Error<B::Error>> {
-        Ok(DrdyPulsed::try_from(Ctrl7::read(self)?.drdy_pulsed()).unwrap_or_default())
+    pub fn full_scale_get(&mut self) -> Result<Fs, Error<B::Error>> {
+        Ok(Fs::try_from(Ctrl6::read(self)?.fs()).unwrap_or_default())
     }
 
-    /// Set the accelerometer filtering path for outputs.
+    /// Get the status register.
     ///
-    /// This function configures the filtering path for accelerometer outputs by updating the `fds` field in the `CTRL6` register
-    /// and the `usr_off_on_out` field in the `CTRL7` register.
+    /// This function retrieves the current status of the device by reading the `STATUS` register.
+    /// The `STATUS` register provides information about various events, such as data-ready, free-fall detection, and tap detection.
     ///
-    /// ### Arguments
-    /// - `val`: A [`Fds`] value representing the desired filtering path:
-    ///   - `LpfOnOut`: Low-pass filter on output (default).
-    ///   - `UserOffsetOnOut`: User offset on output.
-    ///   - `HighPassOnOut`: High-pass filter on output.
+    /// `STATUS` is the source of the data-ready flag: every data-ready poll of the driver reads it. The event
+    /// flags and the sleep state are read from `STATUS_DUP`, together with the source registers, see
+    /// [`Self::all_sources_get`]. The two registers are read in different transactions and may disagree when a
+    /// flag changes in between; see [`Self::status_coherent_get`] to read both.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn filter_path_set(&mut self, val: Fds) -> Result<(), Error<B::Error>> {
-        let mut ctrl6 = Ctrl6::read(self)?;
-        ctrl6.set_fds(val.fds());
-        ctrl6.write(self)?;
-
-        let mut ctrl7 = Ctrl7::read(self)?;
-        ctrl7.set_usr_off_on_out(val.usr_off_on_out());
-        ctrl7.write(self)
+    /// - `Ok(Status)`: The current status as a [`Status`] struct, which represents the union of registers from `STATUS`.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn status_reg_get(&mut self) -> Result<Status, Error<B::Error>> {
+        Status::read(self)
     }
 
-    /// Get the accelerometer filtering path for outputs.
+    /// Get the `STATUS` and `STATUS_DUP` registers, back-to-back.
     ///
-    /// This function retrieves the current filtering path for accelerometer outputs by reading the `fds` field from the `CTRL6` register
-    /// and the `usr_off_on_out` field from the `CTRL7` register.
+    /// This function reads the `STATUS` register, then the `STATUS_DUP` register, and compares the flags they
+    /// share, see [`Status::shared_flags_match`]. A mismatch means that a flag changed between the two reads;
+    /// each register is still consistent on its own. Most users need only one of them, see
+    /// [`Self::status_reg_get`].
     ///
     /// ### Returns
-    /// - `Ok(Fds)`: The current filtering path as a [`Fds`] value:
-    ///   - `LpfOnOut`: Low-pass filter on output (default).
-    ///   - `UserOffsetOnOut`: User offset on output.
-    ///   - `HighPassOnOut`: High-pass filter on output.
+    /// - `Ok((Status, StatusDup, bool))`: The two registers, and `true` if a shared flag changed between the reads.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn filter_path_get(&mut self) -> Result<Fds, Error<B::Error>> {
-        let ctrl6 = Ctrl6::read(self)?;
-        let ctrl7 = Ctrl7::read(self)?;
-
-        Ok(Fds::new(ctrl6.fds(), ctrl7.usr_off_on_out()))
+    pub fn status_coherent_get(&mut self) -> Result<(Status, StatusDup, bool), Error<B::Error>> {
+        let status = Status::read(self)?;
+        let dup = StatusDup::read(self)?;
+        let changed = !status.shared_flags_match(&dup);
+        Ok((status, dup, changed))
     }
 
-    /// Set the accelerometer cutoff filter frequency.
-    ///
-    /// This function configures the cutoff frequency for the accelerometer's low-pass or high-pass filter by updating the `bw_filt` field in the `CTRL6` register.
+    /// Get the accelerometer new data availability flag.
     ///
-    /// ### Arguments
-    /// - `val`: A [`BwFilt`] value representing the desired cutoff frequency:
-    ///   - `OdrDiv2`: ODR/2 (default).
-    ///   - `OdrDiv4`: ODR/4.
-    ///   - `OdrDiv10`: ODR/10.
-    ///   - `OdrDiv20`: ODR/20.
+    /// This function checks whether new accelerometer data is available by reading the `drdy` field in the `STATUS` register.
+    /// When brown-out recovery is enabled, see [`Self::brownout_recovery_set`], a `0` flag also triggers
+    /// [`Self::brownout_check`] and, if the device lost its configuration, [`Self::power_cycle_recover`].
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn filter_bandwidth_set(&mut self, val: BwFilt) -> Result<(), Error<B::Error>> {
-        let mut ctrl6 = Ctrl6::read(self)?;
-        ctrl6.set_bw_filt(val as u8);
-        ctrl6.write(self)
+    /// - `Ok(u8)`: The value of the `drdy` field:
+    ///   - `0`: No new data available.
+    ///   - `1`: New data is available.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation..
+    pub fn flag_data_ready_get(&mut self) -> Result<u8, Error<B::Error>> {
+        let drdy = self.status_reg_get()?.drdy();
+        if let (0, Some(snapshot)) = (drdy, self.state.brownout_recovery) {
+            if self.brownout_check()? {
+                self.power_cycle_recover(&snapshot)?;
+            }
+        }
+        Ok(drdy)
     }
 
-    /// Get the accelerometer cutoff filter frequency.
+    /// Get the temperature new data availability flag.
     ///
-    /// This function retrieves the current cutoff frequency for the accelerometer's low-pass or high-pass filter by reading the `bw_filt` field from the `CTRL6` register.
+    /// This function checks whether a new temperature sample is available by reading the `drdy_t` field in the
+    /// `STATUS_DUP` register. The read does not clear the event flags of `STATUS_DUP`, which are latched by the
+    /// source registers, see [`Self::all_sources_get`].
     ///
     /// ### Returns
-    /// - `Ok(BwFilt)`: The current cutoff frequency as a [`BwFilt`] value:
-    ///   - `OdrDiv2`: ODR/2 (default).
-    ///   - `OdrDiv4`: ODR/4.
-    ///   - `OdrDiv10`: ODR/10.
-    ///   - `OdrDiv20`: ODR/20.
+    /// - `Ok(u8)`: The value of the `drdy_t` field:
+    ///   - `0`: No new temperature data available.
+    ///   - `1`: New temperature data is available.
+    /// - `Err(Error::InvalidConfig)`: If the temperature policy is [`TempPolicy::Ignore`]; nothing is read.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn filter_bandwidth_get(&mut self) -> Result<BwFilt, Error<B::Error>> {
-        Ok(BwFilt::try_from(Ctrl6::read(self)?.bw_filt()).unwrap_or_default())
+    pub fn flag_temp_data_ready_get(&mut self) -> Result<u8, Error<B::Error>> {
+        self.temp_policy_check()?;
+        Ok(StatusDup::read(self)?.drdy_t())
     }
 
-    /// Enable or disable the high-pass filter reference mode.
+    /// Record the time of consecutive data-ready events.
     ///
-    /// This function configures the high-pass filter reference mode by updating the `hp_ref_mode` field in the `CTRL7` register.
+    /// This function discards a pending sample, then polls the `drdy` field in the `STATUS` register and, each
+    /// time it is set, stores the value of `clock` and reads the sample to clear the flag. The resolution of
+    /// the timestamps is the duration of one poll, a single register read.
     ///
     /// ### Arguments
-    /// - `val`: The desired value for the `hp_ref_mode` field:
-    ///   - `0`: Disable high-pass filter reference mode.
-    ///   - `1`: Enable high-pass filter reference mode.
+    /// - `clock`: A free-running counter, for example the cycle counter of the MCU.
+    /// - `out`: Destination of the timestamps, filled oldest first.
+    /// - `max_polls`: The maximum number of `STATUS` reads before giving up.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn reference_mode_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut ctrl7 = Ctrl7::read(self)?;
-        ctrl7.set_hp_ref_mode(val);
-        ctrl7.write(self)
+    /// - `Ok(usize)`: The number of timestamps written to `out`; fewer than `out.len()` if `max_polls` ran out.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn drdy_timestamps_get(
+        &mut self,
+        mut clock: impl FnMut() -> u32,
+        out: &mut [u32],
+        max_polls: u32,
+    ) -> Result<usize, Error<B::Error>> {
+        if self.status_reg_get()?.drdy() == 1 {
+            self.acceleration_raw_get()?;
+        }
+
+        let mut count = 0;
+        for _ in 0..max_polls {
+            let Some(slot) = out.get_mut(count) else {
+                break;
+            };
+            if self.status_reg_get()?.drdy() == 1 {
+                *slot = clock();
+                self.acceleration_raw_get()?;
+                count += 1;
+            }
+        }
+        Ok(count)
     }
 
-    /// Get the high-pass filter reference mode status.
+    /// Get a summary of the sensor configuration.
     ///
-    /// This function retrieves the current status of the high-pass filter reference mode from the `hp_ref_mode` field in the `CTRL7` register.
+    /// This function reads the `CTRL1` .. `CTRL6` registers and the `FIFO_CTRL` .. `FREE_FALL` registers with one
+    /// burst each, then the `CTRL7` register, and decodes them as the individual getters do, see [`summary`].
+    /// The burst reads need the register address auto-increment, see [`Self::auto_increment_set`]; they also
+    /// reload the copies of the pad routing, see [`Self::int1_routes_shadow`].
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current value of the `hp_ref_mode` field:
-    ///   - `0`: High-pass filter reference mode is disabled.
-    ///   - `1`: High-pass filter reference mode is enabled.
+    /// - `Ok(SensorState)`: The configuration.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn reference_mode_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(Ctrl7::read(self)?.hp_ref_mode())
+    pub fn sensor_state_get(&mut self) -> Result<summary::SensorState, Error<B::Error>> {
+        let mut ctrl = [0; summary::CTRL_LEN];
+        let mut func = [0; summary::FUNC_LEN];
+        self.read_from_register(Reg::Ctrl1 as u8, &mut ctrl)?;
+        self.read_from_register(Reg::FifoCtrl as u8, &mut func)?;
+        let ctrl7 = Ctrl7::read(self)?;
+        Ok(summary::SensorState::new(ctrl, func, ctrl7.into_bits()))
     }
 
-    /// Set the SPI serial interface mode.
+    /// Save the device configuration.
     ///
-    /// This function configures the SPI serial interface mode by updating the `sim` field in the `CTRL2` register.
-    /// The SPI interface can operate in either 4-wire or 3-wire mode.
+    /// This function reads every configuration register listed in [`CONFIG_REGS`].
+    ///
+    /// ### Returns
+    /// - `Ok(ConfigSnapshot)`: The saved configuration.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn config_snapshot_get(&mut self) -> Result<ConfigSnapshot, Error<B::Error>> {
+        let mut snapshot = ConfigSnapshot::default();
+        for (reg, val) in CONFIG_REGS.iter().zip(snapshot.regs.iter_mut()) {
+            let mut buf = [0];
+            self.read_from_register(*reg as u8, &mut buf)?;
+            *val = buf[0];
+        }
+        Ok(snapshot)
+    }
+
+    /// Restore a saved device configuration.
+    ///
+    /// This function writes back every register of the snapshot phase by phase, see [`phase`]: the data rate is
+    /// written last. The `SOFT_RESET`, `BOOT` and `SLP_MODE_1` command bits are not written back.
+    /// If a write fails, the registers already written are restored, see [`Self::config_snapshot_apply_checked`].
     ///
     /// ### Arguments
-    /// - `val`: A [`Sim`] value representing the desired SPI mode:
-    ///   - `Spi4Wire`: 4-wire SPI mode (default).
-    ///   - `Spi3Wire`: 3-wire SPI mode.
+    /// - `snapshot`: The configuration to restore.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn spi_mode_set(&mut self, val: Sim) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_sim(val as u8);
-        ctrl2.write(self)
+    pub fn config_snapshot_apply(
+        &mut self,
+        snapshot: &ConfigSnapshot,
+    ) -> Result<(), Error<B::Error>> {
+        Ok(self.config_snapshot_apply_checked(snapshot)?)
     }
 
-    /// Get the SPI serial interface mode.
+    /// Apply a configuration blob.
     ///
-    /// This function retrieves the current SPI serial interface mode from the `sim` field in the `CTRL2` register.
+    /// This function decodes the blob, see [`ConfigBlob::decode`], then writes the configuration as
+    /// [`Self::config_snapshot_apply`] does: if a write fails, the registers already written are restored. A
+    /// rejected blob writes nothing.
+    ///
+    /// ### Arguments
+    /// - `blob`: The encoded configuration, see [`blob`] for the format.
     ///
     /// ### Returns
-    /// - `Ok(Sim)`: The current SPI mode as a [`Sim`] value:
-    ///   - `Spi4Wire`: 4-wire SPI mode (default).
-    ///   - `Spi3Wire`: 3-wire SPI mode.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn spi_mode_get(&mut self) -> Result<Sim, Error<B::Error>> {
-        Ok(Sim::try_from(Ctrl2::read(self)?.sim()).unwrap_or_default())
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Blob)`: If the blob is truncated, corrupted or of an unknown layout; nothing is written.
+    /// - `Err(Error::ScalingLocked)`: If the blob changes the locked scaling, see [`Self::strict_scaling_lock`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn apply_blob(&mut self, blob: &[u8]) -> Result<(), Error<B::Error>> {
+        let snapshot = ConfigBlob::decode(blob).map_err(Error::Blob)?;
+        self.config_snapshot_apply(&snapshot)
     }
 
-    /// Enable or disable the I²C interface.
+    /// Restore a saved device configuration, rolling back on failure.
     ///
-    /// This function configures the I²C interface by updating the `i2c_disable` field in the `CTRL2` register.
-    /// The I²C interface can be enabled or disabled based on the provided value.
+    /// This function reads the current value of every register listed in [`CONFIG_REGS`], then writes the snapshot
+    /// as [`Self::config_snapshot_apply`] does. If a write fails, the registers written so far and the failing one are
+    /// written back to their previous value, latest first, and the outcome is reported in the [`ApplyError`]; the
+    /// rollback goes on after a failed rollback write.
     ///
     /// ### Arguments
-    /// - `val`: A [`I2cDisable`] value representing the desired I²C interface state:
-    ///   - `I2cEnable`: Enable the I²C interface (default).
-    ///   - `I2cDisable`: Disable the I²C interface.
+    /// - `snapshot`: The configuration to restore.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn i2c_interface_set(&mut self, val: I2cDisable) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_i2c_disable(val as u8);
-        ctrl2.write(self)
+    /// - `Err(ApplyError)`: The bus error, the registers applied, rolled back and failing the rollback; all the
+    ///   lists are empty if the error happened while reading the current values, before any write.
+    pub fn config_snapshot_apply_checked(
+        &mut self,
+        snapshot: &ConfigSnapshot,
+    ) -> Result<(), ApplyError<B::Error>> {
+        let stash = self.config_snapshot_get().map_err(|error| ApplyError {
+            error,
+            applied: RegList::default(),
+            rolled_back: RegList::default(),
+            rollback_failed: RegList::default(),
+        })?;
+
+        let mut writes = [(Reg::Ctrl1, 0); CONFIG_REGS.len()];
+        for (slot, write) in writes.iter_mut().zip(snapshot.writes()) {
+            *slot = write;
+        }
+        self.apply_phases(&stash, None, &writes)?;
+        self.state.snapshot_applied(snapshot);
+        Ok(())
     }
 
-    /// Get the I²C interface state.
+    /// Move the device to another configuration with the fewest writes.
     ///
-    /// This function retrieves the current state of the I²C interface from the `i2c_disable` field in the `CTRL2` register.
+    /// This function writes the registers of the delta in order, see [`ConfigSnapshot::diff`]; nothing is read, the
+    /// device is expected to hold [`ConfigDelta::source`]. If a write fails, the registers written so far and the
+    /// failing one are written back to their value in the source configuration, latest first, as
+    /// [`Self::config_snapshot_apply_checked`] does: `CTRL1`, written first when the data rate is stopped, is then
+    /// rolled back last.
+    ///
+    /// ### Arguments
+    /// - `delta`: The writes to apply.
     ///
     /// ### Returns
-    /// - `Ok(I2cDisable)`: The current I²C interface state as a [`I2cDisable`] value:
-    ///   - `I2cEnable`: I²C interface is enabled (default).
-    ///   - `I2cDisable`: I²C interface is disabled.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn i2c_interface_get(&mut self) -> Result<I2cDisable, Error<B::Error>> {
-        Ok(I2cDisable::try_from(Ctrl2::read(self)?.i2c_disable()).unwrap_or_default())
+    /// - `Ok(())`: If the operation is successful; an empty delta does not access the bus.
+    /// - `Err(ApplyError)`: The error, e.g. [`Error::ScalingLocked`] or [`Error::Bus`], the registers applied, rolled
+    ///   back and failing the rollback.
+    pub fn apply_delta(&mut self, delta: &ConfigDelta) -> Result<(), ApplyError<B::Error>> {
+        let (stop, writes) = delta.phase_writes();
+        self.apply_phases(delta.source(), stop, writes)?;
+        self.state.snapshot_applied(delta.target());
+        Ok(())
     }
 
-    /// Configure the CS pull-up resistor.
+    /// Write configuration registers in the documented order.
     ///
-    /// This function configures the CS pull-up resistor by updating the `cs_pu_disc` field in the `CTRL2` register.
-    /// The pull-up resistor can be connected or disconnected based on the provided value.
+    /// This function reads the configuration registers, computes the writes moving them to the given values with
+    /// [`ConfigSnapshot::diff`] and applies them with [`Self::apply_delta`]: phase by phase, see [`phase`], whatever
+    /// the order of `writes`, with the data rate stopped first when the scaling of a running stream changes, and
+    /// rolled back if a write fails. A register already holding its value is not written, and the last value given
+    /// for a register wins. The `SOFT_RESET`, `BOOT` and `SLP_MODE_1` command bits are not written.
     ///
     /// ### Arguments
-    /// - `val`: A [`CsPuDisc`] value representing the desired CS pull-up configuration:
-    ///   - `PullUpConnect`: Connect the pull-up resistor (default).
-    ///   - `PullUpDisconnect`: Disconnect the pull-up resistor.
+    /// - `writes`: The registers and their values, in any order.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn cs_mode_set(&mut self, val: CsPuDisc) -> Result<(), Error<B::Error>> {
-        let mut ctrl2 = Ctrl2::read(self)?;
-        ctrl2.set_cs_pu_disc(val as u8);
-        ctrl2.write(self)
+    /// - `Err(ApplyError)`: The error, e.g. [`Error::ScalingLocked`] or [`Error::Bus`], the registers applied, rolled
+    ///   back and failing the rollback; [`ConfigError::NotConfigRegister`] if a register has no phase, then nothing
+    ///   is accessed.
+    pub fn ordered_apply(&mut self, writes: &[(Reg, u8)]) -> Result<(), ApplyError<B::Error>> {
+        let unwritten = |error| ApplyError {
+            error,
+            applied: RegList::default(),
+            rolled_back: RegList::default(),
+            rollback_failed: RegList::default(),
+        };
+        if let Some((reg, _)) = writes.iter().find(|(reg, _)| Phase::of(*reg).is_none()) {
+            return Err(unwritten(Error::InvalidConfig(
+                ConfigError::NotConfigRegister { reg: *reg },
+            )));
+        }
+
+        let source = self.config_snapshot_get().map_err(unwritten)?;
+        let mut target = source;
+        for (reg, val) in writes {
+            target.set(*reg, *val);
+        }
+        self.apply_delta(&source.diff(&target))
+    }
+
+    /// Write configuration registers phase by phase, see [`phase`], rolling back to `source` if a write fails.
+    ///
+    /// `stop` is the `CTRL1` value stopping the data rate before the first phase, if any. A register written twice
+    /// is listed once in the applied registers.
+    fn apply_phases(
+        &mut self,
+        source: &ConfigSnapshot,
+        stop: Option<u8>,
+        writes: &[(Reg, u8)],
+    ) -> Result<(), ApplyError<B::Error>> {
+        let mut applied = RegList::default();
+        let stop = stop.map(|val| (Reg::Ctrl1, val));
+        for (reg, val) in stop.into_iter().chain(phase::ordered(writes)) {
+            if let Err(error) = self.write_to_register(reg as u8, &[val]) {
+                let (rolled_back, rollback_failed) = self.config_rollback(source, &applied, reg);
+                return Err(ApplyError {
+                    error,
+                    applied,
+                    rolled_back,
+                    rollback_failed,
+                });
+            }
+            if !applied.contains(reg) {
+                applied.push(reg);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write back the saved value of `failed` and of the `applied` registers, latest first.
+    ///
+    /// A failed register already in `applied` is only written back at its place in the list.
+    ///
+    /// Returns the registers rolled back and those whose write failed.
+    fn config_rollback(
+        &mut self,
+        stash: &ConfigSnapshot,
+        applied: &RegList,
+        failed: Reg,
+    ) -> (RegList, RegList) {
+        let mut rolled_back = RegList::default();
+        let mut rollback_failed = RegList::default();
+        let failed = Some(failed).filter(|reg| !applied.contains(*reg));
+        for reg in failed
+            .into_iter()
+            .chain(applied.as_slice().iter().rev().copied())
+        {
+            let val = ConfigSnapshot::restore_value(reg, stash.get(reg).unwrap_or_default());
+            match self.write_to_register(reg as u8, &[val]) {
+                Ok(()) => rolled_back.push(reg),
+                Err(_) => rollback_failed.push(reg),
+            }
+        }
+        (rolled_back, rollback_failed)
     }
 
-    /// Get the CS pull-up resistor configuration.
+    /// Check whether the device went back to its default configuration.
     ///
-    /// This function retrieves the current CS pull-up resistor configuration from the `cs_pu_disc` field in the `CTRL2` register.
+    /// A supply brown-out resets the device, but not the host: the data rate silently falls back to power-down.
+    /// This function reads the `CTRL1` register and reports a reset when it holds its default value (`0x00`)
+    /// while the data rate last written or read through the driver was running, by a typed setter,
+    /// [`Self::write_to_register`] or [`Self::config_snapshot_apply`] alike. A driver created on a device already
+    /// running learns its data rate from the first read of `CTRL1`, this one included. When `CTRL1` reads `0x00` or
+    /// `0xFF`, the bus is checked with [`Self::bus_sanity_check`] first, so that a stuck data line is not taken for a
+    /// reset, or for a running configuration.
     ///
     /// ### Returns
-    /// - `Ok(CsPuDisc)`: The current CS pull-up configuration as a [`CsPuDisc`] value:
-    ///   - `PullUpConnect`: Pull-up resistor is connected (default).
-    ///   - `PullUpDisconnect`: Pull-up resistor is disconnected.
+    /// - `Ok(true)`: If the device lost its configuration; the driver-side register copies are invalidated, see
+    ///   [`Self::invalidate_cache`]. The read marks the data rate as off: the reset is reported once.
+    /// - `Ok(false)`: If the configuration is in place, or the data rate was last known off or never known.
+    /// - `Err(Error::UnexpectedValue)`: If the bus is stuck, with the value every register reads, `0xFF` or `0x00`.
+    /// - `Err(Error::WhoAmIError)`: If the bus check reads another identifier.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn cs_mode_get(&mut self) -> Result<CsPuDisc, Error<B::Error>> {
-        Ok(CsPuDisc::try_from(Ctrl2::read(self)?.cs_pu_disc()).unwrap_or_default())
+    pub fn brownout_check(&mut self) -> Result<bool, Error<B::Error>> {
+        let running = self.state.odr_running();
+        if running == Some(false) {
+            return Ok(false);
+        }
+        let known = self.state.ctrl1_odr;
+        let ctrl1 = Ctrl1::read(self)?.into_bits();
+        if matches!(ctrl1, 0x00 | 0xFF) {
+            if let Err(err) = self.bus_sanity_require() {
+                // The value read is not the device's
+                self.state.ctrl1_odr = known;
+                return Err(err);
+            }
+        }
+        let reset = ctrl1 == 0x00 && running == Some(true);
+        if reset {
+            self.invalidate_cache();
+        }
+        Ok(reset)
     }
 
-    /// Interrupt active-high/low.
+    /// Restore the configuration after a brown-out.
     ///
-    /// # Arguments
+    /// This function checks the device identifier, restores the interface settings of the `CTRL2` register,
+    /// applies the snapshot, see [`Self::config_snapshot_apply`], and waits one output data period so that
+    /// the first sample is available.
     ///
-    /// * `val`: change the values of h_lactive in reg CTRL3.
+    /// ### Arguments
+    /// - `snapshot`: The configuration to restore.
     ///
-    /// # Returns
+    /// ### Returns
+    /// - `Ok(u32)`: The time spent waiting, in microseconds.
+    /// - `Err(Error::WhoAmIError)`: If the device does not answer with the expected identifier.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn power_cycle_recover(
+        &mut self,
+        snapshot: &ConfigSnapshot,
+    ) -> Result<u32, Error<B::Error>> {
+        let id = self.device_id_get()?;
+        if id != ID {
+            return Err(Error::WhoAmIError(id));
+        }
+
+        let ctrl2 = snapshot.get(Reg::Ctrl2).unwrap_or_default();
+        self.write_to_register(
+            Reg::Ctrl2 as u8,
+            &[ConfigSnapshot::restore_value(Reg::Ctrl2, ctrl2)],
+        )?;
+        self.config_snapshot_apply(snapshot)?;
+
+        let settle_us = snapshot.data_rate().period_us().unwrap_or(0);
+        self.tim.wait_us(settle_us);
+        Ok(settle_us)
+    }
+
+    /// Enable or disable the automatic brown-out recovery.
     ///
-    /// * `Result`
-    ///     * `()`
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn pin_polarity_set(&mut self, val: HLactive) -> Result<(), Error<B::Error>> {
-        let mut ctrl3 = Ctrl3::read(self)?;
-        ctrl3.set_h_lactive(val as u8);
-        ctrl3.write(self)
+    /// When enabled, [`Self::flag_data_ready_get`] checks for a brown-out whenever no new data is available,
+    /// and restores `snapshot` if the device lost its configuration.
+    ///
+    /// ### Arguments
+    /// - `snapshot`: The configuration to restore, or `None` to disable the automatic recovery (default).
+    pub fn brownout_recovery_set(&mut self, snapshot: Option<ConfigSnapshot>) {
+        self.state.brownout_recovery = snapshot;
     }
 
-    /// Interrupt active-high/low.
+    /// Read every register.
     ///
-    /// # Returns
+    /// This function reads the registers listed in [`Reg::ALL`] one at a time, in that order, until `out` is full.
+    /// Reading the source registers clears the latched interrupts.
     ///
-    /// * `Result`
-    ///     * `HLactive`: Get the values of h_lactive in reg CTRL3.
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn pin_polarity_get(&mut self) -> Result<HLactive, Error<B::Error>> {
-        Ok(HLactive::try_from(Ctrl3::read(self)?.h_lactive()).unwrap_or_default())
+    /// ### Arguments
+    /// - `out`: Destination of the register and its value; `Reg::ALL.len()` entries hold every register.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of registers read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn register_dump(&mut self, out: &mut [(Reg, u8)]) -> Result<usize, Error<B::Error>> {
+        for (slot, reg) in out.iter_mut().zip(Reg::ALL) {
+            let mut buf = [0];
+            self.read_from_register(reg as u8, &mut buf)?;
+            *slot = (reg, buf[0]);
+        }
+        Ok(out.len().min(Reg::ALL.len()))
     }
 
-    /// Latched/pulsed interrupt.
-    ///
-    /// # Arguments
-    ///
-    /// * `val`: change the values of lir in reg CTRL3.
+    /// Read every register into a `heapless::Vec`.
     ///
-    /// # Returns
+    /// This function is [`Self::register_dump`] with an owned result: with `N` lower than `Reg::ALL.len()` only the
+    /// first `N` registers are read, and the length of the vector tells how many. `N` must not be 0.
     ///
-    /// * `Result`
-    ///     * `()`
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn int_notification_set(&mut self, val: Lir) -> Result<(), Error<B::Error>> {
-        let mut ctrl3 = Ctrl3::read(self)?;
-        ctrl3.set_lir(val as u8);
-        ctrl3.write(self)
+    /// ### Returns
+    /// - `Ok(heapless::Vec<(Reg, u8), N>)`: The registers and their values.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    #[cfg(feature = "heapless")]
+    pub fn register_dump_vec<const N: usize>(
+        &mut self,
+    ) -> Result<heapless::Vec<(Reg, u8), N>, Error<B::Error>> {
+        const { assert!(N >= 1, "the vector must hold at least one register") };
+        let mut buf = [(Reg::WhoAmI, 0); Reg::ALL.len()];
+        let len = N.min(buf.len());
+        let count = self.register_dump(buf.get_mut(..len).unwrap_or_default())?;
+        Ok(owned(buf.get(..count).unwrap_or_default()))
     }
 
-    /// Latched/pulsed interrupt.
+    /// Read the whole register map.
     ///
-    /// # Arguments
-    ///
-    /// * `val`: Get the values of lir in reg CTRL3.
+    /// This function reads the `OUT_T_L` .. `WHO_AM_I`, `CTRL1` .. `OUT_Z_H` and `FIFO_CTRL` .. `CTRL7` registers with
+    /// one burst each into `image`, at their address, and clears the reserved addresses. The bursts need the register
+    /// address auto-increment, see [`Self::auto_increment_set`]; the second one stops at `OUT_Z_H`, where the address
+    /// rolls back to `OUT_X_L` while the FIFO is enabled. Reading the output registers pops a FIFO sample, and reading
+    /// the source registers clears the latched interrupts.
     ///
-    /// # Returns
+    /// ### Arguments
+    /// - `image`: Destination of the register map, indexed by register address.
     ///
-    /// * `Result`
-    ///     * `()`
-    pub fn int_notification_get(&mut self) -> Result<Lir, Error<B::Error>> {
-        Ok(Lir::try_from(Ctrl3::read(self)?.lir()).unwrap_or_default())
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn registers_dump(&mut self, image: &mut [u8; REG_MAP_LEN]) -> Result<(), Error<B::Error>> {
+        image.fill(0);
+        for (first, last) in [
+            (Reg::OutTL, Reg::WhoAmI),
+            (Reg::Ctrl1, Reg::OutZH),
+            (Reg::FifoCtrl, Reg::Ctrl7),
+        ] {
+            let burst = image
+                .get_mut(first as usize..=last as usize)
+                .unwrap_or_default();
+            self.read_from_register(first as u8, burst)?;
+        }
+        Ok(())
     }
 
-    /// Push-pull/open drain selection on interrupt pads.
-    ///
-    /// # Arguments
+    /// Restore the configuration of a register map.
     ///
-    /// * `val`: change the values of pp_od in reg CTRL3.
+    /// This function writes back the configuration registers of `image`, those listed in [`CONFIG_REGS`], as
+    /// [`Self::config_snapshot_apply`] does: phase by phase, see [`phase`], the interface settings of `CTRL2` first
+    /// and the data rate last, with the registers already written restored if a write fails. The read-only registers
+    /// and the reserved addresses are never written, nor the `SOFT_RESET`, `BOOT` and `SLP_MODE_1` command bits. See
+    /// [`ConfigSnapshot::from_image`] to keep the configuration without the rest of the map.
     ///
-    /// # Returns
+    /// ### Arguments
+    /// - `image`: The register map, indexed by register address, e.g. read by [`Self::registers_dump`].
     ///
-    /// * `Result`
-    ///     * `()`
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn pin_mode_set(&mut self, val: PpOd) -> Result<(), Error<B::Error>> {
-        let mut ctrl3 = Ctrl3::read(self)?;
-        ctrl3.set_pp_od(val as u8);
-        ctrl3.write(self)
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn registers_restore(&mut self, image: &[u8; REG_MAP_LEN]) -> Result<(), Error<B::Error>> {
+        self.config_snapshot_apply(&ConfigSnapshot::from_image(image))
     }
 
-    /// Push-pull/open drain selection on interrupt pads.
+    /// Print the decoded content of every register.
     ///
-    /// # Returns
+    /// This function reads the registers listed in [`Reg::ALL`] one at a time and writes one line per register,
+    /// its address followed by [`Reg::decode`], e.g. `0x25 CTRL6 { bw_filt: 0, fs: 0, fds: 0, low_noise: 0 }`.
+    /// Reading the source registers clears the latched interrupts. Errors of `out` are ignored.
     ///
-    /// * `Result`
-    ///     * `PpOd`: Get the values of pp_od in reg CTRL3.
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn pin_mode_get(&mut self) -> Result<PpOd, Error<B::Error>> {
-        Ok(PpOd::try_from(Ctrl3::read(self)?.pp_od()).unwrap_or_default())
-    }
-
-    /// Select the signal that need to route on int1 pad.
-    pub fn pin_int1_route_set(&mut self, val: &Ctrl4Int1PadCtrl) -> Result<(), Error<B::Error>> {
-        let ctrl5 = Ctrl5Int2PadCtrl::read(self)?;
-        let mut ctrl7: Ctrl7 = Ctrl7::read(self)?;
-
-        if (ctrl5.int2_sleep_state()
-            | ctrl5.int2_sleep_chg()
-            | val.int1_tap()
-            | val.int1_ff()
-            | val.int1_wu()
-            | val.int1_single_tap()
-            | val.int1_6d())
-            != 0
-        {
-            ctrl7.set_interrupts_enable(PROPERTY_ENABLE);
-        } else {
-            ctrl7.set_interrupts_enable(PROPERTY_DISABLE);
+    /// ### Arguments
+    /// - `out`: The sink of the dump.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If every register was read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn dump_pretty(&mut self, out: &mut impl core::fmt::Write) -> Result<(), Error<B::Error>> {
+        for reg in Reg::ALL {
+            let mut buf = [0];
+            self.read_from_register(reg as u8, &mut buf)?;
+            let _ = writeln!(
+                out,
+                "0x{:02x} {}",
+                reg as u8,
+                Reg::decode(reg as u8, buf[0])
+            );
         }
-
-        val.write(self)?;
-        ctrl7.write(self)
+        Ok(())
     }
 
-    /// Select the signal that need to route on int1 pad.
-    pub fn pin_int1_route_get(&mut self) -> Result<Ctrl4Int1PadCtrl, Error<B::Error>> {
-        Ctrl4Int1PadCtrl::read(self)
-    }
-
-    /// Select the signal that need to route on int2 pad.
-    pub fn pin_int2_route_set(&mut self, val: &Ctrl5Int2PadCtrl) -> Result<(), Error<B::Error>> {
-        let ctrl4 = Ctrl4Int1PadCtrl::read(self)?;
-        let mut ctrl7 = Ctrl7::read(self)?;
+    /// Build a telemetry frame.
+    ///
+    /// This function reads the `STATUS_DUP` .. `ALL_INT_SRC` registers and the `OUT_X_L` .. `OUT_Z_H` registers
+    /// with one burst each, and tags them with the current full scale and operating mode.
+    /// The layout is described in the [`telemetry`] module; decode the frame with [`telemetry::telemetry_decode`].
+    /// The burst reads need the register address auto-increment, see [`Self::auto_increment_set`].
+    ///
+    /// ### Arguments
+    /// - `buf`: The frame buffer.
+    ///
+    /// ### Returns
+    /// - `Ok(usize)`: The number of bytes written ([`telemetry::FRAME_LEN`]).
+    /// - `Err(Error::InvalidConfig)`: If the FIFO path is active, see [`Self::allow_mixed_data_paths`]; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    #[cfg(feature = "telemetry")]
+    pub fn telemetry_frame(
+        &mut self,
+        buf: &mut [u8; telemetry::FRAME_LEN],
+    ) -> Result<usize, Error<B::Error>> {
+        self.direct_path_check()?;
+        let (sources, rest) = buf.split_at_mut(telemetry::SOURCES_LEN);
+        let (sample, _) = rest.split_at_mut(batch::SAMPLE_BYTES);
 
-        if (val.int2_sleep_state()
-            | val.int2_sleep_chg()
-            | ctrl4.int1_tap()
-            | ctrl4.int1_ff()
-            | ctrl4.int1_wu()
-            | ctrl4.int1_single_tap()
-            | ctrl4.int1_6d())
-            != 0
-        {
-            ctrl7.set_interrupts_enable(PROPERTY_ENABLE);
-        } else {
-            ctrl7.set_interrupts_enable(PROPERTY_DISABLE);
-        }
+        self.read_from_register(Reg::StatusDup as u8, sources)?;
+        self.read_from_register(Reg::OutXL as u8, sample)?;
+        let fs = self.full_scale_get()?;
+        let mode = self.power_mode_get()?;
+        buf[telemetry::FRAME_LEN - 1] = telemetry::tag(fs, mode);
 
-        val.write(self)?;
-        ctrl7.write(self)
+        Ok(telemetry::FRAME_LEN)
     }
 
-    /// Select the signal that need to route on int2 pad.
+    /// Set the X-axis user offset correction.
     ///
-    /// # Returns
+    /// This function configures the X-axis user offset correction value in the `X_OFS_USR` register.
+    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// With an axis remapping, `val` applies to the board X axis, see [`Self::axis_remap_set`].
     ///
-    /// * `Result`
-    ///     * `Ctrl5Int2PadCtrl`: register CTRL5_INT2_PAD_CTRL.
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn pin_int2_route_get(&mut self) -> Result<Ctrl5Int2PadCtrl, Error<B::Error>> {
-        Ctrl5Int2PadCtrl::read(self)
+    /// ### Arguments
+    /// - `val`: The X-axis user offset correction value to set.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
+    pub fn usr_offset_x_set(&mut self, val: i8) -> Result<(), Error<B::Error>> {
+        self.usr_offset_board_set(Axis::X, val)
     }
 
-    /// All interrupt signals become available on INT1 pin.
+    /// Get the X-axis user offset correction.
     ///
-    /// # Arguments
+    /// This function retrieves the X-axis user offset correction value from the `X_OFS_USR` register.
+    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// With an axis remapping, the value applies to the board X axis, see [`Self::axis_remap_set`].
     ///
-    /// * `val`: Change the values of int2_on_int1 in reg CTRL_REG7.
+    /// ### Returns
+    /// - `Ok(i8)`: The X-axis user offset correction value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn usr_offset_x_get(&mut self) -> Result<i8, Error<B::Error>> {
+        self.usr_offset_board_get(Axis::X)
+    }
+
+    /// Set the Y-axis user offset correction.
     ///
-    /// # Returns
+    /// This function configures the Y-axis user offset correction value in the `Y_OFS_USR` register.
+    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// With an axis remapping, `val` applies to the board Y axis, see [`Self::axis_remap_set`].
     ///
-    /// * `Result`
-    ///     * `()`
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn all_on_int1_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = Ctrl7::read(self)?;
-        reg.set_int2_on_int1(val);
-        reg.write(self)
+    /// ### Arguments
+    /// - `val`: The Y-axis user offset correction value to set.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
+    pub fn usr_offset_y_set(&mut self, val: i8) -> Result<(), Error<B::Error>> {
+        self.usr_offset_board_set(Axis::Y, val)
     }
 
-    /// All interrupt signals become available on INT1 pin.
+    /// Get the Y-axis user offset correction.
     ///
-    /// # Returns
+    /// This function retrieves the Y-axis user offset correction value from the `Y_OFS_USR` register.
+    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// With an axis remapping, the value applies to the board Y axis, see [`Self::axis_remap_set`].
     ///
-    /// * `Result`
-    ///     * `u8`: change the values of int2_on_int1 in reg CTRL_REG7.
-    ///     * `Err`: Returns an error if the operation fails.
-    pub fn all_on_int1_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(Ctrl7::read(self)?.int2_on_int1())
+    /// ### Returns
+    /// - `Ok(i8)`: The Y-axis user offset correction value.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn usr_offset_y_get(&mut self) -> Result<i8, Error<B::Error>> {
+        self.usr_offset_board_get(Axis::Y)
     }
 
-    /// Set the wake-up threshold.
+    /// Set the Z-axis user offset correction.
     ///
-    /// This function configures the wake-up threshold by updating the `wk_ths` field in the `WAKE_UP_THS` register.
-    /// The threshold is expressed in LSB, where 1 LSB = FS_XL / 64.
+    /// This function configures the Z-axis user offset correction value in the `Z_OFS_USR` register.
+    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// With an axis remapping, `val` applies to the board Z axis, see [`Self::axis_remap_set`].
     ///
     /// ### Arguments
-    /// - `val`: The desired wake-up threshold value.
+    /// - `val`: The Z-axis user offset correction value to set.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn wkup_threshold_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = WakeUpThs::read(self)?;
-        reg.set_wk_ths(val);
-        reg.write(self)
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
+    pub fn usr_offset_z_set(&mut self, val: i8) -> Result<(), Error<B::Error>> {
+        self.usr_offset_board_set(Axis::Z, val)
     }
 
-    /// Get the wake-up threshold.
+    /// Get the Z-axis user offset correction.
     ///
-    /// This function retrieves the current wake-up threshold from the `wk_ths` field in the `WAKE_UP_THS` register.
-    /// The threshold is expressed in LSB, where 1 LSB = FS_XL / 64.
+    /// This function retrieves the Z-axis user offset correction value from the `Z_OFS_USR` register.
+    /// The value's weight depends on the `USR_OFF_W` bit in the `CTRL7` register.
+    /// With an axis remapping, the value applies to the board Z axis, see [`Self::axis_remap_set`].
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current wake-up threshold value.
+    /// - `Ok(i8)`: The Z-axis user offset correction value.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn wkup_threshold_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(WakeUpThs::read(self)?.wk_ths())
+    pub fn usr_offset_z_get(&mut self) -> Result<i8, Error<B::Error>> {
+        self.usr_offset_board_get(Axis::Z)
     }
 
-    /// Set the wake-up duration event.
+    /// Set the user offset corrections of the three axes.
     ///
-    /// This function configures the wake-up duration by updating the `wake_dur` field in the `WAKE_UP_DUR` register.
-    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    /// This function writes the `X_OFS_USR`, `Y_OFS_USR` and `Z_OFS_USR` registers. With an axis remapping the
+    /// values apply to the board X, Y and Z axes: each one is written to the register of the sensor axis the
+    /// board axis reads, negated if that axis is reversed (`-128` then saturates to `127`).
     ///
     /// ### Arguments
-    /// - `val`: The desired wake-up duration value.
+    /// - `val`: The X, Y and Z offsets, in the board frame.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn wkup_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = WakeUpDur::read(self)?;
-        reg.set_wake_dur(val);
-        reg.write(self)
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the write operation.
+    pub fn usr_offsets_set(&mut self, val: [i8; 3]) -> Result<(), Error<B::Error>> {
+        let [x, y, z] = self.state.axis_remap.offsets_to_sensor(val);
+        XOfsUsr::from_bits(x.cast_unsigned()).write(self)?;
+        YOfsUsr::from_bits(y.cast_unsigned()).write(self)?;
+        ZOfsUsr::from_bits(z.cast_unsigned()).write(self)
     }
 
-    /// Get the wake-up duration event.
+    /// Get the user offset corrections of the three axes.
     ///
-    /// This function retrieves the current wake-up duration from the `wake_dur` field in the `WAKE_UP_DUR` register.
-    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    /// This function reads the `X_OFS_USR`, `Y_OFS_USR` and `Z_OFS_USR` registers and converts them to the board
+    /// frame, see [`Self::usr_offsets_set`].
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current wake-up duration value.
+    /// - `Ok([i8; 3])`: The X, Y and Z offsets, in the board frame.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn wkup_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(WakeUpDur::read(self)?.wake_dur())
+    pub fn usr_offsets_get(&mut self) -> Result<[i8; 3], Error<B::Error>> {
+        let sensor = [
+            XOfsUsr::read(self)?.x_ofs_usr(),
+            YOfsUsr::read(self)?.y_ofs_usr(),
+            ZOfsUsr::read(self)?.z_ofs_usr(),
+        ];
+        Ok(self.state.axis_remap.offsets_to_board(sensor))
+    }
+
+    /// Write the user offset of a board axis to the register of the sensor axis it reads.
+    fn usr_offset_board_set(&mut self, board: Axis, val: i8) -> Result<(), Error<B::Error>> {
+        let sensor = self.state.axis_remap.sensor_axis(board);
+        let val = if sensor.is_negative() {
+            val.saturating_neg()
+        } else {
+            val
+        };
+        match sensor.axis() {
+            Axis::X => XOfsUsr::from_bits(val.cast_unsigned()).write(self),
+            Axis::Y => YOfsUsr::from_bits(val.cast_unsigned()).write(self),
+            Axis::Z => ZOfsUsr::from_bits(val.cast_unsigned()).write(self),
+        }
+    }
+
+    /// Read the user offset of a board axis from the register of the sensor axis it reads.
+    fn usr_offset_board_get(&mut self, board: Axis) -> Result<i8, Error<B::Error>> {
+        let sensor = self.state.axis_remap.sensor_axis(board);
+        let val = match sensor.axis() {
+            Axis::X => XOfsUsr::read(self)?.x_ofs_usr(),
+            Axis::Y => YOfsUsr::read(self)?.y_ofs_usr(),
+            Axis::Z => ZOfsUsr::read(self)?.z_ofs_usr(),
+        };
+        Ok(if sensor.is_negative() {
+            val.saturating_neg()
+        } else {
+            val
+        })
     }
 
-    /// Set the data sent to the wake-up interrupt function.
+    /// Set the weight of XL user offset bits.
     ///
-    /// This function configures the data source for the wake-up interrupt function by updating the `usr_off_on_wu` field in the `CTRL7` register.
-    /// The data source can be either high-pass filtered data or user offset data.
+    /// This function configures the weight of the user offset bits in the `X_OFS_USR`, `Y_OFS_USR`, and `Z_OFS_USR` registers by updating the `usr_off_w` field in the `CTRL7` register.
     ///
     /// ### Arguments
-    /// - `val`: A [`UsrOffOnWu`] value representing the desired data source:
-    ///   - `HpFeed`: High-pass filtered data (default).
-    ///   - `UserOffsetFeed`: User offset data.
+    /// - `val`: A [`UsrOffW`] value representing the desired weight:
+    ///   - `Lsb977ug`: 977 μg/LSB (default).
+    ///   - `Lsb15mg6`: 15.6 mg/LSB.
+    ///
+    /// When the wake-up function is fed with offset-corrected data ([`UsrOffOnWu::UserOffsetFeed`]), the new weight
+    /// moves the wake-up trigger point: the function then returns [`Warning::WakeupThresholdShifted`].
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn wkup_feed_data_set(&mut self, val: UsrOffOnWu) -> Result<(), Error<B::Error>> {
-        let mut reg = Ctrl7::read(self)?;
-        reg.set_usr_off_on_wu(val as u8);
-        reg.write(self)
+    /// - `Ok(None)`: If the operation is successful.
+    /// - `Ok(Some(Warning::WakeupThresholdShifted))`: If the weight changed while the wake-up function uses the offsets.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation..
+    pub fn offset_weight_set(&mut self, val: UsrOffW) -> Result<Option<Warning>, Error<B::Error>> {
+        let mut ctrl7 = Ctrl7::read(self)?;
+        let shifted = ctrl7.usr_off_on_wu() == UsrOffOnWu::UserOffsetFeed as u8
+            && ctrl7.usr_off_w() != val as u8;
+        ctrl7.set_usr_off_w(val as u8);
+        ctrl7.write(self)?;
+
+        Ok(shifted.then_some(Warning::WakeupThresholdShifted))
     }
 
-    /// Get the data sent to the wake-up interrupt function.
+    /// Get the weight of XL user offset bits.
     ///
-    /// This function retrieves the current data source for the wake-up interrupt function from the `usr_off_on_wu` field in the `CTRL7` register.
+    /// This function retrieves the weight of the user offset bits from the `usr_off_w` field in the `CTRL7` register.
     ///
     /// ### Returns
-    /// - `Ok(UsrOffOnWu)`: The current data source as a [`UsrOffOnWu`] value:
-    ///   - `HpFeed`: High-pass filtered data (default).
-    ///   - `UserOffsetFeed`: User offset data.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn wkup_feed_data_get(&mut self) -> Result<UsrOffOnWu, Error<B::Error>> {
-        Ok(UsrOffOnWu::try_from(Ctrl7::read(self)?.usr_off_on_wu()).unwrap_or_default())
+    /// - `Ok(UsrOffW)`: The current weight of the user offset bits:
+    ///   - `Lsb977ug`: 977 μg/LSB (default).
+    ///   - `Lsb15mg6`: 15.6 mg/LSB.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation..
+    pub fn offset_weight_get(&mut self) -> Result<UsrOffW, Error<B::Error>> {
+        Ok(UsrOffW::try_from(Ctrl7::read(self)?.usr_off_w()).unwrap_or_default())
     }
 
-    /// Configure activity/inactivity or stationary/motion detection.
+    /// Get the raw temperature data.
     ///
-    /// This function configures the activity/inactivity or stationary/motion detection by updating the `sleep_on` field in the `WAKE_UP_THS` register
-    /// and the `stationary` field in the `WAKE_UP_DUR` register.
+    /// This function retrieves the raw temperature data from the `OUT_T_L` and `OUT_T_H` registers.
+    /// The device left-justifies the 12-bit two's complement value in the 16-bit word; the returned value is
+    /// right-justified and sign-extended, ready for [`from_lsb_to_celsius`] (16 LSB/°C).
     ///
-    /// ### Arguments
-    /// - `val`: A [`SleepOn`] value representing the desired detection mode:
-    ///   - `NoDetection`: No detection (default).
-    ///   - `DetectActInact`: Detect activity/inactivity.
-    ///   - `DetectStatMotion`: Detect stationary/motion.
+    /// ### Returns
+    /// - `Ok(i16)`: The raw temperature data, from `-2048` to `2047`.
+    /// - `Err(Error::InvalidConfig)`: If the temperature policy is [`TempPolicy::Ignore`]; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn temperature_raw_get(&mut self) -> Result<i16, Error<B::Error>> {
+        self.temp_policy_check()?;
+        Ok(OutT::from_le_bytes(self.output_bytes(Reg::OutTL)?).temp())
+    }
+
+    /// Get the temperature in degrees Celsius.
+    ///
+    /// This function reads the raw temperature, see [`Self::temperature_raw_get`], and converts it with
+    /// [`from_lsb_to_celsius`].
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn act_mode_set(&mut self, val: SleepOn) -> Result<(), Error<B::Error>> {
-        let mut wake_up_ths = WakeUpThs::read(self)?;
-        let mut wake_up_dur: WakeUpDur = WakeUpDur::read(self)?;
+    /// - `Ok(f32)`: The temperature in degrees Celsius.
+    /// - `Err(Error::InvalidConfig)`: If the temperature policy is [`TempPolicy::Ignore`]; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn temperature_celsius_get(&mut self) -> Result<f32, Error<B::Error>> {
+        Ok(from_lsb_to_celsius(self.temperature_raw_get()?))
+    }
 
-        wake_up_ths.set_sleep_on(val.sleep_on());
-        wake_up_dur.set_stationary(val.stationary());
+    /// Get the 8-bit temperature data.
+    ///
+    /// This function retrieves the temperature from the `OUT_T` register, the high byte of the value of
+    /// [`Self::temperature_raw_get`] in a single-byte read. In the low-power modes only these 8 bits are
+    /// meaningful.
+    ///
+    /// ### Returns
+    /// - `Ok(i8)`: The raw temperature, 1 LSB/°C, ready for [`from_lsb8_to_celsius`].
+    /// - `Err(Error::InvalidConfig)`: If the temperature policy is [`TempPolicy::Ignore`]; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn temperature_8bit_get(&mut self) -> Result<i8, Error<B::Error>> {
+        self.temp_policy_check()?;
+        Ok(OutT8::read(self)?.temp())
+    }
 
-        wake_up_ths.write(self)?;
-        wake_up_dur.write(self)
+    /// Read the low and high bytes of a 16-bit output register.
+    fn output_bytes(&mut self, low: Reg) -> Result<[u8; 2], Error<B::Error>> {
+        let mut buf = [0; 2];
+        self.read_from_register(low as u8, &mut buf)?;
+        Ok(buf)
     }
 
-    /// Get the activity/inactivity or stationary/motion detection configuration.
+    /// Set the handling of the temperature sensor.
     ///
-    /// This function retrieves the current detection mode by reading the `sleep_on` field from the `WAKE_UP_THS` register
-    /// and the `stationary` field from the `WAKE_UP_DUR` register.
+    /// With [`TempPolicy::Ignore`], [`Self::temperature_raw_get`], [`Self::temperature_8bit_get`],
+    /// [`Self::flag_temp_data_ready_get`], [`Self::sample_get`] and a [`Self::pin_int2_route_set`] routing
+    /// `int2_drdy_t` return [`ConfigError::TemperatureIgnored`].
+    /// The policy is not checked against the current routing: clear `int2_drdy_t` first.
+    ///
+    /// ### Arguments
+    /// - `val`: The [`TempPolicy`] to use.
+    pub fn temp_policy_set(&mut self, val: TempPolicy) {
+        self.state.temp_policy = val;
+    }
+
+    /// Get the handling of the temperature sensor.
     ///
     /// ### Returns
-    /// - `Ok(SleepOn)`: The current detection mode as a [`SleepOn`] value:
-    ///   - `NoDetection`: No detection (default).
-    ///   - `DetectActInact`: Detect activity/inactivity.
-    ///   - `DetectStatMotion`: Detect stationary/motion.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn act_mode_get(&mut self) -> Result<SleepOn, Error<B::Error>> {
-        let wake_up_ths = WakeUpThs::read(self)?;
-        let wake_up_dur: WakeUpDur = WakeUpDur::read(self)?;
+    /// - `TempPolicy`: The current [`TempPolicy`].
+    pub fn temp_policy_get(&self) -> TempPolicy {
+        self.state.temp_policy
+    }
 
-        Ok(SleepOn::new(
-            wake_up_ths.sleep_on(),
-            wake_up_dur.stationary(),
-        ))
+    fn temp_policy_check(&self) -> Result<(), Error<B::Error>> {
+        match self.state.temp_policy {
+            TempPolicy::Enabled => Ok(()),
+            TempPolicy::Ignore => Err(Error::InvalidConfig(ConfigError::TemperatureIgnored)),
+        }
     }
 
-    /// Set the duration to enter sleep mode.
+    /// Set the axis remapping.
     ///
-    /// This function configures the duration required to enter sleep mode by updating the `sleep_dur` field in the `WAKE_UP_DUR` register.
-    /// The duration is expressed in LSB, where 1 LSB = 512 / ODR.
+    /// This function performs no bus access: the remapping is applied by the driver to the samples, the user
+    /// offsets and the decoded events, see [`remap`]. The embedded functions of the device keep working in the
+    /// sensor frame.
     ///
     /// ### Arguments
-    /// - `val`: The desired sleep duration value.
+    /// - `remap`: The remapping; [`AxisRemap::IDENTITY`] turns it off.
+    pub fn axis_remap_set(&mut self, remap: AxisRemap) {
+        self.state.axis_remap = remap;
+    }
+
+    /// Get the axis remapping.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn act_sleep_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = WakeUpDur::read(self)?;
-        reg.set_sleep_dur(val);
-        reg.write(self)
+    /// - `AxisRemap`: The current remapping, [`AxisRemap::IDENTITY`] by default.
+    pub fn axis_remap_get(&self) -> AxisRemap {
+        self.state.axis_remap
+    }
+
+    /// Get the raw acceleration data.
+    ///
+    /// This function retrieves the raw acceleration data for the X, Y, and Z axes from the `OUT_X_L`, `OUT_X_H`, `OUT_Y_L`, `OUT_Y_H`, `OUT_Z_L`, and `OUT_Z_H` registers.
+    /// The values are expressed as 16-bit words in two's complement format, in the board frame, see [`Self::axis_remap_set`].
+    /// The six registers are read with a single 6-byte burst, so that the three axes come from the same
+    /// sample with block data update enabled; the burst read needs the register address auto-increment,
+    /// see [`Self::auto_increment_set`].
+    ///
+    /// ### Returns
+    /// - `Ok([i16; 3])`: An array containing the raw acceleration data for the X, Y, and Z axes.
+    /// - `Err(Error::InvalidConfig)`: If the FIFO path is active, see [`Self::allow_mixed_data_paths`]; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn acceleration_raw_get(&mut self) -> Result<[i16; 3], Error<B::Error>> {
+        let raw = self.acceleration_raw_sensor_get()?;
+        Ok(self.state.axis_remap.raw(raw))
     }
 
-    /// Get the duration to enter sleep mode.
+    /// Get the raw acceleration data in the sensor frame, whatever the axis remapping.
     ///
-    /// This function retrieves the current sleep duration from the `sleep_dur` field in the `WAKE_UP_DUR` register.
-    /// The duration is expressed in LSB, where 1 LSB = 512 / ODR.
+    /// This function is [`Self::acceleration_raw_get`] without the axis remapping, see [`Self::axis_remap_set`].
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current sleep duration value.
+    /// - `Ok([i16; 3])`: The raw acceleration data on the X, Y and Z axes of the sensor.
+    /// - `Err(Error::InvalidConfig)`: If the FIFO path is active, see [`Self::allow_mixed_data_paths`]; nothing is read.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn act_sleep_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(WakeUpDur::read(self)?.sleep_dur())
+    pub fn acceleration_raw_sensor_get(&mut self) -> Result<[i16; 3], Error<B::Error>> {
+        self.direct_path_check()?;
+        let mut buf = [0; batch::SAMPLE_BYTES];
+        self.read_from_register(Reg::OutXL as u8, &mut buf)?;
+        Ok(batch::raw_from_bytes(&buf))
     }
 
-    /// Set the threshold for tap recognition on the X-axis.
+    /// Get the raw acceleration data at a fixed tick, without checking for new data.
     ///
-    /// This function configures the tap threshold for the X-axis by updating the `tap_thsx` field in the `TAP_THS_X` register.
+    /// This function reads the `OUT_X_L` .. `OUT_Z_H` registers with one 6-byte burst, as
+    /// [`Self::acceleration_raw_get`] does, for control loops that read the outputs at a fixed tick
+    /// rather than after [`Self::flag_data_ready_get`].
     ///
-    /// ### Arguments
-    /// - `val`: The desired tap threshold value for the X-axis.
+    /// Freshness contract: the sample may be the same as the previous read if no conversion completed in
+    /// between. With block data update enabled the registers hold the last complete sample until it is read;
+    /// without it a conversion may complete during the burst and the axes may mix two samples.
+    /// Call [`Self::assert_bdu_enabled`] once at initialization in applications relying on this function.
+    ///
+    /// Unlike [`Self::acceleration_raw_get`], the function does not check the data path either: on the
+    /// [`DataPath::Fifo`] path the read pops a FIFO sample, see [`Self::allow_mixed_data_paths`]. The axis
+    /// remapping applies, see [`Self::axis_remap_set`].
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_threshold_x_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsX::read(self)?;
-        reg.set_tap_thsx(val);
-        reg.write(self)
+    /// - `Ok([i16; 3])`: An array containing the raw acceleration data for the X, Y, and Z axes.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn acceleration_raw_unchecked_get(&mut self) -> Result<[i16; 3], Error<B::Error>> {
+        let mut buf = [0; batch::SAMPLE_BYTES];
+        self.read_from_register(Reg::OutXL as u8, &mut buf)?;
+        Ok(self.state.axis_remap.raw(batch::raw_from_bytes(&buf)))
     }
 
-    /// Get the threshold for tap recognition on the X-axis.
+    /// Assert that block data update is enabled, in debug builds.
     ///
-    /// This function retrieves the current tap threshold for the X-axis from the `tap_thsx` field in the `TAP_THS_X` register.
+    /// This function reads the `bdu` field in the `CTRL2` register and panics if it is not set, see
+    /// [`Self::acceleration_raw_unchecked_get`]. In release builds it does nothing and touches no bus.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current tap threshold value for the X-axis.
+    /// - `Ok(())`: If block data update is enabled, or in release builds.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_threshold_x_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsX::read(self)?.tap_thsx())
+    pub fn assert_bdu_enabled(&mut self) -> Result<(), Error<B::Error>> {
+        if cfg!(debug_assertions) {
+            let bdu = self.block_data_update_get()?;
+            debug_assert!(bdu, "block data update is disabled");
+        }
+        Ok(())
     }
 
-    /// Set the threshold for tap recognition on the Y-axis.
+    /// Get the acceleration data in mg.
     ///
-    /// This function configures the tap threshold for the Y-axis by updating the `tap_thsy` field in the `TAP_THS_Y` register.
+    /// This function reads the raw acceleration data, see [`Self::acceleration_raw_get`], and scales it
+    /// according to the current full scale and operating mode (12-bit resolution in low-power mode 1), see
+    /// [`sensitivity_mg_per_lsb`]. The full scale and the mode are read from the `CTRL1` and `CTRL6` registers
+    /// on every call, so the scaling follows a configuration written by any means.
+    /// The values are in the board frame, see [`Self::axis_remap_set`].
     ///
-    /// ### Arguments
-    /// - `val`: The desired tap threshold value for the Y-axis.
+    /// ### Returns
+    /// - `Ok([f32; 3])`: The acceleration on the X, Y and Z axes in mg.
+    /// - `Err(Error::InvalidConfig)`: If the FIFO path is active, see [`Self::allow_mixed_data_paths`]; nothing is read.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn acceleration_mg_get(&mut self) -> Result<[f32; 3], Error<B::Error>> {
+        self.direct_path_check()?;
+        let fs = self.full_scale_get()?;
+        let low_power_1 = self.power_mode_get()?.is_low_power_1();
+        let raw = self.acceleration_raw_sensor_get()?;
+        let mg = raw.map(|lsb| batch::raw_to_mg(lsb, fs, low_power_1));
+        Ok(self.state.axis_remap.mg(mg))
+    }
+
+    /// Get an acceleration and temperature sample.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_threshold_y_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsY::read(self)?;
-        reg.set_tap_thsy(val);
-        reg.write(self)
+    /// - `Ok(Sample)`: The acceleration in mg and the temperature in degrees Celsius.
+    /// - `Err(Error::InvalidConfig)`: If the temperature policy is [`TempPolicy::Ignore`]; nothing is read, use
+    ///   [`Self::acceleration_mg_get`] instead. Also if the FIFO path is active, see [`Self::allow_mixed_data_paths`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub fn sample_get(&mut self) -> Result<Sample, Error<B::Error>> {
+        self.temp_policy_check()?;
+        Ok(Sample {
+            acceleration_mg: self.acceleration_mg_get()?,
+            temperature_celsius: self.temperature_celsius_get()?,
+        })
     }
 
-    /// Get the threshold for tap recognition on the Y-axis.
+    /// Get the acceleration data as typed quantities.
     ///
-    /// This function retrieves the current tap threshold for the Y-axis from the `tap_thsy` field in the `TAP_THS_Y` register.
+    /// This function reads the acceleration in mg, see [`Self::acceleration_mg_get`], and converts it to
+    /// [`uom::si::f32::Acceleration`] (1 mg = 0.00980665 m/s²).
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current tap threshold value for the Y-axis.
+    /// - `Ok([Acceleration; 3])`: The acceleration on the X, Y and Z axes.
+    /// - `Err(Error::InvalidConfig)`: If the FIFO path is active, see [`Self::allow_mixed_data_paths`]; nothing is read.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_threshold_y_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsY::read(self)?.tap_thsy())
+    #[cfg(feature = "uom")]
+    pub fn acceleration_si_get(
+        &mut self,
+    ) -> Result<[uom::si::f32::Acceleration; 3], Error<B::Error>> {
+        Ok(self.acceleration_mg_get()?.map(sample::mg_to_acceleration))
     }
 
-    /// Set the axis priority for tap detection.
-    ///
-    /// This function configures the axis priority for tap detection by updating the `tap_prior` field in the `TAP_THS_Y` register.
+    /// Get the device ID.
     ///
-    /// ### Arguments
-    /// - `val`: A [`TapPrior`] value representing the desired axis priority:
-    ///   - `Xyz`: X > Y > Z (default).
-    ///   - `Yxz`: Y > X > Z.
-    ///   - `Xzy`: X > Z > Y.
-    ///   - `Zyx`: Z > Y > X.
-    ///   - `Yzx`: Y > Z > X.
-    ///   - `Zxy`: Z > X > Y.
+    /// This function retrieves the device ID from the `WHO_AM_I` register.
+    /// The device ID is a fixed value that identifies the IIS2DLPC sensor.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_axis_priority_set(&mut self, val: TapPrior) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsY::read(self)?;
-        reg.set_tap_prior(val as u8);
-        reg.write(self)
+    /// - `Ok(u8)`: The device ID (expected value: `0x44`).
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation..
+    pub fn device_id_get(&mut self) -> Result<u8, Error<B::Error>> {
+        let mut buff: [u8; 1] = [0];
+        self.read_from_register(Reg::WhoAmI as u8, &mut buff)?;
+        Ok(buff[0])
     }
 
-    /// Get the axis priority for tap detection.
+    /// Check that the bus reaches the device.
     ///
-    /// This function retrieves the current axis priority for tap detection from the `tap_prior` field in the `TAP_THS_Y` register.
+    /// A data line stuck high reads every register as `0xFF`, one stuck low as `0x00`, and most getters decode
+    /// either as a plausible configuration. This function reads the `WHO_AM_I` register and the `CTRL2` register,
+    /// whose self-clearing `boot` and `soft_reset` bits keep it from reading `0xFF` and whose `if_add_inc` bit,
+    /// set at reset and needed by the burst reads of the driver, from reading `0x00`: two registers reading the
+    /// same extreme value classify the bus. It performs two single-byte reads and never waits.
     ///
     /// ### Returns
-    /// - `Ok(TapPrior)`: The current axis priority as a [`TapPrior`] value.
+    /// - `Ok(BusSanity::Ok)`: If `WHO_AM_I` holds the identifier of the device.
+    /// - `Ok(BusSanity::StuckHigh)`: If both registers read `0xFF`.
+    /// - `Ok(BusSanity::StuckLow)`: If both registers read `0x00`.
+    /// - `Err(Error::WhoAmIError)`: If `WHO_AM_I` holds another value: another device, or a partly stuck bus.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_axis_priority_get(&mut self) -> Result<TapPrior, Error<B::Error>> {
-        Ok(TapPrior::try_from(TapThsY::read(self)?.tap_prior()).unwrap_or_default())
+    pub fn bus_sanity_check(&mut self) -> Result<BusSanity, Error<B::Error>> {
+        let id = self.device_id_get()?;
+        let mut ctrl2 = [0];
+        self.read_from_register(Reg::Ctrl2 as u8, &mut ctrl2)?;
+        BusSanity::classify(id, ctrl2[0])
     }
 
-    /// Set the threshold for tap recognition on the Z-axis.
-    ///
-    /// This function configures the tap threshold for the Z-axis by updating the `tap_thsz` field in the `TAP_THS_Z` register.
+    /// Run [`Self::bus_sanity_check`], reporting a stuck bus with the value every register reads.
+    fn bus_sanity_require(&mut self) -> Result<(), Error<B::Error>> {
+        self.bus_sanity_check()?.require()
+    }
+
+    /// Bring the device to a known configuration.
     ///
-    /// ### Arguments
-    /// - `val`: The desired tap threshold value for the Z-axis.
+    /// This function checks the bus and the device identifier with [`Self::bus_sanity_check`], performs a software
+    /// reset and polls the `soft_reset` field in the `CTRL2` register every [`RESET_POLL_US`], up to
+    /// [`RESET_MAX_POLLS`] times, until the reset is over. It then sets the `bdu` and `if_add_inc` fields in the
+    /// `CTRL2` register: block data update and the register address auto-increment needed by the burst reads.
+    /// The data rate is left at power-down; see [`Self::apply_config`] for the data path.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::WhoAmIError)`: If `WHO_AM_I` holds another value than [`ID`]; nothing is written.
+    /// - `Err(Error::UnexpectedValue)`: If the bus is stuck, with the value every register reads, `0xFF` or `0x00`;
+    ///   nothing is written.
+    /// - `Err(Error::Timeout)`: If the reset is still in progress after the last poll.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_threshold_z_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsZ::read(self)?;
-        reg.set_tap_thsz(val);
-        reg.write(self)
+    pub fn init(&mut self) -> Result<(), Error<B::Error>> {
+        self.bus_sanity_require()?;
+
+        self.reset_set()?;
+        let mut polls = 1;
+        while self.reset_get()? != 0 {
+            if polls >= RESET_MAX_POLLS {
+                return Err(Error::Timeout);
+            }
+            self.tim.wait_us(RESET_POLL_US);
+            polls += 1;
+        }
+
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_bdu(PROPERTY_ENABLE);
+        ctrl2.set_if_add_inc(PROPERTY_ENABLE);
+        ctrl2.write(self)
     }
 
-    /// Get the threshold for tap recognition on the Z-axis.
+    /// Write the data path settings.
+    ///
+    /// This function checks the operating mode against the data rate as [`mode_odr_check`] does, reads the
+    /// configuration registers and writes the fields of `config` to the `CTRL1`, `CTRL2`, `CTRL3`, `CTRL6` and
+    /// `CTRL7` registers as [`Self::ordered_apply`] does: phase by phase, see [`phase`], with the data rate last, only
+    /// the registers that change, and rolled back if a write fails.
     ///
-    /// This function retrieves the current tap threshold for the Z-axis from the `tap_thsz` field in the `TAP_THS_Z` register.
+    /// ### Arguments
+    /// - `config`: The settings, see [`Config`].
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current tap threshold value for the Z-axis.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_threshold_z_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsZ::read(self)?.tap_thsz())
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(Error::InvalidConfig)`: If the mode cannot be used with the data rate, see [`ConfigError`]; nothing
+    ///   is accessed.
+    /// - `Err(Error::ScalingLocked)`: If the full scale or the mode changes the locked scaling, see
+    ///   [`Self::strict_scaling_lock`].
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error<B::Error>> {
+        mode_odr_conflict(config.mode, config.odr).map_err(Error::InvalidConfig)?;
+
+        let source = self.config_snapshot_get()?;
+        let mut target = source;
+        let reg = |reg| source.get(reg).unwrap_or_default();
+
+        let mut ctrl1 = Ctrl1::from_bits(reg(Reg::Ctrl1));
+        ctrl1.set_odr(config.odr.odr());
+        ctrl1.set_mode(config.mode.mode());
+        ctrl1.set_lp_mode(config.mode.lp_mode());
+        target.set(Reg::Ctrl1, ctrl1.into_bits());
+
+        let mut ctrl2 = Ctrl2::from_bits(reg(Reg::Ctrl2));
+        ctrl2.set_bdu(config.bdu as u8);
+        target.set(Reg::Ctrl2, ctrl2.into_bits());
+
+        let mut ctrl3 = Ctrl3::from_bits(reg(Reg::Ctrl3));
+        ctrl3.set_slp_mode(config.odr.slp_mode());
+        target.set(Reg::Ctrl3, ctrl3.into_bits());
+
+        let mut ctrl6 = Ctrl6::from_bits(reg(Reg::Ctrl6));
+        ctrl6.set_fs(config.fs as u8);
+        ctrl6.set_low_noise(config.mode.low_noise());
+        ctrl6.set_fds(config.filter_path.fds());
+        ctrl6.set_bw_filt(config.bandwidth as u8);
+        target.set(Reg::Ctrl6, ctrl6.into_bits());
+
+        let mut ctrl7 = Ctrl7::from_bits(reg(Reg::Ctrl7));
+        ctrl7.set_usr_off_on_out(config.filter_path.usr_off_on_out());
+        target.set(Reg::Ctrl7, ctrl7.into_bits());
+
+        Ok(self.apply_delta(&source.diff(&target))?)
     }
 
-    /// Enable Z direction in tap recognition.
+    /// Enable or disable automatic register address increment.
     ///
-    /// This function enables or disables tap recognition on the Z-axis by updating the `tap_z_en` field in the `TAP_THS_Z` register.
+    /// This function configures the automatic register address increment feature by updating the `if_add_inc` field in the `CTRL2` register.
+    /// When enabled, the register address is automatically incremented during multiple-byte access.
     ///
     /// ### Arguments
-    /// - `val`: The desired value for the `tap_z_en` field:
-    ///   - `0`: Disable Z-axis tap recognition.
-    ///   - `1`: Enable Z-axis tap recognition.
+    /// - `val`: The desired value for the `if_add_inc` field:
+    ///   - `false`: Disable automatic increment.
+    ///   - `true`: Enable automatic increment.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_detection_on_z_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsZ::read(self)?;
-        reg.set_tap_z_en(val);
-        reg.write(self)
+    pub fn auto_increment_set(&mut self, val: bool) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_if_add_inc(val as u8);
+        ctrl2.write(self)
     }
 
-    /// Get the Z direction tap recognition status.
+    /// Get the automatic register address increment configuration.
     ///
-    /// This function retrieves the current status of tap recognition on the Z-axis from the `tap_z_en` field in the `TAP_THS_Z` register.
+    /// This function retrieves the current value of the `if_add_inc` field from the `CTRL2` register.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current value of the `tap_z_en` field:
-    ///   - `0`: Z-axis tap recognition is disabled.
-    ///   - `1`: Z-axis tap recognition is enabled.
+    /// - `Ok(bool)`: The current value of the `if_add_inc` field:
+    ///   - `false`: Automatic increment is disabled.
+    ///   - `true`: Automatic increment is enabled.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_detection_on_z_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsZ::read(self)?.tap_z_en())
+    pub fn auto_increment_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(Ctrl2::read(self)?.if_add_inc() == PROPERTY_ENABLE)
     }
 
-    /// Enable Y direction in tap recognition.
-    ///
-    /// This function enables or disables tap recognition on the Y-axis by updating the `tap_y_en` field in the `TAP_THS_Z` register.
+    /// Perform a software reset.
     ///
-    /// ### Arguments
-    /// - `val`: The desired value for the `tap_y_en` field:
-    ///   - `0`: Disable Y-axis tap recognition.
-    ///   - `1`: Enable Y-axis tap recognition.
+    /// This function performs a software reset by updating the `soft_reset` field in the `CTRL2` register.
+    /// A software reset restores the default values in all user registers.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_detection_on_y_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsZ::read(self)?;
-        reg.set_tap_y_en(val);
-        reg.write(self)
+    pub fn reset_set(&mut self) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_soft_reset(PROPERTY_ENABLE);
+        ctrl2.write(self)
     }
 
-    /// Get the Y direction tap recognition status.
+    /// Get the software reset status.
     ///
-    /// This function retrieves the current status of tap recognition on the Y-axis from the `tap_y_en` field in the `TAP_THS_Z` register.
+    /// This function retrieves the current value of the `soft_reset` field from the `CTRL2` register.
+    /// The value indicates whether a software reset has been performed.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current value of the `tap_y_en` field:
-    ///   - `0`: Y-axis tap recognition is disabled.
-    ///   - `1`: Y-axis tap recognition is enabled.
+    /// - `Ok(u8)`: The current value of the `soft_reset` field:
+    ///   - `0`: No reset in progress.
+    ///   - `1`: Reset in progress.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_detection_on_y_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsZ::read(self)?.tap_y_en())
+    pub fn reset_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(Ctrl2::read(self)?.soft_reset())
     }
 
-    /// Enable X direction in tap recognition.
-    ///
-    /// This function enables or disables tap recognition on the X-axis by updating the `tap_x_en` field in the `TAP_THS_Z` register.
+    /// Reboot memory content and reload calibration parameters.
     ///
-    /// ### Arguments
-    /// - `val`: The desired value for the `tap_x_en` field:
-    ///   - `0`: Disable X-axis tap recognition.
-    ///   - `1`: Enable X-axis tap recognition.
+    /// This function triggers a reboot of the device's memory content by updating the `boot` field in the `CTRL2` register.
+    /// The reboot operation reloads the calibration parameters from non-volatile memory.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_detection_on_x_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsZ::read(self)?;
-        reg.set_tap_x_en(val);
-        reg.write(self)
+    pub fn boot_set(&mut self) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_boot(PROPERTY_ENABLE);
+        ctrl2.write(self)
     }
 
-    /// Get the X direction tap recognition status.
+    /// Get the reboot memory content status.
     ///
-    /// This function retrieves the current status of tap recognition on the X-axis from the `tap_x_en` field in the `TAP_THS_Z` register.
+    /// This function retrieves the current value of the `boot` field from the `CTRL2` register.
+    /// The value indicates whether a reboot operation is in progress.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current value of the `tap_x_en` field:
-    ///   - `0`: X-axis tap recognition is disabled.
-    ///   - `1`: X-axis tap recognition is enabled.
+    /// - `Ok(u8)`: The current value of the `boot` field:
+    ///   - `0`: No reboot in progress.
+    ///   - `1`: Reboot in progress.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_detection_on_x_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsZ::read(self)?.tap_x_en())
+    pub fn boot_get(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(Ctrl2::read(self)?.boot())
     }
 
-    /// Set the maximum duration for tap recognition.
+    /// Enable or disable the sensor self-test.
     ///
-    /// This function configures the maximum time an over-threshold signal is detected to be recognized as a tap event.
-    /// The duration is set in the `shock` field of the `INT_DUR` register.
-    /// - The default value (`00b`) corresponds to `4 * ODR_XL` time.
-    /// - If the `shock` bits are set to a different value, 1 LSB corresponds to `8 * ODR_XL` time.
+    /// This function configures the self-test mode of the sensor by updating the `st` field in the `CTRL3` register.
+    /// The self-test mode allows verifying the functionality of the sensor without external stimuli.
     ///
     /// ### Arguments
-    /// - `val`: The desired maximum duration value for tap recognition.
+    /// - `val`: A [`St`] value representing the desired self-test mode:
+    ///   - `XlStDisable`: Self-test disabled (default).
+    ///   - `XlStPositive`: Positive sign self-test.
+    ///   - `XlStNegative`: Negative sign self-test.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_shock_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = IntDur::read(self)?;
-        reg.set_shock(val);
-        reg.write(self)
+    pub fn self_test_set(&mut self, val: St) -> Result<(), Error<B::Error>> {
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_st(val as u8);
+        ctrl3.write(self)
     }
 
-    /// Get the maximum duration for tap recognition.
+    /// Get the sensor self-test mode.
     ///
-    /// This function retrieves the current maximum time an over-threshold signal is detected to be recognized as a tap event.
-    /// The duration is stored in the `shock` field of the `INT_DUR` register.
+    /// This function retrieves the current self-test mode of the sensor from the `st` field in the `CTRL3` register.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current maximum duration value for tap recognition.
+    /// - `Ok(St)`: The current self-test mode as a [`St`] value:
+    ///   - `XlStDisable`: Self-test disabled (default).
+    ///   - `XlStPositive`: Positive sign self-test.
+    ///   - `XlStNegative`: Negative sign self-test.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_shock_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(IntDur::read(self)?.shock())
+    pub fn self_test_get(&mut self) -> Result<St, Error<B::Error>> {
+        Ok(St::try_from(Ctrl3::read(self)?.st()).unwrap_or_default())
     }
 
-    /// Set the quiet time for tap recognition.
+    /// Run the full self-test procedure.
     ///
-    /// This function configures the quiet time after the first detected tap during which no over-threshold event should occur.
-    /// The quiet time is set in the `quiet` field of the `INT_DUR` register.
-    /// - The default value (`00b`) corresponds to `2 * ODR_XL` time.
-    /// - If the `quiet` bits are set to a different value, 1 LSB corresponds to `4 * ODR_XL` time.
+    /// This function saves the configuration with [`Self::config_snapshot_get`], sets 50 Hz, ±4 g,
+    /// high-performance mode, block data update, the low-pass path and the FIFO bypass, then runs the baseline,
+    /// positive and negative phases described in [`selftest`]. The saved configuration is written back
+    /// afterwards, also when a phase fails; the `st` field in the `CTRL3` register is part of it.
+    ///
+    /// The phase timing uses the delay of the driver: every averaged sample is read one data period after the
+    /// previous one, and block data update keeps each read coherent.
     ///
     /// ### Arguments
-    /// - `val`: The desired quiet time value.
+    /// - `cfg`: The procedure parameters and limits, see [`SelfTestConfig::default`] for the datasheet ones.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SelfTestReport)`: The per-axis averages, deflections and verdicts; see [`SelfTestReport::passed`].
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_quiet_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = IntDur::read(self)?;
-        reg.set_quiet(val);
-        reg.write(self)
-    }
+    pub fn self_test_full(
+        &mut self,
+        cfg: &SelfTestConfig,
+    ) -> Result<SelfTestReport, Error<B::Error>> {
+        let snapshot = self.config_snapshot_get()?;
+        let report = self.self_test_phases(cfg);
+        let restored = self.config_snapshot_apply(&snapshot);
+        let report = report?;
+        restored?;
+        Ok(report)
+    }
+
+    fn self_test_phases(
+        &mut self,
+        cfg: &SelfTestConfig,
+    ) -> Result<SelfTestReport, Error<B::Error>> {
+        self.fifo_mode_set(Fmode::BypassMode)?;
+        self.block_data_update_set(true)?;
+        self.write_to_register(
+            Reg::Ctrl6 as u8,
+            &[Ctrl6::new().with_fs(Fs::_4g as u8).into_bits()],
+        )?;
+        let mut ctrl3 = Ctrl3::read(self)?;
+        ctrl3.set_slp_mode(0);
+        ctrl3.write(self)?;
+        let mut ctrl1 = Ctrl1::read(self)?;
+        ctrl1.set_odr(Odr::_50hz.odr());
+        ctrl1.set_mode(Mode::HighPerformance.mode());
+        ctrl1.set_lp_mode(Mode::HighPerformance.lp_mode());
+        ctrl1.write(self)?;
 
-    /// Get the quiet time for tap recognition.
-    ///
-    /// This function retrieves the current quiet time after the first detected tap during which no over-threshold event should occur.
-    /// The quiet time is stored in the `quiet` field of the `INT_DUR` register.
-    ///
-    /// ### Returns
-    /// - `Ok(u8)`: The current quiet time value.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_quiet_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(IntDur::read(self)?.quiet())
+        let baseline = self.self_test_phase(St::Disable, cfg)?;
+        let positive = self.self_test_phase(St::Positive, cfg)?;
+        let negative = self.self_test_phase(St::Negative, cfg)?;
+        Ok(SelfTestReport::new(baseline, positive, negative, cfg))
+    }
+
+    /// Enter a self-test phase, settle, discard and return the average acceleration in mg.
+    fn self_test_phase(
+        &mut self,
+        st: St,
+        cfg: &SelfTestConfig,
+    ) -> Result<[f32; 3], Error<B::Error>> {
+        let period_us = Odr::_50hz.period_us().unwrap_or_default();
+        self.self_test_set(st)?;
+        self.tim.wait_ms(cfg.settle_ms);
+        for _ in 0..cfg.discard {
+            self.tim.wait_us(period_us);
+            self.acceleration_raw_get()?;
+        }
+
+        let samples = cfg.samples.max(1);
+        let mut sum = [0.0f32; 3];
+        for _ in 0..samples {
+            self.tim.wait_us(period_us);
+            let raw = self.acceleration_raw_sensor_get()?;
+            for (acc, lsb) in sum.iter_mut().zip(raw) {
+                *acc += batch::raw_to_mg(lsb, Fs::_4g, false);
+            }
+        }
+        Ok(sum.map(|acc| acc / f32::from(samples)))
     }
 
-    /// Set the maximum duration for double-tap recognition.
+    /// Set the accelerometer filtering path for outputs.
     ///
-    /// This function configures the maximum time between two consecutive detected taps to determine a double-tap event.
-    /// The duration is set in the `latency` field of the `INT_DUR` register.
-    /// - The default value (`0000b`) corresponds to `16 * ODR_XL` time.
-    /// - If the `latency` bits are set to a different value, 1 LSB corresponds to `32 * ODR_XL` time.
+    /// This function configures the filtering path for accelerometer outputs by updating the `fds` field in the `CTRL6` register
+    /// and the `usr_off_on_out` field in the `CTRL7` register. Both fields are written, so that the one the path does not use
+    /// is cleared, e.g. `usr_off_on_out` for `HighPassOnOut`.
     ///
     /// ### Arguments
-    /// - `val`: The desired maximum duration value for double-tap recognition.
+    /// - `val`: A [`Fds`] value representing the desired filtering path:
+    ///   - `LpfOnOut`: Low-pass filter on output (default).
+    ///   - `UserOffsetOnOut`: User offset on output.
+    ///   - `HighPassOnOut`: High-pass filter on output.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SettleHint)`: The wait before the output goes through the new path, see [`settle`]: on a running data
+    ///   rate, the settling of the bandwidth in the `bw_filt` field of the `CTRL6` register when the filter changes,
+    ///   [`settle::PENDING_SAMPLES`] when only the user offset does.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = IntDur::read(self)?;
-        reg.set_latency(val);
-        reg.write(self)
+    pub fn filter_path_set(&mut self, val: Fds) -> Result<SettleHint, Error<B::Error>> {
+        let mut ctrl6 = Ctrl6::read(self)?;
+        let filter_changed = ctrl6.fds() != val.fds();
+        ctrl6.set_fds(val.fds());
+        ctrl6.write(self)?;
+
+        let mut ctrl7 = Ctrl7::read(self)?;
+        let offset_changed = ctrl7.usr_off_on_out() != val.usr_off_on_out();
+        ctrl7.set_usr_off_on_out(val.usr_off_on_out());
+        ctrl7.write(self)?;
+
+        if !filter_changed {
+            return self.pending_samples_hint(offset_changed);
+        }
+        Ok(match self.running_period_us()? {
+            Some(period_us) => {
+                let bw = BwFilt::try_from(ctrl6.bw_filt()).unwrap_or_default();
+                settle::filter_hint(bw, period_us, 0)
+            }
+            None => SettleHint::None,
+        })
     }
 
-    /// Get the maximum duration for double-tap recognition.
+    /// Get the accelerometer filtering path for outputs.
     ///
-    /// This function retrieves the current maximum time between two consecutive detected taps to determine a double-tap event.
-    /// The duration is stored in the `latency` field of the `INT_DUR` register.
+    /// This function retrieves the current filtering path for accelerometer outputs by reading the `fds` field from the `CTRL6` register
+    /// and the `usr_off_on_out` field from the `CTRL7` register.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current maximum duration value for double-tap recognition.
+    /// - `Ok(Fds)`: The current filtering path as a [`Fds`] value:
+    ///   - `LpfOnOut`: Low-pass filter on output (default).
+    ///   - `UserOffsetOnOut`: User offset on output.
+    ///   - `HighPassOnOut`: High-pass filter on output.
+    /// - `Err(Error::UnexpectedValue)`: If both fields are set, the user offset on the high-pass path, which the
+    ///   [`Fds`] variants do not represent, after the retry of [`Self::decode_retry_set`]; the payload is the combined
+    ///   code `fds << 4 | usr_off_on_out`, `0x11`.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(IntDur::read(self)?.latency())
+    pub fn filter_path_get(&mut self) -> Result<Fds, Error<B::Error>> {
+        self.read_decoded(|sensor| {
+            let ctrl6 = Ctrl6::read(sensor)?;
+            let ctrl7 = Ctrl7::read(sensor)?;
+            Ok(Fds::code(ctrl6.fds(), ctrl7.usr_off_on_out()))
+        })
     }
 
-    /// Enable or disable single/double-tap event detection.
+    /// Set the accelerometer cutoff filter frequency.
     ///
-    /// This function configures the single/double-tap event detection by updating the `single_double_tap` field in the `WAKE_UP_THS` register.
-    /// The mode determines whether only single-tap events or both single- and double-tap events are detected.
+    /// This function configures the cutoff frequency for the accelerometer's low-pass or high-pass filter by updating the `bw_filt` field in the `CTRL6` register.
     ///
     /// ### Arguments
-    /// - `val`: A [`SingleDoubleTap`] value representing the desired tap mode:
-    ///   - `OnlySingle`: Detect only single-tap events (default).
-    ///   - `BothSingleDouble`: Detect both single- and double-tap events.
+    /// - `val`: A [`BwFilt`] value representing the desired cutoff frequency:
+    ///   - `OdrDiv2`: ODR/2 (default).
+    ///   - `OdrDiv4`: ODR/4.
+    ///   - `OdrDiv10`: ODR/10.
+    ///   - `OdrDiv20`: ODR/20.
+    ///
+    /// The resulting configuration is validated with [`Self::wakeup_high_pass_check`], with the `wakeup` feature.
+    /// With [`Workarounds::FILTER_DISCARD`] and a running data rate, the function then discards the samples
+    /// filtered with both bandwidths, see [`errata::FILTER_DISCARD_SAMPLES`].
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok((SettleHint, None))`: If the operation is successful, with the wait before the output is filtered with
+    ///   the new bandwidth, see [`settle`]: on a running data rate, the settling of `val` minus the samples discarded
+    ///   by the workaround.
+    /// - `Ok((SettleHint, Some(Warning::WakeupHighPassSlow)))`: If the wake-up function is left with a slow
+    ///   high-pass filter.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn tap_mode_set(&mut self, val: SingleDoubleTap) -> Result<(), Error<B::Error>> {
-        let mut reg = WakeUpThs::read(self)?;
-        reg.set_single_double_tap(val as u8);
-        reg.write(self)
+    pub fn filter_bandwidth_set(
+        &mut self,
+        val: BwFilt,
+    ) -> Result<(SettleHint, Option<Warning>), Error<B::Error>> {
+        let mut ctrl6 = Ctrl6::read(self)?;
+        let changed = ctrl6.bw_filt() != val as u8;
+        ctrl6.set_bw_filt(val as u8);
+        ctrl6.write(self)?;
+
+        let period_us = self.running_period_us()?;
+        let mut waited = 0;
+        if let Some(period_us) =
+            period_us.filter(|_| self.state.workarounds.contains(Workarounds::FILTER_DISCARD))
+        {
+            waited = errata::FILTER_DISCARD_SAMPLES;
+            self.tim.wait_us(period_us.saturating_mul(waited));
+            if self.state.data_path == DataPath::Direct {
+                let mut discard = [0; batch::SAMPLE_BYTES];
+                self.read_from_register(Reg::OutXL as u8, &mut discard)?;
+            }
+        }
+
+        let hint = match period_us {
+            Some(period_us) if changed => settle::filter_hint(val, period_us, waited),
+            _ => SettleHint::None,
+        };
+        #[cfg(feature = "wakeup")]
+        let warning = self.wakeup_high_pass_check()?;
+        #[cfg(not(feature = "wakeup"))]
+        let warning = None;
+        Ok((hint, warning))
     }
 
-    /// Get the single/double-tap event detection mode.
+    /// Get the accelerometer cutoff filter frequency.
     ///
-    /// This function retrieves the current single/double-tap event detection mode from the `single_double_tap` field in the `WAKE_UP_THS` register.
+    /// This function retrieves the current cutoff frequency for the accelerometer's low-pass or high-pass filter by reading the `bw_filt` field from the `CTRL6` register.
     ///
     /// ### Returns
-    /// - `Ok(SingleDoubleTap)`: The current tap mode as a [`SingleDoubleTap`] value:
-    ///   - `OnlySingle`: Detect only single-tap events (default).
-    ///   - `BothSingleDouble`: Detect both single- and double-tap events.
+    /// - `Ok(BwFilt)`: The current cutoff frequency as a [`BwFilt`] value:
+    ///   - `OdrDiv2`: ODR/2 (default).
+    ///   - `OdrDiv4`: ODR/4.
+    ///   - `OdrDiv10`: ODR/10.
+    ///   - `OdrDiv20`: ODR/20.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_mode_get(&mut self) -> Result<SingleDoubleTap, Error<B::Error>> {
-        Ok(
-            SingleDoubleTap::try_from(WakeUpThs::read(self)?.single_double_tap())
-                .unwrap_or_default(),
-        )
+    pub fn filter_bandwidth_get(&mut self) -> Result<BwFilt, Error<B::Error>> {
+        Ok(BwFilt::try_from(Ctrl6::read(self)?.bw_filt()).unwrap_or_default())
     }
 
-    /// Read the tap/double-tap source register.
+    /// Get the accelerometer filter cutoff frequency in Hz.
     ///
-    /// This function retrieves the tap/double-tap source information from the `TAP_SRC` register.
-    /// The `TAP_SRC` register provides details about the tap events, such as the axis of detection and the type of tap event.
+    /// This function combines the current data rate with the `bw_filt` field in the `CTRL6` register.
+    /// The same corner applies to the low-pass and to the high-pass path.
     ///
     /// ### Returns
-    /// - `Ok(TapSrc)`: The tap source information as a [`TapSrc`] struct.
+    /// - `Ok(f32)`: The cutoff frequency.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn tap_src_get(&mut self) -> Result<TapSrc, Error<B::Error>> {
-        TapSrc::read(self)
+    pub fn filter_cutoff_hz(&mut self) -> Result<f32, Error<B::Error>> {
+        let odr = self.data_rate_get()?;
+        self.filter_bandwidth_get()?
+            .cutoff_hz(odr)
+            .ok_or(Error::InvalidConfig(ConfigError::DataRateNotRunning))
     }
 
-    /// Set the threshold for 4D/6D orientation detection.
+    /// Set the accelerometer filter bandwidth closest to a cutoff frequency.
     ///
-    /// This function configures the threshold for 4D/6D orientation detection by updating the `6d_ths` field in the `TAP_THS_X` register.
+    /// This function picks the [`BwFilt`] divider whose cutoff, at the current data rate as returned by [`Self::odr_hz`], is closest to `target_hz`
+    /// (by ratio) and applies it with [`Self::filter_bandwidth_set`].
     ///
     /// ### Arguments
-    /// - `val`: The desired threshold value for 4D/6D orientation detection.
+    /// - `target_hz`: The desired cutoff frequency in Hz.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(BwFilt)`: The applied bandwidth.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn sixd_threshold_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsX::read(self)?;
-        reg.set_six_d_ths(val);
-        reg.write(self)
-    }
+    pub fn filter_bandwidth_for_cutoff_set(
+        &mut self,
+        target_hz: f32,
+    ) -> Result<BwFilt, Error<B::Error>> {
+        let odr_hz = self.odr_hz()?;
+
+        let ratio = |bw: BwFilt| {
+            let hz = odr_hz / bw.divider() as f32;
+            if hz > target_hz {
+                hz / target_hz
+            } else {
+                target_hz / hz
+            }
+        };
+        let mut best = BwFilt::OdrDiv2;
+        for bw in [BwFilt::OdrDiv4, BwFilt::OdrDiv10, BwFilt::OdrDiv20] {
+            if ratio(bw) < ratio(best) {
+                best = bw;
+            }
+        }
 
-    /// Get the threshold for 4D/6D orientation detection.
-    ///
-    /// This function retrieves the current threshold for 4D/6D orientation detection from the `6d_ths` field in the `TAP_THS_X` register.
-    ///
-    /// ### Returns
-    /// - `Ok(u8)`: The current threshold value for 4D/6D orientation detection.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn sixd_threshold_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsX::read(self)?.six_d_ths())
+        self.filter_bandwidth_set(best)?;
+        Ok(best)
     }
 
-    /// Enable or disable 4D orientation detection.
+    /// Enable or disable the high-pass filter reference mode.
     ///
-    /// This function configures the 4D orientation detection by updating the `4d_en` field in the `TAP_THS_X` register.
+    /// This function configures the high-pass filter reference mode by updating the `hp_ref_mode` field in the `CTRL7` register.
     ///
     /// ### Arguments
-    /// - `val`: The desired value for the `4d_en` field:
-    ///   - `0`: Disable 4D orientation detection.
-    ///   - `1`: Enable 4D orientation detection.
+    /// - `val`: The desired value for the `hp_ref_mode` field:
+    ///   - `false`: Disable high-pass filter reference mode.
+    ///   - `true`: Enable high-pass filter reference mode.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
+    /// - `Ok(SettleHint)`: The wait before the output is referenced the new way, see [`settle`]:
+    ///   [`settle::PENDING_SAMPLES`] on a running data rate.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn fourd_mode_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = TapThsX::read(self)?;
-        reg.set_four_d_en(val);
-        reg.write(self)
-    }
+    pub fn reference_mode_set(&mut self, val: bool) -> Result<SettleHint, Error<B::Error>> {
+        let mut ctrl7 = Ctrl7::read(self)?;
+        let changed = ctrl7.hp_ref_mode() != val as u8;
+        ctrl7.set_hp_ref_mode(val as u8);
+        ctrl7.write(self)?;
 
-    /// Get the 4D orientation detection status.
-    ///
-    /// This function retrieves the current status of 4D orientation detection from the `4d_en` field in the `TAP_THS_X` register.
-    ///
-    /// ### Returns
-    /// - `Ok(u8)`: The current value of the `4d_en` field:
-    ///   - `0`: 4D orientation detection is disabled.
-    ///   - `1`: 4D orientation detection is enabled.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn fourd_mode_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(TapThsX::read(self)?.four_d_en())
+        self.pending_samples_hint(changed)
     }
 
-    /// Read the 6D tap source register.
+    /// Get the high-pass filter reference mode status.
     ///
-    /// This function retrieves the 6D tap source information from the `SIXD_SRC` register.
-    /// The `SIXD_SRC` register provides details about the 6D orientation events, such as axis-specific thresholds and event detection.
+    /// This function retrieves the current status of the high-pass filter reference mode from the `hp_ref_mode` field in the `CTRL7` register.
     ///
     /// ### Returns
-    /// - `Ok(SixdSrc)`: The 6D source information as a [`SixdSrc`] struct.
+    /// - `Ok(bool)`: The current value of the `hp_ref_mode` field:
+    ///   - `false`: High-pass filter reference mode is disabled.
+    ///   - `true`: High-pass filter reference mode is enabled.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn sixd_src_get(&mut self) -> Result<SixdSrc, Error<B::Error>> {
-        SixdSrc::read(self)
+    pub fn reference_mode_get(&mut self) -> Result<bool, Error<B::Error>> {
+        Ok(Ctrl7::read(self)?.hp_ref_mode() == PROPERTY_ENABLE)
     }
 
-    /// Set the data source for the 6D interrupt function.
+    /// Set the SPI serial interface mode.
+    ///
+    /// This function configures the SPI serial interface mode by updating the `sim` field in the `CTRL2` register.
+    /// The SPI interface can operate in either 4-wire or 3-wire mode.
     ///
-    /// This function configures the data source for the 6D interrupt function by updating the `lpass_on6d` field in the `CTRL7` register.
-    /// The data source can be either ODR/2 low-pass filtered data or LPF2 output data.
+    /// On a 3-wire wiring, this function cannot set the mode itself: it reads `CTRL2` first, which only answers once the
+    /// mode is set. Construct the driver with [`Iis2dlpc::new_spi_3wire`] instead, whose bus sets it before the first
+    /// read and keeps it set in this function.
     ///
     /// ### Arguments
-    /// - `val`: A [`LpassOn6d`] value representing the desired data source:
-    ///   - `OdrDiv2Feed`: ODR/2 low-pass filtered data (default).
-    ///   - `Lpf2Feed`: LPF2 output data.
+    /// - `val`: A [`Sim`] value representing the desired SPI mode:
+    ///   - `Spi4Wire`: 4-wire SPI mode (default).
+    ///   - `Spi3Wire`: 3-wire SPI mode.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn sixd_feed_data_set(&mut self, val: LpassOn6d) -> Result<(), Error<B::Error>> {
-        let mut reg = Ctrl7::read(self)?;
-        reg.set_lpass_on6d(val as u8);
-        reg.write(self)
+    pub fn spi_mode_set(&mut self, val: Sim) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_sim(val as u8);
+        ctrl2.write(self)
     }
 
-    /// Get the data source for the 6D interrupt function.
+    /// Get the SPI serial interface mode.
     ///
-    /// This function retrieves the current data source for the 6D interrupt function from the `lpass_on6d` field in the `CTRL7` register.
+    /// This function retrieves the current SPI serial interface mode from the `sim` field in the `CTRL2` register.
     ///
     /// ### Returns
-    /// - `Ok(LpassOn6d)`: The current data source as a [`LpassOn6d`] value.
+    /// - `Ok(Sim)`: The current SPI mode as a [`Sim`] value:
+    ///   - `Spi4Wire`: 4-wire SPI mode (default).
+    ///   - `Spi3Wire`: 3-wire SPI mode.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn sixd_feed_data_get(&mut self) -> Result<LpassOn6d, Error<B::Error>> {
-        Ok(LpassOn6d::try_from(Ctrl7::read(self)?.lpass_on6d()).unwrap_or_default())
+    pub fn spi_mode_get(&mut self) -> Result<Sim, Error<B::Error>> {
+        Ok(Sim::try_from(Ctrl2::read(self)?.sim()).unwrap_or_default())
     }
 
-    /// Set the wake-up duration event.
+    /// Enable or disable the I²C interface.
     ///
-    /// This function configures the wake-up duration event by updating the `ff_dur` field in the `WAKE_UP_DUR` and `FREE_FALL` registers.
-    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    /// This function configures the I²C interface by updating the `i2c_disable` field in the `CTRL2` register.
+    /// The I²C interface can be enabled or disabled based on the provided value.
     ///
     /// ### Arguments
-    /// - `val`: The desired wake-up duration value.
+    /// - `val`: A [`I2cDisable`] value representing the desired I²C interface state:
+    ///   - `I2cEnable`: Enable the I²C interface (default).
+    ///   - `I2cDisable`: Disable the I²C interface.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn ff_dur_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut wake_up_dur = WakeUpDur::read(self)?;
-        let mut free_fall = FreeFall::read(self)?;
-
-        wake_up_dur.set_ff_dur((val & 0x20) >> 5);
-        free_fall.set_ff_dur(val & 0x1F);
-
-        wake_up_dur.write(self)?;
-        free_fall.write(self)
+    pub fn i2c_interface_set(&mut self, val: I2cDisable) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_i2c_disable(val as u8);
+        ctrl2.write(self)
     }
 
-    /// Get the wake-up duration event.
+    /// Get the I²C interface state.
     ///
-    /// This function retrieves the current wake-up duration event from the `ff_dur` field in the `WAKE_UP_DUR` and `FREE_FALL` registers.
-    /// The duration is expressed in LSB, where 1 LSB = 1 / ODR.
+    /// This function retrieves the current state of the I²C interface from the `i2c_disable` field in the `CTRL2` register.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current wake-up duration value.
+    /// - `Ok(I2cDisable)`: The current I²C interface state as a [`I2cDisable`] value:
+    ///   - `I2cEnable`: I²C interface is enabled (default).
+    ///   - `I2cDisable`: I²C interface is disabled.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn ff_dur_get(&mut self) -> Result<u8, Error<B::Error>> {
-        let wake_up_dur = WakeUpDur::read(self)?;
-        let free_fall = FreeFall::read(self)?;
-
-        Ok((wake_up_dur.ff_dur() << 5) + free_fall.ff_dur())
+    pub fn i2c_interface_get(&mut self) -> Result<I2cDisable, Error<B::Error>> {
+        Ok(I2cDisable::try_from(Ctrl2::read(self)?.i2c_disable()).unwrap_or_default())
     }
 
-    /// Set the free-fall threshold.
+    /// Configure the CS pull-up resistor.
     ///
-    /// This function configures the free-fall threshold by updating the `ff_ths` field in the `FREE_FALL` register.
-    /// The threshold determines the sensitivity of the free-fall detection.
+    /// This function configures the CS pull-up resistor by updating the `cs_pu_disc` field in the `CTRL2` register.
+    /// The pull-up resistor can be connected or disconnected based on the provided value.
     ///
     /// ### Arguments
-    /// - `val`: A [`FfThs`] value representing the desired free-fall threshold:
-    ///   - `FfTsh5lsbFs2g`: 5 LSB @ ±2g (default).
-    ///   - `FfTsh7lsbFs2g`: 7 LSB @ ±2g.
-    ///   - `FfTsh8lsbFs2g`: 8 LSB @ ±2g.
-    ///   - `FfTsh10lsbFs2g`: 10 LSB @ ±2g.
-    ///   - `FfTsh11lsbFs2g`: 11 LSB @ ±2g.
-    ///   - `FfTsh13lsbFs2g`: 13 LSB @ ±2g.
-    ///   - `FfTsh15lsbFs2g`: 15 LSB @ ±2g.
-    ///   - `FfTsh16lsbFs2g`: 16 LSB @ ±2g.
+    /// - `val`: A [`CsPuDisc`] value representing the desired CS pull-up configuration:
+    ///   - `PullUpConnect`: Connect the pull-up resistor (default).
+    ///   - `PullUpDisconnect`: Disconnect the pull-up resistor.
     ///
     /// ### Returns
     /// - `Ok(())`: If the operation is successful.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn ff_threshold_set(&mut self, val: FfThs) -> Result<(), Error<B::Error>> {
-        let mut reg = FreeFall::read(self)?;
-        reg.set_ff_ths(val as u8);
-        reg.write(self)
+    pub fn cs_mode_set(&mut self, val: CsPuDisc) -> Result<(), Error<B::Error>> {
+        let mut ctrl2 = Ctrl2::read(self)?;
+        ctrl2.set_cs_pu_disc(val as u8);
+        ctrl2.write(self)
     }
 
-    /// Get the free-fall threshold.
+    /// Get the CS pull-up resistor configuration.
     ///
-    /// This function retrieves the current free-fall threshold from the `ff_ths` field in the `FREE_FALL` register.
+    /// This function retrieves the current CS pull-up resistor configuration from the `cs_pu_disc` field in the `CTRL2` register.
     ///
     /// ### Returns
-    /// - `Ok(FfThs)`: The current free-fall threshold as a [`FfThs`] value.
+    /// - `Ok(CsPuDisc)`: The current CS pull-up configuration as a [`CsPuDisc`] value:
+    ///   - `PullUpConnect`: Pull-up resistor is connected (default).
+    ///   - `PullUpDisconnect`: Pull-up resistor is disconnected.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn ff_threshold_get(&mut self) -> Result<FfThs, Error<B::Error>> {
-        Ok(FfThs::try_from(FreeFall::read(self)?.ff_ths()).unwrap_or_default())
+    pub fn cs_mode_get(&mut self) -> Result<CsPuDisc, Error<B::Error>> {
+        Ok(CsPuDisc::try_from(Ctrl2::read(self)?.cs_pu_disc()).unwrap_or_default())
     }
 
-    /// Set the FIFO watermark level.
+    /// Set the tolerance used by the millisecond-based duration setters.
     ///
-    /// This function configures the FIFO watermark level by updating the `fth` field in the `FIFO_CTRL` register.
-    /// The watermark level determines the threshold at which the FIFO generates an interrupt when the number of unread samples reaches the specified level.
+    /// A requested duration is accepted when the nearest register code is within `percent` of it.
+    /// Defaults to [`DEFAULT_DURATION_TOLERANCE`].
     ///
     /// ### Arguments
-    /// - `val`: The desired FIFO watermark level.
-    ///
-    /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn fifo_watermark_set(&mut self, val: u8) -> Result<(), Error<B::Error>> {
-        let mut reg = FifoCtrl::read(self)?;
-        reg.set_fth(val);
-        reg.write(self)
+    /// - `percent`: The accepted relative error, in percent.
+    pub fn duration_tolerance_set(&mut self, percent: u8) {
+        self.state.duration_tolerance = percent;
     }
 
-    /// Get the FIFO watermark level.
-    ///
-    /// This function retrieves the current FIFO watermark level from the `fth` field in the `FIFO_CTRL` register.
+    /// Get the tolerance used by the millisecond-based duration setters.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current FIFO watermark level.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn fifo_watermark_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(FifoCtrl::read(self)?.fth())
+    /// - `u8`: The accepted relative error, in percent.
+    pub fn duration_tolerance_get(&self) -> u8 {
+        self.state.duration_tolerance
     }
 
-    /// Set the FIFO mode.
+    /// Set the data rate correction from a measured data rate.
     ///
-    /// This function configures the FIFO operating mode by updating the `fmode` field in the `FIFO_CTRL` register.
-    /// The FIFO mode determines how data is managed in the FIFO buffer.
+    /// The data rates come from an internal oscillator whose tolerance shifts every rate by the same factor.
+    /// The correction computed from one measured rate, for example with [`Self::measure_odr`], applies to every
+    /// data rate in [`Self::odr_hz`], [`Self::lsb_duration_us`] and the millisecond-based duration setters.
     ///
     /// ### Arguments
-    /// - `val`: A [`Fmode`] value representing the desired FIFO mode:
-    ///   - `BypassMode`: FIFO is disabled (default).
-    ///   - `FifoMode`: FIFO stops collecting data when full.
-    ///   - `StreamToFifoMode`: Stream mode until a trigger event, then FIFO mode.
-    ///   - `BypassToStreamMode`: Bypass mode until a trigger event, then stream mode.
-    ///   - `StreamMode`: Continuously updates FIFO, overwriting old data when full.
+    /// - `measured_hz`: The measured data rate in Hz.
+    /// - `nominal`: The data rate the device ran at during the measurement.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the correction is stored.
+    /// - `Err(Error::InvalidConfig)`: If `nominal` is off or triggered on demand; the correction is unchanged.
+    /// - `Err(Error::InvalidArgument)`: If `measured_hz` is not a positive number; the correction is unchanged.
+    /// - `Err(Error::OutOfRange)`: If the correction, in ppm, exceeds [`MAX_ODR_CALIBRATION_PPM`]; the correction is
+    ///   unchanged.
+    pub fn odr_calibration_set(
+        &mut self,
+        measured_hz: f32,
+        nominal: Odr,
+    ) -> Result<(), Error<B::Error>> {
+        let nominal_hz = nominal
+            .to_hz()
+            .ok_or(Error::InvalidConfig(ConfigError::DataRateNotRunning))?;
+        if !measured_hz.is_finite() || measured_hz <= 0.0 {
+            return Err(Error::InvalidArgument);
+        }
+        let ppm = (measured_hz / nominal_hz - 1.0) * 1e6;
+        if ppm.abs() > MAX_ODR_CALIBRATION_PPM as f32 {
+            return Err(Error::OutOfRange {
+                requested: ppm.abs() as u32,
+                max: MAX_ODR_CALIBRATION_PPM,
+            });
+        }
+        self.state.odr_calibration_ppm = ppm as i32;
+        Ok(())
+    }
+
+    /// Get the data rate correction.
     ///
     /// ### Returns
-    /// - `Ok(())`: If the operation is successful.
-    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
-    pub fn fifo_mode_set(&mut self, val: Fmode) -> Result<(), Error<B::Error>> {
-        let mut reg = FifoCtrl::read(self)?;
-        reg.set_fmode(val as u8);
-        reg.write(self)
+    /// - `i32`: The correction in parts per million, positive if the device runs fast; `0` if not calibrated.
+    pub fn odr_calibration_get(&self) -> i32 {
+        self.state.odr_calibration_ppm
     }
 
-    /// Get the FIFO mode.
+    /// Clear the data rate correction: the time conversions use the nominal data rates again.
+    pub fn odr_calibration_clear(&mut self) {
+        self.state.odr_calibration_ppm = 0;
+    }
+
+    /// Get the current data rate, corrected with the calibration.
     ///
-    /// This function retrieves the current FIFO operating mode from the `fmode` field in the `FIFO_CTRL` register.
+    /// This function reads the data rate from the `CTRL1` and `CTRL3` registers and applies the correction set
+    /// with [`Self::odr_calibration_set`].
     ///
     /// ### Returns
-    /// - `Ok(Fmode)`: The current FIFO mode as a [`Fmode`] value.
+    /// - `Ok(f32)`: The data rate in Hz.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn fifo_mode_get(&mut self) -> Result<Fmode, Error<B::Error>> {
-        Ok(Fmode::try_from(FifoCtrl::read(self)?.fmode()).unwrap_or_default())
+    pub fn odr_hz(&mut self) -> Result<f32, Error<B::Error>> {
+        let nominal_hz = self
+            .data_rate_get()?
+            .to_hz()
+            .ok_or(Error::InvalidConfig(ConfigError::DataRateNotRunning))?;
+        Ok(nominal_hz * (1.0 + self.state.odr_calibration_ppm as f32 / 1e6))
     }
 
-    /// Get the number of unread samples stored in the FIFO.
+    /// Get the duration step of one LSB of a duration field at the current data rate, corrected with the calibration.
     ///
-    /// This function retrieves the number of unread samples currently stored in the FIFO buffer from the `diff` field in the `FIFO_SAMPLES` register.
+    /// See [`DurationField::lsb_duration_us`] for the nominal step.
+    ///
+    /// ### Arguments
+    /// - `field`: The duration field.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The number of unread samples in the FIFO.
+    /// - `Ok(u64)`: The step in microseconds.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn fifo_data_level_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(FifoSamples::read(self)?.diff())
+    pub fn lsb_duration_us(&mut self, field: DurationField) -> Result<u64, Error<B::Error>> {
+        Ok(field.periods(1) * self.odr_period_us()?)
     }
 
-    /// Get the FIFO overrun status.
+    /// Measure the data rate by timing data-ready events.
     ///
-    /// This function retrieves the FIFO overrun status from the `fifo_ovr` field in the `FIFO_SAMPLES` register.
-    /// The overrun status indicates whether the FIFO buffer has overwritten old data due to being full.
+    /// This function discards a pending sample, then polls the `drdy` field in the `STATUS` register and reads
+    /// each new sample, taking the time of the first and of the last data-ready. The resolution is the duration
+    /// of one poll, so the more samples, the more accurate the result.
+    ///
+    /// ### Arguments
+    /// - `samples`: The number of data periods to measure; `0` is treated as `1`.
+    /// - `clock`: A free-running clock in microseconds.
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current FIFO overrun status:
-    ///   - `0`: No overrun has occurred.
-    ///   - `1`: FIFO has overwritten old data.
+    /// - `Ok(f32)`: The measured data rate in Hz.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand, or no data-ready came within
+    ///   four nominal periods.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn fifo_ovr_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(FifoSamples::read(self)?.fifo_ovr())
+    pub fn measure_odr(
+        &mut self,
+        samples: u16,
+        mut clock: impl FnMut() -> u64,
+    ) -> Result<f32, Error<B::Error>> {
+        let timeout_us = 4 * self
+            .data_rate_get()?
+            .period_us()
+            .ok_or(Error::InvalidConfig(ConfigError::DataRateNotRunning))?
+            as u64;
+
+        if self.status_reg_get()?.drdy() == 1 {
+            self.acceleration_raw_get()?;
+        }
+        let samples = samples.max(1);
+        let mut first = 0;
+        let mut last = clock();
+        for i in 0..=samples {
+            loop {
+                let now = clock();
+                if self.status_reg_get()?.drdy() == 1 {
+                    last = now;
+                    break;
+                }
+                if now.saturating_sub(last) > timeout_us {
+                    return Err(Error::InvalidConfig(ConfigError::DataRateNotRunning));
+                }
+            }
+            self.acceleration_raw_get()?;
+            if i == 0 {
+                first = last;
+            }
+        }
+
+        let elapsed_us = last.saturating_sub(first).max(1);
+        Ok(samples as f32 * 1e6 / elapsed_us as f32)
     }
 
-    /// Get the FIFO threshold status flag.
+    /// Get the current data period, corrected with the calibration.
     ///
-    /// This function retrieves the FIFO threshold status flag from the `fifo_fth` field in the `FIFO_SAMPLES` register.
-    /// The threshold status indicates whether the number of unread samples in the FIFO has reached the configured watermark level.
+    /// This function reads the data rate from the `CTRL1` and `CTRL3` registers and applies the correction set
+    /// with [`Self::odr_calibration_set`].
     ///
     /// ### Returns
-    /// - `Ok(u8)`: The current FIFO threshold status flag:
-    ///   - `0`: FIFO filling is below the threshold level.
-    ///   - `1`: FIFO filling has reached or exceeded the threshold level.
+    /// - `Ok(u64)`: The data period in microseconds.
+    /// - `Err(Error::InvalidConfig)`: If the data rate is off or triggered on demand.
     /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
-    pub fn fifo_wtm_flag_get(&mut self) -> Result<u8, Error<B::Error>> {
-        Ok(FifoSamples::read(self)?.fifo_fth())
+    pub fn odr_period_us(&mut self) -> Result<u64, Error<B::Error>> {
+        let period_us = self
+            .data_rate_get()?
+            .period_us()
+            .ok_or(Error::InvalidConfig(ConfigError::DataRateNotRunning))?;
+        Ok(self.period_calibrated_us(period_us))
+    }
+
+    /// Apply the correction set with [`Self::odr_calibration_set`] to a nominal data period.
+    fn period_calibrated_us(&self, period_us: u32) -> u64 {
+        let corrected =
+            period_us as i64 * 1_000_000 / (1_000_000 + self.state.odr_calibration_ppm as i64);
+        corrected as u64
+    }
+
+    /// Whether `ms` is within the duration tolerance of the highest code of `field` at some data rate.
+    ///
+    /// The slowest data rate, 1.6 Hz, bounds every other: a duration it cannot reach is refused without reading the
+    /// current data rate.
+    #[cfg(feature = "freefall")]
+    fn duration_reachable_ms(&self, ms: u32, field: DurationField) -> bool {
+        let slowest_us = Odr::_1_6hzLpOnly
+            .period_us()
+            .map_or(0, |us| self.period_calibrated_us(us));
+        let max_us = field.periods(field.max_code()) * slowest_us;
+        let requested_us = ms as u64 * 1000;
+        requested_us.saturating_sub(max_us) * 100
+            <= requested_us * self.state.duration_tolerance as u64
+    }
+
+    /// Find the code of `field` whose duration is nearest to `ms` at the current data rate, corrected with the
+    /// calibration.
+    ///
+    /// Returns the code and the achieved duration in milliseconds.
+    #[cfg(any(feature = "wakeup", feature = "freefall"))]
+    fn duration_code_for_ms(
+        &mut self,
+        ms: u32,
+        field: DurationField,
+    ) -> Result<(u8, u32), Error<B::Error>> {
+        let period_us = self.odr_period_us()?;
+        let requested_us = ms as u64 * 1000;
+
+        let (code, achieved_us) = field.nearest_code(requested_us, period_us);
+        if achieved_us.abs_diff(requested_us) * 100
+            > requested_us * self.state.duration_tolerance as u64
+        {
+            let max_us = field.periods(field.max_code()) * period_us;
+            if requested_us > max_us {
+                return Err(Error::OutOfRange {
+                    requested: ms,
+                    max: us_to_ms_rounded(max_us),
+                });
+            }
+            return Err(Error::Inexact {
+                requested: ms,
+                nearest: us_to_ms_rounded(achieved_us),
+            });
+        }
+
+        Ok((code, us_to_ms_rounded(achieved_us)))
+    }
+
+    /// Convert a code of `field` to milliseconds at the current data rate, corrected with the calibration.
+    #[cfg(any(feature = "wakeup", feature = "freefall"))]
+    fn duration_ms_for_code(
+        &mut self,
+        code: u8,
+        field: DurationField,
+    ) -> Result<u32, Error<B::Error>> {
+        let duration_us = field.periods(code) * self.odr_period_us()?;
+        Ok(us_to_ms_rounded(duration_us))
     }
 }
 
@@ -1776,6 +3658,8 @@ pub fn from_fs2_to_mg(lsb: i16) -> f32 {
 /// Convert from full-scale ±4g to mg.
 ///
 /// This function converts a raw sensor value in least significant bits (LSB) to mg for a full-scale range of ±4g.
+/// The raw value is the 14-bit output of [`Iis2dlpc::acceleration_raw_get`], so 1 LSB is 0.488 mg; the 0.122 mg
+/// of the reference C driver applies to the left-aligned 16-bit word.
 ///
 /// ### Arguments
 /// - `lsb`: The raw value in LSB.
@@ -1783,7 +3667,6 @@ pub fn from_fs2_to_mg(lsb: i16) -> f32 {
 /// ### Returns
 /// - `f32`: The converted value in mg.
 pub fn from_fs4_to_mg(lsb: i16) -> f32 {
-    // (lsb as f32) * 0.122
     (lsb as f32) * 0.488
 }
 
@@ -1865,12 +3748,40 @@ pub fn from_fs16_lp1_to_mg(lsb: i16) -> f32 {
     (lsb as f32) * 7.808
 }
 
+/// Get the sensitivity of the acceleration outputs.
+///
+/// This function gives the weight of one LSB at the resolution of `mode`: 12 bits in low-power mode 1, see
+/// [`Mode::is_low_power_1`], 14 bits in every other mode. It is the factor of the `from_fs*_to_mg` helpers
+/// matching `fs` and `mode`. The raw values of [`Iis2dlpc::acceleration_raw_get`] are 14-bit: in low-power mode 1
+/// shift them right by 2 first, as [`Iis2dlpc::acceleration_mg_get`] does.
+///
+/// ### Arguments
+/// - `fs`: The full scale.
+/// - `mode`: The operating mode.
+///
+/// ### Returns
+/// - `f32`: The sensitivity in mg/LSB.
+pub fn sensitivity_mg_per_lsb(fs: Fs, mode: Mode) -> f32 {
+    match (fs, mode.is_low_power_1()) {
+        (Fs::_2g, false) => 0.244,
+        (Fs::_4g, false) => 0.488,
+        (Fs::_8g, false) => 0.976,
+        (Fs::_16g, false) => 1.952,
+        (Fs::_2g, true) => 0.976,
+        (Fs::_4g, true) => 1.952,
+        (Fs::_8g, true) => 3.904,
+        (Fs::_16g, true) => 7.808,
+    }
+}
+
 /// Convert from LSB to Celsius.
 ///
 /// This function converts a raw temperature value in least significant bits (LSB) to degrees Celsius (°C).
+/// The sensitivity is 16 LSB/°C and `0` stands for 25 °C.
 ///
 /// ### Arguments
-/// - `lsb`: The raw temperature value in LSB.
+/// - `lsb`: The right-justified 12-bit value returned by [`Iis2dlpc::temperature_raw_get`]; the left-justified
+///   16-bit word of the `OUT_T_L` and `OUT_T_H` registers must be shifted right by 4 first.
 ///
 /// ### Returns
 /// - `f32`: The temperature in degrees Celsius.
@@ -1878,11 +3789,26 @@ pub fn from_lsb_to_celsius(lsb: i16) -> f32 {
     (lsb as f32 / 16.0) + 25.0
 }
 
+/// Convert from 8-bit LSB to Celsius.
+///
+/// This function converts the 8-bit temperature of the `OUT_T` register to degrees Celsius (°C).
+/// The sensitivity is 1 LSB/°C and `0` stands for 25 °C.
+///
+/// ### Arguments
+/// - `lsb`: The value returned by [`Iis2dlpc::temperature_8bit_get`].
+///
+/// ### Returns
+/// - `f32`: The temperature in degrees Celsius.
+pub fn from_lsb8_to_celsius(lsb: i8) -> f32 {
+    lsb as f32 + 25.0
+}
+
 /// I²C Address Map.
 ///
 /// This enum represents the possible I²C addresses for the IIS2DLPC sensor, depending on the configuration of the SA0 pin.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum I2CAddress {
     /// I²C address when SA0 is connected to GND.
     I2cAddL = 0x18,
@@ -0,0 +1,319 @@
+//! Register-level bus model for host-side use.
+//!
+//! [`MockBus`] implements [`BusOperation`] on top of a register map initialized with
+//! [`reset_image`], so the driver runs unchanged without hardware. Register accesses follow
+//! the `IF_ADD_INC` auto-increment of the device; with the FIFO enabled, reads of the output
+//! registers roll back from `OUT_Z_H` to `OUT_X_L` as they do on the device. A software reset
+//! restores the reset values at once.
+//!
+//! The bus can also account for the time the same traffic would take on a real bus, see
+//! [`MockBus::set_transaction_cost`].
+//!
+//! To check the exact register traffic of a function, queue the expected accesses with
+//! [`MockBus::expect`] and check them once the function returned:
+//!
+//! ```ignore
+//! sensor.bus.expect(&[
+//!     Access::read(Reg::WakeUpDur),
+//!     Access::read(Reg::FreeFall),
+//!     Access::write(Reg::WakeUpDur, 0x80),
+//!     Access::write(Reg::FreeFall, 0x50),
+//! ]);
+//! sensor.ff_dur_set(0x2A)?;
+//! assert_eq!(sensor.bus.expectations_check(), Ok(()));
+//! ```
+
+use core::convert::Infallible;
+
+use crate::prelude::*;
+use crate::{BusOperation, DelayNs};
+
+/// Number of accesses [`MockBus::expect`] can queue.
+pub const MAX_EXPECTATIONS: usize = 32;
+
+/// Register access of the driver, as checked by [`MockBus::expect`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Access {
+    /// Read transaction starting at the register address.
+    Read(u8),
+    /// Byte written to the register address; a multiple byte write is one access per byte, at the
+    /// auto-incremented addresses.
+    Write(u8, u8),
+}
+
+impl Access {
+    /// Read transaction starting at `reg`.
+    pub fn read(reg: Reg) -> Self {
+        Access::Read(reg as u8)
+    }
+
+    /// Byte `val` written to `reg`.
+    pub fn write(reg: Reg, val: u8) -> Self {
+        Access::Write(reg as u8, val)
+    }
+}
+
+/// Failure reported by [`MockBus::expectations_check`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ExpectationError {
+    /// Access `index` differs from the expected one.
+    Mismatch {
+        index: usize,
+        expected: Access,
+        found: Access,
+    },
+    /// Access `index` came after the last expected one.
+    Unexpected { index: usize, found: Access },
+    /// Expected access `index` did not happen.
+    Missing { index: usize, expected: Access },
+    /// More than [`MAX_EXPECTATIONS`] accesses were queued.
+    Overflow,
+}
+
+/// Register map behind a fake bus.
+#[derive(Clone, Debug)]
+pub struct MockBus {
+    regs: [u8; REG_MAP_LEN],
+    addr: u8,
+    fixed_us: f32,
+    per_byte_us: f32,
+    elapsed_us: f64,
+    transactions: u32,
+    expected: [Option<Access>; MAX_EXPECTATIONS],
+    expected_len: usize,
+    observed: usize,
+    armed: bool,
+    failure: Option<ExpectationError>,
+}
+
+impl Default for MockBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBus {
+    /// Create a bus whose registers hold their reset values.
+    pub fn new() -> Self {
+        Self {
+            regs: reset_image(),
+            addr: 0,
+            fixed_us: 0.0,
+            per_byte_us: 0.0,
+            elapsed_us: 0.0,
+            transactions: 0,
+            expected: [None; MAX_EXPECTATIONS],
+            expected_len: 0,
+            observed: 0,
+            armed: false,
+            failure: None,
+        }
+    }
+
+    /// Get the content of a register.
+    pub fn reg(&self, reg: Reg) -> u8 {
+        self.regs.get(reg as usize).copied().unwrap_or_default()
+    }
+
+    /// Set the content of a register, for example a status or an output register.
+    pub fn set_reg(&mut self, reg: Reg, val: u8) {
+        if let Some(slot) = self.regs.get_mut(reg as usize) {
+            *slot = val;
+        }
+    }
+
+    /// Set the time model of a bus transaction.
+    ///
+    /// Every transaction costs `fixed_us`, plus `per_byte_us` for each byte on the bus,
+    /// register address included.
+    ///
+    /// ### Arguments
+    /// - `fixed_us`: Cost of a transaction, for example start, device address and stop conditions, in microseconds.
+    /// - `per_byte_us`: Cost of a byte, in microseconds.
+    pub fn set_transaction_cost(&mut self, fixed_us: f32, per_byte_us: f32) {
+        self.fixed_us = fixed_us;
+        self.per_byte_us = per_byte_us;
+    }
+
+    /// Get the bus time accounted since the creation or the last [`Self::cost_reset`], in microseconds.
+    pub fn elapsed_us(&self) -> f64 {
+        self.elapsed_us
+    }
+
+    /// Get the number of transactions since the creation or the last [`Self::cost_reset`].
+    pub fn transactions(&self) -> u32 {
+        self.transactions
+    }
+
+    /// Clear the accounted bus time and transaction count.
+    pub fn cost_reset(&mut self) {
+        self.elapsed_us = 0.0;
+        self.transactions = 0;
+    }
+
+    /// Queue expected register accesses.
+    ///
+    /// From the first call on, every access of the driver is compared with the next expected one, reads and
+    /// writes alike, until [`Self::expectations_check`]; the registers are updated whatever the outcome.
+    ///
+    /// ### Arguments
+    /// - `accesses`: The accesses, in order, appended to those already queued.
+    pub fn expect(&mut self, accesses: &[Access]) {
+        self.armed = true;
+        for access in accesses {
+            match self.expected.get_mut(self.expected_len) {
+                Some(slot) => {
+                    *slot = Some(*access);
+                    self.expected_len += 1;
+                }
+                None => {
+                    self.failure.get_or_insert(ExpectationError::Overflow);
+                }
+            }
+        }
+    }
+
+    /// Check that the accesses since the first [`Self::expect`] are the expected ones, then clear the
+    /// expectations.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If every expected access happened, in order, and no other.
+    /// - `Err(ExpectationError)`: The first difference.
+    pub fn expectations_check(&mut self) -> Result<(), ExpectationError> {
+        let missing = self
+            .expected
+            .get(self.observed)
+            .copied()
+            .flatten()
+            .map(|expected| ExpectationError::Missing {
+                index: self.observed,
+                expected,
+            });
+        let result = match self.failure.or(missing) {
+            Some(failure) => Err(failure),
+            None => Ok(()),
+        };
+
+        self.expected = [None; MAX_EXPECTATIONS];
+        self.expected_len = 0;
+        self.observed = 0;
+        self.armed = false;
+        self.failure = None;
+        result
+    }
+
+    fn observe(&mut self, found: Access) {
+        if !self.armed {
+            return;
+        }
+        let index = self.observed;
+        self.observed += 1;
+        // The slots past the queued accesses are empty
+        let failure = match self.expected.get(index).copied().flatten() {
+            Some(expected) if expected == found => return,
+            Some(expected) => ExpectationError::Mismatch {
+                index,
+                expected,
+                found,
+            },
+            None => ExpectationError::Unexpected { index, found },
+        };
+        self.failure.get_or_insert(failure);
+    }
+
+    fn account(&mut self, bytes: usize) {
+        self.elapsed_us += self.fixed_us as f64 + self.per_byte_us as f64 * bytes as f64;
+        self.transactions = self.transactions.saturating_add(1);
+    }
+
+    fn next_addr(&self, addr: u8) -> u8 {
+        if Ctrl2::from_bits(self.reg(Reg::Ctrl2)).if_add_inc() == 0 {
+            return addr;
+        }
+        let fifo_on =
+            FifoCtrl::from_bits(self.reg(Reg::FifoCtrl)).fmode() != Fmode::BypassMode as u8;
+        match addr {
+            a if fifo_on && a == Reg::OutZH as u8 => Reg::OutXL as u8,
+            a => (a + 1) % REG_MAP_LEN as u8,
+        }
+    }
+
+    fn read_at(&mut self, rbuf: &mut [u8]) {
+        for byte in rbuf.iter_mut() {
+            *byte = self
+                .regs
+                .get(self.addr as usize)
+                .copied()
+                .unwrap_or_default();
+            self.addr = self.next_addr(self.addr);
+        }
+    }
+}
+
+impl BusOperation for MockBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.account(rbuf.len());
+        self.observe(Access::Read(self.addr));
+        self.read_at(rbuf);
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.account(wbuf.len());
+        let Some((reg, data)) = wbuf.split_first() else {
+            return Ok(());
+        };
+        self.addr = *reg;
+        for byte in data {
+            self.observe(Access::Write(self.addr, *byte));
+            if self.addr == Reg::Ctrl2 as u8 && Ctrl2::from_bits(*byte).soft_reset() == 1 {
+                // The software reset completes at once and clears itself
+                self.regs = reset_image();
+            } else if let Some(slot) = self.regs.get_mut(self.addr as usize) {
+                *slot = *byte;
+            }
+            self.addr = self.next_addr(self.addr);
+        }
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.account(wbuf.len() + rbuf.len());
+        self.addr = wbuf[0];
+        self.observe(Access::Read(self.addr));
+        self.read_at(rbuf);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl crate::asynch::AsyncBusOperation for MockBus {
+    type Error = Infallible;
+
+    async fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        BusOperation::read_from_register(self, reg, buf)
+    }
+
+    async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        BusOperation::write_to_register(self, reg, buf)
+    }
+}
+
+/// Delay that returns immediately.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for NoDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
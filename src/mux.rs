@@ -0,0 +1,184 @@
+//! Devices behind a PCA954x I2C switch.
+//!
+//! Multi-sensor rigs put several devices with the same address behind an I2C switch (PCA9543,
+//! PCA9545, PCA9546, PCA9548): a device only answers while the channel it sits on is enabled in
+//! the control register of the switch. [`MuxedI2cBus`] talks to the device on a given channel: each
+//! register access enables that channel first, once per transaction of the driver, never in the
+//! middle of a burst:
+//!
+//! ```ignore
+//! let i2c = RefCell::new(i2c);
+//! let bus = MuxedI2cBus::new(RefCellDevice::new(&i2c), PCA9548_ADDR, 3, I2CAddress::I2cAddH)?;
+//! let mut sensor = Iis2dlpc::from_bus(bus, delay);
+//! ```
+//!
+//! Selecting the channel before every transaction, rather than once, keeps each driver correct
+//! when several of them share the switch, and reconnects the device after the switch lost its
+//! selection, e.g. after a reset of the switch: the next transaction selects the channel again.
+//! The selection lives in the bus, not in a bus prelude, so that it survives the conversions
+//! between the blocking and the async driver: with the `async` feature, the bus also implements
+//! `asynch::AsyncBusOperation` over an async I2C peripheral.
+//!
+//! The PCA9542 and PCA9544 multiplexers encode the channel differently in their control register
+//! and are not covered.
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+use st_mems_bus::BusOperation;
+
+use crate::prelude::*;
+use crate::{Error, I2CAddress};
+
+/// Number of channels of a PCA9548; the smaller switches use the lowest ones.
+pub const CHANNELS: u8 = 8;
+
+/// I2C bus of a device behind a PCA954x switch.
+///
+/// Every register access selects the channel of the device, then performs a single I2C transaction,
+/// see the [module](self) documentation.
+#[derive(Debug)]
+pub struct MuxedI2cBus<P> {
+    i2c: P,
+    mux_address: SevenBitAddress,
+    control: u8,
+    address: SevenBitAddress,
+}
+
+impl<P: ErrorType> MuxedI2cBus<P> {
+    /// Wrap the I2C peripheral for the device on a channel of the switch.
+    ///
+    /// ### Arguments
+    /// - `i2c`: The I2C peripheral, usually a shared one when several devices sit behind the switch.
+    /// - `mux_address`: The 7-bit address of the switch, `0x70` to `0x77`.
+    /// - `channel`: The channel the device sits on, from 0 to [`CHANNELS`] - 1.
+    /// - `address`: The I2C address of the device.
+    ///
+    /// ### Returns
+    /// - `Ok(MuxedI2cBus)`: The bus.
+    /// - `Err(Error::OutOfRange)`: If the channel does not exist; `max` is the highest channel.
+    pub fn new(
+        i2c: P,
+        mux_address: SevenBitAddress,
+        channel: u8,
+        address: I2CAddress,
+    ) -> Result<Self, Error<P::Error>> {
+        Ok(Self {
+            i2c,
+            mux_address,
+            control: control_for(channel)?,
+            address: address as SevenBitAddress,
+        })
+    }
+
+    /// Give back the I2C peripheral.
+    pub fn release(self) -> P {
+        self.i2c
+    }
+}
+
+impl<P: I2c> MuxedI2cBus<P> {
+    /// Enable the channel of the device, and only that one, in the switch.
+    ///
+    /// This function writes the control register of the switch, a single byte write to `mux_address`. The
+    /// register accesses of the bus call it first: it is only needed on its own before a raw transfer.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(P::Error)`: If there is an error at the bus level, e.g. the switch does not acknowledge.
+    pub fn select_channel(&mut self) -> Result<(), P::Error> {
+        self.i2c.write(self.mux_address, &[self.control])
+    }
+}
+
+impl<P: I2c> BusOperation for MuxedI2cBus<P> {
+    type Error = P::Error;
+
+    fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.select_channel()?;
+        self.i2c.write_read(self.address, &[reg], buf)
+    }
+
+    fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        self.select_channel()?;
+        // Adjacent writes of a transaction are sent as a single one
+        self.i2c.transaction(
+            self.address,
+            &mut [Operation::Write(&[reg]), Operation::Write(buf)],
+        )
+    }
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.read(self.address, rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.write(self.address, wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c.write_read(self.address, wbuf, rbuf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: embedded_hal_async::i2c::I2c> crate::asynch::AsyncBusOperation for MuxedI2cBus<P> {
+    type Error = P::Error;
+
+    async fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write(self.mux_address, &[self.control]).await?;
+        self.i2c.write_read(self.address, &[reg], buf).await
+    }
+
+    async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.write(self.mux_address, &[self.control]).await?;
+        self.i2c
+            .transaction(
+                self.address,
+                &mut [Operation::Write(&[reg]), Operation::Write(buf)],
+            )
+            .await
+    }
+}
+
+/// Read the device identifier behind a PCA954x switch without constructing the driver.
+///
+/// This function is the switch counterpart of [`crate::probe_who_am_i`]: it selects the channel and reads
+/// `WHO_AM_I` in one register access, through a borrow of `i2c`. Scanning the channels of a rig with it finds
+/// the populated ones.
+///
+/// ### Arguments
+/// - `i2c`: The I2C peripheral.
+/// - `mux_address`: The 7-bit address of the switch.
+/// - `channel`: The channel to probe, from 0 to [`CHANNELS`] - 1.
+/// - `addr`: The I2C address of the device.
+///
+/// ### Returns
+/// - `Ok(u8)`: The content of `WHO_AM_I`, [`crate::ID`] for an IIS2DLPC.
+/// - `Err(Error::OutOfRange)`: If the channel does not exist; nothing is written.
+/// - `Err(Error::Bus)`: If there is an error at the bus level.
+pub fn probe_who_am_i_muxed<P: I2c>(
+    i2c: &mut P,
+    mux_address: SevenBitAddress,
+    channel: u8,
+    addr: I2CAddress,
+) -> Result<u8, Error<P::Error>> {
+    let mut bus = MuxedI2cBus::new(i2c, mux_address, channel, addr)?;
+    let mut buf = [0];
+    bus.read_from_register(Reg::WhoAmI as u8, &mut buf)
+        .map_err(Error::Bus)?;
+    Ok(buf[0])
+}
+
+/// Compute the control register value enabling `channel` alone.
+fn control_for<E>(channel: u8) -> Result<u8, Error<E>> {
+    if channel >= CHANNELS {
+        return Err(Error::OutOfRange {
+            requested: channel.into(),
+            max: (CHANNELS - 1).into(),
+        });
+    }
+    Ok(1 << channel)
+}
@@ -0,0 +1,144 @@
+//! Noise and current consumption of the operating modes.
+//!
+//! The low-power modes 1 to 4 and the high-performance mode trade noise for current, and the
+//! low-noise configuration lowers the noise at no current cost. The tables below hold the
+//! typical values of the datasheet (±2 g, 25 °C, 1.8 V supply) so that the tradeoff can be
+//! evaluated by the application: [`crate::prelude::Mode::noise_ug_per_sqrt_hz`] and
+//! [`crate::prelude::Mode::typical_current_ua`] query them, [`mode_for_budget`] picks the
+//! lowest-power mode meeting a noise and a current budget.
+//!
+//! The values are typical, not guaranteed: leave a margin in the budgets.
+
+use crate::prelude::*;
+
+/// Power level of the tables, shared by the continuous, single and low-noise variants.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PowerLevel {
+    /// Low-power mode 1, 12-bit resolution.
+    LowPower1,
+    /// Low-power mode 2.
+    LowPower2,
+    /// Low-power mode 3.
+    LowPower3,
+    /// Low-power mode 4.
+    LowPower4,
+    /// High-performance mode.
+    HighPerformance,
+}
+
+impl PowerLevel {
+    /// Every power level, from the lowest current to the highest.
+    pub const ALL: [PowerLevel; 5] = [
+        PowerLevel::LowPower1,
+        PowerLevel::LowPower2,
+        PowerLevel::LowPower3,
+        PowerLevel::LowPower4,
+        PowerLevel::HighPerformance,
+    ];
+
+    /// Get the power level of an operating mode.
+    pub fn of(mode: Mode) -> Self {
+        match (mode.mode(), mode.lp_mode()) {
+            (0x01, _) => PowerLevel::HighPerformance,
+            (_, 0x00) => PowerLevel::LowPower1,
+            (_, 0x01) => PowerLevel::LowPower2,
+            (_, 0x02) => PowerLevel::LowPower3,
+            _ => PowerLevel::LowPower4,
+        }
+    }
+}
+
+/// Noise density in µg/√Hz, per power level (see [`PowerLevel::ALL`]): low noise off, low noise on.
+pub const NOISE_DENSITY_UG: [[u32; 2]; 5] =
+    [[1300, 1000], [930, 700], [650, 500], [550, 390], [110, 90]];
+
+/// Typical supply current in µA, per output data rate and power level (see [`PowerLevel::ALL`]).
+///
+/// `None` where the datasheet has no entry: the low-power modes run at 200 Hz at most, the
+/// high-performance mode at 12.5 Hz at least.
+pub const TYPICAL_CURRENT_UA: [(Odr, [Option<f32>; 5]); 9] = [
+    (
+        Odr::_1_6hzLpOnly,
+        [Some(0.38), Some(0.52), Some(0.62), Some(0.84), None],
+    ),
+    (
+        Odr::_12_5hz,
+        [Some(1.0), Some(1.6), Some(2.3), Some(4.0), Some(90.0)],
+    ),
+    (
+        Odr::_25hz,
+        [Some(1.5), Some(2.6), Some(4.0), Some(7.0), Some(90.0)],
+    ),
+    (
+        Odr::_50hz,
+        [Some(2.5), Some(4.5), Some(7.5), Some(14.0), Some(90.0)],
+    ),
+    (
+        Odr::_100hz,
+        [Some(4.5), Some(8.5), Some(15.0), Some(27.0), Some(90.0)],
+    ),
+    (
+        Odr::_200hz,
+        [Some(8.5), Some(17.0), Some(29.0), Some(54.0), Some(90.0)],
+    ),
+    (Odr::_400hz, [None, None, None, None, Some(90.0)]),
+    (Odr::_800hz, [None, None, None, None, Some(90.0)]),
+    (Odr::_1_6khz, [None, None, None, None, Some(90.0)]),
+];
+
+/// Continuous operating modes considered by [`mode_for_budget`].
+pub const CONTINUOUS_MODES: [Mode; 10] = [
+    Mode::ContLowPwr12bit,
+    Mode::ContLowPwrLowNoise12bit,
+    Mode::ContLowPwr2,
+    Mode::ContLowPwrLowNoise2,
+    Mode::ContLowPwr3,
+    Mode::ContLowPwrLowNoise3,
+    Mode::ContLowPwr4,
+    Mode::ContLowPwrLowNoise4,
+    Mode::HighPerformance,
+    Mode::HighPerformanceLowNoise,
+];
+
+/// Select the lowest-power continuous mode meeting a noise and a current budget.
+///
+/// Among [`CONTINUOUS_MODES`], the modes with a table entry at `odr` and within both budgets are
+/// kept, and the one with the lowest typical current is returned; at equal current, the lowest
+/// noise wins, so a low-noise variant is preferred.
+///
+/// ### Arguments
+/// - `max_noise`: The highest acceptable noise density, in µg/√Hz.
+/// - `max_current`: The highest acceptable typical current, in µA.
+/// - `odr`: The output data rate the mode will run at.
+///
+/// ### Returns
+/// - `Some(Mode)`: The selected mode.
+/// - `None`: If no mode meets both budgets at `odr`, or `odr` is off or triggered on demand.
+pub fn mode_for_budget(max_noise: u32, max_current: u32, odr: Odr) -> Option<Mode> {
+    CONTINUOUS_MODES
+        .into_iter()
+        .filter(|mode| mode.noise_ug_per_sqrt_hz() <= max_noise)
+        .filter_map(|mode| Some((mode, mode.typical_current_ua(odr)?)))
+        .filter(|(_, current)| *current <= max_current as f32)
+        .min_by(|(a, a_current), (b, b_current)| {
+            a_current
+                .total_cmp(b_current)
+                .then(a.noise_ug_per_sqrt_hz().cmp(&b.noise_ug_per_sqrt_hz()))
+        })
+        .map(|(mode, _)| mode)
+}
+
+/// Get the noise density of a power level, see [`NOISE_DENSITY_UG`].
+pub(crate) fn noise_density(level: PowerLevel, low_noise: bool) -> u32 {
+    let [off, on] = NOISE_DENSITY_UG
+        .get(level as usize)
+        .copied()
+        .unwrap_or_default();
+    if low_noise { on } else { off }
+}
+
+/// Get the typical current of a power level, see [`TYPICAL_CURRENT_UA`].
+pub(crate) fn typical_current(level: PowerLevel, odr: Odr) -> Option<f32> {
+    let (_, row) = TYPICAL_CURRENT_UA.iter().find(|(rate, _)| *rate == odr)?;
+    row.get(level as usize).copied().flatten()
+}
@@ -0,0 +1,124 @@
+//! Synchronized acquisition on two sensors.
+//!
+//! A [`SensorPair`] drives two IIS2DLPC with the same configuration and acquires paired samples
+//! for differential measurements, for example machine versus frame vibration. The conversions
+//! are started on demand, back-to-back, so that the two samples are taken as close as possible.
+
+use crate::snapshot::ConfigSnapshot;
+use crate::wait::WaitStrategy;
+use crate::{BusOperation, DelayNs, Error, Iis2dlpc};
+
+/// Interval between two data-ready polls, in microseconds.
+pub const PAIR_POLL_US: u32 = 100;
+
+/// Maximum number of data-ready polls of a paired acquisition.
+pub const PAIR_MAX_POLLS: u32 = 1000;
+
+/// Errors of a [`SensorPair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairError<E1, E2> {
+    /// Error on the first sensor.
+    A(Error<E1>),
+    /// Error on the second sensor.
+    B(Error<E2>),
+    /// The conversions did not complete within [`PAIR_MAX_POLLS`] polls.
+    NotReady,
+}
+
+/// Paired acceleration samples.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct PairedSample {
+    /// Raw acceleration of the first sensor.
+    pub a: [i16; 3],
+    /// Raw acceleration of the second sensor.
+    pub b: [i16; 3],
+    /// Estimated delay between the two conversions, in microseconds.
+    pub skew_us: u32,
+}
+
+/// Two sensors acquired together.
+#[derive(Debug)]
+pub struct SensorPair<B1: BusOperation, B2: BusOperation, T> {
+    /// First sensor.
+    pub a: Iis2dlpc<B1, T>,
+    /// Second sensor.
+    pub b: Iis2dlpc<B2, T>,
+    /// Timer used to wait for the conversions.
+    pub tim: T,
+    transaction_us: u32,
+}
+
+impl<B1: BusOperation, B2: BusOperation, T: DelayNs> SensorPair<B1, B2, T> {
+    /// Create a sensor pair.
+    ///
+    /// ### Arguments
+    /// - `a`: The first sensor.
+    /// - `b`: The second sensor.
+    /// - `tim`: The timer used to wait for the conversions.
+    /// - `transaction_us`: Duration of a single register write on the bus of the second sensor, in microseconds
+    ///   (about 70 µs at 400 kHz I²C); it is the delay between the two conversion triggers.
+    pub fn new(a: Iis2dlpc<B1, T>, b: Iis2dlpc<B2, T>, tim: T, transaction_us: u32) -> Self {
+        Self {
+            a,
+            b,
+            tim,
+            transaction_us,
+        }
+    }
+
+    /// Release the two sensors and the timer.
+    pub fn release(self) -> (Iis2dlpc<B1, T>, Iis2dlpc<B2, T>, T) {
+        (self.a, self.b, self.tim)
+    }
+
+    /// Apply the same configuration to both sensors.
+    ///
+    /// The configuration is usually captured with [`Iis2dlpc::config_snapshot_get`] on one sensor.
+    /// It must select a single data conversion mode, see [`Self::sample_pair_get`].
+    ///
+    /// ### Arguments
+    /// - `config`: The configuration to apply.
+    ///
+    /// ### Returns
+    /// - `Ok(())`: If the operation is successful.
+    /// - `Err(PairError)`: The error of the failing sensor.
+    pub fn sync_configure(
+        &mut self,
+        config: &ConfigSnapshot,
+    ) -> Result<(), PairError<B1::Error, B2::Error>> {
+        self.a.config_snapshot_apply(config).map_err(PairError::A)?;
+        self.b.config_snapshot_apply(config).map_err(PairError::B)
+    }
+
+    /// Acquire a paired sample.
+    ///
+    /// This function triggers a single conversion on the first sensor, then on the second one, waits for
+    /// both data-ready flags, polling the sensors alternately, and reads both samples.
+    ///
+    /// ### Returns
+    /// - `Ok(PairedSample)`: The samples and the skew estimate, one register write.
+    /// - `Err(PairError::NotReady)`: If a conversion did not complete in time.
+    /// - `Err(PairError)`: `Error::InvalidConfig` if a sensor is not in a single data conversion mode, or the bus error of the failing sensor.
+    pub fn sample_pair_get(&mut self) -> Result<PairedSample, PairError<B1::Error, B2::Error>> {
+        self.a.on_demand_check().map_err(PairError::A)?;
+        self.b.on_demand_check().map_err(PairError::B)?;
+
+        self.a.single_conversion_trigger().map_err(PairError::A)?;
+        self.b.single_conversion_trigger().map_err(PairError::B)?;
+
+        let (mut a_ready, mut b_ready) = (false, false);
+        for _ in 0..PAIR_MAX_POLLS {
+            a_ready = a_ready || self.a.status_reg_get().map_err(PairError::A)?.drdy() == 1;
+            b_ready = b_ready || self.b.status_reg_get().map_err(PairError::B)?.drdy() == 1;
+            if a_ready && b_ready {
+                return Ok(PairedSample {
+                    a: self.a.acceleration_raw_get().map_err(PairError::A)?,
+                    b: self.b.acceleration_raw_get().map_err(PairError::B)?,
+                    skew_us: self.transaction_us,
+                });
+            }
+            self.tim.wait_us(PAIR_POLL_US);
+        }
+        Err(PairError::NotReady)
+    }
+}
@@ -0,0 +1,142 @@
+//! Write order of the configuration registers.
+//!
+//! Every function writing several configuration registers at once,
+//! [`crate::Iis2dlpc::config_snapshot_apply`] and its checked variant, [`crate::Iis2dlpc::apply_blob`],
+//! [`crate::Iis2dlpc::apply_delta`], [`crate::Iis2dlpc::ordered_apply`] and the async
+//! `config_snapshot_apply`, writes them phase by phase, in the order of [`REG_PHASES`]:
+//!
+//! | Phase                     | Registers                                                   |
+//! |---------------------------|-------------------------------------------------------------|
+//! | 1 [`Phase::Interface`]    | `CTRL2`, `CTRL3`                                            |
+//! | 2 [`Phase::Offsets`]      | `X_OFS_USR`, `Y_OFS_USR`, `Z_OFS_USR`                       |
+//! | 3 [`Phase::Thresholds`]   | `TAP_THS_X` .. `FREE_FALL`                                  |
+//! | 4 [`Phase::FilterScale`]  | `CTRL6`                                                     |
+//! | 5 [`Phase::Routing`]      | `CTRL7`, `CTRL4_INT1_PAD_CTRL`, `CTRL5_INT2_PAD_CTRL`       |
+//! | 6 [`Phase::Fifo`]         | `FIFO_CTRL`                                                 |
+//! | 7 [`Phase::DataRate`]     | `CTRL1`                                                     |
+//!
+//! The interface comes first so that the following accesses are reliable; the values the embedded
+//! functions compare against come before the filter and scale they apply to; the events are routed
+//! once they are configured, the FIFO collects once the data is, and the data rate starts the whole
+//! last, so that no sample or event comes from a partially written configuration. Within a phase
+//! the registers are written in the order of the table.
+//!
+//! A register belongs to one phase: `CTRL3` also holds the pad polarity and drive, which configure
+//! the interface of the pads, and `CTRL7` also holds the offset weight and the filter feeds, but
+//! enables the interrupts. The only write outside the phases is the data rate stop of a
+//! [`crate::snapshot::ConfigDelta`] changing the scaling of a running stream, see
+//! [`crate::snapshot::ConfigSnapshot::diff`]: a `CTRL1` write with the data rate at power-down,
+//! before phase 1.
+
+use crate::prelude::*;
+
+/// Step of the write order of the configuration registers, see the [module](self) documentation.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Phase {
+    /// Serial interface and pad electrical settings.
+    Interface = 1,
+    /// User offsets.
+    Offsets = 2,
+    /// Thresholds and durations of the embedded functions.
+    Thresholds = 3,
+    /// Filter, full scale and low noise.
+    FilterScale = 4,
+    /// Interrupt routing and notification.
+    Routing = 5,
+    /// FIFO mode and watermark.
+    Fifo = 6,
+    /// Data rate and operating mode, which start the conversions.
+    DataRate = 7,
+}
+
+/// Phase of every configuration register, in write order.
+pub const REG_PHASES: [(Reg, Phase); 18] = [
+    (Reg::Ctrl2, Phase::Interface),
+    (Reg::Ctrl3, Phase::Interface),
+    (Reg::XOfsUsr, Phase::Offsets),
+    (Reg::YOfsUsr, Phase::Offsets),
+    (Reg::ZOfsUsr, Phase::Offsets),
+    (Reg::TapThsX, Phase::Thresholds),
+    (Reg::TapThsY, Phase::Thresholds),
+    (Reg::TapThsZ, Phase::Thresholds),
+    (Reg::IntDur, Phase::Thresholds),
+    (Reg::WakeUpThs, Phase::Thresholds),
+    (Reg::WakeUpDur, Phase::Thresholds),
+    (Reg::FreeFall, Phase::Thresholds),
+    (Reg::Ctrl6, Phase::FilterScale),
+    (Reg::Ctrl7, Phase::Routing),
+    (Reg::Ctrl4Int1PadCtrl, Phase::Routing),
+    (Reg::Ctrl5Int2PadCtrl, Phase::Routing),
+    (Reg::FifoCtrl, Phase::Fifo),
+    (Reg::Ctrl1, Phase::DataRate),
+];
+
+impl Phase {
+    /// Every phase, in write order.
+    pub const ALL: [Phase; 7] = [
+        Phase::Interface,
+        Phase::Offsets,
+        Phase::Thresholds,
+        Phase::FilterScale,
+        Phase::Routing,
+        Phase::Fifo,
+        Phase::DataRate,
+    ];
+
+    /// Get the phase of a register.
+    ///
+    /// ### Returns
+    /// - `Some(Phase)`: The phase `reg` is written in.
+    /// - `None`: If `reg` is not a configuration register, e.g. an output or a source register.
+    pub fn of(reg: Reg) -> Option<Phase> {
+        REG_PHASES
+            .iter()
+            .find(|(r, _)| *r == reg)
+            .map(|(_, phase)| *phase)
+    }
+
+    /// Get the number of the phase, from 1 to 7.
+    pub fn number(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Iterate over the registers of the phase, in write order.
+    pub fn regs(self) -> impl Iterator<Item = Reg> {
+        REG_PHASES
+            .into_iter()
+            .filter(move |(_, phase)| *phase == self)
+            .map(|(reg, _)| reg)
+    }
+}
+
+/// Check that a sequence of register writes follows the phases.
+///
+/// ### Arguments
+/// - `regs`: The registers written, in order.
+///
+/// ### Returns
+/// - `true`: If every register is a configuration register and no write goes back to an earlier phase.
+/// - `false`: Otherwise.
+pub fn is_phase_ordered(regs: impl IntoIterator<Item = Reg>) -> bool {
+    let mut last = Phase::Interface;
+    regs.into_iter().all(|reg| match Phase::of(reg) {
+        Some(phase) if phase >= last => {
+            last = phase;
+            true
+        }
+        _ => false,
+    })
+}
+
+/// Order register writes by phase and, within a phase, as in [`REG_PHASES`].
+///
+/// The writes to the same register keep their order, those to registers out of [`REG_PHASES`] are dropped.
+pub(crate) fn ordered(writes: &[(Reg, u8)]) -> impl Iterator<Item = (Reg, u8)> + '_ {
+    REG_PHASES.into_iter().flat_map(move |(reg, _)| {
+        writes
+            .iter()
+            .copied()
+            .filter(move |(written, _)| *written == reg)
+    })
+}
@@ -7,7 +7,9 @@ use st_mem_bank_macro::register;
 ///
 /// This enum represents the memory-mapped registers of the IIS2DLPC sensor. Each variant corresponds to a specific register address.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[try_from(repr)]
 pub enum Reg {
     /// Temperature output register (low byte).
     OutTL = 0x0D,
@@ -117,17 +119,150 @@ pub enum Reg {
     Ctrl7 = 0x3F,
 }
 
+impl Reg {
+    /// Every register, in address order.
+    pub const ALL: [Reg; 35] = [
+        Reg::OutTL,
+        Reg::OutTH,
+        Reg::WhoAmI,
+        Reg::Ctrl1,
+        Reg::Ctrl2,
+        Reg::Ctrl3,
+        Reg::Ctrl4Int1PadCtrl,
+        Reg::Ctrl5Int2PadCtrl,
+        Reg::Ctrl6,
+        Reg::OutT,
+        Reg::Status,
+        Reg::OutXL,
+        Reg::OutXH,
+        Reg::OutYL,
+        Reg::OutYH,
+        Reg::OutZL,
+        Reg::OutZH,
+        Reg::FifoCtrl,
+        Reg::FifoSamples,
+        Reg::TapThsX,
+        Reg::TapThsY,
+        Reg::TapThsZ,
+        Reg::IntDur,
+        Reg::WakeUpThs,
+        Reg::WakeUpDur,
+        Reg::FreeFall,
+        Reg::StatusDup,
+        Reg::WakeUpSrc,
+        Reg::TapSrc,
+        Reg::SixdSrc,
+        Reg::AllIntSrc,
+        Reg::XOfsUsr,
+        Reg::YOfsUsr,
+        Reg::ZOfsUsr,
+        Reg::Ctrl7,
+    ];
+
+    /// Get the datasheet name of the register.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reg::OutTL => "OUT_T_L",
+            Reg::OutTH => "OUT_T_H",
+            Reg::WhoAmI => "WHO_AM_I",
+            Reg::Ctrl1 => "CTRL1",
+            Reg::Ctrl2 => "CTRL2",
+            Reg::Ctrl3 => "CTRL3",
+            Reg::Ctrl4Int1PadCtrl => "CTRL4_INT1_PAD_CTRL",
+            Reg::Ctrl5Int2PadCtrl => "CTRL5_INT2_PAD_CTRL",
+            Reg::Ctrl6 => "CTRL6",
+            Reg::OutT => "OUT_T",
+            Reg::Status => "STATUS",
+            Reg::OutXL => "OUT_X_L",
+            Reg::OutXH => "OUT_X_H",
+            Reg::OutYL => "OUT_Y_L",
+            Reg::OutYH => "OUT_Y_H",
+            Reg::OutZL => "OUT_Z_L",
+            Reg::OutZH => "OUT_Z_H",
+            Reg::FifoCtrl => "FIFO_CTRL",
+            Reg::FifoSamples => "FIFO_SAMPLES",
+            Reg::TapThsX => "TAP_THS_X",
+            Reg::TapThsY => "TAP_THS_Y",
+            Reg::TapThsZ => "TAP_THS_Z",
+            Reg::IntDur => "INT_DUR",
+            Reg::WakeUpThs => "WAKE_UP_THS",
+            Reg::WakeUpDur => "WAKE_UP_DUR",
+            Reg::FreeFall => "FREE_FALL",
+            Reg::StatusDup => "STATUS_DUP",
+            Reg::WakeUpSrc => "WAKE_UP_SRC",
+            Reg::TapSrc => "TAP_SRC",
+            Reg::SixdSrc => "SIXD_SRC",
+            Reg::AllIntSrc => "ALL_INT_SRC",
+            Reg::XOfsUsr => "X_OFS_USR",
+            Reg::YOfsUsr => "Y_OFS_USR",
+            Reg::ZOfsUsr => "Z_OFS_USR",
+            Reg::Ctrl7 => "CTRL7",
+        }
+    }
+
+    /// Decode a raw register byte for logging.
+    ///
+    /// The returned value displays the register name and its fields, most significant first, e.g.
+    /// `CTRL6 { bw_filt: 1, fs: 2, fds: 0, low_noise: 1 }`. The bytes of the output registers, which hold
+    /// a part of a 16-bit value, and `WHO_AM_I` display as hexadecimal, as do unknown addresses.
+    ///
+    /// ### Arguments
+    /// - `addr`: The register address.
+    /// - `value`: The raw register content.
+    pub fn decode(addr: u8, value: u8) -> RegDecode {
+        RegDecode { addr, value }
+    }
+}
+
+/// Raw register byte decoded for display, see [`Reg::decode`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegDecode {
+    addr: u8,
+    value: u8,
+}
+
+impl core::fmt::Display for RegDecode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Ok(reg) = Reg::try_from(self.addr) else {
+            return write!(f, "0x{:02x} = 0x{:02x}", self.addr, self.value);
+        };
+        // Only registers whose fields fit in the byte are split
+        let fields = REGISTERS
+            .iter()
+            .find(|(_, addr, fields)| {
+                *addr == self.addr && fields.iter().all(|field| field.offset + field.width <= 8)
+            })
+            .map(|(_, _, fields)| *fields);
+        let Some(fields) = fields else {
+            return write!(f, "{} = 0x{:02x}", reg.name(), self.value);
+        };
+
+        write!(f, "{} {{", reg.name())?;
+        let mut first = true;
+        for offset in (0..8).rev() {
+            for field in fields.iter().filter(|field| field.offset == offset) {
+                let mask = u8::MAX >> (8 - field.width);
+                let val = (self.value >> field.offset) & mask;
+                let sep = if first { " " } else { ", " };
+                write!(f, "{sep}{}: {val}", field.name)?;
+                first = false;
+            }
+        }
+        write!(f, " }}")
+    }
+}
+
 /// Temperature output register (12-bit resolution, read-only).
 ///
 /// The `OutT` register contains the raw temperature sensor output as a 12-bit two's complement value.
 /// The temperature data is left-justified within the 16-bit register.
 ///
-/// The bit order for this struct can be configured using the `bit_order_msb` feature:
-/// * `Msb`: Most significant bit first.
-/// * `Lsb`: Least significant bit first (default).
+/// The layout is fixed by the device and does not follow the `bit_order_msb` feature: the value is
+/// left-justified in the 16-bit word assembled from the low and high bytes, see [`OutT::from_le_bytes`].
 #[register(address = Reg::OutTL, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u16, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u16, order = Lsb))]
+#[bitfield(u16, order = Lsb, defmt = cfg(feature = "defmt"))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct OutT {
     #[bits(4, access = RO, default = 0)]
     not_used: u8,
@@ -144,8 +279,9 @@ pub struct OutT {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl1, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl1 {
     /// Low-power mode selection.
     ///
@@ -180,8 +316,9 @@ pub struct Ctrl1 {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl2, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl2 {
     /// SPI serial interface mode selection.
     ///
@@ -265,12 +402,13 @@ pub struct Ctrl2 {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl3, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl3 {
     /// Single data conversion on demand mode configuration.
     ///
-    /// This field combines two subfields:
+    /// This field combines two subfields, `slp_mode_sel` as the high bit and `slp_mode_1` as the low bit:
     /// - `slp_mode_sel`: Determines the trigger source for single data conversion:
     ///   - `0`: External trigger on INT2.
     ///   - `1`: Triggered by writing `slp_mode_1` to `1` via I²C/SPI.
@@ -329,8 +467,9 @@ pub struct Ctrl3 {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl4Int1PadCtrl, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl4Int1PadCtrl {
     /// Data-ready interrupt routed to INT1 pad.
     ///
@@ -389,8 +528,9 @@ pub struct Ctrl4Int1PadCtrl {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl5Int2PadCtrl, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl5Int2PadCtrl {
     /// Data-ready interrupt routed to INT2 pad.
     ///
@@ -449,8 +589,9 @@ pub struct Ctrl5Int2PadCtrl {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl6, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl6 {
     #[bits(2, access = RO, default = 0)]
     not_used_01: u8,
@@ -500,6 +641,24 @@ pub struct Ctrl6 {
     pub bw_filt: u8,
 }
 
+/// Temperature output register (8-bit resolution, read-only).
+///
+/// The `OUT_T` register holds the 8-bit two's complement temperature, 1 LSB/°C with `0` standing for 25 °C.
+/// It is the high byte of the 12-bit value of [`OutT`], the only meaningful part in the low-power modes.
+///
+/// The bit order for this struct can be configured using the `bit_order_msb` feature:
+/// * `Msb`: Most significant bit first.
+/// * `Lsb`: Least significant bit first (default).
+#[register(address = Reg::OutT, access_type = Iis2dlpc, generics = 2)]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
+pub struct OutT8 {
+    /// Temperature sensor output value.
+    #[bits(8, access = RO, default = 0)]
+    pub temp: i8,
+}
+
 /// Status register (R).
 ///
 /// The `STATUS` register provides the status of various events detected by the IIS2DLPC sensor.
@@ -508,8 +667,9 @@ pub struct Ctrl6 {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Status, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Status {
     /// Data-ready status.
     ///
@@ -597,12 +757,11 @@ pub struct Status {
 /// The `OutX` register contains the raw acceleration data for the X-axis as a 14-bit two's complement value.
 /// The data is left-justified within the 16-bit register, with 2 unused bits.
 ///
-/// The bit order for this struct can be configured using the `bit_order_msb` feature:
-/// * `Msb`: Most significant bit first.
-/// * `Lsb`: Least significant bit first (default).
+/// The layout is fixed by the device and does not follow the `bit_order_msb` feature: the value is
+/// left-justified in the 16-bit word assembled from the low and high bytes, see [`OutX::from_le_bytes`].
 #[register(address = Reg::OutXL, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u16, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u16, order = Lsb))]
+#[bitfield(u16, order = Lsb, defmt = cfg(feature = "defmt"))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct OutX {
     #[bits(2, access = RO, default = 0)]
     not_used: u8,
@@ -616,12 +775,11 @@ pub struct OutX {
 /// The `OutY` register contains the raw acceleration data for the Y-axis as a 14-bit two's complement value.
 /// The data is left-justified within the 16-bit register, with 2 unused bits.
 ///
-/// The bit order for this struct can be configured using the `bit_order_msb` feature:
-/// * `Msb`: Most significant bit first.
-/// * `Lsb`: Least significant bit first (default).
+/// The layout is fixed by the device and does not follow the `bit_order_msb` feature: the value is
+/// left-justified in the 16-bit word assembled from the low and high bytes, see [`OutY::from_le_bytes`].
 #[register(address = Reg::OutYL, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u16, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u16, order = Lsb))]
+#[bitfield(u16, order = Lsb, defmt = cfg(feature = "defmt"))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct OutY {
     #[bits(2, access = RO, default = 0)]
     not_used: u8,
@@ -635,12 +793,11 @@ pub struct OutY {
 /// The `OutZ` register contains the raw acceleration data for the Z-axis as a 14-bit two's complement value.
 /// The data is left-justified within the 16-bit register, with 2 unused bits.
 ///
-/// The bit order for this struct can be configured using the `bit_order_msb` feature:
-/// * `Msb`: Most significant bit first.
-/// * `Lsb`: Least significant bit first (default).
+/// The layout is fixed by the device and does not follow the `bit_order_msb` feature: the value is
+/// left-justified in the 16-bit word assembled from the low and high bytes, see [`OutZ::from_le_bytes`].
 #[register(address = Reg::OutZL, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u16, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u16, order = Lsb))]
+#[bitfield(u16, order = Lsb, defmt = cfg(feature = "defmt"))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct OutZ {
     #[bits(2, access = RO, default = 0)]
     not_used: u8,
@@ -649,6 +806,25 @@ pub struct OutZ {
     pub z: i16,
 }
 
+/// Implement the assembly of the 16-bit output registers from the bytes on the wire.
+macro_rules! from_le_bytes {
+    ($($reg:ident),* $(,)?) => {
+        $(
+            impl $reg {
+                /// Assemble the register from its low and high bytes, as read from the bus.
+                ///
+                /// The device sends the low byte first, at the lower address; the driver reads the
+                /// output registers through this function only, whatever the `bit_order_msb` feature.
+                pub const fn from_le_bytes(bytes: [u8; 2]) -> Self {
+                    Self::from_bits(u16::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+from_le_bytes!(OutT, OutX, OutY, OutZ);
+
 /// FIFO control register (R/W).
 ///
 /// The `FIFO_CTRL` register is used to configure the FIFO threshold level and mode.
@@ -657,8 +833,9 @@ pub struct OutZ {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::FifoCtrl, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct FifoCtrl {
     /// FIFO threshold level.
     ///
@@ -690,8 +867,9 @@ pub struct FifoCtrl {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::FifoSamples, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct FifoSamples {
     /// Number of unread samples in FIFO.
     ///
@@ -730,8 +908,9 @@ pub struct FifoSamples {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::TapThsX, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct TapThsX {
     /// Tap threshold for the X-axis.
     ///
@@ -766,8 +945,9 @@ pub struct TapThsX {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::TapThsY, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct TapThsY {
     /// Tap threshold for the Y-axis.
     ///
@@ -792,8 +972,9 @@ pub struct TapThsY {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::TapThsZ, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct TapThsZ {
     /// Tap threshold for the Z-axis.
     ///
@@ -828,8 +1009,9 @@ pub struct TapThsZ {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::IntDur, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct IntDur {
     /// Shock duration.
     ///
@@ -858,8 +1040,9 @@ pub struct IntDur {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::WakeUpThs, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct WakeUpThs {
     /// Wakeup threshold.
     ///
@@ -888,8 +1071,9 @@ pub struct WakeUpThs {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::WakeUpDur, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct WakeUpDur {
     /// Sleep duration.
     ///
@@ -924,8 +1108,9 @@ pub struct WakeUpDur {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::FreeFall, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct FreeFall {
     /// Free-fall threshold.
     ///
@@ -948,8 +1133,9 @@ pub struct FreeFall {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::StatusDup, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct StatusDup {
     /// Data-ready status.
     ///
@@ -1040,8 +1226,9 @@ pub struct StatusDup {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::WakeUpSrc, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct WakeUpSrc {
     /// Wakeup event detection status on the Z-axis.
     ///
@@ -1103,8 +1290,9 @@ pub struct WakeUpSrc {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::TapSrc, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct TapSrc {
     /// Tap event detection status on the Z-axis.
     ///
@@ -1172,8 +1360,9 @@ pub struct TapSrc {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::SixdSrc, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct SixdSrc {
     /// X-axis low threshold status.
     ///
@@ -1233,8 +1422,9 @@ pub struct SixdSrc {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::AllIntSrc, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct AllIntSrc {
     /// Free-fall event detection status.
     ///
@@ -1309,8 +1499,9 @@ pub struct AllIntSrc {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::XOfsUsr, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct XOfsUsr {
     /// User offset value for the X-axis.
     #[bits(8, default = 0)]
@@ -1326,8 +1517,9 @@ pub struct XOfsUsr {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::YOfsUsr, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct YOfsUsr {
     /// User offset value for the Y-axis.
     #[bits(8, default = 0)]
@@ -1343,8 +1535,9 @@ pub struct YOfsUsr {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::ZOfsUsr, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct ZOfsUsr {
     /// User offset value for the Z-axis.
     #[bits(8, default = 0)]
@@ -1359,8 +1552,9 @@ pub struct ZOfsUsr {
 /// * `Msb`: Most significant bit first.
 /// * `Lsb`: Least significant bit first (default).
 #[register(address = Reg::Ctrl7, access_type = Iis2dlpc, generics = 2)]
-#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb))]
-#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb))]
+#[cfg_attr(feature = "bit_order_msb", bitfield(u8, order = Msb, defmt = cfg(feature = "defmt")))]
+#[cfg_attr(not(feature = "bit_order_msb"), bitfield(u8, order = Lsb, defmt = cfg(feature = "defmt")))]
+#[derive(PartialEq, Eq, Hash)]
 pub struct Ctrl7 {
     /// Low-pass filter data sent to 6D function.
     ///
@@ -1443,10 +1637,261 @@ pub struct Ctrl7 {
     pub drdy_pulsed: u8,
 }
 
+/// Implement the reset value helpers of register structs.
+macro_rules! reset_value {
+    ($($reg:ident: $ty:ty = $val:expr),+ $(,)?) => {
+        $(
+            impl $reg {
+                /// Raw content of the register after power-up or software reset, as documented in the datasheet.
+                pub const RESET_VALUE: $ty = $val;
+
+                /// Check whether the register holds its reset value.
+                pub fn is_reset_default(&self) -> bool {
+                    self.into_bits() == Self::RESET_VALUE
+                }
+            }
+        )+
+    };
+}
+
+reset_value!(
+    OutT: u16 = 0x0000,
+    Ctrl1: u8 = 0x00,
+    Ctrl2: u8 = 0x04,
+    Ctrl3: u8 = 0x00,
+    Ctrl4Int1PadCtrl: u8 = 0x00,
+    Ctrl5Int2PadCtrl: u8 = 0x00,
+    Ctrl6: u8 = 0x00,
+    OutT8: u8 = 0x00,
+    Status: u8 = 0x00,
+    OutX: u16 = 0x0000,
+    OutY: u16 = 0x0000,
+    OutZ: u16 = 0x0000,
+    FifoCtrl: u8 = 0x00,
+    FifoSamples: u8 = 0x00,
+    TapThsX: u8 = 0x00,
+    TapThsY: u8 = 0x00,
+    TapThsZ: u8 = 0x00,
+    IntDur: u8 = 0x00,
+    WakeUpThs: u8 = 0x00,
+    WakeUpDur: u8 = 0x00,
+    FreeFall: u8 = 0x00,
+    StatusDup: u8 = 0x00,
+    WakeUpSrc: u8 = 0x00,
+    TapSrc: u8 = 0x00,
+    SixdSrc: u8 = 0x00,
+    AllIntSrc: u8 = 0x00,
+    XOfsUsr: u8 = 0x00,
+    YOfsUsr: u8 = 0x00,
+    ZOfsUsr: u8 = 0x00,
+    Ctrl7: u8 = 0x00,
+);
+
+/// Layout of a register field, for tools that enumerate the register map.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FieldDesc {
+    /// Name of the field, as its getter in the register struct.
+    pub name: &'static str,
+    /// Position of the least significant bit of the field in the register.
+    pub offset: u8,
+    /// Width of the field, in bits.
+    pub width: u8,
+    /// `true` if the field cannot be written.
+    pub read_only: bool,
+}
+
+/// Implement the field descriptors of register structs.
+///
+/// Offsets and widths are found by probing every bit of the raw value through the field getter, at
+/// compile time, so that the descriptors cannot drift from the bitfield layout or its bit order.
+/// Padding fields have no getter and are not described.
+macro_rules! field_desc {
+    (@read_only RO) => { true };
+    (@read_only RW) => { false };
+    (@layout $field:ident) => {{
+        let mut offset = 0;
+        let mut width = 0;
+        let mut bit = 0;
+        while bit < 8 * core::mem::size_of::<Self>() as u32 {
+            if Self::from_bits(1 << bit).$field() != 0 {
+                if width == 0 {
+                    offset = bit as u8;
+                }
+                width += 1;
+            }
+            bit += 1;
+        }
+        (offset, width)
+    }};
+    ($($reg:ident: $access:ident [$($field:ident),+ $(,)?]),+ $(,)?) => {
+        $(
+            impl $reg {
+                /// Layout of the fields of the register, in declaration order.
+                pub const FIELDS: &'static [FieldDesc] = &[
+                    $(
+                        FieldDesc {
+                            name: stringify!($field),
+                            offset: field_desc!(@layout $field).0,
+                            width: field_desc!(@layout $field).1,
+                            read_only: field_desc!(@read_only $access),
+                        },
+                    )+
+                ];
+            }
+        )+
+    };
+}
+
+field_desc!(
+    OutT: RO [temp],
+    Ctrl1: RW [lp_mode, mode, odr],
+    Ctrl2: RW [sim, i2c_disable, if_add_inc, bdu, cs_pu_disc, soft_reset, boot],
+    Ctrl3: RW [slp_mode, h_lactive, lir, pp_od, st],
+    Ctrl4Int1PadCtrl: RW [int1_drdy, int1_fth, int1_diff5, int1_tap, int1_ff, int1_wu, int1_single_tap, int1_6d],
+    Ctrl5Int2PadCtrl: RW [int2_drdy, int2_fth, int2_diff5, int2_ovr, int2_drdy_t, int2_boot, int2_sleep_chg, int2_sleep_state],
+    Ctrl6: RW [low_noise, fds, fs, bw_filt],
+    OutT8: RO [temp],
+    Status: RO [drdy, ff_ia, six_d_ia, single_tap, double_tap, sleep_state, wu_ia, fifo_ths],
+    OutX: RO [x],
+    OutY: RO [y],
+    OutZ: RO [z],
+    FifoCtrl: RW [fth, fmode],
+    FifoSamples: RO [diff, fifo_ovr, fifo_fth],
+    TapThsX: RW [tap_thsx, six_d_ths, four_d_en],
+    TapThsY: RW [tap_thsy, tap_prior],
+    TapThsZ: RW [tap_thsz, tap_z_en, tap_y_en, tap_x_en],
+    IntDur: RW [shock, quiet, latency],
+    WakeUpThs: RW [wk_ths, sleep_on, single_double_tap],
+    WakeUpDur: RW [sleep_dur, stationary, wake_dur, ff_dur],
+    FreeFall: RW [ff_ths, ff_dur],
+    StatusDup: RO [drdy, ff_ia, six_d_ia, single_tap, double_tap, sleep_state_ia, drdy_t, ovr],
+    WakeUpSrc: RO [z_wu, y_wu, x_wu, wu_ia, sleep_state_ia, ff_ia],
+    TapSrc: RO [z_tap, y_tap, x_tap, tap_sign, double_tap, single_tap, tap_ia],
+    SixdSrc: RO [xl, xh, yl, yh, zl, zh, six_d_ia],
+    AllIntSrc: RO [ff_ia, wu_ia, single_tap, double_tap, six_d_ia, sleep_change_ia],
+    XOfsUsr: RW [x_ofs_usr],
+    YOfsUsr: RW [y_ofs_usr],
+    ZOfsUsr: RW [z_ofs_usr],
+    Ctrl7: RW [lpass_on6d, hp_ref_mode, usr_off_w, usr_off_on_wu, usr_off_on_out, interrupts_enable, int2_on_int1, drdy_pulsed],
+);
+
+/// Field layout of every register: datasheet name, address and fields.
+///
+/// The 16-bit output registers are listed at the address of their low byte, with field offsets
+/// counted over both bytes. `WHO_AM_I` holds a single value and has no register struct. `OUT_T`, the 8-bit
+/// temperature, has its own address next to the 12-bit value at `OUT_T_L`.
+pub const REGISTERS: &[(&str, u8, &[FieldDesc])] = &[
+    ("OUT_T_L", Reg::OutTL as u8, OutT::FIELDS),
+    ("CTRL1", Reg::Ctrl1 as u8, Ctrl1::FIELDS),
+    ("CTRL2", Reg::Ctrl2 as u8, Ctrl2::FIELDS),
+    ("CTRL3", Reg::Ctrl3 as u8, Ctrl3::FIELDS),
+    (
+        "CTRL4_INT1_PAD_CTRL",
+        Reg::Ctrl4Int1PadCtrl as u8,
+        Ctrl4Int1PadCtrl::FIELDS,
+    ),
+    (
+        "CTRL5_INT2_PAD_CTRL",
+        Reg::Ctrl5Int2PadCtrl as u8,
+        Ctrl5Int2PadCtrl::FIELDS,
+    ),
+    ("CTRL6", Reg::Ctrl6 as u8, Ctrl6::FIELDS),
+    ("OUT_T", Reg::OutT as u8, OutT8::FIELDS),
+    ("STATUS", Reg::Status as u8, Status::FIELDS),
+    ("OUT_X_L", Reg::OutXL as u8, OutX::FIELDS),
+    ("OUT_Y_L", Reg::OutYL as u8, OutY::FIELDS),
+    ("OUT_Z_L", Reg::OutZL as u8, OutZ::FIELDS),
+    ("FIFO_CTRL", Reg::FifoCtrl as u8, FifoCtrl::FIELDS),
+    ("FIFO_SAMPLES", Reg::FifoSamples as u8, FifoSamples::FIELDS),
+    ("TAP_THS_X", Reg::TapThsX as u8, TapThsX::FIELDS),
+    ("TAP_THS_Y", Reg::TapThsY as u8, TapThsY::FIELDS),
+    ("TAP_THS_Z", Reg::TapThsZ as u8, TapThsZ::FIELDS),
+    ("INT_DUR", Reg::IntDur as u8, IntDur::FIELDS),
+    ("WAKE_UP_THS", Reg::WakeUpThs as u8, WakeUpThs::FIELDS),
+    ("WAKE_UP_DUR", Reg::WakeUpDur as u8, WakeUpDur::FIELDS),
+    ("FREE_FALL", Reg::FreeFall as u8, FreeFall::FIELDS),
+    ("STATUS_DUP", Reg::StatusDup as u8, StatusDup::FIELDS),
+    ("WAKE_UP_SRC", Reg::WakeUpSrc as u8, WakeUpSrc::FIELDS),
+    ("TAP_SRC", Reg::TapSrc as u8, TapSrc::FIELDS),
+    ("SIXD_SRC", Reg::SixdSrc as u8, SixdSrc::FIELDS),
+    ("ALL_INT_SRC", Reg::AllIntSrc as u8, AllIntSrc::FIELDS),
+    ("X_OFS_USR", Reg::XOfsUsr as u8, XOfsUsr::FIELDS),
+    ("Y_OFS_USR", Reg::YOfsUsr as u8, YOfsUsr::FIELDS),
+    ("Z_OFS_USR", Reg::ZOfsUsr as u8, ZOfsUsr::FIELDS),
+    ("CTRL7", Reg::Ctrl7 as u8, Ctrl7::FIELDS),
+];
+
+/// Size of the register address space, in bytes.
+pub const REG_MAP_LEN: usize = 0x40;
+
+/// Get the content of the whole register map after power-up or software reset.
+///
+/// The array is indexed by register address; reserved addresses read as `0`.
+/// Intended to initialize register-level bus models.
+///
+/// ### Returns
+/// - `[u8; REG_MAP_LEN]`: The reset value of every register.
+pub fn reset_image() -> [u8; REG_MAP_LEN] {
+    let out_t = OutT::RESET_VALUE.to_le_bytes();
+    let out_x = OutX::RESET_VALUE.to_le_bytes();
+    let out_y = OutY::RESET_VALUE.to_le_bytes();
+    let out_z = OutZ::RESET_VALUE.to_le_bytes();
+
+    let mut image = [0; REG_MAP_LEN];
+    for (reg, val) in [
+        (Reg::OutTL, out_t[0]),
+        (Reg::OutTH, out_t[1]),
+        (Reg::WhoAmI, crate::ID),
+        (Reg::Ctrl1, Ctrl1::RESET_VALUE),
+        (Reg::Ctrl2, Ctrl2::RESET_VALUE),
+        (Reg::Ctrl3, Ctrl3::RESET_VALUE),
+        (Reg::Ctrl4Int1PadCtrl, Ctrl4Int1PadCtrl::RESET_VALUE),
+        (Reg::Ctrl5Int2PadCtrl, Ctrl5Int2PadCtrl::RESET_VALUE),
+        (Reg::Ctrl6, Ctrl6::RESET_VALUE),
+        (Reg::OutT, out_t[1]),
+        (Reg::Status, Status::RESET_VALUE),
+        (Reg::OutXL, out_x[0]),
+        (Reg::OutXH, out_x[1]),
+        (Reg::OutYL, out_y[0]),
+        (Reg::OutYH, out_y[1]),
+        (Reg::OutZL, out_z[0]),
+        (Reg::OutZH, out_z[1]),
+        (Reg::FifoCtrl, FifoCtrl::RESET_VALUE),
+        (Reg::FifoSamples, FifoSamples::RESET_VALUE),
+        (Reg::TapThsX, TapThsX::RESET_VALUE),
+        (Reg::TapThsY, TapThsY::RESET_VALUE),
+        (Reg::TapThsZ, TapThsZ::RESET_VALUE),
+        (Reg::IntDur, IntDur::RESET_VALUE),
+        (Reg::WakeUpThs, WakeUpThs::RESET_VALUE),
+        (Reg::WakeUpDur, WakeUpDur::RESET_VALUE),
+        (Reg::FreeFall, FreeFall::RESET_VALUE),
+        (Reg::StatusDup, StatusDup::RESET_VALUE),
+        (Reg::WakeUpSrc, WakeUpSrc::RESET_VALUE),
+        (Reg::TapSrc, TapSrc::RESET_VALUE),
+        (Reg::SixdSrc, SixdSrc::RESET_VALUE),
+        (Reg::AllIntSrc, AllIntSrc::RESET_VALUE),
+        (Reg::XOfsUsr, XOfsUsr::RESET_VALUE),
+        (Reg::YOfsUsr, YOfsUsr::RESET_VALUE),
+        (Reg::ZOfsUsr, ZOfsUsr::RESET_VALUE),
+        (Reg::Ctrl7, Ctrl7::RESET_VALUE),
+    ] {
+        if let Some(slot) = image.get_mut(reg as usize) {
+            *slot = val;
+        }
+    }
+    image
+}
+
 /// All interrupt and status sources.
 ///
 /// This struct aggregates the status and interrupt source registers of the IIS2DLPC sensor.
 /// It provides a comprehensive view of the device's current status and interrupt events.
+///
+/// The struct is a plain `Copy` value: an interrupt handler can read it, or its byte form, see
+/// [`Self::to_bytes`], and queue it for processing in another context.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct AllSources {
     /// Status duplicate register.
     ///
@@ -1474,6 +1919,103 @@ pub struct AllSources {
     pub all_int_src: AllIntSrc,
 }
 
+impl Status {
+    /// Check that the flags shared with `STATUS_DUP` have the same values.
+    ///
+    /// `STATUS` and `STATUS_DUP` share `drdy`, `ff_ia`, `six_d_ia`, `single_tap`, `double_tap` and the sleep
+    /// state; the other bits (`wu_ia` and `fifo_ths`, `drdy_t` and `ovr`) are specific to each register.
+    ///
+    /// ### Arguments
+    /// - `dup`: The `STATUS_DUP` content.
+    ///
+    /// ### Returns
+    /// - `true` if every shared flag matches.
+    pub fn shared_flags_match(&self, dup: &StatusDup) -> bool {
+        self.drdy() == dup.drdy()
+            && self.ff_ia() == dup.ff_ia()
+            && self.six_d_ia() == dup.six_d_ia()
+            && self.single_tap() == dup.single_tap()
+            && self.double_tap() == dup.double_tap()
+            && self.sleep_state() == dup.sleep_state_ia()
+    }
+}
+
+impl AllSources {
+    /// Number of bytes of the byte form, see [`Self::to_bytes`].
+    pub const BYTES: usize = 5;
+
+    /// Build the sources from their byte form.
+    ///
+    /// ### Arguments
+    /// - `bytes`: The `STATUS_DUP`, `WAKE_UP_SRC`, `TAP_SRC`, `SIXD_SRC` and `ALL_INT_SRC` registers, in this
+    ///   order, as read by [`crate::Iis2dlpc::all_sources_bytes_get`].
+    pub const fn from_bytes(bytes: [u8; Self::BYTES]) -> Self {
+        let [status_dup, wake_up_src, tap_src, sixd_src, all_int_src] = bytes;
+        Self {
+            status_dup: StatusDup::from_bits(status_dup),
+            wake_up_src: WakeUpSrc::from_bits(wake_up_src),
+            tap_src: TapSrc::from_bits(tap_src),
+            sixd_src: SixdSrc::from_bits(sixd_src),
+            all_int_src: AllIntSrc::from_bits(all_int_src),
+        }
+    }
+
+    /// Get the byte form of the sources.
+    ///
+    /// The bytes are the raw registers in address order, the inverse of [`Self::from_bytes`]; every bit is kept,
+    /// reserved ones included.
+    pub const fn to_bytes(&self) -> [u8; Self::BYTES] {
+        [
+            self.status_dup.into_bits(),
+            self.wake_up_src.into_bits(),
+            self.tap_src.into_bits(),
+            self.sixd_src.into_bits(),
+            self.all_int_src.into_bits(),
+        ]
+    }
+
+    /// Reserved bits of `WAKE_UP_SRC`.
+    pub const WAKE_UP_SRC_RESERVED: u8 = 0xC0;
+    /// Reserved bits of `TAP_SRC`.
+    pub const TAP_SRC_RESERVED: u8 = 0x80;
+    /// Reserved bits of `SIXD_SRC`.
+    pub const SIXD_SRC_RESERVED: u8 = 0x80;
+    /// Reserved bits of `ALL_INT_SRC`.
+    pub const ALL_INT_SRC_RESERVED: u8 = 0xC0;
+
+    /// Check that no reserved bit is set in the source registers.
+    ///
+    /// A reserved bit set, for example in a frame from a newer silicon revision, carries information this
+    /// version of the driver does not decode; the raw registers still hold it.
+    ///
+    /// ### Returns
+    /// - `true` if every set bit has a known meaning.
+    pub fn is_fully_decoded(&self) -> bool {
+        self.wake_up_src.into_bits() & Self::WAKE_UP_SRC_RESERVED == 0
+            && self.tap_src.into_bits() & Self::TAP_SRC_RESERVED == 0
+            && self.sixd_src.into_bits() & Self::SIXD_SRC_RESERVED == 0
+            && self.all_int_src.into_bits() & Self::ALL_INT_SRC_RESERVED == 0
+    }
+
+    /// Check that every event of the `ALL_INT_SRC` summary has its detail register flag set.
+    ///
+    /// The registers are read in address order, detail registers first and `ALL_INT_SRC` last, see
+    /// [`crate::event`]: an event asserting during the read is flagged in the summary only, and reported here.
+    /// A detail flag without its summary flag is not reported, as it also happens when a pulsed interrupt
+    /// ends during the read.
+    ///
+    /// ### Returns
+    /// - `true` if the summary and the detail registers agree.
+    pub fn is_consistent(&self) -> bool {
+        let summary = self.all_int_src;
+        summary.ff_ia() <= self.wake_up_src.ff_ia()
+            && summary.wu_ia() <= self.wake_up_src.wu_ia()
+            && summary.single_tap() <= self.tap_src.single_tap()
+            && summary.double_tap() <= self.tap_src.double_tap()
+            && summary.six_d_ia() <= self.sixd_src.six_d_ia()
+    }
+}
+
 /// Accelerometer operating modes.
 ///
 /// This enum represents the various operating modes of the IIS2DLPC accelerometer. Each mode is associated with specific configurations for:
@@ -1481,7 +2023,8 @@ pub struct AllSources {
 /// - `lp_mode`: Low-power mode configuration.
 /// - `low_noise`: Low-noise mode configuration.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Mode {
     /// High-performance mode.
@@ -1537,12 +2080,64 @@ impl Mode {
     /// - `lp_mode`: The low-power mode configuration value.
     /// - `low_noise`: The low-noise mode configuration value.
     ///
+    /// In high-performance mode the device ignores `lp_mode`, so do stale `lp_mode` bits here.
+    ///
     /// ### Returns
     /// - A `Mode` instance corresponding to the provided values.
-    /// - Defaults to `ContLowPwr12bit` if the provided values do not match a valid mode.
+    /// - Defaults to `ContLowPwr12bit` if `mode` holds the reserved code.
+    #[deprecated(
+        since = "2.0.0",
+        note = "use `Mode::try_new`, which reports the reserved `mode` code instead of a default"
+    )]
     pub fn new(mode: u8, lp_mode: u8, low_noise: u8) -> Self {
+        Self::try_new(mode, lp_mode, low_noise).unwrap_or_default()
+    }
+
+    /// Create a new `Mode` instance, checking the `mode` field.
+    ///
+    /// In high-performance mode the device ignores `lp_mode`, so do stale `lp_mode` bits here.
+    ///
+    /// ### Arguments
+    /// - `mode`: The operating mode value.
+    /// - `lp_mode`: The low-power mode configuration value.
+    /// - `low_noise`: The low-noise mode configuration value.
+    ///
+    /// ### Returns
+    /// - `Ok(Mode)`: The mode corresponding to the provided values.
+    /// - `Err(u8)`: The combined code, `low_noise << 4 | mode << 2 | lp_mode`, if `mode` holds the reserved
+    ///   code `0b11`.
+    pub fn try_new(mode: u8, lp_mode: u8, low_noise: u8) -> Result<Self, u8> {
+        let code = Self::code(mode, lp_mode, low_noise);
+        Self::try_from(code).map_err(|_| code)
+    }
+
+    /// Combine the register fields into the code of a `Mode`, with the `lp_mode` bits of the
+    /// high-performance mode ignored; the code of a reserved `mode` is not a variant.
+    pub(crate) fn code(mode: u8, lp_mode: u8, low_noise: u8) -> u8 {
+        let lp_mode = if mode == 0x01 { 0 } else { lp_mode & 0x03 };
         // low_noise | mode1 | mode2 | lp_mode1 | lp_mode2 |
-        Self::try_from((low_noise << 4) + (mode << 2) + lp_mode).unwrap_or_default()
+        ((low_noise & 0x01) << 4) + ((mode & 0x03) << 2) + lp_mode
+    }
+
+    /// Check whether the mode is a low-power mode 1 variant.
+    ///
+    /// In low-power mode 1 the output has a 12-bit resolution, 14-bit in every other mode.
+    ///
+    /// ### Returns
+    /// - `true` for the low-power mode 1 variants, `false` otherwise.
+    pub fn is_low_power_1(&self) -> bool {
+        self.mode() != 0x01 && self.lp_mode() == 0x00
+    }
+
+    /// Get the resolution of the output in the mode.
+    ///
+    /// The raw values of [`crate::Iis2dlpc::acceleration_raw_get`] are 14-bit: at 12 bits, shift them right by 2
+    /// before applying the sensitivity of [`crate::sensitivity_mg_per_lsb`], which depends on the resolution.
+    ///
+    /// ### Returns
+    /// - `12` for the low-power mode 1 variants, `14` otherwise.
+    pub fn resolution_bits(&self) -> u8 {
+        if self.is_low_power_1() { 12 } else { 14 }
     }
 
     /// Get the `mode` value.
@@ -1557,7 +2152,7 @@ impl Mode {
 
     /// Get the `lp_mode` value.
     ///
-    /// Extracts the `lp_mode` field from the `Mode` instance.
+    /// Extracts the `lp_mode` field from the `Mode` instance; always `0` for the high-performance variants.
     ///
     /// ### Returns
     /// - The `lp_mode` value as a `u8`.
@@ -1574,13 +2169,40 @@ impl Mode {
     pub fn low_noise(&self) -> u8 {
         (*self as u8 & 0x10) >> 4
     }
+
+    /// Get the typical noise density of the mode.
+    ///
+    /// The value is the datasheet typical at ±2 g, see [`crate::noise::NOISE_DENSITY_UG`]; the single and continuous
+    /// variants have the same noise.
+    ///
+    /// ### Returns
+    /// - The noise density in µg/√Hz.
+    pub fn noise_ug_per_sqrt_hz(&self) -> u32 {
+        crate::noise::noise_density(crate::noise::PowerLevel::of(*self), self.low_noise() == 1)
+    }
+
+    /// Get the typical supply current of the mode.
+    ///
+    /// The value is the datasheet typical, see [`crate::noise::TYPICAL_CURRENT_UA`]; the low-noise configuration does
+    /// not change it. For the single modes, `odr` is the rate at which the conversions are triggered.
+    ///
+    /// ### Arguments
+    /// - `odr`: The output data rate.
+    ///
+    /// ### Returns
+    /// - `Some(f32)`: The current in µA.
+    /// - `None`: If the mode cannot run at `odr`, or `odr` is off or triggered on demand.
+    pub fn typical_current_ua(&self, odr: Odr) -> Option<f32> {
+        crate::noise::typical_current(crate::noise::PowerLevel::of(*self), odr)
+    }
 }
 
 /// Accelerometer output data rates (ODR).
 ///
 /// This enum represents the various output data rates supported by the IIS2DLPC accelerometer. Each variant corresponds to a specific ODR configuration.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Odr {
     /// Accelerometer off (default).
@@ -1614,11 +2236,13 @@ pub enum Odr {
     /// Accelerometer ODR: 1.6 kHz.
     _1_6khz = 0x09,
 
-    /// Accelerometer ODR: Software trigger.
-    SetSwTrig = 0x12,
+    /// Accelerometer ODR: Software trigger (`slp_mode_sel` set, conversions started by
+    /// [`crate::Iis2dlpc::sw_trigger`]).
+    SetSwTrig = 0x22,
 
-    /// Accelerometer ODR: Pin trigger.
-    SetPinTrig = 0x22,
+    /// Accelerometer ODR: Pin trigger (`slp_mode_sel` cleared, `slp_mode_1` set, conversions started
+    /// by a rising edge on INT2).
+    SetPinTrig = 0x12,
 }
 
 impl Odr {
@@ -1634,7 +2258,30 @@ impl Odr {
     /// - An `Odr` instance corresponding to the provided values.
     /// - Defaults to `XlOdrOff` if the provided values do not match a valid ODR.
     pub fn new(odr: u8, slp_mode: u8) -> Self {
-        Self::try_from((slp_mode << 4) + odr).unwrap_or_default()
+        Self::decode(Self::code(odr, slp_mode)).unwrap_or_default()
+    }
+
+    /// Combine the register fields into the code of an `Odr`.
+    pub(crate) fn code(odr: u8, slp_mode: u8) -> u8 {
+        ((slp_mode & 0x03) << 4) + (odr & 0x0F)
+    }
+
+    /// Decode the code of an `Odr`, see [`Self::code`].
+    ///
+    /// With `slp_mode_sel` set, `slp_mode_1` is the self-clearing trigger of a conversion in
+    /// progress and is left out, so that the code decodes as [`Odr::SetSwTrig`].
+    ///
+    /// ### Returns
+    /// - `Some(Odr)`: As [`Self::new`] for the defined data rates: a combination with the sleep
+    ///   mode bits the variants do not list decodes as `Off`.
+    /// - `None`: If the `odr` field holds a reserved code (10 to 15).
+    pub(crate) fn decode(code: u8) -> Option<Self> {
+        let code = if code & 0x30 == 0x30 {
+            code & !0x10
+        } else {
+            code
+        };
+        (code & 0x0F <= 0x09).then(|| Self::try_from(code).unwrap_or_default())
     }
 
     /// Get the `odr` value.
@@ -1656,6 +2303,52 @@ impl Odr {
     pub fn slp_mode(&self) -> u8 {
         (*self as u8 & 0x30) >> 4
     }
+
+    /// Get the nominal output data rate in Hz.
+    ///
+    /// ### Returns
+    /// - `Some(f32)`: The nominal data rate.
+    /// - `None`: If the accelerometer is off or the conversions are triggered on demand.
+    pub fn to_hz(&self) -> Option<f32> {
+        self.period_us().map(|us| 1_000_000.0 / us as f32)
+    }
+
+    /// Get the nominal output data period in microseconds.
+    ///
+    /// All the time-based fields of the device (wake-up, sleep, free-fall and tap durations) are expressed
+    /// in multiples of this period.
+    ///
+    /// ### Returns
+    /// - `Some(u32)`: The nominal period, e.g. `625_000` at 1.6 Hz and `625` at 1.6 kHz.
+    /// - `None`: If the accelerometer is off or the conversions are triggered on demand.
+    pub fn period_us(&self) -> Option<u32> {
+        match self {
+            Odr::_1_6hzLpOnly => Some(625_000),
+            Odr::_12_5hz => Some(80_000),
+            Odr::_25hz => Some(40_000),
+            Odr::_50hz => Some(20_000),
+            Odr::_100hz => Some(10_000),
+            Odr::_200hz => Some(5_000),
+            Odr::_400hz => Some(2_500),
+            Odr::_800hz => Some(1_250),
+            Odr::_1_6khz => Some(625),
+            Odr::Off | Odr::SetSwTrig | Odr::SetPinTrig => None,
+        }
+    }
+
+    /// Get the time the FIFO takes to collect a number of samples, e.g. a watermark level.
+    ///
+    /// The result is in 64 bits: at 1.6 Hz a full FIFO takes 20 s.
+    ///
+    /// ### Arguments
+    /// - `samples`: The number of samples.
+    ///
+    /// ### Returns
+    /// - `Some(u64)`: The fill time in microseconds.
+    /// - `None`: If the accelerometer is off or the conversions are triggered on demand.
+    pub fn fifo_fill_us(&self, samples: u8) -> Option<u64> {
+        self.period_us().map(|us| samples as u64 * us as u64)
+    }
 }
 
 /// Accelerometer full-scale selection.
@@ -1663,7 +2356,8 @@ impl Odr {
 /// This enum represents the full-scale range of the accelerometer, which determines the maximum measurable acceleration.
 /// The full-scale range is configured in the `CTRL6` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Fs {
     /// ±2g full-scale range (default).
@@ -1680,12 +2374,43 @@ pub enum Fs {
     _16g = 3,
 }
 
+impl Fs {
+    /// Get the full-scale range in g.
+    ///
+    /// ### Returns
+    /// - The full-scale range as a `u8` (2, 4, 8 or 16).
+    pub fn to_g(&self) -> u8 {
+        2 << (*self as u8)
+    }
+
+    /// Get the weight of one `WK_THS` LSB in mg.
+    ///
+    /// The wake-up threshold is 6 bits wide and 1 LSB = FS / 64.
+    ///
+    /// ### Returns
+    /// - The threshold weight as an `f32`.
+    pub fn wkup_threshold_mg_per_lsb(&self) -> f32 {
+        self.to_g() as f32 * 1000.0 / 64.0
+    }
+
+    /// Get the weight of one tap threshold LSB in mg.
+    ///
+    /// The `tap_thsx`, `tap_thsy` and `tap_thsz` thresholds are 5 bits wide and 1 LSB = FS / 32.
+    ///
+    /// ### Returns
+    /// - The threshold weight as an `f32`.
+    pub fn tap_threshold_mg_per_lsb(&self) -> f32 {
+        self.to_g() as f32 * 1000.0 / 32.0
+    }
+}
+
 /// User offset weight configuration.
 ///
 /// This enum represents the weight of the user offset bits in the `X_OFS_USR`, `Y_OFS_USR`, and `Z_OFS_USR` registers.
 /// The weight determines the scaling factor applied to the user offset values.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum UsrOffW {
     /// 977 μg/LSB (default).
@@ -1696,12 +2421,26 @@ pub enum UsrOffW {
     _15_6mgLsb = 1,
 }
 
+impl UsrOffW {
+    /// Get the weight of one user offset LSB in mg.
+    ///
+    /// ### Returns
+    /// - The offset weight as an `f32`.
+    pub fn mg_per_lsb(&self) -> f32 {
+        match self {
+            UsrOffW::_977ugLsb => 0.977,
+            UsrOffW::_15_6mgLsb => 15.6,
+        }
+    }
+}
+
 /// Sensor self-test configuration.
 ///
 /// This enum represents the self-test modes for the IIS2DLPC sensor.
 /// The self-test mode is configured in the `CTRL3` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum St {
     /// Self-test disabled (default).
@@ -1719,8 +2458,10 @@ pub enum St {
 ///
 /// This enum represents the data-ready interrupt mode for the IIS2DLPC sensor.
 /// The mode is configured in the `CTRL7` register.
+#[cfg(feature = "events")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum DrdyPulsed {
     /// Latched mode (default).
@@ -1736,7 +2477,8 @@ pub enum DrdyPulsed {
 /// This enum represents the filtering path options for accelerometer outputs.
 /// The filtering path is configured in the `CTRL6` and `CTRL7` registers.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Fds {
     /// Low-pass filter on output (default).
@@ -1761,9 +2503,16 @@ impl Fds {
     ///
     /// ### Returns
     /// - An `Fds` instance corresponding to the provided values.
-    /// - Defaults to `LpfOnOut` if the provided values do not match a valid configuration.
+    /// - Defaults to `LpfOnOut` if both fields are set, the user offset on the high-pass path, which no
+    ///   variant represents; [`crate::Iis2dlpc::filter_path_get`] reports it as an error instead.
     pub fn new(fds: u8, usr_off_on_out: u8) -> Self {
-        Self::try_from((fds << 4) + usr_off_on_out).unwrap_or_default()
+        Self::try_from(Self::code(fds, usr_off_on_out)).unwrap_or_default()
+    }
+
+    /// Combine the register fields into the code of an `Fds`; the code with both fields set, `0x11`,
+    /// is not a variant.
+    pub(crate) fn code(fds: u8, usr_off_on_out: u8) -> u8 {
+        ((fds & 0x01) << 4) + (usr_off_on_out & 0x01)
     }
 
     /// Get the `fds` value.
@@ -1792,7 +2541,8 @@ impl Fds {
 /// This enum represents the cutoff frequency options for the accelerometer's low-pass or high-pass filter.
 /// The cutoff frequency is configured in the `CTRL6` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum BwFilt {
     /// ODR/2 (default).
@@ -1809,12 +2559,55 @@ pub enum BwFilt {
     OdrDiv20 = 3,
 }
 
+impl BwFilt {
+    /// Get the ODR divider of the filter.
+    ///
+    /// ### Returns
+    /// - The divider as a `u8` (2, 4, 10 or 20).
+    pub fn divider(&self) -> u8 {
+        match self {
+            BwFilt::OdrDiv2 => 2,
+            BwFilt::OdrDiv4 => 4,
+            BwFilt::OdrDiv10 => 10,
+            BwFilt::OdrDiv20 => 20,
+        }
+    }
+
+    /// Get the filter cutoff frequency in Hz at the given data rate.
+    ///
+    /// ### Arguments
+    /// - `odr`: The output data rate.
+    ///
+    /// ### Returns
+    /// - `Some(f32)`: The cutoff frequency.
+    /// - `None`: If the accelerometer is off or the conversions are triggered on demand.
+    pub fn cutoff_hz(&self, odr: Odr) -> Option<f32> {
+        odr.to_hz().map(|hz| hz / self.divider() as f32)
+    }
+
+    /// Get the settling time of the filter in milliseconds at the given data rate.
+    ///
+    /// The filter is modeled as a first order low-pass filter settling within 1% of a step, that is
+    /// `ln(100) / (2π · cutoff)`.
+    ///
+    /// ### Arguments
+    /// - `odr_hz`: The output data rate in Hz.
+    ///
+    /// ### Returns
+    /// - `f32`: The settling time.
+    pub fn settling_ms(&self, odr_hz: f32) -> f32 {
+        1000.0 * core::f32::consts::LN_10 * 2.0 * self.divider() as f32
+            / (2.0 * core::f32::consts::PI * odr_hz)
+    }
+}
+
 /// SPI serial interface mode configuration.
 ///
 /// This enum represents the SPI serial interface modes for the IIS2DLPC sensor.
 /// The mode is configured in the `CTRL2` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Sim {
     /// 4-wire SPI mode (default).
@@ -1830,7 +2623,8 @@ pub enum Sim {
 /// This enum represents the enable/disable states of the I²C interface for the IIS2DLPC sensor.
 /// The state is configured in the `CTRL2` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum I2cDisable {
     /// Enable the I²C interface (default).
@@ -1846,7 +2640,8 @@ pub enum I2cDisable {
 /// This enum represents the configuration of the CS pull-up resistor for the IIS2DLPC sensor.
 /// The configuration is set in the `CTRL2` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum CsPuDisc {
     /// Connect the pull-up resistor (default).
@@ -1861,8 +2656,10 @@ pub enum CsPuDisc {
 ///
 /// This enum represents the active level configuration for interrupts.
 /// The configuration is set in the `CTRL3` register.
+#[cfg(feature = "events")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum HLactive {
     /// Active high (default).
@@ -1873,12 +2670,30 @@ pub enum HLactive {
     ActiveLow = 1,
 }
 
+/// Trigger of a single data conversion on demand.
+///
+/// This enum represents the `slp_mode_sel` bit of the `slp_mode` field in the `CTRL3` register.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[try_from(repr)]
+pub enum SlpModeSel {
+    /// Rising edge on the INT2 pin (default).
+    #[default]
+    PinTrigger = 0,
+
+    /// Write of the `slp_mode_1` bit over the serial interface.
+    SwTrigger = 1,
+}
+
 /// Interrupt latching configuration.
 ///
 /// This enum represents the latching behavior of interrupts.
 /// The configuration is set in the `CTRL3` register.
+#[cfg(feature = "events")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Lir {
     /// Pulsed interrupt mode (default).
@@ -1893,8 +2708,10 @@ pub enum Lir {
 ///
 /// This enum represents the type of interrupt pad configuration.
 /// The configuration is set in the `CTRL3` register.
+#[cfg(feature = "events")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum PpOd {
     /// Push-pull configuration (default).
@@ -1910,7 +2727,8 @@ pub enum PpOd {
 /// This enum represents the data source options for the wake-up interrupt function.
 /// The data source is configured in the `CTRL7` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum UsrOffOnWu {
     /// High-pass filtered data (default).
@@ -1925,8 +2743,10 @@ pub enum UsrOffOnWu {
 ///
 /// This enum represents the detection modes for activity/inactivity or stationary/motion.
 /// The configuration is set in the `WAKE_UP_THS` and `WAKE_UP_DUR` registers.
+#[cfg(feature = "wakeup")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum SleepOn {
     /// No detection (default).
@@ -1940,6 +2760,7 @@ pub enum SleepOn {
     StatMotion = 3,
 }
 
+#[cfg(feature = "wakeup")]
 impl SleepOn {
     /// Create a new `SleepOn` instance.
     ///
@@ -1953,7 +2774,7 @@ impl SleepOn {
     /// - A `SleepOn` instance corresponding to the provided values.
     /// - Defaults to `NoDetection` if the provided values do not match a valid configuration.
     pub fn new(sleep_on: u8, stationary: u8) -> Self {
-        Self::try_from((stationary << 1) + sleep_on).unwrap_or_default()
+        Self::try_from(((stationary & 0x01) << 1) + (sleep_on & 0x01)).unwrap_or_default()
     }
 
     /// Get the `sleep_on` value.
@@ -1981,8 +2802,10 @@ impl SleepOn {
 ///
 /// This enum represents the axis priority for tap detection.
 /// The priority is configured in the `TAP_THS_Y` register.
+#[cfg(feature = "events")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum TapPrior {
     /// X > Y > Z (default).
@@ -2005,12 +2828,62 @@ pub enum TapPrior {
     Zxy = 6,
 }
 
+#[cfg(feature = "events")]
+impl TapPrior {
+    /// Get the axis priority as an ordered list, highest priority first.
+    ///
+    /// ### Returns
+    /// - The axes, from the highest to the lowest priority.
+    pub fn ordering(&self) -> [Axis; 3] {
+        match self {
+            TapPrior::Xyz => [Axis::X, Axis::Y, Axis::Z],
+            TapPrior::Yxz => [Axis::Y, Axis::X, Axis::Z],
+            TapPrior::Xzy => [Axis::X, Axis::Z, Axis::Y],
+            TapPrior::Zyx => [Axis::Z, Axis::Y, Axis::X],
+            TapPrior::Yzx => [Axis::Y, Axis::Z, Axis::X],
+            TapPrior::Zxy => [Axis::Z, Axis::X, Axis::Y],
+        }
+    }
+
+    /// Create a `TapPrior` from an ordered list of axes, highest priority first.
+    ///
+    /// ### Arguments
+    /// - `ordering`: The axes, from the highest to the lowest priority.
+    ///
+    /// ### Returns
+    /// - `Some(TapPrior)`: The matching priority.
+    /// - `None`: If `ordering` is not a permutation of the three axes.
+    pub fn from_ordering(ordering: [Axis; 3]) -> Option<Self> {
+        [
+            TapPrior::Xyz,
+            TapPrior::Yxz,
+            TapPrior::Xzy,
+            TapPrior::Zyx,
+            TapPrior::Yzx,
+            TapPrior::Zxy,
+        ]
+        .into_iter()
+        .find(|prior| prior.ordering() == ordering)
+    }
+}
+
+/// Accelerometer axis.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
 /// Single/double-tap event detection mode.
 ///
 /// This enum represents the detection mode for single- and double-tap events.
 /// The mode is configured in the `WAKE_UP_THS` register.
+#[cfg(feature = "tap")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum SingleDoubleTap {
     /// Detect only single-tap events (default).
@@ -2025,8 +2898,10 @@ pub enum SingleDoubleTap {
 ///
 /// This enum represents the data source options for the 6D interrupt function.
 /// The data source is configured in the `CTRL7` register.
+#[cfg(feature = "sixd")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum LpassOn6d {
     /// ODR/2 low-pass filtered data (default).
@@ -2037,12 +2912,64 @@ pub enum LpassOn6d {
     Lpf2Feed = 1,
 }
 
+/// 6D/4D orientation threshold.
+///
+/// This enum represents the minimum tilt angle that changes the detected orientation.
+/// The threshold is configured in the `TAP_THS_X` register.
+#[cfg(feature = "sixd")]
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[try_from(repr)]
+pub enum SixdThs {
+    /// 80 degrees (default).
+    #[default]
+    Deg80 = 0,
+
+    /// 70 degrees.
+    Deg70 = 1,
+
+    /// 60 degrees.
+    Deg60 = 2,
+
+    /// 50 degrees.
+    Deg50 = 3,
+}
+
+#[cfg(feature = "sixd")]
+impl SixdThs {
+    /// Get the threshold for a tilt angle.
+    ///
+    /// ### Arguments
+    /// - `deg`: The angle in degrees: 80, 70, 60 or 50.
+    ///
+    /// ### Returns
+    /// - `Some(SixdThs)`: The threshold of the angle.
+    /// - `None`: If the angle is not one of the four thresholds.
+    pub fn from_degrees(deg: u8) -> Option<Self> {
+        match deg {
+            80 => Some(SixdThs::Deg80),
+            70 => Some(SixdThs::Deg70),
+            60 => Some(SixdThs::Deg60),
+            50 => Some(SixdThs::Deg50),
+            _ => None,
+        }
+    }
+
+    /// Get the tilt angle of the threshold in degrees.
+    pub fn degrees(&self) -> u8 {
+        80 - 10 * (*self as u8)
+    }
+}
+
 /// Free-fall threshold configuration.
 ///
 /// This enum represents the free-fall threshold options for the IIS2DLPC sensor.
 /// The threshold is configured in the `FREE_FALL` register.
+#[cfg(feature = "freefall")]
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum FfThs {
     /// 5 LSB @ ±2g (default).
@@ -2076,7 +3003,8 @@ pub enum FfThs {
 /// This enum represents the FIFO operating modes for the IIS2DLPC sensor.
 /// The mode is configured in the `FIFO_CTRL` register.
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Default, TryFrom)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, TryFrom)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[try_from(repr)]
 pub enum Fmode {
     /// Bypass mode (default).
@@ -2095,3 +3023,446 @@ pub enum Fmode {
     /// Stream mode: Continuously updates FIFO, overwriting old data when full.
     StreamMode = 6,
 }
+
+// Every public type of the module implements `defmt::Format`, the register structs through the
+// `defmt` argument of `bitfield`
+#[cfg(feature = "defmt")]
+const _: () = {
+    const fn assert_format<T: defmt::Format>() {}
+    assert_format::<Reg>();
+    assert_format::<RegDecode>();
+    assert_format::<OutT>();
+    assert_format::<Ctrl1>();
+    assert_format::<Ctrl2>();
+    assert_format::<Ctrl3>();
+    assert_format::<Ctrl4Int1PadCtrl>();
+    assert_format::<Ctrl5Int2PadCtrl>();
+    assert_format::<Ctrl6>();
+    assert_format::<OutT8>();
+    assert_format::<Status>();
+    assert_format::<OutX>();
+    assert_format::<OutY>();
+    assert_format::<OutZ>();
+    assert_format::<FifoCtrl>();
+    assert_format::<FifoSamples>();
+    assert_format::<TapThsX>();
+    assert_format::<TapThsY>();
+    assert_format::<TapThsZ>();
+    assert_format::<IntDur>();
+    assert_format::<WakeUpThs>();
+    assert_format::<WakeUpDur>();
+    assert_format::<FreeFall>();
+    assert_format::<StatusDup>();
+    assert_format::<WakeUpSrc>();
+    assert_format::<TapSrc>();
+    assert_format::<SixdSrc>();
+    assert_format::<AllIntSrc>();
+    assert_format::<XOfsUsr>();
+    assert_format::<YOfsUsr>();
+    assert_format::<ZOfsUsr>();
+    assert_format::<Ctrl7>();
+    assert_format::<FieldDesc>();
+    assert_format::<AllSources>();
+    assert_format::<Mode>();
+    assert_format::<Odr>();
+    assert_format::<Fs>();
+    assert_format::<UsrOffW>();
+    assert_format::<St>();
+    #[cfg(feature = "events")]
+    assert_format::<DrdyPulsed>();
+    assert_format::<Fds>();
+    assert_format::<BwFilt>();
+    assert_format::<Sim>();
+    assert_format::<I2cDisable>();
+    assert_format::<CsPuDisc>();
+    #[cfg(feature = "events")]
+    assert_format::<HLactive>();
+    assert_format::<SlpModeSel>();
+    #[cfg(feature = "events")]
+    assert_format::<Lir>();
+    #[cfg(feature = "events")]
+    assert_format::<PpOd>();
+    assert_format::<UsrOffOnWu>();
+    #[cfg(feature = "wakeup")]
+    assert_format::<SleepOn>();
+    #[cfg(feature = "events")]
+    assert_format::<TapPrior>();
+    assert_format::<Axis>();
+    #[cfg(feature = "tap")]
+    assert_format::<SingleDoubleTap>();
+    #[cfg(feature = "sixd")]
+    assert_format::<LpassOn6d>();
+    #[cfg(feature = "sixd")]
+    assert_format::<SixdThs>();
+    #[cfg(feature = "freefall")]
+    assert_format::<FfThs>();
+    assert_format::<Fmode>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_traits<T: Copy + Eq + core::hash::Hash + core::fmt::Debug>() {}
+
+    /// Every public type of the module can be copied, compared, hashed and printed, so that a dropped derive fails
+    /// here rather than downstream.
+    #[test]
+    fn every_type_derives_the_standard_traits() {
+        assert_traits::<Reg>();
+        assert_traits::<RegDecode>();
+        assert_traits::<OutT>();
+        assert_traits::<Ctrl1>();
+        assert_traits::<Ctrl2>();
+        assert_traits::<Ctrl3>();
+        assert_traits::<Ctrl4Int1PadCtrl>();
+        assert_traits::<Ctrl5Int2PadCtrl>();
+        assert_traits::<Ctrl6>();
+        assert_traits::<OutT8>();
+        assert_traits::<Status>();
+        assert_traits::<OutX>();
+        assert_traits::<OutY>();
+        assert_traits::<OutZ>();
+        assert_traits::<FifoCtrl>();
+        assert_traits::<FifoSamples>();
+        assert_traits::<TapThsX>();
+        assert_traits::<TapThsY>();
+        assert_traits::<TapThsZ>();
+        assert_traits::<IntDur>();
+        assert_traits::<WakeUpThs>();
+        assert_traits::<WakeUpDur>();
+        assert_traits::<FreeFall>();
+        assert_traits::<StatusDup>();
+        assert_traits::<WakeUpSrc>();
+        assert_traits::<TapSrc>();
+        assert_traits::<SixdSrc>();
+        assert_traits::<AllIntSrc>();
+        assert_traits::<XOfsUsr>();
+        assert_traits::<YOfsUsr>();
+        assert_traits::<ZOfsUsr>();
+        assert_traits::<Ctrl7>();
+        assert_traits::<FieldDesc>();
+        assert_traits::<AllSources>();
+        assert_traits::<Mode>();
+        assert_traits::<Odr>();
+        assert_traits::<Fs>();
+        assert_traits::<UsrOffW>();
+        assert_traits::<St>();
+        #[cfg(feature = "events")]
+        assert_traits::<DrdyPulsed>();
+        assert_traits::<Fds>();
+        assert_traits::<BwFilt>();
+        assert_traits::<Sim>();
+        assert_traits::<I2cDisable>();
+        assert_traits::<CsPuDisc>();
+        #[cfg(feature = "events")]
+        assert_traits::<HLactive>();
+        assert_traits::<SlpModeSel>();
+        #[cfg(feature = "events")]
+        assert_traits::<Lir>();
+        #[cfg(feature = "events")]
+        assert_traits::<PpOd>();
+        assert_traits::<UsrOffOnWu>();
+        #[cfg(feature = "wakeup")]
+        assert_traits::<SleepOn>();
+        #[cfg(feature = "events")]
+        assert_traits::<TapPrior>();
+        assert_traits::<Axis>();
+        #[cfg(feature = "tap")]
+        assert_traits::<SingleDoubleTap>();
+        #[cfg(feature = "sixd")]
+        assert_traits::<LpassOn6d>();
+        #[cfg(feature = "sixd")]
+        assert_traits::<SixdThs>();
+        #[cfg(feature = "freefall")]
+        assert_traits::<FfThs>();
+        assert_traits::<Fmode>();
+    }
+
+    /// Every combination of the 2-bit `mode`, the 2-bit `lp_mode` and the 1-bit `low_noise` fields, against the
+    /// mode table of the datasheet.
+    #[test]
+    fn mode_decodes_every_field_combination() {
+        let mut decoded = 0;
+        for low_noise in 0..2 {
+            for mode in 0..4 {
+                for lp_mode in 0..4 {
+                    let expected = match (mode, lp_mode) {
+                        (0b11, _) => Err(low_noise << 4 | 0b11 << 2 | lp_mode),
+                        // High performance: lp_mode is ignored
+                        (0b01, _) if low_noise == 0 => Ok(Mode::HighPerformance),
+                        (0b01, _) => Ok(Mode::HighPerformanceLowNoise),
+                        (0b00, 0b00) if low_noise == 0 => Ok(Mode::ContLowPwr12bit),
+                        (0b00, 0b01) if low_noise == 0 => Ok(Mode::ContLowPwr2),
+                        (0b00, 0b10) if low_noise == 0 => Ok(Mode::ContLowPwr3),
+                        (0b00, _) if low_noise == 0 => Ok(Mode::ContLowPwr4),
+                        (0b00, 0b00) => Ok(Mode::ContLowPwrLowNoise12bit),
+                        (0b00, 0b01) => Ok(Mode::ContLowPwrLowNoise2),
+                        (0b00, 0b10) => Ok(Mode::ContLowPwrLowNoise3),
+                        (0b00, _) => Ok(Mode::ContLowPwrLowNoise4),
+                        (_, 0b00) if low_noise == 0 => Ok(Mode::SingleLowPwr12bit),
+                        (_, 0b01) if low_noise == 0 => Ok(Mode::SingleLowPwr2),
+                        (_, 0b10) if low_noise == 0 => Ok(Mode::SingleLowPwr3),
+                        (_, _) if low_noise == 0 => Ok(Mode::SingleLowPwr4),
+                        (_, 0b00) => Ok(Mode::SingleLowLowNoisePwr12bit),
+                        (_, 0b01) => Ok(Mode::SingleLowPwrLowNoise2),
+                        (_, 0b10) => Ok(Mode::SingleLowPwrLowNoise3),
+                        (_, _) => Ok(Mode::SingleLowPwrLowNoise4),
+                    };
+                    assert_eq!(
+                        Mode::try_new(mode, lp_mode, low_noise),
+                        expected,
+                        "mode {mode}, lp_mode {lp_mode}, low_noise {low_noise}"
+                    );
+                    if let Ok(val) = expected {
+                        // The fields written back select the same mode, lp_mode cleared in high performance
+                        assert_eq!(val.mode(), mode);
+                        assert_eq!(val.low_noise(), low_noise);
+                        assert_eq!(val.lp_mode(), if mode == 0b01 { 0 } else { lp_mode });
+                        decoded += 1;
+                    }
+                }
+            }
+        }
+        assert_eq!(decoded, 24);
+    }
+
+    /// Check the descriptors of writable registers against their setters: the highest code of each field over a
+    /// cleared register sets exactly the described bits, and 0 over a filled register clears exactly them.
+    macro_rules! setters_match_fields {
+        ($($reg:ident [$($with:ident: $ty:ty),+ $(,)?]),+ $(,)?) => {$(
+            let mut fields = $reg::FIELDS.iter();
+            $(
+                let desc = fields.next().unwrap();
+                let name = concat!(stringify!($reg), "::", stringify!($with));
+                assert_eq!(stringify!($with).strip_prefix("with_"), Some(desc.name), "{name}");
+                assert!(!desc.read_only, "{name}");
+
+                let mask = ((1u32 << desc.width) - 1) << desc.offset;
+                let max = ((1u16 << desc.width) - 1) as u8 as $ty;
+                let filled = u32::from($reg::from_bits(!0).into_bits());
+                let set = u32::from($reg::from_bits(0).$with(max).into_bits());
+                let cleared = u32::from($reg::from_bits(!0).$with(0).into_bits());
+                assert_eq!(set, mask, "{name}");
+                assert_eq!(cleared, filled & !mask, "{name}");
+            )+
+            assert_eq!(fields.next(), None, "{}", stringify!($reg));
+        )+};
+    }
+
+    #[test]
+    fn field_descriptors_select_the_bits_of_the_setters() {
+        setters_match_fields!(
+            Ctrl1 [with_lp_mode: u8, with_mode: u8, with_odr: u8],
+            Ctrl2 [
+                with_sim: u8, with_i2c_disable: u8, with_if_add_inc: u8, with_bdu: u8, with_cs_pu_disc: u8,
+                with_soft_reset: u8, with_boot: u8,
+            ],
+            Ctrl3 [with_slp_mode: u8, with_h_lactive: u8, with_lir: u8, with_pp_od: u8, with_st: u8],
+            Ctrl4Int1PadCtrl [
+                with_int1_drdy: u8, with_int1_fth: u8, with_int1_diff5: u8, with_int1_tap: u8, with_int1_ff: u8,
+                with_int1_wu: u8, with_int1_single_tap: u8, with_int1_6d: u8,
+            ],
+            Ctrl5Int2PadCtrl [
+                with_int2_drdy: u8, with_int2_fth: u8, with_int2_diff5: u8, with_int2_ovr: u8,
+                with_int2_drdy_t: u8, with_int2_boot: u8, with_int2_sleep_chg: u8, with_int2_sleep_state: u8,
+            ],
+            Ctrl6 [with_low_noise: u8, with_fds: u8, with_fs: u8, with_bw_filt: u8],
+            FifoCtrl [with_fth: u8, with_fmode: u8],
+            TapThsX [with_tap_thsx: u8, with_six_d_ths: u8, with_four_d_en: u8],
+            TapThsY [with_tap_thsy: u8, with_tap_prior: u8],
+            TapThsZ [with_tap_thsz: u8, with_tap_z_en: u8, with_tap_y_en: u8, with_tap_x_en: u8],
+            IntDur [with_shock: u8, with_quiet: u8, with_latency: u8],
+            WakeUpThs [with_wk_ths: u8, with_sleep_on: u8, with_single_double_tap: u8],
+            WakeUpDur [with_sleep_dur: u8, with_stationary: u8, with_wake_dur: u8, with_ff_dur: u8],
+            FreeFall [with_ff_ths: u8, with_ff_dur: u8],
+            XOfsUsr [with_x_ofs_usr: i8],
+            YOfsUsr [with_y_ofs_usr: i8],
+            ZOfsUsr [with_z_ofs_usr: i8],
+            Ctrl7 [
+                with_lpass_on6d: u8, with_hp_ref_mode: u8, with_usr_off_w: u8, with_usr_off_on_wu: u8,
+                with_usr_off_on_out: u8, with_interrupts_enable: u8, with_int2_on_int1: u8,
+                with_drdy_pulsed: u8,
+            ],
+        );
+    }
+
+    /// Check the descriptors of read-only registers against their getters: a raw value with exactly the described
+    /// bits set reads the field with all its bits set, -1 for a signed field, and the other bits read it as 0.
+    macro_rules! getters_match_fields {
+        ($($reg:ident [$($field:ident: $ty:ty),+ $(,)?]),+ $(,)?) => {$(
+            let mut fields = $reg::FIELDS.iter();
+            $(
+                let desc = fields.next().unwrap();
+                let name = concat!(stringify!($reg), "::", stringify!($field));
+                assert_eq!(stringify!($field), desc.name, "{name}");
+                assert!(desc.read_only, "{name}");
+
+                let mask = ((1u32 << desc.width) - 1) << desc.offset;
+                let all_set: i64 = if <$ty>::MIN != 0 { -1 } else { (1 << desc.width) - 1 };
+                let inside: $ty = $reg::from_bits(mask as _).$field();
+                let outside: $ty = $reg::from_bits(!mask as _).$field();
+                assert_eq!(inside as i64, all_set, "{name}");
+                assert_eq!(outside, 0, "{name}");
+            )+
+            assert_eq!(fields.next(), None, "{}", stringify!($reg));
+        )+};
+    }
+
+    #[test]
+    fn field_descriptors_select_the_bits_of_the_getters() {
+        getters_match_fields!(
+            OutT [temp: i16],
+            OutT8 [temp: i8],
+            Status [
+                drdy: u8, ff_ia: u8, six_d_ia: u8, single_tap: u8, double_tap: u8, sleep_state: u8, wu_ia: u8,
+                fifo_ths: u8,
+            ],
+            OutX [x: i16],
+            OutY [y: i16],
+            OutZ [z: i16],
+            FifoSamples [diff: u8, fifo_ovr: u8, fifo_fth: u8],
+            StatusDup [
+                drdy: u8, ff_ia: u8, six_d_ia: u8, single_tap: u8, double_tap: u8, sleep_state_ia: u8,
+                drdy_t: u8, ovr: u8,
+            ],
+            WakeUpSrc [z_wu: u8, y_wu: u8, x_wu: u8, wu_ia: u8, sleep_state_ia: u8, ff_ia: u8],
+            TapSrc [
+                z_tap: u8, y_tap: u8, x_tap: u8, tap_sign: u8, double_tap: u8, single_tap: u8, tap_ia: u8,
+            ],
+            SixdSrc [xl: u8, xh: u8, yl: u8, yh: u8, zl: u8, zh: u8, six_d_ia: u8],
+            AllIntSrc [
+                ff_ia: u8, wu_ia: u8, single_tap: u8, double_tap: u8, six_d_ia: u8, sleep_change_ia: u8,
+            ],
+        );
+    }
+
+    #[test]
+    fn every_register_with_fields_is_listed() {
+        let listed = |reg: Reg| REGISTERS.iter().any(|(_, addr, _)| *addr == reg as u8);
+        for reg in Reg::ALL {
+            // The high bytes of the 16-bit registers are covered by their low byte
+            let high_byte = matches!(reg, Reg::OutTH | Reg::OutXH | Reg::OutYH | Reg::OutZH);
+            assert_eq!(listed(reg), reg != Reg::WhoAmI && !high_byte, "{reg:?}");
+        }
+    }
+
+    #[test]
+    fn field_descriptors_do_not_overlap() {
+        for (reg, _, fields) in REGISTERS {
+            let mut used = 0u32;
+            for desc in *fields {
+                assert!(
+                    desc.width > 0 && desc.offset + desc.width <= 16,
+                    "{reg}::{}",
+                    desc.name
+                );
+                let mask = ((1u32 << desc.width) - 1) << desc.offset;
+                assert_eq!(used & mask, 0, "{reg}::{}", desc.name);
+                used |= mask;
+            }
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_new_defaults_the_reserved_mode() {
+        assert_eq!(Mode::new(0b11, 0b10, 1), Mode::ContLowPwr12bit);
+        assert_eq!(Mode::new(0b01, 0b10, 1), Mode::HighPerformanceLowNoise);
+    }
+
+    /// Reset values of the datasheet register table; every other register resets to `0x00`.
+    const DATASHEET_RESET: [(Reg, u8); 2] = [(Reg::WhoAmI, 0x44), (Reg::Ctrl2, 0x04)];
+
+    #[test]
+    fn reset_image_matches_the_datasheet() {
+        let image = reset_image();
+        for (addr, val) in image.into_iter().enumerate() {
+            let expected = Reg::ALL
+                .into_iter()
+                .find(|reg| *reg as usize == addr)
+                .and_then(|reg| DATASHEET_RESET.into_iter().find(|(r, _)| *r == reg))
+                .map_or(0x00, |(_, val)| val);
+            assert_eq!(val, expected, "address {addr:#04x}");
+        }
+        assert_eq!(Ctrl2::RESET_VALUE, 0x04);
+        assert_eq!(OutX::RESET_VALUE, 0x0000);
+    }
+
+    #[test]
+    fn reset_value_is_the_default_of_the_fields() {
+        macro_rules! defaults_match {
+            ($($reg:ident),+ $(,)?) => {
+                $(
+                    let reset = $reg::from_bits($reg::RESET_VALUE);
+                    assert!(reset.is_reset_default(), stringify!($reg));
+                    assert!(!$reg::from_bits(!$reg::RESET_VALUE).is_reset_default(), stringify!($reg));
+                    // The raw value of the field defaults depends on the bit order
+                    #[cfg(not(feature = "bit_order_msb"))]
+                    assert_eq!($reg::new().into_bits(), $reg::RESET_VALUE, stringify!($reg));
+                )+
+            };
+        }
+        defaults_match!(
+            OutT,
+            Ctrl1,
+            Ctrl2,
+            Ctrl3,
+            Ctrl4Int1PadCtrl,
+            Ctrl5Int2PadCtrl,
+            Ctrl6,
+            OutT8,
+            Status,
+            OutX,
+            OutY,
+            OutZ,
+            FifoCtrl,
+            FifoSamples,
+            TapThsX,
+            TapThsY,
+            TapThsZ,
+            IntDur,
+            WakeUpThs,
+            WakeUpDur,
+            FreeFall,
+            StatusDup,
+            WakeUpSrc,
+            TapSrc,
+            SixdSrc,
+            AllIntSrc,
+            XOfsUsr,
+            YOfsUsr,
+            ZOfsUsr,
+            Ctrl7,
+        );
+    }
+
+    /// Little-endian byte pairs, as the bus delivers them, and the 14-bit sample they hold.
+    const AXIS_BYTES: [([u8; 2], i16); 5] = [
+        ([0x00, 0x00], 0),
+        ([0x8C, 0x04], 0x0123),
+        ([0xF8, 0xFF], -2),
+        ([0xFC, 0x7F], 8191),
+        ([0x00, 0x80], -8192),
+    ];
+
+    /// Little-endian byte pairs and the 12-bit temperature they hold.
+    const TEMP_BYTES: [([u8; 2], i16); 4] = [
+        ([0x30, 0x01], 0x013),
+        ([0xF0, 0xFF], -1),
+        ([0xF0, 0x7F], 2047),
+        ([0x00, 0x80], -2048),
+    ];
+
+    #[test]
+    fn output_registers_assemble_the_wire_bytes_low_first() {
+        for (bytes, sample) in AXIS_BYTES {
+            assert_eq!(OutX::from_le_bytes(bytes).x(), sample, "{bytes:02x?}");
+            assert_eq!(OutY::from_le_bytes(bytes).y(), sample, "{bytes:02x?}");
+            assert_eq!(OutZ::from_le_bytes(bytes).z(), sample, "{bytes:02x?}");
+        }
+        for (bytes, temp) in TEMP_BYTES {
+            assert_eq!(OutT::from_le_bytes(bytes).temp(), temp, "{bytes:02x?}");
+        }
+    }
+}
@@ -1 +1,5 @@
+//! Register map of the IIS2DLPC.
+//!
+//! Register structs compare and hash by their raw value, reserved bits included.
+
 pub mod main;
@@ -0,0 +1,327 @@
+//! Axis remapping between the sensor frame and the board frame.
+//!
+//! Boards often mount the sensor rotated. An [`AxisRemap`] set with
+//! [`crate::Iis2dlpc::axis_remap_set`] tells, for each axis of the board, which axis of the sensor
+//! it reads and with which sign; the driver then applies it to everything it decodes, so that
+//! samples and events agree on the axes:
+//!
+//! - the acceleration getters ([`crate::Iis2dlpc::acceleration_raw_get`],
+//!   [`crate::Iis2dlpc::acceleration_mg_get`] and the functions built on them), the decoded FIFO
+//!   drains (`fifo_drain_vec`, [`crate::Iis2dlpc::fifo_drain_exact`], `fifo_drain_into`) and the
+//!   triggered capture;
+//!   [`crate::Iis2dlpc::acceleration_raw_sensor_get`] still reads the sensor frame;
+//! - the user offsets: [`crate::Iis2dlpc::usr_offsets_set`], [`crate::Iis2dlpc::usr_offset_x_set`]
+//!   and their siblings take board-frame values and write them to the matching sensor registers,
+//!   negated where the axis is flipped, and the getters read them back the same way;
+//! - the decoded events: the tap axis and sign of [`crate::event::TapEvent`], the
+//!   [`SixdSrc`] of [`crate::event::Event::SixD`] and the [`Orientation`] of
+//!   [`crate::Iis2dlpc::orientation_get`].
+//!
+//! What the device computes stays in the sensor frame: the thresholds, durations and axis
+//! enables of the embedded functions, the tap axis priority, the self-test deflections, and the
+//! register contents returned as they are read, e.g. [`crate::Iis2dlpc::all_sources_get`], the
+//! byte buffers of [`crate::Iis2dlpc::fifo_drain`] and [`crate::batch::SampleBatch`]. The
+//! functions of [`AxisRemap`] convert them when needed, e.g. [`AxisRemap::wake_up_src`].
+//!
+//! ```ignore
+//! // Sensor rotated by 90° around Z: the board X axis is the sensor Y axis, the board Y axis is
+//! // the sensor X axis reversed
+//! let remap = AxisRemap::new([SignedAxis::PosY, SignedAxis::NegX, SignedAxis::PosZ]).unwrap();
+//! sensor.axis_remap_set(remap);
+//! ```
+
+#[cfg(feature = "events")]
+use crate::event::{Event, Orientation, TapEvent};
+use crate::prelude::*;
+
+/// Axis of the sensor frame, with a sign.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SignedAxis {
+    /// Sensor X axis.
+    PosX,
+    /// Sensor X axis, reversed.
+    NegX,
+    /// Sensor Y axis.
+    PosY,
+    /// Sensor Y axis, reversed.
+    NegY,
+    /// Sensor Z axis.
+    PosZ,
+    /// Sensor Z axis, reversed.
+    NegZ,
+}
+
+impl SignedAxis {
+    /// Every signed axis.
+    pub const ALL: [SignedAxis; 6] = [
+        SignedAxis::PosX,
+        SignedAxis::NegX,
+        SignedAxis::PosY,
+        SignedAxis::NegY,
+        SignedAxis::PosZ,
+        SignedAxis::NegZ,
+    ];
+
+    /// Create a signed axis.
+    pub fn new(axis: Axis, negative: bool) -> Self {
+        match (axis, negative) {
+            (Axis::X, false) => SignedAxis::PosX,
+            (Axis::X, true) => SignedAxis::NegX,
+            (Axis::Y, false) => SignedAxis::PosY,
+            (Axis::Y, true) => SignedAxis::NegY,
+            (Axis::Z, false) => SignedAxis::PosZ,
+            (Axis::Z, true) => SignedAxis::NegZ,
+        }
+    }
+
+    /// Get the sensor axis.
+    pub fn axis(&self) -> Axis {
+        match self {
+            SignedAxis::PosX | SignedAxis::NegX => Axis::X,
+            SignedAxis::PosY | SignedAxis::NegY => Axis::Y,
+            SignedAxis::PosZ | SignedAxis::NegZ => Axis::Z,
+        }
+    }
+
+    /// Check whether the axis is reversed.
+    pub fn is_negative(&self) -> bool {
+        matches!(self, SignedAxis::NegX | SignedAxis::NegY | SignedAxis::NegZ)
+    }
+}
+
+/// Mapping from the sensor frame to the board frame, see the [module](self) documentation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AxisRemap {
+    board: [SignedAxis; 3],
+}
+
+impl Default for AxisRemap {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl AxisRemap {
+    /// The board frame is the sensor frame.
+    pub const IDENTITY: AxisRemap = AxisRemap {
+        board: [SignedAxis::PosX, SignedAxis::PosY, SignedAxis::PosZ],
+    };
+
+    /// Create a remapping.
+    ///
+    /// ### Arguments
+    /// - `board`: The sensor axis read by the board X, Y and Z axes.
+    ///
+    /// ### Returns
+    /// - `Some(AxisRemap)`: The remapping.
+    /// - `None`: If a sensor axis is used twice.
+    pub fn new(board: [SignedAxis; 3]) -> Option<Self> {
+        let [x, y, z] = board.map(|axis| axis.axis());
+        (x != y && y != z && x != z).then_some(Self { board })
+    }
+
+    /// Iterate over the 24 remappings that are rotations, see [`Self::is_rotation`].
+    pub fn rotations() -> impl Iterator<Item = AxisRemap> {
+        SignedAxis::ALL
+            .into_iter()
+            .flat_map(|x| SignedAxis::ALL.into_iter().map(move |y| (x, y)))
+            .flat_map(|(x, y)| SignedAxis::ALL.into_iter().map(move |z| [x, y, z]))
+            .filter_map(AxisRemap::new)
+            .filter(AxisRemap::is_rotation)
+    }
+
+    /// Get the sensor axis read by the board X, Y and Z axes.
+    pub fn board_axes(&self) -> [SignedAxis; 3] {
+        self.board
+    }
+
+    /// Get the sensor axis read by a board axis.
+    pub fn sensor_axis(&self, board: Axis) -> SignedAxis {
+        pick(self.board, board)
+    }
+
+    /// Get the board axis reading a sensor axis.
+    pub fn board_axis(&self, sensor: Axis) -> SignedAxis {
+        AXES.into_iter()
+            .zip(self.board)
+            .find(|(_, axis)| axis.axis() == sensor)
+            .map_or(SignedAxis::new(sensor, false), |(board, axis)| {
+                SignedAxis::new(board, axis.is_negative())
+            })
+    }
+
+    /// Check whether the remapping is a rotation, that is a possible mounting of the sensor.
+    ///
+    /// The other remappings mirror the frame: they turn the right-handed sensor frame into a
+    /// left-handed one.
+    pub fn is_rotation(&self) -> bool {
+        let [x, y, z] = self.board.map(|axis| axis.axis() as usize);
+        // Even permutations are the cyclic ones
+        let even = (y + 3 - x) % 3 == 1 && (z + 3 - y) % 3 == 1;
+        let flips = self.board.iter().filter(|axis| axis.is_negative()).count();
+        even == (flips % 2 == 0)
+    }
+
+    /// Convert raw values from the sensor frame to the board frame.
+    ///
+    /// Reversing `i16::MIN` saturates to `i16::MAX`.
+    pub fn raw(&self, sensor: [i16; 3]) -> [i16; 3] {
+        self.board.map(|axis| {
+            let val = pick(sensor, axis.axis());
+            if axis.is_negative() {
+                val.saturating_neg()
+            } else {
+                val
+            }
+        })
+    }
+
+    /// Convert values in mg from the sensor frame to the board frame.
+    pub fn mg(&self, sensor: [f32; 3]) -> [f32; 3] {
+        self.board.map(|axis| {
+            let val = pick(sensor, axis.axis());
+            if axis.is_negative() { -val } else { val }
+        })
+    }
+
+    /// Convert user offsets from the board frame to the sensor frame.
+    ///
+    /// Reversing `i8::MIN` saturates to `i8::MAX`.
+    pub fn offsets_to_sensor(&self, board: [i8; 3]) -> [i8; 3] {
+        AXES.map(|sensor| {
+            let axis = self.board_axis(sensor);
+            let val = pick(board, axis.axis());
+            if axis.is_negative() {
+                val.saturating_neg()
+            } else {
+                val
+            }
+        })
+    }
+
+    /// Convert user offsets from the sensor frame to the board frame.
+    ///
+    /// Reversing `i8::MIN` saturates to `i8::MAX`.
+    pub fn offsets_to_board(&self, sensor: [i8; 3]) -> [i8; 3] {
+        self.board.map(|axis| {
+            let val = pick(sensor, axis.axis());
+            if axis.is_negative() {
+                val.saturating_neg()
+            } else {
+                val
+            }
+        })
+    }
+
+    /// Convert a tap event to the board frame.
+    ///
+    /// The axis and the sign are converted; the raw `TAP_SRC` content is kept as read.
+    #[cfg(feature = "events")]
+    pub fn tap_event(&self, event: TapEvent) -> TapEvent {
+        let Some(sensor) = event.axis else {
+            return event;
+        };
+        let board = self.board_axis(sensor);
+        TapEvent {
+            axis: Some(board.axis()),
+            negative: event.negative != board.is_negative(),
+            ..event
+        }
+    }
+
+    /// Convert the axis flags of a `WAKE_UP_SRC` content to the board frame.
+    ///
+    /// A wake-up flag tells that the axis exceeded the threshold in either direction, so only the
+    /// axes are permuted.
+    pub fn wake_up_src(&self, src: WakeUpSrc) -> WakeUpSrc {
+        let flags = [
+            mask(|b| WakeUpSrc::from_bits(b).x_wu()),
+            mask(|b| WakeUpSrc::from_bits(b).y_wu()),
+            mask(|b| WakeUpSrc::from_bits(b).z_wu()),
+        ];
+        let bits = src.into_bits();
+        let mut out = bits & !flags.iter().fold(0, |m, b| m | b);
+        for (board, axis) in flags.into_iter().zip(self.board) {
+            if bits & pick(flags, axis.axis()) != 0 {
+                out |= board;
+            }
+        }
+        WakeUpSrc::from_bits(out)
+    }
+
+    /// Convert the orientation flags of a `SIXD_SRC` content to the board frame.
+    ///
+    /// The high and low flags of a reversed axis are swapped.
+    pub fn sixd_src(&self, src: SixdSrc) -> SixdSrc {
+        let high = [
+            mask(|b| SixdSrc::from_bits(b).xh()),
+            mask(|b| SixdSrc::from_bits(b).yh()),
+            mask(|b| SixdSrc::from_bits(b).zh()),
+        ];
+        let low = [
+            mask(|b| SixdSrc::from_bits(b).xl()),
+            mask(|b| SixdSrc::from_bits(b).yl()),
+            mask(|b| SixdSrc::from_bits(b).zl()),
+        ];
+        let bits = src.into_bits();
+        let mut out = bits & !high.iter().chain(&low).fold(0, |m, b| m | b);
+        for ((board_high, board_low), axis) in high.into_iter().zip(low).zip(self.board) {
+            let (sensor_high, sensor_low) = (pick(high, axis.axis()), pick(low, axis.axis()));
+            let (from_high, from_low) = if axis.is_negative() {
+                (sensor_low, sensor_high)
+            } else {
+                (sensor_high, sensor_low)
+            };
+            if bits & from_high != 0 {
+                out |= board_high;
+            }
+            if bits & from_low != 0 {
+                out |= board_low;
+            }
+        }
+        SixdSrc::from_bits(out)
+    }
+
+    /// Convert an orientation to the board frame.
+    ///
+    /// An orientation on a reversed axis points the other way.
+    #[cfg(feature = "events")]
+    pub fn orientation(&self, orientation: Orientation) -> Orientation {
+        let board = self.board_axis(orientation.axis());
+        Orientation::new(board.axis(), orientation.is_down() != board.is_negative())
+    }
+
+    /// Convert a decoded event to the board frame.
+    ///
+    /// The axes of a wake-up event are permuted as in [`Self::wake_up_src`].
+    #[cfg(feature = "events")]
+    pub fn event(&self, event: Event) -> Event {
+        match event {
+            Event::Tap(tap) => Event::Tap(self.tap_event(tap)),
+            Event::SixD(src) => Event::SixD(self.sixd_src(src)),
+            Event::WakeUp { axes } => Event::WakeUp {
+                axes: AXES.map(|board| pick(axes, self.sensor_axis(board).axis())),
+            },
+            event => event,
+        }
+    }
+}
+
+const AXES: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+/// Get the value of an axis.
+fn pick<T: Copy>([x, y, z]: [T; 3], axis: Axis) -> T {
+    match axis {
+        Axis::X => x,
+        Axis::Y => y,
+        Axis::Z => z,
+    }
+}
+
+/// Find the bit of a 1-bit field, whatever the bit order of the register structs.
+fn mask(get: impl Fn(u8) -> u8) -> u8 {
+    (0..8)
+        .map(|i| 1 << i)
+        .find(|b| get(*b) != 0)
+        .unwrap_or_default()
+}
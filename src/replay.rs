@@ -0,0 +1,98 @@
+//! Offline replay of recorded samples.
+//!
+//! Field units record raw FIFO drains, as DMA dumps or telemetry frames, so that the
+//! detection parameters can be tuned on a host afterwards. [`replay_fifo_bytes`] feeds such a
+//! recording to a set of [`SampleSink`]s through the same decoding and scaling as
+//! [`crate::Iis2dlpc::fifo_drain_into`], which is built on it: the sinks see the same values
+//! offline as in production.
+//!
+//! ```ignore
+//! // Host side: the recording was acquired at ±4 g, high-performance mode, 800 Hz
+//! let mut bank = GoertzelBank::<2>::new(&[(100.0, 50.0), (150.0, 50.0)], 800.0).unwrap();
+//! let mut stats = AxisStats::new();
+//! replay_fifo_bytes(&recording, Fs::_4g, Mode::HighPerformance, &mut [&mut bank.on_axis(Axis::Z), &mut stats])?;
+//! ```
+//!
+//! The helpers fed this way own no bus access: [`crate::batch::SampleBatch`], the sinks of the
+//! `dsp` module and the threshold weights of [`Fs`] and [`UsrOffW`] are plain values.
+
+use crate::batch::{MisalignedBatch, SampleBatch, raw_to_mg};
+use crate::prelude::*;
+use crate::remap::AxisRemap;
+#[cfg(feature = "telemetry")]
+use crate::telemetry::TelemetryFrame;
+
+/// Consumer of decoded samples.
+///
+/// A sink is fed one sample at a time, oldest first, by [`replay_fifo_bytes`],
+/// `replay_telemetry_frame` and [`crate::Iis2dlpc::fifo_drain_into`].
+pub trait SampleSink {
+    /// Feed a sample.
+    ///
+    /// ### Arguments
+    /// - `raw`: The raw X, Y and Z values, as returned by [`crate::Iis2dlpc::acceleration_raw_get`].
+    /// - `mg`: The same values in mg.
+    fn feed(&mut self, raw: [i16; 3], mg: [f32; 3]);
+}
+
+/// Feed recorded FIFO bytes to sinks.
+///
+/// Every sample is fed to every sink, in the order of `sinks`, before the next sample.
+///
+/// ### Arguments
+/// - `bytes`: The recorded bytes, 6 per sample laid out as in [`SampleBatch`], oldest first.
+/// - `fs`: The full scale the samples were acquired with.
+/// - `mode`: The operating mode the samples were acquired with; 12-bit modes are scaled accordingly.
+/// - `sinks`: The sinks to feed.
+///
+/// ### Returns
+/// - `Ok(usize)`: The number of samples fed.
+/// - `Err(MisalignedBatch)`: If the length is not a multiple of 6; nothing is fed.
+pub fn replay_fifo_bytes(
+    bytes: &[u8],
+    fs: Fs,
+    mode: Mode,
+    sinks: &mut [&mut dyn SampleSink],
+) -> Result<usize, MisalignedBatch> {
+    replay_fifo_bytes_remapped(bytes, fs, mode, AxisRemap::IDENTITY, sinks)
+}
+
+/// Feed recorded FIFO bytes to sinks, in the board frame.
+///
+/// This function is [`replay_fifo_bytes`] with the samples converted by `remap`, as
+/// [`crate::Iis2dlpc::fifo_drain_into`] does with the remapping set by
+/// [`crate::Iis2dlpc::axis_remap_set`]; the recording itself is in the sensor frame.
+///
+/// ### Returns
+/// - `Ok(usize)`: The number of samples fed.
+/// - `Err(MisalignedBatch)`: If the length is not a multiple of 6; nothing is fed.
+pub fn replay_fifo_bytes_remapped(
+    bytes: &[u8],
+    fs: Fs,
+    mode: Mode,
+    remap: AxisRemap,
+    sinks: &mut [&mut dyn SampleSink],
+) -> Result<usize, MisalignedBatch> {
+    let batch = SampleBatch::new(bytes)?;
+    for raw in batch {
+        feed(remap.raw(raw), fs, mode.is_low_power_1(), sinks);
+    }
+    Ok(batch.len())
+}
+
+/// Feed the sample of a telemetry frame to sinks.
+///
+/// ### Arguments
+/// - `frame`: The decoded frame, which carries its full scale and resolution.
+/// - `sinks`: The sinks to feed.
+#[cfg(feature = "telemetry")]
+pub fn replay_telemetry_frame(frame: &TelemetryFrame, sinks: &mut [&mut dyn SampleSink]) {
+    feed(frame.raw, frame.fs, frame.low_power_1, sinks);
+}
+
+fn feed(raw: [i16; 3], fs: Fs, low_power_1: bool, sinks: &mut [&mut dyn SampleSink]) {
+    let mg = raw.map(|lsb| raw_to_mg(lsb, fs, low_power_1));
+    for sink in sinks.iter_mut() {
+        sink.feed(raw, mg);
+    }
+}
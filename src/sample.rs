@@ -0,0 +1,62 @@
+//! Scaled accelerometer samples.
+
+/// Acceleration and temperature sample, as returned by [`crate::Iis2dlpc::sample_get`].
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Sample {
+    /// Acceleration on the X, Y and Z axes, in mg.
+    pub acceleration_mg: [f32; 3],
+    /// Temperature, in degrees Celsius.
+    pub temperature_celsius: f32,
+}
+
+/// Handling of the temperature sensor.
+///
+/// The device has no bit to disable the temperature sensor: the temperature is converted together with
+/// the acceleration and its cost is part of the supply current of every mode. What the host controls is
+/// the traffic: reading `OUT_T_L`/`OUT_T_H` and routing the temperature data-ready signal to INT2.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TempPolicy {
+    /// The temperature is read on request (default).
+    #[default]
+    Enabled,
+    /// The temperature is unused: the temperature APIs and the `INT2_DRDY_T` routing are rejected, so
+    /// that a leftover read in shared code is caught.
+    Ignore,
+}
+
+/// Standard gravity, in m/s² per g.
+pub const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// Convert an acceleration from mg to m/s².
+///
+/// ### Arguments
+/// - `mg`: The acceleration in mg.
+///
+/// ### Returns
+/// - `f32`: The acceleration in m/s² (1 mg = 0.00980665 m/s²).
+pub fn from_mg_to_m_s2(mg: f32) -> f32 {
+    mg * STANDARD_GRAVITY / 1000.0
+}
+
+#[cfg(feature = "uom")]
+impl Sample {
+    /// Get the acceleration as typed quantities.
+    pub fn acceleration(&self) -> [uom::si::f32::Acceleration; 3] {
+        self.acceleration_mg.map(mg_to_acceleration)
+    }
+
+    /// Get the temperature as a typed quantity.
+    pub fn temperature(&self) -> uom::si::f32::ThermodynamicTemperature {
+        uom::si::f32::ThermodynamicTemperature::new::<
+            uom::si::thermodynamic_temperature::degree_celsius,
+        >(self.temperature_celsius)
+    }
+}
+
+/// Convert an acceleration from mg to a typed quantity.
+#[cfg(feature = "uom")]
+pub(crate) fn mg_to_acceleration(mg: f32) -> uom::si::f32::Acceleration {
+    uom::si::f32::Acceleration::new::<uom::si::acceleration::meter_per_second_squared>(
+        from_mg_to_m_s2(mg),
+    )
+}
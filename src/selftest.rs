@@ -0,0 +1,109 @@
+//! Full self-test procedure with per-axis results.
+//!
+//! [`crate::Iis2dlpc::self_test_full`] runs the datasheet procedure in three phases: a baseline
+//! with the self-test disabled, then the positive and the negative sign. Every phase waits
+//! [`SelfTestConfig::settle_ms`], discards [`SelfTestConfig::discard`] samples and averages
+//! [`SelfTestConfig::samples`] samples, one data period apart. The procedure runs at 50 Hz,
+//! ±4 g, high-performance mode, with block data update and the FIFO bypassed; the previous
+//! configuration is written back afterwards, also when a phase fails.
+//!
+//! The [`SelfTestReport`] keeps every intermediate value, so that a production test station can
+//! bin failures by axis and phase rather than by a single pass/fail flag.
+
+/// Parameters of the self-test procedure.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestConfig {
+    /// Number of samples averaged in every phase; `0` is read as `1`.
+    pub samples: u8,
+    /// Number of samples discarded after settling, before the averaged ones.
+    pub discard: u8,
+    /// Wait after entering a phase, in milliseconds.
+    pub settle_ms: u32,
+    /// Lowest accepted self-test deflection, in mg.
+    pub min_mg: f32,
+    /// Highest accepted self-test deflection, in mg.
+    pub max_mg: f32,
+}
+
+impl Default for SelfTestConfig {
+    /// The datasheet procedure: 5 samples, 1 discarded, 100 ms settling, 70 mg to 1500 mg.
+    fn default() -> Self {
+        Self {
+            samples: 5,
+            discard: 1,
+            settle_ms: 100,
+            min_mg: 70.0,
+            max_mg: 1500.0,
+        }
+    }
+}
+
+/// Self-test results of one axis.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisSelfTest {
+    /// Average acceleration with the self-test disabled, in mg.
+    pub baseline_mg: f32,
+    /// Average with the positive self-test minus the baseline, in mg.
+    pub positive_delta_mg: f32,
+    /// Average with the negative self-test minus the baseline, in mg.
+    pub negative_delta_mg: f32,
+    /// The positive deflection lies within the limits.
+    pub positive_pass: bool,
+    /// The negative deflection lies within the limits, with the opposite sign.
+    pub negative_pass: bool,
+}
+
+impl AxisSelfTest {
+    /// Check whether both phases passed on this axis.
+    pub fn passed(&self) -> bool {
+        self.positive_pass && self.negative_pass
+    }
+}
+
+/// Results of [`crate::Iis2dlpc::self_test_full`].
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestReport {
+    /// Results of the X, Y and Z axes.
+    pub axes: [AxisSelfTest; 3],
+    /// Lowest accepted deflection used, in mg.
+    pub min_mg: f32,
+    /// Highest accepted deflection used, in mg.
+    pub max_mg: f32,
+}
+
+impl SelfTestReport {
+    /// Build the report from the phase averages.
+    pub(crate) fn new(
+        baseline: [f32; 3],
+        positive: [f32; 3],
+        negative: [f32; 3],
+        cfg: &SelfTestConfig,
+    ) -> Self {
+        let mut axes = [AxisSelfTest::default(); 3];
+        for (i, axis) in axes.iter_mut().enumerate() {
+            let base = baseline.get(i).copied().unwrap_or_default();
+            let positive_delta_mg = positive.get(i).copied().unwrap_or_default() - base;
+            let negative_delta_mg = negative.get(i).copied().unwrap_or_default() - base;
+            *axis = AxisSelfTest {
+                baseline_mg: base,
+                positive_delta_mg,
+                negative_delta_mg,
+                positive_pass: (cfg.min_mg..=cfg.max_mg).contains(&positive_delta_mg),
+                negative_pass: (cfg.min_mg..=cfg.max_mg).contains(&-negative_delta_mg),
+            };
+        }
+        Self {
+            axes,
+            min_mg: cfg.min_mg,
+            max_mg: cfg.max_mg,
+        }
+    }
+
+    /// Check whether every axis passed both phases.
+    pub fn passed(&self) -> bool {
+        self.axes.iter().all(AxisSelfTest::passed)
+    }
+}
@@ -0,0 +1,97 @@
+//! Settling of the signal path after a configuration change.
+//!
+//! A change of the filter, the operating mode, the full scale, the data rate or the high-pass
+//! reference takes effect on a running data stream without the output telling the samples
+//! produced before and after it apart. The setters of these settings return a [`SettleHint`]: how
+//! long to wait, once the function returns, before the output reflects the change.
+//! [`crate::Iis2dlpc::settle_for`] performs that wait:
+//!
+//! ```ignore
+//! let (hint, _) = sensor.filter_bandwidth_set(BwFilt::OdrDiv10)?;
+//! sensor.settle_for(hint)?;
+//! ```
+//!
+//! | Setter                                    | Hint                                                           |
+//! |-------------------------------------------|----------------------------------------------------------------|
+//! | [`crate::Iis2dlpc::filter_bandwidth_set`] | filter settling at the current data rate                       |
+//! | [`crate::Iis2dlpc::filter_path_set`]      | filter settling; [`PENDING_SAMPLES`] for the user offset alone |
+//! | [`crate::Iis2dlpc::data_rate_set`]        | filter settling at the new data rate                           |
+//! | [`crate::Iis2dlpc::power_mode_set`]       | [`PENDING_SAMPLES`]                                            |
+//! | [`crate::Iis2dlpc::full_scale_set`]       | [`PENDING_SAMPLES`]                                            |
+//! | [`crate::Iis2dlpc::reference_mode_set`]   | [`PENDING_SAMPLES`]                                            |
+//!
+//! The filter settling comes from [`FILTER_SETTLING_SAMPLES`] and is returned as a duration, fixed
+//! by the data rate the filter runs at; the other hints are counted in samples, whatever the data
+//! rate when waiting. The waits already made by the [`crate::errata`] workarounds are deducted.
+//! The hint is [`SettleHint::None`] when the value written is the one already set, and with the
+//! data rate off or triggered on demand: there is no stream to settle.
+
+use crate::prelude::*;
+
+/// Samples converted with the previous setting when a change applies from the next conversion:
+/// the one in progress.
+pub const PENDING_SAMPLES: u32 = 1;
+
+/// Data periods for the digital filter to settle within 1% of a step, per bandwidth.
+///
+/// [`BwFilt::settling_ms`] expressed in samples, `ln(100) · divider / 2π`, rounded up.
+pub const FILTER_SETTLING_SAMPLES: [(BwFilt, u32); 4] = [
+    (BwFilt::OdrDiv2, 2),
+    (BwFilt::OdrDiv4, 3),
+    (BwFilt::OdrDiv10, 8),
+    (BwFilt::OdrDiv20, 15),
+];
+
+/// Wait needed before the output reflects a configuration change, see the [module](self)
+/// documentation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum SettleHint {
+    /// The output reflects the change as it is.
+    #[default]
+    None,
+    /// Wait for this number of data periods, at the data rate in use when waiting.
+    Samples(u32),
+    /// Wait for this number of microseconds.
+    DurationUs(u32),
+}
+
+impl SettleHint {
+    /// Check whether no wait is needed.
+    pub fn is_none(&self) -> bool {
+        matches!(
+            self,
+            SettleHint::None | SettleHint::Samples(0) | SettleHint::DurationUs(0)
+        )
+    }
+
+    /// Get the wait in microseconds at a data rate.
+    ///
+    /// ### Arguments
+    /// - `odr`: The data rate the samples are counted at.
+    ///
+    /// ### Returns
+    /// - `u32`: The wait, saturated; `0` for a number of samples with `odr` off or triggered on demand.
+    pub fn duration_us(&self, odr: Odr) -> u32 {
+        match *self {
+            SettleHint::None => 0,
+            SettleHint::Samples(n) => odr.period_us().map_or(0, |us| us.saturating_mul(n)),
+            SettleHint::DurationUs(us) => us,
+        }
+    }
+}
+
+/// Get the filter settling of a bandwidth, in data periods, see [`FILTER_SETTLING_SAMPLES`].
+pub fn filter_settling_samples(bw: BwFilt) -> u32 {
+    FILTER_SETTLING_SAMPLES
+        .iter()
+        .find(|(b, _)| *b == bw)
+        .map_or(0, |(_, n)| *n)
+}
+
+/// Get the hint for a filter settling at a data period, minus the samples already waited.
+pub(crate) fn filter_hint(bw: BwFilt, period_us: u32, waited: u32) -> SettleHint {
+    match filter_settling_samples(bw).saturating_sub(waited) {
+        0 => SettleHint::None,
+        n => SettleHint::DurationUs(period_us.saturating_mul(n)),
+    }
+}
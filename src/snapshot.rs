@@ -0,0 +1,317 @@
+//! Configuration snapshots.
+//!
+//! A [`ConfigSnapshot`] holds the content of every writable configuration register, so that a
+//! configuration can be saved with [`crate::Iis2dlpc::config_snapshot_get`] and written back,
+//! for example after a brown-out, with [`crate::Iis2dlpc::config_snapshot_apply`].
+//!
+//! Writing a snapshot back is transactional: the current values are saved first and, if a write fails,
+//! the registers already written are restored, so that a flaky bus does not leave a mix of the old and
+//! the new configuration. [`crate::Iis2dlpc::config_snapshot_apply_checked`] reports the outcome in an
+//! [`ApplyError`].
+//!
+//! # Moving between configurations
+//!
+//! [`ConfigSnapshot::diff`] computes the [`ConfigDelta`] from one snapshot to another, e.g. between
+//! the shipping, active and diagnostic profiles of a product, and
+//! [`crate::Iis2dlpc::apply_delta`] writes it with the same rollback. Only the registers that differ
+//! are written, in the phase order of [`crate::phase`], with one rule on top: when the operating mode, the
+//! full scale or the low-noise setting changes while the data rate is running, `CTRL1` is first
+//! written with the data rate in power-down, so that no sample is converted with a partially
+//! changed scaling, and written with its target value last.
+//!
+//! | Change                         | Writes                                              |
+//! |--------------------------------|-----------------------------------------------------|
+//! | Data rate only                 | `CTRL1`                                             |
+//! | Full scale only, running       | `CTRL1` power-down, `CTRL6`, `CTRL1`                |
+//! | Full scale only, powered down  | `CTRL6`                                             |
+//! | Full scale and data rate       | `CTRL1` power-down, `CTRL6`, `CTRL1`                |
+//! | Operating mode, running        | `CTRL1` power-down, `CTRL1`                         |
+//! | Nothing                        | none                                                |
+
+use crate::Error;
+use crate::phase::REG_PHASES;
+use crate::prelude::*;
+
+/// Configuration registers, in the order a snapshot stores them, e.g. in a [`crate::blob`].
+///
+/// They are written back in the order of [`REG_PHASES`], see [`crate::phase`].
+pub const CONFIG_REGS: [Reg; 18] = [
+    Reg::Ctrl2,
+    Reg::Ctrl3,
+    Reg::Ctrl6,
+    Reg::FifoCtrl,
+    Reg::TapThsX,
+    Reg::TapThsY,
+    Reg::TapThsZ,
+    Reg::IntDur,
+    Reg::WakeUpThs,
+    Reg::WakeUpDur,
+    Reg::FreeFall,
+    Reg::XOfsUsr,
+    Reg::YOfsUsr,
+    Reg::ZOfsUsr,
+    Reg::Ctrl7,
+    Reg::Ctrl4Int1PadCtrl,
+    Reg::Ctrl5Int2PadCtrl,
+    Reg::Ctrl1,
+];
+
+/// Saved content of the configuration registers listed in [`CONFIG_REGS`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ConfigSnapshot {
+    pub(crate) regs: [u8; CONFIG_REGS.len()],
+}
+
+impl ConfigSnapshot {
+    /// Get the saved content of a register.
+    ///
+    /// ### Arguments
+    /// - `reg`: The register.
+    ///
+    /// ### Returns
+    /// - `Some(u8)`: The saved value.
+    /// - `None`: If `reg` is not part of the snapshot.
+    pub fn get(&self, reg: Reg) -> Option<u8> {
+        CONFIG_REGS
+            .iter()
+            .position(|r| *r == reg)
+            .and_then(|i| self.regs.get(i).copied())
+    }
+
+    /// Create a snapshot from a register image, e.g. read by [`crate::Iis2dlpc::registers_dump`].
+    ///
+    /// Only the registers listed in [`CONFIG_REGS`] are kept.
+    ///
+    /// ### Arguments
+    /// - `image`: The content of the register map, indexed by register address.
+    pub fn from_image(image: &[u8; REG_MAP_LEN]) -> Self {
+        let mut snapshot = Self::default();
+        for (reg, val) in CONFIG_REGS.iter().zip(snapshot.regs.iter_mut()) {
+            *val = image.get(*reg as usize).copied().unwrap_or_default();
+        }
+        snapshot
+    }
+
+    /// Get the data rate saved in the snapshot.
+    pub fn data_rate(&self) -> Odr {
+        let ctrl1 = Ctrl1::from_bits(self.get(Reg::Ctrl1).unwrap_or_default());
+        let ctrl3 = Ctrl3::from_bits(self.get(Reg::Ctrl3).unwrap_or_default());
+        Odr::new(ctrl1.odr(), ctrl3.slp_mode())
+    }
+
+    /// Get the value to write back to `reg` from its saved value.
+    ///
+    /// The self-clearing command bits (`SOFT_RESET`, `BOOT`, and `SLP_MODE_1` with the software trigger
+    /// selected) are never written back.
+    pub(crate) fn restore_value(reg: Reg, val: u8) -> u8 {
+        match reg {
+            Reg::Ctrl2 => Ctrl2::from_bits(val)
+                .with_soft_reset(0)
+                .with_boot(0)
+                .into_bits(),
+            Reg::Ctrl3 => {
+                let ctrl3 = Ctrl3::from_bits(val);
+                let slp_mode = if ctrl3.slp_mode() == 0x03 {
+                    0x02
+                } else {
+                    ctrl3.slp_mode()
+                };
+                ctrl3.with_slp_mode(slp_mode).into_bits()
+            }
+            _ => val,
+        }
+    }
+
+    /// Get the values to write back, in phase order, see [`crate::phase`].
+    pub(crate) fn writes(&self) -> impl Iterator<Item = (Reg, u8)> + '_ {
+        REG_PHASES.into_iter().map(|(reg, _)| {
+            (
+                reg,
+                Self::restore_value(reg, self.get(reg).unwrap_or_default()),
+            )
+        })
+    }
+
+    /// Set the saved content of a register; a register out of [`CONFIG_REGS`] is ignored.
+    pub(crate) fn set(&mut self, reg: Reg, val: u8) {
+        if let Some(slot) = CONFIG_REGS
+            .iter()
+            .position(|r| *r == reg)
+            .and_then(|i| self.regs.get_mut(i))
+        {
+            *slot = val;
+        }
+    }
+
+    /// Compute the register writes moving the device from this configuration to `target`.
+    ///
+    /// The registers whose values to write back differ are written in the phase order of [`crate::phase`]; when the
+    /// operating mode (the `mode` and `lp_mode` fields in the `CTRL1` register), the full scale or the low-noise
+    /// setting (the `fs` and `low_noise` fields in the `CTRL6` register) change while the data rate of this
+    /// configuration is running, `CTRL1` is first written with the `odr` field at power-down. See the
+    /// [module](self) documentation for the resulting sequences.
+    ///
+    /// ### Arguments
+    /// - `target`: The configuration to move to.
+    ///
+    /// ### Returns
+    /// - `ConfigDelta`: The writes, empty if both configurations write back the same values.
+    pub fn diff(&self, target: &ConfigSnapshot) -> ConfigDelta {
+        let value = |snapshot: &ConfigSnapshot, reg: Reg| {
+            Self::restore_value(reg, snapshot.get(reg).unwrap_or_default())
+        };
+        let (ctrl1, target_ctrl1) = (
+            Ctrl1::from_bits(value(self, Reg::Ctrl1)),
+            Ctrl1::from_bits(value(target, Reg::Ctrl1)),
+        );
+        let (ctrl6, target_ctrl6) = (
+            Ctrl6::from_bits(value(self, Reg::Ctrl6)),
+            Ctrl6::from_bits(value(target, Reg::Ctrl6)),
+        );
+        let scaling_changes = ctrl1.mode() != target_ctrl1.mode()
+            || ctrl1.lp_mode() != target_ctrl1.lp_mode()
+            || ctrl6.fs() != target_ctrl6.fs()
+            || ctrl6.low_noise() != target_ctrl6.low_noise();
+
+        let mut delta = ConfigDelta {
+            writes: [(Reg::Ctrl1, 0); DELTA_MAX_WRITES],
+            len: 0,
+            stops_data_rate: ctrl1.odr() != 0 && scaling_changes,
+            source: *self,
+            target: *target,
+        };
+        // Content of CTRL1 once the writes before the target value are done
+        let mut device_ctrl1 = ctrl1;
+        if delta.stops_data_rate {
+            device_ctrl1 = ctrl1.with_odr(0);
+            delta.push(Reg::Ctrl1, device_ctrl1.into_bits());
+        }
+        for (reg, val) in target.writes() {
+            let current = match reg {
+                Reg::Ctrl1 => device_ctrl1.into_bits(),
+                _ => value(self, reg),
+            };
+            if val != current {
+                delta.push(reg, val);
+            }
+        }
+        delta
+    }
+}
+
+/// Largest number of writes of a [`ConfigDelta`]: every register of [`CONFIG_REGS`], and `CTRL1` once more.
+pub const DELTA_MAX_WRITES: usize = CONFIG_REGS.len() + 1;
+
+/// Register writes moving the device between two configurations, see [`ConfigSnapshot::diff`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ConfigDelta {
+    writes: [(Reg, u8); DELTA_MAX_WRITES],
+    len: usize,
+    stops_data_rate: bool,
+    source: ConfigSnapshot,
+    target: ConfigSnapshot,
+}
+
+impl ConfigDelta {
+    /// Get the writes, in order.
+    pub fn writes(&self) -> &[(Reg, u8)] {
+        self.writes.get(..self.len).unwrap_or_default()
+    }
+
+    /// Check whether the two configurations write back the same values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check whether the data rate is set to power-down before the scaling changes.
+    pub fn stops_data_rate(&self) -> bool {
+        self.stops_data_rate
+    }
+
+    /// Get the configuration the writes start from, also the one a failed apply rolls back to.
+    pub fn source(&self) -> &ConfigSnapshot {
+        &self.source
+    }
+
+    /// Get the configuration the writes lead to.
+    pub fn target(&self) -> &ConfigSnapshot {
+        &self.target
+    }
+
+    /// Split the writes into the data rate stop, if any, and the phase writes, see [`crate::phase`].
+    pub(crate) fn phase_writes(&self) -> (Option<u8>, &[(Reg, u8)]) {
+        match self.writes().split_first() {
+            Some(((_, stop), writes)) if self.stops_data_rate => (Some(*stop), writes),
+            _ => (None, self.writes()),
+        }
+    }
+
+    fn push(&mut self, reg: Reg, val: u8) {
+        if let Some(slot) = self.writes.get_mut(self.len) {
+            *slot = (reg, val);
+            self.len += 1;
+        }
+    }
+}
+
+/// Registers involved in a failed configuration apply, in write order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct RegList {
+    regs: [Reg; CONFIG_REGS.len()],
+    len: usize,
+}
+
+impl Default for RegList {
+    fn default() -> Self {
+        Self {
+            regs: CONFIG_REGS,
+            len: 0,
+        }
+    }
+}
+
+impl RegList {
+    /// Get the registers.
+    pub fn as_slice(&self) -> &[Reg] {
+        self.regs.get(..self.len).unwrap_or_default()
+    }
+
+    /// Check whether `reg` is in the list.
+    pub fn contains(&self, reg: Reg) -> bool {
+        self.as_slice().contains(&reg)
+    }
+
+    pub(crate) fn push(&mut self, reg: Reg) {
+        if let Some(slot) = self.regs.get_mut(self.len) {
+            *slot = reg;
+            self.len += 1;
+        }
+    }
+}
+
+/// Failure of a configuration apply, with the outcome of the rollback.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ApplyError<E> {
+    /// The error that stopped the apply.
+    pub error: Error<E>,
+    /// Registers written with their new value before the failure.
+    pub applied: RegList,
+    /// Registers written back to their value before the apply: the one whose write failed, which may have
+    /// taken effect, unless it was applied before, then the applied ones, latest first.
+    pub rolled_back: RegList,
+    /// Registers whose rollback write failed too; their content is unknown.
+    pub rollback_failed: RegList,
+}
+
+impl<E> ApplyError<E> {
+    /// Check that the device holds its configuration from before the apply.
+    pub fn is_rolled_back(&self) -> bool {
+        self.rollback_failed.as_slice().is_empty()
+    }
+}
+
+impl<E> From<ApplyError<E>> for Error<E> {
+    fn from(err: ApplyError<E>) -> Self {
+        err.error
+    }
+}
@@ -0,0 +1,126 @@
+//! SPI 3-wire bus.
+//!
+//! In the 3-wire mode the device sends its data on the SDA line it receives on, instead of SDO,
+//! but only once `sim` is set in `CTRL2`: until then a read returns whatever the idle data line
+//! shows, typically `0xFF`. [`Spi3WireBus`] writes `sim` before the first register read, and
+//! again after a software reset or a reboot of the device, which clears it; the register writes
+//! need no setting, the device receives on SDA in both modes:
+//!
+//! ```ignore
+//! let mut sensor = Iis2dlpc::new_spi_3wire(spi, delay);
+//! // The first read writes CTRL2 first
+//! let whoami = sensor.device_id_get()?;
+//! sensor.init()?;
+//! ```
+//!
+//! The bus performs half-duplex transactions only, a write of the address then a read or a write
+//! of the data, never a full-duplex transfer; the SPI peripheral behind the [`SpiDevice`] must
+//! still be configured for a bidirectional data line, e.g. `BIDIMODE` on STM32.
+//!
+//! Since nothing can be read before `sim` is set, the first write of `CTRL2` is its reset value
+//! with `sim` set: on a device configured before, e.g. across a restart of the host only, it clears
+//! `bdu`, `i2c_disable` and `cs_pu_disc`, which [`crate::Iis2dlpc::init`] and the setters restore.
+//! Every later write of `CTRL2` keeps `sim` set, so that the driver cannot lose the data line,
+//! e.g. through [`crate::Iis2dlpc::spi_mode_set`] or the restore of a register image.
+
+use embedded_hal::spi::{Operation, SpiDevice};
+use st_mems_bus::BusOperation;
+
+use crate::PROPERTY_ENABLE;
+use crate::prelude::*;
+
+/// Read bit of the SPI address byte.
+const SPI_READ: u8 = 0x80;
+
+/// SPI bus of a device wired in the 3-wire mode.
+///
+/// See the [module](self) documentation for the ordering of the `sim` write.
+#[derive(Debug)]
+pub struct Spi3WireBus<P> {
+    spi: P,
+    sim_pending: bool,
+}
+
+impl<P: SpiDevice> Spi3WireBus<P> {
+    /// Wrap the SPI device; `sim` is written before the first register read.
+    ///
+    /// ### Arguments
+    /// - `spi`: The SPI device, its peripheral configured for a bidirectional data line.
+    pub fn new(spi: P) -> Self {
+        Self {
+            spi,
+            sim_pending: true,
+        }
+    }
+
+    /// Write `sim` again before the next register read.
+    ///
+    /// The bus tracks the software reset and the reboot requested through it; call this function
+    /// after a reset it cannot see, e.g. a power cycle of the device.
+    pub fn resync(&mut self) {
+        self.sim_pending = true;
+    }
+
+    /// Give back the SPI device.
+    pub fn release(self) -> P {
+        self.spi
+    }
+
+    /// Write `CTRL2` with its reset value and `sim` set, if a read needs it.
+    fn sim_ensure(&mut self) -> Result<(), P::Error> {
+        if self.sim_pending {
+            let ctrl2 = Ctrl2::new().with_sim(PROPERTY_ENABLE);
+            self.spi.write(&[Reg::Ctrl2 as u8, ctrl2.into_bits()])?;
+            self.sim_pending = false;
+        }
+        Ok(())
+    }
+}
+
+impl<P: SpiDevice> BusOperation for Spi3WireBus<P> {
+    type Error = P::Error;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.sim_ensure()?;
+        self.spi.read(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.spi.write(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.sim_ensure()?;
+        self.spi.transaction(&mut [
+            Operation::Write(&[wbuf[0] | SPI_READ]),
+            Operation::Read(rbuf),
+        ])
+    }
+
+    fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        // The address auto-increments: the byte of CTRL2, if the write spans it
+        let offset = (Reg::Ctrl2 as u8).wrapping_sub(reg) as usize;
+        let Some(&byte) = buf.get(offset) else {
+            return self
+                .spi
+                .transaction(&mut [Operation::Write(&[reg]), Operation::Write(buf)]);
+        };
+        let mut ctrl2 = Ctrl2::from_bits(byte);
+        if ctrl2.soft_reset() == PROPERTY_ENABLE || ctrl2.boot() == PROPERTY_ENABLE {
+            // The device comes back in the 4-wire mode
+            self.sim_pending = true;
+        }
+        ctrl2.set_sim(PROPERTY_ENABLE);
+        let (head, tail) = buf.split_at(offset);
+        self.spi.transaction(&mut [
+            Operation::Write(&[reg]),
+            Operation::Write(head),
+            Operation::Write(&[ctrl2.into_bits()]),
+            Operation::Write(tail.get(1..).unwrap_or_default()),
+        ])
+    }
+}
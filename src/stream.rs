@@ -0,0 +1,212 @@
+//! Pull-based stream of samples and events for async tasks.
+//!
+//! An [`Iis2dlpcStream`] owns the driver and turns the data-ready flag and the event sources
+//! into a single sequence of [`Item`]s:
+//!
+//! ```ignore
+//! loop {
+//!     match stream.next().await? {
+//!         Item::Sample(raw) => { /* .. */ }
+//!         Item::Event(event) => { /* .. */ }
+//!     }
+//! }
+//! ```
+//!
+//! The stream is built on the async driver, [`Iis2dlpcAsync`]: between two reads it either waits
+//! for an interrupt pin or sleeps on a timer paced from the configured data rate. The events go
+//! through the drain of [`Iis2dlpcAsync::event_get`], with its interrupt storm guard and its queue
+//! of pending events, and are returned before the sample flagged by the same burst read.
+
+use core::convert::Infallible;
+
+use embedded_hal::digital::ErrorType;
+use embedded_hal_async::delay::DelayNs as AsyncDelayNs;
+use embedded_hal_async::digital::Wait;
+
+use crate::Error;
+use crate::asynch::{AsyncBusOperation, Iis2dlpcAsync};
+use crate::event::Event;
+
+/// Polling interval when the data rate has no period (power-down or on-demand conversion), in microseconds.
+pub const IDLE_POLL_US: u32 = 10_000;
+
+/// Item produced by an [`Iis2dlpcStream`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Item {
+    /// Raw acceleration on the X, Y and Z axes.
+    Sample([i16; 3]),
+    /// Decoded event.
+    Event(Event),
+}
+
+/// How an [`Iis2dlpcStream`] waits when nothing is pending.
+#[derive(Debug)]
+pub enum Pacing<P, D> {
+    /// Wait for an interrupt pin to go high; the data-ready and event signals must be routed to it.
+    Pin(P),
+    /// Poll twice per output data rate period.
+    Timer(D),
+}
+
+/// Placeholder pin of a stream paced by a timer.
+#[derive(Debug)]
+pub enum NoPin {}
+
+impl ErrorType for NoPin {
+    type Error = Infallible;
+}
+
+impl Wait for NoPin {
+    async fn wait_for_high(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Infallible> {
+        match *self {}
+    }
+}
+
+/// Placeholder timer of a stream paced by an interrupt pin.
+#[derive(Debug)]
+pub enum NoDelay {}
+
+impl AsyncDelayNs for NoDelay {
+    async fn delay_ns(&mut self, _ns: u32) {
+        match *self {}
+    }
+}
+
+/// Stream of samples and events.
+#[derive(Debug)]
+pub struct Iis2dlpcStream<B, T, P, D> {
+    sensor: Iis2dlpcAsync<B, T>,
+    pacing: Pacing<P, D>,
+    poll_us: u32,
+    sample_ready: bool,
+}
+
+impl<B: AsyncBusOperation, T, P: Wait<Error = Infallible>> Iis2dlpcStream<B, T, P, NoDelay> {
+    /// Create a stream that waits for an interrupt pin.
+    ///
+    /// ### Arguments
+    /// - `sensor`: The configured driver.
+    /// - `pin`: The interrupt pin, for example an EXTI input.
+    ///
+    /// ### Returns
+    /// - `Ok(Iis2dlpcStream)`: The stream.
+    /// - `Err(Error::UnexpectedValue)`: If the data rate holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn with_pin(sensor: Iis2dlpcAsync<B, T>, pin: P) -> Result<Self, Error<B::Error>> {
+        Self::new(sensor, Pacing::Pin(pin)).await
+    }
+}
+
+impl<B: AsyncBusOperation, T, D: AsyncDelayNs> Iis2dlpcStream<B, T, NoPin, D> {
+    /// Create a stream that polls the device on a timer.
+    ///
+    /// ### Arguments
+    /// - `sensor`: The configured driver.
+    /// - `delay`: The async timer.
+    ///
+    /// ### Returns
+    /// - `Ok(Iis2dlpcStream)`: The stream.
+    /// - `Err(Error::UnexpectedValue)`: If the data rate holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn polling(sensor: Iis2dlpcAsync<B, T>, delay: D) -> Result<Self, Error<B::Error>> {
+        Self::new(sensor, Pacing::Timer(delay)).await
+    }
+}
+
+impl<B, T, P, D> Iis2dlpcStream<B, T, P, D>
+where
+    B: AsyncBusOperation,
+    P: Wait<Error = Infallible>,
+    D: AsyncDelayNs,
+{
+    /// Create a stream.
+    ///
+    /// The data rate is read once: reconfigure the device through [`Self::release`] and create a new stream.
+    ///
+    /// ### Arguments
+    /// - `sensor`: The configured driver.
+    /// - `pacing`: How the stream waits when nothing is pending.
+    ///
+    /// ### Returns
+    /// - `Ok(Iis2dlpcStream)`: The stream.
+    /// - `Err(Error::UnexpectedValue)`: If the data rate holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read operation.
+    pub async fn new(
+        mut sensor: Iis2dlpcAsync<B, T>,
+        pacing: Pacing<P, D>,
+    ) -> Result<Self, Error<B::Error>> {
+        let poll_us = sensor
+            .data_rate_get()
+            .await?
+            .period_us()
+            .map_or(IDLE_POLL_US, |us| us.div_ceil(2));
+        Ok(Self {
+            sensor,
+            pacing,
+            poll_us,
+            sample_ready: false,
+        })
+    }
+
+    /// Release the driver and the pacing resource.
+    ///
+    /// The events of the last read not returned yet stay pending in the driver, see
+    /// [`Iis2dlpcAsync::event_get`].
+    pub fn release(self) -> (Iis2dlpcAsync<B, T>, Pacing<P, D>) {
+        (self.sensor, self.pacing)
+    }
+
+    /// Get the next sample or event.
+    ///
+    /// This function returns the pending events of [`Iis2dlpcAsync::event_get`] first. Once they are all
+    /// returned, it reads `STATUS_DUP` .. `ALL_INT_SRC` in a single burst through the same drain, storm guard
+    /// included, returns the decoded events, then the sample if the data-ready flag of that read was set, and
+    /// waits when nothing is pending. Samples and events alike are in the board frame, see
+    /// [`Iis2dlpc::axis_remap_set`](crate::Iis2dlpc::axis_remap_set).
+    ///
+    /// ### Returns
+    /// - `Ok(Item)`: The next item.
+    /// - `Err(Error::InvalidConfig)`: If a sample is ready while the FIFO path is active, see
+    ///   [`Iis2dlpc::allow_mixed_data_paths`](crate::Iis2dlpc::allow_mixed_data_paths).
+    /// - `Err(Error::UnexpectedValue)`: If the tap axis priority holds a reserved code.
+    /// - `Err(Error::Bus)`: If there is an error at the bus level during the read or write operation.
+    pub async fn next(&mut self) -> Result<Item, Error<B::Error>> {
+        loop {
+            if let Some(event) = self.sensor.event_pending_take() {
+                return Ok(Item::Event(event));
+            }
+            if self.sample_ready {
+                self.sample_ready = false;
+                return Ok(Item::Sample(self.sensor.acceleration_raw_get().await?));
+            }
+
+            let (queued, sources) = self.sensor.events_enqueue().await?;
+            self.sample_ready = sources.status_dup.drdy() == 1;
+            if self.sample_ready || queued > 0 {
+                continue;
+            }
+            match &mut self.pacing {
+                Pacing::Pin(pin) => {
+                    let Ok(()) = pin.wait_for_high().await;
+                }
+                Pacing::Timer(delay) => delay.delay_us(self.poll_us).await,
+            }
+        }
+    }
+}
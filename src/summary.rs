@@ -0,0 +1,266 @@
+//! Configuration summary for support tooling.
+//!
+//! [`crate::Iis2dlpc::sensor_state_get`] answers "how is this sensor configured right now" with
+//! three reads instead of a getter per setting: a burst of `CTRL1` .. `CTRL6`, a burst of
+//! `FIFO_CTRL` .. `FREE_FALL` and a read of `CTRL7`. The [`SensorState`] decodes them with the
+//! same rules as the individual getters, and prints them one setting per line:
+//!
+//! ```text
+//! mode: HighPerformance
+//! data rate: _100hz
+//! full scale: ±4 g
+//! ...
+//! ```
+
+use core::fmt;
+
+use crate::prelude::*;
+#[cfg(feature = "freefall")]
+use crate::timing::{FfDuration, FreeFallTiming};
+
+/// Number of bytes of the `CTRL1` .. `CTRL6` burst.
+pub(crate) const CTRL_LEN: usize = 6;
+
+/// Number of bytes of the `FIFO_CTRL` .. `FREE_FALL` burst.
+pub(crate) const FUNC_LEN: usize = 9;
+
+/// Configuration of the sensor, see [`crate::Iis2dlpc::sensor_state_get`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SensorState {
+    /// Operating mode, see [`crate::Iis2dlpc::power_mode_get`].
+    pub mode: Mode,
+    /// Output data rate, see [`crate::Iis2dlpc::data_rate_get`].
+    pub odr: Odr,
+    /// Full scale.
+    pub fs: Fs,
+    /// Block data update.
+    pub bdu: bool,
+    /// Data sent to the output registers and the FIFO, see [`crate::Iis2dlpc::filter_path_get`].
+    pub filter_path: Fds,
+    /// Filter bandwidth.
+    pub bandwidth: BwFilt,
+    /// Low-noise configuration; also part of [`Self::mode`].
+    pub low_noise: bool,
+    /// FIFO mode.
+    #[cfg(feature = "fifo")]
+    pub fifo_mode: Fmode,
+    /// FIFO watermark, in samples.
+    #[cfg(feature = "fifo")]
+    pub fifo_watermark: u8,
+    /// Wake-up threshold, 1 LSB = FS / 64.
+    #[cfg(feature = "wakeup")]
+    pub wkup_threshold: u8,
+    /// Wake-up duration, 1 LSB = 1 / ODR.
+    #[cfg(feature = "wakeup")]
+    pub wkup_dur: u8,
+    /// Duration before going to sleep, 1 LSB = 512 / ODR.
+    #[cfg(feature = "wakeup")]
+    pub sleep_dur: u8,
+    /// Activity/inactivity or stationary/motion detection, see [`crate::Iis2dlpc::act_mode_get`].
+    #[cfg(feature = "wakeup")]
+    pub act_mode: SleepOn,
+    /// Free-fall duration, with the data rate attached, and threshold.
+    #[cfg(feature = "freefall")]
+    pub free_fall: FreeFallTiming,
+    /// Tap detection enabled on the X, Y and Z axes.
+    #[cfg(feature = "tap")]
+    pub tap_axes: [bool; 3],
+    /// Single or single and double tap.
+    #[cfg(feature = "tap")]
+    pub tap_mode: SingleDoubleTap,
+    /// Signals routed on the INT1 pad.
+    #[cfg(feature = "events")]
+    pub int1_routes: Ctrl4Int1PadCtrl,
+    /// Signals routed on the INT2 pad.
+    #[cfg(feature = "events")]
+    pub int2_routes: Ctrl5Int2PadCtrl,
+    /// Interrupt pads enabled, the `interrupts_enable` field in the `CTRL7` register.
+    #[cfg(feature = "events")]
+    pub interrupts_enable: bool,
+    /// Polarity of the interrupt pads.
+    #[cfg(feature = "events")]
+    pub polarity: HLactive,
+    /// Latched or pulsed interrupts.
+    #[cfg(feature = "events")]
+    pub latch: Lir,
+    /// Push-pull or open-drain pads.
+    #[cfg(feature = "events")]
+    pub pin_mode: PpOd,
+}
+
+impl SensorState {
+    /// Decode the register contents.
+    pub(crate) fn new(ctrl: [u8; CTRL_LEN], func: [u8; FUNC_LEN], ctrl7: u8) -> Self {
+        // The bytes of the functions left out of the build are not decoded
+        #[allow(unused_variables)]
+        let [c1, c2, c3, c4, c5, c6] = ctrl;
+        #[allow(unused_variables)]
+        let [
+            fifo_ctrl,
+            _fifo_samples,
+            _tap_ths_x,
+            _tap_ths_y,
+            tap_ths_z,
+            _int_dur,
+            wu_ths,
+            wu_dur,
+            ff,
+        ] = func;
+        let (ctrl1, ctrl2, ctrl3) = (
+            Ctrl1::from_bits(c1),
+            Ctrl2::from_bits(c2),
+            Ctrl3::from_bits(c3),
+        );
+        let ctrl6 = Ctrl6::from_bits(c6);
+        let ctrl7 = Ctrl7::from_bits(ctrl7);
+        #[cfg(feature = "fifo")]
+        let fifo_ctrl = FifoCtrl::from_bits(fifo_ctrl);
+        #[cfg(feature = "tap")]
+        let tap_ths_z = TapThsZ::from_bits(tap_ths_z);
+        #[cfg(any(feature = "wakeup", feature = "tap"))]
+        let wake_up_ths = WakeUpThs::from_bits(wu_ths);
+        #[cfg(any(feature = "wakeup", feature = "freefall"))]
+        let wake_up_dur = WakeUpDur::from_bits(wu_dur);
+        #[cfg(feature = "freefall")]
+        let free_fall = FreeFall::from_bits(ff);
+        let odr = Odr::new(ctrl1.odr(), ctrl3.slp_mode());
+
+        Self {
+            mode: Mode::try_new(ctrl1.mode(), ctrl1.lp_mode(), ctrl6.low_noise())
+                .unwrap_or_default(),
+            odr,
+            fs: Fs::try_from(ctrl6.fs()).unwrap_or_default(),
+            bdu: ctrl2.bdu() != 0,
+            filter_path: Fds::new(ctrl6.fds(), ctrl7.usr_off_on_out()),
+            bandwidth: BwFilt::try_from(ctrl6.bw_filt()).unwrap_or_default(),
+            low_noise: ctrl6.low_noise() != 0,
+            #[cfg(feature = "fifo")]
+            fifo_mode: Fmode::try_from(fifo_ctrl.fmode()).unwrap_or_default(),
+            #[cfg(feature = "fifo")]
+            fifo_watermark: fifo_ctrl.fth(),
+            #[cfg(feature = "wakeup")]
+            wkup_threshold: wake_up_ths.wk_ths(),
+            #[cfg(feature = "wakeup")]
+            wkup_dur: wake_up_dur.wake_dur(),
+            #[cfg(feature = "wakeup")]
+            sleep_dur: wake_up_dur.sleep_dur(),
+            #[cfg(feature = "wakeup")]
+            act_mode: SleepOn::new(wake_up_ths.sleep_on(), wake_up_dur.stationary()),
+            #[cfg(feature = "freefall")]
+            free_fall: FreeFallTiming {
+                duration: FfDuration::from_lsb((wake_up_dur.ff_dur() << 5) + free_fall.ff_dur())
+                    .unwrap_or_default()
+                    .with_odr(odr),
+                threshold: FfThs::try_from(free_fall.ff_ths()).unwrap_or_default(),
+            },
+            #[cfg(feature = "tap")]
+            tap_axes: [
+                tap_ths_z.tap_x_en() != 0,
+                tap_ths_z.tap_y_en() != 0,
+                tap_ths_z.tap_z_en() != 0,
+            ],
+            #[cfg(feature = "tap")]
+            tap_mode: SingleDoubleTap::try_from(wake_up_ths.single_double_tap())
+                .unwrap_or_default(),
+            #[cfg(feature = "events")]
+            int1_routes: Ctrl4Int1PadCtrl::from_bits(c4),
+            #[cfg(feature = "events")]
+            int2_routes: Ctrl5Int2PadCtrl::from_bits(c5),
+            #[cfg(feature = "events")]
+            interrupts_enable: ctrl7.interrupts_enable() != 0,
+            #[cfg(feature = "events")]
+            polarity: HLactive::try_from(ctrl3.h_lactive()).unwrap_or_default(),
+            #[cfg(feature = "events")]
+            latch: Lir::try_from(ctrl3.lir()).unwrap_or_default(),
+            #[cfg(feature = "events")]
+            pin_mode: PpOd::try_from(ctrl3.pp_od()).unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for SensorState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let on = |flag: bool| if flag { "on" } else { "off" };
+        writeln!(f, "mode: {:?}", self.mode)?;
+        writeln!(f, "data rate: {:?}", self.odr)?;
+        writeln!(f, "full scale: ±{} g", self.fs.to_g())?;
+        writeln!(f, "block data update: {}", on(self.bdu))?;
+        // The lines of the optional functions start with their own line break, so that the last line never ends
+        // with one whatever the features
+        write!(
+            f,
+            "filter: {:?}, bandwidth {:?}, low noise {}",
+            self.filter_path,
+            self.bandwidth,
+            on(self.low_noise)
+        )?;
+        #[cfg(feature = "fifo")]
+        write!(
+            f,
+            "\nfifo: {:?}, watermark {}",
+            self.fifo_mode, self.fifo_watermark
+        )?;
+        #[cfg(feature = "wakeup")]
+        {
+            write!(
+                f,
+                "\nwake-up: threshold {} LSB = {} mg, duration {} LSB",
+                self.wkup_threshold,
+                self.wkup_threshold as f32 * self.fs.wkup_threshold_mg_per_lsb(),
+                self.wkup_dur
+            )?;
+            write!(
+                f,
+                "\nsleep: {:?}, duration {} LSB",
+                self.act_mode, self.sleep_dur
+            )?;
+        }
+        #[cfg(feature = "freefall")]
+        write!(
+            f,
+            "\nfree-fall: threshold {:?}, duration {}",
+            self.free_fall.threshold, self.free_fall.duration
+        )?;
+        #[cfg(feature = "tap")]
+        {
+            let [x, y, z] = self.tap_axes;
+            write!(
+                f,
+                "\ntap: x {}, y {}, z {}, {:?}",
+                on(x),
+                on(y),
+                on(z),
+                self.tap_mode
+            )?;
+        }
+        #[cfg(feature = "events")]
+        {
+            write!(
+                f,
+                "\nINT1: {}",
+                Reg::decode(Reg::Ctrl4Int1PadCtrl as u8, self.int1_routes.into_bits())
+            )?;
+            write!(
+                f,
+                "\nINT2: {}",
+                Reg::decode(Reg::Ctrl5Int2PadCtrl as u8, self.int2_routes.into_bits())
+            )?;
+            write!(
+                f,
+                "\npads: {}, {:?}, {:?}, {:?}",
+                on(self.interrupts_enable),
+                self.polarity,
+                self.latch,
+                self.pin_mode
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SensorState {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", defmt::Display2Format(self));
+    }
+}
@@ -0,0 +1,77 @@
+//! Tap detection configuration in a single call.
+//!
+//! [`TapConfig`] gathers the tap settings that the individual setters split over `TAP_THS_X`,
+//! `TAP_THS_Y`, `TAP_THS_Z`, `INT_DUR` and `WAKE_UP_THS`; [`crate::Iis2dlpc::tap_config_set`]
+//! writes each register once:
+//!
+//! ```ignore
+//! sensor.tap_config_set(
+//!     &TapConfig {
+//!         axes: [true; 3],
+//!         shock: 3,
+//!         quiet: 3,
+//!         latency: 7,
+//!         mode: SingleDoubleTap::BothSingleDouble,
+//!         ..Default::default()
+//!     }
+//!     .with_thresholds_mg([375.0; 3], Fs::_2g),
+//! )?;
+//! ```
+//!
+//! The durations are codes counted in ODR periods, see [`crate::timing::DurationField`].
+
+use crate::prelude::*;
+
+/// Highest code of the 5-bit tap thresholds.
+const THRESHOLD_MAX: u8 = 0x1F;
+
+/// Tap detection settings written by [`crate::Iis2dlpc::tap_config_set`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct TapConfig {
+    /// Thresholds of the X, Y and Z axes, `tap_thsx`, `tap_thsy` and `tap_thsz`: 1 LSB = FS / 32.
+    pub thresholds: [u8; 3],
+    /// Tap detection on the X, Y and Z axes, `tap_x_en`, `tap_y_en` and `tap_z_en` in `TAP_THS_Z`.
+    pub axes: [bool; 3],
+    /// Maximum duration of an over-threshold signal, `shock` in `INT_DUR`: 0 = 4 / ODR, otherwise
+    /// 1 LSB = 8 / ODR.
+    pub shock: u8,
+    /// Quiet time after a tap, `quiet` in `INT_DUR`: 0 = 2 / ODR, otherwise 1 LSB = 4 / ODR.
+    pub quiet: u8,
+    /// Maximum time between the taps of a double tap, `latency` in `INT_DUR`: 0 = 16 / ODR,
+    /// otherwise 1 LSB = 32 / ODR.
+    pub latency: u8,
+    /// Axis priority, `tap_prior` in `TAP_THS_Y`.
+    pub priority: TapPrior,
+    /// Single tap only, or single and double tap, `single_double_tap` in `WAKE_UP_THS`.
+    pub mode: SingleDoubleTap,
+}
+
+impl TapConfig {
+    /// Set the thresholds in mg for a full scale.
+    ///
+    /// Each threshold becomes the nearest code, 1 LSB = FS / 32, clamped to the 5-bit field: a
+    /// request above 31 / 32 of the full scale gives code 31, a negative one or not a number
+    /// gives code 0. See [`Self::thresholds_mg`] for the thresholds actually programmed.
+    ///
+    /// ### Arguments
+    /// - `mg`: The thresholds of the X, Y and Z axes in mg.
+    /// - `fs`: The full scale the sensor runs at.
+    pub fn with_thresholds_mg(mut self, mg: [f32; 3], fs: Fs) -> Self {
+        let mg_per_lsb = fs.tap_threshold_mg_per_lsb();
+        // Nearest code; the cast saturates a negative request and not a number to 0
+        self.thresholds = mg.map(|mg| ((mg / mg_per_lsb + 0.5) as u8).min(THRESHOLD_MAX));
+        self
+    }
+
+    /// Get the thresholds in mg for a full scale.
+    ///
+    /// ### Arguments
+    /// - `fs`: The full scale the sensor runs at.
+    ///
+    /// ### Returns
+    /// - `[f32; 3]`: The thresholds of the X, Y and Z axes in mg.
+    pub fn thresholds_mg(&self, fs: Fs) -> [f32; 3] {
+        let mg_per_lsb = fs.tap_threshold_mg_per_lsb();
+        self.thresholds.map(|ths| ths as f32 * mg_per_lsb)
+    }
+}
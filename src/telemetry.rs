@@ -0,0 +1,108 @@
+//! Compact binary telemetry frame.
+//!
+//! [`crate::Iis2dlpc::telemetry_frame`] packs the event sources and an acceleration sample in
+//! [`FRAME_LEN`] bytes, ready for a low-bandwidth radio link. [`telemetry_decode`] is the
+//! receiver-side counterpart and does not need a bus.
+//!
+//! Layout (version 1):
+//!
+//! | Byte    | Content                                                               |
+//! |---------|-----------------------------------------------------------------------|
+//! | 0       | `STATUS_DUP`                                                          |
+//! | 1       | `WAKE_UP_SRC`                                                         |
+//! | 2       | `TAP_SRC`                                                             |
+//! | 3       | `SIXD_SRC`                                                            |
+//! | 4       | `ALL_INT_SRC`                                                         |
+//! | 5..=10  | `OUT_X_L`, `OUT_X_H`, `OUT_Y_L`, `OUT_Y_H`, `OUT_Z_L`, `OUT_Z_H`       |
+//! | 11      | Tag: bits 7..4 version, bits 1..0 full scale, bit 2 low-power mode 1  |
+
+use crate::batch::{SAMPLE_BYTES, raw_from_bytes, raw_to_mg};
+use crate::prelude::*;
+
+/// Length of a telemetry frame, in bytes.
+pub const FRAME_LEN: usize = 12;
+
+/// Layout version written in the tag byte.
+pub const FRAME_VERSION: u8 = 1;
+
+/// Reserved bits of the tag byte.
+pub const TAG_RESERVED: u8 = 0x08;
+
+/// Number of source register bytes at the start of the frame.
+pub(crate) const SOURCES_LEN: usize = AllSources::BYTES;
+
+/// Telemetry decoding errors.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TelemetryError {
+    /// The buffer is shorter than [`FRAME_LEN`]; holds the actual length.
+    Length(usize),
+    /// The frame was encoded with an unknown layout version.
+    Version(u8),
+}
+
+/// Decoded telemetry frame.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TelemetryFrame {
+    /// Event sources.
+    pub sources: AllSources,
+    /// Raw acceleration on the X, Y and Z axes.
+    pub raw: [i16; 3],
+    /// Full scale of the sample.
+    pub fs: Fs,
+    /// `true` if the sample was acquired in low-power mode 1 (12-bit resolution).
+    pub low_power_1: bool,
+    /// Raw tag byte.
+    pub tag: u8,
+}
+
+impl TelemetryFrame {
+    /// Get the acceleration in mg.
+    pub fn mg(&self) -> [f32; 3] {
+        self.raw
+            .map(|lsb| raw_to_mg(lsb, self.fs, self.low_power_1))
+    }
+
+    /// Check that no reserved bit is set in the source registers and in the tag.
+    ///
+    /// ### Returns
+    /// - `true` if every set bit has a known meaning; otherwise the raw bytes are still available.
+    pub fn is_fully_decoded(&self) -> bool {
+        self.sources.is_fully_decoded() && self.tag & TAG_RESERVED == 0
+    }
+}
+
+/// Build the tag byte of a frame.
+pub(crate) fn tag(fs: Fs, mode: Mode) -> u8 {
+    (FRAME_VERSION << 4) | ((mode.is_low_power_1() as u8) << 2) | fs as u8
+}
+
+/// Decode a telemetry frame.
+///
+/// ### Arguments
+/// - `buf`: The received bytes; only the first [`FRAME_LEN`] are used.
+///
+/// ### Returns
+/// - `Ok(TelemetryFrame)`: The decoded frame.
+/// - `Err(TelemetryError)`: If the buffer is too short or the version is unknown.
+pub fn telemetry_decode(buf: &[u8]) -> Result<TelemetryFrame, TelemetryError> {
+    let frame: &[u8; FRAME_LEN] = buf
+        .get(..FRAME_LEN)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(TelemetryError::Length(buf.len()))?;
+
+    let tag = frame[FRAME_LEN - 1];
+    if tag >> 4 != FRAME_VERSION {
+        return Err(TelemetryError::Version(tag >> 4));
+    }
+
+    let mut sample = [0; SAMPLE_BYTES];
+    sample.copy_from_slice(&frame[SOURCES_LEN..SOURCES_LEN + SAMPLE_BYTES]);
+
+    Ok(TelemetryFrame {
+        sources: AllSources::from_bytes([frame[0], frame[1], frame[2], frame[3], frame[4]]),
+        raw: raw_from_bytes(&sample),
+        fs: Fs::try_from(tag & 0x03).unwrap_or_default(),
+        low_power_1: tag & 0x04 != 0,
+        tag,
+    })
+}
@@ -0,0 +1,235 @@
+use super::sensor;
+use crate::batch::{MisalignedBatch, SampleBatch};
+use crate::fifo::DataPath;
+use crate::mock::Access;
+use crate::prelude::*;
+use crate::remap::{AxisRemap, SignedAxis};
+use crate::{ConfigError, Error};
+
+/// Output registers holding X = 0x0123, Y = -2 and Z = 0x1FFF, in 14-bit left-aligned form.
+fn outputs() -> [(Reg, u8); 6] {
+    [
+        (Reg::OutXL, 0x8C),
+        (Reg::OutXH, 0x04),
+        (Reg::OutYL, 0xF8),
+        (Reg::OutYH, 0xFF),
+        (Reg::OutZL, 0xFC),
+        (Reg::OutZH, 0x7F),
+    ]
+}
+
+const SAMPLE: [i16; 3] = [0x0123, -2, 0x1FFF];
+
+#[test]
+fn unchecked_read_is_a_single_burst() {
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor.bus.cost_reset();
+    sensor.bus.expect(&[Access::read(Reg::OutXL)]);
+    assert_eq!(sensor.acceleration_raw_unchecked_get(), Ok(SAMPLE));
+    // STATUS is never read
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.bus.transactions(), 1);
+}
+
+#[test]
+fn unchecked_read_skips_the_data_path_check() {
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    assert_eq!(
+        sensor.acceleration_raw_get(),
+        Err(Error::InvalidConfig(ConfigError::FifoPathActive))
+    );
+    sensor.bus.expect(&[Access::read(Reg::OutXL)]);
+    assert_eq!(sensor.acceleration_raw_unchecked_get(), Ok(SAMPLE));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn unchecked_read_is_remapped() {
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    let [x, y, z] = SAMPLE;
+    sensor.axis_remap_set(
+        AxisRemap::new([SignedAxis::PosY, SignedAxis::NegX, SignedAxis::PosZ]).unwrap(),
+    );
+    assert_eq!(sensor.acceleration_raw_unchecked_get(), Ok([y, -x, z]));
+    assert_eq!(sensor.acceleration_raw_get(), Ok([y, -x, z]));
+}
+
+#[test]
+fn batch_over_a_burst_decodes_as_the_driver() {
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor.full_scale_set(Fs::_8g).unwrap();
+    sensor.power_mode_set(Mode::ContLowPwr12bit).unwrap();
+
+    // Two bursts of the same registers, as a DMA transfer would leave them
+    let mut buf = [0; 12];
+    for half in buf.chunks_exact_mut(6) {
+        sensor.read_from_register(Reg::OutXL as u8, half).unwrap();
+    }
+    let batch = SampleBatch::new(&buf).unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch.iter().len(), 2);
+    assert_eq!(batch.raw(1), Some(sensor.acceleration_raw_get().unwrap()));
+    assert_eq!(batch.raw(2), None);
+    assert_eq!(
+        batch.mg(0, Fs::_8g, Mode::ContLowPwr12bit),
+        Some(sensor.acceleration_mg_get().unwrap())
+    );
+}
+
+#[test]
+fn batch_of_partial_samples_is_refused() {
+    let buf = [0; 13];
+    assert_eq!(
+        SampleBatch::new(&buf).err(),
+        Some(MisalignedBatch { len: 13 })
+    );
+    let empty = SampleBatch::new(&buf[..0]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.into_iter().next(), None);
+}
+
+#[cfg(feature = "uom")]
+#[test]
+fn typed_quantities_use_the_standard_gravity() {
+    use crate::sample::Sample;
+    use uom::si::acceleration::meter_per_second_squared;
+    use uom::si::thermodynamic_temperature::kelvin;
+
+    let sample = Sample {
+        acceleration_mg: [1000.0, -500.0, 0.0],
+        temperature_celsius: 25.0,
+    };
+    let m_s2 = sample
+        .acceleration()
+        .map(|a| a.get::<meter_per_second_squared>());
+    assert_eq!(m_s2, [9.80665, -4.903325, 0.0]);
+    assert!((sample.temperature().get::<kelvin>() - 298.15).abs() < 1e-3);
+
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    let mg = sensor.acceleration_mg_get().unwrap();
+    let si = sensor.acceleration_si_get().unwrap();
+    for (si, mg) in si.into_iter().zip(mg) {
+        assert_eq!(si.get::<meter_per_second_squared>(), mg * 0.009_806_65);
+    }
+}
+
+#[test]
+fn collecting_fifo_guards_the_direct_reads() {
+    let mut sensor = sensor();
+    assert_eq!(sensor.data_path_get(), DataPath::Direct);
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    assert_eq!(sensor.data_path_get(), DataPath::Fifo);
+
+    let guarded = Err(Error::InvalidConfig(ConfigError::FifoPathActive));
+    sensor.bus.expect(&[]);
+    assert_eq!(sensor.acceleration_raw_get(), guarded);
+    assert_eq!(sensor.acceleration_raw_sensor_get(), guarded);
+    assert!(sensor.acceleration_mg_get().is_err());
+    assert!(sensor.sample_get().is_err());
+    #[cfg(feature = "telemetry")]
+    assert_eq!(
+        sensor.telemetry_frame(&mut [0; crate::telemetry::FRAME_LEN]),
+        Err(Error::InvalidConfig(ConfigError::FifoPathActive))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // Back to bypass, the output registers hold the latest sample again
+    sensor.fifo_mode_set(Fmode::BypassMode).unwrap();
+    assert_eq!(sensor.data_path_get(), DataPath::Direct);
+    assert!(sensor.acceleration_raw_get().is_ok());
+}
+
+#[cfg(feature = "fifo")]
+#[test]
+fn path_follows_every_write_of_the_fifo_mode() {
+    let mut sensor = sensor();
+    // A raw write of FIFO_CTRL
+    let fifo = FifoCtrl::new().with_fmode(Fmode::StreamToFifoMode as u8);
+    sensor
+        .write_to_register(Reg::FifoCtrl as u8, &[fifo.into_bits()])
+        .unwrap();
+    assert_eq!(sensor.data_path_get(), DataPath::Fifo);
+
+    // The watermark shares the register and keeps the mode
+    sensor.fifo_watermark_set(16).unwrap();
+    assert_eq!(sensor.data_path_get(), DataPath::Fifo);
+
+    // A software reset puts the FIFO in bypass mode
+    sensor.reset_set().unwrap();
+    assert_eq!(sensor.data_path_get(), DataPath::Direct);
+}
+
+#[test]
+fn mixed_paths_can_be_allowed() {
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.allow_mixed_data_paths(true);
+    assert_eq!(sensor.acceleration_raw_get(), Ok(SAMPLE));
+
+    sensor.allow_mixed_data_paths(false);
+    assert_eq!(
+        sensor.acceleration_raw_get(),
+        Err(Error::InvalidConfig(ConfigError::FifoPathActive))
+    );
+}
+
+#[cfg(feature = "fifo")]
+#[test]
+fn direct_read_on_the_fifo_path_duplicates_the_drained_sample() {
+    let mut sensor = sensor();
+    for (reg, val) in outputs() {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.bus.set_reg(Reg::FifoSamples, 2);
+    sensor.allow_mixed_data_paths(true);
+
+    // The "latest" sample is the oldest FIFO sample, which the drain returns once more
+    let latest = sensor.acceleration_raw_get().unwrap();
+    let mut history = [[0; 3]; 4];
+    assert_eq!(sensor.fifo_data_get(&mut history), Ok(2));
+    assert_eq!(history[0], latest);
+}
+
+#[test]
+fn outputs_are_assembled_low_byte_first() {
+    let mut sensor = sensor();
+    // X = -2, Y = 8191, Z = -8192 and T = 0x013, as the bus delivers them
+    for (reg, val) in [
+        (Reg::OutXL, 0xF8),
+        (Reg::OutXH, 0xFF),
+        (Reg::OutYL, 0xFC),
+        (Reg::OutYH, 0x7F),
+        (Reg::OutZL, 0x00),
+        (Reg::OutZH, 0x80),
+        (Reg::OutTL, 0x30),
+        (Reg::OutTH, 0x01),
+    ] {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::OutXL), Access::read(Reg::OutTL)]);
+    assert_eq!(sensor.acceleration_raw_get(), Ok([-2, 8191, -8192]));
+    assert_eq!(sensor.temperature_raw_get(), Ok(0x013));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
@@ -0,0 +1,122 @@
+use super::{bit, sensor};
+use crate::adaptive::{AdaptiveRate, AdaptiveRateConfig};
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::{ConfigError, Error, Iis2dlpc};
+
+const CONFIG: AdaptiveRateConfig = AdaptiveRateConfig {
+    active_odr: Odr::_400hz,
+    inactive_odr: Odr::_12_5hz,
+    wake_threshold: 2,
+    sleep_dur: 1,
+    hysteresis: 3,
+};
+
+/// Driver in high-performance mode and a started helper.
+fn started(config: AdaptiveRateConfig) -> (Iis2dlpc<MockBus, NoDelay>, AdaptiveRate) {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    let mut adaptive = AdaptiveRate::new(config);
+    adaptive.start(&mut sensor).unwrap();
+    (sensor, adaptive)
+}
+
+/// Set the sleep state the device reports in `STATUS_DUP`.
+fn still(sensor: &mut Iis2dlpc<MockBus, NoDelay>, sleeping: bool) {
+    let sleep_state = bit(|b| StatusDup::from_bits(b).sleep_state_ia());
+    let val = if sleeping { sleep_state } else { 0 };
+    sensor.bus.set_reg(Reg::StatusDup, val);
+}
+
+#[test]
+fn start_configures_the_motion_detection() {
+    let (mut sensor, adaptive) = started(CONFIG);
+    assert_eq!(sensor.act_mode_get(), Ok(SleepOn::StatMotion));
+    assert_eq!(sensor.wkup_threshold_get(), Ok(2));
+    assert_eq!(sensor.act_sleep_dur_get(), Ok(1));
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::_400hz));
+    assert_eq!(adaptive.current_odr(), Odr::_400hz);
+}
+
+#[test]
+fn start_rejects_an_invalid_configuration() {
+    let mut sensor = sensor();
+    sensor.bus.expect(&[]);
+    let config = AdaptiveRateConfig {
+        inactive_odr: Odr::Off,
+        ..CONFIG
+    };
+    assert_eq!(
+        AdaptiveRate::new(config).start(&mut sensor),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    let config = AdaptiveRateConfig {
+        wake_threshold: 64,
+        ..CONFIG
+    };
+    assert_eq!(
+        AdaptiveRate::new(config).start(&mut sensor),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn still_state_switches_after_the_hysteresis() {
+    let (mut sensor, mut adaptive) = started(CONFIG);
+    still(&mut sensor, true);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::StatusDup), Access::read(Reg::StatusDup)]);
+    assert_eq!(adaptive.service(&mut sensor), Ok(None));
+    assert_eq!(adaptive.service(&mut sensor), Ok(None));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    let change = adaptive.service(&mut sensor).unwrap().unwrap();
+    assert_eq!((change.from, change.to), (Odr::_400hz, Odr::_12_5hz));
+    assert!(change.sleeping);
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::_12_5hz));
+    assert_eq!(adaptive.current_odr(), Odr::_12_5hz);
+    // 512 periods at 400 Hz are 16 at 12.5 Hz, the sleep code 0
+    assert_eq!(
+        (
+            change.retune.sleep_dur.old_code,
+            change.retune.sleep_dur.new_code
+        ),
+        (1, 0)
+    );
+
+    still(&mut sensor, false);
+    for _ in 0..2 {
+        assert_eq!(adaptive.service(&mut sensor), Ok(None));
+    }
+    let change = adaptive.service(&mut sensor).unwrap().unwrap();
+    assert_eq!((change.from, change.to), (Odr::_12_5hz, Odr::_400hz));
+    assert!(!change.sleeping);
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::_400hz));
+}
+
+#[test]
+fn flapping_state_never_switches() {
+    let (mut sensor, mut adaptive) = started(CONFIG);
+    let accesses = [Access::read(Reg::StatusDup); 20];
+    sensor.bus.expect(&accesses);
+    for sleeping in [true, true, false].into_iter().cycle().take(accesses.len()) {
+        still(&mut sensor, sleeping);
+        assert_eq!(adaptive.service(&mut sensor), Ok(None));
+    }
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(adaptive.current_odr(), Odr::_400hz);
+}
+
+#[test]
+fn no_hysteresis_switches_at_once() {
+    let (mut sensor, mut adaptive) = started(AdaptiveRateConfig {
+        hysteresis: 0,
+        ..CONFIG
+    });
+    still(&mut sensor, true);
+    let change = adaptive.service(&mut sensor).unwrap().unwrap();
+    assert_eq!(change.to, Odr::_12_5hz);
+    assert_eq!(adaptive.service(&mut sensor), Ok(None));
+}
@@ -0,0 +1,124 @@
+use super::{bit, block_on, sensor};
+use crate::asynch::Iis2dlpcAsync;
+use crate::errata::Workarounds;
+use crate::event::{Event, EventCounters};
+use crate::fifo::DataPath;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::remap::{AxisRemap, SignedAxis};
+use crate::sample::TempPolicy;
+use crate::{Error, Iis2dlpc, RouteShadow};
+
+/// Board frame turned a quarter around Z.
+fn remap() -> AxisRemap {
+    AxisRemap::new([SignedAxis::NegY, SignedAxis::PosX, SignedAxis::PosZ]).unwrap()
+}
+
+const ROUTES: Ctrl4Int1PadCtrl = Ctrl4Int1PadCtrl::new().with_int1_wu(1);
+
+/// Blocking driver configured at 100 Hz in high-performance mode, with every piece of driver-side state moved
+/// off its default.
+fn configured() -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.odr_calibration_set(101.0, Odr::_100hz).unwrap();
+    sensor.pin_int1_route_set(&ROUTES).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.axis_remap_set(remap());
+    sensor.workarounds_set(Workarounds::NONE);
+    sensor.temp_policy_set(TempPolicy::Ignore);
+    sensor.strict_scaling_lock(true).unwrap();
+    sensor
+        .scaling_unlock_and_apply(false, |sensor| sensor.full_scale_set(Fs::_8g))
+        .unwrap();
+    sensor
+}
+
+/// Latch a wake-up on the X axis.
+fn wake_up_latch(bus: &mut MockBus) {
+    bus.set_reg(Reg::WakeUpSrc, bit(|b| WakeUpSrc::from_bits(b).x_wu()));
+    bus.set_reg(Reg::AllIntSrc, bit(|b| AllIntSrc::from_bits(b).wu_ia()));
+}
+
+#[test]
+fn blocking_state_carries_into_the_async_driver() {
+    let sensor = configured();
+    let epoch = sensor.config_epoch();
+    assert!(epoch > 0);
+    let shadow = RouteShadow {
+        routes: ROUTES,
+        shadow_valid: true,
+    };
+    assert_eq!(sensor.int1_routes_shadow(), shadow);
+
+    let mut sensor: Iis2dlpcAsync<MockBus, NoDelay> = sensor.into_async(|bus| bus);
+    assert_eq!(sensor.config_epoch(), epoch);
+    assert_eq!(sensor.int1_routes_shadow(), shadow);
+    assert_eq!(sensor.data_path_get(), DataPath::Fifo);
+    assert_eq!(sensor.event_counters(), EventCounters::default());
+    assert!(sensor.scaling_locked());
+
+    // The lock taken in the blocking stage still guards the scaling, before any access
+    sensor.bus.expect(&[Access::read(Reg::Ctrl6)]);
+    assert_eq!(
+        block_on(sensor.full_scale_set(Fs::_2g)),
+        Err(Error::ScalingLocked)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(block_on(sensor.full_scale_get()), Ok(Fs::_8g));
+    assert_eq!(sensor.config_epoch(), epoch);
+}
+
+#[test]
+fn async_state_carries_back_into_the_blocking_driver() {
+    let sensor = configured();
+    let ppm = sensor.odr_calibration_get();
+    assert_ne!(ppm, 0);
+    let mut sensor = sensor.into_async(|bus| bus);
+    let epoch = sensor.config_epoch();
+
+    block_on(sensor.fifo_mode_set(Fmode::BypassMode)).unwrap();
+    wake_up_latch(&mut sensor.bus);
+    let mut out = [Event::FreeFall; 4];
+    assert_eq!(block_on(sensor.events_drain(&mut out)), Ok(1));
+    assert_eq!(
+        out.first(),
+        Some(&remap().event(Event::WakeUp {
+            axes: [true, false, false]
+        }))
+    );
+
+    let mut sensor: Iis2dlpc<MockBus, NoDelay> = sensor.into_blocking(|bus| bus);
+    assert_eq!(sensor.data_path_get(), DataPath::Direct);
+    assert_eq!(sensor.event_counters().wake_up, 1);
+    assert_eq!(sensor.config_epoch(), epoch);
+    assert!(sensor.scaling_locked());
+    assert_eq!(
+        sensor.int1_routes_shadow(),
+        RouteShadow {
+            routes: ROUTES,
+            shadow_valid: true,
+        }
+    );
+    assert_eq!(sensor.odr_calibration_get(), ppm);
+    assert_eq!(sensor.axis_remap_get(), remap());
+    assert_eq!(sensor.workarounds_get(), Workarounds::NONE);
+    assert_eq!(sensor.temp_policy_get(), TempPolicy::Ignore);
+    assert_eq!(sensor.full_scale_set(Fs::_2g), Err(Error::ScalingLocked));
+    assert_eq!(sensor.full_scale_get(), Ok(Fs::_8g));
+    assert_eq!(sensor.fifo_mode_get(), Ok(Fmode::BypassMode));
+}
+
+#[test]
+fn snapshot_taken_blocking_applies_async() {
+    let mut source = configured();
+    let snapshot = source.config_snapshot_get().unwrap();
+
+    let mut sensor = sensor().into_async(|bus| bus);
+    block_on(sensor.config_snapshot_apply(&snapshot)).unwrap();
+    let mut sensor = sensor.into_blocking(|bus| bus);
+    assert_eq!(sensor.config_snapshot_get(), Ok(snapshot));
+    assert_eq!(sensor.full_scale_get(), Ok(Fs::_8g));
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::_100hz));
+}
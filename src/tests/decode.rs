@@ -0,0 +1,62 @@
+use core::fmt::Write;
+
+use super::{Text, sensor};
+use crate::prelude::*;
+
+/// The display of [`Reg::decode`].
+fn decoded(addr: u8, value: u8) -> Text<128> {
+    let mut text = Text::new();
+    write!(text, "{}", Reg::decode(addr, value)).unwrap();
+    text
+}
+
+#[test]
+fn fields_are_listed_most_significant_first() {
+    let cases = [
+        (
+            Reg::Ctrl6,
+            0x64,
+            "CTRL6 { bw_filt: 1, fs: 2, fds: 0, low_noise: 1 }",
+        ),
+        (Reg::Ctrl1, 0x54, "CTRL1 { odr: 5, mode: 1, lp_mode: 0 }"),
+        (
+            Reg::WakeUpDur,
+            0xA3,
+            "WAKE_UP_DUR { ff_dur: 1, wake_dur: 1, stationary: 0, sleep_dur: 3 }",
+        ),
+    ];
+    for (reg, value, expected) in cases {
+        assert_eq!(decoded(reg as u8, value).as_str(), expected);
+    }
+}
+
+#[test]
+fn values_without_fields_are_hexadecimal() {
+    assert_eq!(decoded(Reg::OutXL as u8, 0x12).as_str(), "OUT_X_L = 0x12");
+    assert_eq!(decoded(Reg::WhoAmI as u8, 0x44).as_str(), "WHO_AM_I = 0x44");
+    assert_eq!(decoded(0x10, 0xAB).as_str(), "0x10 = 0xab");
+}
+
+#[test]
+fn dump_prints_one_line_per_register() {
+    let mut sensor = sensor();
+    sensor.full_scale_set(Fs::_8g).unwrap();
+    sensor.bus.cost_reset();
+
+    let mut text = Text::<4096>::new();
+    sensor.dump_pretty(&mut text).unwrap();
+    assert_eq!(sensor.bus.transactions(), Reg::ALL.len() as u32);
+
+    let mut lines = text.as_str().lines();
+    for reg in Reg::ALL {
+        let mut prefix = Text::<32>::new();
+        write!(prefix, "0x{:02x} {}", reg as u8, reg.name()).unwrap();
+        let line = lines.next().unwrap();
+        assert!(line.starts_with(prefix.as_str()), "{line}");
+    }
+    assert_eq!(lines.next(), None);
+    assert!(
+        text.as_str()
+            .contains("0x25 CTRL6 { bw_filt: 0, fs: 2, fds: 0, low_noise: 0 }\n")
+    );
+}
@@ -0,0 +1,163 @@
+use super::{bit, sensor};
+use crate::Iis2dlpc;
+use crate::event::{Event, EventCounters};
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// Driver whose source registers flag a free fall in `WAKE_UP_SRC` and `ALL_INT_SRC` as requested.
+fn free_fall(detail: bool, summary: bool) -> Sensor {
+    let mut sensor = sensor();
+    if detail {
+        let ff_ia = bit(|b| WakeUpSrc::from_bits(b).ff_ia());
+        sensor.bus.set_reg(Reg::WakeUpSrc, ff_ia);
+    }
+    if summary {
+        let ff_ia = bit(|b| AllIntSrc::from_bits(b).ff_ia());
+        sensor.bus.set_reg(Reg::AllIntSrc, ff_ia);
+    }
+    sensor
+}
+
+#[test]
+fn sources_are_read_in_one_detail_first_burst() {
+    let mut sensor = sensor();
+    let regs = [
+        (Reg::StatusDup, 0x01),
+        (Reg::WakeUpSrc, 0x02),
+        (Reg::TapSrc, 0x03),
+        (Reg::SixdSrc, 0x04),
+        (Reg::AllIntSrc, 0x05),
+    ];
+    for (reg, val) in regs {
+        sensor.bus.set_reg(reg, val);
+    }
+
+    sensor.bus.expect(&[Access::read(Reg::StatusDup)]);
+    let sources = sensor.all_sources_get().unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sources.to_bytes(), regs.map(|(_, val)| val));
+
+    sensor.bus.expect(&[Access::read(Reg::StatusDup)]);
+    assert_eq!(sensor.all_sources_bytes_get(), Ok(sources.to_bytes()));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn drains_follow_the_same_order() {
+    let mut sensor = free_fall(true, true);
+    // The sources, then the tap axis priority to decode them
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::StatusDup), Access::read(Reg::TapThsY)]);
+    let mut events = [Event::FreeFall; 6];
+    assert_eq!(sensor.events_drain(&mut events), Ok(1));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::StatusDup), Access::read(Reg::TapThsY)]);
+    assert_eq!(sensor.event_get(), Ok(Some(Event::FreeFall)));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn event_asserting_during_the_read_is_flagged() {
+    // The summary, read last, sees a free fall the detail register missed
+    let mut sensor = free_fall(false, true);
+    let sources = sensor.all_sources_get().unwrap();
+    assert!(!sources.is_consistent());
+    assert_eq!(sensor.event_get(), Ok(Some(Event::FreeFall)));
+
+    let mut sensor = free_fall(true, true);
+    assert!(sensor.all_sources_get().unwrap().is_consistent());
+}
+
+#[test]
+fn detail_without_its_summary_is_consistent() {
+    // A pulsed interrupt ending during the read
+    let mut sensor = free_fall(true, false);
+    let sources = sensor.all_sources_get().unwrap();
+    assert!(sources.is_consistent());
+    assert_eq!(sensor.event_get(), Ok(None));
+}
+
+/// Driver whose source registers flag a single tap, a wake-up and an orientation change, in the detail
+/// registers and in the summary.
+fn flagged() -> Sensor {
+    let mut sensor = sensor();
+    let tap = bit(|b| TapSrc::from_bits(b).single_tap()) | bit(|b| TapSrc::from_bits(b).x_tap());
+    let wake_up = bit(|b| WakeUpSrc::from_bits(b).wu_ia());
+    let sixd = bit(|b| SixdSrc::from_bits(b).six_d_ia());
+    let summary = bit(|b| AllIntSrc::from_bits(b).single_tap())
+        | bit(|b| AllIntSrc::from_bits(b).wu_ia())
+        | bit(|b| AllIntSrc::from_bits(b).six_d_ia());
+    sensor.bus.set_reg(Reg::TapSrc, tap);
+    sensor.bus.set_reg(Reg::WakeUpSrc, wake_up);
+    sensor.bus.set_reg(Reg::SixdSrc, sixd);
+    sensor.bus.set_reg(Reg::AllIntSrc, summary);
+    sensor
+}
+
+#[test]
+fn every_source_read_counts_alike() {
+    let reads: [fn(&mut Sensor); 4] = [
+        |s| {
+            let _ = s.all_sources_get();
+        },
+        |s| {
+            let _ = s.all_sources_bytes_get();
+        },
+        |s| {
+            let _ = s.events_drain(&mut [Event::FreeFall; 6]);
+        },
+        |s| {
+            let _ = s.event_get();
+        },
+    ];
+    let expected = EventCounters {
+        single_tap: 1,
+        wake_up: 1,
+        six_d: 1,
+        ..EventCounters::default()
+    };
+    for read in reads {
+        let mut sensor = flagged();
+        read(&mut sensor);
+        assert_eq!(sensor.event_counters(), expected);
+    }
+}
+
+#[cfg(all(feature = "sixd", feature = "tap"))]
+#[test]
+fn single_register_reads_count_their_events() {
+    let mut sensor = flagged();
+    sensor.tap_src_get().unwrap();
+    sensor.tap_event_get().unwrap();
+    sensor.sixd_src_get().unwrap();
+    assert_eq!(
+        sensor.event_counters(),
+        EventCounters {
+            single_tap: 2,
+            six_d: 1,
+            ..EventCounters::default()
+        }
+    );
+}
+
+#[test]
+fn counters_accumulate_until_reset() {
+    let mut sensor = flagged();
+    // The events kept by the first call are not read again
+    assert!(sensor.event_get().unwrap().is_some());
+    assert!(sensor.event_get().unwrap().is_some());
+    assert_eq!(sensor.event_counters().wake_up, 1);
+
+    sensor.all_sources_get().unwrap();
+    assert_eq!(sensor.event_counters().wake_up, 2);
+    assert_eq!(sensor.event_counters().single_tap, 2);
+
+    sensor.event_counters_reset();
+    assert_eq!(sensor.event_counters(), EventCounters::default());
+}
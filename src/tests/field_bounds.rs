@@ -0,0 +1,129 @@
+use core::convert::Infallible;
+
+use super::sensor;
+use crate::activity::ActivityConfig;
+use crate::mock::{MockBus, NoDelay};
+use crate::tap::TapConfig;
+use crate::timing::ActivityTiming;
+use crate::{Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+type Setter = fn(&mut Sensor, u8) -> Result<(), Error<Infallible>>;
+type Getter = fn(&mut Sensor) -> Result<u8, Error<Infallible>>;
+
+/// Setter and getter of each field narrower than a byte, with the highest code of the field.
+const FIELDS: [(Setter, Getter, u8); 12] = [
+    (Sensor::fifo_watermark_set, Sensor::fifo_watermark_get, 31),
+    (Sensor::wkup_threshold_set, Sensor::wkup_threshold_get, 63),
+    (Sensor::wkup_dur_set, Sensor::wkup_dur_get, 3),
+    (Sensor::act_sleep_dur_set, Sensor::act_sleep_dur_get, 15),
+    (Sensor::tap_threshold_x_set, Sensor::tap_threshold_x_get, 31),
+    (Sensor::tap_threshold_y_set, Sensor::tap_threshold_y_get, 31),
+    (Sensor::tap_threshold_z_set, Sensor::tap_threshold_z_get, 31),
+    (Sensor::tap_dur_set, Sensor::tap_dur_get, 15),
+    (Sensor::tap_quiet_set, Sensor::tap_quiet_get, 3),
+    (Sensor::tap_shock_set, Sensor::tap_shock_get, 3),
+    (Sensor::sixd_threshold_set, Sensor::sixd_threshold_get, 3),
+    (Sensor::ff_dur_set, Sensor::ff_dur_get, 63),
+];
+
+#[test]
+fn highest_code_is_written() {
+    for (set, get, max) in FIELDS {
+        let mut sensor = sensor();
+        assert_eq!(set(&mut sensor, max), Ok(()));
+        assert_eq!(get(&mut sensor), Ok(max));
+    }
+}
+
+#[test]
+fn past_the_highest_code_nothing_is_accessed() {
+    for (set, _, max) in FIELDS {
+        let mut sensor = sensor();
+        sensor.bus.expect(&[]);
+        assert_eq!(set(&mut sensor, max + 1), Err(Error::InvalidArgument));
+        assert_eq!(set(&mut sensor, u8::MAX), Err(Error::InvalidArgument));
+        assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    }
+}
+
+#[test]
+fn bulk_setters_check_every_field_first() {
+    let activity = [
+        ActivityConfig {
+            wake_threshold: 64,
+            ..Default::default()
+        },
+        ActivityConfig {
+            wake_dur: 4,
+            ..Default::default()
+        },
+        ActivityConfig {
+            sleep_dur: 16,
+            ..Default::default()
+        },
+    ];
+    let tap = [
+        TapConfig {
+            thresholds: [0, 0, 32],
+            ..Default::default()
+        },
+        TapConfig {
+            shock: 4,
+            ..Default::default()
+        },
+        TapConfig {
+            quiet: 4,
+            ..Default::default()
+        },
+        TapConfig {
+            latency: 16,
+            ..Default::default()
+        },
+    ];
+    let timing = ActivityTiming {
+        sleep_dur: 16,
+        ..Default::default()
+    };
+
+    let mut sensor = sensor();
+    sensor.bus.expect(&[]);
+    for cfg in &activity {
+        assert_eq!(sensor.activity_config_set(cfg), Err(Error::InvalidArgument));
+    }
+    for cfg in &tap {
+        assert_eq!(sensor.tap_config_set(cfg), Err(Error::InvalidArgument));
+    }
+    assert_eq!(
+        sensor.wake_and_ff_config_set(Some(timing), None),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // The highest codes go through
+    let cfg = TapConfig {
+        thresholds: [31; 3],
+        shock: 3,
+        quiet: 3,
+        latency: 15,
+        ..Default::default()
+    };
+    assert_eq!(sensor.tap_config_set(&cfg), Ok(()));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_watermark_past_the_field_nothing_is_accessed() {
+    use super::block_on;
+    use crate::asynch::Iis2dlpcAsync;
+
+    let mut sensor = Iis2dlpcAsync::from_bus(MockBus::new(), NoDelay);
+    sensor.bus.expect(&[]);
+    assert_eq!(
+        block_on(sensor.fifo_watermark_set(32)),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(block_on(sensor.fifo_watermark_set(31)), Ok(()));
+    assert_eq!(block_on(sensor.fifo_watermark_get()), Ok(31));
+}
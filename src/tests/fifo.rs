@@ -0,0 +1,327 @@
+use core::convert::Infallible;
+
+use super::bit;
+use crate::fifo::{FifoDrainReport, FifoIntegrity};
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::{BusOperation, Error, Iis2dlpc};
+
+/// Mock bus whose FIFO overruns while the samples are read.
+///
+/// Each of the first `overruns` burst reads of the output registers sets the `fifo_ovr` flag, the following ones
+/// clear it as reading the samples does. A write of the bypass mode flushes the FIFO, which then holds `refill`
+/// samples once the previous mode is back.
+struct OverrunBus {
+    mock: MockBus,
+    overruns: u8,
+    refill: u8,
+    bursts: u8,
+}
+
+impl OverrunBus {
+    fn new(level: u8, overruns: u8, refill: u8) -> Self {
+        let mut mock = MockBus::new();
+        mock.set_reg(Reg::FifoSamples, level);
+        Self {
+            mock,
+            overruns,
+            refill,
+            bursts: 0,
+        }
+    }
+}
+
+impl BusOperation for OverrunBus {
+    type Error = Infallible;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.mock.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        if let [reg, val] = *wbuf {
+            let bypass = FifoCtrl::from_bits(val).fmode() == Fmode::BypassMode as u8;
+            if reg == Reg::FifoCtrl as u8 && bypass {
+                self.mock.set_reg(Reg::FifoSamples, self.refill);
+            }
+        }
+        self.mock.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.mock.write_byte_read_bytes(wbuf, rbuf)?;
+        if wbuf[0] == Reg::OutXL as u8 {
+            self.bursts += 1;
+            let ovr = bit(|b| FifoSamples::from_bits(b).fifo_ovr());
+            let samples = self.mock.reg(Reg::FifoSamples);
+            if self.overruns > 0 {
+                self.overruns -= 1;
+                self.mock.set_reg(Reg::FifoSamples, samples | ovr);
+            } else {
+                self.mock.set_reg(Reg::FifoSamples, samples & !ovr);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Driver collecting samples in stream mode.
+fn streaming(bus: OverrunBus) -> Iis2dlpc<OverrunBus, NoDelay> {
+    let mut sensor = Iis2dlpc::from_bus(bus, NoDelay);
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor
+}
+
+#[test]
+fn intact_drain_reads_the_level_twice() {
+    let mut sensor = streaming(OverrunBus::new(10, 0, 0));
+    sensor.bus.mock.expect(&[
+        Access::read(Reg::FifoSamples),
+        Access::read(Reg::OutXL),
+        Access::read(Reg::FifoSamples),
+    ]);
+    let mut buf = [0; 32 * 6];
+    assert_eq!(
+        sensor.fifo_drain(&mut buf, true),
+        Ok(FifoDrainReport {
+            samples: 10,
+            integrity: FifoIntegrity::Intact,
+            retried: false,
+            start_diff: 10,
+        })
+    );
+    assert_eq!(sensor.bus.mock.expectations_check(), Ok(()));
+}
+
+#[test]
+fn overrun_before_the_drain_keeps_the_ordering() {
+    let ovr = bit(|b| FifoSamples::from_bits(b).fifo_ovr());
+    let mut sensor = streaming(OverrunBus::new(32 | ovr, 0, 0));
+    let mut buf = [0; 32 * 6];
+    // Only older samples were lost: no retry
+    assert_eq!(
+        sensor.fifo_drain(&mut buf, true),
+        Ok(FifoDrainReport {
+            samples: 32,
+            integrity: FifoIntegrity::Overrun,
+            retried: false,
+            start_diff: 32,
+        })
+    );
+
+    let mut sensor = streaming(OverrunBus::new(32 | ovr, 0, 0));
+    let mut out = [[0; 3]; 32];
+    assert_eq!(
+        sensor.fifo_drain_exact(32, &mut out),
+        Ok(FifoIntegrity::Overrun)
+    );
+}
+
+#[test]
+fn overrun_during_the_drain_is_reported() {
+    let mut sensor = streaming(OverrunBus::new(10, 1, 4));
+    let mut buf = [0; 32 * 6];
+    let report = sensor.fifo_drain(&mut buf, false).unwrap();
+    assert_eq!(report.integrity, FifoIntegrity::Compromised);
+    assert!(!report.retried);
+    assert_eq!(sensor.bus.bursts, 1);
+}
+
+#[test]
+fn recovery_flushes_and_drains_once_more() {
+    let mut sensor = streaming(OverrunBus::new(10, 1, 4));
+    let mut buf = [0; 32 * 6];
+    assert_eq!(
+        sensor.fifo_drain(&mut buf, true),
+        Ok(FifoDrainReport {
+            samples: 4,
+            integrity: FifoIntegrity::Intact,
+            retried: true,
+            start_diff: 4,
+        })
+    );
+    assert_eq!(sensor.bus.bursts, 2);
+    assert_eq!(sensor.fifo_mode_get(), Ok(Fmode::StreamMode));
+}
+
+#[test]
+fn recovery_is_attempted_once() {
+    let mut sensor = streaming(OverrunBus::new(10, 2, 4));
+    let mut buf = [0; 32 * 6];
+    let report = sensor.fifo_drain(&mut buf, true).unwrap();
+    assert_eq!(report.integrity, FifoIntegrity::Compromised);
+    assert!(report.retried);
+    assert_eq!(sensor.bus.bursts, 2);
+}
+
+#[test]
+fn full_drain_ages_count_down_to_one() {
+    let mut sensor = streaming(OverrunBus::new(10, 0, 0));
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    let mut buf = [0; 32 * 6];
+    let report = sensor.fifo_drain(&mut buf, false).unwrap();
+
+    let mut ages = [0; 32];
+    assert_eq!(report.sample_ages(&mut ages), 10);
+    assert_eq!(ages[..10], [10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(report.sample_age(10), None);
+
+    let period_us = sensor.odr_period_us().unwrap();
+    assert_eq!(report.sample_age_us(0, period_us), Some(100_000));
+    assert_eq!(report.sample_age_us(9, period_us), Some(10_000));
+
+    // The calibrated period: the device runs 6.25 % fast
+    sensor.odr_calibration_set(106.25, Odr::_100hz).unwrap();
+    let period_us = sensor.odr_period_us().unwrap();
+    assert_eq!(report.sample_age_us(0, period_us), Some(94_110));
+}
+
+#[test]
+fn partial_drains_continue_the_ages() {
+    // 20 samples stored, 8 fit in the buffer
+    let mut sensor = streaming(OverrunBus::new(20, 0, 0));
+    let mut buf = [0; 8 * 6];
+    let report = sensor.fifo_drain(&mut buf, false).unwrap();
+    assert_eq!((report.samples, report.start_diff), (8, 20));
+    let mut ages = [0; 8];
+    assert_eq!(report.sample_ages(&mut ages), 8);
+    assert_eq!(ages, [20, 19, 18, 17, 16, 15, 14, 13]);
+
+    // The 12 samples left, and one more collected meanwhile
+    sensor.bus.mock.set_reg(Reg::FifoSamples, 13);
+    let report = sensor.fifo_drain(&mut buf, false).unwrap();
+    assert_eq!(report.sample_age(0), Some(13));
+    assert_eq!(report.sample_age(7), Some(6));
+
+    // A destination shorter than the drain gets the oldest ages
+    let mut ages = [0; 3];
+    assert_eq!(report.sample_ages(&mut ages), 3);
+    assert_eq!(ages, [13, 12, 11]);
+}
+
+#[test]
+fn exact_drain_refuses_a_short_fifo() {
+    let mut sensor = streaming(OverrunBus::new(5, 0, 0));
+    sensor.bus.mock.expect(&[Access::read(Reg::FifoSamples)]);
+    let mut out = [[0; 3]; 8];
+    assert_eq!(
+        sensor.fifo_drain_exact(8, &mut out),
+        Err(Error::FifoUnderrun {
+            available: 5,
+            requested: 8,
+        })
+    );
+    assert_eq!(sensor.bus.mock.expectations_check(), Ok(()));
+}
+
+#[test]
+fn exact_drain_reads_exactly_n_samples() {
+    let mut sensor = streaming(OverrunBus::new(20, 0, 0));
+    // X = 0x0100, Y = 0x0200, Z = -0x0100, left-aligned 14-bit values
+    let bytes = [0x00, 0x04, 0x00, 0x08, 0x00, 0xFC];
+    for (reg, byte) in [
+        Reg::OutXL,
+        Reg::OutXH,
+        Reg::OutYL,
+        Reg::OutYH,
+        Reg::OutZL,
+        Reg::OutZH,
+    ]
+    .into_iter()
+    .zip(bytes)
+    {
+        sensor.bus.mock.set_reg(reg, byte);
+    }
+    sensor.bus.mock.expect(&[
+        Access::read(Reg::FifoSamples),
+        Access::read(Reg::OutXL),
+        Access::read(Reg::FifoSamples),
+    ]);
+    let mut out = [[0x7FFF; 3]; 10];
+    assert_eq!(
+        sensor.fifo_drain_exact(8, &mut out),
+        Ok(FifoIntegrity::Intact)
+    );
+    assert_eq!(sensor.bus.mock.expectations_check(), Ok(()));
+    assert!(out[..8].iter().all(|raw| *raw == [0x100, 0x200, -0x100]));
+    assert_eq!(out[8], [0x7FFF; 3]);
+}
+
+#[test]
+fn exact_drain_flags_an_overrun_and_still_drains() {
+    let mut sensor = streaming(OverrunBus::new(10, 1, 0));
+    let mut out = [[0; 3]; 8];
+    assert_eq!(
+        sensor.fifo_drain_exact(8, &mut out),
+        Ok(FifoIntegrity::Compromised)
+    );
+    assert_eq!(sensor.bus.bursts, 1);
+}
+
+#[test]
+fn exact_drain_checks_the_count_first() {
+    let mut sensor = streaming(OverrunBus::new(32, 0, 0));
+    sensor.bus.mock.expect(&[]);
+    let mut out = [[0; 3]; 40];
+    assert_eq!(
+        sensor.fifo_drain_exact(33, &mut out),
+        Err(Error::OutOfRange {
+            requested: 33,
+            max: 32,
+        })
+    );
+    assert_eq!(
+        sensor.fifo_drain_exact(9, &mut out[..8]),
+        Err(Error::OutOfRange {
+            requested: 9,
+            max: 8,
+        })
+    );
+    assert_eq!(sensor.bus.mock.expectations_check(), Ok(()));
+
+    // Nothing to drain: only the level is read
+    sensor.bus.mock.expect(&[Access::read(Reg::FifoSamples)]);
+    assert_eq!(
+        sensor.fifo_drain_exact(0, &mut out),
+        Ok(FifoIntegrity::Intact)
+    );
+    assert_eq!(sensor.bus.mock.expectations_check(), Ok(()));
+}
+
+#[cfg(feature = "dsp")]
+#[test]
+fn drain_feeds_the_chosen_axis_to_the_goertzel_bank() {
+    use crate::batch::raw_to_mg;
+    use crate::dsp::GoertzelBank;
+
+    let mut sensor = streaming(OverrunBus::new(16, 0, 0));
+    // X = 0x0123 and Z = -2, left-aligned 14-bit values
+    for (reg, byte) in [
+        (Reg::OutXL, 0x8C),
+        (Reg::OutXH, 0x04),
+        (Reg::OutZL, 0xF8),
+        (Reg::OutZH, 0xFF),
+    ] {
+        sensor.bus.mock.set_reg(reg, byte);
+    }
+    let low_power_1 = sensor.power_mode_get().unwrap().is_low_power_1();
+
+    // A 0 Hz bin over blocks of 16 samples: a constant gives 2 · A²
+    let mut x = GoertzelBank::<1>::new(&[(0.0, 50.0)], 800.0).unwrap();
+    let report = sensor.fifo_drain_into_goertzel(&mut x, Axis::X).unwrap();
+    assert_eq!(
+        (report.samples, report.integrity),
+        (16, FifoIntegrity::Intact)
+    );
+    let mg = raw_to_mg(0x0123, Fs::_2g, low_power_1);
+    assert!((x.energies()[0] - 2.0 * mg * mg).abs() <= mg * mg * 1e-4);
+
+    let mut z = GoertzelBank::<1>::new(&[(0.0, 50.0)], 800.0).unwrap();
+    sensor.fifo_drain_into_goertzel(&mut z, Axis::Z).unwrap();
+    let mg = raw_to_mg(-2, Fs::_2g, low_power_1);
+    assert!((z.energies()[0] - 2.0 * mg * mg).abs() <= mg * mg * 1e-4);
+}
@@ -0,0 +1,226 @@
+use super::{WaitLog, sensor};
+#[cfg(feature = "wakeup")]
+use crate::Warning;
+use crate::errata::Workarounds;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::settle::SettleHint;
+use crate::{ConfigError, Error, Iis2dlpc};
+
+const BANDWIDTHS: [BwFilt; 4] = [
+    BwFilt::OdrDiv2,
+    BwFilt::OdrDiv4,
+    BwFilt::OdrDiv10,
+    BwFilt::OdrDiv20,
+];
+
+/// Cutoff in Hz of each data rate with the bandwidths of [`BANDWIDTHS`].
+const CUTOFFS: [(Odr, [f32; 4]); 9] = [
+    (Odr::_1_6hzLpOnly, [0.8, 0.4, 0.16, 0.08]),
+    (Odr::_12_5hz, [6.25, 3.125, 1.25, 0.625]),
+    (Odr::_25hz, [12.5, 6.25, 2.5, 1.25]),
+    (Odr::_50hz, [25.0, 12.5, 5.0, 2.5]),
+    (Odr::_100hz, [50.0, 25.0, 10.0, 5.0]),
+    (Odr::_200hz, [100.0, 50.0, 20.0, 10.0]),
+    (Odr::_400hz, [200.0, 100.0, 40.0, 20.0]),
+    (Odr::_800hz, [400.0, 200.0, 80.0, 40.0]),
+    (Odr::_1_6khz, [800.0, 400.0, 160.0, 80.0]),
+];
+
+/// Driver running at `odr` in high-performance mode.
+fn running(odr: Odr) -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(odr).unwrap();
+    sensor
+}
+
+/// Whether `hz` matches `expected` up to the rounding of the period.
+fn close(hz: f32, expected: f32) -> bool {
+    (hz - expected).abs() <= expected * 1e-5
+}
+
+#[test]
+fn cutoff_follows_the_data_rate_and_the_divider() {
+    let mut sensor = sensor();
+    for (odr, cutoffs) in CUTOFFS {
+        let ctrl1 = Ctrl1::new().with_odr(odr as u8 & 0x0F);
+        sensor.bus.set_reg(Reg::Ctrl1, ctrl1.into_bits());
+        for (bw, expected) in BANDWIDTHS.into_iter().zip(cutoffs) {
+            let ctrl6 = Ctrl6::new().with_bw_filt(bw as u8);
+            sensor.bus.set_reg(Reg::Ctrl6, ctrl6.into_bits());
+            let hz = sensor.filter_cutoff_hz().unwrap();
+            assert!(close(hz, expected), "{odr:?} {bw:?}: {hz}");
+            assert_eq!(bw.cutoff_hz(odr), Some(hz));
+        }
+    }
+}
+
+#[test]
+fn cutoff_needs_a_running_data_rate() {
+    let mut sensor = sensor();
+    assert_eq!(
+        sensor.filter_cutoff_hz(),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.filter_bandwidth_for_cutoff_set(10.0),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    for bw in BANDWIDTHS {
+        assert_eq!(bw.cutoff_hz(Odr::SetSwTrig), None);
+    }
+}
+
+#[test]
+fn closest_divider_is_selected_by_ratio() {
+    // Cutoffs at 100 Hz: 50, 25, 10 and 5 Hz
+    let cases = [
+        (1_000.0, BwFilt::OdrDiv2),
+        (50.0, BwFilt::OdrDiv2),
+        (30.0, BwFilt::OdrDiv4),
+        (15.0, BwFilt::OdrDiv10),
+        (7.5, BwFilt::OdrDiv10),
+        (7.0, BwFilt::OdrDiv20),
+        (0.1, BwFilt::OdrDiv20),
+    ];
+    let mut sensor = running(Odr::_100hz);
+    for (target_hz, expected) in cases {
+        assert_eq!(
+            sensor.filter_bandwidth_for_cutoff_set(target_hz),
+            Ok(expected),
+            "{target_hz} Hz"
+        );
+        assert_eq!(sensor.filter_bandwidth_get(), Ok(expected));
+    }
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn slow_high_pass_feeding_the_wake_up_is_reported() {
+    // 12.5 Hz / 20 puts the corner at 0.625 Hz
+    let mut sensor = running(Odr::_12_5hz);
+    let (_, warning) = sensor.filter_bandwidth_set(BwFilt::OdrDiv20).unwrap();
+    assert_eq!(warning, Some(Warning::WakeupHighPassSlow));
+    assert_eq!(
+        sensor.wakeup_high_pass_check(),
+        Ok(Some(Warning::WakeupHighPassSlow))
+    );
+
+    let (_, warning) = sensor.filter_bandwidth_set(BwFilt::OdrDiv10).unwrap();
+    assert_eq!(warning, None);
+
+    // The offset feed bypasses the filter
+    sensor
+        .wkup_feed_data_set(UsrOffOnWu::UserOffsetFeed)
+        .unwrap();
+    let (_, warning) = sensor.filter_bandwidth_set(BwFilt::OdrDiv20).unwrap();
+    assert_eq!(warning, None);
+}
+
+/// Driver running at 100 Hz in high-performance mode with the workarounds `workarounds`, its delay log cleared.
+fn logged(workarounds: Workarounds) -> Iis2dlpc<MockBus, WaitLog> {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), WaitLog::default());
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.workarounds_set(workarounds);
+    sensor.tim = WaitLog::default();
+    sensor
+}
+
+#[test]
+fn recommended_workarounds_are_the_default() {
+    let sensor = sensor();
+    assert_eq!(sensor.workarounds_get(), Workarounds::RECOMMENDED);
+    assert_eq!(Workarounds::default(), Workarounds::RECOMMENDED);
+    assert!(Workarounds::RECOMMENDED.contains(Workarounds::FILTER_DISCARD));
+    assert!(Workarounds::RECOMMENDED.contains(Workarounds::MODE_SWITCH_SETTLE));
+
+    let mut set = Workarounds::from_bits_truncate(0xFF);
+    assert_eq!(set, Workarounds::ALL);
+    set.remove(Workarounds::FILTER_DISCARD);
+    assert_eq!(set, Workarounds::MODE_SWITCH_SETTLE);
+    set.insert(Workarounds::FILTER_DISCARD);
+    assert_eq!(
+        set,
+        Workarounds::FILTER_DISCARD | Workarounds::MODE_SWITCH_SETTLE
+    );
+    assert!(Workarounds::NONE.is_empty());
+}
+
+#[test]
+fn bandwidth_change_discards_the_mixed_samples() {
+    let mut sensor = logged(Workarounds::FILTER_DISCARD);
+    sensor.bus.cost_reset();
+    // 8 samples to settle at ODR/10, 2 of them waited and dropped
+    assert_eq!(
+        sensor.filter_bandwidth_set(BwFilt::OdrDiv10),
+        Ok((SettleHint::DurationUs(60_000), None))
+    );
+    assert_eq!(sensor.tim.waits, 1);
+    assert_eq!(sensor.tim.total_ns, 20_000_000);
+
+    // Without the workaround: the same accesses but the output read, no wait and the whole settling
+    let discarded = sensor.bus.transactions();
+    let mut sensor = logged(Workarounds::NONE);
+    sensor.bus.cost_reset();
+    assert_eq!(
+        sensor.filter_bandwidth_set(BwFilt::OdrDiv10),
+        Ok((SettleHint::DurationUs(80_000), None))
+    );
+    assert_eq!(sensor.tim.waits, 0);
+    assert_eq!(sensor.bus.transactions(), discarded - 1);
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn discard_reads_the_outputs_on_the_direct_path_only() {
+    let mut sensor = logged(Workarounds::FILTER_DISCARD);
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl6),
+        Access::write(Reg::Ctrl6, 0x40),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::OutXL),
+        // The check of the wake-up high-pass filter
+        Access::read(Reg::Ctrl7),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::Ctrl6),
+    ]);
+    sensor.filter_bandwidth_set(BwFilt::OdrDiv4).unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // A read would pop a FIFO sample: the workaround only waits
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.tim = WaitLog::default();
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl6),
+        Access::write(Reg::Ctrl6, 0x80),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        // The check of the wake-up high-pass filter
+        Access::read(Reg::Ctrl7),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::Ctrl6),
+    ]);
+    sensor.filter_bandwidth_set(BwFilt::OdrDiv10).unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.tim.waits, 1);
+}
+
+#[test]
+fn discard_needs_a_running_data_rate() {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), WaitLog::default());
+    sensor.bus.cost_reset();
+    assert_eq!(
+        sensor.filter_bandwidth_set(BwFilt::OdrDiv10),
+        Ok((SettleHint::None, None))
+    );
+    assert_eq!(sensor.tim.waits, 0);
+}
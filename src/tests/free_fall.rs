@@ -0,0 +1,276 @@
+use core::fmt::Write;
+
+use super::{Text, sensor};
+#[cfg(feature = "wakeup")]
+use crate::Warning;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+#[cfg(feature = "wakeup")]
+use crate::timing::ActivityTiming;
+use crate::timing::{FfDuration, FreeFallTiming};
+use crate::{Error, Iis2dlpc};
+
+/// Driver running at 100 Hz in high-performance mode.
+fn running() -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor
+}
+
+#[test]
+fn duration_splits_across_the_two_registers() {
+    let mut sensor = running();
+    for code in 0..=0x3F {
+        sensor.ff_dur_set(code).unwrap();
+        let high = WakeUpDur::from_bits(sensor.bus.reg(Reg::WakeUpDur)).ff_dur();
+        let low = FreeFall::from_bits(sensor.bus.reg(Reg::FreeFall)).ff_dur();
+        assert_eq!((high, low), (code >> 5, code & 0x1F));
+        assert_eq!(sensor.ff_dur_get(), Ok(code));
+    }
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn config_round_trips_in_milliseconds() {
+    let mut sensor = running();
+    sensor.wkup_dur_set(3).unwrap();
+    assert_eq!(sensor.ff_config_set(FfThs::_13Lsb, 630), Ok(630));
+    assert_eq!(sensor.ff_dur_get(), Ok(63));
+    assert_eq!(sensor.ff_config_get(), Ok((FfThs::_13Lsb, 630)));
+    // The other fields of WAKE_UP_DUR are kept
+    assert_eq!(sensor.wkup_dur_get(), Ok(3));
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn wake_up_setters_keep_the_duration_bit_they_share() {
+    let mut sensor = running();
+    sensor.ff_dur_set(0x25).unwrap();
+    sensor.wkup_dur_set(2).unwrap();
+    sensor.act_sleep_dur_set(9).unwrap();
+    sensor.act_mode_set(SleepOn::StatMotion).unwrap();
+    assert_eq!(sensor.ff_dur_get(), Ok(0x25));
+}
+
+#[test]
+fn duration_out_of_reach_at_any_rate_is_refused_without_access() {
+    let mut sensor = running();
+    sensor.bus.expect(&[]);
+    // 63 periods at 1.6 Hz last 39.375 s
+    assert_eq!(
+        sensor.ff_config_set(FfThs::_5Lsb, 60_000),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn duration_out_of_reach_at_the_current_rate_writes_nothing() {
+    let mut sensor = running();
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.ff_config_set(FfThs::_5Lsb, 800),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[cfg(feature = "wakeup")]
+const ACTIVITY: ActivityTiming = ActivityTiming {
+    mode: SleepOn::StatMotion,
+    wake_dur: 2,
+    sleep_dur: 9,
+};
+
+/// Free-fall timing whose duration uses the bit held in `WAKE_UP_DUR`.
+#[cfg(feature = "wakeup")]
+fn long_fall() -> FreeFallTiming {
+    FreeFallTiming {
+        duration: FfDuration::from_lsb(0x25).unwrap(),
+        threshold: FfThs::_10Lsb,
+    }
+}
+
+/// The bytes of the three registers shared by the activity and free-fall timings.
+#[cfg(feature = "wakeup")]
+fn shared(sensor: &Iis2dlpc<MockBus, NoDelay>) -> [u8; 3] {
+    [
+        sensor.bus.reg(Reg::WakeUpThs),
+        sensor.bus.reg(Reg::WakeUpDur),
+        sensor.bus.reg(Reg::FreeFall),
+    ]
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn combined_setter_gives_the_same_bytes_in_any_order() {
+    let mut activity_first = running();
+    activity_first
+        .wake_and_ff_config_set(Some(ACTIVITY), None)
+        .unwrap();
+    activity_first
+        .wake_and_ff_config_set(None, Some(long_fall()))
+        .unwrap();
+
+    let mut ff_first = running();
+    ff_first
+        .wake_and_ff_config_set(None, Some(long_fall()))
+        .unwrap();
+    ff_first
+        .wake_and_ff_config_set(Some(ACTIVITY), None)
+        .unwrap();
+
+    let mut both = running();
+    both.wake_and_ff_config_set(Some(ACTIVITY), Some(long_fall()))
+        .unwrap();
+
+    assert_eq!(shared(&activity_first), shared(&ff_first));
+    assert_eq!(shared(&activity_first), shared(&both));
+
+    assert_eq!(both.wkup_dur_get(), Ok(2));
+    assert_eq!(both.act_sleep_dur_get(), Ok(9));
+    assert_eq!(both.act_mode_get(), Ok(SleepOn::StatMotion));
+    let ff = both.free_fall_timing_get().unwrap();
+    assert_eq!((ff.duration.lsb(), ff.threshold), (0x25, FfThs::_10Lsb));
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn combined_setter_writes_each_register_once() {
+    let mut sensor = running();
+    sensor.bus.expect(&[
+        Access::read(Reg::WakeUpThs),
+        Access::read(Reg::WakeUpDur),
+        Access::read(Reg::FreeFall),
+        Access::write(Reg::WakeUpThs, 0x40),
+        Access::write(Reg::WakeUpDur, 0xD9),
+        Access::write(Reg::FreeFall, 0x2B),
+    ]);
+    assert_eq!(
+        sensor.wake_and_ff_config_set(Some(ACTIVITY), Some(long_fall())),
+        Ok(None)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn free_fall_under_activity_detection_is_stretched() {
+    let mut sensor = running();
+    let activity = ActivityTiming {
+        mode: SleepOn::ActInact,
+        ..ACTIVITY
+    };
+    assert_eq!(
+        sensor.wake_and_ff_config_set(Some(activity), Some(long_fall())),
+        Ok(Some(Warning::FreeFallDurationStretched))
+    );
+    // The warning follows the free-fall duration already programmed
+    assert_eq!(
+        sensor.wake_and_ff_config_set(Some(activity), None),
+        Ok(Some(Warning::FreeFallDurationStretched))
+    );
+    assert_eq!(
+        sensor.wake_and_ff_config_set(Some(ACTIVITY), None),
+        Ok(None)
+    );
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn combined_setter_refuses_wide_codes_without_access() {
+    let mut sensor = running();
+    sensor.bus.expect(&[]);
+    for activity in [
+        ActivityTiming {
+            wake_dur: 4,
+            ..ACTIVITY
+        },
+        ActivityTiming {
+            sleep_dur: 16,
+            ..ACTIVITY
+        },
+    ] {
+        assert_eq!(
+            sensor.wake_and_ff_config_set(Some(activity), Some(long_fall())),
+            Err(Error::InvalidArgument)
+        );
+    }
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn typed_duration_validates_the_code() {
+    for lsb in 0..=0x3F {
+        assert_eq!(FfDuration::from_lsb(lsb).map(|dur| dur.lsb()), Some(lsb));
+    }
+    assert_eq!(FfDuration::from_lsb(0x40), None);
+    assert_eq!(FfDuration::from_lsb(u8::MAX), None);
+}
+
+#[test]
+fn typed_duration_converts_milliseconds() {
+    // 10 ms per LSB at 100 Hz
+    let dur = FfDuration::from_ms(370, Odr::_100hz).unwrap();
+    assert_eq!((dur.lsb(), dur.odr()), (37, Some(Odr::_100hz)));
+    assert_eq!(dur.duration_us(), Some(370_000));
+    assert_eq!(FfDuration::from_ms(634, Odr::_100hz).unwrap().lsb(), 63);
+    assert_eq!(FfDuration::from_ms(636, Odr::_100hz), None);
+    assert_eq!(FfDuration::from_ms(10, Odr::Off), None);
+    assert_eq!(FfDuration::from_ms(10, Odr::SetSwTrig), None);
+    assert_eq!(FfDuration::from_lsb(37).unwrap().duration_us(), None);
+}
+
+#[test]
+fn typed_duration_displays_both_representations() {
+    let mut text = Text::<32>::new();
+    write!(text, "{}", FfDuration::from_ms(4, Odr::_1_6khz).unwrap()).unwrap();
+    assert_eq!(text.as_str(), "6 LSB = 3.750 ms");
+
+    let mut text = Text::<32>::new();
+    write!(text, "{}", FfDuration::from_lsb(6).unwrap()).unwrap();
+    assert_eq!(text.as_str(), "6 LSB (1 / ODR each)");
+}
+
+#[test]
+fn typed_timing_round_trips_past_the_split() {
+    let mut sensor = running();
+    for lsb in [0, 1, 0x1F, 0x20, 0x21, 0x2A, 0x3E, 0x3F] {
+        let timing = FreeFallTiming {
+            duration: FfDuration::from_lsb(lsb).unwrap(),
+            threshold: FfThs::_16Lsb,
+        };
+        sensor.free_fall_timing_set(timing).unwrap();
+        let high = WakeUpDur::from_bits(sensor.bus.reg(Reg::WakeUpDur)).ff_dur();
+        assert_eq!(high, lsb >> 5, "{lsb:#04x}");
+        assert_eq!(sensor.ff_dur_get(), Ok(lsb));
+
+        let read = sensor.free_fall_timing_get().unwrap();
+        assert_eq!(read.duration.lsb(), lsb);
+        assert_eq!(read.duration.odr(), Some(Odr::_100hz));
+        assert_eq!(read.threshold, FfThs::_16Lsb);
+    }
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn typed_timing_writes_each_register_once() {
+    let mut sensor = running();
+    sensor.wkup_dur_set(3).unwrap();
+    sensor.bus.expect(&[
+        Access::read(Reg::WakeUpDur),
+        Access::read(Reg::FreeFall),
+        Access::write(Reg::WakeUpDur, 0xE0),
+        Access::write(Reg::FreeFall, 0x53),
+    ]);
+    // 0x2A: bit 5 in WAKE_UP_DUR, 0x0A in FREE_FALL
+    let timing = FreeFallTiming {
+        duration: FfDuration::from_lsb(0x2A).unwrap(),
+        threshold: FfThs::_10Lsb,
+    };
+    assert_eq!(sensor.free_fall_timing_set(timing), Ok(()));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
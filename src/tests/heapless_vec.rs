@@ -0,0 +1,83 @@
+use super::{bit, sensor};
+use crate::Iis2dlpc;
+use crate::event::{Event, MAX_EVENTS};
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+
+/// Driver collecting samples in stream mode, `level` of them stored, each X = 0x0100, Y = 0x0200, Z = -0x0100.
+fn streaming(level: u8) -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.bus.set_reg(Reg::FifoSamples, level);
+    for (reg, byte) in [(Reg::OutXH, 0x04), (Reg::OutYH, 0x08), (Reg::OutZH, 0xFC)] {
+        sensor.bus.set_reg(reg, byte);
+    }
+    sensor
+}
+
+#[test]
+fn fifo_vector_truncates_to_its_capacity() {
+    let mut sensor = streaming(20);
+    let (samples, report) = sensor.fifo_drain_vec::<8>().unwrap();
+    assert_eq!(samples.len(), 8);
+    assert!(samples.iter().all(|raw| *raw == [0x100, 0x200, -0x100]));
+    // The samples left in the FIFO
+    assert_eq!((report.samples, report.start_diff), (8, 20));
+}
+
+#[test]
+fn fifo_vector_holds_every_stored_sample() {
+    let mut sensor = streaming(10);
+    let (samples, report) = sensor.fifo_drain_vec::<32>().unwrap();
+    assert_eq!(
+        (samples.len(), report.samples, report.start_diff),
+        (10, 10, 10)
+    );
+
+    // A vector larger than the FIFO is never filled past its depth
+    let mut sensor = streaming(32);
+    let (samples, _) = sensor.fifo_drain_vec::<40>().unwrap();
+    assert_eq!(samples.len(), 32);
+}
+
+#[test]
+fn register_vector_reads_the_first_registers() {
+    let mut sensor = sensor();
+    sensor.bus.expect(&Reg::ALL.map(Access::read)[..4]);
+    let regs = sensor.register_dump_vec::<4>().unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert!(
+        regs.iter()
+            .map(|(reg, _)| *reg)
+            .eq(Reg::ALL.into_iter().take(4))
+    );
+
+    let regs = sensor.register_dump_vec::<64>().unwrap();
+    assert_eq!(regs.len(), Reg::ALL.len());
+    assert!(regs.contains(&(Reg::WhoAmI, 0x44)));
+}
+
+#[test]
+fn event_vector_drops_what_does_not_fit() {
+    let flag = |sensor: &mut Iis2dlpc<MockBus, NoDelay>| {
+        let wake_up =
+            bit(|b| WakeUpSrc::from_bits(b).wu_ia()) | bit(|b| WakeUpSrc::from_bits(b).ff_ia());
+        let summary =
+            bit(|b| AllIntSrc::from_bits(b).wu_ia()) | bit(|b| AllIntSrc::from_bits(b).ff_ia());
+        sensor.bus.set_reg(Reg::WakeUpSrc, wake_up);
+        sensor.bus.set_reg(Reg::AllIntSrc, summary);
+    };
+
+    let mut sensor = sensor();
+    flag(&mut sensor);
+    let mut all = [Event::FreeFall; MAX_EVENTS];
+    assert_eq!(sensor.events_drain(&mut all), Ok(2));
+
+    flag(&mut sensor);
+    let events = sensor.events_drain_vec::<MAX_EVENTS>().unwrap();
+    assert_eq!(*events, all[..2]);
+
+    flag(&mut sensor);
+    let events = sensor.events_drain_vec::<1>().unwrap();
+    assert_eq!(*events, all[..1]);
+}
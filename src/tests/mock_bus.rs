@@ -0,0 +1,157 @@
+use super::sensor;
+#[cfg(feature = "freefall")]
+use crate::mock::Access;
+use crate::mock::MockBus;
+use crate::prelude::*;
+use crate::{BusOperation, ID};
+
+const MODES: [Mode; 18] = [
+    Mode::HighPerformance,
+    Mode::ContLowPwr4,
+    Mode::ContLowPwr3,
+    Mode::ContLowPwr2,
+    Mode::ContLowPwr12bit,
+    Mode::SingleLowPwr4,
+    Mode::SingleLowPwr3,
+    Mode::SingleLowPwr2,
+    Mode::SingleLowPwr12bit,
+    Mode::HighPerformanceLowNoise,
+    Mode::ContLowPwrLowNoise4,
+    Mode::ContLowPwrLowNoise3,
+    Mode::ContLowPwrLowNoise2,
+    Mode::ContLowPwrLowNoise12bit,
+    Mode::SingleLowPwrLowNoise4,
+    Mode::SingleLowPwrLowNoise3,
+    Mode::SingleLowPwrLowNoise2,
+    Mode::SingleLowLowNoisePwr12bit,
+];
+
+#[test]
+fn reset_values_and_identifier() {
+    let mut sensor = sensor();
+    assert_eq!(sensor.device_id_get(), Ok(ID));
+    assert_eq!(sensor.bus.reg(Reg::Ctrl2), 0x04);
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn registers_start_and_reset_to_the_reset_image() {
+    let image = reset_image();
+    let mut sensor = sensor();
+    for reg in Reg::ALL {
+        assert_eq!(sensor.bus.reg(reg), image[reg as usize], "{reg:?}");
+    }
+
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.wkup_threshold_set(12).unwrap();
+    sensor.reset_set().unwrap();
+    for reg in Reg::ALL {
+        assert_eq!(sensor.bus.reg(reg), image[reg as usize], "{reg:?}");
+    }
+}
+
+#[test]
+fn reads_follow_the_auto_increment() {
+    let mut bus = MockBus::new();
+    bus.set_reg(Reg::Ctrl1, 0x11);
+    bus.set_reg(Reg::Ctrl2, 0x04);
+    bus.set_reg(Reg::Ctrl3, 0x33);
+    let mut buf = [0; 3];
+    bus.read_from_register(Reg::Ctrl1 as u8, &mut buf).unwrap();
+    assert_eq!(buf, [0x11, 0x04, 0x33]);
+
+    bus.set_reg(Reg::Ctrl2, 0x00);
+    bus.read_from_register(Reg::Ctrl1 as u8, &mut buf).unwrap();
+    assert_eq!(buf, [0x11; 3]);
+}
+
+#[test]
+fn power_mode_round_trip() {
+    let mut sensor = sensor();
+    for mode in MODES {
+        sensor.power_mode_set(mode).unwrap();
+        assert_eq!(sensor.power_mode_get(), Ok(mode));
+    }
+}
+
+#[test]
+fn data_rate_round_trip() {
+    let mut sensor = sensor();
+    let rates = [
+        (Mode::ContLowPwr4, Odr::_1_6hzLpOnly),
+        (Mode::ContLowPwr4, Odr::_12_5hz),
+        (Mode::ContLowPwr4, Odr::_25hz),
+        (Mode::ContLowPwr4, Odr::_50hz),
+        (Mode::ContLowPwr4, Odr::_100hz),
+        (Mode::ContLowPwr4, Odr::_200hz),
+        (Mode::HighPerformance, Odr::_400hz),
+        (Mode::HighPerformance, Odr::_800hz),
+        (Mode::HighPerformance, Odr::_1_6khz),
+        (Mode::SingleLowPwr4, Odr::SetSwTrig),
+        (Mode::SingleLowPwr4, Odr::SetPinTrig),
+    ];
+    for (mode, odr) in rates {
+        sensor.data_rate_set(Odr::Off).unwrap();
+        sensor.power_mode_set(mode).unwrap();
+        sensor.data_rate_set(odr).unwrap();
+        assert_eq!(sensor.data_rate_get(), Ok(odr));
+    }
+    sensor.data_rate_set(Odr::Off).unwrap();
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::Off));
+}
+
+#[cfg(feature = "freefall")]
+#[test]
+fn ff_dur_split_across_two_registers() {
+    let mut sensor = sensor();
+    sensor.bus.expect(&[
+        Access::read(Reg::WakeUpDur),
+        Access::read(Reg::FreeFall),
+        Access::write(Reg::WakeUpDur, 0x80),
+        Access::write(Reg::FreeFall, 0x50),
+    ]);
+    sensor.ff_dur_set(0x2A).unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    for val in 0..=0x3F {
+        sensor.ff_dur_set(val).unwrap();
+        assert_eq!(sensor.ff_dur_get(), Ok(val));
+    }
+}
+
+#[cfg(feature = "events")]
+#[test]
+fn int1_routing_enables_the_embedded_functions() {
+    let mut sensor = sensor();
+    sensor
+        .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_tap(1))
+        .unwrap();
+    assert_eq!(sensor.bus.reg(Reg::Ctrl4Int1PadCtrl), 0x08);
+    assert_eq!(
+        Ctrl7::from_bits(sensor.bus.reg(Reg::Ctrl7)).interrupts_enable(),
+        1
+    );
+
+    // Data-ready alone does not need them
+    sensor
+        .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_drdy(1))
+        .unwrap();
+    assert_eq!(
+        Ctrl7::from_bits(sensor.bus.reg(Reg::Ctrl7)).interrupts_enable(),
+        0
+    );
+
+    // Unless an event is routed on INT2
+    sensor.bus.set_reg(
+        Reg::Ctrl5Int2PadCtrl,
+        Ctrl5Int2PadCtrl::new().with_int2_sleep_chg(1).into_bits(),
+    );
+    sensor
+        .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_drdy(1))
+        .unwrap();
+    assert_eq!(
+        Ctrl7::from_bits(sensor.bus.reg(Reg::Ctrl7)).interrupts_enable(),
+        1
+    );
+}
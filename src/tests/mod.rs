@@ -0,0 +1,145 @@
+//! Host tests of the driver on the mock bus.
+//!
+//! The tests of a module living next to its code, the tests here cover the functions of the driver itself, one
+//! file per area. They run with `cargo test`, without hardware; the mock bus follows the register layout of the
+//! default bit order only, so they are left out with `bit_order_msb`.
+
+// The set-up helpers panic on a failed step, as the tests do
+#![allow(clippy::unwrap_used)]
+
+use crate::Iis2dlpc;
+use crate::mock::{MockBus, NoDelay};
+
+mod acceleration;
+#[cfg(feature = "wakeup")]
+mod adaptive;
+#[cfg(all(feature = "async", feature = "events", feature = "fifo"))]
+mod conversion;
+mod decode;
+#[cfg(feature = "events")]
+mod events;
+#[cfg(all(
+    feature = "fifo",
+    feature = "tap",
+    feature = "wakeup",
+    feature = "sixd",
+    feature = "freefall"
+))]
+mod field_bounds;
+#[cfg(feature = "fifo")]
+mod fifo;
+mod filter;
+#[cfg(feature = "freefall")]
+mod free_fall;
+#[cfg(all(feature = "heapless", feature = "events", feature = "fifo"))]
+mod heapless_vec;
+mod mock_bus;
+mod mux;
+#[cfg(feature = "sixd")]
+mod orientation;
+#[cfg(all(
+    feature = "events",
+    feature = "fifo",
+    feature = "tap",
+    feature = "wakeup",
+    feature = "sixd",
+    feature = "freefall"
+))]
+mod panic_free;
+mod power_mode;
+mod probe;
+#[cfg(feature = "recorder")]
+mod recorder;
+#[cfg(all(feature = "wakeup", feature = "tap", feature = "freefall"))]
+mod retune;
+#[cfg(feature = "events")]
+mod routing;
+mod scaling_lock;
+mod snapshot;
+#[cfg(all(feature = "async", feature = "events"))]
+mod stream;
+#[cfg(feature = "tap")]
+mod tap;
+#[cfg(feature = "telemetry")]
+mod telemetry;
+mod timing;
+#[cfg(feature = "hooks")]
+mod transaction;
+#[cfg(feature = "wakeup")]
+mod wake_up;
+
+/// Driver on a mock bus holding the reset values.
+fn sensor() -> Iis2dlpc<MockBus, NoDelay> {
+    Iis2dlpc::from_bus(MockBus::new(), NoDelay)
+}
+
+/// The mask of the single-bit field read by `get`; the source registers are read-only.
+fn bit(get: impl Fn(u8) -> u8) -> u8 {
+    (0..8).map(|i| 1 << i).find(|b| get(*b) != 0).unwrap()
+}
+
+/// Delay recording the waits requested by the driver.
+#[derive(Default)]
+struct WaitLog {
+    waits: u32,
+    total_ns: u64,
+}
+
+impl embedded_hal::delay::DelayNs for WaitLog {
+    fn delay_ns(&mut self, ns: u32) {
+        self.waits += 1;
+        self.total_ns += u64::from(ns);
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for WaitLog {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.waits += 1;
+        self.total_ns += u64::from(ns);
+    }
+}
+
+/// Fixed-capacity text sink.
+struct Text<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Text<N> {
+    fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(self.buf.get(..self.len).unwrap()).unwrap()
+    }
+}
+
+impl<const N: usize> core::fmt::Write for Text<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        let dest = self.buf.get_mut(self.len..end).ok_or(core::fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Poll a future of the async driver to completion; the mock bus never pends.
+#[cfg(feature = "async")]
+fn block_on<F: Future>(fut: F) -> F::Output {
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    let mut fut = pin!(fut);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
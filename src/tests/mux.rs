@@ -0,0 +1,118 @@
+use embedded_hal::i2c::{self, Operation};
+
+use crate::mock::NoDelay;
+use crate::mux::MuxedI2cBus;
+use crate::{Error, I2CAddress, ID, Iis2dlpc};
+
+const MUX: u8 = 0x70;
+const DEVICE: u8 = I2CAddress::I2cAddH as u8;
+
+/// I2C peripheral of a switch and one device, logging the address of each transaction.
+#[derive(Default)]
+struct Rig {
+    addresses: [u8; 8],
+    len: usize,
+    control: u8,
+}
+
+impl Rig {
+    fn addresses(&self) -> &[u8] {
+        self.addresses.get(..self.len).unwrap()
+    }
+
+    fn record(&mut self, address: u8, operations: &mut [Operation<'_>]) {
+        if let Some(slot) = self.addresses.get_mut(self.len) {
+            *slot = address;
+            self.len += 1;
+        }
+        for op in operations {
+            match op {
+                Operation::Write(bytes) if address == MUX => {
+                    self.control = bytes.first().copied().unwrap_or_default();
+                }
+                Operation::Write(_) => {}
+                Operation::Read(buf) => buf.fill(ID),
+            }
+        }
+    }
+}
+
+impl i2c::ErrorType for Rig {
+    type Error = i2c::ErrorKind;
+}
+
+impl i2c::I2c for Rig {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.record(address, operations);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for Rig {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.record(address, operations);
+        Ok(())
+    }
+}
+
+/// Driver on channel 3 of the switch.
+fn sensor(rig: &mut Rig) -> Iis2dlpc<MuxedI2cBus<&mut Rig>, NoDelay> {
+    let bus = MuxedI2cBus::new(rig, MUX, 3, I2CAddress::I2cAddH).unwrap();
+    Iis2dlpc::from_bus(bus, NoDelay)
+}
+
+#[test]
+fn every_transaction_selects_the_channel_once() {
+    let mut rig = Rig::default();
+    assert_eq!(sensor(&mut rig).device_id_get(), Ok(ID));
+    assert_eq!(rig.addresses(), [MUX, DEVICE]);
+    assert_eq!(rig.control, 1 << 3);
+
+    // A burst is a single transaction
+    let mut rig = Rig::default();
+    sensor(&mut rig).acceleration_raw_unchecked_get().unwrap();
+    assert_eq!(rig.addresses(), [MUX, DEVICE]);
+
+    // Read-modify-write
+    let mut rig = Rig::default();
+    sensor(&mut rig).block_data_update_set(true).unwrap();
+    assert_eq!(rig.addresses(), [MUX, DEVICE, MUX, DEVICE]);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn channel_survives_the_conversions() {
+    use super::block_on;
+
+    let mut rig = Rig::default();
+    {
+        let mut sensor = sensor(&mut rig).into_async(|bus| bus);
+        assert_eq!(block_on(sensor.device_id_get()), Ok(ID));
+        let mut sensor = sensor.into_blocking(|bus| bus);
+        assert_eq!(sensor.device_id_get(), Ok(ID));
+    }
+    assert_eq!(rig.addresses(), [MUX, DEVICE, MUX, DEVICE]);
+    assert_eq!(rig.control, 1 << 3);
+}
+
+#[test]
+fn missing_channel_is_refused() {
+    let mut rig = Rig::default();
+    assert!(matches!(
+        MuxedI2cBus::new(&mut rig, MUX, 8, I2CAddress::I2cAddH),
+        Err(Error::OutOfRange {
+            requested: 8,
+            max: 7
+        })
+    ));
+    assert_eq!(rig.addresses(), []);
+}
@@ -0,0 +1,71 @@
+use super::sensor;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::{ConfigError, Error, Iis2dlpc};
+
+/// Driver running at `odr` in high-performance mode.
+fn running(odr: Odr) -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(odr).unwrap();
+    sensor
+}
+
+#[test]
+fn lowest_bandwidth_settling_in_time_is_selected() {
+    // The settling time is about 733 ms times the divider over the data rate in Hz
+    let cases = [
+        (Odr::_100hz, 200, BwFilt::OdrDiv20),
+        (Odr::_100hz, 100, BwFilt::OdrDiv10),
+        (Odr::_100hz, 50, BwFilt::OdrDiv4),
+        (Odr::_100hz, 20, BwFilt::OdrDiv2),
+        (Odr::_12_5hz, 200, BwFilt::OdrDiv2),
+        (Odr::_12_5hz, 1_200, BwFilt::OdrDiv20),
+        (Odr::_400hz, 40, BwFilt::OdrDiv20),
+        (Odr::_1_6khz, 1, BwFilt::OdrDiv2),
+    ];
+    for (odr, settle_ms, expected) in cases {
+        let mut sensor = running(odr);
+        assert_eq!(
+            sensor.sixd_stable_config(SixdThs::Deg60, settle_ms),
+            Ok(expected),
+            "{odr:?} {settle_ms} ms"
+        );
+        assert_eq!(sensor.filter_bandwidth_get(), Ok(expected));
+    }
+}
+
+#[test]
+fn configuration_is_applied_across_the_registers() {
+    let mut sensor = running(Odr::_100hz);
+    sensor.sixd_stable_config(SixdThs::Deg60, 100).unwrap();
+    assert_eq!(sensor.sixd_feed_data_get(), Ok(LpassOn6d::Lpf2Feed));
+    assert_eq!(sensor.sixd_threshold_get(), Ok(SixdThs::Deg60 as u8));
+    assert_eq!(sensor.pin_int1_route_get().unwrap().int1_6d(), 1);
+    assert_eq!(
+        Ctrl7::from_bits(sensor.bus.reg(Reg::Ctrl7)).interrupts_enable(),
+        1
+    );
+}
+
+#[test]
+fn unreachable_settling_time_writes_nothing() {
+    // 14.7 ms at least at 100 Hz
+    let mut sensor = running(Odr::_100hz);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.sixd_stable_config(SixdThs::Deg80, 10),
+        Err(Error::InvalidConfig(ConfigError::SettleTimeUnreachable {
+            min_ms: 15
+        }))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    let mut sensor = running(Odr::Off);
+    assert_eq!(
+        sensor.sixd_stable_config(SixdThs::Deg80, 1_000),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+}
@@ -0,0 +1,128 @@
+use crate::Iis2dlpc;
+use crate::batch::SampleBatch;
+use crate::event::Event;
+use crate::mock::{MockBus, NoDelay};
+use crate::prelude::*;
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// Every register of the device.
+const REGS: [Reg; 34] = [
+    Reg::OutTL,
+    Reg::OutTH,
+    Reg::WhoAmI,
+    Reg::Ctrl1,
+    Reg::Ctrl2,
+    Reg::Ctrl3,
+    Reg::Ctrl4Int1PadCtrl,
+    Reg::Ctrl5Int2PadCtrl,
+    Reg::Ctrl6,
+    Reg::OutT,
+    Reg::Status,
+    Reg::OutXL,
+    Reg::OutXH,
+    Reg::OutYL,
+    Reg::OutYH,
+    Reg::OutZL,
+    Reg::OutZH,
+    Reg::FifoCtrl,
+    Reg::FifoSamples,
+    Reg::TapThsX,
+    Reg::TapThsY,
+    Reg::TapThsZ,
+    Reg::IntDur,
+    Reg::WakeUpThs,
+    Reg::WakeUpDur,
+    Reg::FreeFall,
+    Reg::StatusDup,
+    Reg::WakeUpSrc,
+    Reg::TapSrc,
+    Reg::SixdSrc,
+    Reg::AllIntSrc,
+    Reg::XOfsUsr,
+    Reg::YOfsUsr,
+    Reg::ZOfsUsr,
+];
+
+/// Run the decoding getters; a value they cannot map is an error, never a panic.
+fn decode_all(sensor: &mut Sensor) {
+    let _ = sensor.power_mode_get();
+    let _ = sensor.data_rate_get();
+    let _ = sensor.trigger_mode_get();
+    let _ = sensor.full_scale_get();
+    let _ = sensor.status_reg_get();
+    let _ = sensor.all_sources_get();
+    let _ = sensor.config_snapshot_get();
+    let _ = sensor.offset_weight_get();
+    let _ = sensor.acceleration_mg_get();
+    let _ = sensor.temperature_celsius_get();
+    let _ = sensor.temperature_8bit_get();
+    let _ = sensor.self_test_get();
+    let _ = sensor.data_ready_mode_get();
+    let _ = sensor.filter_path_get();
+    let _ = sensor.filter_bandwidth_get();
+    let _ = sensor.filter_cutoff_hz();
+    let _ = sensor.spi_mode_get();
+    let _ = sensor.i2c_interface_get();
+    let _ = sensor.cs_mode_get();
+    let _ = sensor.pin_polarity_get();
+    let _ = sensor.int_notification_get();
+    let _ = sensor.pin_mode_get();
+    let _ = sensor.pin_int1_route_get();
+    let _ = sensor.pin_int2_route_get();
+    let _ = sensor.interrupt_config_get();
+    let _ = sensor.wkup_feed_data_get();
+    let _ = sensor.act_mode_get();
+    let _ = sensor.activity_config_get();
+    let _ = sensor.activity_state_get();
+    let _ = sensor.tap_axis_priority_get();
+    let _ = sensor.tap_mode_get();
+    let _ = sensor.tap_config_get();
+    let _ = sensor.tap_src_get();
+    let _ = sensor.tap_event_get();
+    let _ = sensor.event_get();
+    let _ = sensor.sixd_src_get();
+    let _ = sensor.orientation_get();
+    let _ = sensor.sixd_feed_data_get();
+    let _ = sensor.free_fall_timing_get();
+    let _ = sensor.ff_threshold_get();
+    let _ = sensor.ff_dur_get();
+    let _ = sensor.fifo_mode_get();
+    let _ = sensor.fifo_flags_get();
+    let _ = sensor.events_drain(&mut [Event::FreeFall; 4]);
+    let _ = sensor.fifo_data_get(&mut [[0; 3]; 4]);
+    let _ = sensor.fifo_drain(&mut [0; 7 * 6 + 1], true);
+}
+
+#[test]
+fn every_register_value_decodes_without_panicking() {
+    for val in 0..=u8::MAX {
+        let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+        for reg in REGS {
+            sensor.bus.set_reg(reg, val);
+        }
+        decode_all(&mut sensor);
+    }
+}
+
+#[test]
+fn each_register_alone_decodes_without_panicking() {
+    for reg in REGS {
+        for val in 0..=u8::MAX {
+            let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+            sensor.bus.set_reg(reg, val);
+            decode_all(&mut sensor);
+        }
+    }
+}
+
+#[test]
+fn batch_index_past_the_end_is_none() {
+    // The 14-bit values are left-aligned
+    let data = [0x40, 0x00, 0x80, 0x00, 0xC0, 0x00];
+    let batch = SampleBatch::new(&data).unwrap();
+    assert_eq!(batch.raw(0), Some([0x10, 0x20, 0x30]));
+    assert_eq!(batch.raw(1), None);
+    assert_eq!(batch.raw(usize::MAX), None);
+    assert_eq!(batch.mg(1, Fs::_2g, Mode::HighPerformance), None);
+}
@@ -0,0 +1,177 @@
+use super::WaitLog;
+use crate::errata::Workarounds;
+use crate::mock::{Access, MockBus};
+use crate::prelude::*;
+use crate::settle::{self, SettleHint};
+use crate::{Error, Iis2dlpc};
+
+/// Driver running at 100 Hz in high-performance mode, its delay log cleared.
+fn running() -> Iis2dlpc<MockBus, WaitLog> {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), WaitLog::default());
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.tim = WaitLog::default();
+    sensor
+}
+
+#[test]
+fn same_mode_neither_writes_nor_waits() {
+    let mut sensor = running();
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl6),
+    ]);
+    assert_eq!(
+        sensor.power_mode_set(Mode::HighPerformance),
+        Ok(SettleHint::None)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.tim.waits, 0);
+}
+
+#[test]
+fn mode_change_waits_one_period() {
+    let mut sensor = running();
+    assert_eq!(
+        sensor.power_mode_set(Mode::ContLowPwr4),
+        Ok(SettleHint::None)
+    );
+    assert_eq!(sensor.tim.waits, 1);
+    assert_eq!(sensor.tim.total_ns, 10_000_000);
+
+    // Low noise alone is a mode change too
+    assert_eq!(
+        sensor.power_mode_set(Mode::ContLowPwrLowNoise4),
+        Ok(SettleHint::None)
+    );
+    assert_eq!(sensor.tim.waits, 2);
+}
+
+#[test]
+fn mode_change_writes_only_the_changed_register() {
+    let mut sensor = running();
+    let ctrl6 = Ctrl6::from_bits(sensor.bus.reg(Reg::Ctrl6));
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl6),
+        Access::write(Reg::Ctrl6, ctrl6.with_low_noise(1).into_bits()),
+    ]);
+    sensor
+        .power_mode_set(Mode::HighPerformanceLowNoise)
+        .unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn without_the_workaround_the_change_is_a_hint() {
+    let mut sensor = running();
+    sensor.workarounds_set(Workarounds::NONE);
+    assert_eq!(
+        sensor.power_mode_set(Mode::ContLowPwr2),
+        Ok(SettleHint::Samples(settle::PENDING_SAMPLES))
+    );
+    assert_eq!(
+        sensor.power_mode_set(Mode::ContLowPwr2),
+        Ok(SettleHint::None)
+    );
+    assert_eq!(sensor.tim.waits, 0);
+}
+
+#[test]
+fn powered_down_never_waits() {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), WaitLog::default());
+    assert_eq!(
+        sensor.power_mode_set(Mode::ContLowPwr12bit),
+        Ok(SettleHint::None)
+    );
+    assert_eq!(sensor.tim.waits, 0);
+}
+
+#[test]
+fn device_running_before_the_driver_waits_too() {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), WaitLog::default());
+    let ctrl1 = Ctrl1::new()
+        .with_mode(Mode::HighPerformance.mode())
+        .with_odr(Odr::_100hz.odr());
+    sensor.bus.set_reg(Reg::Ctrl1, ctrl1.into_bits());
+    sensor.power_mode_set(Mode::ContLowPwr4).unwrap();
+    assert_eq!(sensor.tim.waits, 1);
+    assert_eq!(sensor.tim.total_ns, 10_000_000);
+}
+
+#[test]
+fn raw_data_rate_writes_are_waited_on() {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), WaitLog::default());
+    let ctrl1 = Ctrl1::new().with_odr(Odr::_50hz.odr());
+    sensor
+        .write_to_register(Reg::Ctrl1 as u8, &[ctrl1.into_bits()])
+        .unwrap();
+    sensor
+        .settle_for(SettleHint::Samples(settle::PENDING_SAMPLES))
+        .unwrap();
+    assert_eq!(sensor.tim.waits, 1);
+    assert_eq!(
+        sensor.tim.total_ns,
+        20_000_000 * u64::from(settle::PENDING_SAMPLES)
+    );
+
+    sensor.write_to_register(Reg::Ctrl1 as u8, &[0x00]).unwrap();
+    sensor.bus.expect(&[]);
+    sensor
+        .settle_for(SettleHint::Samples(settle::PENDING_SAMPLES))
+        .unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.tim.waits, 1);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_same_mode_neither_writes_nor_waits() {
+    use super::block_on;
+    use crate::asynch::Iis2dlpcAsync;
+
+    let mut sensor = Iis2dlpcAsync::from_bus(MockBus::new(), WaitLog::default());
+    block_on(sensor.power_mode_set(Mode::HighPerformance)).unwrap();
+    block_on(sensor.data_rate_set(Odr::_100hz)).unwrap();
+    sensor.tim = WaitLog::default();
+
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl6),
+    ]);
+    assert_eq!(
+        block_on(sensor.power_mode_set(Mode::HighPerformance)),
+        Ok(SettleHint::None)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.tim.waits, 0);
+
+    block_on(sensor.power_mode_set(Mode::ContLowPwr4)).unwrap();
+    assert_eq!(sensor.tim.waits, 1);
+}
+
+#[test]
+fn combined_setter_refuses_a_pair_as_the_separate_setters_do() {
+    let mut sensor = running();
+    sensor.data_rate_set(Odr::_1_6khz).unwrap();
+    let refused = sensor.power_mode_set(Mode::ContLowPwr4);
+    assert_eq!(refused, Err(Error::InvalidArgument));
+
+    sensor.bus.expect(&[]);
+    assert_eq!(
+        sensor.odr_mode_set(Mode::ContLowPwr4, Odr::_1_6khz),
+        refused
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // Lowering the data rate first lets the mode follow
+    sensor.data_rate_set(Odr::_200hz).unwrap();
+    sensor.power_mode_set(Mode::ContLowPwr4).unwrap();
+    assert_eq!(sensor.power_mode_get(), Ok(Mode::ContLowPwr4));
+}
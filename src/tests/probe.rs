@@ -0,0 +1,125 @@
+use embedded_hal::i2c::{self, I2c};
+use embedded_hal::spi::{self, SpiDevice};
+
+use crate::prelude::*;
+use crate::{I2CAddress, ID, probe_who_am_i, probe_who_am_i_spi};
+
+/// I2C peripheral answering every read with `WHO_AM_I`, recording the shape of the transactions.
+#[derive(Default)]
+struct FakeI2c {
+    transactions: usize,
+    address: u8,
+    /// Kind and length of each operation of the last transaction: `true` for a write.
+    ops: [Option<(bool, usize)>; 4],
+    written: u8,
+    fail: bool,
+}
+
+impl i2c::ErrorType for FakeI2c {
+    type Error = i2c::ErrorKind;
+}
+
+impl I2c for FakeI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transactions += 1;
+        self.address = address;
+        self.ops = [None; 4];
+        for (slot, op) in self.ops.iter_mut().zip(operations.iter_mut()) {
+            match op {
+                i2c::Operation::Write(bytes) => {
+                    self.written = bytes.first().copied().unwrap_or_default();
+                    *slot = Some((true, bytes.len()));
+                }
+                i2c::Operation::Read(buf) => {
+                    buf.fill(ID);
+                    *slot = Some((false, buf.len()));
+                }
+            }
+        }
+        if self.fail {
+            return Err(i2c::ErrorKind::NoAcknowledge(
+                i2c::NoAcknowledgeSource::Address,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// SPI device answering every read with `WHO_AM_I`, recording the shape of the transactions.
+#[derive(Default)]
+struct FakeSpi {
+    transactions: usize,
+    /// Kind and length of each operation of the last transaction: `true` for a write.
+    ops: [Option<(bool, usize)>; 4],
+    written: u8,
+}
+
+impl spi::ErrorType for FakeSpi {
+    type Error = spi::ErrorKind;
+}
+
+impl SpiDevice for FakeSpi {
+    fn transaction(
+        &mut self,
+        operations: &mut [spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        self.transactions += 1;
+        self.ops = [None; 4];
+        for (slot, op) in self.ops.iter_mut().zip(operations.iter_mut()) {
+            match op {
+                spi::Operation::Write(bytes) => {
+                    self.written = bytes.first().copied().unwrap_or_default();
+                    *slot = Some((true, bytes.len()));
+                }
+                spi::Operation::Read(buf) => {
+                    buf.fill(ID);
+                    *slot = Some((false, buf.len()));
+                }
+                _ => return Err(spi::ErrorKind::Other),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn i2c_probe_is_one_write_read() {
+    let mut i2c = FakeI2c::default();
+    assert_eq!(probe_who_am_i(&mut i2c, I2CAddress::I2cAddH), Ok(ID));
+    assert_eq!(i2c.transactions, 1);
+    assert_eq!(i2c.address, I2CAddress::I2cAddH as u8);
+    assert_eq!(i2c.ops, [Some((true, 1)), Some((false, 1)), None, None]);
+    assert_eq!(i2c.written, Reg::WhoAmI as u8);
+}
+
+#[test]
+fn i2c_probe_can_be_retried() {
+    let mut i2c = FakeI2c {
+        fail: true,
+        ..FakeI2c::default()
+    };
+    assert_eq!(
+        probe_who_am_i(&mut i2c, I2CAddress::I2cAddL),
+        Err(i2c::ErrorKind::NoAcknowledge(
+            i2c::NoAcknowledgeSource::Address
+        ))
+    );
+    // The peripheral is still owned by the caller
+    i2c.fail = false;
+    assert_eq!(probe_who_am_i(&mut i2c, I2CAddress::I2cAddL), Ok(ID));
+    assert_eq!(i2c.transactions, 2);
+    assert_eq!(i2c.address, I2CAddress::I2cAddL as u8);
+}
+
+#[test]
+fn spi_probe_is_one_transaction_with_the_read_bit() {
+    let mut spi = FakeSpi::default();
+    assert_eq!(probe_who_am_i_spi(&mut spi), Ok(ID));
+    assert_eq!(spi.transactions, 1);
+    assert_eq!(spi.ops, [Some((true, 1)), Some((false, 1)), None, None]);
+    assert_eq!(spi.written, Reg::WhoAmI as u8 | 0x80);
+}
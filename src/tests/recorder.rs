@@ -0,0 +1,127 @@
+use crate::Iis2dlpc;
+use crate::mock::{MockBus, NoDelay};
+use crate::prelude::*;
+use crate::transaction::{
+    RECORD_PAYLOAD, Recorder, RecordingBus, TransactionInfo, TransactionKind, TransactionRecord,
+};
+use crate::{BusOperation, Error};
+
+/// Bus failing every transfer.
+struct FailingBus;
+
+impl BusOperation for FailingBus {
+    type Error = ();
+
+    fn read_bytes(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+        Err(())
+    }
+
+    fn write_bytes(&mut self, _: &[u8]) -> Result<(), Self::Error> {
+        Err(())
+    }
+
+    fn write_byte_read_bytes(&mut self, _: &[u8; 1], _: &mut [u8]) -> Result<(), Self::Error> {
+        Err(())
+    }
+}
+
+fn seqs<const N: usize>(recorder: &Recorder<N>) -> [u32; N] {
+    let mut seqs = [u32::MAX; N];
+    for (slot, record) in seqs.iter_mut().zip(recorder.records()) {
+        *slot = record.seq;
+    }
+    seqs
+}
+
+#[test]
+fn ring_wraps_around_oldest_first() {
+    let mut sensor = Iis2dlpc::from_bus(RecordingBus::<_, 4>::new(MockBus::new()), NoDelay);
+    assert_eq!(sensor.bus.recorder().records().count(), 0);
+
+    for _ in 0..3 {
+        sensor.device_id_get().unwrap();
+    }
+    assert_eq!(seqs(sensor.bus.recorder()), [0, 1, 2, u32::MAX]);
+
+    // Past the capacity, the oldest records are overwritten
+    for _ in 0..7 {
+        sensor.device_id_get().unwrap();
+    }
+    assert_eq!(seqs(sensor.bus.recorder()), [6, 7, 8, 9]);
+    let record = sensor.bus.recorder().records().last().unwrap();
+    assert_eq!(
+        record.info,
+        Some(TransactionInfo {
+            kind: TransactionKind::Read,
+            reg: Reg::WhoAmI as u8,
+            len: 1,
+        })
+    );
+    assert!(record.ok);
+    assert_eq!(record.payload(), [crate::ID]);
+
+    sensor.bus.recorder_mut().clear();
+    assert_eq!(sensor.bus.recorder().records().count(), 0);
+}
+
+#[test]
+fn long_transfers_are_truncated() {
+    let mut sensor = Iis2dlpc::from_bus(RecordingBus::<_, 2>::new(MockBus::new()), NoDelay);
+    sensor.bus.inner_mut().set_reg(Reg::Ctrl1, 0x50);
+    let mut buf = [0; 14];
+    sensor
+        .read_from_register(Reg::Ctrl1 as u8, &mut buf)
+        .unwrap();
+    sensor.write_to_register(Reg::Ctrl1 as u8, &[0x40]).unwrap();
+
+    let [long, short]: [&TransactionRecord; 2] = {
+        let mut records = sensor.bus.recorder().records();
+        [records.next().unwrap(), records.next().unwrap()]
+    };
+    assert!(long.truncated());
+    assert_eq!(long.payload().len(), RECORD_PAYLOAD);
+    assert_eq!(long.payload(), &buf[..RECORD_PAYLOAD]);
+    assert!(!short.truncated());
+    assert_eq!(
+        short.info.map(|info| info.kind),
+        Some(TransactionKind::Write)
+    );
+    assert_eq!(short.payload(), [0x40]);
+}
+
+#[test]
+fn failed_transfers_are_marked() {
+    let mut sensor = Iis2dlpc::from_bus(RecordingBus::<_, 4>::new(FailingBus), NoDelay);
+    assert_eq!(sensor.device_id_get(), Err(Error::Bus(())));
+    assert_eq!(
+        sensor.write_to_register(Reg::Ctrl1 as u8, &[0x40]),
+        Err(Error::Bus(()))
+    );
+
+    let (_, recorder) = sensor.release_without_powerdown().0.release();
+    let mut records = recorder.records();
+    let read = records.next().unwrap();
+    assert!(!read.ok);
+    // The bytes of a failed read are not kept, those of a failed write are
+    assert_eq!(read.data, [0; RECORD_PAYLOAD]);
+    let write = records.next().unwrap();
+    assert!(!write.ok);
+    assert_eq!(write.payload(), [0x40]);
+    assert!(records.next().is_none());
+}
+
+#[test]
+fn released_recorder_goes_on() {
+    let mut sensor = Iis2dlpc::from_bus(RecordingBus::<_, 4>::new(MockBus::new()), NoDelay);
+    sensor.device_id_get().unwrap();
+    let (bus, recorder) = sensor.release_without_powerdown().0.release();
+
+    let mut sensor = Iis2dlpc::from_bus(RecordingBus::with_recorder(bus, recorder), NoDelay);
+    sensor.device_id_get().unwrap();
+    assert_eq!(seqs(sensor.bus.recorder()), [0, 1, u32::MAX, u32::MAX]);
+
+    // A recorder of capacity 0 logs nothing
+    let mut sensor = Iis2dlpc::from_bus(RecordingBus::<_, 0>::new(MockBus::new()), NoDelay);
+    sensor.device_id_get().unwrap();
+    assert_eq!(sensor.bus.recorder().records().count(), 0);
+}
@@ -0,0 +1,151 @@
+use super::sensor;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::timing::FieldRetune;
+use crate::{ConfigError, Error, Iis2dlpc};
+
+/// Driver running at `odr` in high-performance mode, with every duration field set.
+fn running(
+    odr: Odr,
+    wake: u8,
+    sleep: u8,
+    shock: u8,
+    quiet: u8,
+    latency: u8,
+    ff: u8,
+) -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(odr).unwrap();
+    sensor.wkup_dur_set(wake).unwrap();
+    sensor.act_sleep_dur_set(sleep).unwrap();
+    sensor.tap_shock_set(shock).unwrap();
+    sensor.tap_quiet_set(quiet).unwrap();
+    sensor.tap_dur_set(latency).unwrap();
+    sensor.ff_dur_set(ff).unwrap();
+    sensor
+}
+
+/// The field codes as read back through the getters.
+fn codes(sensor: &mut Iis2dlpc<MockBus, NoDelay>) -> [u8; 6] {
+    [
+        sensor.wkup_dur_get().unwrap(),
+        sensor.act_sleep_dur_get().unwrap(),
+        sensor.tap_shock_get().unwrap(),
+        sensor.tap_quiet_get().unwrap(),
+        sensor.tap_dur_get().unwrap(),
+        sensor.ff_dur_get().unwrap(),
+    ]
+}
+
+#[test]
+fn doubling_the_rate_doubles_the_codes() {
+    let mut sensor = running(Odr::_100hz, 1, 1, 1, 1, 2, 10);
+    let report = sensor.data_rate_set_retuned(Odr::_200hz).unwrap();
+
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::_200hz));
+    assert_eq!(codes(&mut sensor), [2, 2, 2, 2, 4, 20]);
+    assert_eq!(
+        report.ff_dur,
+        FieldRetune {
+            old_code: 10,
+            new_code: 20,
+            old_us: 100_000,
+            new_us: 100_000,
+            truncated: false,
+        }
+    );
+    assert_eq!(report.sleep_dur.new_us, 5_120_000);
+    assert_eq!(report.latency.new_us, 640_000);
+}
+
+#[test]
+fn durations_past_the_fields_are_clamped_and_reported() {
+    // 30 ms of wake-up and 630 ms of free fall are out of reach at 800 Hz
+    let mut sensor = running(Odr::_100hz, 3, 0, 0, 0, 0, 63);
+    let report = sensor.data_rate_set_retuned(Odr::_800hz).unwrap();
+
+    assert_eq!(
+        (report.wake_dur.new_code, report.wake_dur.truncated),
+        (3, true)
+    );
+    assert_eq!(report.wake_dur.new_us, 3_750);
+    assert_eq!(
+        (report.ff_dur.new_code, report.ff_dur.truncated),
+        (63, true)
+    );
+    assert_eq!(report.ff_dur.new_us, 78_750);
+
+    // The code 0 of shock, quiet and latency is 4, 2 and 16 periods: 32, 16 and 128 periods at the new rate
+    assert_eq!(
+        (
+            report.shock.new_code,
+            report.shock.new_us,
+            report.shock.truncated
+        ),
+        (3, 30_000, true)
+    );
+    assert_eq!(
+        (
+            report.quiet.new_code,
+            report.quiet.new_us,
+            report.quiet.truncated
+        ),
+        (3, 15_000, true)
+    );
+    assert_eq!(
+        (
+            report.latency.new_code,
+            report.latency.new_us,
+            report.latency.truncated
+        ),
+        (4, 160_000, false)
+    );
+}
+
+#[test]
+fn free_fall_code_is_split_across_the_two_registers() {
+    // 40 periods at 100 Hz are 80 at 200 Hz, clamped to 63
+    let mut sensor = running(Odr::_100hz, 0, 0, 0, 0, 0, 40);
+    let report = sensor.data_rate_set_retuned(Odr::_200hz).unwrap();
+    assert_eq!(
+        (report.ff_dur.new_code, report.ff_dur.truncated),
+        (63, true)
+    );
+    let high = WakeUpDur::from_bits(sensor.bus.reg(Reg::WakeUpDur)).ff_dur();
+    let low = FreeFall::from_bits(sensor.bus.reg(Reg::FreeFall)).ff_dur();
+    assert_eq!((high, low), (1, 0x1F));
+}
+
+#[test]
+fn slowing_down_picks_the_nearest_code() {
+    // 210 ms at 12.5 Hz: 160 ms (2) or 240 ms (3)
+    let mut sensor = running(Odr::_100hz, 0, 0, 0, 0, 0, 21);
+    let report = sensor.data_rate_set_retuned(Odr::_12_5hz).unwrap();
+    assert_eq!((report.ff_dur.new_code, report.ff_dur.new_us), (3, 240_000));
+    assert!(!report.ff_dur.truncated);
+    assert_eq!(sensor.ff_dur_get(), Ok(3));
+}
+
+#[test]
+fn retuning_needs_two_running_data_rates() {
+    let mut sensor = sensor();
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.data_rate_set_retuned(Odr::_100hz),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    let mut sensor = running(Odr::_100hz, 1, 1, 1, 1, 1, 1);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.data_rate_set_retuned(Odr::SetSwTrig),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
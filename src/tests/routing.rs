@@ -0,0 +1,209 @@
+use super::sensor;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::{ConfigError, Error, Iis2dlpc, TempPolicy};
+
+/// `CTRL7` with `interrupts_enable` as given, and a user offset weight to check the other fields are kept.
+fn ctrl7(interrupts_enable: u8) -> u8 {
+    Ctrl7::new()
+        .with_usr_off_w(1)
+        .with_interrupts_enable(interrupts_enable)
+        .into_bits()
+}
+
+/// Driver whose `CTRL7` holds a user offset weight.
+fn weighted() -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.bus.set_reg(Reg::Ctrl7, ctrl7(0));
+    sensor
+}
+
+#[test]
+fn int1_enable_disables_the_functions_meanwhile() {
+    let mut sensor = weighted();
+    let wake_up = Ctrl4Int1PadCtrl::new().with_int1_wu(1).into_bits();
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl4Int1PadCtrl),
+        Access::read(Reg::Ctrl5Int2PadCtrl),
+        Access::read(Reg::Ctrl7),
+        Access::write(Reg::Ctrl7, ctrl7(0)),
+        Access::write(Reg::Ctrl4Int1PadCtrl, wake_up),
+        Access::write(Reg::Ctrl7, ctrl7(1)),
+    ]);
+    sensor
+        .int1_route_update_critical(|int1| int1.set_int1_wu(1))
+        .unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.bus.reg(Reg::Ctrl7), ctrl7(1));
+}
+
+#[test]
+fn int1_disable_leaves_the_functions_off() {
+    let mut sensor = weighted();
+    sensor
+        .int1_route_update_critical(|int1| int1.set_int1_wu(1))
+        .unwrap();
+
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl4Int1PadCtrl),
+        Access::read(Reg::Ctrl5Int2PadCtrl),
+        Access::read(Reg::Ctrl7),
+        Access::write(Reg::Ctrl7, ctrl7(0)),
+        Access::write(Reg::Ctrl4Int1PadCtrl, 0),
+        Access::write(Reg::Ctrl7, ctrl7(0)),
+    ]);
+    sensor
+        .int1_route_update_critical(|int1| int1.set_int1_wu(0))
+        .unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(sensor.pin_int1_route_get().unwrap().into_bits(), 0);
+}
+
+#[test]
+fn enable_follows_the_routing_of_both_pads() {
+    let mut sensor = weighted();
+    sensor
+        .int2_route_update_critical(|int2| int2.set_int2_sleep_chg(1))
+        .unwrap();
+    assert_eq!(sensor.bus.reg(Reg::Ctrl7), ctrl7(1));
+
+    // The INT2 event still needs the embedded functions
+    sensor
+        .int1_route_update_critical(|int1| int1.set_int1_ff(1))
+        .unwrap();
+    sensor
+        .int1_route_update_critical(|int1| int1.set_int1_ff(0))
+        .unwrap();
+    assert_eq!(sensor.bus.reg(Reg::Ctrl7), ctrl7(1));
+
+    // The data-ready signal does not
+    sensor
+        .int2_route_update_critical(|int2| {
+            int2.set_int2_sleep_chg(0);
+            int2.set_int2_drdy(1);
+        })
+        .unwrap();
+    assert_eq!(sensor.bus.reg(Reg::Ctrl7), ctrl7(0));
+}
+
+#[test]
+fn int2_enable_and_disable_order_the_writes() {
+    let mut sensor = weighted();
+    let sleep = Ctrl5Int2PadCtrl::new().with_int2_sleep_state(1).into_bits();
+    for (routes, enable) in [(sleep, 1), (0, 0)] {
+        sensor.bus.expect(&[
+            Access::read(Reg::Ctrl4Int1PadCtrl),
+            Access::read(Reg::Ctrl5Int2PadCtrl),
+            Access::read(Reg::Ctrl7),
+            Access::write(Reg::Ctrl7, ctrl7(0)),
+            Access::write(Reg::Ctrl5Int2PadCtrl, routes),
+            Access::write(Reg::Ctrl7, ctrl7(enable)),
+        ]);
+        sensor
+            .int2_route_update_critical(|int2| int2.set_int2_sleep_state(enable))
+            .unwrap();
+        assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    }
+}
+
+#[test]
+fn ignored_temperature_is_not_routed() {
+    let mut sensor = weighted();
+    sensor.temp_policy_set(TempPolicy::Ignore);
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl4Int1PadCtrl),
+        Access::read(Reg::Ctrl5Int2PadCtrl),
+        Access::read(Reg::Ctrl7),
+    ]);
+    assert_eq!(
+        sensor.int2_route_update_critical(|int2| int2.set_int2_drdy_t(1)),
+        Err(Error::InvalidConfig(ConfigError::TemperatureIgnored))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn shadow_follows_the_route_setters() {
+    let mut sensor = sensor();
+    assert!(!sensor.int1_routes_shadow().shadow_valid);
+    assert!(!sensor.int2_routes_shadow().shadow_valid);
+
+    let int1 = Ctrl4Int1PadCtrl::new().with_int1_ff(1);
+    sensor.pin_int1_route_set(&int1).unwrap();
+    let int2 = Ctrl5Int2PadCtrl::new().with_int2_drdy(1);
+    sensor.pin_int2_route_set(&int2).unwrap();
+
+    // No bus access to answer
+    sensor.bus.expect(&[]);
+    let shadow = sensor.int1_routes_shadow();
+    assert!(shadow.shadow_valid);
+    assert_eq!(shadow.routes, int1);
+    assert_eq!(sensor.int2_routes_shadow().routes, int2);
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    sensor
+        .int1_route_update_critical(|int1| int1.set_int1_wu(1))
+        .unwrap();
+    assert_eq!(sensor.int1_routes_shadow().routes, int1.with_int1_wu(1));
+}
+
+#[test]
+fn reads_reload_the_shadow() {
+    let mut sensor = sensor();
+    let int1 = Ctrl4Int1PadCtrl::new().with_int1_6d(1);
+    let int2 = Ctrl5Int2PadCtrl::new().with_int2_sleep_chg(1);
+    sensor.bus.set_reg(Reg::Ctrl4Int1PadCtrl, int1.into_bits());
+    sensor.bus.set_reg(Reg::Ctrl5Int2PadCtrl, int2.into_bits());
+
+    sensor.pin_int1_route_get().unwrap();
+    assert_eq!(sensor.int1_routes_shadow().routes, int1);
+    assert!(!sensor.int2_routes_shadow().shadow_valid);
+
+    // A burst covering the register counts too
+    let mut buf = [0; 6];
+    sensor
+        .read_from_register(Reg::Ctrl1 as u8, &mut buf)
+        .unwrap();
+    let shadow = sensor.int2_routes_shadow();
+    assert!(shadow.shadow_valid);
+    assert_eq!(shadow.routes, int2);
+}
+
+#[test]
+fn raw_writes_and_resets_invalidate_the_shadow() {
+    let mut sensor = sensor();
+    sensor.pin_int1_route_get().unwrap();
+    sensor.pin_int2_route_get().unwrap();
+
+    sensor
+        .write_to_register(Reg::Ctrl4Int1PadCtrl as u8, &[0x01])
+        .unwrap();
+    assert!(!sensor.int1_routes_shadow().shadow_valid);
+    assert!(sensor.int2_routes_shadow().shadow_valid);
+    sensor
+        .write_to_register(Reg::Ctrl5Int2PadCtrl as u8, &[0x01])
+        .unwrap();
+    assert!(!sensor.int2_routes_shadow().shadow_valid);
+
+    sensor.pin_int1_route_get().unwrap();
+    sensor.reset_set().unwrap();
+    assert!(!sensor.int1_routes_shadow().shadow_valid);
+
+    sensor.pin_int1_route_get().unwrap();
+    sensor.invalidate_cache();
+    assert!(!sensor.int1_routes_shadow().shadow_valid);
+}
+
+#[test]
+fn snapshot_apply_sets_the_shadow() {
+    let mut sensor = sensor();
+    let int1 = Ctrl4Int1PadCtrl::new().with_int1_tap(1);
+    sensor.pin_int1_route_set(&int1).unwrap();
+    let snapshot = sensor.config_snapshot_get().unwrap();
+
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.config_snapshot_apply(&snapshot).unwrap();
+    let shadow = sensor.int1_routes_shadow();
+    assert!(shadow.shadow_valid);
+    assert_eq!(shadow.routes, int1);
+}
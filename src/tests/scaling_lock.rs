@@ -0,0 +1,173 @@
+use core::convert::Infallible;
+
+use super::sensor;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::snapshot::ConfigSnapshot;
+use crate::{Error, Iis2dlpc};
+
+type Sensor = Iis2dlpc<MockBus, NoDelay>;
+
+/// A configuration change through the driver.
+type Change = fn(&mut Sensor) -> Result<(), Error<Infallible>>;
+
+/// Driver running at 100 Hz in high-performance mode at ±4g, its scaling locked.
+fn locked() -> Sensor {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.full_scale_set(Fs::_4g).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.strict_scaling_lock(true).unwrap();
+    sensor
+}
+
+/// The content of `CTRL1` and `CTRL6`, where the scaling fields live.
+fn scaling_regs(sensor: &Sensor) -> [u8; 2] {
+    [sensor.bus.reg(Reg::Ctrl1), sensor.bus.reg(Reg::Ctrl6)]
+}
+
+#[test]
+fn every_scaling_change_is_refused_without_a_write() {
+    let changes: [Change; 9] = [
+        |s| s.full_scale_set(Fs::_8g).map(|_| ()),
+        |s| s.power_mode_set(Mode::ContLowPwr4).map(|_| ()),
+        |s| s.power_mode_set(Mode::HighPerformanceLowNoise).map(|_| ()),
+        |s| s.odr_mode_set(Mode::ContLowPwr2, Odr::_100hz).map(|_| ()),
+        |s| {
+            let val = Ctrl1::from_bits(s.bus.reg(Reg::Ctrl1)).with_lp_mode(2);
+            s.write_to_register(Reg::Ctrl1 as u8, &[val.into_bits()])
+        },
+        |s| {
+            // A burst from CTRL1 to CTRL6 changing the full scale only
+            let mut burst = [
+                Reg::Ctrl1,
+                Reg::Ctrl2,
+                Reg::Ctrl3,
+                Reg::Ctrl4Int1PadCtrl,
+                Reg::Ctrl5Int2PadCtrl,
+                Reg::Ctrl6,
+            ]
+            .map(|reg| s.bus.reg(reg));
+            let ctrl6 = Ctrl6::from_bits(s.bus.reg(Reg::Ctrl6));
+            burst[5] = ctrl6.with_fs(Fs::_16g as u8).into_bits();
+            s.write_to_register(Reg::Ctrl1 as u8, &burst)
+        },
+        |s| s.reset_set(),
+        |s| s.boot_set(),
+        |s| {
+            let mut image = [0; REG_MAP_LEN];
+            image[Reg::Ctrl6 as usize] = Ctrl6::new().with_fs(Fs::_2g as u8).into_bits();
+            let snapshot = ConfigSnapshot::from_image(&image);
+            s.config_snapshot_apply(&snapshot)
+        },
+    ];
+
+    for (i, change) in changes.into_iter().enumerate() {
+        let mut sensor = locked();
+        let before = scaling_regs(&sensor);
+        assert_eq!(change(&mut sensor), Err(Error::ScalingLocked), "change {i}");
+        assert_eq!(scaling_regs(&sensor), before, "change {i}");
+        assert_eq!(sensor.full_scale_get(), Ok(Fs::_4g), "change {i}");
+    }
+}
+
+#[test]
+fn refused_raw_write_touches_no_register() {
+    let mut sensor = locked();
+    let ctrl6 = Ctrl6::from_bits(sensor.bus.reg(Reg::Ctrl6)).with_low_noise(1);
+    sensor.bus.expect(&[]);
+    assert_eq!(
+        sensor.write_to_register(Reg::Ctrl6 as u8, &[ctrl6.into_bits()]),
+        Err(Error::ScalingLocked)
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn unrelated_setters_pass_through() {
+    let mut sensor = locked();
+    sensor.data_rate_set(Odr::_400hz).unwrap();
+    sensor.filter_bandwidth_set(BwFilt::OdrDiv4).unwrap();
+    sensor.wkup_threshold_set(12).unwrap();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    // Writing the locked scaling again is no change
+    sensor.full_scale_set(Fs::_4g).unwrap();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::_400hz));
+    assert_eq!(sensor.filter_bandwidth_get(), Ok(BwFilt::OdrDiv4));
+    assert!(sensor.scaling_locked());
+}
+
+#[test]
+fn sanctioned_change_moves_the_lock() {
+    let mut sensor = locked();
+    assert_eq!(sensor.config_epoch(), 0);
+    sensor
+        .scaling_unlock_and_apply(false, |s| s.full_scale_set(Fs::_8g))
+        .unwrap();
+    assert_eq!(sensor.config_epoch(), 1);
+    assert!(sensor.scaling_locked());
+    assert_eq!(sensor.full_scale_get(), Ok(Fs::_8g));
+
+    // The new scaling is the locked one
+    assert_eq!(
+        sensor.full_scale_set(Fs::_4g).map(|_| ()),
+        Err(Error::ScalingLocked)
+    );
+    sensor.full_scale_set(Fs::_8g).unwrap();
+
+    sensor.strict_scaling_lock(false).unwrap();
+    sensor.full_scale_set(Fs::_2g).unwrap();
+    assert_eq!(sensor.config_epoch(), 1);
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn failed_change_bumps_the_epoch_and_keeps_the_lock() {
+    let mut sensor = locked();
+    assert_eq!(
+        sensor.scaling_unlock_and_apply(false, |s| s.wkup_threshold_set(0x40)),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(sensor.config_epoch(), 1);
+    assert!(sensor.scaling_locked());
+}
+
+#[test]
+fn change_without_a_lock_sets_none() {
+    let mut sensor = sensor();
+    sensor
+        .scaling_unlock_and_apply(false, |s| s.full_scale_set(Fs::_16g))
+        .unwrap();
+    assert!(!sensor.scaling_locked());
+    assert_eq!(sensor.config_epoch(), 1);
+}
+
+#[test]
+fn sanctioned_change_can_flush_the_fifo() {
+    let mut sensor = locked();
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    let bypass = FifoCtrl::new().with_fmode(Fmode::BypassMode as u8);
+    let stream = FifoCtrl::new().with_fmode(Fmode::StreamMode as u8);
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl6),
+        Access::write(Reg::Ctrl6, Ctrl6::new().with_fs(Fs::_8g as u8).into_bits()),
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        // The flush
+        Access::read(Reg::FifoCtrl),
+        Access::read(Reg::FifoCtrl),
+        Access::write(Reg::FifoCtrl, bypass.into_bits()),
+        Access::read(Reg::FifoCtrl),
+        Access::write(Reg::FifoCtrl, stream.into_bits()),
+        // The new lock
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl6),
+    ]);
+    sensor
+        .scaling_unlock_and_apply(true, |s| s.full_scale_set(Fs::_8g))
+        .unwrap();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
@@ -0,0 +1,293 @@
+use super::sensor;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::snapshot::{CONFIG_REGS, ConfigSnapshot};
+use crate::{BusOperation, Error, Iis2dlpc};
+
+/// Driver running at 100 Hz in high-performance mode, with a wake-up configuration, and its snapshot.
+fn configured() -> (Iis2dlpc<MockBus, NoDelay>, ConfigSnapshot) {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.full_scale_set(Fs::_8g).unwrap();
+    WakeUpThs::new().with_wk_ths(12).write(&mut sensor).unwrap();
+    WakeUpDur::new()
+        .with_wake_dur(2)
+        .write(&mut sensor)
+        .unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    let snapshot = sensor.config_snapshot_get().unwrap();
+    (sensor, snapshot)
+}
+
+#[test]
+fn snapshot_round_trips_through_a_reset_device() {
+    let (mut sensor, snapshot) = configured();
+    assert_eq!(snapshot.data_rate(), Odr::_100hz);
+
+    sensor.bus = MockBus::new();
+    sensor.config_snapshot_apply(&snapshot).unwrap();
+    assert_eq!(sensor.config_snapshot_get(), Ok(snapshot));
+    assert_eq!(sensor.full_scale_get(), Ok(Fs::_8g));
+    assert_eq!(WakeUpThs::read(&mut sensor).unwrap().wk_ths(), 12);
+}
+
+#[test]
+fn command_bits_are_not_written_back() {
+    let mut image = [0; REG_MAP_LEN];
+    image[Reg::Ctrl2 as usize] = Ctrl2::new()
+        .with_soft_reset(1)
+        .with_boot(1)
+        .with_if_add_inc(1)
+        .into_bits();
+    let snapshot = ConfigSnapshot::from_image(&image);
+
+    let mut sensor = sensor();
+    sensor.config_snapshot_apply(&snapshot).unwrap();
+    let ctrl2 = Ctrl2::from_bits(sensor.bus.reg(Reg::Ctrl2));
+    assert_eq!((ctrl2.soft_reset(), ctrl2.boot()), (0, 0));
+    assert_eq!(ctrl2.if_add_inc(), 1);
+}
+
+/// `CTRL1` of a device running at 100 Hz in high-performance mode.
+fn running_ctrl1() -> u8 {
+    Ctrl1::new()
+        .with_mode(Mode::HighPerformance.mode())
+        .with_odr(Odr::_100hz.odr())
+        .into_bits()
+}
+
+#[test]
+fn powered_down_driver_never_checks() {
+    let mut sensor = sensor();
+    sensor.data_rate_set(Odr::Off).unwrap();
+    sensor.bus.expect(&[]);
+    assert_eq!(sensor.brownout_check(), Ok(false));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn driver_on_a_running_device_learns_the_data_rate() {
+    let mut sensor = sensor();
+    sensor.bus.set_reg(Reg::Ctrl1, running_ctrl1());
+    sensor.bus.expect(&[Access::read(Reg::Ctrl1)]);
+    assert_eq!(sensor.brownout_check(), Ok(false));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // Reported once: the read shows the data rate off
+    sensor.bus = MockBus::new();
+    assert_eq!(sensor.brownout_check(), Ok(true));
+    sensor.bus.expect(&[]);
+    assert_eq!(sensor.brownout_check(), Ok(false));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn raw_data_rate_writes_are_tracked() {
+    let mut sensor = sensor();
+    sensor
+        .write_to_register(Reg::Ctrl1 as u8, &[running_ctrl1()])
+        .unwrap();
+    sensor.bus = MockBus::new();
+    assert_eq!(sensor.brownout_check(), Ok(true));
+
+    sensor
+        .write_to_register(Reg::Ctrl1 as u8, &[running_ctrl1()])
+        .unwrap();
+    sensor.write_to_register(Reg::Ctrl1 as u8, &[0x00]).unwrap();
+    sensor.bus.expect(&[]);
+    assert_eq!(sensor.brownout_check(), Ok(false));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn configuration_in_place_is_one_read() {
+    let (mut sensor, _) = configured();
+    sensor.bus.expect(&[Access::read(Reg::Ctrl1)]);
+    assert_eq!(sensor.brownout_check(), Ok(false));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[cfg(feature = "events")]
+#[test]
+fn brownout_is_detected_and_recovered() {
+    let (mut sensor, snapshot) = configured();
+
+    // The supply dips: the device is back to its reset values
+    sensor.bus = MockBus::new();
+    assert_eq!(sensor.brownout_check(), Ok(true));
+    assert!(!sensor.int1_routes_shadow().shadow_valid);
+
+    // One period at 100 Hz
+    assert_eq!(sensor.power_cycle_recover(&snapshot), Ok(10_000));
+    assert_eq!(sensor.config_snapshot_get(), Ok(snapshot));
+    assert_eq!(sensor.brownout_check(), Ok(false));
+}
+
+#[test]
+fn stuck_bus_is_not_taken_for_a_brownout() {
+    let (mut sensor, _) = configured();
+    for reg in Reg::ALL {
+        sensor.bus.set_reg(reg, 0x00);
+    }
+    assert_eq!(sensor.brownout_check(), Err(Error::UnexpectedValue(0x00)));
+
+    // The stuck read does not count as the device's data rate
+    sensor.bus = MockBus::new();
+    assert_eq!(sensor.brownout_check(), Ok(true));
+}
+
+#[test]
+fn recovery_checks_the_identifier_first() {
+    let (mut sensor, snapshot) = configured();
+    sensor.bus = MockBus::new();
+    sensor.bus.set_reg(Reg::WhoAmI, 0x33);
+    sensor.bus.expect(&[Access::read(Reg::WhoAmI)]);
+    assert_eq!(
+        sensor.power_cycle_recover(&snapshot),
+        Err(Error::WhoAmIError(0x33))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn data_ready_poll_heals_when_enabled() {
+    let (mut sensor, snapshot) = configured();
+
+    // Disabled by default: the poll only reports the flag
+    sensor.bus = MockBus::new();
+    assert_eq!(sensor.flag_data_ready_get(), Ok(0));
+    assert_eq!(sensor.bus.reg(Reg::Ctrl1), 0x00);
+
+    sensor.brownout_recovery_set(Some(snapshot));
+    assert_eq!(sensor.flag_data_ready_get(), Ok(0));
+    assert_eq!(sensor.config_snapshot_get(), Ok(snapshot));
+}
+
+/// Mock bus failing the writes whose index is set in `fail`, and the reads once `fail_reads` is set.
+struct FlakyBus {
+    mock: MockBus,
+    fail: u64,
+    fail_reads: bool,
+    writes: u32,
+    written: [Option<Reg>; 64],
+}
+
+impl FlakyBus {
+    fn new(mock: MockBus, fail: u64) -> Self {
+        Self {
+            mock,
+            fail,
+            fail_reads: false,
+            writes: 0,
+            written: [None; 64],
+        }
+    }
+
+    /// The register of write `i`.
+    fn written(&self, i: u32) -> Reg {
+        self.written.get(i as usize).copied().flatten().unwrap()
+    }
+}
+
+impl BusOperation for FlakyBus {
+    type Error = ();
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        let Ok(()) = self.mock.read_bytes(rbuf);
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        let index = self.writes;
+        self.writes += 1;
+        if let Some(slot) = self.written.get_mut(index as usize) {
+            *slot = wbuf
+                .first()
+                .and_then(|addr| Reg::ALL.into_iter().find(|reg| *reg as u8 == *addr));
+        }
+        if self.fail & (1 << index) != 0 {
+            return Err(());
+        }
+        let Ok(()) = self.mock.write_bytes(wbuf);
+        Ok(())
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        if self.fail_reads {
+            return Err(());
+        }
+        let Ok(()) = self.mock.write_byte_read_bytes(wbuf, rbuf);
+        Ok(())
+    }
+}
+
+/// Driver holding the reset values on a bus failing the writes set in `fail`, and the snapshot of [`configured`].
+fn flaky(fail: u64) -> (Iis2dlpc<FlakyBus, NoDelay>, ConfigSnapshot) {
+    let (_, snapshot) = configured();
+    let sensor = Iis2dlpc::from_bus(FlakyBus::new(MockBus::new(), fail), NoDelay);
+    (sensor, snapshot)
+}
+
+#[test]
+fn failure_at_each_write_is_rolled_back() {
+    for failing in 0..CONFIG_REGS.len() as u32 {
+        let (mut sensor, snapshot) = flaky(1 << failing);
+        let before = sensor.config_snapshot_get().unwrap();
+        let err = sensor.config_snapshot_apply_checked(&snapshot).unwrap_err();
+
+        assert_eq!(err.error, Error::Bus(()));
+        assert_eq!(err.applied.as_slice().len(), failing as usize);
+        for (i, reg) in err.applied.as_slice().iter().enumerate() {
+            assert_eq!(sensor.bus.written(i as u32), *reg);
+        }
+
+        // The failed register first, then the applied ones, latest first
+        let rolled_back = err.rolled_back.as_slice();
+        assert_eq!(rolled_back[0], sensor.bus.written(failing));
+        assert!(
+            rolled_back[1..]
+                .iter()
+                .eq(err.applied.as_slice().iter().rev())
+        );
+        for (i, reg) in rolled_back.iter().enumerate() {
+            assert_eq!(sensor.bus.written(failing + 1 + i as u32), *reg);
+        }
+        assert!(err.is_rolled_back());
+        assert_eq!(sensor.config_snapshot_get(), Ok(before));
+    }
+}
+
+#[test]
+fn failed_rollback_write_is_reported() {
+    // The third write fails, then the rollback of the second register
+    let (mut sensor, snapshot) = flaky(1 << 2 | 1 << 4);
+    let err = sensor.config_snapshot_apply_checked(&snapshot).unwrap_err();
+    let (first, second, third) = (
+        sensor.bus.written(0),
+        sensor.bus.written(1),
+        sensor.bus.written(2),
+    );
+    assert_eq!(err.applied.as_slice(), [first, second]);
+    assert_eq!(err.rolled_back.as_slice(), [third, first]);
+    assert_eq!(err.rollback_failed.as_slice(), [second]);
+    assert!(!err.is_rolled_back());
+}
+
+#[test]
+fn failed_read_of_the_current_values_writes_nothing() {
+    let (mut sensor, snapshot) = flaky(0);
+    sensor.bus.fail_reads = true;
+    let err = sensor.config_snapshot_apply_checked(&snapshot).unwrap_err();
+    assert_eq!(err.error, Error::Bus(()));
+    assert!(err.applied.as_slice().is_empty());
+    assert!(err.rolled_back.as_slice().is_empty());
+    assert!(err.is_rolled_back());
+    assert_eq!(sensor.bus.writes, 0);
+
+    // The plain apply reports the bus error only
+    assert_eq!(sensor.config_snapshot_apply(&snapshot), Err(Error::Bus(())));
+}
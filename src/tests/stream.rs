@@ -0,0 +1,118 @@
+use super::{bit, block_on};
+use crate::Iis2dlpc;
+use crate::asynch::Iis2dlpcAsync;
+use crate::event::Event;
+#[cfg(feature = "storm-guard")]
+use crate::event::StormPolicy;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::remap::{AxisRemap, SignedAxis};
+use crate::stream::{Iis2dlpcStream, Item, NoPin};
+
+type Sensor = Iis2dlpcAsync<MockBus, NoDelay>;
+type Stream = Iis2dlpcStream<MockBus, NoDelay, NoPin, NoDelay>;
+
+const WAKE_UP_X: Event = Event::WakeUp {
+    axes: [true, false, false],
+};
+
+/// Async driver running at 100 Hz in high-performance mode, with the given remap.
+fn sensor(remap: AxisRemap) -> Sensor {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor.axis_remap_set(remap);
+    sensor.into_async(|bus| bus)
+}
+
+/// Stream polling the driver.
+fn polling(sensor: Sensor) -> Stream {
+    block_on(Iis2dlpcStream::polling(sensor, NoDelay)).unwrap()
+}
+
+/// Latch a wake-up on the sensor X axis, with the data-ready flag if `drdy`.
+fn wake_up_latch(bus: &mut MockBus, drdy: bool) {
+    bus.set_reg(Reg::WakeUpSrc, bit(|b| WakeUpSrc::from_bits(b).x_wu()));
+    bus.set_reg(Reg::AllIntSrc, bit(|b| AllIntSrc::from_bits(b).wu_ia()));
+    let drdy = if drdy {
+        bit(|b| StatusDup::from_bits(b).drdy())
+    } else {
+        0
+    };
+    bus.set_reg(Reg::StatusDup, drdy);
+}
+
+#[test]
+fn events_come_before_the_sample_of_the_same_read() {
+    let mut sensor = sensor(AxisRemap::IDENTITY);
+    wake_up_latch(&mut sensor.bus, true);
+    sensor.bus.set_reg(Reg::OutXL, 0x40);
+    sensor.bus.expect(&[
+        Access::read(Reg::Ctrl1),
+        Access::read(Reg::Ctrl3),
+        Access::read(Reg::StatusDup),
+        Access::read(Reg::TapThsY),
+        Access::read(Reg::OutXL),
+    ]);
+
+    let mut stream = polling(sensor);
+    assert_eq!(block_on(stream.next()), Ok(Item::Event(WAKE_UP_X)));
+    assert_eq!(block_on(stream.next()), Ok(Item::Sample([16, 0, 0])));
+    let (mut sensor, _) = stream.release();
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn pending_events_are_shared_with_event_get() {
+    let mut sensor = sensor(AxisRemap::IDENTITY);
+    wake_up_latch(&mut sensor.bus, false);
+    sensor.bus.set_reg(
+        Reg::AllIntSrc,
+        bit(|b| AllIntSrc::from_bits(b).wu_ia()) | bit(|b| AllIntSrc::from_bits(b).ff_ia()),
+    );
+
+    let mut stream = polling(sensor);
+    assert_eq!(block_on(stream.next()), Ok(Item::Event(WAKE_UP_X)));
+
+    // The free fall of the same read stays pending in the driver
+    let (mut sensor, _) = stream.release();
+    sensor.bus.expect(&[]);
+    assert_eq!(block_on(sensor.event_get()), Ok(Some(Event::FreeFall)));
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // And the other way round
+    assert_eq!(block_on(sensor.event_get()), Ok(Some(WAKE_UP_X)));
+    let mut stream = polling(sensor);
+    assert_eq!(block_on(stream.next()), Ok(Item::Event(Event::FreeFall)));
+}
+
+#[cfg(feature = "storm-guard")]
+#[test]
+fn storm_guard_trips_through_the_stream() {
+    let mut sensor = sensor(AxisRemap::IDENTITY);
+    wake_up_latch(&mut sensor.bus, false);
+    let ctrl4 = Ctrl4Int1PadCtrl::new().with_int1_wu(1);
+    sensor.bus.set_reg(Reg::Ctrl4Int1PadCtrl, ctrl4.into_bits());
+    sensor.events_rate_limit_set(0);
+    sensor.events_storm_policy_set(StormPolicy::DisableRoute);
+
+    let mut stream = polling(sensor);
+    for _ in 0..crate::event::STORM_POLLS {
+        assert_eq!(block_on(stream.next()), Ok(Item::Event(WAKE_UP_X)));
+    }
+    let (sensor, _) = stream.release();
+    let ctrl4 = Ctrl4Int1PadCtrl::from_bits(sensor.bus.reg(Reg::Ctrl4Int1PadCtrl));
+    assert_eq!(ctrl4.int1_wu(), 0);
+}
+
+#[test]
+fn events_are_in_the_board_frame() {
+    let z90 = AxisRemap::new([SignedAxis::PosY, SignedAxis::NegX, SignedAxis::PosZ]).unwrap();
+    let mut sensor = sensor(z90);
+    wake_up_latch(&mut sensor.bus, false);
+
+    let board = z90.event(WAKE_UP_X);
+    assert_ne!(board, WAKE_UP_X);
+    let mut stream = polling(sensor);
+    assert_eq!(block_on(stream.next()), Ok(Item::Event(board)));
+}
@@ -0,0 +1,173 @@
+use super::{bit, sensor};
+use crate::event::{TapEvent, TapKind, TapPolicy};
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::{DecodeRetry, Error, Iis2dlpc};
+
+/// Latch a tap on the X and Z axes, negative, with the given single- and double-tap flags.
+fn tap_latch(bus: &mut MockBus, single: bool, double: bool) -> u8 {
+    let mut src = bit(|b| TapSrc::from_bits(b).x_tap())
+        | bit(|b| TapSrc::from_bits(b).z_tap())
+        | bit(|b| TapSrc::from_bits(b).tap_sign())
+        | bit(|b| TapSrc::from_bits(b).tap_ia());
+    if single {
+        src |= bit(|b| TapSrc::from_bits(b).single_tap());
+    }
+    if double {
+        src |= bit(|b| TapSrc::from_bits(b).double_tap());
+    }
+    bus.set_reg(Reg::TapSrc, src);
+    src
+}
+
+fn tap(kind: TapKind, axis: Axis, raw: u8) -> TapEvent {
+    TapEvent {
+        kind,
+        axis: Some(axis),
+        negative: true,
+        raw,
+    }
+}
+
+fn taps(sensor: &mut Iis2dlpc<MockBus, NoDelay>) -> [Option<TapEvent>; 3] {
+    let mut events = sensor.tap_event_get().unwrap();
+    [events.next(), events.next(), events.next()]
+}
+
+#[test]
+fn double_tap_hides_the_single_tap_by_default() {
+    let mut sensor = sensor();
+    let raw = tap_latch(&mut sensor.bus, true, true);
+    assert_eq!(sensor.tap_policy_get(), TapPolicy::PreferDouble);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::TapSrc), Access::read(Reg::TapThsY)]);
+    assert_eq!(
+        taps(&mut sensor),
+        [Some(tap(TapKind::Double, Axis::X, raw)), None, None]
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn both_taps_are_reported_single_first() {
+    let mut sensor = sensor();
+    let raw = tap_latch(&mut sensor.bus, true, true);
+    sensor.tap_policy_set(TapPolicy::ReportBoth);
+    assert_eq!(
+        taps(&mut sensor),
+        [
+            Some(tap(TapKind::Single, Axis::X, raw)),
+            Some(tap(TapKind::Double, Axis::X, raw)),
+            None
+        ]
+    );
+
+    // A single tap alone is reported under either policy
+    let raw = tap_latch(&mut sensor.bus, true, false);
+    sensor.tap_policy_set(TapPolicy::PreferDouble);
+    assert_eq!(
+        taps(&mut sensor),
+        [Some(tap(TapKind::Single, Axis::X, raw)), None, None]
+    );
+}
+
+#[test]
+fn no_tap_flag_no_event() {
+    let mut sensor = sensor();
+    tap_latch(&mut sensor.bus, false, false);
+    assert_eq!(taps(&mut sensor), [None; 3]);
+}
+
+const PRIORITIES: [TapPrior; 6] = [
+    TapPrior::Xyz,
+    TapPrior::Yxz,
+    TapPrior::Xzy,
+    TapPrior::Zyx,
+    TapPrior::Yzx,
+    TapPrior::Zxy,
+];
+
+#[test]
+fn every_priority_round_trips_through_its_ordering() {
+    let mut sensor = sensor();
+    for prior in PRIORITIES {
+        let ordering = prior.ordering();
+        assert_eq!(TapPrior::from_ordering(ordering), Some(prior));
+        sensor.tap_axis_priority_set(prior).unwrap();
+        assert_eq!(sensor.tap_axis_priority_get(), Ok(prior));
+    }
+    assert_eq!(TapPrior::from_ordering([Axis::X, Axis::X, Axis::Y]), None);
+}
+
+#[test]
+fn reserved_priority_codes_are_errors() {
+    let mut sensor = sensor();
+    for code in [4, 7] {
+        let ths_y = TapThsY::new().with_tap_prior(code);
+        sensor.bus.set_reg(Reg::TapThsY, ths_y.into_bits());
+
+        // Read a second time by default
+        sensor
+            .bus
+            .expect(&[Access::read(Reg::TapThsY), Access::read(Reg::TapThsY)]);
+        assert_eq!(
+            sensor.tap_axis_priority_get(),
+            Err(Error::UnexpectedValue(code))
+        );
+        assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+        // The events are not decoded with a made-up priority
+        tap_latch(&mut sensor.bus, true, false);
+        assert!(matches!(
+            sensor.tap_event_get(),
+            Err(Error::UnexpectedValue(_))
+        ));
+    }
+
+    sensor.decode_retry_set(DecodeRetry::Never);
+    sensor.bus.expect(&[Access::read(Reg::TapThsY)]);
+    assert_eq!(
+        sensor.tap_axis_priority_get(),
+        Err(Error::UnexpectedValue(7))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn tap_on_several_axes_goes_to_the_highest_priority() {
+    // X and Z are flagged
+    let mut sensor = sensor();
+    let raw = tap_latch(&mut sensor.bus, true, false);
+    for prior in PRIORITIES {
+        sensor.tap_axis_priority_set(prior).unwrap();
+        let axis = prior
+            .ordering()
+            .into_iter()
+            .find(|axis| *axis != Axis::Y)
+            .unwrap();
+        assert_eq!(
+            taps(&mut sensor),
+            [Some(tap(TapKind::Single, axis, raw)), None, None],
+            "{prior:?}"
+        );
+    }
+}
+
+#[test]
+fn reserved_tap_source_bit_is_kept_raw() {
+    let mut sensor = sensor();
+    let raw = tap_latch(&mut sensor.bus, true, false);
+    let [Some(event), None, None] = taps(&mut sensor) else {
+        panic!("one tap expected");
+    };
+    assert!(event.is_fully_decoded());
+
+    let raw = raw | AllSources::TAP_SRC_RESERVED;
+    sensor.bus.set_reg(Reg::TapSrc, raw);
+    let [Some(event), None, None] = taps(&mut sensor) else {
+        panic!("one tap expected");
+    };
+    assert_eq!(event, tap(TapKind::Single, Axis::X, raw));
+    assert!(!event.is_fully_decoded());
+}
@@ -0,0 +1,65 @@
+use super::{bit, sensor};
+use crate::mock::MockBus;
+use crate::prelude::*;
+use crate::telemetry::{FRAME_LEN, TAG_RESERVED, telemetry_decode};
+
+/// Sources with a wake-up on X and a single tap on Z.
+fn sources() -> [(Reg, u8); 4] {
+    [
+        (Reg::WakeUpSrc, bit(|b| WakeUpSrc::from_bits(b).x_wu())),
+        (
+            Reg::TapSrc,
+            bit(|b| TapSrc::from_bits(b).z_tap()) | bit(|b| TapSrc::from_bits(b).single_tap()),
+        ),
+        (Reg::SixdSrc, 0),
+        (Reg::AllIntSrc, bit(|b| AllIntSrc::from_bits(b).wu_ia())),
+    ]
+}
+
+#[test]
+fn frame_round_trips_through_the_decoder() {
+    let mut sensor = sensor();
+    for (reg, val) in sources() {
+        sensor.bus.set_reg(reg, val);
+    }
+    sensor.bus.set_reg(Reg::OutXH, 0x10);
+
+    let mut buf = [0; FRAME_LEN];
+    assert_eq!(sensor.telemetry_frame(&mut buf), Ok(FRAME_LEN));
+    let frame = telemetry_decode(&buf).unwrap();
+    assert_eq!(frame.raw, [0x0400, 0, 0]);
+    assert_eq!(frame.sources.to_bytes()[1..], sources().map(|(_, val)| val));
+    assert!(frame.is_fully_decoded());
+}
+
+#[test]
+fn reserved_bits_are_kept_raw_and_flagged() {
+    let mut sensor = sensor();
+    let reserved = [
+        AllSources::WAKE_UP_SRC_RESERVED,
+        AllSources::TAP_SRC_RESERVED,
+        AllSources::SIXD_SRC_RESERVED,
+        AllSources::ALL_INT_SRC_RESERVED,
+    ];
+    for ((reg, val), reserved) in sources().into_iter().zip(reserved) {
+        for (other, val) in sources() {
+            sensor.bus.set_reg(other, val);
+        }
+        sensor.bus.set_reg(reg, val | reserved);
+
+        let mut buf = [0; FRAME_LEN];
+        sensor.telemetry_frame(&mut buf).unwrap();
+        let frame = telemetry_decode(&buf).unwrap();
+        assert!(!frame.is_fully_decoded(), "{reg:?}");
+        assert!(frame.sources.to_bytes().contains(&(val | reserved)));
+    }
+
+    // A reserved tag bit, e.g. from a newer encoder
+    let mut buf = [0; FRAME_LEN];
+    sensor.bus = MockBus::new();
+    sensor.telemetry_frame(&mut buf).unwrap();
+    buf[FRAME_LEN - 1] |= TAG_RESERVED;
+    let frame = telemetry_decode(&buf).unwrap();
+    assert_eq!(frame.tag & TAG_RESERVED, TAG_RESERVED);
+    assert!(!frame.is_fully_decoded());
+}
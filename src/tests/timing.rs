@@ -0,0 +1,170 @@
+use super::{bit, sensor};
+use crate::mock::{MockBus, NoDelay};
+use crate::prelude::*;
+use crate::timing::DurationField;
+#[cfg(all(feature = "wakeup", feature = "freefall"))]
+use crate::timing::FfDuration;
+use crate::{ConfigError, Error, Iis2dlpc, MAX_ODR_CALIBRATION_PPM, mode_odr_check};
+
+const SLOWEST: Odr = Odr::_1_6hzLpOnly;
+
+/// Driver running at 1.6 Hz in a low-power mode.
+#[cfg(all(feature = "wakeup", feature = "freefall"))]
+fn slowest() -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::ContLowPwr4).unwrap();
+    sensor.data_rate_set(SLOWEST).unwrap();
+    sensor
+}
+
+#[test]
+fn slowest_rate_period_and_fill_time() {
+    assert_eq!(SLOWEST.period_us(), Some(625_000));
+    assert_eq!(SLOWEST.to_hz(), Some(1.6));
+    assert_eq!(SLOWEST.fifo_fill_us(31), Some(19_375_000));
+    assert_eq!(SLOWEST.fifo_fill_us(32), Some(20_000_000));
+    assert_eq!(Odr::Off.fifo_fill_us(32), None);
+}
+
+#[test]
+fn longest_durations_at_the_slowest_rate_do_not_overflow() {
+    let cases = [
+        (DurationField::WakeDur, 1_875_000, 625_000),
+        (DurationField::SleepDur, 4_800_000_000, 320_000_000),
+        (DurationField::Shock, 15_000_000, 5_000_000),
+        (DurationField::Quiet, 7_500_000, 2_500_000),
+        (DurationField::Latency, 300_000_000, 20_000_000),
+        (DurationField::FfDur, 39_375_000, 625_000),
+    ];
+    for (field, longest_us, lsb_us) in cases {
+        assert_eq!(
+            field.duration_us(field.max_code(), SLOWEST),
+            Some(longest_us),
+            "{field:?}"
+        );
+        assert_eq!(field.lsb_duration_us(SLOWEST), Some(lsb_us), "{field:?}");
+    }
+}
+
+#[cfg(all(feature = "wakeup", feature = "freefall"))]
+#[test]
+fn millisecond_setters_at_the_slowest_rate() {
+    let mut sensor = slowest();
+    assert_eq!(sensor.wkup_dur_ms_set(1_875), Ok(1_875));
+    assert_eq!(sensor.wkup_dur_get(), Ok(3));
+    assert_eq!(sensor.wkup_dur_ms_get(), Ok(1_875));
+
+    assert_eq!(sensor.act_sleep_dur_ms_set(320_000), Ok(320_000));
+    assert_eq!(sensor.act_sleep_dur_get(), Ok(1));
+    assert_eq!(sensor.act_sleep_dur_ms_set(4_800_000), Ok(4_800_000));
+    assert_eq!(sensor.act_sleep_dur_get(), Ok(15));
+    assert_eq!(sensor.act_sleep_dur_ms_get(), Ok(4_800_000));
+
+    let ff = FfDuration::from_ms(39_375, SLOWEST).unwrap();
+    assert_eq!((ff.lsb(), ff.duration_us()), (63, Some(39_375_000)));
+    assert_eq!(FfDuration::from_ms(40_000, SLOWEST), None);
+}
+
+#[test]
+fn slowest_rate_is_rejected_in_high_performance_mode() {
+    assert_eq!(
+        mode_odr_check::<()>(Mode::HighPerformance, SLOWEST),
+        Err(Error::InvalidArgument)
+    );
+    assert_eq!(mode_odr_check::<()>(Mode::ContLowPwr4, SLOWEST), Ok(()));
+
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    assert_eq!(sensor.data_rate_set(SLOWEST), Err(Error::InvalidArgument));
+    assert_eq!(sensor.data_rate_get(), Ok(Odr::Off));
+}
+
+/// Driver running at 100 Hz in high-performance mode.
+fn running() -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(Odr::_100hz).unwrap();
+    sensor
+}
+
+#[cfg(feature = "wakeup")]
+#[test]
+fn calibration_applies_to_every_conversion() {
+    let mut sensor = running();
+    // Measured at 12.5 Hz, the device runs 6.25 % fast at every rate
+    sensor.odr_calibration_set(13.281_25, Odr::_12_5hz).unwrap();
+    assert_eq!(sensor.odr_calibration_get(), 62_500);
+    assert_eq!(sensor.odr_hz(), Ok(106.25));
+    assert_eq!(sensor.odr_period_us(), Ok(9_411));
+    assert_eq!(sensor.lsb_duration_us(DurationField::WakeDur), Ok(9_411));
+    assert_eq!(
+        sensor.lsb_duration_us(DurationField::SleepDur),
+        Ok(512 * 9_411)
+    );
+
+    assert_eq!(sensor.wkup_dur_ms_set(28), Ok(28));
+    assert_eq!(sensor.wkup_dur_get(), Ok(3));
+
+    sensor.odr_calibration_clear();
+    assert_eq!(sensor.odr_hz(), Ok(100.0));
+    assert_eq!(sensor.wkup_dur_ms_get(), Ok(30));
+}
+
+#[test]
+fn calibration_rejects_unusable_measurements() {
+    let mut sensor = running();
+    sensor.odr_calibration_set(13.281_25, Odr::_12_5hz).unwrap();
+    for measured_hz in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+        assert_eq!(
+            sensor.odr_calibration_set(measured_hz, Odr::_100hz),
+            Err(Error::InvalidArgument)
+        );
+    }
+    assert_eq!(
+        sensor.odr_calibration_set(100.0, Odr::Off),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    assert_eq!(
+        sensor.odr_calibration_set(125.0, Odr::_100hz),
+        Err(Error::OutOfRange {
+            requested: 250_000,
+            max: MAX_ODR_CALIBRATION_PPM,
+        })
+    );
+    assert_eq!(sensor.odr_calibration_get(), 62_500);
+}
+
+#[test]
+fn measured_rate_is_the_mean_data_ready_period() {
+    let mut sensor = running();
+    let drdy = bit(|b| Status::from_bits(b).drdy());
+    sensor.bus.set_reg(Reg::Status, drdy);
+
+    // A data-ready on every poll, 9.6 ms apart
+    let mut now = 0;
+    let clock = move || {
+        now += 9_600;
+        now
+    };
+    assert_eq!(sensor.measure_odr(4, clock), Ok(1e6 / 9_600.0));
+}
+
+#[test]
+fn measurement_times_out_without_data_ready() {
+    let mut sensor = sensor();
+    assert_eq!(
+        sensor.measure_odr(4, || 0),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+
+    let mut sensor = running();
+    let mut now = 0;
+    let clock = move || {
+        now += 10_000;
+        now
+    };
+    assert_eq!(
+        sensor.measure_odr(4, clock),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+}
@@ -0,0 +1,167 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::mock::{MockBus, NoDelay};
+use crate::prelude::*;
+use crate::transaction::{TransactionHooks, TransactionInfo, TransactionKind};
+use crate::{BusOperation, Error, Iis2dlpc};
+
+/// Calls of the hooks of one test.
+struct HookLog {
+    before: AtomicUsize,
+    after: AtomicUsize,
+    writes: AtomicUsize,
+    last_reg: AtomicUsize,
+    last_len: AtomicUsize,
+}
+
+impl HookLog {
+    const fn new() -> Self {
+        Self {
+            before: AtomicUsize::new(0),
+            after: AtomicUsize::new(0),
+            writes: AtomicUsize::new(0),
+            last_reg: AtomicUsize::new(0),
+            last_len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Calls of the `before` and `after` hooks, and the register and length of the last transaction.
+    fn calls(&self) -> (usize, usize, u8, usize) {
+        (
+            self.before.load(Ordering::Relaxed),
+            self.after.load(Ordering::Relaxed),
+            self.last_reg.load(Ordering::Relaxed) as u8,
+            self.last_len.load(Ordering::Relaxed),
+        )
+    }
+}
+
+// The hooks are plain functions and the tests run in parallel: each test logs in its own slot, the `K`
+// parameter of the hooks.
+static LOGS: [HookLog; 4] = [const { HookLog::new() }; 4];
+
+fn log<const K: usize>() -> &'static HookLog {
+    LOGS.get(K).unwrap()
+}
+
+fn before<const K: usize>(info: &TransactionInfo) {
+    let log = log::<K>();
+    log.before.fetch_add(1, Ordering::Relaxed);
+    log.last_reg.store(info.reg as usize, Ordering::Relaxed);
+    log.last_len.store(info.len, Ordering::Relaxed);
+    if info.kind == TransactionKind::Write {
+        log.writes.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn after<const K: usize>(_: &TransactionInfo) {
+    log::<K>().after.fetch_add(1, Ordering::Relaxed);
+}
+
+fn hooks<const K: usize>() -> TransactionHooks {
+    TransactionHooks {
+        before: Some(before::<K>),
+        after: Some(after::<K>),
+    }
+}
+
+#[test]
+fn hooks_fire_once_per_transaction() {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.transaction_hooks_set(hooks::<0>());
+
+    sensor.device_id_get().unwrap();
+    assert_eq!(log::<0>().calls(), (1, 1, Reg::WhoAmI as u8, 1));
+
+    // Read-modify-write
+    sensor.wkup_threshold_set(12).unwrap();
+    assert_eq!(log::<0>().calls(), (3, 3, Reg::WakeUpThs as u8, 1));
+    assert_eq!(log::<0>().writes.load(Ordering::Relaxed), 1);
+
+    // A burst is one transaction
+    sensor.acceleration_raw_unchecked_get().unwrap();
+    assert_eq!(log::<0>().calls(), (4, 4, Reg::OutXL as u8, 6));
+
+    sensor.transaction_hooks_set(TransactionHooks::default());
+    sensor.device_id_get().unwrap();
+    assert_eq!(log::<0>().calls().0, 4);
+}
+
+#[test]
+fn fifo_burst_hint_is_the_bytes_read() {
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.fifo_mode_set(Fmode::StreamMode).unwrap();
+    sensor.bus.set_reg(Reg::FifoSamples, 10);
+    sensor.transaction_hooks_set(hooks::<1>());
+
+    // FIFO_SAMPLES, then the samples that fit in one burst
+    let mut out = [[0; 3]; 32];
+    assert_eq!(sensor.fifo_data_get(&mut out), Ok(10));
+    assert_eq!(log::<1>().calls(), (2, 2, Reg::OutXL as u8, 60));
+
+    let mut out = [[0; 3]; 4];
+    assert_eq!(sensor.fifo_data_get(&mut out), Ok(4));
+    assert_eq!(log::<1>().calls(), (4, 4, Reg::OutXL as u8, 24));
+}
+
+/// Bus failing every transfer.
+struct FailingBus;
+
+impl BusOperation for FailingBus {
+    type Error = ();
+
+    fn read_bytes(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+        Err(())
+    }
+
+    fn write_bytes(&mut self, _: &[u8]) -> Result<(), Self::Error> {
+        Err(())
+    }
+
+    fn write_byte_read_bytes(&mut self, _: &[u8; 1], _: &mut [u8]) -> Result<(), Self::Error> {
+        Err(())
+    }
+}
+
+#[test]
+fn after_hook_fires_on_a_failed_transaction() {
+    let mut sensor = Iis2dlpc::from_bus(FailingBus, NoDelay);
+    sensor.transaction_hooks_set(hooks::<2>());
+    assert_eq!(sensor.device_id_get(), Err(Error::Bus(())));
+    assert_eq!(log::<2>().calls(), (1, 1, Reg::WhoAmI as u8, 1));
+
+    // The read fails first: nothing is written
+    assert_eq!(sensor.wkup_threshold_set(12), Err(Error::Bus(())));
+    assert_eq!(log::<2>().calls().0, 2);
+    assert_eq!(log::<2>().writes.load(Ordering::Relaxed), 0);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_driver_shares_the_hooks_and_the_lock() {
+    use super::block_on;
+
+    let mut sensor = Iis2dlpc::from_bus(MockBus::new(), NoDelay);
+    sensor.transaction_hooks_set(hooks::<3>());
+    sensor.strict_scaling_lock(true).unwrap();
+    let mut sensor = sensor.into_async(|bus| bus);
+    let (before, after, _, _) = log::<3>().calls();
+    assert_eq!(before, after);
+
+    block_on(sensor.device_id_get()).unwrap();
+    assert_eq!(
+        log::<3>().calls(),
+        (before + 1, after + 1, Reg::WhoAmI as u8, 1)
+    );
+
+    // The refused write reaches neither the bus nor the hooks
+    assert_eq!(
+        block_on(sensor.full_scale_set(Fs::_16g)),
+        Err(Error::ScalingLocked)
+    );
+    assert_eq!(
+        log::<3>().calls(),
+        (before + 2, after + 2, Reg::Ctrl6 as u8, 1)
+    );
+    assert_eq!(log::<3>().writes.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,155 @@
+use super::sensor;
+use crate::mock::{Access, MockBus, NoDelay};
+use crate::prelude::*;
+use crate::{ConfigError, Error, Iis2dlpc, WakeupLevel, Warning};
+
+/// Driver running at `odr` in high-performance mode.
+fn running(odr: Odr) -> Iis2dlpc<MockBus, NoDelay> {
+    let mut sensor = sensor();
+    sensor.power_mode_set(Mode::HighPerformance).unwrap();
+    sensor.data_rate_set(odr).unwrap();
+    sensor
+}
+
+#[test]
+fn wake_duration_is_rounded_to_the_nearest_period() {
+    let mut sensor = running(Odr::_100hz);
+    assert_eq!(sensor.wkup_dur_ms_set(20), Ok(20));
+    assert_eq!(sensor.wkup_dur_get(), Ok(2));
+    assert_eq!(sensor.wkup_dur_ms_get(), Ok(20));
+
+    // 1.875 ms for 2 ms at 1.6 kHz, within the default tolerance
+    let mut sensor = running(Odr::_1_6khz);
+    assert_eq!(sensor.wkup_dur_ms_set(2), Ok(2));
+    assert_eq!(sensor.wkup_dur_get(), Ok(3));
+}
+
+#[test]
+fn wake_duration_past_the_field_writes_nothing() {
+    let mut sensor = running(Odr::_1_6khz);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.wkup_dur_ms_set(5),
+        Err(Error::OutOfRange {
+            requested: 5,
+            max: 2
+        })
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+}
+
+#[test]
+fn duration_between_two_codes_reports_the_nearest() {
+    // 80 ms and 160 ms at 12.5 Hz, the field reaches 240 ms
+    let mut sensor = running(Odr::_12_5hz);
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.wkup_dur_ms_set(100),
+        Err(Error::Inexact {
+            requested: 100,
+            nearest: 80
+        })
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+
+    // Below the first sleep code, 160 ms at 100 Hz
+    let mut sensor = running(Odr::_100hz);
+    assert_eq!(
+        sensor.act_sleep_dur_ms_set(50),
+        Err(Error::Inexact {
+            requested: 50,
+            nearest: 160
+        })
+    );
+    assert_eq!(
+        sensor.act_sleep_dur_ms_set(200_000),
+        Err(Error::OutOfRange {
+            requested: 200_000,
+            max: 76_800
+        })
+    );
+}
+
+#[test]
+fn tolerance_widens_the_accepted_durations() {
+    let mut sensor = running(Odr::_1_6khz);
+    assert!(matches!(
+        sensor.wkup_dur_ms_set(3),
+        Err(Error::OutOfRange { .. })
+    ));
+    sensor.duration_tolerance_set(50);
+    assert_eq!(sensor.duration_tolerance_get(), 50);
+    assert_eq!(sensor.wkup_dur_ms_set(3), Ok(2));
+}
+
+#[test]
+fn sleep_duration_uses_the_coarse_steps() {
+    let mut sensor = running(Odr::_100hz);
+    // Code 0 is 16 periods, any other code 512 periods per LSB
+    assert_eq!(sensor.act_sleep_dur_ms_set(160), Ok(160));
+    assert_eq!(sensor.act_sleep_dur_get(), Ok(0));
+    assert_eq!(sensor.act_sleep_dur_ms_set(10_240), Ok(10_240));
+    assert_eq!(sensor.act_sleep_dur_get(), Ok(2));
+    assert_eq!(sensor.act_sleep_dur_ms_get(), Ok(10_240));
+}
+
+#[test]
+fn durations_need_a_running_data_rate() {
+    let mut sensor = sensor();
+    sensor
+        .bus
+        .expect(&[Access::read(Reg::Ctrl1), Access::read(Reg::Ctrl3)]);
+    assert_eq!(
+        sensor.wkup_dur_ms_set(10),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+    assert_eq!(sensor.bus.expectations_check(), Ok(()));
+    assert_eq!(
+        sensor.act_sleep_dur_ms_get(),
+        Err(Error::InvalidConfig(ConfigError::DataRateNotRunning))
+    );
+}
+
+#[test]
+fn levels_are_centered_on_the_offsets_of_an_offset_feed() {
+    let mut sensor = sensor();
+    sensor.full_scale_set(Fs::_2g).unwrap();
+    sensor.wkup_threshold_set(8).unwrap();
+    sensor.usr_offsets_set([10, 0, -10]).unwrap();
+    sensor.offset_weight_set(UsrOffW::_15_6mgLsb).unwrap();
+
+    // The high-pass feed ignores the offsets
+    let level = WakeupLevel::new(8, Fs::_2g, 0.0);
+    assert_eq!(level.above_mg, 250.0);
+    assert_eq!(sensor.wakeup_effective_threshold_mg(), Ok([level; 3]));
+
+    sensor
+        .wkup_feed_data_set(UsrOffOnWu::UserOffsetFeed)
+        .unwrap();
+    let weight = UsrOffW::_15_6mgLsb.mg_per_lsb();
+    assert_eq!(
+        sensor.wakeup_effective_threshold_mg(),
+        Ok([10.0, 0.0, -10.0].map(|ofs| WakeupLevel::new(8, Fs::_2g, ofs * weight)))
+    );
+}
+
+#[test]
+fn weight_change_warns_only_with_the_offset_feed() {
+    let mut sensor = sensor();
+    assert_eq!(sensor.offset_weight_set(UsrOffW::_15_6mgLsb), Ok(None));
+
+    sensor
+        .wkup_feed_data_set(UsrOffOnWu::UserOffsetFeed)
+        .unwrap();
+    assert_eq!(sensor.offset_weight_set(UsrOffW::_15_6mgLsb), Ok(None));
+    assert_eq!(
+        sensor.offset_weight_set(UsrOffW::_977ugLsb),
+        Ok(Some(Warning::WakeupThresholdShifted))
+    );
+    // The weight is written all the same
+    assert_eq!(sensor.offset_weight_get(), Ok(UsrOffW::_977ugLsb));
+}
@@ -0,0 +1,302 @@
+//! Duration fields expressed in output data rate periods.
+//!
+//! The wake-up, sleep, tap and free-fall durations are programmed as a number of ODR periods,
+//! so their real-world length depends on the data rate. [`DurationField`] describes how each
+//! field code maps to ODR periods.
+//!
+//! The actual data rate can be measured on the target from data-ready timestamps taken with
+//! [`crate::Iis2dlpc::drdy_timestamps_get`] and [`measured_rate_hz`].
+
+use crate::prelude::*;
+
+/// Register fields holding a duration.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DurationField {
+    /// `wake_dur` in `WAKE_UP_DUR`: 1 LSB = 1 / ODR.
+    WakeDur,
+    /// `sleep_dur` in `WAKE_UP_DUR`: 0 = 16 / ODR, otherwise 1 LSB = 512 / ODR.
+    SleepDur,
+    /// `shock` in `INT_DUR`: 0 = 4 / ODR, otherwise 1 LSB = 8 / ODR.
+    Shock,
+    /// `quiet` in `INT_DUR`: 0 = 2 / ODR, otherwise 1 LSB = 4 / ODR.
+    Quiet,
+    /// `latency` in `INT_DUR`: 0 = 16 / ODR, otherwise 1 LSB = 32 / ODR.
+    Latency,
+    /// `ff_dur` split across `WAKE_UP_DUR` and `FREE_FALL`: 1 LSB = 1 / ODR.
+    FfDur,
+}
+
+impl DurationField {
+    /// Get the highest code of the field.
+    pub fn max_code(&self) -> u8 {
+        match self {
+            DurationField::WakeDur | DurationField::Shock | DurationField::Quiet => 0x03,
+            DurationField::SleepDur | DurationField::Latency => 0x0F,
+            DurationField::FfDur => 0x3F,
+        }
+    }
+
+    /// Get the length of a field code in ODR periods.
+    ///
+    /// ### Arguments
+    /// - `code`: The field code.
+    pub fn periods(&self, code: u8) -> u64 {
+        let code = code as u64;
+        match (self, code) {
+            (DurationField::WakeDur | DurationField::FfDur, _) => code,
+            (DurationField::SleepDur, 0) => 16,
+            (DurationField::SleepDur, _) => 512 * code,
+            (DurationField::Shock, 0) => 4,
+            (DurationField::Shock, _) => 8 * code,
+            (DurationField::Quiet, 0) => 2,
+            (DurationField::Quiet, _) => 4 * code,
+            (DurationField::Latency, 0) => 16,
+            (DurationField::Latency, _) => 32 * code,
+        }
+    }
+
+    /// Get the duration of a field code at a data rate.
+    ///
+    /// The result is in 64 bits: at 1.6 Hz the longest `sleep_dur` code lasts 4800 s.
+    ///
+    /// ### Arguments
+    /// - `code`: The field code.
+    /// - `odr`: The data rate.
+    ///
+    /// ### Returns
+    /// - `Some(u64)`: The duration in microseconds.
+    /// - `None`: If the accelerometer is off or the conversions are triggered on demand.
+    pub fn duration_us(&self, code: u8, odr: Odr) -> Option<u64> {
+        odr.period_us()
+            .map(|us| self.periods(code).saturating_mul(us as u64))
+    }
+
+    /// Get the duration step of one field LSB at a data rate, e.g. 320 s for `sleep_dur` at 1.6 Hz.
+    ///
+    /// The special meaning of code `0` in some fields is not a step and is ignored.
+    ///
+    /// ### Arguments
+    /// - `odr`: The data rate.
+    ///
+    /// ### Returns
+    /// - `Some(u64)`: The step in microseconds.
+    /// - `None`: If the accelerometer is off or the conversions are triggered on demand.
+    pub fn lsb_duration_us(&self, odr: Odr) -> Option<u64> {
+        self.duration_us(1, odr)
+    }
+
+    /// Find the code whose duration is nearest to a target.
+    ///
+    /// ### Arguments
+    /// - `target_us`: The target duration in microseconds.
+    /// - `period_us`: The ODR period in microseconds.
+    ///
+    /// ### Returns
+    /// - `(u8, u64)`: The code and its duration in microseconds.
+    pub fn nearest_code(&self, target_us: u64, period_us: u64) -> (u8, u64) {
+        let mut best = (0, self.periods(0).saturating_mul(period_us));
+        for code in 1..=self.max_code() {
+            let us = self.periods(code).saturating_mul(period_us);
+            if us.abs_diff(target_us) < best.1.abs_diff(target_us) {
+                best = (code, us);
+            }
+        }
+        best
+    }
+}
+
+/// Outcome of the retuning of a single duration field.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FieldRetune {
+    /// Field code before the data rate change.
+    pub old_code: u8,
+    /// Field code written after the data rate change.
+    pub new_code: u8,
+    /// Duration before the data rate change, in microseconds.
+    pub old_us: u64,
+    /// Duration after the data rate change, in microseconds.
+    pub new_us: u64,
+    /// `true` if the old duration exceeds the range of the field at the new data rate and was clamped.
+    pub truncated: bool,
+}
+
+/// Activity detection settings of [`crate::Iis2dlpc::wake_and_ff_config_set`].
+#[cfg(feature = "wakeup")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ActivityTiming {
+    /// Detection mode: `sleep_on` in `WAKE_UP_THS` and `stationary` in `WAKE_UP_DUR`.
+    pub mode: SleepOn,
+    /// `wake_dur` code, see [`DurationField::WakeDur`].
+    pub wake_dur: u8,
+    /// `sleep_dur` code, see [`DurationField::SleepDur`].
+    pub sleep_dur: u8,
+}
+
+/// Free-fall duration, the 6-bit `ff_dur` field: 1 LSB = 1 / ODR.
+///
+/// The field is split across two registers, bit 5 in `WAKE_UP_DUR` and bits 4..0 in `FREE_FALL`; the driver
+/// packs it. A duration built with [`Self::from_ms`] or read with [`crate::Iis2dlpc::free_fall_timing_get`]
+/// also knows its data rate and displays both representations, e.g. `6 LSB = 3.750 ms`.
+#[cfg(feature = "freefall")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FfDuration {
+    lsb: u8,
+    odr: Option<Odr>,
+}
+
+#[cfg(feature = "freefall")]
+impl FfDuration {
+    /// Create a duration from the field code.
+    ///
+    /// ### Arguments
+    /// - `lsb`: The `ff_dur` code, in ODR periods.
+    ///
+    /// ### Returns
+    /// - `Some(FfDuration)`: The duration, with no data rate attached.
+    /// - `None`: If `lsb` does not fit in the 6-bit field (above `0x3F`).
+    pub const fn from_lsb(lsb: u8) -> Option<Self> {
+        if lsb > 0x3F {
+            return None;
+        }
+        Some(Self { lsb, odr: None })
+    }
+
+    /// Create a duration from milliseconds.
+    ///
+    /// The nearest code is chosen with the nominal period of `odr`; the duration actually programmed is
+    /// given by [`Self::duration_us`]. The driver setters in milliseconds also apply the data rate calibration.
+    ///
+    /// ### Arguments
+    /// - `ms`: The duration in milliseconds.
+    /// - `odr`: The data rate the duration is counted at.
+    ///
+    /// ### Returns
+    /// - `Some(FfDuration)`: The duration, with `odr` attached.
+    /// - `None`: If `odr` is off or triggered on demand, or `ms` exceeds the longest code by more than half an LSB.
+    pub fn from_ms(ms: u32, odr: Odr) -> Option<Self> {
+        let period_us = odr.period_us()? as u64;
+        let target_us = ms as u64 * 1000;
+        let field = DurationField::FfDur;
+        if target_us > field.periods(field.max_code()) * period_us + period_us / 2 {
+            return None;
+        }
+        let (lsb, _) = field.nearest_code(target_us, period_us);
+        Some(Self {
+            lsb,
+            odr: Some(odr),
+        })
+    }
+
+    /// Attach a data rate to the duration.
+    pub fn with_odr(self, odr: Odr) -> Self {
+        Self {
+            odr: Some(odr),
+            ..self
+        }
+    }
+
+    /// Get the field code, in ODR periods.
+    pub fn lsb(&self) -> u8 {
+        self.lsb
+    }
+
+    /// Get the data rate attached to the duration, if any.
+    pub fn odr(&self) -> Option<Odr> {
+        self.odr
+    }
+
+    /// Get the duration at the attached data rate.
+    ///
+    /// ### Returns
+    /// - `Some(u64)`: The duration in microseconds.
+    /// - `None`: If no data rate is attached, or it is off or triggered on demand.
+    pub fn duration_us(&self) -> Option<u64> {
+        DurationField::FfDur.duration_us(self.lsb, self.odr?)
+    }
+}
+
+#[cfg(feature = "freefall")]
+impl core::fmt::Display for FfDuration {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.duration_us() {
+            Some(us) => write!(f, "{} LSB = {}.{:03} ms", self.lsb, us / 1000, us % 1000),
+            None => write!(f, "{} LSB (1 / ODR each)", self.lsb),
+        }
+    }
+}
+
+/// Free-fall settings, see [`crate::Iis2dlpc::free_fall_timing_set`].
+#[cfg(feature = "freefall")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct FreeFallTiming {
+    /// Minimum duration of the free-fall condition.
+    pub duration: FfDuration,
+    /// Free-fall threshold.
+    pub threshold: FfThs,
+}
+
+/// Report of [`crate::Iis2dlpc::data_rate_set_retuned`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct RetuneReport {
+    /// `wake_dur` field.
+    pub wake_dur: FieldRetune,
+    /// `sleep_dur` field.
+    pub sleep_dur: FieldRetune,
+    /// `shock` field.
+    pub shock: FieldRetune,
+    /// `quiet` field.
+    pub quiet: FieldRetune,
+    /// `latency` field.
+    pub latency: FieldRetune,
+    /// `ff_dur` field.
+    pub ff_dur: FieldRetune,
+}
+
+impl FieldRetune {
+    /// Retune a field code from an old to a new ODR period.
+    pub(crate) fn new(
+        field: DurationField,
+        old_code: u8,
+        old_period_us: u64,
+        new_period_us: u64,
+    ) -> Self {
+        let old_us = field.periods(old_code).saturating_mul(old_period_us);
+        let (new_code, new_us) = field.nearest_code(old_us, new_period_us);
+        Self {
+            old_code,
+            new_code,
+            old_us,
+            new_us,
+            truncated: old_us
+                > field
+                    .periods(field.max_code())
+                    .saturating_mul(new_period_us),
+        }
+    }
+}
+
+/// Compute the data rate from consecutive data-ready timestamps.
+///
+/// The timestamps come from a free-running counter and may wrap around once between two of them.
+///
+/// ### Arguments
+/// - `timestamps`: The timestamps, oldest first, as taken by [`crate::Iis2dlpc::drdy_timestamps_get`].
+/// - `ticks_per_s`: The frequency of the counter.
+///
+/// ### Returns
+/// - `Some(f32)`: The mean data rate in Hz.
+/// - `None`: If there are fewer than two timestamps or no time elapsed.
+pub fn measured_rate_hz(timestamps: &[u32], ticks_per_s: u32) -> Option<f32> {
+    let ticks = timestamps
+        .windows(2)
+        .map(|pair| match pair {
+            [a, b] => b.wrapping_sub(*a) as u64,
+            _ => 0,
+        })
+        .sum::<u64>();
+    if ticks == 0 {
+        return None;
+    }
+    let periods = timestamps.len().saturating_sub(1) as f32;
+    Some(periods * ticks_per_s as f32 / ticks as f32)
+}
@@ -0,0 +1,307 @@
+//! Bus transaction hooks.
+//!
+//! Every bus access of the driver goes through a single transaction point. With the `hooks`
+//! feature, optional hooks set with `Iis2dlpc::transaction_hooks_set` are called right before and
+//! right after each transaction, for example to defer a time-critical device sharing the same bus.
+//!
+//! With the `recorder` feature, a `RecordingBus` wraps the bus of the driver and logs the last
+//! transactions with their first data bytes into the ring buffer of a `Recorder` it owns, so that
+//! the context of an intermittent error can be dumped once it surfaces. A driver on an unwrapped
+//! bus carries no recorder at all.
+//!
+//! Both features are on by default; without them the transaction point only runs the bus prelude.
+//!
+//! A bus prelude, set with [`crate::Iis2dlpc::bus_prelude_set`], runs on the bus right before each
+//! transaction, for example to select the channel of an I2C multiplexer, see [`crate::mux`].
+
+use st_mems_bus::BusOperation;
+
+/// Direction of a bus transaction.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TransactionKind {
+    /// Register read.
+    Read,
+    /// Register write.
+    Write,
+}
+
+/// Description of a bus transaction, passed to the hooks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TransactionInfo {
+    /// Read or write.
+    pub kind: TransactionKind,
+    /// First register address.
+    pub reg: u8,
+    /// Number of data bytes transferred.
+    pub len: usize,
+}
+
+#[cfg(feature = "recorder")]
+impl TransactionInfo {
+    fn read(reg: u8, len: usize) -> Self {
+        Self {
+            kind: TransactionKind::Read,
+            reg,
+            len,
+        }
+    }
+
+    fn write(reg: u8, len: usize) -> Self {
+        Self {
+            kind: TransactionKind::Write,
+            reg,
+            len,
+        }
+    }
+}
+
+/// Transaction hook.
+#[cfg(feature = "hooks")]
+pub type TransactionHook = fn(&TransactionInfo);
+
+/// Bus command issued before every transaction, see [`crate::Iis2dlpc::bus_prelude_set`].
+pub type BusPrelude<B> = fn(&mut B) -> Result<(), <B as BusOperation>::Error>;
+
+/// Hooks called around every bus transaction.
+#[cfg(feature = "hooks")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TransactionHooks {
+    /// Called right before the transaction.
+    pub before: Option<TransactionHook>,
+    /// Called right after the transaction, whether it succeeded or not.
+    pub after: Option<TransactionHook>,
+}
+
+/// Number of data bytes kept by a [`TransactionRecord`].
+#[cfg(feature = "recorder")]
+pub const RECORD_PAYLOAD: usize = 8;
+
+/// Bus transaction logged by a [`Recorder`].
+#[cfg(feature = "recorder")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct TransactionRecord {
+    /// Sequence number of the transaction since the recorder was created or cleared, wrapping.
+    pub seq: u32,
+    /// Read or write, first register address and number of data bytes transferred.
+    pub info: Option<TransactionInfo>,
+    /// `true` if the bus reported no error.
+    pub ok: bool,
+    /// First data bytes; the bytes read by a failed transaction are not kept.
+    pub data: [u8; RECORD_PAYLOAD],
+}
+
+#[cfg(feature = "recorder")]
+impl TransactionRecord {
+    /// Unused record, to initialize a buffer.
+    pub const EMPTY: Self = Self {
+        seq: 0,
+        info: None,
+        ok: false,
+        data: [0; RECORD_PAYLOAD],
+    };
+
+    /// Get the data bytes kept, at most [`RECORD_PAYLOAD`].
+    pub fn payload(&self) -> &[u8] {
+        let len = self.info.map_or(0, |info| info.len.min(RECORD_PAYLOAD));
+        self.data.get(..len).unwrap_or_default()
+    }
+
+    /// Check whether the transaction carried more bytes than [`RECORD_PAYLOAD`].
+    pub fn truncated(&self) -> bool {
+        self.info.is_some_and(|info| info.len > RECORD_PAYLOAD)
+    }
+}
+
+/// Ring buffer of the last `N` bus transactions, see [`RecordingBus`].
+///
+/// Once full, each transaction overwrites the oldest record. A record keeps the first [`RECORD_PAYLOAD`] data
+/// bytes, see [`TransactionRecord::truncated`]; a recorder of capacity 0 logs nothing.
+#[cfg(feature = "recorder")]
+#[derive(Clone, Debug)]
+pub struct Recorder<const N: usize> {
+    buf: [TransactionRecord; N],
+    next: usize,
+    seq: u32,
+}
+
+#[cfg(feature = "recorder")]
+impl<const N: usize> Default for Recorder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "recorder")]
+impl<const N: usize> Recorder<N> {
+    /// Create an empty recorder.
+    pub const fn new() -> Self {
+        Self {
+            buf: [TransactionRecord::EMPTY; N],
+            next: 0,
+            seq: 0,
+        }
+    }
+
+    /// Log a transaction over the oldest record.
+    fn log(&mut self, info: &TransactionInfo, data: &[u8], ok: bool) {
+        let Some(record) = self.buf.get_mut(self.next) else {
+            return;
+        };
+        *record = TransactionRecord {
+            seq: self.seq,
+            info: Some(*info),
+            ok,
+            data: [0; RECORD_PAYLOAD],
+        };
+        if ok || info.kind == TransactionKind::Write {
+            for (dst, src) in record.data.iter_mut().zip(data) {
+                *dst = *src;
+            }
+        }
+        self.seq = self.seq.wrapping_add(1);
+        self.next = (self.next + 1) % N;
+    }
+
+    /// Iterate over the logged records, oldest first.
+    ///
+    /// The sequence numbers of the records are consecutive; the first one tells how many transactions were
+    /// overwritten.
+    pub fn records(&self) -> impl Iterator<Item = &TransactionRecord> {
+        let (newer, older) = self.buf.split_at_checked(self.next).unwrap_or_default();
+        older
+            .iter()
+            .chain(newer)
+            .filter(|record| record.info.is_some())
+    }
+
+    /// Get the records in storage order, unused ones included.
+    pub fn as_slice(&self) -> &[TransactionRecord] {
+        &self.buf
+    }
+
+    /// Forget the logged records; the sequence numbers start over.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Bus logging every register access into a [`Recorder`].
+///
+/// The recorder lives in the bus, so that the driver carries no recorder state: wrap the bus of the driver
+/// to record, and take the recorder back with [`Self::release`] or read it through [`crate::Iis2dlpc::bus`].
+/// The register reads and writes are logged, [`BusOperation::read_from_register`] and
+/// [`BusOperation::write_to_register`]; the raw transfers, e.g. of a bus prelude, are not:
+///
+/// ```ignore
+/// let bus = RecordingBus::<_, 16>::new(I2cBus::new(i2c, I2CAddress::I2cAddH as u8));
+/// let mut sensor = Iis2dlpc::from_bus(bus, delay);
+/// if let Err(err) = sensor.init() {
+///     for record in sensor.bus.recorder().records() {
+///         // Dump the context of the error
+///     }
+/// }
+/// ```
+///
+/// A bus prelude receives the recording bus: reach the wrapped bus with [`Self::inner_mut`].
+#[cfg(feature = "recorder")]
+#[derive(Debug)]
+pub struct RecordingBus<B, const N: usize> {
+    bus: B,
+    recorder: Recorder<N>,
+}
+
+#[cfg(feature = "recorder")]
+impl<B, const N: usize> RecordingBus<B, N> {
+    /// Wrap a bus with an empty recorder.
+    ///
+    /// ### Arguments
+    /// - `bus`: The bus of the device.
+    pub fn new(bus: B) -> Self {
+        Self::with_recorder(bus, Recorder::new())
+    }
+
+    /// Wrap a bus, logging into `recorder` after its current records.
+    ///
+    /// ### Arguments
+    /// - `bus`: The bus of the device.
+    /// - `recorder`: The recorder, e.g. given back by [`Self::release`].
+    pub fn with_recorder(bus: B, recorder: Recorder<N>) -> Self {
+        Self { bus, recorder }
+    }
+
+    /// Get the recorder.
+    pub fn recorder(&self) -> &Recorder<N> {
+        &self.recorder
+    }
+
+    /// Get the recorder, e.g. to clear it.
+    pub fn recorder_mut(&mut self) -> &mut Recorder<N> {
+        &mut self.recorder
+    }
+
+    /// Get the wrapped bus; its accesses are not logged.
+    pub fn inner_mut(&mut self) -> &mut B {
+        &mut self.bus
+    }
+
+    /// Give back the wrapped bus and the recorder.
+    pub fn release(self) -> (B, Recorder<N>) {
+        (self.bus, self.recorder)
+    }
+}
+
+#[cfg(feature = "recorder")]
+impl<B: BusOperation, const N: usize> BusOperation for RecordingBus<B, N> {
+    type Error = B::Error;
+
+    fn read_bytes(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.bus.read_bytes(rbuf)
+    }
+
+    fn write_bytes(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.bus.write_bytes(wbuf)
+    }
+
+    fn write_byte_read_bytes(
+        &mut self,
+        wbuf: &[u8; 1],
+        rbuf: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.bus.write_byte_read_bytes(wbuf, rbuf)
+    }
+
+    fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let result = self.bus.read_from_register(reg, buf);
+        self.recorder
+            .log(&TransactionInfo::read(reg, buf.len()), buf, result.is_ok());
+        result
+    }
+
+    fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        let result = self.bus.write_to_register(reg, buf);
+        self.recorder
+            .log(&TransactionInfo::write(reg, buf.len()), buf, result.is_ok());
+        result
+    }
+}
+
+#[cfg(all(feature = "recorder", feature = "async"))]
+impl<B: crate::asynch::AsyncBusOperation, const N: usize> crate::asynch::AsyncBusOperation
+    for RecordingBus<B, N>
+{
+    type Error = B::Error;
+
+    async fn read_from_register(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let result = self.bus.read_from_register(reg, buf).await;
+        self.recorder
+            .log(&TransactionInfo::read(reg, buf.len()), buf, result.is_ok());
+        result
+    }
+
+    async fn write_to_register(&mut self, reg: u8, buf: &[u8]) -> Result<(), Self::Error> {
+        let result = self.bus.write_to_register(reg, buf).await;
+        self.recorder
+            .log(&TransactionInfo::write(reg, buf.len()), buf, result.is_ok());
+        result
+    }
+}
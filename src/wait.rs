@@ -0,0 +1,93 @@
+//! Waits of the blocking driver.
+//!
+//! The driver waits in a few places: the settling of a mode switch or a filter change (see
+//! [`crate::errata`]), the self-test phases, the recovery after a power cycle and the data-ready
+//! polls of [`crate::pair`] and the console. Every such wait goes through [`WaitStrategy`], which
+//! is implemented for every [`DelayNs`]: by default the delay passed to the driver blocks for the
+//! whole duration.
+//!
+//! On a cooperative scheduler a blocking delay of a few tens of milliseconds, e.g. a self-test
+//! settle, stalls every other task. [`YieldingDelay`] splits each wait into short spins of the
+//! wrapped delay and calls a yield callback after each of them, so the scheduler gets the hand
+//! back regularly while the sensor settles:
+//!
+//! ```ignore
+//! // Spin 500 µs at a time and service the other tasks in between
+//! let delay = YieldingDelay::new(embassy_time::Delay, 500, || executor_poll_once());
+//! let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddL, delay);
+//! ```
+//!
+//! The register accessors of the driver need a [`DelayNs`], so the adapter is one itself; the
+//! callback runs inside the driver call and must not access the sensor.
+
+use embedded_hal::delay::DelayNs;
+
+/// Way the driver waits, see the [module](self) documentation.
+pub trait WaitStrategy {
+    /// Wait for at least `us` microseconds.
+    fn wait_us(&mut self, us: u32);
+
+    /// Wait for at least `ms` milliseconds.
+    fn wait_ms(&mut self, ms: u32);
+}
+
+impl<T: DelayNs> WaitStrategy for T {
+    fn wait_us(&mut self, us: u32) {
+        self.delay_us(us);
+    }
+
+    fn wait_ms(&mut self, ms: u32) {
+        self.delay_ms(ms);
+    }
+}
+
+/// Delay spinning in bounded slices and yielding in between.
+///
+/// A wait of `d` nanoseconds spins the wrapped delay `ceil(d / slice)` times, the last spin
+/// possibly shorter, and calls the yield callback after each spin; a zero wait neither spins nor
+/// yields. The total spin time is the requested duration: the time spent in the callback adds to
+/// the wait.
+#[derive(Debug)]
+pub struct YieldingDelay<D, F> {
+    delay: D,
+    slice_ns: u32,
+    yield_now: F,
+}
+
+impl<D: DelayNs, F: FnMut()> YieldingDelay<D, F> {
+    /// Create the adapter.
+    ///
+    /// ### Arguments
+    /// - `delay`: The delay spinning each slice.
+    /// - `slice_us`: The longest spin between two yields, in microseconds; `0` is taken as `1`, values above
+    ///   4 294 967 µs as 4 294 967 µs.
+    /// - `yield_now`: The callback run after each spin.
+    pub fn new(delay: D, slice_us: u32, yield_now: F) -> Self {
+        Self {
+            delay,
+            slice_ns: slice_us.clamp(1, u32::MAX / 1000) * 1000,
+            yield_now,
+        }
+    }
+
+    /// Get the longest spin between two yields, in microseconds.
+    pub fn slice_us(&self) -> u32 {
+        self.slice_ns / 1000
+    }
+
+    /// Release the wrapped delay.
+    pub fn release(self) -> D {
+        self.delay
+    }
+}
+
+impl<D: DelayNs, F: FnMut()> DelayNs for YieldingDelay<D, F> {
+    fn delay_ns(&mut self, mut ns: u32) {
+        while ns > 0 {
+            let spin = ns.min(self.slice_ns);
+            self.delay.delay_ns(spin);
+            (self.yield_now)();
+            ns -= spin;
+        }
+    }
+}
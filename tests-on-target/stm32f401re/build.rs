@@ -0,0 +1,24 @@
+//! This build script copies the `memory.x` file from the crate root into
+//! a directory where the linker can always find it at build time, and sets
+//! the linker scripts of `cortex-m-rt`, `defmt` and `embedded-test`.
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn main() {
+    let out = &PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    File::create(out.join("memory.x"))
+        .unwrap()
+        .write_all(include_bytes!("memory.x"))
+        .unwrap();
+    println!("cargo:rustc-link-search={}", out.display());
+    println!("cargo:rerun-if-changed=memory.x");
+
+    println!("cargo:rustc-link-arg=--nmagic");
+    println!("cargo:rustc-link-arg=-Tlink.x");
+    println!("cargo:rustc-link-arg=-Tdefmt.x");
+    // The test harness linker script only applies to the test binaries
+    println!("cargo:rustc-link-arg-tests=-Tembedded-test.x");
+}
@@ -0,0 +1,3 @@
+//! On-target tests of the iis2dlpc-rs driver, see `tests/sensor.rs`.
+
+#![no_std]
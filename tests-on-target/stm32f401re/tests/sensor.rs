@@ -0,0 +1,345 @@
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+    use core::ops::RangeInclusive;
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use cortex_m::peripheral::DWT;
+    use iis2dlpc_rs::prelude::*;
+    use iis2dlpc_rs::selftest::SelfTestConfig;
+    use iis2dlpc_rs::timing::measured_rate_hz;
+    use iis2dlpc_rs::transaction::{TransactionHooks, TransactionInfo};
+    use iis2dlpc_rs::{from_fs4_to_mg, I2CAddress, Iis2dlpc, PROPERTY_ENABLE};
+    use st_mems_bus::i2c::I2cBus;
+    use st_mems_bus::BusOperation;
+    use stm32f4xx_hal::{
+        gpio::{Input, PA10},
+        hal::delay::DelayNs,
+        i2c::{DutyCycle, I2c, Mode as I2cMode},
+        pac,
+        prelude::*,
+        timer::SysDelay,
+    };
+
+    /// Core clock, also the frequency of the DWT cycle counter used as time base.
+    const SYSCLK_HZ: u32 = 48_000_000;
+    /// Upper bound of the software reset duration.
+    const RESET_MAX_US: u32 = 1_000;
+    /// Accepted deviation of the measured data rate from the nominal one.
+    const ODR_TOLERANCE: f32 = 0.1;
+    /// Largest change between two samples at rest; a torn sample is off by a multiple of 64 LSB.
+    const TORN_LSB: u16 = 32;
+    /// Self-test output change range at ±4g, in mg.
+    const ST_RANGE_MG: RangeInclusive<f32> = 70.0..=1500.0;
+    const SELF_TEST_SAMPLES: usize = 5;
+    /// Plausible board temperature on the bench, in degrees Celsius.
+    const ROOM_CELSIUS: RangeInclusive<f32> = 10.0..=50.0;
+
+    type Sensor = Iis2dlpc<I2cBus<I2c<pac::I2C1>>, SysDelay>;
+
+    struct State {
+        sensor: Sensor,
+        /// INT1 of the sensor on D2.
+        int1: PA10<Input>,
+    }
+
+    #[init]
+    fn init() -> State {
+        let dp = pac::Peripherals::take().unwrap();
+        let mut cp = cortex_m::Peripherals::take().unwrap();
+
+        let rcc = dp.RCC.constrain();
+        let clocks = rcc.cfgr.use_hse(8.MHz()).sysclk(SYSCLK_HZ.Hz()).freeze();
+
+        cp.DCB.enable_trace();
+        cp.DWT.enable_cycle_counter();
+        let delay = cp.SYST.delay(&clocks);
+
+        let gpioa = dp.GPIOA.split();
+        let gpiob = dp.GPIOB.split();
+        let i2c = I2c::new(
+            dp.I2C1,
+            (gpiob.pb8, gpiob.pb9),
+            I2cMode::Fast {
+                frequency: 400.kHz(),
+                duty_cycle: DutyCycle::Ratio2to1,
+            },
+            &clocks,
+        );
+        let int1 = gpioa.pa10.into_pull_down_input();
+
+        let mut sensor = Iis2dlpc::new_i2c(i2c, I2CAddress::I2cAddH, delay);
+        sensor.tim.delay_ms(25);
+
+        // Every test starts from the default configuration
+        sensor.reset_set().unwrap();
+        while sensor.reset_get().unwrap() == 1 {}
+
+        State { sensor, int1 }
+    }
+
+    static PRELUDES: AtomicU32 = AtomicU32::new(0);
+    static TRANSACTIONS: AtomicU32 = AtomicU32::new(0);
+
+    fn count_prelude(
+        _bus: &mut I2cBus<I2c<pac::I2C1>>,
+    ) -> Result<(), <I2cBus<I2c<pac::I2C1>> as BusOperation>::Error> {
+        PRELUDES.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn count_transaction(_info: &TransactionInfo) {
+        TRANSACTIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn elapsed_us(start: u32) -> u32 {
+        DWT::cycle_count().wrapping_sub(start) / (SYSCLK_HZ / 1_000_000)
+    }
+
+    /// Wait for a new sample and discard it.
+    fn flush_sample(sensor: &mut Sensor) {
+        while sensor.flag_data_ready_get().unwrap() == 0 {}
+        sensor.acceleration_raw_get().unwrap();
+    }
+
+    #[test]
+    fn who_am_i(mut state: State) {
+        assert_eq!(state.sensor.device_id_get().unwrap(), iis2dlpc_rs::ID);
+    }
+
+    #[test]
+    fn reset_completes_in_time(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.data_rate_set(Odr::_100hz).unwrap();
+
+        let start = DWT::cycle_count();
+        sensor.reset_set().unwrap();
+        while sensor.reset_get().unwrap() == 1 {
+            assert!(elapsed_us(start) <= RESET_MAX_US, "reset still running");
+        }
+        defmt::info!("reset completed in {} us", elapsed_us(start));
+
+        assert_eq!(sensor.data_rate_get().unwrap(), Odr::Off);
+    }
+
+    #[test]
+    fn temperature_plausible(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+        sensor.data_rate_set(Odr::_50hz).unwrap();
+        sensor.tim.delay_ms(100);
+
+        let celsius = sensor.temperature_celsius_get().unwrap();
+        defmt::info!("temperature: {} °C", celsius);
+        assert!(ROOM_CELSIUS.contains(&celsius));
+    }
+
+    #[test]
+    fn sources_byte_form_round_trip(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.int_notification_set(Lir::Latched).unwrap();
+        sensor
+            .pin_int1_route_set(&Ctrl4Int1PadCtrl::new().with_int1_6d(1))
+            .unwrap();
+        sensor.data_rate_set(Odr::_50hz).unwrap();
+        sensor.tim.delay_ms(100);
+
+        // The first orientation is latched as a 6D event
+        let bytes = sensor.all_sources_bytes_get().unwrap();
+        let sources = AllSources::from_bytes(bytes);
+        defmt::info!("sources: {=[u8]:#04x}", bytes);
+        assert_eq!(sources.to_bytes(), bytes);
+        assert_eq!(
+            sensor.event_counters().six_d,
+            u32::from(sources.all_int_src.six_d_ia())
+        );
+
+        let mut seed: u32 = 0x2545_f491;
+        for _ in 0..1000 {
+            let mut bytes = [0; AllSources::BYTES];
+            for byte in bytes.iter_mut() {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                *byte = (seed >> 24) as u8;
+            }
+            let sources = AllSources::from_bytes(bytes);
+            assert_eq!(sources.to_bytes(), bytes);
+            assert_eq!(AllSources::from_bytes(sources.to_bytes()), sources);
+        }
+    }
+
+    #[test]
+    fn bus_prelude_once_per_transaction(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+        sensor.data_rate_set(Odr::_400hz).unwrap();
+        sensor.tim.delay_ms(10);
+
+        sensor.bus_prelude_set(count_prelude);
+        sensor.transaction_hooks_set(TransactionHooks {
+            before: Some(count_transaction),
+            after: None,
+        });
+
+        // A 6-byte burst is one transaction
+        sensor.acceleration_raw_unchecked_get().unwrap();
+        assert_eq!(PRELUDES.load(Ordering::Relaxed), 1);
+
+        // A full FIFO drain bursts 192 bytes
+        sensor.fifo_mode_set(Fmode::FifoMode).unwrap();
+        sensor.tim.delay_ms(100);
+        let before = PRELUDES.load(Ordering::Relaxed);
+        let mut buf = [0; 192];
+        let report = sensor.fifo_drain(&mut buf, false).unwrap();
+        let preludes = PRELUDES.load(Ordering::Relaxed);
+        defmt::info!("{} samples drained, {} preludes", report.samples, preludes - before);
+        assert!(report.samples >= 31);
+        assert!(preludes - before <= 5);
+        assert_eq!(preludes, TRANSACTIONS.load(Ordering::Relaxed));
+
+        sensor.bus_prelude_clear();
+        sensor.device_id_get().unwrap();
+        assert_eq!(PRELUDES.load(Ordering::Relaxed), preludes);
+    }
+
+    #[test]
+    fn odr_accuracy(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.power_mode_set(Mode::HighPerformance).unwrap();
+
+        for (odr, nominal_hz) in [
+            (Odr::_12_5hz, 12.5),
+            (Odr::_100hz, 100.0),
+            (Odr::_400hz, 400.0),
+        ] {
+            sensor.data_rate_set(odr).unwrap();
+
+            let mut timestamps = [0; 21];
+            let n = sensor
+                .drdy_timestamps_get(DWT::cycle_count, &mut timestamps, 1_000_000)
+                .unwrap();
+            assert_eq!(n, timestamps.len(), "data-ready stopped");
+
+            let rate_hz = measured_rate_hz(&timestamps, SYSCLK_HZ).unwrap();
+            defmt::info!("{}: {} Hz", defmt::Debug2Format(&odr), rate_hz);
+            assert!((rate_hz - nominal_hz).abs() <= nominal_hz * ODR_TOLERANCE);
+        }
+    }
+
+    /// The board must lie still.
+    #[test]
+    fn bdu_no_torn_samples(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+        sensor.full_scale_set(Fs::_2g).unwrap();
+        sensor.power_mode_set(Mode::HighPerformance).unwrap();
+        sensor.filter_bandwidth_set(BwFilt::OdrDiv20).unwrap();
+        sensor.data_rate_set(Odr::_1_6khz).unwrap();
+        sensor.tim.delay_ms(100);
+        flush_sample(sensor);
+
+        // Read as fast as possible, so that the output registers update during the reads
+        let mut previous = sensor.acceleration_raw_get().unwrap();
+        for _ in 0..1000 {
+            let raw = sensor.acceleration_raw_get().unwrap();
+            for (now, before) in raw.iter().zip(previous) {
+                assert!(now.abs_diff(before) < TORN_LSB, "torn sample");
+            }
+            previous = raw;
+        }
+    }
+
+    #[test]
+    fn fifo_watermark_interrupt_latency(mut state: State) {
+        const WATERMARK: u8 = 16;
+
+        let sensor = &mut state.sensor;
+        sensor.power_mode_set(Mode::HighPerformance).unwrap();
+        sensor.data_rate_set(Odr::_100hz).unwrap();
+        sensor.fifo_watermark_set(WATERMARK).unwrap();
+        let mut route = sensor.pin_int1_route_get().unwrap();
+        route.set_int1_fth(PROPERTY_ENABLE);
+        sensor.pin_int1_route_set(&route).unwrap();
+
+        let period_us = Odr::_100hz.period_us().unwrap();
+        // The first sample arrives within one period of the FIFO start
+        let expected_us = (WATERMARK as u32 - 1) * period_us..=(WATERMARK as u32 + 1) * period_us;
+
+        sensor.fifo_mode_set(Fmode::BypassMode).unwrap();
+        let start = DWT::cycle_count();
+        sensor.fifo_mode_set(Fmode::FifoMode).unwrap();
+        while state.int1.is_low() {
+            assert!(
+                elapsed_us(start) <= 2 * expected_us.end(),
+                "no watermark interrupt"
+            );
+        }
+        let latency_us = elapsed_us(start);
+        defmt::info!("watermark interrupt after {} us", latency_us);
+
+        assert_eq!(state.sensor.fifo_wtm_flag_get().unwrap(), 1);
+        let tolerance_us = (*expected_us.end() as f32 * ODR_TOLERANCE) as u32;
+        assert!(latency_us >= expected_us.start() - tolerance_us);
+        assert!(latency_us <= expected_us.end() + tolerance_us);
+    }
+
+    #[test]
+    fn self_test_pass(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.block_data_update_set(PROPERTY_ENABLE).unwrap();
+        sensor.full_scale_set(Fs::_4g).unwrap();
+        sensor.power_mode_set(Mode::HighPerformance).unwrap();
+        sensor.data_rate_set(Odr::_50hz).unwrap();
+        sensor.tim.delay_ms(100);
+
+        let mean_mg = |sensor: &mut Sensor| {
+            flush_sample(sensor);
+            let mut sum = [0.0; 3];
+            for _ in 0..SELF_TEST_SAMPLES {
+                while sensor.flag_data_ready_get().unwrap() == 0 {}
+                let mg = sensor.acceleration_raw_get().unwrap().map(from_fs4_to_mg);
+                sum.iter_mut().zip(mg).for_each(|(s, v)| *s += v);
+            }
+            sum.map(|s| s / SELF_TEST_SAMPLES as f32)
+        };
+
+        let base = mean_mg(sensor);
+        sensor.self_test_set(St::Positive).unwrap();
+        sensor.tim.delay_ms(100);
+        let st = mean_mg(sensor);
+        sensor.self_test_set(St::Disable).unwrap();
+
+        for (on, off) in st.iter().zip(base) {
+            let change = (on - off).abs();
+            defmt::info!("self-test change: {} mg", change);
+            assert!(ST_RANGE_MG.contains(&change));
+        }
+    }
+
+    #[test]
+    fn self_test_full_restores_configuration(mut state: State) {
+        let sensor = &mut state.sensor;
+        sensor.full_scale_set(Fs::_16g).unwrap();
+        sensor.data_rate_set(Odr::_12_5hz).unwrap();
+
+        let report = sensor.self_test_full(&SelfTestConfig::default()).unwrap();
+        for axis in report.axes {
+            defmt::info!(
+                "baseline {} mg, ST+ {} mg, ST- {} mg",
+                axis.baseline_mg,
+                axis.positive_delta_mg,
+                axis.negative_delta_mg
+            );
+        }
+        assert!(report.passed());
+
+        assert_eq!(sensor.full_scale_get().unwrap(), Fs::_16g);
+        assert_eq!(sensor.data_rate_get().unwrap(), Odr::_12_5hz);
+        assert_eq!(sensor.self_test_get().unwrap(), St::Disable);
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Golden vector generator for the conversion functions of iis2dlpc-rs.
+ *
+ * Builds against the reference C driver (iis2dlpc_reg.c / iis2dlpc_reg.h from
+ * https://github.com/STMicroelectronics/iis2dlpc-pid) and prints the Rust
+ * vector table checked in as examples/golden_vectors_host/src/vectors.rs:
+ *
+ *   cc -I<iis2dlpc-pid> gen_vectors.c <iis2dlpc-pid>/iis2dlpc_reg.c -o gen_vectors
+ *   ./gen_vectors > ../../examples/golden_vectors_host/src/vectors.rs
+ *
+ * The C driver converts the left-aligned 16-bit output words; the raw words
+ * below have their 4 least significant bits cleared so that they are valid in
+ * every mode, including the 12-bit low-power mode 1.
+ */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "iis2dlpc_reg.h"
+
+static const int16_t raw_acc[] = {
+  0, 16, -16, 4656, -4672, 16384, -16384, 32752, -32768,
+};
+
+static const int16_t raw_temp[] = {
+  0, 16, -16, 256, -256, 4096, -4096, 32752, -32768,
+};
+
+typedef float_t (*conv_t)(int16_t);
+
+static const struct {
+  const char *fs;
+  conv_t hp;
+  conv_t lp1;
+} fs_table[] = {
+  { "_2g", iis2dlpc_from_fs2_to_mg, iis2dlpc_from_fs2_lp1_to_mg },
+  { "_4g", iis2dlpc_from_fs4_to_mg, iis2dlpc_from_fs4_lp1_to_mg },
+  { "_8g", iis2dlpc_from_fs8_to_mg, iis2dlpc_from_fs8_lp1_to_mg },
+  { "_16g", iis2dlpc_from_fs16_to_mg, iis2dlpc_from_fs16_lp1_to_mg },
+};
+
+#define LEN(a) (sizeof(a) / sizeof((a)[0]))
+
+/* Shortest decimal representation that reads back as the same float. */
+static const char *fmt_f32(float_t val)
+{
+  static char buf[32];
+  int prec;
+
+  for (prec = 1; prec < 10; prec++) {
+    snprintf(buf, sizeof(buf), "%.*g", prec, (double)val);
+    if (strtof(buf, NULL) == val)
+      break;
+  }
+  if (!strpbrk(buf, ".e"))
+    snprintf(buf + strlen(buf), sizeof(buf) - strlen(buf), ".0");
+  return buf;
+}
+
+int main(void)
+{
+  size_t i, j;
+
+  printf("// Generated by tools/golden/gen_vectors.c from the reference C driver, do not edit.\n\n");
+  printf("use iis2dlpc_rs::prelude::Fs;\n\n");
+
+  printf("/// (left-aligned raw word, full scale, low-power mode 1, mg)\n");
+  printf("pub const ACCELERATION: [(i16, Fs, bool, f32); %zu] = [\n",
+         LEN(fs_table) * LEN(raw_acc) * 2);
+  for (i = 0; i < LEN(fs_table); i++) {
+    for (j = 0; j < LEN(raw_acc); j++) {
+      printf("    (%d, Fs::%s, false, %s),\n", raw_acc[j], fs_table[i].fs,
+             fmt_f32(fs_table[i].hp(raw_acc[j])));
+      printf("    (%d, Fs::%s, true, %s),\n", raw_acc[j], fs_table[i].fs,
+             fmt_f32(fs_table[i].lp1(raw_acc[j])));
+    }
+  }
+  printf("];\n\n");
+
+  printf("/// (left-aligned raw word, degrees Celsius)\n");
+  printf("pub const TEMPERATURE: [(i16, f32); %zu] = [\n", LEN(raw_temp));
+  for (j = 0; j < LEN(raw_temp); j++) {
+    printf("    (%d, %s),\n", raw_temp[j],
+           fmt_f32(iis2dlpc_from_lsb_to_celsius(raw_temp[j])));
+  }
+  printf("];\n");
+
+  return 0;
+}